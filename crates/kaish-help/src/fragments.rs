@@ -270,6 +270,9 @@ ENABLED=true              # boolean (only true/false)
 $VAR                      # simple
 ${VAR}                    # braced
 ${VAR:-default}           # default if unset/empty
+${VAR:=default}           # same, and assign it to VAR
+${VAR:?message}           # error with message if unset/empty
+${VAR:+alternate}         # alternate if set and non-empty, else empty
 ${#VAR}                   # string length
 $0 $1 $@ $#              # script name, args, all args, count
 $?                        # last exit code (0-255)
@@ -8,7 +8,8 @@
 //! Two surfaces:
 //!
 //! - [`topic`] — the `help <topic>` command / embedder-prompt compatibility surface:
-//!   [`get_help`], [`list_topics`], [`HelpTopic`]. Whole embedded markdown docs.
+//!   [`get_help`], [`list_topics`], [`HelpTopic`]. Whole embedded markdown docs,
+//!   plus [`tools_markdown`] for a generated reference of the registered tools.
 //! - [`compose`] — the composition surface: a concept-organized [`Fragment`] model
 //!   ([`Concept`] / [`Variant`] / [`Audience`] / [`Depth`] / locale) assembled by
 //!   [`compose`](compose::compose) via [`Selector`]s and ready-made [`Recipe`]s.
@@ -21,7 +22,7 @@ pub mod fragments;
 pub mod topic;
 
 // Compatibility surface — the `help <topic>` command and embedder prompts.
-pub use topic::{get_help, list_topics, HelpTopic};
+pub use topic::{get_help, list_topics, tool_markdown, tools_markdown, HelpTopic};
 
 // Composition surface — recipes for frontends and embedders.
 pub use compose::{
@@ -5,7 +5,7 @@
 //! tool registry.
 //! Behavior here is intentionally byte-stable — frontends and tests depend on it.
 
-use kaish_types::ToolSchema;
+use kaish_types::{ParamSchema, ToolSchema};
 
 use crate::compose::render_syntax_section;
 use crate::content::{IGNORE, LIMITS, OUTPUT_LIMIT, OVERLAY, OVERVIEW, SCATTER, SYNTAX, VFS};
@@ -171,6 +171,77 @@ fn format_tool_list(schemas: &[ToolSchema]) -> String {
     output
 }
 
+/// Render one tool as a Markdown section: a heading, the description, a
+/// usage line, a table of arguments and flags, subcommands, and examples.
+///
+/// The same [`ToolSchema`] backs `help <tool>`, so generated docs can't
+/// drift from what the kernel binds.
+pub fn tool_markdown(schema: &ToolSchema) -> String {
+    let mut output = format!("## `{}`\n\n{}\n\n", schema.name, schema.description);
+    output.push_str(&format!("**Usage:** `{}`\n\n", schema.usage_line()));
+
+    if !schema.params.is_empty() {
+        output.push_str("| Argument | Type | Description |\n|---|---|---|\n");
+        for param in &schema.params {
+            let req = if param.required { " (required)" } else { "" };
+            output.push_str(&format!(
+                "| {} | {}{} | {} |\n",
+                param_spelling(param),
+                param.param_type,
+                req,
+                table_cell(&param.description)
+            ));
+        }
+        output.push('\n');
+    }
+
+    if !schema.subcommands.is_empty() {
+        output.push_str("| Subcommand | Description |\n|---|---|\n");
+        for sub in &schema.subcommands {
+            output.push_str(&format!("| `{}` | {} |\n", sub.name, table_cell(&sub.description)));
+        }
+        output.push('\n');
+    }
+
+    if !schema.examples.is_empty() {
+        output.push_str("**Examples**\n\n");
+        for example in &schema.examples {
+            output.push_str(&format!("{}:\n\n```sh\n{}\n```\n\n", example.description, example.code));
+        }
+    }
+
+    output
+}
+
+/// Render every tool as one Markdown reference: an index, then a
+/// [`tool_markdown`] section per tool, in the order given.
+pub fn tools_markdown(schemas: &[ToolSchema]) -> String {
+    let mut output = String::from("# kaish builtins\n\n");
+    for schema in schemas {
+        output.push_str(&format!(
+            "- [`{}`](#{}) — {}\n",
+            schema.name,
+            schema.name.to_lowercase(),
+            schema.description
+        ));
+    }
+    output.push('\n');
+    for schema in schemas {
+        output.push_str(&tool_markdown(schema));
+    }
+    output
+}
+
+/// A parameter's command-line spellings, each as inline code.
+fn param_spelling(param: &ParamSchema) -> String {
+    param.spellings().iter().map(|s| format!("`{s}`")).collect::<Vec<_>>().join(", ")
+}
+
+/// Text safe inside a Markdown table cell.
+fn table_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
 /// List available help topics (for autocomplete, etc.).
 pub fn list_topics() -> Vec<(&'static str, &'static str)> {
     vec![
@@ -257,6 +328,36 @@ mod tests {
         assert!(content.contains("Unknown topic or tool"));
     }
 
+    fn cat_schema() -> ToolSchema {
+        ToolSchema::new("cat", "Read and output file contents")
+            .param(ParamSchema::new("number", "bool").with_aliases(["n"]))
+            .param(
+                ParamSchema::new("paths", "string")
+                    .with_description("Files | globs to read")
+                    .with_positional(true),
+            )
+            .example("Number lines", "cat -n notes.txt")
+    }
+
+    #[test]
+    fn test_tool_markdown() {
+        let content = tool_markdown(&cat_schema());
+        assert!(content.starts_with("## `cat`\n\nRead and output file contents\n\n"), "{content}");
+        assert!(content.contains("**Usage:** `cat [OPTIONS] [PATHS]`"), "{content}");
+        assert!(content.contains("| `-n`, `--number` | bool |  |\n"), "{content}");
+        assert!(content.contains("| `PATHS` | string | Files \\| globs to read |\n"), "{content}");
+        assert!(content.contains("Number lines:\n\n```sh\ncat -n notes.txt\n```\n"), "{content}");
+    }
+
+    #[test]
+    fn test_tools_markdown_indexes_every_tool() {
+        let schemas = [cat_schema(), ToolSchema::new("kaish-checkpoint", "Snapshot and roll back")];
+        let content = tools_markdown(&schemas);
+        assert!(content.contains("- [`kaish-checkpoint`](#kaish-checkpoint) — Snapshot and roll back\n"), "{content}");
+        assert!(content.contains("## `kaish-checkpoint`"), "{content}");
+        assert!(content.contains("**Usage:** `kaish-checkpoint`\n"), "{content}");
+    }
+
     #[test]
     fn test_tool_help_none_for_missing() {
         assert!(tool_help("nonexistent", &[]).is_none());
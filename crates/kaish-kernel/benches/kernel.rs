@@ -0,0 +1,309 @@
+//! Kernel benchmarks: parsing, evaluation, MemoryFs, and pipelines.
+//!
+//! A small self-contained harness (`harness = false`), so the suite builds
+//! with the locked dependency set and no bench framework. Each case runs
+//! once to warm up, then repeatedly for about a second; the median and the
+//! fastest run are reported.
+//!
+//! ```bash
+//! cargo bench -p kaish-kernel --bench kernel                      # everything
+//! cargo bench -p kaish-kernel --bench kernel -- pipeline          # cases matching "pipeline"
+//! cargo bench -p kaish-kernel --bench kernel -- --save-baseline main
+//! cargo bench -p kaish-kernel --bench kernel -- --baseline main   # compare against it
+//! ```
+//!
+//! Baselines are TSV files under `target/kaish-bench/`. Record one on the
+//! commit before a performance change and compare on the commit after;
+//! numbers from different machines don't compare.
+
+// Bench fixtures: a panic on known-good setup is the bench failing.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::collections::BTreeMap;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use kaish_kernel::parser::{parse, parse_statements};
+use kaish_kernel::vfs::{Filesystem, MemoryFs, VfsRouter};
+use kaish_kernel::{Kernel, KernelConfig};
+use tokio::runtime::Runtime;
+
+/// How long each case keeps running after warm-up.
+const TARGET: Duration = Duration::from_secs(1);
+/// Bounds on runs per case, whatever the time.
+const MIN_RUNS: usize = 10;
+const MAX_RUNS: usize = 10_000;
+
+struct Case {
+    name: String,
+    run: Box<dyn FnMut()>,
+}
+
+impl Case {
+    fn new(name: impl Into<String>, run: impl FnMut() + 'static) -> Self {
+        Self { name: name.into(), run: Box::new(run) }
+    }
+}
+
+struct Sample {
+    runs: usize,
+    median: Duration,
+    min: Duration,
+}
+
+fn measure(run: &mut dyn FnMut()) -> Sample {
+    run();
+    let mut times = Vec::new();
+    let started = Instant::now();
+    while times.len() < MIN_RUNS || (started.elapsed() < TARGET && times.len() < MAX_RUNS) {
+        let at = Instant::now();
+        run();
+        times.push(at.elapsed());
+    }
+    times.sort();
+    Sample { runs: times.len(), median: times[times.len() / 2], min: times[0] }
+}
+
+fn runtime() -> Arc<Runtime> {
+    Arc::new(tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap())
+}
+
+/// A script of `blocks` copies of a block using most of the grammar:
+/// functions, loops, conditionals, pipelines, interpolation, collections.
+fn large_script(blocks: usize) -> String {
+    let mut script = String::new();
+    for i in 0..blocks {
+        script.push_str(&format!(
+            r#"# block {i}
+step_{i}() {{
+    local name="item-$1"
+    if [[ $1 -gt 10 && -n "$name" ]]; then
+        echo "${{name}}: big" | grep big | wc -l
+    elif [[ $1 == 0 ]]; then
+        return 1
+    fi
+    for x in $(seq 1 $1); do
+        total=$((total + x * 2 - 1))
+    done
+}}
+cfg_{i}={{name: "svc-{i}", ports: [80 443], tags: [a b]}}
+case ${{cfg_{i}[name]}} in
+    svc-*) step_{i} {i} 2>&1 || echo 'failed' ;;
+    *) echo "unexpected" ;;
+esac
+"#
+        ));
+    }
+    script
+}
+
+/// `$(( ... ))` nested `depth` parentheses deep.
+fn nested_arithmetic(depth: usize) -> String {
+    let mut expr = "1".to_string();
+    for i in 0..depth {
+        expr = format!("({expr} + {i}) * 1");
+    }
+    format!("X=$(( {expr} ))")
+}
+
+fn parse_cases() -> Vec<Case> {
+    [100, 1_000]
+        .into_iter()
+        .map(|blocks| {
+            let script = large_script(blocks);
+            let lines = script.lines().count();
+            Case::new(format!("parse/{lines}_lines"), move || {
+                black_box(parse(black_box(&script)).unwrap());
+            })
+        })
+        .chain(std::iter::once({
+            // Time to the first statement: what streaming saves.
+            let script = large_script(1_000);
+            let lines = script.lines().count();
+            Case::new(format!("parse/first_statement_of_{lines}_lines"), move || {
+                black_box(parse_statements(black_box(&script)).next().unwrap().unwrap());
+            })
+        }))
+        .collect()
+}
+
+/// Run `script` on one kernel per case. Variables carry over between runs,
+/// so each script sets what it reads.
+fn script_case(name: &str, rt: &Arc<Runtime>, script: impl Into<String>) -> Case {
+    let rt = rt.clone();
+    let script = script.into();
+    let kernel = Kernel::new(KernelConfig::isolated()).unwrap();
+    Case::new(name, move || {
+        let result = rt.block_on(kernel.execute(&script)).unwrap();
+        assert!(result.ok(), "{script}: {result:?}");
+        black_box(result);
+    })
+}
+
+/// Assignments and commands in a session already holding `vars` variables:
+/// what each statement pays for the scope snapshot it syncs back.
+fn large_scope_case(rt: &Arc<Runtime>, vars: usize) -> Case {
+    let rt = rt.clone();
+    let kernel = Kernel::new(KernelConfig::isolated()).unwrap();
+    let setup: String = (0..vars).map(|i| format!("v{i}={i}; ")).collect();
+    assert!(rt.block_on(kernel.execute(&setup)).unwrap().ok());
+    let script = "total=0; for i in $(seq 1 200); do total=$((total + i)); true; done";
+    Case::new(format!("eval/loop_200_scope_{vars}"), move || {
+        let result = rt.block_on(kernel.execute(script)).unwrap();
+        assert!(result.ok(), "{script}: {result:?}");
+        black_box(result);
+    })
+}
+
+/// A function of `stmts` statements called `calls` times: what each call
+/// pays to take the definition out of the kernel.
+fn function_calls(stmts: usize, calls: usize) -> String {
+    let body: String = (0..stmts).map(|i| format!("x{i}=$((x{i} + 1)); ")).collect();
+    format!("f() {{ if [[ $1 -lt 0 ]]; then {body}fi; }}; for i in $(seq 1 {calls}); do f $i; done")
+}
+
+fn eval_cases(rt: &Arc<Runtime>) -> Vec<Case> {
+    vec![
+        script_case("eval/arithmetic_depth_64", rt, nested_arithmetic(64)),
+        script_case("eval/loop_1000", rt, "total=0; for i in $(seq 1 1000); do total=$((total + i)); done"),
+        script_case(
+            "eval/recursion_depth_40",
+            rt,
+            "down() { if [[ $1 -gt 0 ]]; then down $(($1 - 1)); fi; }; down 40",
+        ),
+        script_case("eval/interpolation_1000", rt, {
+            let mut script = "name=kaish; s=''; ".to_string();
+            for _ in 0..1000 {
+                script.push_str(r#"s="${name}-$name"; "#);
+            }
+            script
+        }),
+        large_scope_case(rt, 5000),
+        script_case("eval/call_200_body_500", rt, function_calls(500, 200)),
+    ]
+}
+
+/// A router over one MemoryFs, with `dir` already made.
+fn memory_vfs(rt: &Runtime, dir: &str) -> VfsRouter {
+    let mut vfs = VfsRouter::new();
+    vfs.mount("/", MemoryFs::new());
+    rt.block_on(vfs.mkdir(Path::new(dir))).unwrap();
+    vfs
+}
+
+fn memoryfs_cases(rt: &Arc<Runtime>) -> Vec<Case> {
+    let mut cases = Vec::new();
+    for (label, size) in [("4KiB", 4 << 10), ("1MiB", 1 << 20)] {
+        let rt = rt.clone();
+        let data = vec![b'x'; size];
+        let vfs = memory_vfs(&rt, "/bench");
+        cases.push(Case::new(format!("memoryfs/write_read_{label}"), move || {
+            rt.block_on(async {
+                let path = Path::new("/bench/file");
+                vfs.write(path, &data).await.unwrap();
+                black_box(vfs.read(path).await.unwrap());
+            });
+        }));
+    }
+
+    let rt = rt.clone();
+    let vfs = memory_vfs(&rt, "/many");
+    cases.push(Case::new("memoryfs/write_list_1000", move || {
+        rt.block_on(async {
+            let dir = PathBuf::from("/many");
+            for i in 0..1000 {
+                vfs.write(&dir.join(format!("f{i}")), b"x").await.unwrap();
+            }
+            black_box(vfs.list(&dir).await.unwrap());
+        });
+    }));
+    cases
+}
+
+fn pipeline_cases(rt: &Arc<Runtime>) -> Vec<Case> {
+    [1, 4, 16]
+        .into_iter()
+        .map(|stages| {
+            let script = format!("seq 1 10000{} | wc -l", " | cat".repeat(stages));
+            script_case(&format!("pipeline/{stages}_cat_stages"), rt, script)
+        })
+        .collect()
+}
+
+fn baseline_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/kaish-bench").join(format!("{name}.tsv"))
+}
+
+fn load_baseline(name: &str) -> BTreeMap<String, u128> {
+    let path = baseline_path(name);
+    let text = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("no baseline at {}: {e}", path.display()));
+    text.lines()
+        .filter_map(|line| {
+            let (case, nanos) = line.split_once('\t')?;
+            Some((case.to_string(), nanos.parse().ok()?))
+        })
+        .collect()
+}
+
+fn main() {
+    // The interpreter recurses on the native stack; size the driver thread
+    // like any embedder should (see EMBEDDING.md, "Stack size").
+    std::thread::Builder::new()
+        .stack_size(kaish_kernel::RECOMMENDED_STACK_SIZE)
+        .spawn(run)
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+fn run() {
+    let mut filter = None;
+    let mut save = None;
+    let mut compare = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--save-baseline" => save = args.next(),
+            "--baseline" => compare = args.next().map(|name| load_baseline(&name)),
+            // `cargo bench` passes `--bench`; ignore it and other harness flags.
+            other if other.starts_with("--") => {}
+            other => filter = Some(other.to_string()),
+        }
+    }
+
+    let rt = runtime();
+    let mut cases = parse_cases();
+    cases.extend(eval_cases(&rt));
+    cases.extend(memoryfs_cases(&rt));
+    cases.extend(pipeline_cases(&rt));
+
+    let mut recorded = String::new();
+    println!("{:<32} {:>12} {:>12} {:>7}", "case", "median", "min", "runs");
+    for mut case in cases {
+        if filter.as_ref().is_some_and(|f| !case.name.contains(f.as_str())) {
+            continue;
+        }
+        let sample = measure(&mut case.run);
+        let change = compare
+            .as_ref()
+            .and_then(|baseline| baseline.get(&case.name))
+            .map(|&before| {
+                let delta = sample.median.as_nanos() as f64 / before.max(1) as f64 - 1.0;
+                format!("  {:+.1}%", delta * 100.0)
+            })
+            .unwrap_or_default();
+        let (median, min) = (format!("{:?}", sample.median), format!("{:?}", sample.min));
+        println!("{:<32} {median:>12} {min:>12} {:>7}{change}", case.name, sample.runs);
+        recorded.push_str(&format!("{}\t{}\n", case.name, sample.median.as_nanos()));
+    }
+
+    if let Some(name) = save {
+        let path = baseline_path(&name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, recorded).unwrap();
+        println!("saved baseline to {}", path.display());
+    }
+}
@@ -64,6 +64,16 @@ pub fn format_stmt(stmt: &Stmt) -> String {
             let assigns: Vec<String> = assignments.iter().map(format_assignment).collect();
             format!("(env-scoped ({}) {})", assigns.join(" "), format_stmt(body))
         }
+        Stmt::Par(body) => {
+            let stmts: Vec<String> = body
+                .iter()
+                .filter(|s| !matches!(s, Stmt::Empty))
+                .map(format_stmt)
+                .collect();
+            format!("(par {})", stmts.join(" "))
+        }
+        Stmt::Try(try_stmt) => format_try(try_stmt),
+        Stmt::On(on) => format!("(on {} ({}))", on.event, format_block(&on.body)),
         Stmt::Empty => "(empty)".to_string(),
     }
 }
@@ -189,6 +199,21 @@ fn format_for(for_loop: &ForLoop) -> String {
     )
 }
 
+/// Format a block's statements, skipping empty ones.
+fn format_block(stmts: &[Stmt]) -> String {
+    stmts
+        .iter()
+        .filter(|s| !matches!(s, Stmt::Empty))
+        .map(format_stmt)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Format a try block and its handler as an S-expression.
+fn format_try(try_stmt: &TryStmt) -> String {
+    format!("(try ({}) (catch {}))", format_block(&try_stmt.body), format_block(&try_stmt.catch))
+}
+
 /// Format a while loop as an S-expression.
 fn format_while(while_loop: &WhileLoop) -> String {
     let cond = format_expr(&while_loop.condition);
@@ -243,20 +268,12 @@ fn format_tooldef(tool: &ToolDef) -> String {
 
 /// Format a parameter definition as an S-expression.
 fn format_param(param: &ParamDef) -> String {
-    let type_str = param
-        .param_type
-        .as_ref()
-        .map(|t| match t {
-            ParamType::String => "string",
-            ParamType::Int => "int",
-            ParamType::Float => "float",
-            ParamType::Bool => "bool",
-        })
-        .unwrap_or("any");
+    let type_str = param.param_type.as_ref().map(ParamType::name).unwrap_or("any");
+    let dots = if param.variadic { "..." } else { "" };
 
     match &param.default {
-        Some(default) => format!("(param {} {} {})", param.name, type_str, format_expr(default)),
-        None => format!("(param {} {})", param.name, type_str),
+        Some(default) => format!("(param {} {}{} {})", param.name, type_str, dots, format_expr(default)),
+        None => format!("(param {} {}{})", param.name, type_str, dots),
     }
 }
 
@@ -298,9 +315,15 @@ pub fn format_expr(expr: &Expr) -> String {
         Expr::AllArgs => "(all-args)".to_string(),
         Expr::ArgCount => "(arg-count)".to_string(),
         Expr::VarLength(path) => format!("(var-length {})", format_varpath(path)),
-        Expr::VarWithDefault { path, default } => {
+        Expr::VarWithDefault { path, op, default } => {
             let default_parts: Vec<String> = default.iter().map(format_string_part).collect();
-            format!("(var-default {} ({}))", format_varpath(path), default_parts.join(" "))
+            let head = match op {
+                DefaultOp::UseDefault => "var-default",
+                DefaultOp::AssignDefault => "var-assign-default",
+                DefaultOp::ErrorIfUnset => "var-error-if-unset",
+                DefaultOp::UseAlternate => "var-alternate",
+            };
+            format!("({} {} ({}))", head, format_varpath(path), default_parts.join(" "))
         }
         Expr::Arithmetic(expr_str) => format!("(arithmetic \"{}\")", expr_str),
         Expr::Command(cmd) => format_command(cmd),
@@ -335,6 +358,12 @@ pub fn format_expr(expr: &Expr) -> String {
                 .collect();
             format!("(record {})", parts.join(" "))
         }
+        Expr::Conditional { condition, then_value, else_value } => format!(
+            "(cond {} {} {})",
+            format_expr(condition),
+            format_expr(then_value),
+            format_expr(else_value)
+        ),
     }
 }
 
@@ -408,9 +437,15 @@ fn format_string_part(part: &StringPart) -> String {
     match part {
         StringPart::Literal(s) => format!("\"{}\"", escape_for_display(s)),
         StringPart::Var(path) => format!("(varref {})", format_varpath(path)),
-        StringPart::VarWithDefault { path, default } => {
+        StringPart::VarWithDefault { path, op, default } => {
             let default_parts: Vec<String> = default.iter().map(format_string_part).collect();
-            format!("(vardefault {} ({}))", format_varpath(path), default_parts.join(" "))
+            let head = match op {
+                DefaultOp::UseDefault => "vardefault",
+                DefaultOp::AssignDefault => "varassigndefault",
+                DefaultOp::ErrorIfUnset => "varerrorifunset",
+                DefaultOp::UseAlternate => "varalternate",
+            };
+            format!("({} {} ({}))", head, format_varpath(path), default_parts.join(" "))
         }
         StringPart::VarLength(path) => format!("(varlength {})", format_varpath(path)),
         StringPart::Positional(n) => format!("(positional {})", n),
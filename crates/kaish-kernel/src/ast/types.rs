@@ -1,6 +1,11 @@
 //! AST type definitions.
 
 use std::fmt;
+use std::sync::Arc;
+
+/// Byte range of a node in the source it was parsed from. The same type
+/// validator issues carry, so a node's span attaches to an issue as-is.
+pub use kaish_tool_api::Span as SourceSpan;
 
 /// A complete kaish program is a sequence of statements.
 #[derive(Debug, Clone, PartialEq)]
@@ -46,6 +51,14 @@ pub enum Stmt {
     /// and do not persist after it — distinct from a plain `Assignment`, which
     /// is persistent. `body` is always a command or pipeline.
     EnvScoped { assignments: Vec<Assignment>, body: Box<Stmt> },
+    /// Concurrent block: `par { stmt; stmt }`. The statements run side by
+    /// side when they provably share no variables or written paths, and one
+    /// after another otherwise.
+    Par(Vec<Stmt>),
+    /// Error handler: `try { ... } catch { ... }`
+    Try(TryStmt),
+    /// Event handler: `on command_error { ... }`
+    On(OnStmt),
     /// Empty statement (newline or semicolon only)
     Empty,
 }
@@ -70,6 +83,9 @@ impl Stmt {
             Stmt::AndChain { .. } => "and_chain",
             Stmt::OrChain { .. } => "or_chain",
             Stmt::EnvScoped { .. } => "env_scoped",
+            Stmt::Par(_) => "par",
+            Stmt::Try(_) => "try",
+            Stmt::On(_) => "on",
             Stmt::Empty => "empty",
         }
     }
@@ -87,6 +103,9 @@ pub struct Assignment {
     /// for a subscripted path — a bracket-path write always mutates the
     /// existing root (see `docs/arrays-and-hashes.md`, "Assignment lvalues").
     pub local: bool,
+    /// Where the assignment sits in the parsed source; `None` when
+    /// synthesized rather than parsed.
+    pub span: Option<SourceSpan>,
 }
 
 impl Assignment {
@@ -107,6 +126,10 @@ pub struct Command {
     pub name: String,
     pub args: Vec<Arg>,
     pub redirects: Vec<Redirect>,
+    /// Where the command sits in the parsed source, name through last
+    /// redirect; `None` for commands the kernel builds itself (argv
+    /// execution, `map`/`timeout` inner commands).
+    pub span: Option<SourceSpan>,
 }
 
 /// A pipeline of commands connected by pipes.
@@ -116,6 +139,16 @@ pub struct Pipeline {
     pub background: bool,
 }
 
+impl Pipeline {
+    /// Source span from the first command through the last, when both ends
+    /// were parsed.
+    pub fn span(&self) -> Option<SourceSpan> {
+        let first = self.commands.first()?.span?;
+        let last = self.commands.last()?.span?;
+        Some(SourceSpan::new(first.start, last.end))
+    }
+}
+
 /// Conditional statement.
 #[derive(Debug, Clone, PartialEq)]
 pub struct IfStmt {
@@ -140,6 +173,22 @@ pub struct WhileLoop {
     pub body: Vec<Stmt>,
 }
 
+/// Try block with its handler. The first failing statement in `body` stops
+/// it and runs `catch`, with `${ERROR}` set to what went wrong.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TryStmt {
+    pub body: Vec<Stmt>,
+    pub catch: Vec<Stmt>,
+}
+
+/// Event handler registration. Running it makes `body` the handler for
+/// `event` (see [`crate::hooks`]); an empty body removes the handler.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OnStmt {
+    pub event: String,
+    pub body: Vec<Stmt>,
+}
+
 /// Case statement for pattern matching.
 ///
 /// ```kaish
@@ -171,15 +220,24 @@ pub struct CaseBranch {
 pub struct ToolDef {
     pub name: String,
     pub params: Vec<ParamDef>,
-    pub body: Vec<Stmt>,
+    /// Shared, so taking a definition out of the kernel's table to call it
+    /// copies a pointer rather than the whole body.
+    pub body: Arc<[Stmt]>,
+    /// The namespace the definition was imported into (`import … as git`
+    /// registers `git.sync`); `None` for a function defined directly.
+    pub module: Option<String>,
 }
 
-/// Parameter definition for a tool.
+/// Parameter definition for a tool: `name`, `name:int`, `name:int=3`, or a
+/// trailing variadic `rest:int...` that binds the remaining positionals.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ParamDef {
     pub name: String,
     pub param_type: Option<ParamType>,
     pub default: Option<Expr>,
+    /// Binds every remaining positional argument as a list. For a variadic
+    /// parameter `param_type` is the element type (`array` or none: any).
+    pub variadic: bool,
 }
 
 /// Parameter type annotation.
@@ -189,6 +247,33 @@ pub enum ParamType {
     Int,
     Float,
     Bool,
+    /// A list value (`[a b]`, or a JSON array).
+    Array,
+}
+
+impl ParamType {
+    /// Parse a type annotation as written after `name:`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "string" => Some(Self::String),
+            "int" => Some(Self::Int),
+            "float" => Some(Self::Float),
+            "bool" => Some(Self::Bool),
+            "array" => Some(Self::Array),
+            _ => None,
+        }
+    }
+
+    /// The annotation as written in source.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::Bool => "bool",
+            Self::Array => "array",
+        }
+    }
 }
 
 /// A command argument (positional or named).
@@ -305,8 +390,8 @@ pub enum Expr {
     /// Variable with default: `${VAR:-default}` / `${path[sub]:-default}` — use
     /// default if the path is absent (unset root, missing key, out-of-bounds) or
     /// empty. The default can contain nested variable expansions and command
-    /// substitutions.
-    VarWithDefault { path: VarPath, default: Vec<StringPart> },
+    /// substitutions. `op` selects the `:=`, `:?` and `:+` variants.
+    VarWithDefault { path: VarPath, op: DefaultOp, default: Vec<StringPart> },
     /// Arithmetic expansion: `$((expr))` - evaluates to integer
     Arithmetic(String),
     /// Command as condition: `if grep -q pattern file; then` - exit code determines truthiness
@@ -324,6 +409,14 @@ pub enum Expr {
     /// Record literal: `{name: amy, role: maintainer}`, `{port:8080}` (colon
     /// may be spaced or unspaced). Value-position only, same as `ListLiteral`.
     RecordLiteral(Vec<RecordEntry>),
+    /// Conditional value: `X=if [[ $n -gt 5 ]] then big else small fi`.
+    /// Value-position only, like the literals above. Only the chosen branch
+    /// is evaluated; `elif` nests another `Conditional` in `else_value`.
+    Conditional {
+        condition: Box<Expr>,
+        then_value: Box<Expr>,
+        else_value: Box<Expr>,
+    },
 }
 
 /// One element of a list literal.
@@ -501,6 +594,44 @@ impl VarPath {
     }
 }
 
+/// The operator in a `${path<op>word}` expansion. Every form treats an absent
+/// path (unset root, missing key, out-of-bounds index) and an empty value
+/// alike, as the colon forms do in bash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefaultOp {
+    /// `:-` — the value, or the word when absent/empty.
+    UseDefault,
+    /// `:=` — like `:-`, and also assigns the word to the variable.
+    AssignDefault,
+    /// `:?` — the value, or a loud error (the word is the message).
+    ErrorIfUnset,
+    /// `:+` — the word when the value is present, else empty.
+    UseAlternate,
+}
+
+impl DefaultOp {
+    /// The operator as written, e.g. `:-`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DefaultOp::UseDefault => ":-",
+            DefaultOp::AssignDefault => ":=",
+            DefaultOp::ErrorIfUnset => ":?",
+            DefaultOp::UseAlternate => ":+",
+        }
+    }
+
+    /// The operator whose second character is `c` (`-`, `=`, `?`, `+`).
+    pub fn from_char(c: u8) -> Option<Self> {
+        match c {
+            b'-' => Some(DefaultOp::UseDefault),
+            b'=' => Some(DefaultOp::AssignDefault),
+            b'?' => Some(DefaultOp::ErrorIfUnset),
+            b'+' => Some(DefaultOp::UseAlternate),
+            _ => None,
+        }
+    }
+}
+
 /// A segment in a variable path.
 ///
 /// The first segment of a path is the root name, carried as `Field`. Every
@@ -530,8 +661,8 @@ pub enum StringPart {
     /// Variable interpolation: `${VAR}` or `$VAR`
     Var(VarPath),
     /// Variable with default: `${VAR:-default}` / `${path[sub]:-default}` where
-    /// default can contain nested expansions
-    VarWithDefault { path: VarPath, default: Vec<StringPart> },
+    /// default can contain nested expansions; `op` selects `:=`, `:?` or `:+`
+    VarWithDefault { path: VarPath, op: DefaultOp, default: Vec<StringPart> },
     /// Variable string length: `${#VAR}` or `${#path[sub]}`
     VarLength(VarPath),
     /// Positional parameter: `$0`, `$1`, ..., `$9`
@@ -0,0 +1,484 @@
+//! Append-only audit log of executed commands.
+//!
+//! An [`AuditLog`] is a JSONL file on the host. Set it on
+//! [`KernelConfig`](crate::KernelConfig) with `with_audit_log`, and every
+//! command the kernel dispatches — builtins, `.kai` scripts, external
+//! commands, backend tools — appends one [`AuditEntry`] once it finishes:
+//! timestamp, kernel name, originating interface, command, evaluated argv,
+//! and exit code. Shell functions are not recorded themselves; the commands
+//! they run are.
+//!
+//! The log is shared by `Arc`, so several kernels (a REPL and the forks its
+//! background jobs run on, or every per-call kernel an agent embedder builds)
+//! can write to one file. The `origin` names the frontend that opened the
+//! handle (`repl`, `mcp`, `serve`, …), so a single file can hold several.
+//!
+//! Recording is best effort: a failed append is logged through `tracing` and
+//! never changes the command's result. Read it back with
+//! [`AuditLog::entries`] / [`AuditLog::tail`], trim it with
+//! [`AuditLog::prune`] / [`AuditLog::prune_with`], search it with
+//! [`AuditLog::search`], or from a script with the `kaish-audit` builtin.
+//!
+//! A long-lived kernel shouldn't grow the file forever: give the handle an
+//! [`AuditRetention`] (`AuditLog::with_retention`) and `record` trims the
+//! oldest entries past a count, an age, or a file size. The policy runs on
+//! the first append through a handle and then every
+//! [`RETENTION_CHECK_INTERVAL`] appends, so the file can run over its limits
+//! by that many entries between passes.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// One executed command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// When the command finished, RFC 3339 in UTC.
+    pub timestamp: String,
+    /// Name of the kernel that ran it (`KernelConfig::name`).
+    pub kernel: String,
+    /// Frontend that opened the log (`repl`, `mcp`, …).
+    pub origin: String,
+    /// Command name as dispatched (after alias expansion).
+    pub command: String,
+    /// Arguments after evaluation (variables, globs, `$(…)` expanded).
+    pub argv: Vec<String>,
+    /// Exit code.
+    pub code: i64,
+}
+
+impl AuditEntry {
+    /// The command and its arguments as one line.
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.command.as_str())
+            .chain(self.argv.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// One [`AuditLog::search`] hit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditMatch {
+    /// 1-based position in the log; stable until the next prune.
+    pub id: usize,
+    /// The matching entry.
+    #[serde(flatten)]
+    pub entry: AuditEntry,
+    /// The command line, cut to a window around the first match.
+    pub snippet: String,
+}
+
+/// Characters of command line a search snippet shows.
+const SNIPPET_WIDTH: usize = 80;
+
+/// Appends between the retention passes `AuditLog::record` makes.
+pub const RETENTION_CHECK_INTERVAL: usize = 64;
+
+/// How much an [`AuditLog`] keeps. Unset limits don't apply; when several are
+/// set, an entry survives only if it fits all of them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AuditRetention {
+    /// Keep at most this many entries, the newest.
+    pub max_entries: Option<usize>,
+    /// Drop entries older than this.
+    pub max_age: Option<Duration>,
+    /// Keep the file at or under this many bytes.
+    pub max_bytes: Option<u64>,
+}
+
+impl AuditRetention {
+    /// Keep at most `max_entries` entries.
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    /// Drop entries older than `max_age`.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Keep the file at or under `max_bytes`.
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Whether no limit is set.
+    pub fn is_unbounded(&self) -> bool {
+        self.max_entries.is_none() && self.max_age.is_none() && self.max_bytes.is_none()
+    }
+
+    /// Index of the first entry to keep in `entries` (oldest first) as of `now`.
+    fn first_kept(&self, entries: &[AuditEntry], now: chrono::DateTime<chrono::Utc>) -> usize {
+        let mut start = 0;
+        if let Some(max_entries) = self.max_entries {
+            start = start.max(entries.len().saturating_sub(max_entries));
+        }
+        if let Some(max_age) = self.max_age
+            && let Ok(max_age) = chrono::Duration::from_std(max_age)
+        {
+            let cutoff = now - max_age;
+            // Entries are appended in time order, so everything from the first
+            // one inside the window on is kept. Unparseable stamps count as fresh.
+            let fresh = entries
+                .iter()
+                .position(|entry| {
+                    chrono::DateTime::parse_from_rfc3339(&entry.timestamp)
+                        .ok()
+                        .is_none_or(|t| t.with_timezone(&chrono::Utc) >= cutoff)
+                })
+                .unwrap_or(entries.len());
+            start = start.max(fresh);
+        }
+        if let Some(max_bytes) = self.max_bytes {
+            let mut total = 0u64;
+            let mut first = entries.len();
+            for (index, entry) in entries.iter().enumerate().rev() {
+                total += serde_json::to_string(entry).map_or(0, |line| line.len() as u64 + 1);
+                if total > max_bytes {
+                    break;
+                }
+                first = index;
+            }
+            start = start.max(first);
+        }
+        start
+    }
+}
+
+/// Handle to a JSONL audit log file.
+#[derive(Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+    origin: String,
+    retention: AuditRetention,
+    /// Serializes appends and prunes from concurrent kernels sharing this
+    /// handle; holds the number of appends left before the next retention pass.
+    lock: Mutex<usize>,
+}
+
+impl AuditLog {
+    /// Open (lazily — nothing is touched until the first record) a log at
+    /// `path`, stamping entries with `origin`.
+    pub fn new(path: impl Into<PathBuf>, origin: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            origin: origin.into(),
+            retention: AuditRetention::default(),
+            lock: Mutex::new(0),
+        }
+    }
+
+    /// Enforce `retention` as entries are recorded.
+    pub fn with_retention(mut self, retention: AuditRetention) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// The retention policy `record` enforces.
+    pub fn retention(&self) -> &AuditRetention {
+        &self.retention
+    }
+
+    /// Default location: `$XDG_DATA_HOME/kaish/audit.jsonl`.
+    pub fn default_path() -> PathBuf {
+        crate::paths::data_dir().join("audit.jsonl")
+    }
+
+    /// The log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The origin stamped on entries written through this handle.
+    pub fn origin(&self) -> &str {
+        &self.origin
+    }
+
+    /// Append an entry for `command` run by `kernel`.
+    pub fn record(&self, kernel: &str, command: &str, argv: &[String], code: i64) -> std::io::Result<()> {
+        let entry = AuditEntry {
+            timestamp: chrono::DateTime::<chrono::Utc>::from(kaish_types::clock::system_now())
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            kernel: kernel.to_string(),
+            origin: self.origin.clone(),
+            command: command.to_string(),
+            argv: argv.to_vec(),
+            code,
+        };
+        let mut line = serde_json::to_string(&entry).map_err(std::io::Error::other)?;
+        line.push('\n');
+
+        let mut until_check = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(line.as_bytes())?;
+        drop(file);
+
+        if self.retention.is_unbounded() {
+            return Ok(());
+        }
+        if *until_check == 0 {
+            *until_check = RETENTION_CHECK_INTERVAL;
+            self.prune_locked(&self.retention)?;
+        }
+        *until_check -= 1;
+        Ok(())
+    }
+
+    /// Every entry, oldest first. A missing file is an empty log; lines that
+    /// don't parse (a torn write, hand edits) are skipped.
+    pub fn entries(&self) -> std::io::Result<Vec<AuditEntry>> {
+        let text = match std::fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        Ok(text
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// The most recent `count` entries, oldest first.
+    pub fn tail(&self, count: usize) -> std::io::Result<Vec<AuditEntry>> {
+        let mut entries = self.entries()?;
+        let start = entries.len().saturating_sub(count);
+        Ok(entries.split_off(start))
+    }
+
+    /// Entries whose command line contains every whitespace-separated term of
+    /// `query`, case-insensitively, oldest first. An empty query matches
+    /// everything.
+    pub fn search(&self, query: &str) -> std::io::Result<Vec<AuditMatch>> {
+        let terms: Vec<Vec<char>> = query.split_whitespace().map(fold).collect();
+        Ok(self
+            .entries()?
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                let line = entry.command_line();
+                let folded = fold(&line);
+                let mut first = None;
+                for term in &terms {
+                    let at = find(&folded, term)?;
+                    first.get_or_insert(at);
+                }
+                Some(AuditMatch {
+                    id: index + 1,
+                    snippet: snippet(&line, first.unwrap_or(0)),
+                    entry,
+                })
+            })
+            .collect())
+    }
+
+    /// Drop all but the most recent `keep` entries. Returns how many were
+    /// removed.
+    pub fn prune(&self, keep: usize) -> std::io::Result<usize> {
+        self.prune_with(&AuditRetention::default().with_max_entries(keep))
+    }
+
+    /// Drop every entry `retention` doesn't keep, now. Returns how many were
+    /// removed.
+    pub fn prune_with(&self, retention: &AuditRetention) -> std::io::Result<usize> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.prune_locked(retention)
+    }
+
+    fn prune_locked(&self, retention: &AuditRetention) -> std::io::Result<usize> {
+        let entries = self.entries()?;
+        let now = chrono::DateTime::<chrono::Utc>::from(kaish_types::clock::system_now());
+        let removed = retention.first_kept(&entries, now);
+        if removed == 0 {
+            return Ok(0);
+        }
+        let mut text = String::new();
+        for entry in &entries[removed..] {
+            text.push_str(&serde_json::to_string(entry).map_err(std::io::Error::other)?);
+            text.push('\n');
+        }
+        std::fs::write(&self.path, text)?;
+        Ok(removed)
+    }
+}
+
+/// Lowercase `text` one char per char, so positions in the result are char
+/// positions in `text`.
+fn fold(text: &str) -> Vec<char> {
+    text.chars().map(|c| c.to_lowercase().next().unwrap_or(c)).collect()
+}
+
+fn find(haystack: &[char], needle: &[char]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// Up to [`SNIPPET_WIDTH`] chars of `line` around char position `at`, with
+/// `…` marking cut ends.
+fn snippet(line: &str, at: usize) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.len() <= SNIPPET_WIDTH {
+        return line.to_string();
+    }
+    let start = at.saturating_sub(SNIPPET_WIDTH / 4).min(chars.len() - SNIPPET_WIDTH);
+    let end = start + SNIPPET_WIDTH;
+    let mut out = String::new();
+    if start > 0 {
+        out.push('…');
+    }
+    out.extend(&chars[start..end]);
+    if end < chars.len() {
+        out.push('…');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_and_read_back() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let log = AuditLog::new(dir.path().join("nested").join("audit.jsonl"), "repl");
+        log.record("k1", "echo", &["hi".to_string()], 0).expect("record");
+        log.record("k1", "false", &[], 1).expect("record");
+
+        let entries = log.entries().expect("entries");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].command, "echo");
+        assert_eq!(entries[0].argv, vec!["hi".to_string()]);
+        assert_eq!(entries[0].origin, "repl");
+        assert_eq!(entries[0].kernel, "k1");
+        assert_eq!(entries[1].code, 1);
+        assert!(entries[0].timestamp.ends_with('Z'), "UTC timestamp: {}", entries[0].timestamp);
+    }
+
+    #[test]
+    fn missing_file_is_empty_and_bad_lines_are_skipped() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("audit.jsonl");
+        let log = AuditLog::new(&path, "test");
+        assert!(log.entries().expect("entries").is_empty());
+
+        log.record("k", "echo", &[], 0).expect("record");
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut f| f.write_all(b"{not json\n"))
+            .expect("append garbage");
+        assert_eq!(log.entries().expect("entries").len(), 1);
+    }
+
+    #[test]
+    fn tail_and_prune_keep_the_newest() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let log = AuditLog::new(dir.path().join("audit.jsonl"), "test");
+        for code in 0..5 {
+            log.record("k", "cmd", &[], code).expect("record");
+        }
+        let tail: Vec<i64> = log.tail(2).expect("tail").iter().map(|e| e.code).collect();
+        assert_eq!(tail, vec![3, 4]);
+
+        assert_eq!(log.prune(3).expect("prune"), 2);
+        let codes: Vec<i64> = log.entries().expect("entries").iter().map(|e| e.code).collect();
+        assert_eq!(codes, vec![2, 3, 4]);
+        assert_eq!(log.prune(10).expect("prune"), 0);
+    }
+
+    #[test]
+    fn search_matches_every_term_case_insensitively() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let log = AuditLog::new(dir.path().join("audit.jsonl"), "test");
+        log.record("k", "cargo", &["test".to_string(), "--workspace".to_string()], 0).expect("record");
+        log.record("k", "cargo", &["build".to_string()], 0).expect("record");
+        log.record("k", "echo", &["Cargo Test".to_string()], 0).expect("record");
+
+        let hits = log.search("cargo TEST").expect("search");
+        let ids: Vec<usize> = hits.iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(hits[0].snippet, "cargo test --workspace");
+        assert_eq!(hits[0].entry.command, "cargo");
+        assert!(log.search("cargo deploy").expect("search").is_empty());
+        assert_eq!(log.search("").expect("search").len(), 3);
+    }
+
+    #[test]
+    fn snippet_windows_long_lines_around_the_match() {
+        let line = format!("{} needle {}", "a".repeat(100), "b".repeat(100));
+        let at = fold(&line).iter().position(|&c| c == 'n').expect("match");
+        let cut = snippet(&line, at);
+        assert!(cut.starts_with('…') && cut.ends_with('…'), "got: {cut}");
+        assert!(cut.contains("needle"), "got: {cut}");
+        assert_eq!(cut.chars().count(), SNIPPET_WIDTH + 2);
+        assert_eq!(snippet("short", 0), "short");
+    }
+
+    fn entry_at(timestamp: &str, code: i64) -> AuditEntry {
+        AuditEntry {
+            timestamp: timestamp.to_string(),
+            kernel: "k".to_string(),
+            origin: "test".to_string(),
+            command: "cmd".to_string(),
+            argv: Vec::new(),
+            code,
+        }
+    }
+
+    #[test]
+    fn retention_by_age_keeps_the_recent_window() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-03-10T12:00:00Z")
+            .expect("timestamp")
+            .with_timezone(&chrono::Utc);
+        let entries = vec![
+            entry_at("2026-03-01T00:00:00.000Z", 0),
+            entry_at("2026-03-08T00:00:00.000Z", 1),
+            entry_at("2026-03-10T11:00:00.000Z", 2),
+        ];
+        let week = AuditRetention::default().with_max_age(Duration::from_secs(7 * 86400));
+        assert_eq!(week.first_kept(&entries, now), 1);
+        let hour = AuditRetention::default().with_max_age(Duration::from_secs(3600));
+        assert_eq!(hour.first_kept(&entries, now), 2);
+        assert_eq!(AuditRetention::default().first_kept(&entries, now), 0);
+    }
+
+    #[test]
+    fn retention_by_size_and_count_take_the_stricter_limit() {
+        let now = chrono::Utc::now();
+        let entries: Vec<AuditEntry> = (0..4).map(|code| entry_at("2026-03-10T00:00:00.000Z", code)).collect();
+        let line = serde_json::to_string(&entries[0]).expect("json").len() as u64 + 1;
+
+        let two_lines = AuditRetention::default().with_max_bytes(line * 2);
+        assert_eq!(two_lines.first_kept(&entries, now), 2);
+        let both = two_lines.with_max_entries(1);
+        assert_eq!(both.first_kept(&entries, now), 3);
+    }
+
+    #[test]
+    fn record_enforces_retention() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let log = AuditLog::new(dir.path().join("audit.jsonl"), "test")
+            .with_retention(AuditRetention::default().with_max_entries(3));
+        // One pass on the first append, the next after the interval.
+        for code in 0..=RETENTION_CHECK_INTERVAL as i64 {
+            log.record("k", "cmd", &[], code).expect("record");
+        }
+        let codes: Vec<i64> = log.entries().expect("entries").iter().map(|e| e.code).collect();
+        let last = RETENTION_CHECK_INTERVAL as i64;
+        assert_eq!(codes, vec![last - 2, last - 1, last]);
+    }
+}
@@ -9,7 +9,7 @@ use std::sync::Arc;
 
 use super::{
     BackendError, BackendResult, ConflictError, KernelBackend, PatchOp, ReadRange,
-    ToolInfo, ToolResult, WriteMode,
+    SnapshotInfo, ToolInfo, ToolResult, WriteMode,
 };
 use crate::tools::{ToolArgs, ToolCtx, ToolRegistry};
 use crate::vfs::{DirEntry, Filesystem, MountInfo, VfsRouter};
@@ -244,14 +244,8 @@ impl KernelBackend for LocalBackend {
     }
 
     async fn append(&self, path: &Path, content: &[u8]) -> BackendResult<()> {
-        // Read existing content
-        let mut existing = match self.vfs.read(path).await {
-            Ok(data) => data,
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
-            Err(e) => return Err(e.into()),
-        };
-        existing.extend_from_slice(content);
-        self.vfs.write(path, &existing).await?;
+        // The mount decides how: most read and write back, a pipe just queues.
+        self.vfs.append(path, content).await?;
         Ok(())
     }
 
@@ -399,6 +393,26 @@ impl KernelBackend for LocalBackend {
         }
     }
 
+    // ═══════════════════════════════════════════════════════════════════════════
+    // Snapshots
+    // ═══════════════════════════════════════════════════════════════════════════
+
+    async fn snapshot(&self, path: &Path) -> BackendResult<u64> {
+        Ok(self.vfs.snapshot(path).await?)
+    }
+
+    async fn restore_snapshot(&self, path: &Path, id: u64) -> BackendResult<()> {
+        Ok(self.vfs.restore_snapshot(path, id).await?)
+    }
+
+    async fn drop_snapshot(&self, path: &Path, id: u64) -> BackendResult<()> {
+        Ok(self.vfs.drop_snapshot(path, id).await?)
+    }
+
+    async fn snapshots(&self, path: &Path) -> BackendResult<Vec<SnapshotInfo>> {
+        Ok(self.vfs.snapshots(path).await?)
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // Backend Information
     // ═══════════════════════════════════════════════════════════════════════════
@@ -22,9 +22,11 @@
 
 mod local;
 mod overlay;
+mod policy;
 
 pub use local::LocalBackend;
 pub use overlay::VirtualOverlayBackend;
+pub use policy::PolicyBackend;
 
 #[cfg(test)]
 pub mod testing;
@@ -34,8 +36,8 @@ pub use testing::MockBackend;
 
 // Data types re-exported from kaish-types.
 pub use kaish_types::backend::{
-    BackendError, BackendResult, ConflictError, MountInfo, PatchOp, ReadRange, ToolInfo,
-    ToolResult, WriteMode,
+    BackendError, BackendResult, ConflictError, MountInfo, PatchOp, ReadRange, SnapshotInfo,
+    ToolInfo, ToolResult, WriteMode,
 };
 
 // The `KernelBackend` trait moved to the leaf `kaish-tool-api` crate (its
@@ -36,7 +36,7 @@ use std::sync::Arc;
 
 use super::{
     BackendError, BackendResult, KernelBackend, LocalBackend, PatchOp, ReadRange,
-    ToolInfo, ToolResult, WriteMode,
+    SnapshotInfo, ToolInfo, ToolResult, WriteMode,
 };
 use crate::tools::{ToolArgs, ToolCtx};
 use crate::vfs::{DirEntry, Filesystem, MountInfo, VfsRouter};
@@ -189,13 +189,7 @@ impl KernelBackend for VirtualOverlayBackend {
 
     async fn append(&self, path: &Path, content: &[u8]) -> BackendResult<()> {
         if self.is_virtual_path(path) {
-            let mut existing = match self.vfs.read(path).await {
-                Ok(data) => data,
-                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
-                Err(e) => return Err(e.into()),
-            };
-            existing.extend_from_slice(content);
-            self.vfs.write(path, &existing).await?;
+            self.vfs.append(path, content).await?;
             Ok(())
         } else if self.is_shared_ancestor(path) {
             Err(BackendError::IsDirectory(synth_dir_note(path)))
@@ -393,6 +387,42 @@ impl KernelBackend for VirtualOverlayBackend {
         }
     }
 
+    // ═══════════════════════════════════════════════════════════════════════════
+    // Snapshots
+    // ═══════════════════════════════════════════════════════════════════════════
+
+    async fn snapshot(&self, path: &Path) -> BackendResult<u64> {
+        if self.is_virtual_path(path) {
+            Ok(self.vfs.snapshot(path).await?)
+        } else {
+            self.inner.snapshot(path).await
+        }
+    }
+
+    async fn restore_snapshot(&self, path: &Path, id: u64) -> BackendResult<()> {
+        if self.is_virtual_path(path) {
+            Ok(self.vfs.restore_snapshot(path, id).await?)
+        } else {
+            self.inner.restore_snapshot(path, id).await
+        }
+    }
+
+    async fn drop_snapshot(&self, path: &Path, id: u64) -> BackendResult<()> {
+        if self.is_virtual_path(path) {
+            Ok(self.vfs.drop_snapshot(path, id).await?)
+        } else {
+            self.inner.drop_snapshot(path, id).await
+        }
+    }
+
+    async fn snapshots(&self, path: &Path) -> BackendResult<Vec<SnapshotInfo>> {
+        if self.is_virtual_path(path) {
+            Ok(self.vfs.snapshots(path).await?)
+        } else {
+            self.inner.snapshots(path).await
+        }
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // Tool Dispatch
     // ═══════════════════════════════════════════════════════════════════════════
@@ -0,0 +1,165 @@
+//! PolicyBackend: enforces a [`Policy`]'s writable prefixes on another backend.
+//!
+//! The kernel wraps its backend in this when `Policy::writable_prefixes` is
+//! set. Reads pass straight through; every mutating call is checked against
+//! the prefixes first and refused with `PermissionDenied` when outside them.
+//! A symlink's target is checked as well as the link itself.
+
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::{
+    BackendError, BackendResult, KernelBackend, PatchOp, ReadRange, SnapshotInfo, ToolInfo,
+    ToolResult, WriteMode,
+};
+use crate::policy::Policy;
+use crate::tools::{ToolArgs, ToolCtx};
+use crate::vfs::{DirEntry, MountInfo};
+
+/// Backend that refuses writes outside the policy's writable prefixes.
+pub struct PolicyBackend {
+    inner: Arc<dyn KernelBackend>,
+    policy: Arc<Policy>,
+}
+
+impl PolicyBackend {
+    /// Wrap `inner`, enforcing `policy`'s writable prefixes.
+    pub fn new(inner: Arc<dyn KernelBackend>, policy: Arc<Policy>) -> Self {
+        Self { inner, policy }
+    }
+
+    fn check_write(&self, path: &Path) -> BackendResult<()> {
+        if self.policy.allows_write(path) {
+            Ok(())
+        } else {
+            Err(BackendError::PermissionDenied(Policy::write_denied_message(path)))
+        }
+    }
+}
+
+#[async_trait]
+impl KernelBackend for PolicyBackend {
+    async fn read(&self, path: &Path, range: Option<ReadRange>) -> BackendResult<Vec<u8>> {
+        self.inner.read(path, range).await
+    }
+
+    async fn write(&self, path: &Path, content: &[u8], mode: WriteMode) -> BackendResult<()> {
+        self.check_write(path)?;
+        self.inner.write(path, content, mode).await
+    }
+
+    async fn append(&self, path: &Path, content: &[u8]) -> BackendResult<()> {
+        self.check_write(path)?;
+        self.inner.append(path, content).await
+    }
+
+    async fn patch(&self, path: &Path, ops: &[PatchOp]) -> BackendResult<()> {
+        self.check_write(path)?;
+        self.inner.patch(path, ops).await
+    }
+
+    async fn list(&self, path: &Path) -> BackendResult<Vec<DirEntry>> {
+        self.inner.list(path).await
+    }
+
+    async fn stat(&self, path: &Path) -> BackendResult<DirEntry> {
+        self.inner.stat(path).await
+    }
+
+    async fn mkdir(&self, path: &Path) -> BackendResult<()> {
+        self.check_write(path)?;
+        self.inner.mkdir(path).await
+    }
+
+    async fn set_mtime(&self, path: &Path, mtime: std::time::SystemTime) -> BackendResult<()> {
+        self.check_write(path)?;
+        self.inner.set_mtime(path, mtime).await
+    }
+
+    async fn remove(&self, path: &Path, recursive: bool) -> BackendResult<()> {
+        self.check_write(path)?;
+        self.inner.remove(path, recursive).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> BackendResult<()> {
+        self.check_write(from)?;
+        self.check_write(to)?;
+        self.inner.rename(from, to).await
+    }
+
+    async fn exists(&self, path: &Path) -> bool {
+        self.inner.exists(path).await
+    }
+
+    async fn lstat(&self, path: &Path) -> BackendResult<DirEntry> {
+        self.inner.lstat(path).await
+    }
+
+    async fn read_link(&self, path: &Path) -> BackendResult<PathBuf> {
+        self.inner.read_link(path).await
+    }
+
+    async fn symlink(&self, target: &Path, link: &Path) -> BackendResult<()> {
+        self.check_write(link)?;
+        // A link out of the prefixes would let later writes through it land
+        // anywhere, so its target has to be writable too.
+        let resolved = match link.parent() {
+            Some(parent) if target.is_relative() => parent.join(target),
+            _ => target.to_path_buf(),
+        };
+        self.check_write(&resolved)?;
+        self.inner.symlink(target, link).await
+    }
+
+    async fn snapshot(&self, path: &Path) -> BackendResult<u64> {
+        self.inner.snapshot(path).await
+    }
+
+    async fn restore_snapshot(&self, path: &Path, id: u64) -> BackendResult<()> {
+        self.check_write(path)?;
+        self.inner.restore_snapshot(path, id).await
+    }
+
+    async fn drop_snapshot(&self, path: &Path, id: u64) -> BackendResult<()> {
+        self.check_write(path)?;
+        self.inner.drop_snapshot(path, id).await
+    }
+
+    async fn snapshots(&self, path: &Path) -> BackendResult<Vec<SnapshotInfo>> {
+        self.inner.snapshots(path).await
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        args: ToolArgs,
+        ctx: &mut dyn ToolCtx,
+    ) -> BackendResult<ToolResult> {
+        self.inner.call_tool(name, args, ctx).await
+    }
+
+    async fn list_tools(&self) -> BackendResult<Vec<ToolInfo>> {
+        self.inner.list_tools().await
+    }
+
+    async fn get_tool(&self, name: &str) -> BackendResult<Option<ToolInfo>> {
+        self.inner.get_tool(name).await
+    }
+
+    fn read_only(&self) -> bool {
+        self.inner.read_only()
+    }
+
+    fn backend_type(&self) -> &str {
+        self.inner.backend_type()
+    }
+
+    fn mounts(&self) -> Vec<MountInfo> {
+        self.inner.mounts()
+    }
+
+    fn resolve_real_path(&self, path: &Path) -> Option<PathBuf> {
+        self.inner.resolve_real_path(path)
+    }
+}
@@ -0,0 +1,193 @@
+//! Session checkpoints: snapshot a kernel's variables and cwd, roll back later.
+//!
+//! A [`Checkpoint`] captures every variable (with the export set) and the
+//! working directory at the moment it is taken. Restoring one replaces the
+//! session's variables wholesale — variables set since are dropped — and
+//! returns to the recorded cwd. Shell options (`set -o …`), functions,
+//! aliases, and file contents are not part of a checkpoint; pair a
+//! checkpoint with the overlay (`KernelConfig::with_overlay`) when an agent
+//! also needs its file writes undone.
+//!
+//! The [`CheckpointStore`] lives on the `ExecContext` and is Arc-shared like
+//! the nonce store, so forks and pipeline stages see the same checkpoints.
+//! Embedders use `Kernel::create_checkpoint` / `Kernel::restore_checkpoint`;
+//! scripts use the `kaish-checkpoint` builtin.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast::Value;
+use crate::interpreter::Scope;
+
+/// A snapshot of session variables and cwd.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Store-assigned id, unique for the life of the store.
+    pub id: u64,
+    /// Optional human label (`kaish-checkpoint create before-migration`).
+    pub label: Option<String>,
+    /// When the checkpoint was taken, RFC 3339 in UTC.
+    pub created: String,
+    /// Every variable (inner frames shadowing outer ones).
+    #[serde(with = "crate::state::typed_vars")]
+    pub vars: BTreeMap<String, Value>,
+    /// Names marked for export.
+    pub exported: Vec<String>,
+    /// Working directory.
+    pub cwd: PathBuf,
+    /// Request-history position when the checkpoint was taken (see
+    /// [`crate::history::RequestHistory::mark`]); `distill` starts here.
+    #[serde(default)]
+    pub history_mark: u64,
+}
+
+impl Checkpoint {
+    /// Capture `scope` and `cwd`, at request-history position `history_mark`.
+    pub fn capture(id: u64, label: Option<String>, scope: &Scope, cwd: &Path, history_mark: u64) -> Self {
+        Self {
+            id,
+            label,
+            created: chrono::DateTime::<chrono::Utc>::from(kaish_types::clock::system_now())
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            vars: scope.all().into_iter().collect(),
+            exported: scope.exported_names().into_iter().map(String::from).collect(),
+            cwd: cwd.to_path_buf(),
+            history_mark,
+        }
+    }
+
+    /// Put this checkpoint's variables back into `scope`. The cwd is the
+    /// caller's to restore — it lives on the `ExecContext`, not the scope.
+    pub fn restore_into(&self, scope: &mut Scope) {
+        scope.replace_variables(
+            self.vars.iter().map(|(name, value)| (name.clone(), value.clone())),
+            self.exported.iter().cloned(),
+        );
+    }
+}
+
+/// Shared, in-memory list of checkpoints.
+#[derive(Clone, Debug, Default)]
+pub struct CheckpointStore {
+    inner: Arc<Mutex<CheckpointStoreInner>>,
+}
+
+#[derive(Debug, Default)]
+struct CheckpointStoreInner {
+    next_id: u64,
+    checkpoints: Vec<Checkpoint>,
+}
+
+impl CheckpointStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CheckpointStoreInner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Snapshot `scope` and `cwd`, returning the new checkpoint's id.
+    /// `history_mark` is where the session's request history stands.
+    pub fn create(&self, label: Option<String>, scope: &Scope, cwd: &Path, history_mark: u64) -> u64 {
+        let mut inner = self.lock();
+        inner.next_id += 1;
+        let id = inner.next_id;
+        inner.checkpoints.push(Checkpoint::capture(id, label, scope, cwd, history_mark));
+        id
+    }
+
+    /// The checkpoint with `id`.
+    pub fn get(&self, id: u64) -> Option<Checkpoint> {
+        self.lock().checkpoints.iter().find(|c| c.id == id).cloned()
+    }
+
+    /// Look a checkpoint up by id, or else by label (the newest with that
+    /// label wins).
+    pub fn find(&self, id_or_label: &str) -> Option<Checkpoint> {
+        if let Ok(id) = id_or_label.parse::<u64>()
+            && let Some(checkpoint) = self.get(id)
+        {
+            return Some(checkpoint);
+        }
+        self.lock()
+            .checkpoints
+            .iter()
+            .rev()
+            .find(|c| c.label.as_deref() == Some(id_or_label))
+            .cloned()
+    }
+
+    /// All checkpoints, oldest first.
+    pub fn list(&self) -> Vec<Checkpoint> {
+        self.lock().checkpoints.clone()
+    }
+
+    /// Replace every checkpoint with `checkpoints`, keeping their ids (an
+    /// import). New checkpoints continue numbering past the highest one.
+    pub fn replace_all(&self, checkpoints: Vec<Checkpoint>) {
+        let mut inner = self.lock();
+        inner.next_id = checkpoints.iter().map(|c| c.id).max().unwrap_or(0).max(inner.next_id);
+        inner.checkpoints = checkpoints;
+    }
+
+    /// Drop the checkpoint with `id`. Returns whether it existed.
+    pub fn remove(&self, id: u64) -> bool {
+        let mut inner = self.lock();
+        let before = inner.checkpoints.len();
+        inner.checkpoints.retain(|c| c.id != id);
+        inner.checkpoints.len() != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_and_restore_round_trip() {
+        let store = CheckpointStore::new();
+        let mut scope = Scope::new();
+        scope.set_exported("A", Value::Int(1));
+        let id = store.create(Some("start".into()), &scope, Path::new("/work"), 0);
+
+        scope.set("A", Value::Int(2));
+        scope.set("B", Value::Int(3));
+        scope.unexport("A");
+
+        let checkpoint = store.get(id).expect("checkpoint");
+        assert_eq!(checkpoint.cwd, PathBuf::from("/work"));
+        checkpoint.restore_into(&mut scope);
+        assert_eq!(scope.get("A"), Some(&Value::Int(1)));
+        assert_eq!(scope.get("B"), None);
+        assert!(scope.is_exported("A"));
+    }
+
+    #[test]
+    fn find_by_id_or_newest_label() {
+        let store = CheckpointStore::new();
+        let scope = Scope::new();
+        let first = store.create(Some("x".into()), &scope, Path::new("/"), 0);
+        let second = store.create(Some("x".into()), &scope, Path::new("/"), 0);
+
+        assert_eq!(store.find(&first.to_string()).map(|c| c.id), Some(first));
+        assert_eq!(store.find("x").map(|c| c.id), Some(second));
+        assert!(store.find("missing").is_none());
+    }
+
+    #[test]
+    fn ids_are_not_reused_after_remove() {
+        let store = CheckpointStore::new();
+        let scope = Scope::new();
+        let first = store.create(None, &scope, Path::new("/"), 0);
+        assert!(store.remove(first));
+        assert!(!store.remove(first));
+        let second = store.create(None, &scope, Path::new("/"), 0);
+        assert_ne!(first, second);
+        assert_eq!(store.list().len(), 1);
+    }
+}
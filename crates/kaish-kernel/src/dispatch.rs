@@ -28,6 +28,7 @@ use async_trait::async_trait;
 
 use crate::ast::{Command, Expr, Value};
 use crate::interpreter::ExecResult;
+use crate::tool_library::ToolLibraryReport;
 use crate::tools::ExecContext;
 
 // The following imports are only used by the test-only `BackendDispatcher`.
@@ -110,6 +111,19 @@ pub trait CommandDispatcher: Send + Sync {
     async fn fork_attached(&self) -> Arc<dyn CommandDispatcher> {
         self.fork().await
     }
+
+    /// Whether `name` is a user-defined function in this dispatcher's
+    /// session (`which`). Dispatchers without functions have none.
+    async fn has_function(&self, _name: &str) -> bool {
+        false
+    }
+
+    /// Re-scan the tool library and register its function definitions
+    /// (`kaish-tools reload`). Dispatchers without a tool library, like the
+    /// test dispatcher below, load nothing.
+    async fn reload_tool_library(&self) -> ToolLibraryReport {
+        ToolLibraryReport::default()
+    }
 }
 
 /// Minimal stateless dispatcher used by pipeline/runner unit tests.
@@ -188,7 +202,7 @@ impl BackendDispatcher {
             if resolved.exists() {
                 resolved.to_string_lossy().into_owned()
             } else {
-                return Some(ExecResult::failure(127, format!("{}: No such file or directory", name)));
+                return Some(ExecResult::not_found(format!("{}: No such file or directory", name)));
             }
         } else {
             // PATH from scope only — never OS env (keeps this test-only spawn
@@ -327,7 +341,7 @@ impl BackendDispatcher {
 
         let mut child = match cmd.spawn() {
             Ok(c) => c,
-            Err(e) => return Some(ExecResult::failure(127, format!("{}: {}", name, e))),
+            Err(e) => return Some(ExecResult::not_found(format!("{}: {}", name, e))),
         };
         // Open a pidfd (Linux) for race-free direct-child kill via wait_or_kill.
         let kill_target = crate::pidfd::KillTarget::from_child(&child);
@@ -494,7 +508,7 @@ impl BackendDispatcher {
             stderr = format!("{}{stderr}", stats.overflow_marker("stdout"));
             result.did_spill = true;
         }
-        result.err = stderr;
+        result.stderr = stderr;
         Some(result)
     }
 }
@@ -541,10 +555,10 @@ impl CommandDispatcher for BackendDispatcher {
                 // Fall back to external command execution
                 match self.try_external(&cmd.name, &cmd.args, ctx).await {
                     Some(result) => result,
-                    None => ExecResult::failure(127, format!("command not found: {}", cmd.name)),
+                    None => ExecResult::not_found(format!("command not found: {}", cmd.name)),
                 }
             }
-            Err(e) => ExecResult::failure(127, e.to_string()),
+            Err(e) => ExecResult::not_found(e.to_string()),
         };
 
         // Migrated builtins parse --json via the GlobalFlags flatten and
@@ -623,6 +637,7 @@ mod external_process_tests {
                 Arg::Positional(Expr::Literal(Value::String(script.to_string()))),
             ],
             redirects: vec![],
+            span: None,
         }
     }
 
@@ -837,9 +852,9 @@ mod external_process_tests {
              stays out of scope for try_external as before"
         );
         assert!(
-            result.err.contains("stdout truncated"),
+            result.stderr.contains("stdout truncated"),
             "stderr must carry the loud overflow marker (GH #191): {}",
-            result.err
+            result.stderr
         );
     }
 
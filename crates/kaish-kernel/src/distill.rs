@@ -0,0 +1,227 @@
+//! Distillation: turn a stretch of request history into a reusable script.
+//!
+//! An agent or a person working interactively tries things, fixes typos, and
+//! re-runs commands; what they end up wanting to keep is the commands that
+//! worked. [`distill`] takes [`HistoryEntry`]s (usually everything since the
+//! last checkpoint) and writes a kaish script from them:
+//!
+//! - requests that failed are dropped, or kept as comments with
+//!   [`DistillOptions::comment_failed`];
+//! - requests that only manage the session (`distill`, `kaish-checkpoint`,
+//!   `transcript`) are left out;
+//! - with [`DistillOptions::parameterize`], each distinct quoted string and
+//!   absolute path becomes a variable assigned at the top of the script, so
+//!   the values to change are in one place.
+//!
+//! The requests themselves are kept as typed — distilling never rewrites a
+//! command beyond substituting the parameterized literals.
+
+use std::collections::HashMap;
+
+use crate::checkpoint::CheckpointStore;
+use crate::history::{HistoryEntry, RequestHistory};
+use crate::lexer::{tokenize, Token};
+
+/// Commands that manage the session rather than do the work.
+const SESSION_COMMANDS: &[&str] = &["distill", "kaish-checkpoint", "transcript"];
+
+/// How [`distill`] cleans up history.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DistillOptions {
+    /// Keep failed requests as comments instead of dropping them.
+    pub comment_failed: bool,
+    /// Lift quoted strings and absolute paths into variables.
+    pub parameterize: bool,
+}
+
+/// The history a distillation covers: the entries since checkpoint `since`
+/// (an id or label), or since the newest checkpoint when `since` is `None`
+/// — the whole history if there is none. Errors if `since` names no
+/// checkpoint.
+pub fn history_since(
+    checkpoints: &CheckpointStore,
+    history: &RequestHistory,
+    since: Option<&str>,
+) -> Result<Vec<HistoryEntry>, String> {
+    let checkpoint = match since {
+        Some(which) => Some(checkpoints.find(which).ok_or_else(|| format!("no such checkpoint: {which}"))?),
+        None => checkpoints.list().pop(),
+    };
+    Ok(match checkpoint {
+        Some(checkpoint) => history.since(checkpoint.history_mark),
+        None => history.entries(),
+    })
+}
+
+/// Write a kaish script from `entries`. Unfinished entries (the request
+/// doing the distilling) are skipped.
+pub fn distill(entries: &[HistoryEntry], options: &DistillOptions) -> String {
+    let mut params = Params::default();
+    let mut body = String::new();
+    for entry in entries {
+        let Some(code) = entry.code else { continue };
+        let input = entry.input.trim();
+        if input.is_empty() || is_session_command(input) {
+            continue;
+        }
+        if code != 0 {
+            if options.comment_failed {
+                body.push_str(&format!("# failed (exit {code}):\n"));
+                for line in input.lines() {
+                    body.push_str(&format!("# {line}\n"));
+                }
+            }
+            continue;
+        }
+        let line = if options.parameterize { params.substitute(input) } else { input.to_string() };
+        body.push_str(&line);
+        body.push('\n');
+    }
+
+    let mut script = String::from("#!/usr/bin/env kaish\n\n");
+    if !params.names.is_empty() {
+        for (value, name) in &params.names {
+            script.push_str(&format!("{name}={}\n", quote(value)));
+        }
+        script.push('\n');
+    }
+    script.push_str(&body);
+    script
+}
+
+fn is_session_command(input: &str) -> bool {
+    input.split_whitespace().next().is_some_and(|word| SESSION_COMMANDS.contains(&word))
+}
+
+/// Literals lifted into variables, in order of first appearance.
+#[derive(Default)]
+struct Params {
+    names: Vec<(String, String)>,
+    by_value: HashMap<String, usize>,
+}
+
+impl Params {
+    /// `input` with each quoted string and absolute path replaced by a
+    /// reference to its variable. Input the lexer rejects is kept as is.
+    fn substitute(&mut self, input: &str) -> String {
+        let Ok(tokens) = tokenize(input) else {
+            return input.to_string();
+        };
+        let mut out = String::new();
+        let mut last = 0;
+        for spanned in tokens {
+            let raw = &input[spanned.span.clone()];
+            let value = match spanned.token {
+                // Interpolating strings depend on the session; leave them.
+                Token::String(s) if !raw.contains('$') => s,
+                Token::SingleString(s) => s,
+                Token::Path(p) if p.len() > 1 => p,
+                _ => continue,
+            };
+            if value.is_empty() {
+                continue;
+            }
+            out.push_str(&input[last..spanned.span.start]);
+            out.push_str(&format!("\"${}\"", self.name_for(value)));
+            last = spanned.span.end;
+        }
+        out.push_str(&input[last..]);
+        out
+    }
+
+    fn name_for(&mut self, value: String) -> String {
+        if let Some(&i) = self.by_value.get(&value) {
+            return self.names[i].1.clone();
+        }
+        let name = format!("ARG{}", self.names.len() + 1);
+        self.by_value.insert(value.clone(), self.names.len());
+        self.names.push((value, name.clone()));
+        name
+    }
+}
+
+/// `value` as a kaish word: single-quoted when it can be, else
+/// double-quoted with escapes.
+fn quote(value: &str) -> String {
+    if !value.contains('\'') {
+        return format!("'{value}'");
+    }
+    let mut quoted = String::from("\"");
+    for ch in value.chars() {
+        match ch {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '$' => quoted.push_str("\\$"),
+            '\n' => quoted.push_str("\\n"),
+            other => quoted.push(other),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(seq: u64, input: &str, code: Option<i64>) -> HistoryEntry {
+        HistoryEntry { seq, input: input.to_string(), code }
+    }
+
+    fn history() -> Vec<HistoryEntry> {
+        vec![
+            entry(1, "kaish-checkpoint create start", Some(0)),
+            entry(2, "mkdir /v/out", Some(0)),
+            entry(3, "grpe x data.txt", Some(127)),
+            entry(4, "grep \"needle\" /v/out/data.txt > /v/out/hits.txt\n", Some(0)),
+            entry(5, "echo \"found $n\" 'done'", Some(0)),
+            entry(6, "distill", None),
+        ]
+    }
+
+    #[test]
+    fn failed_and_session_requests_are_dropped() {
+        let script = distill(&history(), &DistillOptions::default());
+        assert_eq!(
+            script,
+            "#!/usr/bin/env kaish\n\n\
+             mkdir /v/out\n\
+             grep \"needle\" /v/out/data.txt > /v/out/hits.txt\n\
+             echo \"found $n\" 'done'\n"
+        );
+    }
+
+    #[test]
+    fn failed_requests_can_stay_as_comments() {
+        let options = DistillOptions { comment_failed: true, ..Default::default() };
+        let script = distill(&history(), &options);
+        assert!(script.contains("mkdir /v/out\n# failed (exit 127):\n# grpe x data.txt\ngrep"), "{script}");
+    }
+
+    #[test]
+    fn literals_become_variables() {
+        let entries = vec![
+            entry(1, "mkdir /v/out", Some(0)),
+            entry(2, "write /v/out/a.txt 'it is' && cat /v/out", Some(0)),
+            entry(3, "echo \"x\" \"$HOME\"", Some(0)),
+        ];
+        let options = DistillOptions { parameterize: true, ..Default::default() };
+        assert_eq!(
+            distill(&entries, &options),
+            "#!/usr/bin/env kaish\n\n\
+             ARG1='/v/out'\n\
+             ARG2='/v/out/a.txt'\n\
+             ARG3='it is'\n\
+             ARG4='x'\n\n\
+             mkdir \"$ARG1\"\n\
+             write \"$ARG2\" \"$ARG3\" && cat \"$ARG1\"\n\
+             echo \"$ARG4\" \"$HOME\"\n"
+        );
+    }
+
+    #[test]
+    fn values_with_single_quotes_are_double_quoted() {
+        assert_eq!(quote("it's $5"), "\"it's \\$5\"");
+        assert_eq!(quote("plain"), "'plain'");
+    }
+}
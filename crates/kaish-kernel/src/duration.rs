@@ -1,11 +1,11 @@
 //! Duration parsing for shell-style time strings.
 //!
 //! Used by the `timeout` builtin and `scatter --timeout` to parse durations
-//! like `30`, `30s`, `500ms`, `5m`, `1h`.
+//! like `30`, `30s`, `500ms`, `5m`, `1h`, `7d`.
 
 use std::time::Duration;
 
-/// Parse a duration string: `30` (seconds), `30s`, `500ms`, `5m`, `1h`.
+/// Parse a duration string: `30` (seconds), `30s`, `500ms`, `5m`, `1h`, `7d`.
 ///
 /// Returns `None` for invalid input (negative, unrecognized suffix, non-numeric).
 pub fn parse_duration(s: &str) -> Option<Duration> {
@@ -47,6 +47,14 @@ pub fn parse_duration(s: &str) -> Option<Duration> {
             None
         };
     }
+    if let Some(num) = s.strip_suffix('d') {
+        let days: f64 = num.trim().parse().ok()?;
+        return if days >= 0.0 {
+            Some(Duration::from_secs_f64(days * 86400.0))
+        } else {
+            None
+        };
+    }
 
     None
 }
@@ -68,6 +76,7 @@ mod tests {
         assert_eq!(parse_duration("5s"), Some(Duration::from_secs(5)));
         assert_eq!(parse_duration("2m"), Some(Duration::from_secs(120)));
         assert_eq!(parse_duration("1h"), Some(Duration::from_secs(3600)));
+        assert_eq!(parse_duration("7d"), Some(Duration::from_secs(7 * 86400)));
     }
 
     #[test]
@@ -6,7 +6,7 @@
 //! keep working. See `docs/composable-help.md`.
 
 // Compatibility surface — the `help <topic>` builtin and MCP prompts.
-pub use kaish_help::topic::{get_help, list_topics, tool_help, HelpTopic};
+pub use kaish_help::topic::{get_help, list_topics, tool_help, tool_markdown, tools_markdown, HelpTopic};
 
 // Composition surface — recipes for frontends and embedders.
 pub use kaish_help::{
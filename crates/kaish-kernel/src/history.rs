@@ -0,0 +1,125 @@
+//! Request history: the top-level requests a kernel has run, in order.
+//!
+//! The kernel records each request as it starts and fills in its exit code
+//! when it finishes, so a builtin running inside a request sees that request
+//! as the newest, unfinished entry. Checkpoints remember the history's
+//! position when they are taken ([`RequestHistory::mark`]), which is what
+//! lets `distill` turn "everything since the checkpoint" into a script (see
+//! [`crate::distill`]).
+//!
+//! Like the log buffer, the history is a bounded ring: past
+//! [`MAX_ENTRIES`] the oldest requests are dropped. Sequence numbers keep
+//! counting, so a mark stays meaningful after the entries before it go.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Requests kept before the oldest are dropped.
+pub const MAX_ENTRIES: usize = 1000;
+
+/// One top-level request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// Position in the session, counting from 1.
+    pub seq: u64,
+    /// The request as submitted.
+    pub input: String,
+    /// Exit code, or `None` while the request is still running.
+    pub code: Option<i64>,
+}
+
+impl HistoryEntry {
+    /// Whether the request finished with exit 0.
+    pub fn succeeded(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+/// Shared, bounded list of the session's requests.
+#[derive(Clone, Debug, Default)]
+pub struct RequestHistory {
+    inner: Arc<Mutex<HistoryInner>>,
+}
+
+#[derive(Debug, Default)]
+struct HistoryInner {
+    next_seq: u64,
+    entries: VecDeque<HistoryEntry>,
+}
+
+impl RequestHistory {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HistoryInner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Record a request that is starting. Returns its sequence number, to
+    /// pass to [`Self::finish`].
+    pub fn begin(&self, input: &str) -> u64 {
+        let mut inner = self.lock();
+        inner.next_seq += 1;
+        let seq = inner.next_seq;
+        if inner.entries.len() == MAX_ENTRIES {
+            inner.entries.pop_front();
+        }
+        inner.entries.push_back(HistoryEntry { seq, input: input.to_string(), code: None });
+        seq
+    }
+
+    /// Record the exit code of request `seq`.
+    pub fn finish(&self, seq: u64, code: i64) {
+        if let Some(entry) = self.lock().entries.iter_mut().rev().find(|e| e.seq == seq) {
+            entry.code = Some(code);
+        }
+    }
+
+    /// The sequence number the next request will get. Entries from here on
+    /// are "since" this point.
+    pub fn mark(&self) -> u64 {
+        self.lock().next_seq + 1
+    }
+
+    /// Entries with a sequence number of at least `mark`, oldest first.
+    pub fn since(&self, mark: u64) -> Vec<HistoryEntry> {
+        self.lock().entries.iter().filter(|e| e.seq >= mark).cloned().collect()
+    }
+
+    /// Every entry still kept, oldest first.
+    pub fn entries(&self) -> Vec<HistoryEntry> {
+        self.lock().entries.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_finish_and_filter_by_mark() {
+        let history = RequestHistory::default();
+        let first = history.begin("echo a");
+        history.finish(first, 0);
+        let mark = history.mark();
+        let second = history.begin("false");
+        history.finish(second, 1);
+        history.begin("distill");
+
+        let since = history.since(mark);
+        assert_eq!(since.len(), 2);
+        assert_eq!(since[0].input, "false");
+        assert_eq!(since[0].code, Some(1));
+        assert_eq!(since[1].code, None, "the running request is unfinished");
+        assert!(history.entries()[0].succeeded());
+    }
+
+    #[test]
+    fn oldest_entries_drop_past_the_cap() {
+        let history = RequestHistory::default();
+        for i in 0..MAX_ENTRIES + 5 {
+            history.begin(&format!("echo {i}"));
+        }
+        let entries = history.entries();
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries[0].seq, 6);
+        assert_eq!(history.mark(), MAX_ENTRIES as u64 + 6);
+    }
+}
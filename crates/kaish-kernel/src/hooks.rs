@@ -0,0 +1,220 @@
+//! Event hooks: script handlers registered with `on EVENT { ... }`, and Rust
+//! callbacks an embedder registers on the kernel.
+//!
+//! A script handler runs like a function call, with the event's details as
+//! positional arguments:
+//!
+//! - `command_error` — a command or pipeline statement failed. `$1` is the
+//!   command (`a | b` for a pipeline), `$2` the exit code, `$3` the error
+//!   text or `exit code N`.
+//! - `cwd_change` — a builtin (`cd`, `kaish-state import`, …) moved the
+//!   working directory. `$1` is the old directory, `$2` the new one.
+//!
+//! A `command_error` is raised where `set -e` would stop: not for the left
+//! side of `&&`/`||` or an `if`/`while` condition, which handle the failure
+//! themselves. Its handler sees the failing status in `$?`, and runs before
+//! `set -e` or a `try` block acts on the failure.
+//!
+//! Registering a handler for an event replaces the previous one, and an
+//! empty block (`on cwd_change {}`) removes it. Events raised while a handler
+//! runs don't run handlers, so a handler that fails or `cd`s can't loop. What
+//! a handler prints joins the output of the statement that raised the event;
+//! its exit status is ignored.
+//!
+//! [`KernelHooks`] holds the embedder side, set with
+//! [`KernelConfig::with_hooks`](crate::KernelConfig::with_hooks). `pre_exec`
+//! and `post_exec` bracket every command the kernel dispatches — the same
+//! set the `command` tracing span and [`crate::metrics`] cover — and
+//! `var_change` sees every assignment statement and
+//! [`Kernel::set_var`](crate::Kernel::set_var). `progress` carries what a
+//! long-running tool reports through
+//! [`ToolCtx::report_progress`](crate::tools::ToolCtx::report_progress) —
+//! the REPL draws it as a status line. Callbacks run synchronously
+//! on the executing task, so they should hand slow work off; forks (pipeline
+//! stages, background jobs, scatter workers) share them.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub use kaish_tool_api::Progress;
+
+use crate::ast::Value;
+use crate::interpreter::ExecResult;
+
+/// An event a script can handle with `on EVENT { ... }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HookEvent {
+    /// A command or pipeline statement failed.
+    CommandError,
+    /// The working directory changed.
+    CwdChange,
+}
+
+impl HookEvent {
+    /// Every event, in the order they are documented.
+    pub const ALL: [HookEvent; 2] = [HookEvent::CommandError, HookEvent::CwdChange];
+
+    /// The event named `name` in `on NAME { ... }`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|event| event.as_str() == name)
+    }
+
+    /// The name scripts use for the event.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HookEvent::CommandError => "command_error",
+            HookEvent::CwdChange => "cwd_change",
+        }
+    }
+
+    /// The event names, comma-separated, for error messages.
+    pub fn names() -> String {
+        Self::ALL.map(HookEvent::as_str).join(", ")
+    }
+}
+
+impl fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+type PreExec = dyn Fn(&str) + Send + Sync;
+type PostExec = dyn Fn(&str, &ExecResult, Duration) + Send + Sync;
+type VarChange = dyn Fn(&str, &Value) + Send + Sync;
+type OnProgress = dyn Fn(&str, &Progress) + Send + Sync;
+
+/// Rust callbacks on kernel execution.
+///
+/// ```ignore
+/// let hooks = KernelHooks::new()
+///     .on_post_exec(|command, result, elapsed| {
+///         if !result.ok() {
+///             tracing::warn!(%command, code = result.code, ?elapsed, "command failed");
+///         }
+///     })
+///     .on_var_change(|name, _| tracing::debug!(%name, "assigned"));
+/// let kernel = Kernel::new(KernelConfig::agent().with_hooks(hooks))?;
+/// ```
+#[derive(Clone, Default)]
+pub struct KernelHooks {
+    pre_exec: Vec<Arc<PreExec>>,
+    post_exec: Vec<Arc<PostExec>>,
+    var_change: Vec<Arc<VarChange>>,
+    progress: Vec<Arc<OnProgress>>,
+}
+
+impl KernelHooks {
+    /// No callbacks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call `hook` with a command's name as it is dispatched.
+    pub fn on_pre_exec(mut self, hook: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.pre_exec.push(Arc::new(hook));
+        self
+    }
+
+    /// Call `hook` with a command's name, result, and wall-clock time once it
+    /// finishes. A command that raised a kernel error is reported as a
+    /// failure carrying the error's message.
+    pub fn on_post_exec(
+        mut self,
+        hook: impl Fn(&str, &ExecResult, Duration) + Send + Sync + 'static,
+    ) -> Self {
+        self.post_exec.push(Arc::new(hook));
+        self
+    }
+
+    /// Call `hook` with a variable's name and new value when it is assigned.
+    /// A subscripted assignment (`user[email]=x`) reports the whole root
+    /// (`user`).
+    pub fn on_var_change(mut self, hook: impl Fn(&str, &Value) + Send + Sync + 'static) -> Self {
+        self.var_change.push(Arc::new(hook));
+        self
+    }
+
+    /// Call `hook` with a command's name and each progress update it
+    /// reports. Updates arrive on the tool's task, mid-command, so the hook
+    /// should be cheap.
+    pub fn on_progress(mut self, hook: impl Fn(&str, &Progress) + Send + Sync + 'static) -> Self {
+        self.progress.push(Arc::new(hook));
+        self
+    }
+
+    /// Whether any command callbacks are registered.
+    pub(crate) fn watches_commands(&self) -> bool {
+        !self.pre_exec.is_empty() || !self.post_exec.is_empty()
+    }
+
+    /// Whether any variable callbacks are registered.
+    pub(crate) fn watches_vars(&self) -> bool {
+        !self.var_change.is_empty()
+    }
+
+    pub(crate) fn pre_exec(&self, command: &str) {
+        for hook in &self.pre_exec {
+            hook(command);
+        }
+    }
+
+    pub(crate) fn post_exec(&self, command: &str, result: &ExecResult, elapsed: Duration) {
+        for hook in &self.post_exec {
+            hook(command, result, elapsed);
+        }
+    }
+
+    pub(crate) fn var_change(&self, name: &str, value: &Value) {
+        for hook in &self.var_change {
+            hook(name, value);
+        }
+    }
+
+    pub(crate) fn progress(&self, command: &str, progress: &Progress) {
+        for hook in &self.progress {
+            hook(command, progress);
+        }
+    }
+}
+
+impl fmt::Debug for KernelHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KernelHooks")
+            .field("pre_exec", &self.pre_exec.len())
+            .field("post_exec", &self.post_exec.len())
+            .field("var_change", &self.var_change.len())
+            .field("progress", &self.progress.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_names_round_trip() {
+        for event in HookEvent::ALL {
+            assert_eq!(HookEvent::from_name(event.as_str()), Some(event));
+        }
+        assert_eq!(HookEvent::from_name("command-error"), None);
+        assert_eq!(HookEvent::names(), "command_error, cwd_change");
+    }
+
+    #[test]
+    fn callbacks_run_in_registration_order() {
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (a, b) = (seen.clone(), seen.clone());
+        let hooks = KernelHooks::new()
+            .on_pre_exec(move |name| a.lock().unwrap().push(format!("a {name}")))
+            .on_pre_exec(move |name| b.lock().unwrap().push(format!("b {name}")));
+        assert!(hooks.watches_commands());
+        assert!(!hooks.watches_vars());
+
+        hooks.clone().pre_exec("ls");
+        assert_eq!(*seen.lock().unwrap(), ["a ls", "b ls"]);
+        assert_eq!(format!("{hooks:?}"), "KernelHooks { pre_exec: 2, post_exec: 0, var_change: 0, progress: 0 }");
+    }
+}
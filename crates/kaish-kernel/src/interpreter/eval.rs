@@ -15,7 +15,7 @@ use kaish_types::json_to_value_no_envelope;
 
 use crate::arithmetic;
 use crate::ast::{
-    spread_non_list_message, BinaryOp, Expr, ListElem, RecordEntry, RecordKey,
+    spread_non_list_message, BinaryOp, DefaultOp, Expr, ListElem, RecordEntry, RecordKey,
     StringPart, StringTestOp, TestCmpOp, TestExpr, Value, VarPath,
 };
 
@@ -130,6 +130,8 @@ pub enum EvalError {
     /// collection form (`${#…}`, `${…:-default}`) was used on a subscripted path
     /// before that path support landed. Carries a full teaching message.
     Unsupported(String),
+    /// `${path:?word}` on an absent or empty path. Carries the full message.
+    Unset(String),
 }
 
 impl fmt::Display for EvalError {
@@ -148,6 +150,7 @@ impl fmt::Display for EvalError {
             EvalError::ArithmeticError(msg) => write!(f, "arithmetic error: {msg}"),
             EvalError::RegexError(msg) => write!(f, "regex error: {msg}"),
             EvalError::Unsupported(msg) => write!(f, "{msg}"),
+            EvalError::Unset(msg) => write!(f, "{msg}"),
         }
     }
 }
@@ -210,7 +213,7 @@ impl<'a> Evaluator<'a> {
             Expr::AllArgs => self.eval_all_args(),
             Expr::ArgCount => self.eval_arg_count(),
             Expr::VarLength(path) => self.eval_var_length(path),
-            Expr::VarWithDefault { path, default } => self.eval_var_with_default(path, default),
+            Expr::VarWithDefault { path, op, default } => self.eval_var_with_default(path, *op, default),
             Expr::Arithmetic(expr_str) => self.eval_arithmetic(expr_str),
             Expr::Command(cmd) => self.eval_command(cmd),
             Expr::LastExitCode => self.eval_last_exit_code(),
@@ -218,6 +221,13 @@ impl<'a> Evaluator<'a> {
             Expr::GlobPattern(s) => Ok(Value::String(s.clone())),
             Expr::ListLiteral(elems) => self.eval_list_literal(elems),
             Expr::RecordLiteral(entries) => self.eval_record_literal(entries),
+            Expr::Conditional { condition, then_value, else_value } => {
+                if is_truthy(&self.eval(condition)?) {
+                    self.eval(then_value)
+                } else {
+                    self.eval(else_value)
+                }
+            }
         }
     }
 
@@ -490,13 +500,29 @@ impl<'a> Evaluator<'a> {
             .map_err(EvalError::InvalidPath)
     }
 
-    /// Evaluate a variable/path with a default (`${VAR:-default}`).
-    /// Yields the value if present and non-empty; on absence or emptiness
-    /// evaluates the default parts; a shape error stays loud (decision A).
-    fn eval_var_with_default(&mut self, path: &VarPath, default: &[StringPart]) -> EvalResult<Value> {
-        match resolve_default(self.scope, path).map_err(EvalError::InvalidPath)? {
-            Some(value) => Ok(value),
-            None => self.eval_interpolated(default),
+    /// Evaluate a variable/path with a default (`${VAR:-default}`, or the
+    /// `:=`/`:?`/`:+` forms). Yields the value if present and non-empty; on
+    /// absence or emptiness evaluates the default parts; a shape error stays
+    /// loud (decision A).
+    fn eval_var_with_default(
+        &mut self,
+        path: &VarPath,
+        op: DefaultOp,
+        default: &[StringPart],
+    ) -> EvalResult<Value> {
+        match resolve_default_op(self.scope, path, op).map_err(EvalError::InvalidPath)? {
+            DefaultAction::Value(value) => Ok(value),
+            DefaultAction::Word => self.eval_interpolated(default),
+            DefaultAction::Assign => {
+                let word = self.eval_interpolated(default)?;
+                assign_default(self.scope, path, word.clone()).map_err(EvalError::InvalidPath)?;
+                Ok(word)
+            }
+            DefaultAction::Fail => {
+                let word = value_to_string(&self.eval_interpolated(default)?);
+                Err(EvalError::Unset(unset_error_message(path, &word)))
+            }
+            DefaultAction::Empty => Ok(Value::String(String::new())),
         }
     }
 
@@ -520,8 +546,8 @@ impl<'a> Evaluator<'a> {
                         }
                     }
                 }
-                StringPart::VarWithDefault { path, default } => {
-                    let value = self.eval_var_with_default(path, default)?;
+                StringPart::VarWithDefault { path, op, default } => {
+                    let value = self.eval_var_with_default(path, *op, default)?;
                     result.push_str(&value_to_text_sink(&value)?);
                 }
                 StringPart::VarLength(path) => {
@@ -681,6 +707,69 @@ pub fn resolve_default(scope: &Scope, path: &VarPath) -> Result<Option<Value>, S
     }
 }
 
+/// What a `${path<op>word}` expansion does, decided over [`resolve_default`]
+/// before the word is evaluated — the word only runs when the operator needs
+/// it, so `${X:+$(cmd)}` never runs `cmd` for an unset `X`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DefaultAction {
+    /// Use the resolved value as-is.
+    Value(Value),
+    /// Evaluate the word and use it.
+    Word,
+    /// Evaluate the word, assign it to the path (see [`assign_default`]), and
+    /// use it.
+    Assign,
+    /// Evaluate the word and fail with it (see [`unset_error_message`]).
+    Fail,
+    /// Expand to the empty string.
+    Empty,
+}
+
+/// The shared `${path<op>word}` decision, so every evaluator (sync, async,
+/// and the reduced scatter/gather one) agrees on all four operators. A shape
+/// error stays loud whatever the operator, as for `:-`.
+pub fn resolve_default_op(scope: &Scope, path: &VarPath, op: DefaultOp) -> Result<DefaultAction, String> {
+    let present = resolve_default(scope, path)?;
+    Ok(match (op, present) {
+        (DefaultOp::UseAlternate, Some(_)) => DefaultAction::Word,
+        (DefaultOp::UseAlternate, None) => DefaultAction::Empty,
+        (_, Some(value)) => DefaultAction::Value(value),
+        (DefaultOp::UseDefault, None) => DefaultAction::Word,
+        (DefaultOp::AssignDefault, None) => DefaultAction::Assign,
+        (DefaultOp::ErrorIfUnset, None) => DefaultAction::Fail,
+    })
+}
+
+/// `${path:=word}`'s write: a bare name assigns with plain `NAME=value`
+/// semantics (`set_global`), a subscripted path writes through like the
+/// `xs[0]=v` lvalue — so its root must already exist.
+pub fn assign_default(scope: &mut Scope, path: &VarPath, value: Value) -> Result<(), String> {
+    use crate::ast::VarSegment;
+    match path.segments.as_slice() {
+        [VarSegment::Field(name)] => {
+            scope.set_global(name.clone(), value);
+            Ok(())
+        }
+        _ => scope.walk_write(path, value).map_err(|e| match e {
+            super::scope::PathError::UndefinedRoot(name) => format!(
+                "{}: cannot assign a default — {name} is undefined",
+                format_path(path)
+            ),
+            super::scope::PathError::Absence(msg) | super::scope::PathError::Shape(msg) => msg,
+        }),
+    }
+}
+
+/// The error `${path:?word}` raises for an absent or empty path: the word
+/// when given, else bash's "parameter null or not set".
+pub fn unset_error_message(path: &VarPath, word: &str) -> String {
+    if word.is_empty() {
+        format!("{}: parameter null or not set", format_path(path))
+    } else {
+        format!("{}: {word}", format_path(path))
+    }
+}
+
 /// Reject exporting a structured value into an OS env var. A list/record can't
 /// cross the process boundary, and kaish will not silently JSON-serialize it into
 /// the child's environment. Returns a loud "serialize first" message naming the
@@ -1533,6 +1622,7 @@ mod tests {
             name: "echo".into(),
             args: vec![],
             redirects: vec![],
+            span: None,
         })]);
 
         assert!(matches!(
@@ -1884,6 +1974,7 @@ mod tests {
         // Variable is set, return its value
         let expr = Expr::VarWithDefault {
             path: VarPath::simple("NAME"),
+            op: DefaultOp::UseDefault,
             default: vec![StringPart::Literal("default".into())],
         };
         let result = eval_expr(&expr, &mut scope).unwrap();
@@ -1897,6 +1988,7 @@ mod tests {
         // Variable is unset, return default
         let expr = Expr::VarWithDefault {
             path: VarPath::simple("MISSING"),
+            op: DefaultOp::UseDefault,
             default: vec![StringPart::Literal("fallback".into())],
         };
         let result = eval_expr(&expr, &mut scope).unwrap();
@@ -1911,6 +2003,7 @@ mod tests {
         // Variable is set but empty, return default
         let expr = Expr::VarWithDefault {
             path: VarPath::simple("EMPTY"),
+            op: DefaultOp::UseDefault,
             default: vec![StringPart::Literal("not empty".into())],
         };
         let result = eval_expr(&expr, &mut scope).unwrap();
@@ -1925,12 +2018,36 @@ mod tests {
         // Variable is set to a non-string value, return the value
         let expr = Expr::VarWithDefault {
             path: VarPath::simple("NUM"),
+            op: DefaultOp::UseDefault,
             default: vec![StringPart::Literal("default".into())],
         };
         let result = eval_expr(&expr, &mut scope).unwrap();
         assert_eq!(result, Value::Int(42));
     }
 
+    #[test]
+    fn eval_var_default_operators() {
+        let mut scope = Scope::new();
+        scope.set("SET", Value::String("v".into()));
+        let word = || vec![StringPart::Literal("w".into())];
+        let expand = |scope: &mut Scope, name: &str, op| {
+            eval_expr(&Expr::VarWithDefault { path: VarPath::simple(name), op, default: word() }, scope)
+        };
+
+        // `:+` swaps in the word only when the value is present.
+        assert_eq!(expand(&mut scope, "SET", DefaultOp::UseAlternate).unwrap(), Value::String("w".into()));
+        assert_eq!(expand(&mut scope, "NOPE", DefaultOp::UseAlternate).unwrap(), Value::String("".into()));
+
+        // `:?` fails with the word as the message.
+        let err = expand(&mut scope, "NOPE", DefaultOp::ErrorIfUnset).unwrap_err();
+        assert_eq!(err.to_string(), "${NOPE}: w");
+        assert_eq!(expand(&mut scope, "SET", DefaultOp::ErrorIfUnset).unwrap(), Value::String("v".into()));
+
+        // `:=` yields the word and assigns it.
+        assert_eq!(expand(&mut scope, "NEW", DefaultOp::AssignDefault).unwrap(), Value::String("w".into()));
+        assert_eq!(scope.get("NEW"), Some(&Value::String("w".into())));
+    }
+
     #[test]
     fn eval_unset_variable_is_empty() {
         let mut scope = Scope::new();
@@ -2106,6 +2223,7 @@ mod tests {
         let val = eval_expr(
             &Expr::VarWithDefault {
                 path: crate::parser::parse_varpath("${cfg[port]}"),
+                op: DefaultOp::UseDefault,
                 default: vec![StringPart::Literal("8080".into())],
             },
             &mut scope,
@@ -2117,6 +2235,7 @@ mod tests {
         let missing = eval_expr(
             &Expr::VarWithDefault {
                 path: crate::parser::parse_varpath("${cfg[nope]}"),
+                op: DefaultOp::UseDefault,
                 default: vec![StringPart::Literal("8080".into())],
             },
             &mut scope,
@@ -2128,6 +2247,7 @@ mod tests {
         let err = eval_expr(
             &Expr::VarWithDefault {
                 path: crate::parser::parse_varpath("${cfg[0]}"),
+                op: DefaultOp::UseDefault,
                 default: vec![StringPart::Literal("x".into())],
             },
             &mut scope,
@@ -0,0 +1,130 @@
+//! Declared function parameters: binding a call's positional arguments to
+//! `name(path count:int=1 rest:string...)` and coercing each to its type.
+//!
+//! A typed parameter accepts any value that converts losslessly — `"42"` for
+//! an `int`, `3` for a `float` or a `string` — and rejects the rest with a
+//! message naming the function, the parameter, and the offending value. A
+//! variadic parameter collects the remaining arguments into a list, checking
+//! each element against its type.
+
+use crate::ast::{ParamDef, ParamType, Value};
+
+use super::{value_to_json, value_to_string};
+
+/// Coerce `value` to `ty`, or describe why it doesn't fit.
+pub(crate) fn coerce_param(value: Value, ty: &ParamType) -> Result<Value, String> {
+    let coerced = match (ty, &value) {
+        (ParamType::String, Value::String(_)) => Some(value.clone()),
+        (ParamType::String, Value::Int(_) | Value::Float(_) | Value::Bool(_)) => {
+            Some(Value::String(value_to_string(&value)))
+        }
+        (ParamType::Int, Value::Int(_)) => Some(value.clone()),
+        (ParamType::Int, Value::String(s)) => s.trim().parse().ok().map(Value::Int),
+        (ParamType::Float, Value::Float(_)) => Some(value.clone()),
+        (ParamType::Float, Value::Int(i)) => Some(Value::Float(*i as f64)),
+        (ParamType::Float, Value::String(s)) => s.trim().parse().ok().map(Value::Float),
+        (ParamType::Bool, Value::Bool(_)) => Some(value.clone()),
+        (ParamType::Bool, Value::String(s)) => match s.as_str() {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        (ParamType::Array, Value::Json(serde_json::Value::Array(_))) => Some(value.clone()),
+        (ParamType::Array, Value::String(s)) => match serde_json::from_str(s) {
+            Ok(json @ serde_json::Value::Array(_)) => Some(Value::Json(json)),
+            _ => None,
+        },
+        _ => None,
+    };
+    coerced.ok_or_else(|| format!("expects {}, got {}", ty.name(), describe(&value)))
+}
+
+/// Bind one argument (or evaluated default) to a scalar parameter.
+pub(crate) fn bind_param(func: &str, param: &ParamDef, value: Value) -> Result<Value, String> {
+    match &param.param_type {
+        Some(ty) => coerce_param(value, ty)
+            .map_err(|e| format!("{func}: argument '{}' {e}", param.name)),
+        None => Ok(value),
+    }
+}
+
+/// Bind the remaining arguments to a variadic parameter as one list.
+pub(crate) fn bind_variadic(func: &str, param: &ParamDef, rest: &[Value]) -> Result<Value, String> {
+    let mut items = Vec::with_capacity(rest.len());
+    for (i, value) in rest.iter().enumerate() {
+        let value = match &param.param_type {
+            Some(ParamType::Array) | None => value.clone(),
+            Some(ty) => coerce_param(value.clone(), ty)
+                .map_err(|e| format!("{func}: argument '{}[{i}]' {e}", param.name))?,
+        };
+        items.push(value_to_json(&value));
+    }
+    Ok(Value::Json(serde_json::Value::Array(items)))
+}
+
+/// The message for a call that leaves a required parameter unfilled.
+pub(crate) fn missing_param(func: &str, param: &ParamDef) -> String {
+    match &param.param_type {
+        Some(ty) => format!("{func}: missing argument '{}' ({})", param.name, ty.name()),
+        None => format!("{func}: missing argument '{}'", param.name),
+    }
+}
+
+/// How a rejected value reads in an error message.
+fn describe(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("'{s}'"),
+        Value::Json(serde_json::Value::Array(_)) => "a list".to_string(),
+        Value::Json(serde_json::Value::Object(_)) => "a record".to_string(),
+        Value::Bytes(_) => "binary data".to_string(),
+        other => value_to_string(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn param(ty: Option<ParamType>, variadic: bool) -> ParamDef {
+        ParamDef { name: "n".to_string(), param_type: ty, default: None, variadic }
+    }
+
+    #[test]
+    fn scalars_coerce_losslessly() {
+        let str_ = |s: &str| Value::String(s.to_string());
+        assert_eq!(coerce_param(str_("42"), &ParamType::Int), Ok(Value::Int(42)));
+        assert_eq!(coerce_param(Value::Int(3), &ParamType::Float), Ok(Value::Float(3.0)));
+        assert_eq!(coerce_param(Value::Int(3), &ParamType::String), Ok(str_("3")));
+        assert_eq!(coerce_param(str_("true"), &ParamType::Bool), Ok(Value::Bool(true)));
+        assert_eq!(
+            coerce_param(str_("[1,2]"), &ParamType::Array),
+            Ok(Value::Json(serde_json::json!([1, 2])))
+        );
+    }
+
+    #[test]
+    fn mismatches_name_the_parameter_and_value() {
+        let int = param(Some(ParamType::Int), false);
+        let err = bind_param("f", &int, Value::String("abc".to_string())).expect_err("not an int");
+        assert_eq!(err, "f: argument 'n' expects int, got 'abc'");
+
+        let err = coerce_param(Value::String("yes".to_string()), &ParamType::Bool).expect_err("strict");
+        assert_eq!(err, "expects bool, got 'yes'");
+        let list = Value::Json(serde_json::json!([1]));
+        assert_eq!(coerce_param(list, &ParamType::String), Err("expects string, got a list".to_string()));
+    }
+
+    #[test]
+    fn variadic_checks_each_element() {
+        let ints = param(Some(ParamType::Int), true);
+        let rest = [Value::Int(1), Value::String("2".to_string())];
+        assert_eq!(bind_variadic("f", &ints, &rest), Ok(Value::Json(serde_json::json!([1, 2]))));
+
+        let rest = [Value::Int(1), Value::String("x".to_string())];
+        let err = bind_variadic("f", &ints, &rest).expect_err("x is not an int");
+        assert_eq!(err, "f: argument 'n[1]' expects int, got 'x'");
+
+        let any = param(None, true);
+        assert_eq!(bind_variadic("f", &any, &[]), Ok(Value::Json(serde_json::json!([]))));
+    }
+}
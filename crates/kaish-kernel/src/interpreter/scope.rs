@@ -372,6 +372,74 @@ fn push_path_error_message(err: PathError, root_name: &str) -> String {
     }
 }
 
+/// Shards a frame's variables are spread over. A write to a frame that a
+/// clone still shares copies one shard — about `1/FRAME_SHARDS` of the
+/// frame — instead of all of it.
+const FRAME_SHARDS: usize = 32;
+
+/// One frame of variables: a map that clones in O(1) and copies on write,
+/// a shard at a time.
+///
+/// Every command snapshots the scope into its `ExecContext` and syncs it
+/// back, so the kernel's scope is shared with a snapshot for most of its
+/// life. With a single `HashMap` per frame, the first assignment after each
+/// snapshot copied every variable in the frame — O(variables) per
+/// statement, which a loop that builds thousands of variables paid on every
+/// iteration. Here that assignment copies the shard table and the one shard
+/// its name hashes to. Shards are allocated on the first insert, so the
+/// empty frames loops and function calls push cost nothing.
+#[derive(Debug, Clone, Default)]
+struct Frame {
+    shards: Arc<Vec<Arc<HashMap<String, Value>>>>,
+}
+
+impl Frame {
+    fn shard_index(name: &str) -> usize {
+        use std::hash::{Hash, Hasher};
+        // Fixed keys, so a name lands in the same shard in every frame.
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        name.hash(&mut hasher);
+        (hasher.finish() % FRAME_SHARDS as u64) as usize
+    }
+
+    fn get(&self, name: &str) -> Option<&Value> {
+        self.shards.get(Self::shard_index(name))?.get(name)
+    }
+
+    fn contains_key(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    fn insert(&mut self, name: String, value: Value) {
+        let shards = Arc::make_mut(&mut self.shards);
+        if shards.is_empty() {
+            shards.resize_with(FRAME_SHARDS, Default::default);
+        }
+        Arc::make_mut(&mut shards[Self::shard_index(&name)]).insert(name, value);
+    }
+
+    fn remove(&mut self, name: &str) -> Option<Value> {
+        let index = Self::shard_index(name);
+        // Only copy a shared shard that actually holds the name.
+        if !self.shards.get(index).is_some_and(|shard| shard.contains_key(name)) {
+            return None;
+        }
+        Arc::make_mut(&mut Arc::make_mut(&mut self.shards)[index]).remove(name)
+    }
+
+    fn clear(&mut self) {
+        self.shards = Arc::default();
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.shards.iter().flat_map(|shard| shard.iter())
+    }
+
+    fn keys(&self) -> impl Iterator<Item = &String> {
+        self.iter().map(|(name, _)| name)
+    }
+}
+
 /// Variable scope with nested frames and last-result tracking.
 ///
 /// Variables are looked up from innermost to outermost frame.
@@ -381,14 +449,22 @@ fn push_path_error_message(err: PathError, root_name: &str) -> String {
 /// Cloning a Scope is O(1) — just bumps the Arc refcount. Mutations use
 /// `Arc::make_mut` to clone the inner data only when shared. This matters
 /// because `execute_pipeline` snapshots the scope into ExecContext (clone)
-/// and syncs it back (clone) on every command.
+/// and syncs it back (clone) on every command. Each [`Frame`] is itself
+/// copy-on-write per shard, so the copy a shared write makes is small.
 #[derive(Debug, Clone)]
 pub struct Scope {
     /// Stack of variable frames. Last element is the innermost scope.
     /// Wrapped in Arc for copy-on-write: clone is O(1), mutation clones on demand.
-    frames: Arc<Vec<HashMap<String, Value>>>,
-    /// Variables marked for export to child processes.
-    exported: HashSet<String>,
+    frames: Arc<Vec<Frame>>,
+    /// Indices into `frames` of the frames pushed for function calls,
+    /// innermost last. `local` binds in the innermost of these (the root
+    /// frame outside any function), so a `local` inside a loop or `if` in a
+    /// function body lives as long as the function, not the block.
+    function_frames: Vec<usize>,
+    /// Variables marked for export to child processes. Shared like
+    /// `frames`: a session seeded from the host environment exports
+    /// dozens of names, and every snapshot would otherwise copy them.
+    exported: Arc<HashSet<String>>,
     /// The result of the last command execution.
     ///
     /// Boxed: `Scope` is cloned/held by value at every recursion level (the
@@ -404,8 +480,12 @@ pub struct Scope {
     /// Error exit mode (set -e): exit on any command failure.
     error_exit: bool,
     /// Counter for temporarily suppressing errexit (e.g. inside && / || left side).
-    /// When > 0, error_exit_enabled() returns false even if error_exit is true.
+    /// When > 0, error_exit_enabled() and stops_on_failure() return false even
+    /// if error_exit is true.
     errexit_suppressed: usize,
+    /// Number of `try` blocks being executed. Inside one, a failing command
+    /// stops the block the way `set -e` stops a script.
+    try_depth: usize,
     /// AST display mode (kaish-ast -on/-off): show AST instead of executing.
     show_ast: bool,
     /// Latch mode (set -o latch): gate dangerous operations behind nonce confirmation.
@@ -432,13 +512,15 @@ impl Scope {
     /// during construction to assign the real session identifier.
     pub fn new() -> Self {
         Self {
-            frames: Arc::new(vec![HashMap::new()]),
-            exported: HashSet::new(),
+            frames: Arc::new(vec![Frame::default()]),
+            function_frames: Vec::new(),
+            exported: Arc::default(),
             last_result: Box::new(ExecResult::default()),
             script_name: String::new(),
             positional: Vec::new(),
             error_exit: false,
             errexit_suppressed: 0,
+            try_depth: 0,
             show_ast: false,
             latch_enabled: false,
             trash_enabled: false,
@@ -462,7 +544,14 @@ impl Scope {
 
     /// Push a new scope frame (for entering a loop, tool call, etc.)
     pub fn push_frame(&mut self) {
-        Arc::make_mut(&mut self.frames).push(HashMap::new());
+        Arc::make_mut(&mut self.frames).push(Frame::default());
+    }
+
+    /// Push a new scope frame for a function call: the frame `local`
+    /// declarations in the function body bind in.
+    pub fn push_function_frame(&mut self) {
+        self.function_frames.push(self.frames.len());
+        self.push_frame();
     }
 
     /// Pop the innermost scope frame.
@@ -471,6 +560,9 @@ impl Scope {
     pub fn pop_frame(&mut self) {
         if self.frames.len() > 1 {
             Arc::make_mut(&mut self.frames).pop();
+            if self.function_frames.last() == Some(&self.frames.len()) {
+                self.function_frames.pop();
+            }
         } else {
             panic!("cannot pop the root scope frame");
         }
@@ -478,13 +570,28 @@ impl Scope {
 
     /// Set a variable in the current (innermost) frame.
     ///
-    /// Use this for `local` variable declarations.
+    /// Used for loop variables and function parameters; a `local`
+    /// declaration goes through [`Scope::set_local`].
     pub fn set(&mut self, name: impl Into<String>, value: Value) {
         if let Some(frame) = Arc::make_mut(&mut self.frames).last_mut() {
             frame.insert(name.into(), value);
         }
     }
 
+    /// Set a variable for a `local` declaration: in the innermost function
+    /// frame, or the root frame outside any function.
+    pub fn set_local(&mut self, name: impl Into<String>, value: Value) {
+        let index = self.function_frames.last().copied().unwrap_or(0);
+        if let Some(frame) = Arc::make_mut(&mut self.frames).get_mut(index) {
+            frame.insert(name.into(), value);
+        }
+    }
+
+    /// Whether a function call is executing (a function frame is pushed).
+    pub fn in_function(&self) -> bool {
+        !self.function_frames.is_empty()
+    }
+
     /// Set a variable with global semantics (shell default).
     ///
     /// If the variable exists in any frame, update it there.
@@ -496,8 +603,8 @@ impl Scope {
         // Search from innermost to outermost to find existing variable
         let frames = Arc::make_mut(&mut self.frames);
         for frame in frames.iter_mut().rev() {
-            if let std::collections::hash_map::Entry::Occupied(mut e) = frame.entry(name.clone()) {
-                e.insert(value);
+            if frame.contains_key(&name) {
+                frame.insert(name, value);
                 return;
             }
         }
@@ -522,12 +629,8 @@ impl Scope {
     ///
     /// Returns the removed value if found, None otherwise.
     pub fn remove(&mut self, name: &str) -> Option<Value> {
-        for frame in Arc::make_mut(&mut self.frames).iter_mut().rev() {
-            if let Some(value) = frame.remove(name) {
-                return Some(value);
-            }
-        }
-        None
+        let index = self.frames.iter().rposition(|frame| frame.contains_key(name))?;
+        Arc::make_mut(&mut self.frames)[index].remove(name)
     }
 
     /// Set the last command result (accessible via `$?`).
@@ -604,6 +707,38 @@ impl Scope {
         self.errexit_suppressed = self.errexit_suppressed.saturating_sub(1);
     }
 
+    /// Whether a failing statement should stop execution here: under
+    /// `set -e`, or anywhere inside a `try` block — unless it is the left
+    /// side of `&&`/`||`, which handles the failure itself.
+    pub fn stops_on_failure(&self) -> bool {
+        (self.error_exit || self.try_depth > 0) && self.errexit_suppressed == 0
+    }
+
+    /// Whether a failing statement here is left unhandled — not the left
+    /// side of `&&`/`||`. Decides whether `command_error` is raised.
+    pub fn failure_unhandled(&self) -> bool {
+        self.errexit_suppressed == 0
+    }
+
+    /// Enter a `try` block, returning the errexit suppression to restore on
+    /// [`Scope::exit_try`]. The block starts unsuppressed, so a `try` on the
+    /// left of `&&` still catches the failures inside it.
+    pub fn enter_try(&mut self) -> usize {
+        self.try_depth += 1;
+        std::mem::take(&mut self.errexit_suppressed)
+    }
+
+    /// Leave a `try` block entered with [`Scope::enter_try`].
+    pub fn exit_try(&mut self, suppressed: usize) {
+        self.try_depth = self.try_depth.saturating_sub(1);
+        self.errexit_suppressed = suppressed;
+    }
+
+    /// Whether a `try` block is being executed.
+    pub fn in_try(&self) -> bool {
+        self.try_depth > 0
+    }
+
     /// Check if AST display mode is enabled (kaish-ast -on).
     pub fn show_ast(&self) -> bool {
         self.show_ast
@@ -658,7 +793,7 @@ impl Scope {
     ///
     /// The variable doesn't need to exist yet; it will be exported when set.
     pub fn export(&mut self, name: impl Into<String>) {
-        self.exported.insert(name.into());
+        Arc::make_mut(&mut self.exported).insert(name.into());
     }
 
     /// Check if a variable is marked for export.
@@ -691,7 +826,9 @@ impl Scope {
 
     /// Unmark a variable from export.
     pub fn unexport(&mut self, name: &str) {
-        self.exported.remove(name);
+        if self.exported.contains(name) {
+            Arc::make_mut(&mut self.exported).remove(name);
+        }
     }
 
     /// Get all exported variables with their values.
@@ -699,7 +836,7 @@ impl Scope {
     /// Only returns variables that exist and are marked for export.
     pub fn exported_vars(&self) -> Vec<(String, Value)> {
         let mut result = Vec::new();
-        for name in &self.exported {
+        for name in self.exported.iter() {
             if let Some(value) = self.get(name) {
                 result.push((name.clone(), value.clone()));
             }
@@ -743,7 +880,8 @@ impl Scope {
                 return Ok(Value::Int(self.last_result.code));
             }
             return Err(PathError::Shape(
-                "$? is the POSIX exit code, not a collection — use `kaish-last` for structured data"
+                "$? is the POSIX exit code, not a collection — use `kaish-last` for structured \
+                 data or `kaish-last --error` for the error kind"
                     .to_string(),
             ));
         }
@@ -947,7 +1085,7 @@ impl Scope {
         let mut result = std::collections::HashMap::new();
         // Iterate outer to inner so inner frames override
         for frame in self.frames.iter() {
-            for (name, value) in frame {
+            for (name, value) in frame.iter() {
                 result.insert(name.clone(), value.clone());
             }
         }
@@ -955,6 +1093,28 @@ impl Scope {
         pairs.sort_by(|(a, _), (b, _)| a.cmp(b));
         pairs
     }
+
+    /// Replace every variable with `vars` and the export set with `exported`.
+    ///
+    /// The variables land in the root frame. Inner frames are emptied rather
+    /// than popped, so a caller inside a function body still unwinds
+    /// normally. Shell options, positionals, `$?`, and `$$` are untouched.
+    pub fn replace_variables(
+        &mut self,
+        vars: impl IntoIterator<Item = (String, Value)>,
+        exported: impl IntoIterator<Item = String>,
+    ) {
+        let frames = Arc::make_mut(&mut self.frames);
+        for frame in frames.iter_mut() {
+            frame.clear();
+        }
+        if let Some(root) = frames.first_mut() {
+            for (name, value) in vars {
+                root.insert(name, value);
+            }
+        }
+        self.exported = Arc::new(exported.into_iter().collect());
+    }
 }
 
 impl Default for Scope {
@@ -973,6 +1133,61 @@ mod tests {
         assert_eq!(scope.frames.len(), 1);
     }
 
+    #[test]
+    fn writes_to_a_shared_scope_copy_one_shard() {
+        let mut scope = Scope::new();
+        for i in 0..1000 {
+            scope.set_global(format!("V{i}"), Value::Int(i));
+        }
+        let snapshot = scope.clone();
+        scope.set_global("V7", Value::Int(-7));
+
+        let index = Frame::shard_index("V7");
+        let (ours, theirs) = (&scope.frames[0].shards, &snapshot.frames[0].shards);
+        for shard in 0..FRAME_SHARDS {
+            assert_eq!(Arc::ptr_eq(&ours[shard], &theirs[shard]), shard != index, "shard {shard}");
+        }
+        assert_eq!(scope.get("V7"), Some(&Value::Int(-7)));
+        assert_eq!(snapshot.get("V7"), Some(&Value::Int(7)), "the snapshot keeps its value");
+        assert_eq!(scope.all().len(), 1000);
+
+        assert_eq!(scope.remove("V8"), Some(Value::Int(8)));
+        assert_eq!(snapshot.get("V8"), Some(&Value::Int(8)));
+        assert_eq!(scope.remove("V8"), None);
+    }
+
+    #[test]
+    fn exports_are_shared_until_changed() {
+        let mut scope = Scope::new();
+        scope.export("HOME");
+        let snapshot = scope.clone();
+        assert!(Arc::ptr_eq(&scope.exported, &snapshot.exported));
+        scope.unexport("NOT_EXPORTED");
+        assert!(Arc::ptr_eq(&scope.exported, &snapshot.exported), "a no-op unexport copies nothing");
+        scope.unexport("HOME");
+        assert!(!scope.is_exported("HOME"));
+        assert!(snapshot.is_exported("HOME"));
+    }
+
+    #[test]
+    fn local_binds_in_the_function_frame() {
+        let mut scope = Scope::new();
+        scope.push_function_frame();
+        scope.push_frame(); // a for loop inside the function
+        scope.set("i", Value::Int(1));
+        scope.set_local("total", Value::Int(3));
+        scope.pop_frame();
+        assert!(scope.get("i").is_none(), "loop variable is scoped to the loop");
+        assert_eq!(scope.get("total"), Some(&Value::Int(3)));
+        assert!(scope.in_function());
+        scope.pop_frame();
+        assert!(scope.get("total").is_none(), "local ends with the function");
+        assert!(!scope.in_function());
+
+        scope.set_local("top", Value::Int(1));
+        assert_eq!(scope.get("top"), Some(&Value::Int(1)), "outside a function, local is root");
+    }
+
     #[test]
     fn set_and_get_variable() {
         let mut scope = Scope::new();
@@ -1272,6 +1487,26 @@ mod tests {
         assert_eq!(names, vec!["A", "M", "Z"]);
     }
 
+    #[test]
+    fn replace_variables_keeps_frame_depth() {
+        let mut scope = Scope::new();
+        scope.set_exported("OLD", Value::Int(1));
+        scope.push_frame();
+        scope.set("LOCAL", Value::Int(2));
+
+        scope.replace_variables(
+            vec![("NEW".to_string(), Value::Int(3))],
+            vec!["NEW".to_string()],
+        );
+        assert_eq!(scope.all(), vec![("NEW".to_string(), Value::Int(3))]);
+        assert!(scope.is_exported("NEW"));
+        assert!(!scope.is_exported("OLD"));
+
+        // The function frame is still there to pop.
+        scope.pop_frame();
+        assert_eq!(scope.get("NEW"), Some(&Value::Int(3)));
+    }
+
     // ── walk_write (lvalue assignment) ──────────────────────────────────────
 
     /// Build `xs[seg]=value` and apply it.
@@ -30,6 +30,7 @@ use std::time::Duration;
 
 use anyhow::{Context, Result};
 use tokio::sync::RwLock;
+use tracing::Instrument;
 
 /// Monotonic counter assigned to each Kernel at construction time, exposed
 /// via `$$` / `${$}`. Starts at 1; each new Kernel gets the next value.
@@ -72,6 +73,21 @@ static KERNEL_COUNTER: AtomicU64 = AtomicU64::new(1);
 /// counter, bounding each chain independently. GH #46 / #47 / #48.
 pub const MAX_RECURSION_DEPTH: usize = 48;
 
+/// Cap on files nested through `source` / `.`: a library may source its own
+/// helpers, but a chain deeper than this is stopped with an error naming it.
+/// Sourcing a file that is already being sourced is refused at any depth.
+pub const MAX_SOURCE_DEPTH: usize = 16;
+
+/// Exit code for a request stopped by a configured execution limit, such as
+/// [`KernelConfig::max_loop_iterations`].
+pub const LIMIT_EXCEEDED_EXIT_CODE: i64 = kaish_types::exit_code::LIMIT_EXCEEDED;
+
+/// Default loop iteration cap for the agent presets. High enough that no
+/// legitimate loop over files or `seq` output comes near it; low enough that
+/// a `while true` without a `break` fails in seconds rather than running until
+/// the request timeout (if any).
+pub const DEFAULT_AGENT_LOOP_ITERATIONS: u64 = 1_000_000;
+
 /// Recommended native stack size (12 MiB) for any thread that drives kaish
 /// execution — the REPL sizes its `block_on` thread and tokio worker threads
 /// to this, and embedders that call `Kernel::execute` (directly or via a tokio
@@ -93,25 +109,31 @@ pub const RECOMMENDED_STACK_SIZE: usize = 12 * 1024 * 1024;
 use async_trait::async_trait;
 
 use crate::ast::{
-    spread_non_list_message, Arg, BinaryOp, Command, Expr, FileTestOp, ListElem, RecordKey, Stmt,
-    StringPart, TestExpr, ToolDef, Value,
+    spread_non_list_message, Arg, BinaryOp, Command, Expr, FileTestOp, ListElem, RecordKey,
+    SourceSpan, Stmt, StringPart, TestExpr, ToolDef, Value,
 };
 pub use kaish_types::{CommandKind, ExecuteOptions};
-use crate::backend::{BackendError, KernelBackend};
+use crate::backend::{BackendError, KernelBackend, PolicyBackend};
 use kaish_glob::glob_match;
 use crate::dispatch::{CommandDispatcher, PipelinePosition};
-use crate::interpreter::{apply_output_format, eval_expr, expand_tilde, json_to_value_no_envelope, value_to_bool, value_to_string, value_to_text_sink, ControlFlow, ExecResult, LatchRequest, PathError, Scope};
+use crate::interpreter::{apply_output_format, eval_expr, exit_code, expand_tilde, json_to_value_no_envelope, value_to_bool, value_to_string, value_to_text_sink, ControlFlow, ExecResult, LatchRequest, PathError, Scope};
 use crate::parser::parse;
+use crate::audit::AuditLog;
+use crate::hooks::{HookEvent, KernelHooks};
+use crate::metrics::KernelMetrics;
+use crate::policy::{Policy, POLICY_DENIED_EXIT_CODE};
 use crate::scheduler::{is_bool_type, schema_param_lookup, select_leaf, stderr_stream, BoundedStream, JobManager, PipelineRunner, StderrReceiver};
+use crate::script_log::LogBuffer;
 #[cfg(feature = "subprocess")]
 use crate::scheduler::{drain_to_stream, DEFAULT_STREAM_MAX_SIZE};
-use crate::tools::{register_builtins, ExecContext, GlobalFlags, ToolArgs, ToolRegistry};
+use crate::tools::{register_builtins, ExecContext, GlobalFlags, ToolArgs, ToolRegistry, UsageError};
+use crate::transcript::Transcript;
 #[cfg(feature = "subprocess")]
 use crate::tools::{resolve_in_path, virtual_cwd_error};
-use crate::validator::{Severity, Validator};
+use crate::validator::{command_suggestion, Severity, Validator};
 #[cfg(feature = "localfs")]
 use crate::vfs::LocalFs;
-use crate::vfs::{BuiltinFs, DevFs, JobFs, MemoryFs, VfsRouter};
+use crate::vfs::{BuiltinFs, DevFs, JobFs, LogFs, MemoryFs, PipeFs, VfsRouter};
 use kaish_vfs::ByteBudget;
 #[cfg(all(feature = "localfs", feature = "overlay"))]
 use kaish_vfs::OverlayFs;
@@ -290,6 +312,46 @@ pub struct KernelConfig {
     /// Follows the same pattern as `OutputLimitConfig`: agent preset bounded, rest unbounded.
     pub vfs_budget_bytes: Option<u64>,
 
+    /// Cap on iterations of any single `for` or `while` loop.
+    ///
+    /// Checked at the same per-iteration checkpoint as cancellation. A loop
+    /// that would run past the cap stops the whole request with exit code
+    /// [`LIMIT_EXCEEDED_EXIT_CODE`] and a `loop iteration limit` message on
+    /// stderr — distinct from a timeout (124) or an interrupt (130), so the
+    /// caller can tell a runaway `while true` from a slow command.
+    ///
+    /// Agent presets default to [`DEFAULT_AGENT_LOOP_ITERATIONS`]; all other
+    /// profiles default to `None` (unbounded), like `vfs_budget_bytes`.
+    pub max_loop_iterations: Option<u64>,
+
+    /// Cap on nested shell-function calls (`f` calling `g` calling `f` …).
+    ///
+    /// Counted in [`ExecContext::call_depth`], which pipeline stages, `$(…)`
+    /// and background jobs inherit, so recursion through a pipe
+    /// (`f() { f | cat; }`) is bounded too — the stack guard
+    /// ([`MAX_RECURSION_DEPTH`]) resets on every fork. A call past the cap
+    /// fails with a `maximum recursion depth exceeded` error. Defaults to
+    /// [`MAX_RECURSION_DEPTH`] in every profile; raising it past that does not
+    /// lift the stack guard for calls made on one kernel.
+    pub max_call_depth: usize,
+
+    /// Execution policy: allowed commands, writable prefixes, and whether
+    /// external commands may run. Refusals exit with
+    /// [`POLICY_DENIED_EXIT_CODE`]; see [`crate::policy`] for what each knob
+    /// covers. A policy with `allow_exec = false` turns off
+    /// [`allow_external_commands`](Self::allow_external_commands). Defaults to
+    /// [`Policy::default`], which allows everything.
+    pub policy: Policy,
+
+    /// Append-only log of executed commands (see [`crate::audit`]). Every
+    /// builtin, script, external command, and backend tool the kernel runs is
+    /// recorded with its evaluated arguments and exit code. Defaults to `None`.
+    pub audit_log: Option<Arc<AuditLog>>,
+
+    /// Rust callbacks on command dispatch and variable assignment (see
+    /// [`crate::hooks`]). Defaults to none.
+    pub hooks: KernelHooks,
+
     /// Enable copy-on-write overlay mode (opt-in).
     ///
     /// When `true`, the primary local filesystem mount is wrapped in an
@@ -312,6 +374,17 @@ pub struct KernelConfig {
     /// in the same call as the writes, or the transaction is discarded on drop.
     /// Frontends (REPL, MCP) expose `--overlay` as an explicit opt-in flag.
     pub overlay: bool,
+
+    /// Tool bundles registered after the builtins in every kernel built from
+    /// this config (see [`crate::tools::ToolBundle`]). A bundle tool named
+    /// like a builtin replaces it. Defaults to empty.
+    pub tool_bundles: Vec<Arc<dyn crate::tools::ToolBundle>>,
+
+    /// VFS directories whose `*.kai` files hold function definitions, loaded
+    /// before the first request and re-scanned by `kaish-tools reload` (see
+    /// [`crate::tool_library`]). Later directories override earlier ones.
+    /// Defaults to empty.
+    pub tool_library: Vec<PathBuf>,
 }
 
 /// Get the default sandbox root ($HOME).
@@ -343,7 +416,14 @@ impl Default for KernelConfig {
                 request_timeout: None,
                 kill_grace: Duration::from_secs(2),
                 vfs_budget_bytes: None,
+                max_loop_iterations: None,
+                max_call_depth: MAX_RECURSION_DEPTH,
+                policy: Policy::default(),
+                audit_log: None,
+                hooks: KernelHooks::default(),
                 overlay: false,
+                tool_bundles: Vec::new(),
+                tool_library: Vec::new(),
             }
         }
         #[cfg(not(feature = "localfs"))]
@@ -364,7 +444,14 @@ impl Default for KernelConfig {
                 request_timeout: None,
                 kill_grace: Duration::from_secs(2),
                 vfs_budget_bytes: None,
+                max_loop_iterations: None,
+                max_call_depth: MAX_RECURSION_DEPTH,
+                policy: Policy::default(),
+                audit_log: None,
+                hooks: KernelHooks::default(),
                 overlay: false,
+                tool_bundles: Vec::new(),
+                tool_library: Vec::new(),
             }
         }
     }
@@ -391,7 +478,14 @@ impl KernelConfig {
             request_timeout: None,
             kill_grace: Duration::from_secs(2),
             vfs_budget_bytes: None,
+            max_loop_iterations: None,
+            max_call_depth: MAX_RECURSION_DEPTH,
+            policy: Policy::default(),
+            audit_log: None,
+            hooks: KernelHooks::default(),
             overlay: false,
+            tool_bundles: Vec::new(),
+            tool_library: Vec::new(),
         }
     }
 
@@ -421,7 +515,14 @@ impl KernelConfig {
             request_timeout: None,
             kill_grace: Duration::from_secs(2),
             vfs_budget_bytes: None,
+            max_loop_iterations: None,
+            max_call_depth: MAX_RECURSION_DEPTH,
+            policy: Policy::default(),
+            audit_log: None,
+            hooks: KernelHooks::default(),
             overlay: false,
+            tool_bundles: Vec::new(),
+            tool_library: Vec::new(),
         }
     }
 
@@ -459,7 +560,14 @@ impl KernelConfig {
             request_timeout: None,
             kill_grace: Duration::from_secs(2),
             vfs_budget_bytes: None,
+            max_loop_iterations: None,
+            max_call_depth: MAX_RECURSION_DEPTH,
+            policy: Policy::default(),
+            audit_log: None,
+            hooks: KernelHooks::default(),
             overlay: false,
+            tool_bundles: Vec::new(),
+            tool_library: Vec::new(),
         }
     }
 
@@ -494,7 +602,14 @@ impl KernelConfig {
             request_timeout: None,
             kill_grace: Duration::from_secs(2),
             vfs_budget_bytes: Some(64 * 1024 * 1024),
+            max_loop_iterations: Some(DEFAULT_AGENT_LOOP_ITERATIONS),
+            max_call_depth: MAX_RECURSION_DEPTH,
+            policy: Policy::default(),
+            audit_log: None,
+            hooks: KernelHooks::default(),
             overlay: false,
+            tool_bundles: Vec::new(),
+            tool_library: Vec::new(),
         }
     }
 
@@ -522,7 +637,14 @@ impl KernelConfig {
             request_timeout: None,
             kill_grace: Duration::from_secs(2),
             vfs_budget_bytes: Some(64 * 1024 * 1024),
+            max_loop_iterations: Some(DEFAULT_AGENT_LOOP_ITERATIONS),
+            max_call_depth: MAX_RECURSION_DEPTH,
+            policy: Policy::default(),
+            audit_log: None,
+            hooks: KernelHooks::default(),
             overlay: false,
+            tool_bundles: Vec::new(),
+            tool_library: Vec::new(),
         }
     }
 
@@ -547,7 +669,14 @@ impl KernelConfig {
             request_timeout: None,
             kill_grace: Duration::from_secs(2),
             vfs_budget_bytes: None,
+            max_loop_iterations: None,
+            max_call_depth: MAX_RECURSION_DEPTH,
+            policy: Policy::default(),
+            audit_log: None,
+            hooks: KernelHooks::default(),
             overlay: false,
+            tool_bundles: Vec::new(),
+            tool_library: Vec::new(),
         }
     }
 
@@ -675,6 +804,61 @@ impl KernelConfig {
         self
     }
 
+    /// Cap iterations of any single `for`/`while` loop at `iterations`.
+    ///
+    /// A loop that would exceed the cap stops the request with exit code
+    /// [`LIMIT_EXCEEDED_EXIT_CODE`]. Use `without_max_loop_iterations` to
+    /// remove the cap.
+    pub fn with_max_loop_iterations(mut self, iterations: u64) -> Self {
+        self.max_loop_iterations = Some(iterations);
+        self
+    }
+
+    /// Remove the loop iteration cap set by `KernelConfig::agent`.
+    pub fn without_max_loop_iterations(mut self) -> Self {
+        self.max_loop_iterations = None;
+        self
+    }
+
+    /// Cap nested shell-function calls at `depth` (see
+    /// [`KernelConfig::max_call_depth`]).
+    pub fn with_max_call_depth(mut self, depth: usize) -> Self {
+        self.max_call_depth = depth;
+        self
+    }
+
+    /// Set the execution policy (see [`crate::policy`]).
+    pub fn with_policy(mut self, policy: Policy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Record executed commands to `log` (see [`crate::audit`]).
+    pub fn with_audit_log(mut self, log: Arc<AuditLog>) -> Self {
+        self.audit_log = Some(log);
+        self
+    }
+
+    /// Call `hooks` as commands run and variables change (see [`crate::hooks`]).
+    pub fn with_hooks(mut self, hooks: KernelHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Register `bundle`'s tools in every kernel built from this config.
+    pub fn with_tool_bundle(mut self, bundle: Arc<dyn crate::tools::ToolBundle>) -> Self {
+        self.tool_bundles.push(bundle);
+        self
+    }
+
+    /// Load the function definitions in `dir`'s `*.kai` files into every
+    /// kernel built from this config. Call once per directory; a later
+    /// directory's definitions replace an earlier one's.
+    pub fn with_tool_library(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.tool_library.push(dir.into());
+        self
+    }
+
     /// Enable or disable copy-on-write overlay mode.
     ///
     /// When `true`, the primary local filesystem mount is wrapped in an
@@ -718,6 +902,12 @@ pub struct Kernel {
     tools: Arc<ToolRegistry>,
     /// User-defined tools (from `tool name { body }` statements).
     user_tools: RwLock<HashMap<String, ToolDef>>,
+    /// Tool library directories (`KernelConfig::tool_library`).
+    tool_library: Vec<PathBuf>,
+    /// Set once the tool library has been loaded, by the first request or an
+    /// explicit [`Kernel::load_tool_library`]. Forks start set: they inherit
+    /// the parent's user tools.
+    tool_library_loaded: std::sync::atomic::AtomicBool,
     /// Virtual filesystem router.
     vfs: Arc<VfsRouter>,
     /// Background job manager.
@@ -753,6 +943,24 @@ pub struct Kernel {
     overlay_handle: Option<Arc<OverlayHandle>>,
     /// Default per-request timeout (None = no default).
     request_timeout: Option<Duration>,
+    /// Per-loop iteration cap (None = unbounded).
+    max_loop_iterations: Option<u64>,
+    /// Cap on nested shell-function calls, checked against `ExecContext::call_depth`.
+    max_call_depth: usize,
+    /// Execution policy, checked at dispatch and by the validator.
+    policy: Arc<Policy>,
+    /// Audit log every dispatched command is recorded to.
+    audit_log: Option<Arc<AuditLog>>,
+    /// Command, VFS, and job metrics; shared with forks.
+    metrics: KernelMetrics,
+    /// Embedder callbacks (see [`crate::hooks`]); shared with forks.
+    hooks: KernelHooks,
+    /// Handlers registered with `on EVENT { ... }`, copied into forks like
+    /// `user_tools`.
+    script_hooks: RwLock<HashMap<HookEvent, Arc<[Stmt]>>>,
+    /// Set while a script handler runs, so the events it raises run no
+    /// handlers of their own.
+    in_hook: std::sync::atomic::AtomicBool,
     /// SIGTERM-to-SIGKILL grace period for child kills.
     kill_grace: Duration,
     /// Receiver for the kernel stderr stream.
@@ -760,6 +968,23 @@ pub struct Kernel {
     /// Pipeline stages write to the corresponding `StderrStream` (set on ExecContext).
     /// The kernel drains this after each statement in `execute_streaming`.
     stderr_receiver: tokio::sync::Mutex<StderrReceiver>,
+    /// Message of a `${path:?word}` expansion that failed in the current
+    /// request. Like bash, that failure stops the request, not just the one
+    /// command: `execute_pipeline` raises it again after the runner has
+    /// folded it into a failed command result, the error unwinds through any
+    /// enclosing statements, and the top-level loop reports it as exit 1.
+    /// Shared with [`Self::fork_attached`] forks, so a failure in a pipeline
+    /// stage, `$(...)`, or scatter worker reaches the statement that ran it.
+    unset_parameter: Arc<std::sync::Mutex<Option<String>>>,
+    /// Span of the innermost spanned statement whose evaluation raised an
+    /// error in the current top-level statement. Only statements parsed from
+    /// the `execute` input record here (re-entered code — functions, `source`,
+    /// command substitution — has spans into other text), so the execute loop
+    /// can render the error against that input.
+    error_site: std::sync::Mutex<Option<SourceSpan>>,
+    /// The failure that stopped the innermost running `try` block, recorded
+    /// by [`Self::errexit_flow`] so the block can hand it to its `catch`.
+    try_failure: std::sync::Mutex<Option<ExecResult>>,
     /// Cancellation token for interrupting execution (Ctrl-C).
     ///
     /// Protected by `std::sync::Mutex` (not tokio) because the SIGINT handler
@@ -817,6 +1042,18 @@ impl Drop for RecursionGuard<'_> {
     }
 }
 
+/// Clears [`Kernel::in_hook`] when a script handler finishes, or when its
+/// future is dropped mid-run (cancellation).
+struct HookGuard<'a> {
+    running: &'a std::sync::atomic::AtomicBool,
+}
+
+impl Drop for HookGuard<'_> {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
 /// Internal result of [`Kernel::setup_vfs`].
 struct VfsSetupResult {
     vfs: VfsRouter,
@@ -833,6 +1070,8 @@ impl Kernel {
 
         // Mount JobFs for job observability at /v/jobs
         setup.vfs.mount("/v/jobs", JobFs::new(jobs.clone()));
+        // Named pipes from `pipe create`, shared with forks through `jobs`
+        setup.vfs.mount("/v/pipes", PipeFs::new(jobs.clone()));
 
         #[cfg(all(feature = "localfs", feature = "overlay"))]
         let overlay_handle = setup.overlay_handle.take();
@@ -1034,7 +1273,8 @@ impl Kernel {
     /// their own storage backend (e.g., CRDT-backed storage in kaijutsu).
     ///
     /// A `VirtualOverlayBackend` routes paths automatically:
-    /// - `/v/*` → Internal VFS (JobFs at `/v/jobs`, MemoryFs at `/v/blobs`)
+    /// - `/v/*` → Internal VFS (JobFs at `/v/jobs`, PipeFs at `/v/pipes`, LogFs at `/v/logs`,
+///   MemoryFs at `/v/blobs`)
     /// - `/dev` → DevFs (synthetic `/dev/null`, `/dev/zero`, `/dev/random`,
     ///   `/dev/urandom`) — kernel-owned so it works even when your backend is
     ///   read-only
@@ -1094,6 +1334,7 @@ impl Kernel {
             .map(|bytes| Arc::new(ByteBudget::labeled(bytes, "vfs-memory")));
 
         vfs.mount("/v/jobs", JobFs::new(jobs.clone()));
+        vfs.mount("/v/pipes", PipeFs::new(jobs.clone()));
         let blobs_fs = match &vfs_budget {
             Some(b) => MemoryFs::with_budget(Arc::clone(b)),
             None => MemoryFs::new(),
@@ -1149,7 +1390,7 @@ impl Kernel {
         let no_host_side_channel =
             no_host_filesystem || matches!(config.vfs_mode, VfsMountMode::NoLocal);
 
-        let KernelConfig { name, cwd, skip_validation, interactive, ignore_config, mut output_limit, allow_external_commands, latch_enabled, trash_enabled, nonce_store, initial_vars, request_timeout, kill_grace, .. } = config;
+        let KernelConfig { name, cwd, skip_validation, interactive, ignore_config, mut output_limit, allow_external_commands, latch_enabled, trash_enabled, nonce_store, initial_vars, request_timeout, kill_grace, max_loop_iterations, max_call_depth, policy, audit_log, hooks, tool_bundles, tool_library, .. } = config;
 
         if no_host_side_channel {
             output_limit.set_spill_mode(crate::output_limit::SpillMode::Memory);
@@ -1158,13 +1399,19 @@ impl Kernel {
 
         let mut tools = ToolRegistry::new();
         register_builtins(&mut tools);
+        for bundle in &tool_bundles {
+            tools.register_bundle(bundle.as_ref());
+        }
         configure_tools(&mut tools);
         let tools = Arc::new(tools);
 
         // Mount BuiltinFs so `ls /v/bin` lists builtins
         vfs.mount("/v/bin", BuiltinFs::new(tools.clone()));
+        let logs = LogBuffer::default();
+        vfs.mount("/v/logs", LogFs::new(logs.clone()));
 
         let vfs = Arc::new(vfs);
+        let metrics = KernelMetrics::new(vfs.clone(), jobs.clone());
 
         let runner = PipelineRunner::new(tools.clone());
 
@@ -1180,7 +1427,19 @@ impl Kernel {
         exec_ctx.stderr = Some(stderr_writer);
         exec_ctx.ignore_config = ignore_config;
         exec_ctx.output_limit = output_limit;
+        // One gate for external commands: a policy without exec closes the
+        // same switch `allow_external_commands` does.
+        let allow_external_commands = allow_external_commands && policy.allow_exec;
         exec_ctx.allow_external_commands = allow_external_commands;
+        let policy = Arc::new(policy);
+        if policy.writable_prefixes.is_some() {
+            exec_ctx.backend = Arc::new(PolicyBackend::new(exec_ctx.backend.clone(), policy.clone()));
+        }
+        exec_ctx.policy = policy.clone();
+        exec_ctx.audit_log = audit_log.clone();
+        exec_ctx.metrics = metrics.clone();
+        exec_ctx.hooks = hooks.clone();
+        exec_ctx.logs = logs;
         exec_ctx.vfs_budget = vfs_budget.clone();
         if let Some(store) = nonce_store {
             exec_ctx.nonce_store = store;
@@ -1209,6 +1468,8 @@ impl Kernel {
             initial_vars,
             tools,
             user_tools: RwLock::new(HashMap::new()),
+            tool_library,
+            tool_library_loaded: std::sync::atomic::AtomicBool::new(false),
             vfs,
             jobs,
             runner,
@@ -1218,8 +1479,19 @@ impl Kernel {
             allow_external_commands,
             vfs_budget,
             request_timeout,
+            max_loop_iterations,
+            max_call_depth,
+            policy,
+            audit_log,
+            metrics,
+            hooks,
+            script_hooks: RwLock::new(HashMap::new()),
+            in_hook: std::sync::atomic::AtomicBool::new(false),
             kill_grace,
             stderr_receiver: tokio::sync::Mutex::new(stderr_receiver),
+            unset_parameter: Arc::default(),
+            error_site: std::sync::Mutex::new(None),
+            try_failure: std::sync::Mutex::new(None),
             cancel_token: std::sync::Mutex::new(tokio_util::sync::CancellationToken::new()),
             interrupt: std::sync::Mutex::new(None),
             #[cfg(all(unix, feature = "subprocess"))]
@@ -1282,7 +1554,7 @@ impl Kernel {
     /// stages, `$(...)` cmdsubs) where parent timeout/cancel must cascade
     /// into the fork's external children, use [`Self::fork_attached`].
     pub async fn fork(&self) -> Arc<Self> {
-        self.fork_inner(tokio_util::sync::CancellationToken::new(), self.bg_job_id)
+        self.fork_inner(tokio_util::sync::CancellationToken::new(), self.bg_job_id, Arc::default())
             .await
     }
 
@@ -1299,7 +1571,7 @@ impl Kernel {
             let parent = self.cancel_token.lock().expect("cancel_token poisoned");
             parent.child_token()
         };
-        self.fork_inner(child_token, self.bg_job_id).await
+        self.fork_inner(child_token, self.bg_job_id, self.unset_parameter.clone()).await
     }
 
     /// Fork for a background job, stamping the job id so external commands
@@ -1311,18 +1583,21 @@ impl Kernel {
         cancel: tokio_util::sync::CancellationToken,
         job_id: crate::scheduler::JobId,
     ) -> Arc<Self> {
-        self.fork_inner(cancel, Some(job_id)).await
+        self.fork_inner(cancel, Some(job_id), Arc::default()).await
     }
 
-    /// Shared fork implementation. Caller decides the cancellation token and
-    /// which background job (if any) this fork runs on behalf of.
+    /// Shared fork implementation. Caller decides the cancellation token,
+    /// which background job (if any) this fork runs on behalf of, and whether
+    /// a `${x:?}` failure in the fork stops the parent's request.
     async fn fork_inner(
         &self,
         cancel: tokio_util::sync::CancellationToken,
         bg_job_id: Option<crate::scheduler::JobId>,
+        unset_parameter: Arc<std::sync::Mutex<Option<String>>>,
     ) -> Arc<Self> {
         let scope_snapshot = self.scope.read().await.clone();
         let user_tools_snapshot = self.user_tools.read().await.clone();
+        let script_hooks_snapshot = self.script_hooks.read().await.clone();
 
         // Snapshot exec_ctx by cloning the cloneable fields, then override
         // the ones that should not carry over (stderr channel, dispatcher,
@@ -1349,6 +1624,8 @@ impl Kernel {
             initial_vars: self.initial_vars.clone(),
             tools: Arc::clone(&self.tools),
             user_tools: RwLock::new(user_tools_snapshot),
+            tool_library: self.tool_library.clone(),
+            tool_library_loaded: std::sync::atomic::AtomicBool::new(true),
             vfs: Arc::clone(&self.vfs),
             jobs: Arc::clone(&self.jobs),
             runner: self.runner.clone(),
@@ -1362,8 +1639,21 @@ impl Kernel {
             // cap as foreground writes.
             vfs_budget: self.vfs_budget.clone(),
             request_timeout: self.request_timeout,
+            max_loop_iterations: self.max_loop_iterations,
+            max_call_depth: self.max_call_depth,
+            policy: self.policy.clone(),
+            audit_log: self.audit_log.clone(),
+            metrics: self.metrics.clone(),
+            hooks: self.hooks.clone(),
+            script_hooks: RwLock::new(script_hooks_snapshot),
+            // A fork made inside a handler (a `&` job, a scatter worker) is
+            // still inside it.
+            in_hook: std::sync::atomic::AtomicBool::new(self.in_hook.load(Ordering::Relaxed)),
             kill_grace: self.kill_grace,
             stderr_receiver: tokio::sync::Mutex::new(stderr_receiver),
+            unset_parameter,
+            error_site: std::sync::Mutex::new(None),
+            try_failure: std::sync::Mutex::new(None),
             cancel_token: std::sync::Mutex::new(cancel),
             interrupt: std::sync::Mutex::new(None),
             #[cfg(all(unix, feature = "subprocess"))]
@@ -1489,6 +1779,46 @@ impl Kernel {
         }
     }
 
+    /// Load the tool library before the first request (see
+    /// [`KernelConfig::tool_library`]). Problems are logged, not raised — a
+    /// broken library file must not fail the request that happened to come
+    /// first. Frontends that want to show them call
+    /// [`Self::load_tool_library`] up front instead.
+    async fn ensure_tool_library(&self) {
+        if self.tool_library_loaded.load(Ordering::Acquire) {
+            return;
+        }
+        for error in self.load_tool_library().await.errors {
+            tracing::warn!(target: "kaish::kernel::tool_library", "{error}");
+        }
+    }
+
+    /// Load the function definitions from the configured tool library
+    /// directories into this kernel, replacing any user tool of the same
+    /// name. Relative directories resolve against the current cwd. Runs
+    /// automatically before the first request; calling it again (or
+    /// `kaish-tools reload`) re-scans the directories.
+    pub async fn load_tool_library(&self) -> crate::tool_library::ToolLibraryReport {
+        self.tool_library_loaded.store(true, Ordering::Release);
+        let (backend, dirs) = {
+            let ctx = self.exec_ctx.read().await;
+            let dirs: Vec<PathBuf> = self.tool_library.iter().map(|dir| ctx.cwd.join(dir)).collect();
+            (ctx.backend.clone(), dirs)
+        };
+        let (defs, errors) = crate::tool_library::scan(backend.as_ref(), &dirs).await;
+
+        let mut user_tools = self.user_tools.write().await;
+        let loaded = defs
+            .into_iter()
+            .map(|def| {
+                let name = def.name.clone();
+                user_tools.insert(name.clone(), def);
+                name
+            })
+            .collect();
+        crate::tool_library::ToolLibraryReport { loaded, errors }
+    }
+
     /// Execute kaish source code with default options.
     ///
     /// Equivalent to `execute_with_options(input, ExecuteOptions::default())`.
@@ -1497,6 +1827,19 @@ impl Kernel {
         self.run_inner(input, ExecuteOptions::default(), None, None).await
     }
 
+    /// Execute kaish source code and deserialize its structured result.
+    ///
+    /// Reads [`ExecResult::data`] — the value a builtin sets explicitly, which
+    /// `--json` provides for any command — never stdout, so a script meant
+    /// for this door usually ends in `--json` (`ls src --json`). A failed
+    /// command, a result with no `.data`, or data that doesn't fit `T` is an
+    /// error carrying the reason; use [`Self::execute`] to inspect the raw
+    /// result instead.
+    pub async fn execute_typed<T: serde::de::DeserializeOwned>(&self, input: &str) -> Result<T> {
+        let result = self.execute(input).await?;
+        typed_data(&result)
+    }
+
     /// Argv-native peer of [`Self::execute`] — run one command whose arguments
     /// are **already tokenized**.
     ///
@@ -1540,6 +1883,7 @@ impl Kernel {
     #[tracing::instrument(level = "info", skip(self, argv), fields(cmd = name, argc = argv.len()))]
     pub async fn execute_argv(&self, name: &str, argv: &[Value]) -> Result<ExecResult> {
         let _guard = self.acquire_execute_lock().await;
+        self.ensure_tool_library().await;
         // Fresh cancel surface for this call: `execute_pipeline` reads
         // `self.cancel_token`, so a stale cancelled token from a prior call must be
         // replaced first. The returned clone is the token the watchdog cancels on
@@ -1550,7 +1894,7 @@ impl Kernel {
         // Honor the kernel-configured request timeout for parity with `execute`.
         let timeout = self.request_timeout;
         if timeout == Some(Duration::ZERO) {
-            return Ok(ExecResult::failure(124, "timeout: timed out after 0s".to_string()));
+            return Ok(ExecResult::timed_out("timeout: timed out after 0s".to_string()));
         }
 
         let pipeline = crate::ast::Pipeline {
@@ -1558,6 +1902,7 @@ impl Kernel {
                 name: name.to_string(),
                 args: argv_to_args(argv),
                 redirects: Vec::new(),
+                span: None,
             }],
             background: false,
         };
@@ -1626,7 +1971,8 @@ impl Kernel {
     /// clock via `ctx.patient`), and when a timeout is set, spawns the watchdog
     /// racing `cancel` — on an elapsed deadline `cancel` fires (cascading
     /// SIGTERM/SIGKILL to external children via `wait_or_kill`) and the result's
-    /// code becomes 124. With `timeout == None`, runs `work` directly. Clears the
+    /// code becomes 124; a cancel from anywhere else makes it 130. With
+    /// `timeout == None`, runs `work` directly. Clears the
     /// watchdog handle from `exec_ctx` on the way out (a patient hold against a
     /// stale handle would silently suspend nothing). Callers must short-circuit a
     /// `Some(Duration::ZERO)` timeout (return 124 without spawning) before calling.
@@ -1644,8 +1990,12 @@ impl Kernel {
         {
             let mut ec = self.exec_ctx.write().await;
             ec.watchdog = watchdog.clone();
+            // A request cancelled mid-call never unwound its functions' depth.
+            ec.call_depth = 0;
+            ec.sourcing.clear();
         }
 
+        let mut timed_out = false;
         let result = if let Some(d) = timeout {
             #[allow(clippy::expect_used)]
             let watchdog = watchdog.clone().expect("watchdog constructed when timeout is set");
@@ -1653,10 +2003,11 @@ impl Kernel {
             let timer = tokio::spawn(watchdog.run(elapsed.clone(), cancel.clone()));
             let r = work.await;
             timer.abort();
+            timed_out = elapsed.load(std::sync::atomic::Ordering::SeqCst);
             match r {
                 Ok(mut res) => {
-                    if elapsed.load(std::sync::atomic::Ordering::SeqCst) {
-                        res.code = 124;
+                    if timed_out {
+                        res.code = exit_code::TIMEOUT;
                         if res.err.is_empty() {
                             res.err = format!("timeout: timed out after {:?}", d);
                         }
@@ -1669,6 +2020,22 @@ impl Kernel {
             work.await
         };
 
+        // Any other cancel is an interrupt (`Kernel::cancel`, the REPL's
+        // Ctrl-C, an embedder token or interrupt check). Whatever the killed
+        // child exited with (143 after SIGTERM), or wherever the checkpoint
+        // that noticed it stopped, the request reports 130 and `$?` says so.
+        let result = match result {
+            Ok(mut res) if !timed_out && cancel.is_cancelled() => {
+                res.code = exit_code::INTERRUPTED;
+                if res.err.is_empty() {
+                    res.err = "interrupted".to_string();
+                }
+                self.update_last_result(&res).await;
+                Ok(res)
+            }
+            other => other,
+        };
+
         // The timer task is gone (fired or aborted); drop the stale handle.
         {
             let mut ec = self.exec_ctx.write().await;
@@ -1825,6 +2192,7 @@ impl Kernel {
         on_output: Option<&mut (dyn FnMut(&ExecResult) + Send)>,
     ) -> Result<ExecResult> {
         let _guard = self.acquire_execute_lock().await;
+        self.ensure_tool_library().await;
 
         // Always reset to a fresh internal token; this is the kernel's own
         // cancel surface for embedders calling `Kernel::cancel()`. The
@@ -1883,7 +2251,7 @@ impl Kernel {
             if let Some(h) = watcher_handle {
                 h.abort();
             }
-            return Ok(ExecResult::failure(124, "timeout: timed out after 0s".to_string()));
+            return Ok(ExecResult::timed_out("timeout: timed out after 0s".to_string()));
         }
 
         // Apply per-call vars overlay (push frame + set_exported), wrapped in
@@ -2045,10 +2413,28 @@ impl Kernel {
             None => &mut *noop_cb,
         };
 
+        // A request is recorded only if a transcript was running both before
+        // and after it, so `transcript start` and `transcript stop` themselves
+        // stay out of the file.
+        let (transcript, history) = {
+            let ctx = self.exec_ctx.read().await;
+            (ctx.transcript.clone(), ctx.history.clone())
+        };
+        let recording = transcript.is_recording();
+        let started = kaish_types::clock::Instant::now();
+        let seq = history.begin(input);
+
         let result = self
             .run_under_watchdog(timeout, &effective_cancel, self.execute_streaming_inner(input, cb_ref))
             .await;
 
+        // A request kaish couldn't run at all (a parse error, say) counts as
+        // failed, with the usage-error code.
+        history.finish(seq, result.as_ref().map_or(2, |r| r.code));
+        if recording && let Ok(result) = &result {
+            self.record_transcript(&transcript, input, result, started.elapsed()).await;
+        }
+
         // Restore self.cancel_token to a fresh, uncancelled token so the
         // embedder's view of `Kernel::cancel()` stays predictable on the
         // next call (it cancels the kernel's own token, not whatever was
@@ -2081,12 +2467,7 @@ impl Kernel {
         on_output: &mut (dyn FnMut(&ExecResult) + Send),
     ) -> Result<ExecResult> {
         let program = parse(input).map_err(|errors| {
-            let msg = errors
-                .iter()
-                .map(|e| e.format(input))
-                .collect::<Vec<_>>()
-                .join("\n");
-            anyhow::anyhow!("parse error:\n{}", msg)
+            anyhow::anyhow!("parse error:\n{}", crate::parser::format_errors(&errors, input))
         })?;
 
         // AST display mode: show AST instead of executing
@@ -2104,8 +2485,11 @@ impl Kernel {
         // to the result's stderr at each return point below.
         let mut surfaced_warnings = String::new();
         if !self.skip_validation {
+            let cwd = self.cwd().await;
             let user_tools = self.user_tools.read().await;
-            let validator = Validator::new(&self.tools, &user_tools);
+            let validator = Validator::new(&self.tools, &user_tools)
+                .with_policy(&self.policy)
+                .with_cwd(&cwd);
             let issues = validator.validate(&program);
 
             // Collect errors (warnings are logged but don't prevent execution)
@@ -2150,19 +2534,50 @@ impl Kernel {
 
         // Reset cancellation token for this execution.
         let cancel = self.reset_cancel();
+        self.take_unset_parameter();
 
-        for stmt in program.statements {
+        for (index, stmt) in program.statements.into_iter().enumerate() {
             if matches!(stmt, Stmt::Empty) {
                 continue;
             }
 
             // Cancellation checkpoint
             if cancel.is_cancelled() {
-                result.code = 130;
+                result.code = exit_code::INTERRUPTED;
                 return Ok(result);
             }
 
-            let flow = self.execute_stmt_flow(&stmt).await?;
+            // A span per top-level statement, under the execute span. Only
+            // this outer loop gets one — `execute_stmt_flow` itself stays
+            // unwrapped on the recursion ring (GH #48); nested structure
+            // shows through the per-command spans in `execute_command`.
+            let span = tracing::info_span!(
+                "statement",
+                index,
+                kind = stmt.kind_name(),
+                code = tracing::field::Empty,
+            );
+            self.error_site.lock().unwrap_or_else(|e| e.into_inner()).take();
+            let flow = match self.execute_stmt_flow(&stmt).instrument(span.clone()).await {
+                Ok(flow) => flow,
+                Err(e) => {
+                    // `${x:?}` ends the request with its message and exit 1,
+                    // like bash, instead of surfacing as a kernel error.
+                    let Some(msg) = self.take_unset_parameter() else { return Err(self.locate_error(e, input)) };
+                    let drained_stderr = self.stderr_receiver.lock().await.drain_lossy();
+                    let mut failed = ExecResult::failure(1, format!("{msg}\n"));
+                    failed.stderr = drained_stderr;
+                    on_output(&failed);
+                    accumulate_result(&mut result, &failed);
+                    if !surfaced_warnings.is_empty() {
+                        result.err = format!("{surfaced_warnings}{}", result.err);
+                    }
+                    return Ok(result);
+                }
+            };
+            if let ControlFlow::Normal(r) = &flow {
+                span.record("code", r.code);
+            }
 
             // Drain any stderr written by pipeline stages during this statement.
             // This captures stderr from intermediate pipeline stages that would
@@ -2175,12 +2590,8 @@ impl Kernel {
             match flow {
                 ControlFlow::Normal(mut r) => {
                     if !drained_stderr.is_empty() {
-                        if !r.err.is_empty() && !r.err.ends_with('\n') {
-                            r.err.push('\n');
-                        }
                         // Prepend pipeline stderr before the last stage's stderr
-                        let combined = format!("{}{}", drained_stderr, r.err);
-                        r.err = combined;
+                        r.stderr = format!("{}{}", drained_stderr, r.stderr);
                     }
                     on_output(&r);
                     // Carry the last statement's structured output for MCP TOON encoding.
@@ -2192,7 +2603,7 @@ impl Kernel {
                 }
                 ControlFlow::Exit { code } => {
                     if !drained_stderr.is_empty() {
-                        result.err.push_str(&drained_stderr);
+                        result.push_stderr(&drained_stderr);
                     }
                     result.code = code;
                     if !surfaced_warnings.is_empty() {
@@ -2202,7 +2613,7 @@ impl Kernel {
                 }
                 ControlFlow::Return { mut value } => {
                     if !drained_stderr.is_empty() {
-                        value.err = format!("{}{}", drained_stderr, value.err);
+                        value.stderr = format!("{}{}", drained_stderr, value.stderr);
                     }
                     on_output(&value);
                     // A top-level `return` stops the script, like `exit` —
@@ -2216,7 +2627,7 @@ impl Kernel {
                 }
                 ControlFlow::Break { result: mut r, .. } | ControlFlow::Continue { result: mut r, .. } => {
                     if !drained_stderr.is_empty() {
-                        r.err = format!("{}{}", drained_stderr, r.err);
+                        r.stderr = format!("{}{}", drained_stderr, r.stderr);
                     }
                     on_output(&r);
                     accumulate_result(&mut result, &r);
@@ -2230,6 +2641,39 @@ impl Kernel {
         Ok(result)
     }
 
+    /// Record `span` as where the current statement's error was raised,
+    /// unless a more deeply nested statement already did or this code was not
+    /// parsed from the `execute` input (see [`Self::error_site`]).
+    fn note_error_site(&self, span: Option<SourceSpan>) {
+        if span.is_none() || self.recursion_depth.load(Ordering::Relaxed) > 0 {
+            return;
+        }
+        let mut site = self.error_site.lock().unwrap_or_else(|e| e.into_inner());
+        if site.is_none() {
+            *site = span;
+        }
+    }
+
+    /// Append the recorded error site to a top-level statement's error, as
+    /// `at line L, col C in `<source>``, rendered against `input`.
+    fn locate_error(&self, error: anyhow::Error, input: &str) -> anyhow::Error {
+        let site = self.error_site.lock().unwrap_or_else(|e| e.into_inner()).take();
+        let Some(span) = site.filter(|s| s.end <= input.len() && s.start <= s.end) else {
+            return error;
+        };
+        let Some(text) = input.get(span.start..span.end) else {
+            return error;
+        };
+        // One line, at most 60 chars, so a long pipeline stays readable.
+        let first_line = text.lines().next().unwrap_or("");
+        let mut snippet: String = first_line.chars().take(60).collect();
+        if snippet.len() < text.len() {
+            snippet.push('…');
+        }
+        let (line, col) = span.to_line_col(input);
+        anyhow::anyhow!("{error:#}\n  at line {line}, col {col} in `{snippet}`")
+    }
+
     /// Execute a single statement, returning control flow information.
     fn execute_stmt_flow<'a>(
         &'a self,
@@ -2245,13 +2689,15 @@ impl Kernel {
             Stmt::Assignment(assign) => {
                 // Use async evaluator to support command substitution
                 let value = self.eval_expr_async(&assign.value).await
-                    .context("failed to evaluate assignment")?;
+                    .context("failed to evaluate assignment")
+                    .inspect_err(|_| self.note_error_site(assign.span))?;
                 let mut scope = self.scope.write().await;
                 if assign.path.segments.len() == 1 {
                     // Plain `NAME=value` — no subscript, so `local` applies.
                     if assign.local {
-                        // local: set in innermost (current function) frame
-                        scope.set(assign.name(), value.clone());
+                        // local: set in the innermost function frame, so a
+                        // local declared inside a loop outlives the loop
+                        scope.set_local(assign.name(), value.clone());
                     } else {
                         // non-local: update existing or create in root frame
                         scope.set_global(assign.name(), value.clone());
@@ -2266,9 +2712,17 @@ impl Kernel {
                             "{name}: undefined — create it first, e.g. `{name}={{}}` or `{name}=[]`"
                         ),
                         PathError::Absence(msg) | PathError::Shape(msg) => anyhow::anyhow!(msg),
-                    })?;
+                    }).inspect_err(|_| self.note_error_site(assign.span))?;
                 }
+                let changed = if self.hooks.watches_vars() {
+                    scope.get(assign.name()).cloned()
+                } else {
+                    None
+                };
                 drop(scope);
+                if let Some(value) = changed {
+                    self.hooks.var_change(assign.name(), &value);
+                }
 
                 // Assignments don't produce output (like sh)
                 Ok(ControlFlow::ok(ExecResult::success("")))
@@ -2280,28 +2734,34 @@ impl Kernel {
                     commands: vec![cmd.clone()],
                     background: false,
                 };
-                let result = Box::pin(self.execute_pipeline(&pipeline)).await?;
+                let mut result = Box::pin(self.execute_pipeline(&pipeline)).await
+                    .inspect_err(|_| self.note_error_site(cmd.span))?;
                 self.update_last_result(&result).await;
+                self.raise_command_error(&pipeline.commands, &mut result).await;
 
                 // Check for error exit mode (set -e)
                 if !result.ok() {
                     let scope = self.scope.read().await;
-                    if scope.error_exit_enabled() {
-                        return Ok(ControlFlow::exit_code(result.code));
+                    if scope.stops_on_failure() {
+                        drop(scope);
+                        return Ok(self.errexit_flow(&result).await);
                     }
                 }
 
                 Ok(ControlFlow::ok(result))
             }
             Stmt::Pipeline(pipeline) => {
-                let result = Box::pin(self.execute_pipeline(pipeline)).await?;
+                let mut result = Box::pin(self.execute_pipeline(pipeline)).await
+                    .inspect_err(|_| self.note_error_site(pipeline.span()))?;
                 self.update_last_result(&result).await;
+                self.raise_command_error(&pipeline.commands, &mut result).await;
 
                 // Check for error exit mode (set -e)
                 if !result.ok() {
                     let scope = self.scope.read().await;
-                    if scope.error_exit_enabled() {
-                        return Ok(ControlFlow::exit_code(result.code));
+                    if scope.stops_on_failure() {
+                        drop(scope);
+                        return Ok(self.errexit_flow(&result).await);
                     }
                 }
 
@@ -2427,14 +2887,19 @@ impl Kernel {
                     scope.push_frame();
                 }
 
-                'outer: for item in items {
+                'outer: for (iteration, item) in (1u64..).zip(items) {
                     // Cancellation checkpoint per iteration
                     if self.is_cancelled() {
                         let mut scope = self.scope.write().await;
                         scope.pop_frame();
-                        result.code = 130;
+                        result.code = exit_code::INTERRUPTED;
                         return Ok(ControlFlow::ok(result));
                     }
+                    if let Some(flow) = self.check_loop_limit("for", iteration).await {
+                        let mut scope = self.scope.write().await;
+                        scope.pop_frame();
+                        return Ok(flow);
+                    }
                     {
                         let mut scope = self.scope.write().await;
                         scope.set(&for_loop.variable, item);
@@ -2454,11 +2919,12 @@ impl Kernel {
                                 accumulate_result(&mut result, r);
                                 if !r.ok() {
                                     let scope = self.scope.read().await;
-                                    if scope.error_exit_enabled() {
+                                    if scope.stops_on_failure() {
                                         drop(scope);
                                         let mut scope = self.scope.write().await;
                                         scope.pop_frame();
-                                        return Ok(ControlFlow::exit_code(r.code));
+                                        drop(scope);
+                                        return Ok(self.errexit_flow(r).await);
                                     }
                                 }
                             }
@@ -2499,12 +2965,13 @@ impl Kernel {
             }
             Stmt::While(while_loop) => {
                 let mut result = ExecResult::success("");
+                let mut iteration: u64 = 0;
 
                 'outer: loop {
                     // Evaluate condition - use async to support command substitution
                     // Cancellation checkpoint per iteration
                     if self.is_cancelled() {
-                        result.code = 130;
+                        result.code = exit_code::INTERRUPTED;
                         return Ok(ControlFlow::ok(result));
                     }
 
@@ -2514,6 +2981,11 @@ impl Kernel {
                         break;
                     }
 
+                    iteration += 1;
+                    if let Some(flow) = self.check_loop_limit("while", iteration).await {
+                        return Ok(flow);
+                    }
+
                     // Execute body
                     for stmt in &while_loop.body {
                         let mut flow = self.execute_stmt_flow(stmt).await?;
@@ -2523,8 +2995,9 @@ impl Kernel {
                                 accumulate_result(&mut result, r);
                                 if !r.ok() {
                                     let scope = self.scope.read().await;
-                                    if scope.error_exit_enabled() {
-                                        return Ok(ControlFlow::exit_code(r.code));
+                                    if scope.stops_on_failure() {
+                                        drop(scope);
+                                        return Ok(self.errexit_flow(r).await);
                                     }
                                 }
                             }
@@ -2619,6 +3092,8 @@ impl Kernel {
                 } else {
                     0
                 };
+                // A real `exit` is not a failure for an enclosing `try` to catch.
+                self.take_try_failure();
                 Ok(ControlFlow::exit_code(code))
             }
             Stmt::ToolDef(tool_def) => {
@@ -2725,8 +3200,9 @@ impl Kernel {
                 self.update_last_result(&result).await;
                 if !result.ok() {
                     let scope = self.scope.read().await;
-                    if scope.error_exit_enabled() {
-                        return Ok(ControlFlow::exit_code(result.code));
+                    if scope.stops_on_failure() {
+                        drop(scope);
+                        return Ok(self.errexit_flow(&result).await);
                     }
                 }
                 Ok(ControlFlow::ok(result))
@@ -2783,11 +3259,219 @@ impl Kernel {
                     None => flow,
                 }
             }
+            Stmt::Par(body) => {
+                let plan = {
+                    let cwd = self.cwd().await;
+                    let user_tools = self.user_tools.read().await;
+                    crate::validator::plan_par(body, Some(&cwd), |name| user_tools.contains_key(name))
+                };
+                if plan.is_concurrent() {
+                    return self.execute_par(body, &plan.writes).await;
+                }
+                // The validator already warned why; run it like any block.
+                let mut result = ExecResult::success("");
+                for stmt in body {
+                    let flow = self.execute_stmt_flow(stmt).await?;
+                    match flow {
+                        ControlFlow::Normal(r) => {
+                            accumulate_result(&mut result, &r);
+                            self.drain_stderr_into(&mut result).await;
+                        }
+                        other => {
+                            self.drain_stderr_into(&mut result).await;
+                            return Ok(other);
+                        }
+                    }
+                }
+                Ok(ControlFlow::ok(result))
+            }
+            Stmt::Try(try_stmt) => {
+                // Inside the body every failing statement stops it, as under
+                // `set -e`; `errexit_flow` records the failure and the `Exit`
+                // it returns unwinds to here. Kernel errors are caught too.
+                let suppressed = {
+                    let mut scope = self.scope.write().await;
+                    scope.enter_try()
+                };
+                self.take_try_failure();
+                let mut result = ExecResult::success("");
+                let mut failed = None;
+                let mut escaped = None;
+                for stmt in &try_stmt.body {
+                    match self.execute_stmt_flow(stmt).await {
+                        Ok(ControlFlow::Normal(r)) => {
+                            accumulate_result(&mut result, &r);
+                            self.drain_stderr_into(&mut result).await;
+                        }
+                        Ok(ControlFlow::Exit { code }) => {
+                            self.drain_stderr_into(&mut result).await;
+                            match self.take_try_failure() {
+                                Some(r) => failed = Some(r),
+                                None => escaped = Some(ControlFlow::exit_code(code)),
+                            }
+                            break;
+                        }
+                        Ok(other) => {
+                            self.drain_stderr_into(&mut result).await;
+                            escaped = Some(other);
+                            break;
+                        }
+                        Err(e) => {
+                            self.error_site.lock().unwrap_or_else(|e| e.into_inner()).take();
+                            // A caught `${x:?}` is handled here; it must not
+                            // go on stopping the statements after the `try`.
+                            self.take_unset_parameter();
+                            failed = Some(ExecResult::failure(1, format!("{e:#}")));
+                            break;
+                        }
+                    }
+                }
+                {
+                    let mut scope = self.scope.write().await;
+                    scope.exit_try(suppressed);
+                }
+                if let Some(flow) = escaped {
+                    return Ok(flow);
+                }
+                let Some(failed) = failed else {
+                    return Ok(ControlFlow::ok(result));
+                };
+                // Ctrl-C is not an error to handle: stop as `set -e` would.
+                if self.is_cancelled() {
+                    return Ok(ControlFlow::exit_code(failed.code));
+                }
+
+                // Keep what the failing statement printed before it failed;
+                // its diagnostics become `${ERROR}` rather than output.
+                let mut partial = failed.clone();
+                partial.err.clear();
+                partial.stderr.clear();
+                accumulate_result(&mut result, &partial);
+                let message = match failed.stderr_text().trim_end() {
+                    "" => format!("exit code {}", failed.code),
+                    text => text.to_string(),
+                };
+                self.update_last_result(&failed).await;
+                {
+                    let mut scope = self.scope.write().await;
+                    scope.set_global("ERROR", Value::String(message));
+                }
+
+                result.code = 0;
+                for stmt in &try_stmt.catch {
+                    let flow = self.execute_stmt_flow(stmt).await?;
+                    match flow {
+                        ControlFlow::Normal(r) => {
+                            accumulate_result(&mut result, &r);
+                            self.drain_stderr_into(&mut result).await;
+                        }
+                        other => {
+                            self.drain_stderr_into(&mut result).await;
+                            return Ok(other);
+                        }
+                    }
+                }
+                Ok(ControlFlow::ok(result))
+            }
+            Stmt::On(on) => {
+                let Some(event) = HookEvent::from_name(&on.event) else {
+                    return Ok(ControlFlow::ok(ExecResult::failure(
+                        2,
+                        format!("on: unknown event '{}' (events: {})", on.event, HookEvent::names()),
+                    )));
+                };
+                let mut hooks = self.script_hooks.write().await;
+                if on.body.is_empty() {
+                    hooks.remove(&event);
+                } else {
+                    hooks.insert(event, on.body.clone().into());
+                }
+                Ok(ControlFlow::ok(ExecResult::success("")))
+            }
             Stmt::Empty => Ok(ControlFlow::ok(ExecResult::success(""))),
         }
         })
     }
 
+    /// Run the statements of a `par` block concurrently, each in its own
+    /// attached fork, and fold their results back in statement order.
+    ///
+    /// [`crate::validator::plan_par`] has shown the statements independent,
+    /// so every fork starts from the same scope and only the variables a
+    /// statement may assign (`writes`, indexed like `body`) are copied back.
+    /// Output is concatenated in statement order; the exit code is that of
+    /// the first statement that failed.
+    async fn execute_par(
+        &self,
+        body: &[Stmt],
+        writes: &[std::collections::BTreeSet<String>],
+    ) -> Result<ControlFlow> {
+        let mut workers = Vec::with_capacity(body.len());
+        for stmt in body {
+            let fork = self.fork_attached().await;
+            let stmt = stmt.clone();
+            workers.push(tokio::spawn(crate::telemetry::bind_current_context(async move {
+                let flow = fork.execute_stmt_flow(&stmt).await;
+                (flow, fork)
+            })));
+        }
+        // Join every worker before looking at any result, so an early return
+        // below never leaves a statement running.
+        let mut joined = Vec::with_capacity(workers.len());
+        for worker in workers {
+            joined.push(worker.await);
+        }
+
+        let mut result = ExecResult::success("");
+        let mut first_failure: Option<i64> = None;
+        for (outcome, names) in joined.into_iter().zip(writes) {
+            let (flow, fork) = outcome.map_err(|e| anyhow::anyhow!("par: statement task failed: {e}"))?;
+            {
+                let fork_scope = fork.scope.read().await;
+                let mut scope = self.scope.write().await;
+                for name in names {
+                    match fork_scope.get(name) {
+                        Some(value) => {
+                            scope.set(name.clone(), value.clone());
+                            if fork_scope.is_exported(name) {
+                                scope.export(name.clone());
+                            }
+                        }
+                        None => {
+                            scope.remove(name);
+                        }
+                    }
+                }
+            }
+            match flow? {
+                ControlFlow::Normal(mut r) => {
+                    fork.drain_stderr_into(&mut r).await;
+                    if !r.ok() && first_failure.is_none() {
+                        first_failure = Some(r.code);
+                    }
+                    accumulate_result(&mut result, &r);
+                }
+                other => {
+                    // `set -e` stopped the statement: its diagnostics went to
+                    // the fork's stderr stream, so forward them to ours.
+                    let mut stray = ExecResult::success("");
+                    fork.drain_stderr_into(&mut stray).await;
+                    if !stray.stderr.is_empty()
+                        && let Some(stderr) = self.exec_ctx.read().await.stderr.clone()
+                    {
+                        stderr.write_str(&stray.stderr);
+                    }
+                    return Ok(other);
+                }
+            }
+        }
+        if let Some(code) = first_failure {
+            result.code = code;
+        }
+        self.update_last_result(&result).await;
+        Ok(ControlFlow::ok(result))
+    }
+
     /// Build a boxed per-command `ExecContext` snapshot from the persistent
     /// kernel state (`ec`/`scope`, both already locked by the caller).
     ///
@@ -2810,6 +3494,7 @@ impl Kernel {
             scope: scope.clone(),
             cwd: ec.cwd.clone(),
             prev_cwd: ec.prev_cwd.clone(),
+            dir_stack: ec.dir_stack.clone(),
             stdin: ec.stdin.clone(),
             stdin_data: ec.stdin_data.clone(),
             stdin_data_rx: None,
@@ -2825,7 +3510,16 @@ impl Kernel {
             ignore_config: ec.ignore_config.clone(),
             output_limit: ec.output_limit.clone(),
             allow_external_commands: self.allow_external_commands,
+            policy: ec.policy.clone(),
+            audit_log: ec.audit_log.clone(),
             nonce_store: ec.nonce_store.clone(),
+            checkpoints: ec.checkpoints.clone(),
+            result_cache: ec.result_cache.clone(),
+            logs: ec.logs.clone(),
+            transcript: ec.transcript.clone(),
+            history: ec.history.clone(),
+            metrics: ec.metrics.clone(),
+            hooks: ec.hooks.clone(),
             trash_backend: ec.trash_backend.clone(),
             #[cfg(all(unix, feature = "subprocess"))]
             terminal_state: ec.terminal_state.clone(),
@@ -2835,6 +3529,8 @@ impl Kernel {
             current_invocation: None,
             vfs_budget: self.vfs_budget.clone(),
             watchdog: ec.watchdog.clone(),
+            call_depth: ec.call_depth,
+            sourcing: ec.sourcing.clone(),
             #[cfg(all(feature = "localfs", feature = "overlay"))]
             overlay_handle: self.overlay_handle.clone(),
         })
@@ -2892,6 +3588,12 @@ impl Kernel {
 
         let mut result = self.runner.run(&pipeline.commands, &mut ctx, self).await;
 
+        // The runner reports a `${x:?}` failure as an ordinary failed command;
+        // raise it again so it stops the request (see `unset_parameter`).
+        if let Some(msg) = self.unset_parameter.lock().unwrap_or_else(|e| e.into_inner()).clone() {
+            return Err(anyhow::anyhow!(msg));
+        }
+
         // Post-hoc spill check (catches builtins and fast external commands)
         if ctx.output_limit.is_enabled() {
             let _ = crate::output_limit::spill_if_needed(&mut result, &ctx.output_limit).await;
@@ -2909,6 +3611,7 @@ impl Kernel {
             let mut ec = self.exec_ctx.write().await;
             ec.cwd = ctx.cwd.clone();
             ec.prev_cwd = ctx.prev_cwd.clone();
+            ec.dir_stack = ctx.dir_stack.clone();
             ec.aliases = ctx.aliases.clone();
             ec.ignore_config = ctx.ignore_config.clone();
             ec.output_limit = ctx.output_limit.clone();
@@ -2991,8 +3694,9 @@ impl Kernel {
             if !text.is_empty() {
                 stdout.write(text.as_bytes()).await;
             }
-            if !result.err.is_empty() {
-                stderr.write(result.err.as_bytes()).await;
+            let diagnostics = result.stderr_text();
+            if !diagnostics.is_empty() {
+                stderr.write(diagnostics.as_bytes()).await;
             }
 
             // Close streams
@@ -3083,8 +3787,39 @@ impl Kernel {
     }
 
     /// Execute a single command.
+    ///
+    /// Every dispatched command gets a `command` span (name, argument count,
+    /// exit code, duration) and a metrics sample here. Alias re-entry goes
+    /// through `execute_command_depth`, so one invocation is one span. The
+    /// span costs one `Instrumented` wrapper per dispatch level — tens of bytes
+    /// beside the ~50 KB a level already takes (see [`MAX_RECURSION_DEPTH`]);
+    /// the per-statement and per-dispatch spans GH #48 removed sat on every
+    /// statement and alias hop instead. The embedder's `pre_exec` and
+    /// `post_exec` hooks bracket the same dispatch.
     async fn execute_command(&self, name: &str, args: &[Arg]) -> Result<ExecResult> {
-        self.execute_command_depth(name, args, 0).await
+        let span = tracing::debug_span!(
+            "command",
+            command = %name,
+            argc = args.len(),
+            code = tracing::field::Empty,
+            duration_ms = tracing::field::Empty,
+        );
+        self.hooks.pre_exec(name);
+        let started = kaish_types::clock::Instant::now();
+        let result = self.execute_command_depth(name, args, 0).instrument(span.clone()).await;
+        let elapsed = started.elapsed();
+        if let Ok(result) = &result {
+            span.record("code", result.code);
+            span.record("duration_ms", u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX));
+            self.metrics.record_command(name, result.code, elapsed);
+        }
+        if self.hooks.watches_commands() {
+            match &result {
+                Ok(result) => self.hooks.post_exec(name, result, elapsed),
+                Err(e) => self.hooks.post_exec(name, &ExecResult::failure(1, format!("{e:#}")), elapsed),
+            }
+        }
+        result
     }
 
     async fn execute_command_depth(&self, name: &str, args: &[Arg], alias_depth: u8) -> Result<ExecResult> {
@@ -3101,11 +3836,24 @@ impl Kernel {
         // updated. A name that is not a special-form falls through to alias /
         // `/v/bin/` / user-tool / builtin / `PATH` resolution unchanged.
         if let Some(form) = crate::validator::SpecialForm::from_name(name) {
-            return match form {
-                crate::validator::SpecialForm::True => Ok(ExecResult::success("")),
-                crate::validator::SpecialForm::False => Ok(ExecResult::failure(1, "")),
-                crate::validator::SpecialForm::Source => Box::pin(self.execute_source(args)).await,
+            let result = match form {
+                crate::validator::SpecialForm::True => ExecResult::success(""),
+                crate::validator::SpecialForm::False => ExecResult::failure(1, ""),
+                crate::validator::SpecialForm::Source => {
+                    return Box::pin(self.execute_source(args)).await;
+                }
+                crate::validator::SpecialForm::Import => self.execute_import(args).await?,
             };
+            // `true`/`false`/`import` never reach the tool paths that audit, so
+            // record them here; `source` is covered by the commands it runs.
+            self.audit(name, &[], result.code);
+            return Ok(result);
+        }
+
+        // An explicit namespace (`builtin:cat`, `user:deploy`, `backend:x`)
+        // names exactly one tool: no alias expansion, no fall-through.
+        if let Some((namespace, bare)) = crate::validator::CommandNamespace::split(name) {
+            return Box::pin(self.execute_namespaced(namespace, bare, args)).await;
         }
 
         // Alias expansion (with recursion limit)
@@ -3128,11 +3876,11 @@ impl Kernel {
             }
         }
 
-        // Handle /v/bin/ prefix — dispatch to builtins via virtual path
+        // Handle /v/bin/ prefix — the builtin itself, like `builtin:`.
         if let Some(builtin_name) = name.strip_prefix("/v/bin/") {
             return match self.tools.get(builtin_name) {
-                Some(_) => Box::pin(self.execute_command_depth(builtin_name, args, alias_depth)).await,
-                None => Ok(ExecResult::failure(127, format!("command not found: {}", name))),
+                Some(_) => Box::pin(self.execute_tool(builtin_name, args)).await,
+                None => Ok(ExecResult::not_found(format!("command not found: {}", name))),
             };
         }
 
@@ -3146,6 +3894,56 @@ impl Kernel {
             }
         }
 
+        Box::pin(self.execute_tool(name, args)).await
+    }
+
+    /// Run `bare` in `namespace` only (`builtin:cat`, `user:deploy`,
+    /// `backend:x`), skipping aliases and every other kind of command.
+    async fn execute_namespaced(
+        &self,
+        namespace: crate::validator::CommandNamespace,
+        bare: &str,
+        args: &[Arg],
+    ) -> Result<ExecResult> {
+        use crate::validator::CommandNamespace;
+
+        let qualified = format!("{}{}", namespace.prefix(), bare);
+        match namespace {
+            CommandNamespace::User => {
+                let tool_def = self.user_tools.read().await.get(bare).cloned();
+                match tool_def {
+                    Some(tool_def) => Box::pin(self.execute_user_tool(tool_def, args)).await,
+                    None => Ok(ExecResult::not_found(format!("command not found: {qualified}"))),
+                }
+            }
+            CommandNamespace::Builtin => {
+                if self.tools.contains(bare) {
+                    Box::pin(self.execute_tool(bare, args)).await
+                } else {
+                    Ok(ExecResult::not_found(format!("command not found: {qualified}")))
+                }
+            }
+            CommandNamespace::Backend => {
+                if !self.policy.allows_tool(bare) {
+                    return Ok(ExecResult::failure(POLICY_DENIED_EXIT_CODE, Policy::tool_denied_message(bare)));
+                }
+                match Box::pin(self.try_execute_backend_tool(bare, args)).await? {
+                    Some(result) => Ok(result),
+                    None => Ok(ExecResult::not_found(format!("command not found: {qualified}"))),
+                }
+            }
+        }
+    }
+
+    /// Run `name` as a tool: everything past user functions in the
+    /// resolution order — builtins, `.kai` scripts on `PATH`, external
+    /// commands, then backend tools.
+    async fn execute_tool(&self, name: &str, args: &[Arg]) -> Result<ExecResult> {
+        // Everything past user tools is a tool the policy may refuse.
+        if !self.policy.allows_tool(name) {
+            return Ok(ExecResult::failure(POLICY_DENIED_EXIT_CODE, Policy::tool_denied_message(name)));
+        }
+
         // Look up builtin tool
         let tool = match self.tools.get(name) {
             Some(t) => t,
@@ -3157,78 +3955,28 @@ impl Kernel {
                 // Try executing as external command from PATH — boxed because its
                 // future is the heaviest branch here (holds a `tokio::process::Command`,
                 // argv, the child's stdio streams, and kill/reap drop guards); leaving
-                // it inline fattens every `execute_command_depth` frame on the recursion
-                // ring even when the command is a builtin.
-                if let Some(result) = Box::pin(self.try_execute_external(name, args)).await? {
+                // it inline fattens every `execute_tool` frame on the recursion ring
+                // even when the command is a builtin.
+                let mut external_argv = None;
+                if let Some(result) = Box::pin(self.try_execute_external(name, args, &mut external_argv)).await? {
+                    if let Some(argv) = external_argv {
+                        self.audit(name, &argv, result.code);
+                    }
                     return Ok(result);
                 }
 
                 // Try backend-registered tools (embedder engines, etc.)
-                // Look up tool schema for positional→named mapping.
-                // Clone backend and drop read lock before awaiting (may involve network I/O).
-                // Backend tools expect named JSON params, so enable positional mapping.
-                let backend = self.exec_ctx.read().await.backend.clone();
-                let tool_schema = backend
-                    .get_tool(name)
-                    .await
-                    .unwrap_or_else(|e| {
-                        // Schema lookup failing just means positionals won't
-                        // get name-mapped below — `call_tool` is still
-                        // attempted. Trace it so the degradation is visible
-                        // rather than silently swallowed.
-                        tracing::debug!("backend get_tool error for {name}: {e}");
-                        None
-                    })
-                    .map(|t| {
-                    let mut s = t.schema;
-                    // Flat backend/MCP tools expect named JSON params, so map
-                    // bare positionals onto named params. Subcommand-aware tools
-                    // route positionals through the subcommand path and declare
-                    // map_positionals per leaf (kj keeps it false so it re-parses
-                    // the argv with its own clap) — don't blanket-override them.
-                    if s.subcommands.is_empty() {
-                        s.map_positionals = true;
-                    }
-                    s
-                });
-                let tool_args = self.build_args_async(args, tool_schema.as_ref()).await?;
-                let mut ctx = self.exec_ctx.write().await;
-                {
-                    let scope = self.scope.read().await;
-                    ctx.scope = scope.clone();
-                }
-                let backend = ctx.backend.clone();
-                match backend.call_tool(name, tool_args, &mut *ctx).await {
-                    Ok(tool_result) => {
-                        let mut scope = self.scope.write().await;
-                        *scope = ctx.scope.clone();
-                        // Preserve every field (data/content_type/baggage/latch,
-                        // not just stdout text) — this is the embedder seam:
-                        // `x=$(embedder_tool)` and structured iteration over
-                        // its result depend on `.data` surviving the crossing
-                        // back into the kernel.
-                        return Ok(ExecResult::from(tool_result));
-                    }
-                    Err(BackendError::ToolNotFound(_)) => {
-                        // The backend confirms no such tool exists — fall
-                        // through to "command not found" below.
-                    }
-                    Err(e) => {
-                        // The tool was found (dispatch reached real
-                        // execution) but running it failed — a genuine
-                        // execution error, not "command not found". Surface
-                        // it loudly instead of masking it as exit-127.
-                        return Ok(ExecResult::failure(1, format!("{}: {}", name, e)));
-                    }
+                if let Some(result) = Box::pin(self.try_execute_backend_tool(name, args)).await? {
+                    return Ok(result);
                 }
 
-                return Ok(ExecResult::failure(127, format!("command not found: {}", name)));
+                return Ok(self.command_not_found(name).await);
             }
         };
 
         // Build arguments (async to support command substitution, schema-aware for flag values)
         let schema = tool.schema();
-        let tool_args = self.build_args_async(args, Some(&schema)).await?;
+        let (tool_args, audit_argv) = self.build_args_audited(args, Some(&schema)).await?;
 
         // --help / -h: show the generic whole-tool help, unless either the tool's
         // root schema claims that flag OR the tool owns its output. Owned-output
@@ -3256,6 +4004,16 @@ impl Kernel {
         // `ToolSchema` now so it doesn't ride that await in every command's frame
         // (GH #48, item 7).
         let owns_output = schema.owns_output;
+        let tool_timeout = schema.timeout_ms.map(Duration::from_millis);
+        // What the schema can see wrong with the call, kept for when the tool
+        // rejects its arguments. Boxed for the same reason as above. Tools
+        // with subcommands are left to clap, whose errors name the leaf.
+        let usage_error = schema
+            .subcommands
+            .is_empty()
+            .then(|| UsageError::check(&tool_args, &schema))
+            .flatten()
+            .map(Box::new);
         drop(schema);
 
         // Snapshot exec_ctx into a local context and release the write lock
@@ -3319,24 +4077,97 @@ impl Kernel {
         // `tool.execute()`; dropping the error here only empties this
         // side-channel capture, never the command's real result.
         let argv = tool_args.to_argv().unwrap_or_default();
+
+        // A pure invocation (`Tool::cache_ttl`) is answered from the result
+        // cache. Any stdin makes the call impure. The key leaves out
+        // `--no-cache`, which skips the lookup but still refreshes the entry.
+        let fed_stdin = ctx.stdin.is_some()
+            || ctx.stdin_data.is_some()
+            || ctx.stdin_data_rx.is_some()
+            || ctx.pipe_stdin.is_some();
+        let cache_slot = match tool.cache_ttl(&tool_args, &*ctx) {
+            Some(ttl) if !fed_stdin => {
+                let key_argv: Vec<String> = argv.iter().filter(|a| *a != "--no-cache").cloned().collect();
+                // Stamp each operand so a file changed behind a read-only
+                // mount misses instead of serving stale output.
+                let mut stamps = Vec::with_capacity(tool_args.positional.len());
+                for operand in &tool_args.positional {
+                    let stamp = match operand {
+                        Value::String(path) => ctx.backend.stat(&ctx.resolve_path(path)).await.ok(),
+                        _ => None,
+                    };
+                    stamps.push(stamp.map(|entry| (entry.size, entry.modified)));
+                }
+                let key = crate::result_cache::ResultCache::key(name, &key_argv, &ctx.cwd, &stamps);
+                let command = std::iter::once(name.to_string()).chain(key_argv).collect::<Vec<_>>().join(" ");
+                Some((key, command, ttl))
+            }
+            _ => None,
+        };
+        let result_cache = ctx.result_cache.clone();
+        if let Some((key, _, _)) = &cache_slot
+            && !tool_args.has_flag("no-cache")
+            && let Some(result) = result_cache.get(key)
+        {
+            // Hand the unused pipe writer back so the runner forwards the
+            // cached output to the next stage.
+            self.exec_ctx.write().await.pipe_stdout = ctx.pipe_stdout.take();
+            if let Some(argv) = audit_argv {
+                self.audit(name, &argv, result.code);
+            }
+            return Ok(result);
+        }
         ctx.current_invocation = Some(Box::new((name.to_string(), argv)));
 
-        let result = tool.execute(tool_args, &mut *ctx).await;
+        let had_pipe_stdout = ctx.pipe_stdout.is_some();
+        let result = match tool_timeout {
+            None => tool.execute(tool_args, &mut *ctx).await,
+            Some(limit) => {
+                // The tool's own bound (`ToolSchema::timeout_ms`), separate
+                // from the script timeout. Run under a child token so work the
+                // tool spawned sees the cancel when the limit hits.
+                let child = ctx.cancel.child_token();
+                let saved = std::mem::replace(&mut ctx.cancel, child.clone());
+                let outcome = tokio::time::timeout(limit, tool.execute(tool_args, &mut *ctx)).await;
+                ctx.cancel = saved;
+                match outcome {
+                    Ok(result) => result,
+                    Err(_) => {
+                        child.cancel();
+                        ExecResult::timed_out(format!("{name}: timed out after {}ms", limit.as_millis()))
+                    }
+                }
+            }
+        };
+        // A usage failure the schema can explain gets the schema's account:
+        // each problem with its did-you-mean, the usage line, the parameters.
+        // Only after the tool has failed — schemas don't list every flag a
+        // tool accepts, so a schema complaint alone proves nothing.
+        let result = match usage_error {
+            Some(usage) if result.code == exit_code::USAGE => usage_error_result(&usage),
+            _ => result,
+        };
+        // A tool that took the pipe writer streamed its output there; the
+        // result holds none of it, so there is nothing to cache.
+        let streamed = had_pipe_stdout && ctx.pipe_stdout.is_none();
 
         // Sync mutations back. Tools may have changed scope (set/cd),
-        // cwd/prev_cwd (cd), and aliases (alias). Also return any unused pipe
-        // endpoints to self.exec_ctx so dispatch_command's post-execute sync
-        // hands them back to the pipeline runner — the runner uses
-        // stage_ctx.pipe_stdout to write the result to the next stage when
-        // the tool itself didn't take and write to it.
+        // cwd/prev_cwd/dir_stack (cd, pushd), and aliases (alias). Also
+        // return any unused pipe endpoints to self.exec_ctx so
+        // dispatch_command's post-execute sync hands them back to the
+        // pipeline runner — the runner uses stage_ctx.pipe_stdout to write
+        // the result to the next stage when the tool itself didn't take and
+        // write to it.
         {
             let mut scope = self.scope.write().await;
             *scope = ctx.scope.clone();
         }
-        {
+        let cwd_moved = {
             let mut ec = self.exec_ctx.write().await;
+            let moved = (ec.cwd != ctx.cwd).then(|| (ec.cwd.clone(), ctx.cwd.clone()));
             ec.cwd = ctx.cwd;
             ec.prev_cwd = ctx.prev_cwd;
+            ec.dir_stack = ctx.dir_stack;
             ec.aliases = ctx.aliases;
             // A builtin (`set -o output-limit`, `kaish-output-limit set`) can
             // mutate the runtime output limit; without this sync the change is
@@ -3350,17 +4181,58 @@ impl Kernel {
             ec.ignore_config = ctx.ignore_config.clone();
             ec.pipe_stdin = ctx.pipe_stdin.take();
             ec.pipe_stdout = ctx.pipe_stdout.take();
-        }
+            moved
+        };
 
         // Builtins parse --json via the GlobalFlags flatten in their clap
         // struct and write ctx.output_format. The kernel applies it — unless the
         // tool owns its own output (renders --json itself), in which case we
         // leave its bytes untouched.
-        let result = finalize_output(result, ctx.output_format, owns_output);
+        let mut result = finalize_output(result, ctx.output_format, owns_output);
+        if let Some((key, command, ttl)) = cache_slot
+            && !streamed
+        {
+            result_cache.insert(key, command, &result, ttl);
+        }
+        if let Some(argv) = audit_argv {
+            self.audit(name, &argv, result.code);
+        }
+
+        // `cd`, `kaish-state import`, a checkpoint restore: whichever
+        // builtin moved the working directory raises `cwd_change`.
+        if let Some((old, new)) = cwd_moved {
+            let args = vec![old.to_string_lossy().into_owned(), new.to_string_lossy().into_owned()];
+            if let Some(handler) = self.run_script_hook(HookEvent::CwdChange, args).await {
+                append_hook_output(&mut result, &handler);
+            }
+        }
 
         Ok(result)
     }
 
+    /// Append a finished request to the running transcript (see
+    /// [`crate::transcript`]). Best effort: a failed write is logged and
+    /// never changes the result.
+    async fn record_transcript(&self, transcript: &Transcript, input: &str, result: &ExecResult, elapsed: Duration) {
+        let Some((path, bytes)) = transcript.entry(input, result, elapsed) else {
+            return;
+        };
+        let backend = self.exec_ctx.read().await.backend.clone();
+        if let Err(e) = backend.append(&path, &bytes).await {
+            tracing::warn!("transcript {}: {e}", path.display());
+        }
+    }
+
+    /// Append a finished command to the audit log, if one is configured. Best
+    /// effort: a failed write is traced, never surfaced as the command's error.
+    fn audit(&self, command: &str, argv: &[String], code: i64) {
+        if let Some(log) = &self.audit_log
+            && let Err(e) = log.record(&self.name, command, argv, code)
+        {
+            tracing::warn!("audit log {}: {e}", log.path().display());
+        }
+    }
+
     /// The session `HOME` from the kernel scope, if set. Tilde expansion reads
     /// this rather than `std::env::var("HOME")` so the kernel stays hermetic —
     /// a hermetic embedder (empty `initial_vars`) gets `None`, and `~` is left
@@ -3390,6 +4262,24 @@ impl Kernel {
         bind_tool_args(args, schema, self).await
     }
 
+    /// [`Self::build_args_async`], plus the argv to record in the audit log
+    /// when one is configured. The argv follows `args` in source order, with
+    /// each expression's evaluated value, so the log shows what was typed
+    /// (`echo cargo test --workspace`) rather than the flags-first form
+    /// [`ToolArgs::to_argv`] rebuilds. Nothing is evaluated twice.
+    async fn build_args_audited(
+        &self,
+        args: &[Arg],
+        schema: Option<&crate::tools::ToolSchema>,
+    ) -> Result<(ToolArgs, Option<Vec<String>>)> {
+        if self.audit_log.is_none() {
+            return Ok((self.build_args_async(args, schema).await?, None));
+        }
+        let recorder = RecordingSource::new(self);
+        let tool_args = bind_tool_args(args, schema, &recorder).await?;
+        Ok((tool_args, Some(recorder.source_order_argv(args))))
+    }
+
     /// Build arguments as flat string list for external commands.
     ///
     /// Unlike `build_args_async` which separates flags into a HashSet (for schema-aware builtins),
@@ -3559,6 +4449,7 @@ impl Kernel {
                     (
                         ec.cwd.clone(),
                         ec.prev_cwd.clone(),
+                        ec.dir_stack.clone(),
                         ec.aliases.clone(),
                         ec.ignore_config.clone(),
                         ec.output_limit.clone(),
@@ -3578,9 +4469,10 @@ impl Kernel {
                 }
                 {
                     let mut ec = self.exec_ctx.write().await;
-                    let (cwd, prev_cwd, aliases, ignore_config, output_limit) = saved_ec;
+                    let (cwd, prev_cwd, dir_stack, aliases, ignore_config, output_limit) = saved_ec;
                     ec.cwd = cwd;
                     ec.prev_cwd = prev_cwd;
+                    ec.dir_stack = dir_stack;
                     ec.aliases = aliases;
                     ec.ignore_config = ignore_config;
                     ec.output_limit = output_limit;
@@ -3645,18 +4537,8 @@ impl Kernel {
                     .map(Value::Int)
                     .map_err(|msg| anyhow::anyhow!(msg))
             }
-            Expr::VarWithDefault { path, default } => {
-                // Resolve inside a scoped guard so the lock is released before the
-                // recursive default evaluation.
-                let resolved = {
-                    let scope = self.scope.read().await;
-                    crate::interpreter::resolve_default(&scope, path)
-                        .map_err(|msg| anyhow::anyhow!(msg))?
-                };
-                match resolved {
-                    Some(value) => Ok(value),
-                    None => self.eval_string_parts_async(default).await.map(Value::String),
-                }
+            Expr::VarWithDefault { path, op, default } => {
+                self.eval_var_with_default_async(path, *op, default).await
             }
             Expr::Arithmetic(expr_str) => {
                 let scope = self.scope.read().await;
@@ -3720,10 +4602,54 @@ impl Kernel {
                 }
                 Ok(Value::Json(serde_json::Value::Object(map)))
             }
+            Expr::Conditional { condition, then_value, else_value } => {
+                // Same truthiness as `if`; the other branch never runs, so a
+                // `$(…)` in it has no side effects.
+                let chosen = if is_truthy(&self.eval_expr_async(condition).await?) {
+                    then_value
+                } else {
+                    else_value
+                };
+                self.eval_expr_async(chosen).await
+            }
         }
         })
     }
 
+    /// `${path<op>word}` over the shared decision
+    /// ([`crate::interpreter::resolve_default_op`]). The scope lock is released
+    /// before the word is evaluated, since the word may run `$(...)`.
+    async fn eval_var_with_default_async(
+        &self,
+        path: &crate::ast::VarPath,
+        op: crate::ast::DefaultOp,
+        default: &[StringPart],
+    ) -> Result<Value> {
+        use crate::interpreter::DefaultAction;
+        let action = {
+            let scope = self.scope.read().await;
+            crate::interpreter::resolve_default_op(&scope, path, op).map_err(|msg| anyhow::anyhow!(msg))?
+        };
+        match action {
+            DefaultAction::Value(value) => Ok(value),
+            DefaultAction::Word => self.eval_string_parts_async(default).await.map(Value::String),
+            DefaultAction::Assign => {
+                let word = Value::String(self.eval_string_parts_async(default).await?);
+                let mut scope = self.scope.write().await;
+                crate::interpreter::assign_default(&mut scope, path, word.clone())
+                    .map_err(|msg| anyhow::anyhow!(msg))?;
+                Ok(word)
+            }
+            DefaultAction::Fail => {
+                let word = self.eval_string_parts_async(default).await?;
+                let msg = crate::interpreter::unset_error_message(path, &word);
+                *self.unset_parameter.lock().unwrap_or_else(|e| e.into_inner()) = Some(msg.clone());
+                Err(anyhow::anyhow!(msg))
+            }
+            DefaultAction::Empty => Ok(Value::String(String::new())),
+        }
+    }
+
     /// Async helper to evaluate multiple StringParts into a single string.
     fn eval_string_parts_async<'a>(&'a self, parts: &'a [StringPart]) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<String>> + Send + 'a>> {
         Box::pin(async move {
@@ -3888,16 +4814,9 @@ impl Kernel {
                         }
                     }
                 }
-                StringPart::VarWithDefault { path, default } => {
-                    let resolved = {
-                        let scope = self.scope.read().await;
-                        crate::interpreter::resolve_default(&scope, path)
-                            .map_err(|msg| anyhow::anyhow!(msg))?
-                    };
-                    match resolved {
-                        Some(value) => value_to_text_sink(&value).map_err(|e| anyhow::anyhow!("{e}")),
-                        None => self.eval_string_parts_async(default).await,
-                    }
+                StringPart::VarWithDefault { path, op, default } => {
+                    let value = self.eval_var_with_default_async(path, *op, default).await?;
+                    value_to_text_sink(&value).map_err(|e| anyhow::anyhow!("{e}"))
                 }
             StringPart::VarLength(path) => {
                 let scope = self.scope.read().await;
@@ -3948,6 +4867,7 @@ impl Kernel {
                     (
                         ec.cwd.clone(),
                         ec.prev_cwd.clone(),
+                        ec.dir_stack.clone(),
                         ec.aliases.clone(),
                         ec.ignore_config.clone(),
                         ec.output_limit.clone(),
@@ -3967,9 +4887,10 @@ impl Kernel {
                 }
                 {
                     let mut ec = self.exec_ctx.write().await;
-                    let (cwd, prev_cwd, aliases, ignore_config, output_limit) = saved_ec;
+                    let (cwd, prev_cwd, dir_stack, aliases, ignore_config, output_limit) = saved_ec;
                     ec.cwd = cwd;
                     ec.prev_cwd = prev_cwd;
+                    ec.dir_stack = dir_stack;
                     ec.aliases = aliases;
                     ec.ignore_config = ignore_config;
                     ec.output_limit = output_limit;
@@ -4032,10 +4953,7 @@ impl Kernel {
             receiver.drain_lossy()
         };
         if !drained.is_empty() {
-            if !result.err.is_empty() && !result.err.ends_with('\n') {
-                result.err.push('\n');
-            }
-            result.err.push_str(&drained);
+            result.push_stderr(&drained);
         }
     }
 
@@ -4044,17 +4962,55 @@ impl Kernel {
     /// Functions push a new scope frame for local variables. Variables declared
     /// with `local` are scoped to the function; other assignments modify outer
     /// scopes (or create in root if new).
+    ///
+    /// The body runs through the same dispatch as any other statement, so a
+    /// function may call other functions or itself; nesting is bounded by
+    /// [`KernelConfig::max_call_depth`], counted in `ExecContext::call_depth`.
     async fn execute_user_tool(&self, def: ToolDef, args: &[Arg]) -> Result<ExecResult> {
+        let call_depth = self.exec_ctx.read().await.call_depth;
+        if call_depth >= self.max_call_depth {
+            return Err(anyhow::anyhow!(
+                "maximum recursion depth exceeded calling '{}': shell functions are \
+                 nested {} deep — add a base case, or raise \
+                 KernelConfig::with_max_call_depth if the recursion is legitimate",
+                def.name,
+                self.max_call_depth
+            ));
+        }
         let _depth = self.enter_recursion("a shell function")?;
 
         // 1. Build function args from AST args (async to support command substitution)
         let tool_args = self.build_args_async(args, None).await?;
 
-        // 2. Push a new scope frame for local variables
+        // 1b. Bind declared parameters, checking each against its type
+        let mut bound = Vec::with_capacity(def.params.len());
+        for (i, param) in def.params.iter().enumerate() {
+            let value = if param.variadic {
+                let rest = tool_args.positional.get(i..).unwrap_or_default();
+                crate::interpreter::params::bind_variadic(&def.name, param, rest)
+            } else if let Some(value) = tool_args.positional.get(i) {
+                crate::interpreter::params::bind_param(&def.name, param, value.clone())
+            } else if let Some(default) = &param.default {
+                let value = self.eval_expr_async(default).await?;
+                crate::interpreter::params::bind_param(&def.name, param, value)
+            } else {
+                Err(crate::interpreter::params::missing_param(&def.name, param))
+            };
+            match value {
+                Ok(value) => bound.push((param.name.clone(), value)),
+                Err(msg) => return Ok(ExecResult::failure(2, msg)),
+            }
+        }
+
+        // 2. Push a new scope frame for local variables and count the call
         {
             let mut scope = self.scope.write().await;
-            scope.push_frame();
+            scope.push_function_frame();
+            for (name, value) in bound {
+                scope.set(name, value);
+            }
         }
+        self.exec_ctx.write().await.call_depth = call_depth + 1;
 
         // 3. Save current positional parameters and set new ones for this function
         let saved_positional = {
@@ -4077,6 +5033,7 @@ impl Kernel {
         // function body survives instead of being lossy-decoded here.
         let mut accumulated_out: Vec<u8> = Vec::new();
         let mut accumulated_err = String::new();
+        let mut accumulated_stderr = String::new();
         let mut last_code = 0i64;
         let mut last_data: Option<Value> = None;
 
@@ -4091,7 +5048,7 @@ impl Kernel {
         let mut exec_error: Option<anyhow::Error> = None;
         let mut exit_code: Option<i64> = None;
 
-        for stmt in &def.body {
+        for stmt in def.body.iter() {
             match self.execute_stmt_flow(stmt).await {
                 Ok(flow) => {
                     // Drain pipeline stderr after each sub-statement.
@@ -4100,19 +5057,21 @@ impl Kernel {
                         receiver.drain_lossy()
                     };
                     if !drained.is_empty() {
-                        accumulated_err.push_str(&drained);
+                        accumulated_stderr.push_str(&drained);
                     }
 
                     match flow {
                         ControlFlow::Normal(r) => {
                             push_out(&mut accumulated_out, &r);
                             accumulated_err.push_str(&r.err);
+                            accumulated_stderr.push_str(&r.stderr);
                             last_code = r.code;
                             last_data = r.data;
                         }
                         ControlFlow::Return { value } => {
                             push_out(&mut accumulated_out, &value);
                             accumulated_err.push_str(&value.err);
+                            accumulated_stderr.push_str(&value.stderr);
                             last_code = value.code;
                             last_data = value.data;
                             break;
@@ -4124,6 +5083,7 @@ impl Kernel {
                         ControlFlow::Break { result: r, .. } | ControlFlow::Continue { result: r, .. } => {
                             push_out(&mut accumulated_out, &r);
                             accumulated_err.push_str(&r.err);
+                            accumulated_stderr.push_str(&r.stderr);
                             last_code = r.code;
                             last_data = r.data;
                         }
@@ -4142,6 +5102,7 @@ impl Kernel {
             scope.pop_frame();
             scope.set_positional(saved_positional.0, saved_positional.1);
         }
+        self.exec_ctx.write().await.call_depth = call_depth;
 
         // 5. Propagate error or exit after cleanup
         if let Some(e) = exec_error {
@@ -4150,6 +5111,12 @@ impl Kernel {
         let code = exit_code.unwrap_or(last_code);
         let mut result = ExecResult::success_text_or_bytes(accumulated_out).with_code(code);
         result.err = accumulated_err;
+        // A failure that stopped the body inside a `try` becomes this call's
+        // failure, diagnostics and all, so the caller's `catch` can report it.
+        if exit_code.is_some() && let Some(failed) = self.take_try_failure() {
+            result.err.push_str(&failed.stderr_text());
+        }
+        result.stderr = accumulated_stderr;
         result.data = last_data;
         Ok(result)
     }
@@ -4183,6 +5150,112 @@ impl Kernel {
         Ok(guard)
     }
 
+    /// Run the script handler for `event` with `args` as its positional
+    /// arguments (see [`crate::hooks`]). `None` when no handler is registered
+    /// or one is already running.
+    async fn run_script_hook(&self, event: HookEvent, args: Vec<String>) -> Option<ExecResult> {
+        let body = self.script_hooks.read().await.get(&event).cloned()?;
+        if self.in_hook.swap(true, Ordering::Relaxed) {
+            return None;
+        }
+        let _running = HookGuard { running: &self.in_hook };
+        let handler = ToolDef { name: format!("on {event}"), params: Vec::new(), body, module: None };
+        let args: Vec<Arg> = args
+            .into_iter()
+            .map(|arg| Arg::Positional(Expr::Literal(Value::String(arg))))
+            .collect();
+        Some(match Box::pin(self.execute_user_tool(handler, &args)).await {
+            Ok(result) => result,
+            Err(e) => {
+                // The handler's failure is reported, not raised, so a `${x:?}`
+                // inside it stops only the handler.
+                self.take_unset_parameter();
+                ExecResult::failure(1, format!("on {event}: {e:#}"))
+            }
+        })
+    }
+
+    /// Raise `command_error` for a failed command or pipeline statement,
+    /// unless the `&&`/`||` around it handles the failure. The handler's
+    /// output joins `result`; `$?` stays the failing status.
+    async fn raise_command_error(&self, commands: &[Command], result: &mut ExecResult) {
+        if result.ok() || !self.scope.read().await.failure_unhandled() {
+            return;
+        }
+        if !self.script_hooks.read().await.contains_key(&HookEvent::CommandError) {
+            return;
+        }
+        let command = commands.iter().map(|c| c.name.as_str()).collect::<Vec<_>>().join(" | ");
+        let message = match result.stderr_text().trim_end() {
+            "" => format!("exit code {}", result.code),
+            text => text.to_string(),
+        };
+        let args = vec![command, result.code.to_string(), message];
+        if let Some(handler) = self.run_script_hook(HookEvent::CommandError, args).await {
+            append_hook_output(result, &handler);
+            self.update_last_result(result).await;
+        }
+    }
+
+    /// The flow that stops a request under `set -e` after `failed`.
+    ///
+    /// `ControlFlow::Exit` carries only the code, so the failing command's
+    /// stderr and error message go out on the kernel stderr stream first —
+    /// otherwise `set -e; cat missing` exits 1 with no diagnostic at all.
+    /// Inside a `try` block the failure is recorded for its `catch` instead.
+    async fn errexit_flow(&self, failed: &ExecResult) -> ControlFlow {
+        if self.scope.read().await.in_try() {
+            *self.try_failure.lock().unwrap_or_else(|e| e.into_inner()) = Some(failed.clone());
+            return ControlFlow::exit_code(failed.code);
+        }
+        let diagnostics = failed.stderr_text();
+        if !diagnostics.is_empty() {
+            let stderr = self.exec_ctx.read().await.stderr.clone();
+            if let Some(stderr) = stderr {
+                let mut text = diagnostics.into_owned();
+                if !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                stderr.write_str(&text);
+            }
+        }
+        ControlFlow::exit_code(failed.code)
+    }
+
+    /// Take the message of a `${x:?}` failure still stopping the request.
+    fn take_unset_parameter(&self) -> Option<String> {
+        self.unset_parameter.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+
+    /// Take the failure recorded for the innermost `try` block, if any. An
+    /// `Exit` flow reaching a `try` with one recorded came from a failing
+    /// statement; without one it is a real `exit`.
+    fn take_try_failure(&self) -> Option<ExecResult> {
+        self.try_failure.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+
+    /// Enforce [`KernelConfig::max_loop_iterations`] at a loop's
+    /// per-iteration checkpoint. `iteration` is 1-based. Once the cap is
+    /// passed, reports on the kernel stderr stream and returns the flow that
+    /// stops the request with [`LIMIT_EXCEEDED_EXIT_CODE`].
+    async fn check_loop_limit(&self, what: &str, iteration: u64) -> Option<ControlFlow> {
+        let max = self.max_loop_iterations?;
+        if iteration <= max {
+            return None;
+        }
+        let stderr = self.exec_ctx.read().await.stderr.clone();
+        if let Some(stderr) = stderr {
+            stderr.write_str(&format!(
+                "{what}: loop iteration limit ({max}) exceeded — a loop without a \
+                 reachable exit was stopped; raise the cap with \
+                 KernelConfig::with_max_loop_iterations if the work is legitimate\n"
+            ));
+        }
+        // Nor is a runaway loop: the cap stops the request, `try` or not.
+        self.take_try_failure();
+        Some(ControlFlow::exit_code(LIMIT_EXCEEDED_EXIT_CODE))
+    }
+
     async fn execute_block_capturing(&self, stmts: &[Stmt]) -> Result<ExecResult> {
         let _depth = self.enter_recursion("command substitution")?;
         // Accumulate stdout as raw bytes so a binary-producing statement
@@ -4190,6 +5263,7 @@ impl Kernel {
         // caller can preserve it. The final result is text iff valid UTF-8.
         let mut accumulated_out: Vec<u8> = Vec::new();
         let mut accumulated_err = String::new();
+        let mut accumulated_stderr = String::new();
         let mut last_code = 0i64;
         let mut last_data: Option<Value> = None;
 
@@ -4211,7 +5285,7 @@ impl Kernel {
                 receiver.drain_lossy()
             };
             if !drained.is_empty() {
-                accumulated_err.push_str(&drained);
+                accumulated_stderr.push_str(&drained);
             }
 
             match flow {
@@ -4220,12 +5294,14 @@ impl Kernel {
                 | ControlFlow::Continue { result: r, .. } => {
                     push_out(&mut accumulated_out, &r);
                     accumulated_err.push_str(&r.err);
+                    accumulated_stderr.push_str(&r.stderr);
                     last_code = r.code;
                     last_data = r.data;
                 }
                 ControlFlow::Return { value } => {
                     push_out(&mut accumulated_out, &value);
                     accumulated_err.push_str(&value.err);
+                    accumulated_stderr.push_str(&value.stderr);
                     last_code = value.code;
                     last_data = value.data;
                     break;
@@ -4239,10 +5315,109 @@ impl Kernel {
 
         let mut result = ExecResult::success_text_or_bytes(accumulated_out).with_code(last_code);
         result.err = accumulated_err;
+        result.stderr = accumulated_stderr;
         result.data = last_data;
         Ok(result)
     }
 
+    /// The exit-127 result for a command nothing resolves, naming the
+    /// closest command that does exist — builtin, user tool, alias, or
+    /// backend (MCP) tool — when one is near.
+    async fn command_not_found(&self, name: &str) -> ExecResult {
+        let (aliases, backend) = {
+            let ctx = self.exec_ctx.read().await;
+            (ctx.aliases.keys().cloned().collect::<Vec<_>>(), ctx.backend.clone())
+        };
+        let mut known: Vec<String> = self.tools.names().into_iter().map(str::to_string).collect();
+        match backend.list_tools().await {
+            Ok(tools) => known.extend(tools.into_iter().map(|t| t.name)),
+            Err(e) => tracing::debug!("backend list_tools error: {e}"),
+        }
+        // Don't point at tools the policy would refuse anyway.
+        known.retain(|tool| self.policy.allows_tool(tool));
+        known.extend(self.user_tools.read().await.keys().cloned());
+        known.extend(aliases);
+
+        match command_suggestion(name, known.iter().map(String::as_str)) {
+            Some(hint) => ExecResult::not_found(format!("command not found: {name} ({hint})")),
+            None => ExecResult::not_found(format!("command not found: {name}")),
+        }
+    }
+
+    /// Try to run `name` as a tool registered by the backend (embedder
+    /// engines, MCP proxies).
+    ///
+    /// Returns `Ok(None)` when the backend has no such tool.
+    async fn try_execute_backend_tool(&self, name: &str, args: &[Arg]) -> Result<Option<ExecResult>> {
+        // Look up tool schema for positional→named mapping.
+        // Clone backend and drop read lock before awaiting (may involve network I/O).
+        // Backend tools expect named JSON params, so enable positional mapping.
+        let backend = self.exec_ctx.read().await.backend.clone();
+        let tool_schema = backend
+            .get_tool(name)
+            .await
+            .unwrap_or_else(|e| {
+                // Schema lookup failing just means positionals won't
+                // get name-mapped below — `call_tool` is still
+                // attempted. Trace it so the degradation is visible
+                // rather than silently swallowed.
+                tracing::debug!("backend get_tool error for {name}: {e}");
+                None
+            })
+            .map(|t| {
+            let mut s = t.schema;
+            // Flat backend/MCP tools expect named JSON params, so map
+            // bare positionals onto named params. Subcommand-aware tools
+            // route positionals through the subcommand path and declare
+            // map_positionals per leaf (kj keeps it false so it re-parses
+            // the argv with its own clap) — don't blanket-override them.
+            if s.subcommands.is_empty() {
+                s.map_positionals = true;
+            }
+            s
+        });
+        let (tool_args, audit_argv) = self.build_args_audited(args, tool_schema.as_ref()).await?;
+        let mut ctx = self.exec_ctx.write().await;
+        {
+            let scope = self.scope.read().await;
+            ctx.scope = scope.clone();
+        }
+        let backend = ctx.backend.clone();
+        let outcome = backend.call_tool(name, tool_args, &mut *ctx).await;
+        // Take the scope back out rather than cloning it: left in the
+        // long-lived exec_ctx, the snapshot would share the kernel scope's
+        // frames and make the next assignment copy on write.
+        let scope_after = std::mem::take(&mut ctx.scope);
+        match outcome {
+            Ok(tool_result) => {
+                let mut scope = self.scope.write().await;
+                *scope = scope_after;
+                // Preserve every field (data/content_type/baggage/latch,
+                // not just stdout text) — this is the embedder seam:
+                // `x=$(embedder_tool)` and structured iteration over
+                // its result depend on `.data` surviving the crossing
+                // back into the kernel.
+                let result = ExecResult::from(tool_result);
+                if let Some(argv) = audit_argv {
+                    self.audit(name, &argv, result.code);
+                }
+                Ok(Some(result))
+            }
+            // The backend confirms no such tool exists.
+            Err(BackendError::ToolNotFound(_)) => Ok(None),
+            Err(e) => {
+                // The tool was found (dispatch reached real
+                // execution) but running it failed — a genuine
+                // execution error, not "command not found". Surface
+                // it loudly instead of masking it as exit-127.
+                if let Some(argv) = audit_argv {
+                    self.audit(name, &argv, 1);
+                }
+                Ok(Some(ExecResult::failure(1, format!("{}: {}", name, e))))
+            }
+        }
+    }
+
     /// Execute the `source` / `.` command to include and run a script.
     ///
     /// Unlike regular tool execution, `source` executes in the CURRENT scope,
@@ -4265,16 +5440,31 @@ impl Kernel {
             }
         };
 
-        // Resolve path relative to cwd
-        let full_path = {
+        // Resolve path relative to cwd, normalized so `./lib.kai` and
+        // `lib.kai` are the same file to the cycle check below.
+        let (full_path, sourcing) = {
             let ctx = self.exec_ctx.read().await;
-            if path.starts_with('/') {
-                std::path::PathBuf::from(&path)
-            } else {
-                ctx.cwd.join(&path)
-            }
+            (ctx.resolve_path(&path), ctx.sourcing.clone())
         };
 
+        // Refuse an include cycle or a runaway chain before reading anything;
+        // the message shows the chain that led here.
+        let cycle = sourcing.contains(&full_path);
+        if cycle || sourcing.len() >= MAX_SOURCE_DEPTH {
+            let chain = sourcing
+                .iter()
+                .chain(std::iter::once(&full_path))
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            let msg = if cycle {
+                format!("source: {path}: already being sourced ({chain})")
+            } else {
+                format!("source: {path}: files nested deeper than {MAX_SOURCE_DEPTH} ({chain})")
+            };
+            return Ok(ExecResult::failure(1, msg));
+        }
+
         // Read file content via backend
         let content = {
             let ctx = self.exec_ctx.read().await;
@@ -4299,17 +5489,26 @@ impl Kernel {
             Err(errors) => {
                 let msg = errors
                     .iter()
-                    .map(|e| format!("{}:{}: {}", path, e.span.start, e.message))
+                    .map(|e| format!("source: {}:{}", path, e.format(&content)))
                     .collect::<Vec<_>>()
                     .join("\n");
-                return Ok(ExecResult::failure(1, format!("source: {}", msg)));
+                return Ok(ExecResult::failure(1, msg));
             }
         };
 
-        // Execute each statement in the CURRENT scope (not isolated), accumulating
-        // stdout/stderr across statements like `execute_user_tool` — a sourced
-        // script's earlier statements must not be silently dropped in favor of
-        // just the last one.
+        self.exec_ctx.write().await.sourcing.push(full_path);
+        let result = self.execute_sourced(&path, program).await;
+        self.exec_ctx.write().await.sourcing.pop();
+        result
+    }
+
+    /// Run the statements of a sourced file for [`Self::execute_source`].
+    ///
+    /// Each statement executes in the CURRENT scope (not isolated), accumulating
+    /// stdout/stderr across statements like `execute_user_tool` — a sourced
+    /// script's earlier statements must not be silently dropped in favor of
+    /// just the last one.
+    async fn execute_sourced(&self, path: &str, program: crate::ast::Program) -> Result<ExecResult> {
         fn push_out(buf: &mut Vec<u8>, r: &ExecResult) {
             match r.out_bytes() {
                 Some(b) => buf.extend_from_slice(b),
@@ -4319,6 +5518,7 @@ impl Kernel {
 
         let mut accumulated_out: Vec<u8> = Vec::new();
         let mut accumulated_err = String::new();
+        let mut accumulated_stderr = String::new();
         let mut last_code = 0i64;
         let mut last_data: Option<Value> = None;
 
@@ -4334,12 +5534,13 @@ impl Kernel {
                         receiver.drain_lossy()
                     };
                     if !drained.is_empty() {
-                        accumulated_err.push_str(&drained);
+                        accumulated_stderr.push_str(&drained);
                     }
                     match flow {
                         ControlFlow::Normal(r) => {
                             push_out(&mut accumulated_out, &r);
                             accumulated_err.push_str(&r.err);
+                            accumulated_stderr.push_str(&r.stderr);
                             last_code = r.code;
                             last_data = r.data.clone();
                             self.update_last_result(&r).await;
@@ -4353,9 +5554,11 @@ impl Kernel {
                         ControlFlow::Return { value } => {
                             push_out(&mut accumulated_out, &value);
                             accumulated_err.push_str(&value.err);
+                            accumulated_stderr.push_str(&value.stderr);
                             let mut result = ExecResult::success_text_or_bytes(accumulated_out)
                                 .with_code(value.code);
                             result.err = accumulated_err;
+                            result.stderr = accumulated_stderr;
                             result.data = value.data;
                             return Ok(result);
                         }
@@ -4363,6 +5566,7 @@ impl Kernel {
                             let mut result =
                                 ExecResult::success_text_or_bytes(accumulated_out).with_code(code);
                             result.err = accumulated_err;
+                            result.stderr = accumulated_stderr;
                             result.data = last_data;
                             return Ok(result);
                         }
@@ -4376,10 +5580,48 @@ impl Kernel {
 
         let mut result = ExecResult::success_text_or_bytes(accumulated_out).with_code(last_code);
         result.err = accumulated_err;
+        result.stderr = accumulated_stderr;
         result.data = last_data;
         Ok(result)
     }
 
+    /// Execute `import PATH as NAME`: register the module's function
+    /// definitions as `NAME.function` (see [`crate::module`]), replacing
+    /// whatever the namespace held before.
+    async fn execute_import(&self, args: &[Arg]) -> Result<ExecResult> {
+        let tool_args = self.build_args_async(args, None).await?;
+        let words: Vec<String> = tool_args.positional.iter().map(value_to_string).collect();
+        let (path, namespace) = match crate::module::import_args(&words) {
+            Ok(parsed) => parsed,
+            Err(msg) => return Ok(ExecResult::failure(2, msg)),
+        };
+
+        let (full_path, backend) = {
+            let ctx = self.exec_ctx.read().await;
+            (ctx.resolve_path(path), ctx.backend.clone())
+        };
+        let source = match backend.read(&full_path, None).await {
+            Ok(bytes) => match String::from_utf8(bytes) {
+                Ok(source) => source,
+                Err(e) => {
+                    return Ok(ExecResult::failure(1, format!("import: {path}: invalid UTF-8: {e}")));
+                }
+            },
+            Err(e) => return Ok(ExecResult::failure(1, format!("import: {path}: {e}"))),
+        };
+        let defs = match crate::tool_library::tool_defs(&source, &full_path) {
+            Ok(defs) => crate::module::qualify(defs, namespace),
+            Err(e) => return Ok(ExecResult::failure(1, format!("import: {e}"))),
+        };
+
+        let mut user_tools = self.user_tools.write().await;
+        user_tools.retain(|_, def| def.module.as_deref() != Some(namespace));
+        for def in defs {
+            user_tools.insert(def.name.clone(), def);
+        }
+        Ok(ExecResult::success(""))
+    }
+
     /// Try to execute a script from PATH directories.
     ///
     /// Searches PATH for `{name}.kai` files and executes them in isolated scope
@@ -4447,7 +5689,7 @@ impl Kernel {
                 Err(errors) => {
                     let msg = errors
                         .iter()
-                        .map(|e| format!("{}:{}: {}", script_path.display(), e.span.start, e.message))
+                        .map(|e| format!("{}:{}", script_path.display(), e.format(&content)))
                         .collect::<Vec<_>>()
                         .join("\n");
                     return Ok(Some(ExecResult::failure(1, msg)));
@@ -4465,6 +5707,7 @@ impl Kernel {
                 .iter()
                 .map(value_to_string)
                 .collect();
+            let audit_argv = self.audit_log.as_ref().map(|_| positional_args.clone());
             isolated_scope.set_positional(name, positional_args);
 
             // Save current scope and swap with isolated scope
@@ -4485,6 +5728,7 @@ impl Kernel {
 
             let mut accumulated_out: Vec<u8> = Vec::new();
             let mut accumulated_err = String::new();
+            let mut accumulated_stderr = String::new();
             let mut last_code = 0i64;
             let mut last_data: Option<Value> = None;
             let mut exec_error: Option<anyhow::Error> = None;
@@ -4502,18 +5746,20 @@ impl Kernel {
                             receiver.drain_lossy()
                         };
                         if !drained.is_empty() {
-                            accumulated_err.push_str(&drained);
+                            accumulated_stderr.push_str(&drained);
                         }
                         match flow {
                             ControlFlow::Normal(r) => {
                                 push_out(&mut accumulated_out, &r);
                                 accumulated_err.push_str(&r.err);
+                                accumulated_stderr.push_str(&r.stderr);
                                 last_code = r.code;
                                 last_data = r.data;
                             }
                             ControlFlow::Return { value } => {
                                 push_out(&mut accumulated_out, &value);
                                 accumulated_err.push_str(&value.err);
+                                accumulated_stderr.push_str(&value.stderr);
                                 last_code = value.code;
                                 last_data = value.data;
                                 break;
@@ -4525,6 +5771,7 @@ impl Kernel {
                             ControlFlow::Break { result: r, .. } | ControlFlow::Continue { result: r, .. } => {
                                 push_out(&mut accumulated_out, &r);
                                 accumulated_err.push_str(&r.err);
+                                accumulated_stderr.push_str(&r.stderr);
                                 last_code = r.code;
                                 last_data = r.data;
                             }
@@ -4550,7 +5797,11 @@ impl Kernel {
             let code = exit_code.unwrap_or(last_code);
             let mut result = ExecResult::success_text_or_bytes(accumulated_out).with_code(code);
             result.err = accumulated_err;
+            result.stderr = accumulated_stderr;
             result.data = last_data;
+            if let Some(argv) = audit_argv {
+                self.audit(name, &argv, result.code);
+            }
             return Ok(Some(result));
         }
 
@@ -4572,14 +5823,28 @@ impl Kernel {
     /// - `Ok(None)` if command was not found in PATH
     /// - `Err` on execution errors
     #[cfg(not(feature = "subprocess"))]
-    async fn try_execute_external(&self, _name: &str, _args: &[Arg]) -> Result<Option<ExecResult>> {
+    async fn try_execute_external(
+        &self,
+        _name: &str,
+        _args: &[Arg],
+        _audit_argv: &mut Option<Vec<String>>,
+    ) -> Result<Option<ExecResult>> {
         Ok(None)
     }
 
     /// Try to execute an external command from PATH.
+    ///
+    /// With an audit log configured, the evaluated argv is handed back through
+    /// `audit_argv` once built, so the caller can record the command however
+    /// this returns.
     #[cfg(feature = "subprocess")]
-    #[tracing::instrument(level = "debug", skip(self, args), fields(command = %name))]
-    async fn try_execute_external(&self, name: &str, args: &[Arg]) -> Result<Option<ExecResult>> {
+    #[tracing::instrument(level = "debug", skip(self, args, audit_argv), fields(command = %name))]
+    async fn try_execute_external(
+        &self,
+        name: &str,
+        args: &[Arg],
+        audit_argv: &mut Option<Vec<String>>,
+    ) -> Result<Option<ExecResult>> {
         // Read the cancel token from `self.exec_ctx`, which `dispatch_command`
         // populates from the inbound ctx.cancel on every dispatch. This is
         // what makes the `timeout` builtin's swapped child token reach the
@@ -4622,16 +5887,10 @@ impl Kernel {
                 }
             };
             if !resolved.exists() {
-                return Ok(Some(ExecResult::failure(
-                    127,
-                    format!("{}: No such file or directory", name),
-                )));
+                return Ok(Some(ExecResult::not_found(format!("{}: No such file or directory", name))));
             }
             if !resolved.is_file() {
-                return Ok(Some(ExecResult::failure(
-                    126,
-                    format!("{}: Is a directory", name),
-                )));
+                return Ok(Some(ExecResult::not_executable(format!("{}: Is a directory", name))));
             }
             #[cfg(unix)]
             {
@@ -4640,10 +5899,7 @@ impl Kernel {
                     .map(|m| m.permissions().mode())
                     .unwrap_or(0);
                 if mode & 0o111 == 0 {
-                    return Ok(Some(ExecResult::failure(
-                        126,
-                        format!("{}: Permission denied", name),
-                    )));
+                    return Ok(Some(ExecResult::not_executable(format!("{}: Permission denied", name))));
                 }
             }
             resolved.to_string_lossy().into_owned()
@@ -4675,6 +5931,9 @@ impl Kernel {
 
         // Build flat argv (preserves flag format)
         let argv = self.build_args_flat(args).await?;
+        if self.audit_log.is_some() {
+            *audit_argv = Some(argv.clone());
+        }
 
         // Get stdin sources: a streaming `pipe_stdin` (an inter-stage pipeline
         // pipe, or a frontend-seeded process-stdin pipe) and/or a buffered
@@ -4805,10 +6064,7 @@ impl Kernel {
         let mut child = match cmd.spawn() {
             Ok(child) => child,
             Err(e) => {
-                return Ok(Some(ExecResult::failure(
-                    127,
-                    format!("{}: {}", name, e),
-                )));
+                return Ok(Some(ExecResult::not_found(format!("{}: {}", name, e))));
             }
         };
         let kill_target = crate::pidfd::KillTarget::from_child(&child);
@@ -5029,9 +6285,36 @@ impl Kernel {
             let stdout_clone = stdout_stream.clone();
             let stderr_clone = stderr_stream.clone();
 
-            let stdout_task = stdout_pipe.map(|pipe| {
+            // A first/middle pipeline stage streams the child's stdout straight
+            // into the next stage's pipe rather than capturing it whole: the
+            // bounded pipe backpressures the child, and once the reader goes
+            // away (`yes | head -n 5`) the copy stops and drops the child's
+            // stdout, so its next write takes SIGPIPE like it would under bash.
+            // Taking the writer out of `exec_ctx` tells the runner there is
+            // nothing left for it to forward.
+            let pipe_stdout = self.exec_ctx.write().await.pipe_stdout.take();
+
+            let stdout_task = stdout_pipe.map(|mut pipe| {
                 tokio::spawn(async move {
-                    drain_to_stream(pipe, stdout_clone).await;
+                    let Some(mut pipe_out) = pipe_stdout else {
+                        drain_to_stream(pipe, stdout_clone).await;
+                        return;
+                    };
+                    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+                    let mut buf = [0u8; 8192];
+                    loop {
+                        match pipe.read(&mut buf).await {
+                            Ok(0) => break, // EOF
+                            Ok(n) => {
+                                if pipe_out.write_all(&buf[..n]).await.is_err() {
+                                    break; // broken pipe: downstream is done
+                                }
+                            }
+                            Err(_) => break,
+                        }
+                    }
+                    let _ = pipe_out.shutdown().await;
+                    // Dropping `pipe` closes the child's stdout.
                 })
             });
 
@@ -5107,7 +6390,7 @@ impl Kernel {
                 stderr = format!("{}{stderr}", stats.overflow_marker("stdout"));
                 result.did_spill = true;
             }
-            result.err = stderr;
+            result.stderr = stderr;
             Ok(Some(result))
         }
     }
@@ -5129,6 +6412,7 @@ impl Kernel {
 
     /// Set a variable value.
     pub async fn set_var(&self, name: &str, value: Value) {
+        self.hooks.var_change(name, &value);
         let mut scope = self.scope.write().await;
         scope.set(name.to_string(), value);
     }
@@ -5152,6 +6436,101 @@ impl Kernel {
         scope.exported_vars()
     }
 
+    // --- Checkpoints ---
+
+    /// Snapshot the session's variables and cwd (see [`crate::checkpoint`]).
+    /// Returns the checkpoint id to pass to [`Self::restore_checkpoint`].
+    pub async fn create_checkpoint(&self, label: Option<&str>) -> u64 {
+        let scope = self.scope.read().await;
+        let ctx = self.exec_ctx.read().await;
+        ctx.checkpoints.create(label.map(String::from), &scope, &ctx.cwd, ctx.history.mark())
+    }
+
+    /// Roll the session back to checkpoint `id`: its variables replace the
+    /// current ones and the cwd returns to where it was. Errors if no such
+    /// checkpoint exists.
+    pub async fn restore_checkpoint(&self, id: u64) -> Result<()> {
+        let checkpoint = self
+            .exec_ctx
+            .read()
+            .await
+            .checkpoints
+            .get(id)
+            .ok_or_else(|| anyhow::anyhow!("no checkpoint with id {id}"))?;
+        checkpoint.restore_into(&mut *self.scope.write().await);
+        self.exec_ctx.write().await.set_cwd(checkpoint.cwd);
+        Ok(())
+    }
+
+    /// All checkpoints taken in this session, oldest first.
+    pub async fn checkpoints(&self) -> Vec<crate::checkpoint::Checkpoint> {
+        self.exec_ctx.read().await.checkpoints.list()
+    }
+
+    /// The requests this session has run, oldest first (see
+    /// [`crate::history`]).
+    pub async fn history(&self) -> Vec<crate::history::HistoryEntry> {
+        self.exec_ctx.read().await.history.entries()
+    }
+
+    /// A kaish script distilled from the requests since a checkpoint (see
+    /// [`crate::distill`]): `since` names one by id or label, `None` means
+    /// the newest checkpoint, or the whole history if there is none. Errors
+    /// if `since` names no checkpoint.
+    pub async fn distill(&self, since: Option<&str>, options: &crate::distill::DistillOptions) -> Result<String> {
+        let ctx = self.exec_ctx.read().await;
+        let entries = crate::distill::history_since(&ctx.checkpoints, &ctx.history, since)
+            .map_err(|e| anyhow::anyhow!(e))?;
+        Ok(crate::distill::distill(&entries, options))
+    }
+
+    // --- Metrics ---
+
+    /// Command counts and timings, filesystem operations per mount, and job
+    /// statistics collected so far (see [`crate::metrics`]).
+    pub async fn metrics(&self) -> crate::metrics::MetricsSnapshot {
+        self.metrics.snapshot().await
+    }
+
+    /// Zero the command and filesystem metrics.
+    pub fn reset_metrics(&self) {
+        self.metrics.reset();
+    }
+
+    // --- Result cache ---
+
+    /// Commands currently answered from the result cache (see
+    /// [`crate::result_cache`]), soonest to expire first.
+    pub async fn result_cache_entries(&self) -> Vec<crate::result_cache::CacheEntryInfo> {
+        self.exec_ctx.read().await.result_cache.entries()
+    }
+
+    /// Drop every cached result. Returns how many entries were dropped.
+    pub async fn clear_result_cache(&self) -> usize {
+        self.exec_ctx.read().await.result_cache.clear()
+    }
+
+    // --- State export/import ---
+
+    /// Capture the session as a portable [`KernelState`](crate::state::KernelState):
+    /// variables, cwd, checkpoints, and the mount table.
+    pub async fn export_state(&self) -> crate::state::KernelState {
+        let scope = self.scope.read().await;
+        let ctx = self.exec_ctx.read().await;
+        crate::state::KernelState::capture(&scope, &ctx.cwd, &ctx.backend.mounts(), &ctx.checkpoints)
+    }
+
+    /// Replace the session's variables, cwd, and checkpoints with `state`.
+    /// Mounts aren't recreated; the mount points `state` names that this
+    /// kernel lacks are returned so the caller can warn.
+    pub async fn import_state(&self, state: &crate::state::KernelState) -> Vec<PathBuf> {
+        let mut scope = self.scope.write().await;
+        let mut ctx = self.exec_ctx.write().await;
+        state.restore_into(&mut scope, &ctx.checkpoints);
+        ctx.set_cwd(state.cwd.clone());
+        state.missing_mounts(&ctx.backend.mounts())
+    }
+
     // --- CWD ---
 
     /// Get current working directory.
@@ -5240,7 +6619,7 @@ impl Kernel {
             if crate::validator::is_runtime_special_form(&name) {
                 return CommandKind::Special;
             }
-            if alias_depth >= 10 {
+            if alias_depth >= 10 || crate::validator::CommandNamespace::split(&name).is_some() {
                 break;
             }
             let alias_value = {
@@ -5348,6 +6727,7 @@ impl Kernel {
             let mut ec = self.exec_ctx.write().await;
             ec.cwd = ctx.cwd.clone();
             ec.prev_cwd = ctx.prev_cwd.clone();
+            ec.dir_stack = ctx.dir_stack.clone();
             ec.stdin = ctx.stdin.take();
             ec.stdin_data = ctx.stdin_data.take();
             // The structured-data sideband receiver (set by the concurrent
@@ -5378,6 +6758,10 @@ impl Kernel {
             // own kernel must hand the shared script clock to the snapshot so
             // patient holds in forked stages suspend the right timer.
             ec.watchdog = ctx.watchdog.clone();
+            // And the call depth, so a function reached through a pipeline
+            // stage counts the functions that spawned the pipeline.
+            ec.call_depth = ctx.call_depth;
+            ec.sourcing.clone_from(&ctx.sourcing);
         }
 
         // 2. Execute via the full dispatch chain
@@ -5392,6 +6776,7 @@ impl Kernel {
             let mut ec = self.exec_ctx.write().await;
             ctx.cwd = ec.cwd.clone();
             ctx.prev_cwd = ec.prev_cwd.clone();
+            ctx.dir_stack = ec.dir_stack.clone();
             ctx.aliases = ec.aliases.clone();
             ctx.ignore_config = ec.ignore_config.clone();
             ctx.output_limit = ec.output_limit.clone();
@@ -5488,6 +6873,85 @@ impl ArgValueSource for Kernel {
     }
 }
 
+/// An [`ArgValueSource`] that remembers what each argument expression
+/// evaluated to, so the audit log can lay the argv back out in source order
+/// after [`bind_tool_args`] has split it into flags and positionals.
+/// Expressions are keyed by address: `bind_tool_args` only ever evaluates
+/// references into the `args` slice it was handed.
+struct RecordingSource<'a> {
+    inner: &'a dyn ArgValueSource,
+    values: std::sync::Mutex<HashMap<usize, String>>,
+    globs: std::sync::Mutex<HashMap<String, Vec<String>>>,
+}
+
+impl<'a> RecordingSource<'a> {
+    fn new(inner: &'a dyn ArgValueSource) -> Self {
+        Self { inner, values: Default::default(), globs: Default::default() }
+    }
+
+    fn value_of(&self, expr: &Expr) -> Option<String> {
+        let values = self.values.lock().unwrap_or_else(|e| e.into_inner());
+        values.get(&(expr as *const Expr as usize)).cloned()
+    }
+
+    /// The argv as written, with evaluated values. An argument the binder
+    /// never evaluated (an unset variable it dropped) is left out, as it was
+    /// from the command's own argv.
+    fn source_order_argv(&self, args: &[Arg]) -> Vec<String> {
+        let globs = self.globs.lock().unwrap_or_else(|e| e.into_inner());
+        let mut argv = Vec::with_capacity(args.len());
+        for arg in args {
+            match arg {
+                Arg::Positional(expr) => {
+                    let expanded = match expr {
+                        Expr::GlobPattern(pattern) => globs.get(pattern),
+                        _ => None,
+                    };
+                    match expanded {
+                        Some(paths) => argv.extend(paths.iter().cloned()),
+                        None => argv.extend(self.value_of(expr)),
+                    }
+                }
+                Arg::Named { key, value } => {
+                    argv.extend(self.value_of(value).map(|v| format!("--{key}={v}")));
+                }
+                Arg::WordAssign { key, value } => {
+                    argv.extend(self.value_of(value).map(|v| format!("{key}={v}")));
+                }
+                Arg::ShortFlag(name) => argv.push(format!("-{name}")),
+                Arg::LongFlag(name) => argv.push(format!("--{name}")),
+                Arg::DoubleDash => argv.push("--".to_string()),
+            }
+        }
+        argv
+    }
+}
+
+#[async_trait]
+impl ArgValueSource for RecordingSource<'_> {
+    async fn eval(&self, expr: &Expr) -> Result<Option<Value>> {
+        let value = self.inner.eval(expr).await?;
+        if let Some(v) = &value {
+            let mut values = self.values.lock().unwrap_or_else(|e| e.into_inner());
+            values.insert(expr as *const Expr as usize, value_to_string(v));
+        }
+        Ok(value)
+    }
+
+    async fn expand_glob(&self, pattern: &str) -> Result<Option<Vec<String>>> {
+        let paths = self.inner.expand_glob(pattern).await?;
+        if let Some(paths) = &paths {
+            let mut globs = self.globs.lock().unwrap_or_else(|e| e.into_inner());
+            globs.insert(pattern.to_string(), paths.clone());
+        }
+        Ok(paths)
+    }
+
+    async fn home(&self) -> Option<String> {
+        self.inner.home().await
+    }
+}
+
 /// Pull `consumes` positional args after a non-bool flag and stash them on
 /// `tool_args.named` under the canonical param name. Shared core behind
 /// [`bind_tool_args`]'s `ShortFlag`/`LongFlag` value-flag arms — see that
@@ -6214,6 +7678,16 @@ impl CommandDispatcher for Kernel {
         let fork: Arc<Kernel> = Kernel::fork_attached(self).await;
         fork
     }
+
+    /// Whether this kernel has a user-defined function named `name`.
+    async fn has_function(&self, name: &str) -> bool {
+        Kernel::has_function(self, name).await
+    }
+
+    /// Re-scan the configured tool library into this kernel's user tools.
+    async fn reload_tool_library(&self) -> crate::tool_library::ToolLibraryReport {
+        self.load_tool_library().await
+    }
 }
 
 /// Apply the requested output format to a builtin's result, unless the tool
@@ -6242,6 +7716,45 @@ fn finalize_output(
     }
 }
 
+/// The result for a call the tool rejected and [`UsageError`] explains:
+/// exit 2 with the readable message on stderr and the same facts as `.data`
+/// (`tool`, `problems` with `message`/`hint`, `usage`, `parameters`).
+fn usage_error_result(usage: &UsageError) -> ExecResult {
+    let problems: Vec<serde_json::Value> = usage
+        .problems
+        .iter()
+        .map(|p| serde_json::json!({"message": p.message(), "hint": p.hint()}))
+        .collect();
+    let mut result = ExecResult::usage(usage.message());
+    result.data = Some(Value::Json(serde_json::json!({
+        "tool": usage.tool,
+        "problems": problems,
+        "usage": usage.usage,
+        "parameters": usage.params,
+    })));
+    result
+}
+
+/// The `.data` of a successful result as `T`, for [`Kernel::execute_typed`].
+fn typed_data<T: serde::de::DeserializeOwned>(result: &ExecResult) -> Result<T> {
+    if !result.ok() {
+        anyhow::bail!("command failed (exit {}): {}", result.code, result.stderr_text());
+    }
+    let Some(data) = &result.data else {
+        anyhow::bail!("command produced no structured data (add --json to get it)");
+    };
+    serde_json::from_value(crate::interpreter::value_to_json(data))
+        .context("structured data does not match the requested type")
+}
+
+/// Add a hook handler's output to the result of the statement that raised
+/// the event, leaving that result's status and error message alone.
+fn append_hook_output(result: &mut ExecResult, handler: &ExecResult) {
+    result.materialize();
+    result.push_out(&handler.text_out());
+    result.push_stderr(&handler.stderr_text());
+}
+
 /// Accumulate output from one result into another.
 ///
 /// Appends stdout and stderr verbatim and updates the exit code to match the
@@ -6270,6 +7783,7 @@ fn accumulate_result(accumulated: &mut ExecResult, new: &ExecResult) {
         None => accumulated.push_out(&new.text_out()),
     }
     accumulated.err.push_str(&new.err);
+    accumulated.stderr.push_str(&new.stderr);
     accumulated.code = new.code;
     accumulated.data = new.data.clone();
     accumulated.did_spill = new.did_spill;
@@ -6361,7 +7875,7 @@ pub(crate) fn argv_to_args(argv: &[Value]) -> Vec<Arg> {
     argv.iter().map(classify_argv_token).collect()
 }
 
-fn classify_argv_token(token: &Value) -> Arg {
+pub(crate) fn classify_argv_token(token: &Value) -> Arg {
     let Value::String(s) = token else {
         return Arg::Positional(Expr::Literal(token.clone()));
     };
@@ -7796,6 +9310,62 @@ AFTER="yes"'"#)
         // Currently our implementation checks per-statement in the main kernel
     }
 
+    #[tokio::test]
+    async fn test_source_nested_libraries() {
+        let kernel = Kernel::transient().expect("failed to create kernel");
+        kernel
+            .execute(r#"mkdir -p /scripts; write "/scripts/a.kai" 'source /scripts/b.kai; LOADED="yes"'; write "/scripts/b.kai" 'greet() { echo "hi $1"; }'"#)
+            .await
+            .expect("write failed");
+
+        // Relative paths resolve against the cwd, and the same file may be
+        // sourced again once the first include has finished.
+        let result = kernel
+            .execute("cd /scripts; source a.kai; source ./a.kai; greet bob")
+            .await
+            .expect("source failed");
+        assert!(result.ok(), "{result:?}");
+        assert_eq!(result.text_out(), "hi bob\n");
+        assert_eq!(kernel.get_var("LOADED").await, Some(Value::String("yes".into())));
+    }
+
+    #[tokio::test]
+    async fn test_source_cycle_is_refused() {
+        let kernel = Kernel::transient().expect("failed to create kernel");
+        kernel
+            .execute(r#"write "/a.kai" 'source /b.kai'; write "/b.kai" 'source /a.kai'"#)
+            .await
+            .expect("write failed");
+
+        let result = kernel.execute("source /a.kai").await.expect("source ran");
+        assert!(!result.ok(), "{result:?}");
+        assert!(
+            result.err.contains("already being sourced (/a.kai -> /b.kai -> /a.kai)"),
+            "{result:?}"
+        );
+        // The include stack unwound: the file can be sourced again.
+        kernel.execute(r#"write "/b.kai" 'B=1'"#).await.expect("write failed");
+        assert!(kernel.execute("source /a.kai").await.expect("source ran").ok());
+    }
+
+    #[tokio::test]
+    async fn test_source_depth_is_capped() {
+        let kernel = Kernel::transient().expect("failed to create kernel");
+        for i in 0..=MAX_SOURCE_DEPTH {
+            kernel
+                .execute(&format!(r#"write "/s{i}.kai" 'source /s{}.kai'"#, i + 1))
+                .await
+                .expect("write failed");
+        }
+
+        let result = kernel.execute("source /s0.kai").await.expect("source ran");
+        assert!(!result.ok(), "{result:?}");
+        assert!(
+            result.err.contains(&format!("files nested deeper than {MAX_SOURCE_DEPTH}")),
+            "{result:?}"
+        );
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // set -e with && / || chains
     // ═══════════════════════════════════════════════════════════════════════════
@@ -9238,7 +10808,7 @@ AFTER="yes"'"#)
         let schema = kj_tree_schema();
         // kj $(echo context) — routing can't see the value; fail loud.
         let args = vec![Arg::Positional(Expr::CommandSubst(vec![Stmt::Command(
-            crate::ast::Command { name: "echo".into(), args: vec![], redirects: vec![] },
+            crate::ast::Command { name: "echo".into(), args: vec![], redirects: vec![], span: None },
         )]))];
         let err = kernel
             .build_args_async(&args, Some(&schema))
@@ -9504,7 +11074,7 @@ AFTER="yes"'"#)
     #[tokio::test]
     async fn test_classify_command_special_forms() {
         let kernel = Kernel::transient().expect("failed to create kernel");
-        for name in ["true", "false", "source", "."] {
+        for name in ["true", "false", "source", ".", "import"] {
             assert_eq!(
                 kernel.classify_command(name).await,
                 CommandKind::Special,
@@ -9623,7 +11193,7 @@ AFTER="yes"'"#)
         // `true`/`false`): an external miss in this PATH-less kernel would be exit
         // 127, so a non-127 result that matches the form's own behavior proves the
         // short-circuit fired.
-        for name in ["true", "false", "source", "."] {
+        for name in ["true", "false", "source", ".", "import"] {
             assert_eq!(
                 kernel.classify_command(name).await,
                 CommandKind::Special,
@@ -9643,6 +11213,9 @@ AFTER="yes"'"#)
                 r.err,
             );
         }
+        let r = kernel.execute("import").await.expect("run import");
+        assert_eq!(r.code, 2, "import fell through instead of execute_import: {r:?}");
+        assert!(r.err.contains("import: usage"), "{r:?}");
 
         // (2) Builtin: classify Builtin AND the executor runs the builtin.
         assert_eq!(kernel.classify_command("echo").await, CommandKind::Builtin);
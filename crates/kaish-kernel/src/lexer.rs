@@ -2256,6 +2256,11 @@ fn compute_value_context(tokens: &[Spanned<Token>]) -> Vec<ValueContext> {
     // to enclosing scopes, frozen while this scope is active).
     let mut scope_floors: Vec<usize> = vec![0];
 
+    // Conditional values (`x=if c then [a] else [b] fi`) open in each
+    // scope, parallel to `scopes`: while one is open, `then` and `else`
+    // put the next token at value position, as `=` does.
+    let mut value_ifs: Vec<usize> = vec![0];
+
     // Independent `push`-target tracker — see `PushTarget`. Flat (not
     // scope-stacked like `scopes`/`frames`): a `push` inside `$( )` still
     // gets detected via the `StmtHead::Start` check below (scoped
@@ -2422,6 +2427,7 @@ fn compute_value_context(tokens: &[Spanned<Token>]) -> Vec<ValueContext> {
                 frames.push(Frame::Subst);
                 scope_floors.push(frames.len());
                 scopes.push(StmtHead::Start);
+                value_ifs.push(0);
             }
             Token::LParen => {
                 frames.push(Frame::Paren);
@@ -2436,9 +2442,13 @@ fn compute_value_context(tokens: &[Spanned<Token>]) -> Vec<ValueContext> {
                             frames.pop();
                             scope_floors.pop();
                             scopes.pop();
+                            value_ifs.pop();
                             if scopes.is_empty() {
                                 scopes.push(StmtHead::Start);
                             }
+                            if value_ifs.is_empty() {
+                                value_ifs.push(0);
+                            }
                             if scope_floors.is_empty() {
                                 scope_floors.push(0);
                             }
@@ -2489,6 +2499,17 @@ fn compute_value_context(tokens: &[Spanned<Token>]) -> Vec<ValueContext> {
                 // `[[ ]]` grammar is single-line).
                 *scopes.last_mut().unwrap_or_else(|| unreachable!("scopes never empty")) =
                     StmtHead::Start;
+                let open_ifs = value_ifs.last_mut().unwrap_or_else(|| unreachable!("value_ifs never empty"));
+                match t {
+                    Token::If if opens_value => *open_ifs += 1,
+                    Token::Then | Token::Else if *open_ifs > 0 => {
+                        expect_value = true;
+                        *scopes.last_mut().unwrap_or_else(|| unreachable!("scopes never empty")) =
+                            StmtHead::Value;
+                    }
+                    Token::Fi if *open_ifs > 0 => *open_ifs -= 1,
+                    _ => {}
+                }
                 match t {
                     Token::Newline => {
                         while frames.len() > floor && frames.last() == Some(&Frame::Test) {
@@ -2913,17 +2934,6 @@ fn tokenize_impl(
 ) -> Result<Vec<Spanned<Token>>, Vec<Spanned<LexerError>>> {
     let scan_output = scan(source).map_err(|e| vec![e])?;
 
-    // map_position's early `break` depends on the table being ordered by
-    // rewritten-buffer position; the scanner appends in scan order, which
-    // guarantees it.
-    debug_assert!(
-        scan_output
-            .replacements
-            .windows(2)
-            .all(|w| w[0].new_start <= w[1].new_start),
-        "replacement table must be ordered by new_start"
-    );
-
     let mut tokens = Vec::new();
     let mut errors = Vec::new();
     for (result, span) in Token::lexer(&scan_output.text).spanned() {
@@ -2947,7 +2957,28 @@ fn tokenize_impl(
         return Err(errors);
     }
 
-    let resolved = resolve_markers(tokens, &scan_output).map_err(|errs| {
+    finish_tokens(tokens, &scan_output, source)
+}
+
+/// The stages after logos, for a run of tokens in rewritten-buffer
+/// coordinates: marker resolution, span correction, and fusion.
+fn finish_tokens(
+    tokens: Vec<Spanned<Token>>,
+    scan_output: &ScanOutput,
+    source: &str,
+) -> Result<Vec<Spanned<Token>>, Vec<Spanned<LexerError>>> {
+    // map_position's early `break` depends on the table being ordered by
+    // rewritten-buffer position; the scanner appends in scan order, which
+    // guarantees it.
+    debug_assert!(
+        scan_output
+            .replacements
+            .windows(2)
+            .all(|w| w[0].new_start <= w[1].new_start),
+        "replacement table must be ordered by new_start"
+    );
+
+    let resolved = resolve_markers(tokens, scan_output).map_err(|errs| {
         errs.into_iter()
             .map(|e| Spanned::new(e.token, map_span(&e.span, &scan_output.replacements)))
             .collect::<Vec<_>>()
@@ -2967,6 +2998,105 @@ fn tokenize_impl(
     ))
 }
 
+/// Tokenize one top-level statement at a time.
+///
+/// Each item is the tokens of one top-level statement (a whole `if … fi`,
+/// function, or `try … catch` counts as one) with the newlines that end it,
+/// or that statement's lexer errors. Concatenated, the items are exactly
+/// what [`tokenize`] returns; the difference is that only one statement's
+/// tokens are ever held, so a multi-megabyte script costs memory in
+/// proportion to its largest statement rather than its length. The scan
+/// (heredoc and arithmetic extraction) still covers the whole source up
+/// front; a scan error is the only item.
+pub fn tokenize_statements(source: &str) -> StatementTokens<'_> {
+    let (scan, error) = match scan(source) {
+        Ok(scan) => (Some(scan), None),
+        Err(error) => (None, Some(error)),
+    };
+    StatementTokens { source, scan, error, pos: 0 }
+}
+
+/// The iterator returned by [`tokenize_statements`].
+pub struct StatementTokens<'a> {
+    source: &'a str,
+    scan: Option<ScanOutput>,
+    /// A scan error, reported as the first and only item.
+    error: Option<Spanned<LexerError>>,
+    /// Where the next statement starts, in rewritten-buffer coordinates.
+    pos: usize,
+}
+
+impl Iterator for StatementTokens<'_> {
+    type Item = Result<Vec<Spanned<Token>>, Vec<Spanned<LexerError>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(error) = self.error.take() {
+            return Some(Err(vec![error]));
+        }
+        let scan = self.scan.as_ref()?;
+
+        // The blocks the statement has opened, as `skip_statement` in the
+        // parser tracks them; a newline with none open ends the statement,
+        // and the first token after it that isn't a newline (or `catch`,
+        // which may follow a `try { … }` on the next line) starts the next
+        // one. logos restarts cleanly there: tokens never span a newline.
+        let mut open: Vec<Token> = Vec::new();
+        let mut ended = false;
+        let mut next_pos = scan.text.len();
+        let mut tokens = Vec::new();
+        let mut errors = Vec::new();
+        for (result, span) in Token::lexer(&scan.text[self.pos..]).spanned() {
+            let span = span.start + self.pos..span.end + self.pos;
+            let token = match result {
+                Ok(token) => token,
+                Err(err) => {
+                    errors.push(Spanned::new(err, map_span(&span, &scan.replacements)));
+                    continue;
+                }
+            };
+            match &token {
+                Token::Newline if open.is_empty() => ended = true,
+                Token::Newline | Token::Comment | Token::LineContinuation => {}
+                Token::Ident(word) if ended && word == "catch" => ended = false,
+                _ if ended => {
+                    next_pos = span.start;
+                    break;
+                }
+                Token::If => open.push(Token::Fi),
+                Token::For | Token::While => open.push(Token::Done),
+                Token::Case => open.push(Token::Esac),
+                Token::LBrace => open.push(Token::RBrace),
+                Token::LBracket => open.push(Token::RBracket),
+                Token::LParen | Token::CmdSubstStart => open.push(Token::RParen),
+                closer @ (Token::Fi
+                | Token::Done
+                | Token::Esac
+                | Token::RBrace
+                | Token::RBracket
+                | Token::RParen) => {
+                    if open.last() == Some(closer) {
+                        open.pop();
+                    }
+                }
+                _ => {}
+            }
+            if matches!(token, Token::Comment | Token::LineContinuation) {
+                continue;
+            }
+            tokens.push(Spanned::new(token, span));
+        }
+        self.pos = next_pos;
+
+        if !errors.is_empty() {
+            return Some(Err(errors));
+        }
+        if tokens.is_empty() {
+            return None;
+        }
+        Some(finish_tokens(tokens, scan, self.source))
+    }
+}
+
 /// Extract the string content from a string token (removes quotes, processes escapes).
 pub fn parse_string_literal(source: &str) -> Result<String, LexerError> {
     // Remove surrounding quotes
@@ -4665,4 +4795,35 @@ mod tests {
             assert!(!t.is_keyword(), "{t:?} should not be a keyword");
         }
     }
+
+    /// One of each kind of top-level statement a chunk boundary must not
+    /// split: blocks, a heredoc, `try` with `catch` on its own line, a
+    /// function, and `;`-separated commands.
+    const STATEMENTS: &str = "X=1\n\n# note\nif true; then\n  echo $((X + 1))\nfi\n\
+        cat <<EOF\nbody $X\nEOF\ntry {\n  false\n}\ncatch {\n  echo caught\n}\n\
+        f() {\n  echo \"in f\" | tr a-z A-Z\n}\necho a; echo b\n";
+
+    #[test]
+    fn statement_tokens_concatenate_to_tokenize() {
+        let chunks: Vec<Vec<Spanned<Token>>> = tokenize_statements(STATEMENTS)
+            .collect::<Result<_, _>>()
+            .expect("lexer should succeed");
+        assert_eq!(chunks.len(), 6, "{chunks:#?}");
+        assert_eq!(chunks.concat(), tokenize(STATEMENTS).expect("lexer should succeed"));
+        assert_eq!(chunks[3][0].token, Token::Ident("try".to_string()));
+        assert!(chunks[3].iter().any(|t| t.token == Token::Ident("catch".to_string())));
+    }
+
+    #[test]
+    fn statement_tokens_report_errors_where_they_happen() {
+        let mut chunks = tokenize_statements("echo ok\necho `date`\necho after\n");
+        assert!(matches!(chunks.next(), Some(Ok(tokens)) if tokens[0].token == Token::Ident("echo".to_string())));
+        assert!(matches!(chunks.next(), Some(Err(_))));
+        assert!(matches!(chunks.next(), Some(Ok(_))));
+        assert!(chunks.next().is_none());
+
+        let mut unterminated = tokenize_statements("cat <<EOF\nnever closed\n");
+        assert!(matches!(unterminated.next(), Some(Err(_))));
+        assert!(unterminated.next().is_none());
+    }
 }
@@ -13,24 +13,38 @@
 
 pub mod arithmetic;
 pub mod ast;
+pub mod audit;
 pub mod backend;
 pub(crate) mod backend_walker_fs;
+pub mod checkpoint;
 pub mod dispatch;
+pub mod distill;
 pub mod duration;
 pub mod help;
+pub mod history;
+pub mod hooks;
 pub mod ignore_config;
 pub mod interpreter;
 pub mod output_limit;
 pub mod kernel;
 pub mod lexer;
+pub mod metrics;
+pub mod module;
 pub mod nonce;
 pub mod parser;
 pub mod paths;
+pub mod policy;
+pub mod result_cache;
 #[cfg(all(unix, feature = "subprocess"))]
 pub mod pidfd;
 pub mod scheduler;
+pub mod script_log;
+pub mod state;
+pub mod state_store;
 pub(crate) mod telemetry;
+pub mod tool_library;
 pub mod tools;
+pub mod transcript;
 pub mod trash;
 #[cfg(feature = "os-integration")]
 pub mod trash_system;
@@ -58,17 +72,28 @@ pub mod walker {
     pub use crate::backend_walker_fs::BackendWalkerFs;
 }
 
+pub use audit::{AuditEntry, AuditLog, AuditMatch, AuditRetention};
 pub use backend::{
     BackendError, BackendResult, KernelBackend, LocalBackend, PatchOp, ReadRange,
-    ToolInfo, ToolResult, VirtualOverlayBackend, WriteMode,
+    PolicyBackend, ToolInfo, ToolResult, VirtualOverlayBackend, WriteMode,
 };
+pub use checkpoint::{Checkpoint, CheckpointStore};
 pub use dispatch::{CommandDispatcher, PipelinePosition};
+pub use hooks::{HookEvent, KernelHooks, Progress};
 pub use ignore_config::{IgnoreConfig, IgnoreScope};
 pub use kernel::{
-    CommandKind, ExecuteOptions, Kernel, KernelConfig, VfsMountMode, MAX_RECURSION_DEPTH,
-    RECOMMENDED_STACK_SIZE,
+    CommandKind, ExecuteOptions, Kernel, KernelConfig, VfsMountMode,
+    DEFAULT_AGENT_LOOP_ITERATIONS, LIMIT_EXCEEDED_EXIT_CODE, MAX_RECURSION_DEPTH,
+    MAX_SOURCE_DEPTH, RECOMMENDED_STACK_SIZE,
 };
+pub use metrics::{CommandStats, JobStats, KernelMetrics, MetricsSnapshot};
+pub use result_cache::{CacheEntryInfo, ResultCache};
+pub use tool_library::ToolLibraryReport;
 pub use output_limit::OutputLimitConfig;
+pub use policy::{Policy, POLICY_DENIED_EXIT_CODE};
+pub use state::{
+    ChangeKind, ChangeType, KernelState, MountState, StateChange, StateDiff, STATE_FORMAT_VERSION,
+};
 
 // ═══════════════════════════════════════════════════════════════════════════
 // Embedding Conveniences
@@ -100,4 +125,4 @@ pub use paths::{home_dir, xdg_cache_home, xdg_config_home, xdg_data_home, xdg_ru
 pub use interpreter::expand_tilde;
 
 // Tool registration (for embedders registering custom tools)
-pub use tools::{Tool, ToolRegistry, ExecContext};
+pub use tools::{Tool, ToolBundle, ToolRegistry, ExecContext};
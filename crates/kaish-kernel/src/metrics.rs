@@ -0,0 +1,186 @@
+//! Kernel metrics: per-command counts and durations, filesystem operations
+//! per mount, and background job statistics.
+//!
+//! Every command the kernel dispatches is timed where it enters
+//! `execute_command`, so builtins, `.kai` scripts, external commands, backend
+//! tools, and shell functions each get a row keyed by the name they were
+//! invoked as. Pipeline stages are dispatched the same way, so a stage's
+//! timing is its command's row. Filesystem operations are counted by the
+//! [`VfsRouter`](crate::vfs::VfsRouter) against the mount that served them;
+//! job statistics are read from the job manager when a snapshot is taken.
+//!
+//! The collector is Arc-shared with forks, so background jobs and scatter
+//! workers add to the same totals. Read it with `Kernel::metrics` or the
+//! `kaish-metrics` builtin.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::scheduler::{JobManager, JobStatus};
+use crate::vfs::VfsRouter;
+
+/// Counts and timings for one command name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CommandStats {
+    /// Times the command ran.
+    pub calls: u64,
+    /// Runs that exited non-zero.
+    pub failures: u64,
+    /// Wall-clock time across all runs.
+    pub total: Duration,
+    /// Longest single run.
+    pub max: Duration,
+}
+
+impl CommandStats {
+    /// Average wall-clock time per run.
+    pub fn mean(&self) -> Duration {
+        match u32::try_from(self.calls) {
+            Ok(0) => Duration::ZERO,
+            Ok(calls) => self.total / calls,
+            Err(_) => Duration::from_secs_f64(self.total.as_secs_f64() / self.calls as f64),
+        }
+    }
+}
+
+/// Background job counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JobStats {
+    /// Jobs started since the kernel was created, reaped ones included.
+    pub started: u64,
+    /// Jobs still running.
+    pub running: usize,
+    /// Jobs stopped (Ctrl-Z).
+    pub stopped: usize,
+    /// Finished jobs not yet reaped that exited zero.
+    pub done: usize,
+    /// Finished jobs not yet reaped that failed.
+    pub failed: usize,
+    /// Jobs held on a confirmation latch.
+    pub latched: usize,
+}
+
+/// Point-in-time view of a kernel's metrics.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    /// Per-command statistics, by command name.
+    pub commands: BTreeMap<String, CommandStats>,
+    /// Filesystem operations per mount point.
+    pub vfs_ops: BTreeMap<PathBuf, u64>,
+    /// Background job counts.
+    pub jobs: JobStats,
+}
+
+/// Shared metrics collector.
+#[derive(Clone, Default)]
+pub struct KernelMetrics {
+    commands: Arc<Mutex<BTreeMap<String, CommandStats>>>,
+    vfs: Option<Arc<VfsRouter>>,
+    jobs: Option<Arc<JobManager>>,
+}
+
+impl std::fmt::Debug for KernelMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KernelMetrics")
+            .field("commands", &self.lock().len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl KernelMetrics {
+    /// A collector that also reports `vfs`'s operation counts and `jobs`'
+    /// statistics.
+    pub fn new(vfs: Arc<VfsRouter>, jobs: Arc<JobManager>) -> Self {
+        Self {
+            commands: Arc::default(),
+            vfs: Some(vfs),
+            jobs: Some(jobs),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, CommandStats>> {
+        self.commands.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Count one run of `command` that exited with `code` after `elapsed`.
+    pub fn record_command(&self, command: &str, code: i64, elapsed: Duration) {
+        let mut commands = self.lock();
+        let stats = match commands.get_mut(command) {
+            Some(stats) => stats,
+            None => commands.entry(command.to_string()).or_default(),
+        };
+        stats.calls += 1;
+        if code != 0 {
+            stats.failures += 1;
+        }
+        stats.total += elapsed;
+        stats.max = stats.max.max(elapsed);
+    }
+
+    /// Take a snapshot of everything collected so far.
+    pub async fn snapshot(&self) -> MetricsSnapshot {
+        let mut jobs = JobStats::default();
+        if let Some(manager) = &self.jobs {
+            jobs.started = manager.started_count();
+            for job in manager.list().await {
+                match job.status {
+                    JobStatus::Running => jobs.running += 1,
+                    JobStatus::Stopped => jobs.stopped += 1,
+                    JobStatus::Done => jobs.done += 1,
+                    JobStatus::Failed => jobs.failed += 1,
+                    JobStatus::Latched => jobs.latched += 1,
+                    _ => {}
+                }
+            }
+        }
+        MetricsSnapshot {
+            commands: self.lock().clone(),
+            vfs_ops: self.vfs.as_ref().map(|vfs| vfs.op_counts()).unwrap_or_default(),
+            jobs,
+        }
+    }
+
+    /// Zero the command statistics and filesystem counts. Job counts reflect
+    /// the job table and aren't affected.
+    pub fn reset(&self) {
+        self.lock().clear();
+        if let Some(vfs) = &self.vfs {
+            vfs.reset_op_counts();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_accumulates_calls_failures_and_timings() {
+        let metrics = KernelMetrics::default();
+        metrics.record_command("grep", 0, Duration::from_millis(10));
+        metrics.record_command("grep", 1, Duration::from_millis(30));
+        metrics.record_command("ls", 0, Duration::from_millis(5));
+
+        let commands = metrics.lock().clone();
+        let grep = commands["grep"];
+        assert_eq!(grep.calls, 2);
+        assert_eq!(grep.failures, 1);
+        assert_eq!(grep.total, Duration::from_millis(40));
+        assert_eq!(grep.max, Duration::from_millis(30));
+        assert_eq!(grep.mean(), Duration::from_millis(20));
+        assert_eq!(commands["ls"].calls, 1);
+    }
+
+    #[test]
+    fn clones_share_and_reset_clears() {
+        let metrics = KernelMetrics::default();
+        let fork = metrics.clone();
+        fork.record_command("echo", 0, Duration::ZERO);
+        assert_eq!(metrics.lock().len(), 1);
+        metrics.reset();
+        assert!(fork.lock().is_empty());
+        assert_eq!(CommandStats::default().mean(), Duration::ZERO);
+    }
+}
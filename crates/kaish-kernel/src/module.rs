@@ -0,0 +1,279 @@
+//! Modules: `import PATH as NAME` loads a file's function definitions into a
+//! namespace.
+//!
+//! Like a tool library file (see [`crate::tool_library`]), a module
+//! contributes only its top-level function definitions; its other statements
+//! never run. Each function is registered as `NAME.function`, so importing
+//! `git-helpers.kai as git` adds `git.sync` and `git.release` without
+//! touching the flat namespace. Calls between the module's own functions are
+//! rewritten to the qualified names as it loads, so a module reads the same
+//! whichever name it is imported under. Importing into a namespace again
+//! replaces everything it held.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::ast::{Arg, Command, Expr, ListElem, RecordKey, Stmt, StringPart, TestExpr, ToolDef};
+
+/// The `PATH` and `NAME` of `import PATH as NAME`, or a usage message.
+pub(crate) fn import_args(args: &[String]) -> Result<(&str, &str), String> {
+    match args {
+        [path, keyword, namespace] if keyword == "as" => {
+            if is_namespace(namespace) {
+                Ok((path, namespace))
+            } else {
+                Err(format!(
+                    "import: invalid namespace '{namespace}': use letters, digits, '_' or '-'"
+                ))
+            }
+        }
+        _ => Err("import: usage: import PATH as NAME".to_string()),
+    }
+}
+
+/// Whether `name` can name a module namespace: an identifier with no dots,
+/// so a qualified name splits unambiguously at its first `.`.
+pub(crate) fn is_namespace(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Split a qualified name into its namespace and function: `git.sync` →
+/// `("git", "sync")`. `None` for a name that cannot be one, like `script.kai`
+/// run without a namespace of that name in play.
+pub(crate) fn split_qualified(name: &str) -> Option<(&str, &str)> {
+    let (namespace, function) = name.split_once('.')?;
+    (is_namespace(namespace) && !function.is_empty()).then_some((namespace, function))
+}
+
+/// Register `defs`, read from one module file, under `namespace`.
+pub(crate) fn qualify(defs: Vec<ToolDef>, namespace: &str) -> Vec<ToolDef> {
+    let names: HashMap<String, String> = defs
+        .iter()
+        .map(|def| (def.name.clone(), format!("{namespace}.{}", def.name)))
+        .collect();
+    defs.into_iter()
+        .map(|mut def| {
+            def.name = format!("{namespace}.{}", def.name);
+            def.module = Some(namespace.to_string());
+            qualify_body(&mut def.body, &names);
+            def
+        })
+        .collect()
+}
+
+/// A function body is shared; rewrite a copy of it.
+fn qualify_body(body: &mut Arc<[Stmt]>, names: &HashMap<String, String>) {
+    let mut stmts = body.to_vec();
+    qualify_stmts(&mut stmts, names);
+    *body = stmts.into();
+}
+
+fn qualify_stmts(stmts: &mut [Stmt], names: &HashMap<String, String>) {
+    for stmt in stmts {
+        qualify_stmt(stmt, names);
+    }
+}
+
+fn qualify_stmt(stmt: &mut Stmt, names: &HashMap<String, String>) {
+    match stmt {
+        Stmt::Assignment(assign) => qualify_expr(&mut assign.value, names),
+        Stmt::Command(cmd) => qualify_command(cmd, names),
+        Stmt::Pipeline(pipeline) => {
+            for cmd in &mut pipeline.commands {
+                qualify_command(cmd, names);
+            }
+        }
+        Stmt::If(if_stmt) => {
+            qualify_expr(&mut if_stmt.condition, names);
+            qualify_stmts(&mut if_stmt.then_branch, names);
+            if let Some(else_branch) = &mut if_stmt.else_branch {
+                qualify_stmts(else_branch, names);
+            }
+        }
+        Stmt::For(for_loop) => {
+            for item in &mut for_loop.items {
+                qualify_expr(item, names);
+            }
+            qualify_stmts(&mut for_loop.body, names);
+        }
+        Stmt::While(while_loop) => {
+            qualify_expr(&mut while_loop.condition, names);
+            qualify_stmts(&mut while_loop.body, names);
+        }
+        Stmt::Case(case_stmt) => {
+            qualify_expr(&mut case_stmt.expr, names);
+            for branch in &mut case_stmt.branches {
+                qualify_stmts(&mut branch.body, names);
+            }
+        }
+        Stmt::Return(Some(expr)) | Stmt::Exit(Some(expr)) => qualify_expr(expr, names),
+        Stmt::ToolDef(def) => qualify_body(&mut def.body, names),
+        Stmt::Test(test) => qualify_test(test, names),
+        Stmt::AndChain { left, right } | Stmt::OrChain { left, right } => {
+            qualify_stmt(left, names);
+            qualify_stmt(right, names);
+        }
+        Stmt::EnvScoped { assignments, body } => {
+            for assign in assignments {
+                qualify_expr(&mut assign.value, names);
+            }
+            qualify_stmt(body, names);
+        }
+        Stmt::Par(body) => qualify_stmts(body, names),
+        Stmt::Try(try_stmt) => {
+            qualify_stmts(&mut try_stmt.body, names);
+            qualify_stmts(&mut try_stmt.catch, names);
+        }
+        Stmt::On(on) => qualify_stmts(&mut on.body, names),
+        Stmt::Break(_) | Stmt::Continue(_) | Stmt::Return(None) | Stmt::Exit(None) | Stmt::Empty => {}
+    }
+}
+
+fn qualify_command(cmd: &mut Command, names: &HashMap<String, String>) {
+    if let Some(qualified) = names.get(&cmd.name) {
+        cmd.name.clone_from(qualified);
+    }
+    for arg in &mut cmd.args {
+        match arg {
+            Arg::Positional(expr) | Arg::Named { value: expr, .. } | Arg::WordAssign { value: expr, .. } => {
+                qualify_expr(expr, names);
+            }
+            Arg::ShortFlag(_) | Arg::LongFlag(_) | Arg::DoubleDash => {}
+        }
+    }
+    for redirect in &mut cmd.redirects {
+        qualify_expr(&mut redirect.target, names);
+    }
+}
+
+fn qualify_expr(expr: &mut Expr, names: &HashMap<String, String>) {
+    match expr {
+        Expr::Interpolated(parts) | Expr::VarWithDefault { default: parts, .. } => {
+            qualify_parts(parts, names);
+        }
+        Expr::HereDocBody { parts, .. } => {
+            for spanned in parts {
+                qualify_part(&mut spanned.part, names);
+            }
+        }
+        Expr::BinaryOp { left, right, .. } => {
+            qualify_expr(left, names);
+            qualify_expr(right, names);
+        }
+        Expr::CommandSubst(stmts) => qualify_stmts(stmts, names),
+        Expr::Test(test) => qualify_test(test, names),
+        Expr::Command(cmd) => qualify_command(cmd, names),
+        Expr::ListLiteral(elems) => {
+            for elem in elems {
+                match elem {
+                    ListElem::Item(expr) | ListElem::Spread(expr) => qualify_expr(expr, names),
+                }
+            }
+        }
+        Expr::RecordLiteral(entries) => {
+            for entry in entries {
+                if let RecordKey::Interpolated(parts) = &mut entry.key {
+                    qualify_parts(parts, names);
+                }
+                qualify_expr(&mut entry.value, names);
+            }
+        }
+        Expr::Conditional { condition, then_value, else_value } => {
+            qualify_expr(condition, names);
+            qualify_expr(then_value, names);
+            qualify_expr(else_value, names);
+        }
+        Expr::Literal(_)
+        | Expr::VarRef(_)
+        | Expr::Positional(_)
+        | Expr::AllArgs
+        | Expr::ArgCount
+        | Expr::VarLength(_)
+        | Expr::Arithmetic(_)
+        | Expr::LastExitCode
+        | Expr::CurrentPid
+        | Expr::GlobPattern(_) => {}
+    }
+}
+
+fn qualify_parts(parts: &mut [StringPart], names: &HashMap<String, String>) {
+    for part in parts {
+        qualify_part(part, names);
+    }
+}
+
+fn qualify_part(part: &mut StringPart, names: &HashMap<String, String>) {
+    match part {
+        StringPart::CommandSubst(stmts) => qualify_stmts(stmts, names),
+        StringPart::VarWithDefault { default, .. } => qualify_parts(default, names),
+        _ => {}
+    }
+}
+
+fn qualify_test(test: &mut TestExpr, names: &HashMap<String, String>) {
+    match test {
+        TestExpr::FileTest { path: expr, .. } | TestExpr::StringTest { value: expr, .. } => {
+            qualify_expr(expr, names);
+        }
+        TestExpr::Comparison { left, right, .. }
+        | TestExpr::In { left, right }
+        | TestExpr::NotIn { left, right } => {
+            qualify_expr(left, names);
+            qualify_expr(right, names);
+        }
+        TestExpr::And { left, right } | TestExpr::Or { left, right } => {
+            qualify_test(left, names);
+            qualify_test(right, names);
+        }
+        TestExpr::Not { expr } => qualify_test(expr, names),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defs(source: &str) -> Vec<ToolDef> {
+        crate::parser::parse(source)
+            .expect("parses")
+            .statements
+            .into_iter()
+            .filter_map(|stmt| match stmt {
+                Stmt::ToolDef(def) => Some(def),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn functions_and_sibling_calls_are_qualified() {
+        let qualified = qualify(
+            defs("sync() { fetch origin && echo \"$(branch)\"; }\nfetch() { git fetch $1; }\nbranch() { echo main; }"),
+            "git",
+        );
+        let names: Vec<&str> = qualified.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["git.sync", "git.fetch", "git.branch"]);
+        assert!(qualified.iter().all(|d| d.module.as_deref() == Some("git")));
+
+        let body = crate::ast::sexpr::format_stmt(&qualified[0].body[0]);
+        assert!(body.contains("(cmd git.fetch"), "{body}");
+        assert!(body.contains("(cmd git.branch"), "{body}");
+        // Other commands keep their names.
+        let body = crate::ast::sexpr::format_stmt(&qualified[1].body[0]);
+        assert!(body.contains("(cmd git "), "{body}");
+    }
+
+    #[test]
+    fn import_arguments_and_names() {
+        let args = |s: &str| s.split(' ').map(String::from).collect::<Vec<_>>();
+        assert_eq!(import_args(&args("/lib/git.kai as git")), Ok(("/lib/git.kai", "git")));
+        assert!(import_args(&args("/lib/git.kai git")).is_err());
+        assert!(import_args(&args("/lib/git.kai as g.it")).is_err());
+
+        assert_eq!(split_qualified("git.sync"), Some(("git", "sync")));
+        assert_eq!(split_qualified("git."), None);
+        assert_eq!(split_qualified("sync"), None);
+    }
+}
@@ -4,9 +4,10 @@
 //! Uses chumsky for parser combinators with good error recovery.
 
 use crate::ast::{
-    Arg, Assignment, BinaryOp, CaseBranch, CaseStmt, Command, Expr, FileTestOp, ForLoop, IfStmt,
-    ListElem, Pipeline, Program, RecordEntry, RecordKey, Redirect, RedirectKind, SpannedPart, Stmt,
-    StringPart, StringTestOp, TestCmpOp, TestExpr, ToolDef, Value, VarPath, VarSegment, WhileLoop,
+    Arg, Assignment, BinaryOp, CaseBranch, CaseStmt, Command, DefaultOp, Expr, FileTestOp, ForLoop,
+    IfStmt, ListElem, ParamDef, ParamType, Pipeline, Program, RecordEntry, RecordKey, Redirect,
+    RedirectKind, SourceSpan, SpannedPart, Stmt, StringPart, StringTestOp, TestCmpOp, TestExpr,
+    OnStmt, ToolDef, TryStmt, Value, VarPath, VarSegment, WhileLoop,
 };
 use crate::lexer::{self, HereDocData, Token};
 use chumsky::{input::ValueInput, prelude::*};
@@ -14,12 +15,18 @@ use chumsky::{input::ValueInput, prelude::*};
 /// Span type used throughout the parser.
 pub type Span = SimpleSpan;
 
+/// Convert a parser span to the AST's [`SourceSpan`].
+fn source_span(span: Span) -> SourceSpan {
+    SourceSpan::new(span.start, span.end)
+}
+
 /// Parse a raw `${...}` string into an Expr.
 ///
 /// Handles:
 /// - Special variables: `${?}` → LastExitCode, `${$}` → CurrentPid
 /// - Simple paths: `${VAR}`, `${VAR.field}`, `${VAR[0]}` → VarRef
-/// - Default values: `${VAR:-default}` → VarWithDefault (with nested expansion support)
+/// - Default values: `${VAR:-default}` → VarWithDefault (with nested expansion
+///   support), likewise `:=`, `:?` and `:+`
 fn parse_var_expr(raw: &str) -> Expr {
     // Special case: ${?} is the last exit code (same as $?)
     if raw == "${?}" {
@@ -33,7 +40,7 @@ fn parse_var_expr(raw: &str) -> Expr {
 
     // Check for default value syntax: ${VAR:-default}
     // Need to find :- that's not inside a nested ${...}
-    if let Some(colon_idx) = find_default_separator(raw) {
+    if let Some((colon_idx, op)) = find_default_separator(raw) {
         // Extract the variable path (between ${ and :-) — may carry subscripts.
         let path = parse_varpath(&format!("${{{}}}", &raw[2..colon_idx]));
         // Extract default value (between :- and }) and recursively parse it,
@@ -45,7 +52,7 @@ fn parse_var_expr(raw: &str) -> Expr {
         let default_word = unquote_default_word(default_str);
         let default = parse_interpolated_string(&default_word)
             .unwrap_or_else(|_| vec![StringPart::Literal(default_word.clone())]);
-        return Expr::VarWithDefault { path, default };
+        return Expr::VarWithDefault { path, op, default };
     }
 
     // Regular variable path
@@ -134,8 +141,9 @@ fn unquote_default_word(word: &str) -> String {
     out
 }
 
-/// Find the position of :- in a ${VAR:-default} expression, accounting for nested ${...}.
-fn find_default_separator(raw: &str) -> Option<usize> {
+/// Find the position and operator of `:-` (or `:=`, `:?`, `:+`) in a
+/// `${VAR:-default}` expression, accounting for nested `${...}`.
+fn find_default_separator(raw: &str) -> Option<(usize, DefaultOp)> {
     let bytes = raw.as_bytes();
     let mut depth = 0;
     let mut bracket_depth = 0;
@@ -159,23 +167,23 @@ fn find_default_separator(raw: &str) -> Option<usize> {
         } else if bytes[i] == b']' && bracket_depth > 0 {
             bracket_depth -= 1;
         }
-        // Only find :- at the top level (depth == 1 means we're inside the outer
+        // Only find the operator at the top level (depth == 1 means we're inside the outer
         // ${...}) and outside any subscript.
         if depth == 1
             && bracket_depth == 0
-            && i + 1 < bytes.len()
             && bytes[i] == b':'
-            && bytes[i + 1] == b'-'
+            && let Some(op) = bytes.get(i + 1).copied().and_then(DefaultOp::from_char)
         {
-            return Some(i);
+            return Some((i, op));
         }
         i += 1;
     }
     None
 }
 
-/// Find the position of :- in variable content (without outer braces), accounting for nested ${...}.
-fn find_default_separator_in_content(content: &str) -> Option<usize> {
+/// Find the position and operator of `:-` (or `:=`, `:?`, `:+`) in variable
+/// content (without outer braces), accounting for nested `${...}`.
+fn find_default_separator_in_content(content: &str) -> Option<(usize, DefaultOp)> {
     let bytes = content.as_bytes();
     let mut depth = 0;
     let mut bracket_depth = 0;
@@ -199,14 +207,13 @@ fn find_default_separator_in_content(content: &str) -> Option<usize> {
         } else if bytes[i] == b']' && bracket_depth > 0 {
             bracket_depth -= 1;
         }
-        // Find :- at the top level (depth == 0) and outside any subscript.
+        // Find the operator at the top level (depth == 0) and outside any subscript.
         if depth == 0
             && bracket_depth == 0
-            && i + 1 < bytes.len()
             && bytes[i] == b':'
-            && bytes[i + 1] == b'-'
+            && let Some(op) = bytes.get(i + 1).copied().and_then(DefaultOp::from_char)
         {
-            return Some(i);
+            return Some((i, op));
         }
         i += 1;
     }
@@ -504,7 +511,7 @@ fn parse_interpolated_string_spanned(s: &str, base_offset: usize) -> Vec<Spanned
                         .and_then(|s| s.strip_suffix("__"))
                         .unwrap_or("");
                     StringPart::Arithmetic(expr.to_string())
-                } else if let Some(colon_idx) = find_default_separator_in_content(&var_content) {
+                } else if let Some((colon_idx, op)) = find_default_separator_in_content(&var_content) {
                     let path = parse_varpath(&format!("${{{}}}", &var_content[..colon_idx]));
                     let default_str = &var_content[colon_idx + 2..];
                     // Default value spans recursively kept relative to the
@@ -514,7 +521,7 @@ fn parse_interpolated_string_spanned(s: &str, base_offset: usize) -> Vec<Spanned
                     let default_word = unquote_default_word(default_str);
                     let default = parse_interpolated_string(&default_word)
                         .unwrap_or_else(|_| vec![StringPart::Literal(default_word.clone())]);
-                    StringPart::VarWithDefault { path, default }
+                    StringPart::VarWithDefault { path, op, default }
                 } else {
                     StringPart::Var(parse_varpath(&format!("${{{}}}", var_content)))
                 };
@@ -739,12 +746,12 @@ fn parse_interpolated_string(s: &str) -> Result<Vec<StringPart>, String> {
                         .and_then(|s| s.strip_suffix("__"))
                         .unwrap_or("");
                     StringPart::Arithmetic(expr.to_string())
-                } else if let Some(colon_idx) = find_default_separator_in_content(&var_content) {
+                } else if let Some((colon_idx, op)) = find_default_separator_in_content(&var_content) {
                     // Variable with default: ${VAR:-default} - recursively parse the default
                     let path = parse_varpath(&format!("${{{}}}", &var_content[..colon_idx]));
                     let default_str = &var_content[colon_idx + 2..];
                     let default = parse_interpolated_string(&unquote_default_word(default_str))?;
-                    StringPart::VarWithDefault { path, default }
+                    StringPart::VarWithDefault { path, op, default }
                 } else {
                     // Regular variable: ${VAR} or ${VAR.field}
                     StringPart::Var(parse_varpath(&format!("${{{}}}", var_content)))
@@ -831,44 +838,69 @@ pub struct ParseError {
 
 impl ParseError {
     /// Format the error against the original source, emitting a 1-indexed
-    /// `line:col [parse]: <message>` prefix and a snippet of the offending
-    /// line. Mirrors `ValidationIssue::format` so error reporting feels
-    /// consistent across pipeline phases.
+    /// `line:col [parse]: <message>` prefix, the offending line, and a caret
+    /// run under the span. Mirrors `ValidationIssue::format` so error
+    /// reporting feels consistent across pipeline phases.
+    ///
+    /// ```text
+    /// 2:6 [parse]: found ')' expected ...
+    ///   | echo ) oops
+    ///   |      ^
+    /// ```
     pub fn format(&self, source: &str) -> String {
-        let start = self.span.start;
-        let mut line = 1usize;
-        let mut col = 1usize;
-        for (i, ch) in source.char_indices() {
-            if i >= start {
-                break;
-            }
-            if ch == '\n' {
-                line += 1;
-                col = 1;
-            } else {
-                col += 1;
-            }
-        }
-        let line_content = {
-            let line_start = source[..start.min(source.len())]
-                .rfind('\n')
-                .map_or(0, |i| i + 1);
-            let line_end = source[start.min(source.len())..]
-                .find('\n')
-                .map_or(source.len(), |i| start + i);
-            source.get(line_start..line_end).unwrap_or("")
-        };
-        if line_content.is_empty() {
-            format!("{}:{} [parse]: {}", line, col, self.message)
-        } else {
-            format!(
-                "{}:{} [parse]: {}\n  | {}",
-                line, col, self.message, line_content
-            )
+        let start = floor_char_boundary(source, self.span.start);
+        let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[start..]
+            .find('\n')
+            .map_or(source.len(), |i| start + i);
+        let line = source[..start].matches('\n').count() + 1;
+        let col = source[line_start..start].chars().count() + 1;
+        let line_content = &source[line_start..line_end];
+
+        let header = format!("{}:{} [parse]: {}", line, col, self.message);
+        if line_content.trim().is_empty() {
+            return header;
         }
+
+        // Pad with the line's own tabs so the carets stay aligned however the
+        // terminal expands them; clamp the run to this line, minimum one.
+        let pad: String = source[line_start..start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let end = floor_char_boundary(source, self.span.end.clamp(start, line_end));
+        let width = source[start..end].chars().count().max(1);
+        format!(
+            "{}\n  | {}\n  | {}{}",
+            header,
+            line_content,
+            pad,
+            "^".repeat(width)
+        )
     }
 }
 
+/// Render a batch of parse errors against `source`, one
+/// [`ParseError::format`] block per error. Shared by every place that
+/// reports parse failures — the kernel's execute path, `source`, script
+/// files, and `kaish-validate` — so they all print the same snippets.
+pub fn format_errors(errors: &[ParseError], source: &str) -> String {
+    errors
+        .iter()
+        .map(|e| e.format(source))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Largest char boundary in `source` at or before `offset`.
+fn floor_char_boundary(source: &str, offset: usize) -> usize {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
 impl std::fmt::Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} at {:?}", self.message, self.span)
@@ -880,14 +912,7 @@ impl std::error::Error for ParseError {}
 /// Parse kaish source code into a Program AST.
 pub fn parse(source: &str) -> Result<Program, Vec<ParseError>> {
     // Tokenize with logos
-    let tokens = lexer::tokenize(source).map_err(|errs| {
-        errs.into_iter()
-            .map(|e| ParseError {
-                span: (e.span.start..e.span.end).into(),
-                message: format!("lexer error: {}", e.token),
-            })
-            .collect::<Vec<_>>()
-    })?;
+    let tokens = lexer::tokenize(source).map_err(lexer_errors)?;
 
     // Convert tokens to (Token, SimpleSpan) pairs
     let tokens: Vec<(Token, Span)> = tokens
@@ -895,21 +920,126 @@ pub fn parse(source: &str) -> Result<Program, Vec<ParseError>> {
         .map(|spanned| (spanned.token, (spanned.span.start..spanned.span.end).into()))
         .collect();
 
-    // End-of-input span
-    let end_span: Span = (source.len()..source.len()).into();
+    parse_tokens(&tokens, (source.len()..source.len()).into())
+}
+
+/// Parse `source` lazily, a batch of top-level statements at a time.
+///
+/// Yields the statements [`parse`] would return, in order, but tokenizes
+/// and parses only as far as the caller reads (see
+/// [`lexer::tokenize_statements`]), so the first statement of a huge script
+/// arrives without the rest being tokenized and memory stays bounded by a
+/// batch. Errors end the iteration: a batch that fails to parse yields its
+/// errors and nothing else, and statements after it are never read.
+pub fn parse_statements(source: &str) -> Statements<'_> {
+    Statements {
+        source,
+        chunks: lexer::tokenize_statements(source),
+        ready: std::collections::VecDeque::new(),
+        error: None,
+        done: false,
+    }
+}
+
+/// Tokens gathered before a batch is parsed: enough that building the parser
+/// is noise next to running it, few enough to stay small.
+const STATEMENT_BATCH_TOKENS: usize = 4096;
+
+/// The iterator returned by [`parse_statements`].
+pub struct Statements<'a> {
+    source: &'a str,
+    chunks: lexer::StatementTokens<'a>,
+    ready: std::collections::VecDeque<Stmt>,
+    error: Option<Vec<ParseError>>,
+    done: bool,
+}
 
+impl Statements<'_> {
+    /// Parse the next batch into `ready`, or record why not.
+    fn fill(&mut self) {
+        let mut batch: Vec<(Token, Span)> = Vec::new();
+        while batch.len() < STATEMENT_BATCH_TOKENS {
+            match self.chunks.next() {
+                Some(Ok(tokens)) => batch.extend(
+                    tokens
+                        .into_iter()
+                        .map(|spanned| (spanned.token, (spanned.span.start..spanned.span.end).into())),
+                ),
+                Some(Err(errs)) => {
+                    self.error = Some(lexer_errors(errs));
+                    self.done = true;
+                    break;
+                }
+                None => {
+                    self.done = true;
+                    break;
+                }
+            }
+        }
+        let Some((_, last)) = batch.last() else { return };
+
+        // The last batch ends where the source does, as in `parse`.
+        let end = if self.done && self.error.is_none() { self.source.len() } else { last.end };
+        match parse_tokens(&batch, (end..end).into()) {
+            Ok(program) => self.ready.extend(program.statements),
+            Err(errs) => {
+                self.error = Some(errs);
+                self.done = true;
+            }
+        }
+    }
+}
+
+impl Iterator for Statements<'_> {
+    type Item = Result<Stmt, Vec<ParseError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(stmt) = self.ready.pop_front() {
+                return Some(Ok(stmt));
+            }
+            if self.done {
+                return self.error.take().map(Err);
+            }
+            self.fill();
+        }
+    }
+}
+
+fn lexer_errors(errs: Vec<lexer::Spanned<lexer::LexerError>>) -> Vec<ParseError> {
+    errs.into_iter()
+        .map(|e| ParseError {
+            span: (e.span.start..e.span.end).into(),
+            message: format!("lexer error: {}", e.token),
+        })
+        .collect()
+}
+
+/// Parse a token stream; `end_span` is where running out of tokens is
+/// reported.
+fn parse_tokens(tokens: &[(Token, Span)], end_span: Span) -> Result<Program, Vec<ParseError>> {
     // Parse using slice-based input (like nano_rust example)
     let parser = program_parser();
-    let result = parser.parse(tokens.as_slice().map(end_span, |(t, s)| (t, s)));
-
-    let program = result.into_result().map_err(|errs| {
-        errs.into_iter()
+    let result = parser.parse(tokens.map(end_span, |(t, s)| (t, s)));
+
+    // Recovered statements still leave their errors behind; any error fails
+    // the parse, but all of them are reported.
+    let (program, errs) = result.into_output_errors();
+    if !errs.is_empty() {
+        return Err(errs
+            .into_iter()
             .map(|e| ParseError {
                 span: *e.span(),
                 message: e.to_string(),
             })
-            .collect::<Vec<_>>()
-    })?;
+            .collect());
+    }
+    let Some(program) = program else {
+        return Err(vec![ParseError {
+            span: end_span,
+            message: "unparseable input".to_string(),
+        }]);
+    };
 
     // Structural well-formedness checks that chumsky's grammar can't surface a
     // clean message for. A command with two stdin sources (`<`/`<<`/`<<<`)
@@ -950,17 +1080,65 @@ pub fn parse_statement(source: &str) -> Result<Stmt, Vec<ParseError>> {
 // ═══════════════════════════════════════════════════════════════════════════
 
 /// Top-level program parser.
+///
+/// A top-level statement that fails to parse is recovered with
+/// [`skip_statement`]: its error is kept and parsing resumes at the next
+/// statement, so one run reports every bad statement instead of the first.
 fn program_parser<'tokens, 'src: 'tokens, I>(
 ) -> impl Parser<'tokens, I, Program, extra::Err<Rich<'tokens, Token, Span>>>
 where
     I: ValueInput<'tokens, Token = Token, Span = Span>,
 {
     statement_parser()
+        .recover_with(via_parser(skip_statement()))
         .repeated()
         .collect::<Vec<_>>()
         .map(|statements| Program { statements })
 }
 
+/// Recovery for a failed top-level statement: skip to the `;`/newline that
+/// ends it — one outside any block or parenthesis the statement opened — and
+/// stand in a `Stmt::Empty`. Tracking the open blocks keeps a bad line inside
+/// an `if … fi` from resyncing mid-block, where `then`/`fi` would each
+/// report a second, spurious error.
+fn skip_statement<'tokens, I>(
+) -> impl Parser<'tokens, I, Stmt, extra::Err<Rich<'tokens, Token, Span>>> + Clone
+where
+    I: ValueInput<'tokens, Token = Token, Span = Span>,
+{
+    custom(|inp| {
+        let before = inp.cursor();
+        let mut open: Vec<Token> = Vec::new();
+        let mut skipped = false;
+        while let Some(token) = inp.next() {
+            skipped = true;
+            match token {
+                Token::Newline | Token::Semi if open.is_empty() => break,
+                Token::If => open.push(Token::Fi),
+                // The loop opens at `for`/`while`, not `do`: the `;` before
+                // `do` must not end the statement.
+                Token::For | Token::While => open.push(Token::Done),
+                Token::Case => open.push(Token::Esac),
+                Token::LBrace => open.push(Token::RBrace),
+                Token::LParen | Token::CmdSubstStart => open.push(Token::RParen),
+                closer @ (Token::Fi | Token::Done | Token::Esac | Token::RBrace | Token::RParen) => {
+                    // `case` patterns end in a bare `)` — only a closer that
+                    // matches the innermost opener closes it.
+                    if open.last() == Some(&closer) {
+                        open.pop();
+                    }
+                }
+                _ => {}
+            }
+        }
+        if skipped {
+            Ok(Stmt::Empty)
+        } else {
+            Err(Rich::custom(inp.span_since(&before), "nothing to recover"))
+        }
+    })
+}
+
 /// Statement parser - dispatches based on leading token.
 /// Supports statement-level chaining with && and ||.
 fn statement_parser<'tokens, I>(
@@ -1052,19 +1230,21 @@ where
                 .repeated()
                 .collect::<Vec<_>>(),
             )
-            .map(|((_, first_arg), mut rest_args)| {
+            .map_with(|((_, first_arg), mut rest_args), e| {
                 let mut args = vec![first_arg];
                 args.append(&mut rest_args);
                 Stmt::Command(Command {
                     name: "set".to_string(),
                     args,
                     redirects: vec![],
+                    span: Some(source_span(e.span())),
                 })
             });
 
         // set with no args: `set` alone (shows settings)
         // Must be followed by newline, semicolon, end of input, or a chaining operator (&&, ||)
         let set_no_args = just(Token::Set)
+            .map_with(|_, e| source_span(e.span()))
             .then(
                 choice((
                     just(Token::Newline).to(()),
@@ -1075,10 +1255,11 @@ where
                 ))
                 .rewind(),
             )
-            .map(|_| Stmt::Command(Command {
+            .map(|(span, _)| Stmt::Command(Command {
                 name: "set".to_string(),
                 args: vec![],
                 redirects: vec![],
+                span: Some(span),
             }));
 
         // Try set_with_flags first (requires at least one flag)
@@ -1105,7 +1286,12 @@ where
         let env_prefix_assign = ident_parser()
             .then_ignore(just(Token::Eq))
             .then(value_expr_parser())
-            .map(|(name, value)| Assignment { path: VarPath::simple(name), value, local: false });
+            .map_with(|(name, value), e| Assignment {
+                path: VarPath::simple(name),
+                value,
+                local: false,
+                span: Some(source_span(e.span())),
+            });
         let env_scoped = env_prefix_assign
             .repeated()
             .at_least(1)
@@ -1125,6 +1311,9 @@ where
             // Shell-style functions (use $1, $2 positional params)
             posix_function_parser(stmt.clone()).map(Stmt::ToolDef),  // name() { }
             bash_function_parser(stmt.clone()).map(Stmt::ToolDef),   // function name { }
+            par_parser(stmt.clone()).map(Stmt::Par),                 // par { }
+            try_parser(stmt.clone()).map(Stmt::Try),                 // try { } catch { }
+            on_parser(stmt.clone()).map(Stmt::On),                   // on EVENT { }
             if_parser(stmt.clone()).map(Stmt::If),
             for_parser(stmt.clone()).map(Stmt::For),
             while_parser(stmt.clone()).map(Stmt::While),
@@ -1234,10 +1423,11 @@ where
         .ignore_then(lvalue_path_parser())
         .then_ignore(just(Token::Eq))
         .then(value_expr_parser())
-        .map(|(path, value)| Assignment {
+        .map_with(|(path, value), e| Assignment {
             path,
             value,
             local: true,
+            span: Some(source_span(e.span())),
         });
 
     // Bash-style: NAME=value / NAME[sub]=value (no spaces around =)
@@ -1245,10 +1435,11 @@ where
     let bash_assignment = lvalue_path_parser()
         .then_ignore(just(Token::Eq))
         .then(value_expr_parser())
-        .map(|(path, value)| Assignment {
+        .map_with(|(path, value), e| Assignment {
             path,
             value,
             local: false,
+            span: Some(source_span(e.span())),
         });
 
     choice((local_assignment, bash_assignment))
@@ -1256,9 +1447,11 @@ where
         .boxed()
 }
 
-/// POSIX-style function: `name() { body }`
+/// POSIX-style function: `name() { body }`, optionally declaring its
+/// parameters: `name(path count:int=1 rest:string...) { body }`.
 ///
-/// Produces a ToolDef with empty params - uses positional params ($1, $2, etc.)
+/// Declared parameters are bound as local variables on each call (see
+/// `interpreter::params`); `$1`, `$2`, … and `$@` are set either way.
 fn posix_function_parser<'tokens, I, S>(
     stmt: S,
 ) -> impl Parser<'tokens, I, ToolDef, extra::Err<Rich<'tokens, Token, Span>>> + Clone
@@ -1267,8 +1460,7 @@ where
     S: Parser<'tokens, I, Stmt, extra::Err<Rich<'tokens, Token, Span>>> + Clone + 'tokens,
 {
     ident_parser()
-        .then_ignore(just(Token::LParen))
-        .then_ignore(just(Token::RParen))
+        .then(param_list_parser())
         .then_ignore(just(Token::LBrace))
         .then_ignore(just(Token::Newline).repeated())
         .then(
@@ -1278,11 +1470,86 @@ where
         )
         .then_ignore(just(Token::Newline).repeated())
         .then_ignore(just(Token::RBrace))
-        .map(|(name, body)| ToolDef { name, params: vec![], body })
+        .map(|((name, params), body)| ToolDef { name, params, body: body.into(), module: None })
         .labelled("POSIX function")
         .boxed()
 }
 
+/// A function's parenthesized parameter list: `()` or `(a b:int c:int=2
+/// rest...)`, commas optional. A variadic parameter must come last and takes
+/// no default.
+fn param_list_parser<'tokens, I>(
+) -> impl Parser<'tokens, I, Vec<ParamDef>, extra::Err<Rich<'tokens, Token, Span>>> + Clone
+where
+    I: ValueInput<'tokens, Token = Token, Span = Span>,
+{
+    // The lexer fuses `name:type` into one identifier; `name: type` arrives as
+    // `name:` followed by the type.
+    let head = choice((
+        select! { Token::Ident(s) if s.ends_with(':') => s }
+            .then(ident_parser())
+            .map(|(name, ty)| format!("{name}{ty}")),
+        ident_parser(),
+    ));
+
+    let param = head
+        .then(just(Token::DotDotDot).or_not())
+        .then(just(Token::Eq).ignore_then(primary_expr_parser()).or_not())
+        .try_map(|((head, dots), default), span| {
+            let (name, ty) = match head.split_once(':') {
+                Some((name, ty)) => (name, Some(ty)),
+                None => (head.as_str(), None),
+            };
+            if name.is_empty()
+                || name.starts_with(|c: char| c.is_ascii_digit())
+                || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                return Err(Rich::custom(span, format!("invalid parameter name '{name}'")));
+            }
+            let param_type = match ty {
+                Some(ty) => Some(ParamType::from_name(ty).ok_or_else(|| {
+                    Rich::custom(
+                        span,
+                        format!(
+                            "unknown type '{ty}' for parameter '{name}' \
+                             (expected string, int, float, bool, or array)"
+                        ),
+                    )
+                })?),
+                None => None,
+            };
+            let variadic = dots.is_some();
+            if variadic && default.is_some() {
+                return Err(Rich::custom(span, format!("variadic parameter '{name}' cannot have a default")));
+            }
+            Ok(ParamDef { name: name.to_string(), param_type, default, variadic })
+        });
+
+    param
+        .then_ignore(just(Token::Comma).or_not())
+        .repeated()
+        .collect::<Vec<_>>()
+        .delimited_by(just(Token::LParen), just(Token::RParen))
+        .try_map(|params, span| {
+            if let Some(pos) = params.iter().position(|p| p.variadic)
+                && pos + 1 != params.len()
+            {
+                return Err(Rich::custom(
+                    span,
+                    format!("variadic parameter '{}' must be the last parameter", params[pos].name),
+                ));
+            }
+            for (i, param) in params.iter().enumerate() {
+                if params[..i].iter().any(|p| p.name == param.name) {
+                    return Err(Rich::custom(span, format!("duplicate parameter '{}'", param.name)));
+                }
+            }
+            Ok(params)
+        })
+        .labelled("parameter list")
+        .boxed()
+}
+
 /// Bash-style function: `function name { body }` (without parens)
 ///
 /// Produces a ToolDef with empty params - uses positional params ($1, $2, etc.)
@@ -1304,11 +1571,91 @@ where
         )
         .then_ignore(just(Token::Newline).repeated())
         .then_ignore(just(Token::RBrace))
-        .map(|(name, body)| ToolDef { name, params: vec![], body })
+        .map(|(name, body)| ToolDef { name, params: vec![], body: body.into(), module: None })
         .labelled("bash function")
         .boxed()
 }
 
+/// Concurrent block: `par { STMTS }`.
+///
+/// `par` is not a keyword: only `par` directly followed by `{` opens a block,
+/// so a command named `par` still runs.
+fn par_parser<'tokens, I, S>(
+    stmt: S,
+) -> impl Parser<'tokens, I, Vec<Stmt>, extra::Err<Rich<'tokens, Token, Span>>> + Clone
+where
+    I: ValueInput<'tokens, Token = Token, Span = Span>,
+    S: Parser<'tokens, I, Stmt, extra::Err<Rich<'tokens, Token, Span>>> + Clone + 'tokens,
+{
+    select! { Token::Ident(s) if s == "par" => () }
+        .ignore_then(just(Token::LBrace))
+        .ignore_then(just(Token::Newline).repeated())
+        .ignore_then(
+            stmt.repeated()
+                .collect::<Vec<_>>()
+                .map(|stmts| stmts.into_iter().filter(|s| !matches!(s, Stmt::Empty)).collect()),
+        )
+        .then_ignore(just(Token::Newline).repeated())
+        .then_ignore(just(Token::RBrace))
+        .labelled("par block")
+        .boxed()
+}
+
+/// Try block: `try { STMTS } catch { STMTS }`. Like `par`, `try` and `catch`
+/// are keywords only in front of a brace, so a command named `try` still runs.
+fn try_parser<'tokens, I, S>(
+    stmt: S,
+) -> impl Parser<'tokens, I, TryStmt, extra::Err<Rich<'tokens, Token, Span>>> + Clone
+where
+    I: ValueInput<'tokens, Token = Token, Span = Span>,
+    S: Parser<'tokens, I, Stmt, extra::Err<Rich<'tokens, Token, Span>>> + Clone + 'tokens,
+{
+    let block = just(Token::LBrace)
+        .ignore_then(just(Token::Newline).repeated())
+        .ignore_then(
+            stmt.repeated()
+                .collect::<Vec<_>>()
+                .map(|stmts| stmts.into_iter().filter(|s| !matches!(s, Stmt::Empty)).collect::<Vec<_>>()),
+        )
+        .then_ignore(just(Token::Newline).repeated())
+        .then_ignore(just(Token::RBrace));
+
+    select! { Token::Ident(s) if s == "try" => () }
+        .ignore_then(block.clone())
+        .then_ignore(just(Token::Newline).repeated())
+        .then_ignore(select! { Token::Ident(s) if s == "catch" => () }.labelled("catch"))
+        .then(block)
+        .map(|(body, catch)| TryStmt { body, catch })
+        .labelled("try block")
+        .boxed()
+}
+
+/// Event handler: `on EVENT { STMTS }`. `on` is a keyword only in front of
+/// an event name and a brace, so a command named `on` still runs. The event
+/// name is checked by the validator and at runtime, not here.
+fn on_parser<'tokens, I, S>(
+    stmt: S,
+) -> impl Parser<'tokens, I, OnStmt, extra::Err<Rich<'tokens, Token, Span>>> + Clone
+where
+    I: ValueInput<'tokens, Token = Token, Span = Span>,
+    S: Parser<'tokens, I, Stmt, extra::Err<Rich<'tokens, Token, Span>>> + Clone + 'tokens,
+{
+    select! { Token::Ident(s) if s == "on" => () }
+        .ignore_then(select! { Token::Ident(s) => s }.labelled("event name"))
+        .then_ignore(just(Token::LBrace))
+        .then_ignore(just(Token::Newline).repeated())
+        .then(
+            stmt.repeated()
+                .collect::<Vec<_>>()
+                .map(|stmts| stmts.into_iter().filter(|s| !matches!(s, Stmt::Empty)).collect::<Vec<_>>()),
+        )
+        .then_ignore(just(Token::Newline).repeated())
+        .then_ignore(just(Token::RBrace))
+        .map(|(event, body)| OnStmt { event, body })
+        .labelled("event handler")
+        .boxed()
+}
+
 /// If statement: `if COND; then STMTS [elif COND; then STMTS]* [else STMTS] fi`
 ///
 /// elif clauses are desugared to nested if/else:
@@ -1652,10 +1999,11 @@ where
     command_name
         .then(args_list_parser())
         .then(redirect_parser(primary_expr_parser()).repeated().collect::<Vec<_>>())
-        .map(|((name, args), redirects)| Command {
+        .map_with(|((name, args), redirects), e| Command {
             name,
             args,
             redirects,
+            span: Some(source_span(e.span())),
         })
         .labelled("command")
         .boxed()
@@ -1713,6 +2061,9 @@ fn stmt_has_ambiguous_stdin(stmt: &Stmt) -> bool {
         Stmt::While(w) => first_ambiguous_stdin(&w.body),
         Stmt::Case(c) => c.branches.iter().any(|b| first_ambiguous_stdin(&b.body)),
         Stmt::ToolDef(t) => first_ambiguous_stdin(&t.body),
+        Stmt::Par(body) => first_ambiguous_stdin(body),
+        Stmt::Try(t) => first_ambiguous_stdin(&t.body) || first_ambiguous_stdin(&t.catch),
+        Stmt::On(o) => first_ambiguous_stdin(&o.body),
         Stmt::AndChain { left, right } | Stmt::OrChain { left, right } => {
             stmt_has_ambiguous_stdin(left) || stmt_has_ambiguous_stdin(right)
         }
@@ -2374,15 +2725,63 @@ where
 }
 
 /// Value-position expression parser (assignment RHS: bash-style, `local`,
-/// and env-prefix). Adds collection literals on top of everything
-/// `primary_expr_parser` covers, so they appear on assignment RHS but never
-/// in argv or `for`-head items (`expr_parser`, above, stays untouched).
+/// and env-prefix). Adds collection literals and conditional values on top
+/// of everything `primary_expr_parser` covers, so they appear on assignment
+/// RHS but never in argv or `for`-head items (`expr_parser`, above, stays
+/// untouched).
 fn value_expr_parser<'tokens, I>(
 ) -> impl Parser<'tokens, I, Expr, extra::Err<Rich<'tokens, Token, Span>>> + Clone
 where
     I: ValueInput<'tokens, Token = Token, Span = Span>,
 {
-    value_literal_parser()
+    choice((conditional_value_parser(), value_literal_parser())).boxed()
+}
+
+/// Conditional value: `if COND then A [elif COND then B]... else C fi`.
+///
+/// The condition is an `if` statement's (`[[ ]]`, a command, `&&`/`||`);
+/// as there, a command condition needs `;` or a newline before `then`, and
+/// `;`/newlines may separate every part. Each branch is one value —
+/// another conditional, a collection literal, or anything
+/// `primary_expr_parser` covers. `else` is required: the expression always
+/// has a value. Only assignment RHS reaches here, not `value_literal_parser`,
+/// whose `in` operand is parsed inside `condition_parser` — including it
+/// there would make the parser's construction recurse forever.
+fn conditional_value_parser<'tokens, I>(
+) -> impl Parser<'tokens, I, Expr, extra::Err<Rich<'tokens, Token, Span>>> + Clone
+where
+    I: ValueInput<'tokens, Token = Token, Span = Span>,
+{
+    let gap = || choice((just(Token::Semi), just(Token::Newline))).repeated();
+    recursive(|conditional| {
+        let value = choice((conditional, value_literal_parser()));
+        let branch = condition_parser()
+            .then_ignore(gap())
+            .then_ignore(just(Token::Then))
+            .then_ignore(just(Token::Newline).repeated())
+            .then(value.clone())
+            .then_ignore(gap());
+
+        just(Token::If)
+            .ignore_then(branch.clone())
+            .then(just(Token::Elif).ignore_then(branch).repeated().collect::<Vec<_>>())
+            .then_ignore(just(Token::Else))
+            .then_ignore(just(Token::Newline).repeated())
+            .then(value)
+            .then_ignore(gap())
+            .then_ignore(just(Token::Fi))
+            .map(|((first, elifs), else_value)| {
+                std::iter::once(first).chain(elifs).rev().fold(else_value, |else_value, (condition, then_value)| {
+                    Expr::Conditional {
+                        condition: Box::new(condition),
+                        then_value: Box::new(then_value),
+                        else_value: Box::new(else_value),
+                    }
+                })
+            })
+            .labelled("conditional value")
+    })
+    .boxed()
 }
 
 /// Value-position primary parser (`in`/`not in` RHS operand only — the
@@ -2567,6 +2966,12 @@ where
         // when `Token::Set` leads a statement (see `set_command`), so this
         // arm never shadows it.
         Token::Set => "set",
+        // Type keywords only mean something inside a parameter list, so in
+        // argument position they are words (`random int 1 6`).
+        Token::TypeString => "string",
+        Token::TypeInt => "int",
+        Token::TypeFloat => "float",
+        Token::TypeBool => "bool",
     }
     .map(|s| Expr::Literal(Value::String(s.to_string())));
 
@@ -2735,10 +3140,11 @@ where
                 .repeated()
                 .collect::<Vec<_>>(),
         )
-        .map(|((name, args), redirects)| Command {
+        .map_with(|((name, args), redirects), e| Command {
             name,
             args,
             redirects,
+            span: Some(source_span(e.span())),
         });
 
     // Pipeline parser
@@ -3826,6 +4232,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_function_parameter_list() {
+        let result = parse("deploy(env, replicas:int=2 tags: string...) { echo $env }").unwrap();
+        match &result.statements[0] {
+            Stmt::ToolDef(t) => {
+                let params: Vec<_> = t
+                    .params
+                    .iter()
+                    .map(|p| (p.name.as_str(), p.param_type.clone(), p.default.is_some(), p.variadic))
+                    .collect();
+                assert_eq!(
+                    params,
+                    [
+                        ("env", None, false, false),
+                        ("replicas", Some(ParamType::Int), true, false),
+                        ("tags", Some(ParamType::String), false, true),
+                    ]
+                );
+            }
+            other => panic!("expected function def, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_function_parameter_list_errors() {
+        assert!(parse("f(rest... last) { echo }").is_err(), "variadic must be last");
+        assert!(parse("f(rest...=1) { echo }").is_err(), "variadic takes no default");
+        assert!(parse("f(a a) { echo }").is_err(), "duplicate name");
+        assert!(parse("f(n:integer) { echo }").is_err(), "unknown type");
+    }
+
     #[test]
     fn parse_bash_style_function() {
         let result = parse("function greet { echo hello }").unwrap();
@@ -4006,6 +4443,68 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_par_block() {
+        let result = parse("par {\n  A=1\n  echo hi; echo there\n}").unwrap();
+        match &result.statements[0] {
+            Stmt::Par(body) => {
+                assert_eq!(body.len(), 3, "got {body:?}");
+                assert!(matches!(&body[0], Stmt::Assignment(a) if a.name() == "A"));
+                assert!(matches!(&body[2], Stmt::Command(c) if c.name == "echo"));
+            }
+            other => panic!("expected par block, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn par_without_a_brace_is_a_command() {
+        let result = parse("par -w 60 notes.txt").unwrap();
+        assert!(
+            matches!(&result.statements[0], Stmt::Command(c) if c.name == "par"),
+            "got {:?}",
+            result.statements[0]
+        );
+    }
+
+    #[test]
+    fn parse_try_catch() {
+        let result = parse("try {\n  mkdir out\n  cp a out\n}\ncatch { echo \"failed: $ERROR\"; } && echo done").unwrap();
+        match &result.statements[0] {
+            Stmt::AndChain { left, .. } => match left.as_ref() {
+                Stmt::Try(t) => {
+                    assert_eq!(t.body.len(), 2, "got {:?}", t.body);
+                    assert!(matches!(&t.catch[0], Stmt::Command(c) if c.name == "echo"));
+                }
+                other => panic!("expected try block, got {other:?}"),
+            },
+            other => panic!("expected && chain, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_on_handler() {
+        let result = parse("on command_error {\n  echo \"$1 failed\"\n}\non cwd_change {}").unwrap();
+        let handlers: Vec<&Stmt> = result.statements.iter().filter(|s| !matches!(s, Stmt::Empty)).collect();
+        match &handlers[..] {
+            [Stmt::On(error), Stmt::On(cwd)] => {
+                assert_eq!(error.event, "command_error");
+                assert!(matches!(&error.body[..], [Stmt::Command(c)] if c.name == "echo"));
+                assert_eq!(cwd.event, "cwd_change");
+                assert!(cwd.body.is_empty());
+            }
+            other => panic!("expected two handlers, got {other:?}"),
+        }
+        let result = parse("on call").unwrap();
+        assert!(matches!(&result.statements[0], Stmt::Command(c) if c.name == "on"));
+    }
+
+    #[test]
+    fn try_needs_a_catch_block() {
+        assert!(parse("try { echo hi; }").is_err());
+        let result = parse("try again").unwrap();
+        assert!(matches!(&result.statements[0], Stmt::Command(c) if c.name == "try"));
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // Inline env-prefix (`NAME=value command`) Tests
     // ═══════════════════════════════════════════════════════════════════════════
@@ -4582,6 +5081,22 @@ cmd < "input.txt"
         assert!(result.is_err(), "then= should fail - 'then' is a keyword");
     }
 
+    #[test]
+    fn parse_type_keywords_as_arguments() {
+        let program = parse("random int 1 6; echo string float bool").expect("parse");
+        let commands: Vec<_> = program
+            .statements
+            .iter()
+            .filter_map(|stmt| match stmt {
+                Stmt::Command(cmd) => Some(cmd),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].args[0], Arg::Positional(Expr::Literal(Value::String("int".into()))));
+        assert_eq!(commands[1].args.len(), 3);
+    }
+
     #[test]
     fn parse_set_command_with_flag() {
         let result = parse("set -e");
@@ -5152,4 +5667,59 @@ cmd < "input.txt"
             other => panic!("expected a single For statement, got {other:?}"),
         }
     }
+
+    #[test]
+    fn parse_statements_matches_parse() {
+        let source = "X=1\n\nif true; then\n  echo $((X + 1))\nfi\ncat <<EOF\nbody\nEOF\n\
+            try {\n  false\n}\ncatch {\n  echo caught\n}\nf() { echo hi; }\necho a; echo b\n";
+        let streamed: Vec<Stmt> = parse_statements(source)
+            .collect::<Result<_, _>>()
+            .unwrap_or_else(|e| panic!("parse: {e:?}"));
+        let whole = parse(source).unwrap_or_else(|e| panic!("parse: {e:?}"));
+        assert_eq!(streamed, whole.statements);
+
+        let many: String = (0..3000).map(|i| format!("echo {i}\n")).collect();
+        let streamed: Vec<Stmt> = parse_statements(&many)
+            .collect::<Result<_, _>>()
+            .unwrap_or_else(|e| panic!("parse: {e:?}"));
+        assert_eq!(streamed, parse(&many).unwrap_or_else(|e| panic!("parse: {e:?}")).statements);
+    }
+
+    #[test]
+    fn parse_statements_yields_what_precedes_an_error() {
+        let mut statements = parse_statements("echo first\necho `date`\necho never\n");
+        assert!(matches!(statements.next(), Some(Ok(Stmt::Command(cmd))) if cmd.name == "echo"));
+        let errors = match statements.next() {
+            Some(Err(errors)) => errors,
+            other => panic!("expected the lexer error, got {other:?}"),
+        };
+        assert!(errors[0].message.starts_with("lexer error"), "{errors:?}");
+        assert!(statements.next().is_none());
+
+        let mut bad = parse_statements("if true; then\necho x\n");
+        assert!(matches!(bad.next(), Some(Err(_))));
+        assert!(bad.next().is_none());
+    }
+
+    #[test]
+    fn parse_conditional_value() {
+        let program = parse("X=if [[ $N -gt 5 ]] then big elif false; then mid else [a b] fi")
+            .unwrap_or_else(|e| panic!("parse: {e:?}"));
+        let [Stmt::Assignment(assign)] = program.statements.as_slice() else {
+            panic!("expected one assignment, got {:?}", program.statements);
+        };
+        let Expr::Conditional { condition, then_value, else_value } = &assign.value else {
+            panic!("expected a conditional, got {:?}", assign.value);
+        };
+        assert!(matches!(**condition, Expr::Test(_)), "{condition:?}");
+        assert_eq!(**then_value, Expr::Literal(Value::String("big".into())));
+        let Expr::Conditional { condition, then_value, else_value } = &**else_value else {
+            panic!("elif nests a conditional, got {else_value:?}");
+        };
+        assert!(matches!(&**condition, Expr::Command(cmd) if cmd.name == "false"), "{condition:?}");
+        assert_eq!(**then_value, Expr::Literal(Value::String("mid".into())));
+        assert!(matches!(**else_value, Expr::ListLiteral(_)), "{else_value:?}");
+
+        assert!(parse("X=if true; then a; fi").is_err(), "else is required");
+    }
 }
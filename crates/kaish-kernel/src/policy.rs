@@ -0,0 +1,170 @@
+//! Execution policy: which commands a kernel may run and where it may write.
+//!
+//! A [`Policy`] is set on [`KernelConfig`](crate::KernelConfig) with
+//! `with_policy` and enforced at three points:
+//!
+//! - **Commands.** With `allowed_tools` set, any command name outside the list
+//!   (builtin, `.kai` script, external, or backend tool) is refused at
+//!   dispatch with [`POLICY_DENIED_EXIT_CODE`]. Shell functions defined in the
+//!   script are not gated — the commands they run are. The validator flags a
+//!   statically named disallowed command up front (`W007`) so an agent sees
+//!   the refusal before anything runs.
+//! - **Exec.** `allow_exec = false` turns off the kernel's one external-command
+//!   switch, [`KernelConfig::allow_external_commands`](crate::KernelConfig::allow_external_commands).
+//!   External commands are then never looked up — a name that isn't a builtin
+//!   is "command not found", so a script can't probe the host's `PATH` — and
+//!   the `exec` / `spawn` builtins are refused with [`POLICY_DENIED_EXIT_CODE`].
+//!   kaish builtins never open network connections, so an external command is
+//!   the only way a script reaches the network: denying exec is how network
+//!   access is denied.
+//! - **Writes.** With `writable_prefixes` set, the kernel's backend rejects
+//!   writes, removals, renames, and directory/symlink creation outside those
+//!   prefixes with a permission-denied error. External processes write to the
+//!   host directly and are not covered — pair a write allowlist with
+//!   `allow_exec = false`.
+//!
+//! The default policy allows everything, so a kernel built without one behaves
+//! exactly as before.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Exit code for a command refused by the kernel's [`Policy`]. Borrowed from
+/// sysexits' `EX_NOPERM`, and distinct from 126 (found but not executable) and
+/// 127 (not found) so a caller can tell a policy refusal from a missing tool.
+pub const POLICY_DENIED_EXIT_CODE: i64 = kaish_types::exit_code::POLICY_DENIED;
+
+/// What a kernel is permitted to do. See the module docs for enforcement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Policy {
+    /// Command names the kernel may run. `None` allows every command.
+    pub allowed_tools: Option<BTreeSet<String>>,
+    /// VFS path prefixes the kernel may modify. `None` allows writes anywhere
+    /// the mounts themselves allow.
+    pub writable_prefixes: Option<Vec<PathBuf>>,
+    /// Whether external commands (and `exec` / `spawn`) may run. `false`
+    /// overrides `KernelConfig::allow_external_commands`.
+    pub allow_exec: bool,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            allowed_tools: None,
+            writable_prefixes: None,
+            allow_exec: true,
+        }
+    }
+}
+
+impl Policy {
+    /// A policy that allows everything (the default).
+    pub fn permissive() -> Self {
+        Self::default()
+    }
+
+    /// Restrict commands to `tools`.
+    pub fn with_allowed_tools<I, S>(mut self, tools: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_tools = Some(tools.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Restrict writes to paths under `prefixes`.
+    pub fn with_writable_prefixes<I, P>(mut self, prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        self.writable_prefixes =
+            Some(prefixes.into_iter().map(|p| crate::vfs::normalize_path(&p.into())).collect());
+        self
+    }
+
+    /// Allow or deny external commands.
+    pub fn with_exec(mut self, allow: bool) -> Self {
+        self.allow_exec = allow;
+        self
+    }
+
+    /// Whether this policy restricts nothing.
+    pub fn is_permissive(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether the command `name` may run.
+    pub fn allows_tool(&self, name: &str) -> bool {
+        self.allowed_tools
+            .as_ref()
+            .is_none_or(|tools| tools.contains(name))
+    }
+
+    /// Whether `path` may be modified. `..` components are resolved lexically
+    /// first, so `/allowed/../etc` is judged as `/etc`.
+    pub fn allows_write(&self, path: &Path) -> bool {
+        match &self.writable_prefixes {
+            None => true,
+            Some(prefixes) => {
+                let path = crate::vfs::normalize_path(path);
+                prefixes.iter().any(|prefix| path.starts_with(prefix))
+            }
+        }
+    }
+
+    /// Message for a command refused by `allowed_tools`.
+    pub fn tool_denied_message(name: &str) -> String {
+        format!("{name}: denied by policy (not an allowed tool)")
+    }
+
+    /// Message for `exec` / `spawn` with external commands turned off.
+    pub fn exec_denied_message(name: &str) -> String {
+        format!("{name}: denied by policy (external commands are not allowed)")
+    }
+
+    /// Message for a write refused by `writable_prefixes`.
+    pub fn write_denied_message(path: &Path) -> String {
+        format!("{}: denied by policy (not under a writable prefix)", path.display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_allows_everything() {
+        let policy = Policy::default();
+        assert!(policy.is_permissive());
+        assert!(policy.allows_tool("rm"));
+        assert!(policy.allows_write(Path::new("/etc/passwd")));
+        assert!(policy.allow_exec);
+    }
+
+    #[test]
+    fn allowed_tools_is_an_allowlist() {
+        let policy = Policy::default().with_allowed_tools(["echo", "cat"]);
+        assert!(!policy.is_permissive());
+        assert!(policy.allows_tool("echo"));
+        assert!(!policy.allows_tool("rm"));
+    }
+
+    #[test]
+    fn writable_prefixes_match_whole_components() {
+        let policy = Policy::default().with_writable_prefixes(["/work", "/tmp/"]);
+        assert!(policy.allows_write(Path::new("/work")));
+        assert!(policy.allows_write(Path::new("/work/a/b.txt")));
+        assert!(policy.allows_write(Path::new("/tmp/x")));
+        assert!(!policy.allows_write(Path::new("/workshop/x")));
+        assert!(!policy.allows_write(Path::new("/etc/passwd")));
+    }
+
+    #[test]
+    fn parent_components_cannot_escape_a_prefix() {
+        let policy = Policy::default().with_writable_prefixes(["/work"]);
+        assert!(!policy.allows_write(Path::new("/work/../etc/passwd")));
+        assert!(policy.allows_write(Path::new("/work/a/../b")));
+    }
+}
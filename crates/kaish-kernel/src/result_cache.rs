@@ -0,0 +1,326 @@
+//! Result cache for pure tool invocations.
+//!
+//! A tool opts in per invocation through [`Tool::cache_ttl`]: when it returns
+//! `Some(ttl)` the kernel keys the call by a SHA-256 of the tool name, the
+//! evaluated argv, the cwd, and the size and mtime of each operand, and a
+//! later identical call within `ttl` is answered from here instead of running
+//! the tool again. Only successful results are stored, and a call fed any
+//! stdin always runs.
+//!
+//! `cat` and `checksum` opt in when every operand sits on a read-only mount
+//! — content the session cannot change under them. `--no-cache` on any
+//! command skips the lookup and refreshes the entry; `kaish-cache clear` (or
+//! `Kernel::clear_result_cache`) drops everything.
+//!
+//! A read-only mount only means *this session* can't write there; the host
+//! still can. The operand stamps in the key make an edited file miss rather
+//! than serve stale bytes for the rest of the TTL.
+//!
+//! The cache holds at most [`MAX_ENTRIES`] results and [`MAX_BYTES`] of
+//! output. Inserting past either cap evicts expired entries first, then the
+//! ones closest to expiry; a single result larger than [`MAX_BYTES`] is never
+//! stored.
+//!
+//! The [`ResultCache`] lives on the `ExecContext` and is Arc-shared like the
+//! checkpoint store, so forks and pipeline stages hit the same entries.
+//!
+//! [`Tool::cache_ttl`]: crate::tools::Tool::cache_ttl
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use sha2::{Digest, Sha256};
+
+use crate::ast::Value;
+use crate::interpreter::ExecResult;
+use crate::tools::{ToolArgs, ToolCtx};
+
+/// How long `cat` and `checksum` results over read-only mounts are reused.
+pub const READ_ONLY_TTL: Duration = Duration::from_secs(60);
+
+/// Most results the cache holds at once.
+pub const MAX_ENTRIES: usize = 256;
+
+/// Most output bytes (stdout, error, and stderr) the cache holds at once.
+pub const MAX_BYTES: usize = 16 * 1024 * 1024;
+
+/// An operand's size and modification time at lookup, folded into the key.
+/// `None` when the operand couldn't be stat'ed.
+pub type OperandStamp = Option<(u64, Option<SystemTime>)>;
+
+/// One cached result as listed by [`ResultCache::entries`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntryInfo {
+    /// The command as it was invoked, argv joined by spaces.
+    pub command: String,
+    /// Time left before the entry expires.
+    pub expires_in: Duration,
+}
+
+#[derive(Debug)]
+struct Entry {
+    result: ExecResult,
+    command: String,
+    expires: Instant,
+    bytes: usize,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    entries: HashMap<String, Entry>,
+    bytes: usize,
+    hits: u64,
+}
+
+impl Inner {
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.bytes -= entry.bytes;
+        }
+    }
+
+    /// Evict until one more entry of `incoming` bytes fits under both caps:
+    /// expired entries first, then the soonest to expire.
+    fn make_room(&mut self, incoming: usize) {
+        let now = Instant::now();
+        let expired: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.expires <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in expired {
+            self.remove(&key);
+        }
+        while self.entries.len() >= MAX_ENTRIES || self.bytes + incoming > MAX_BYTES {
+            let Some(key) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.expires)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            self.remove(&key);
+        }
+    }
+}
+
+/// Shared, in-memory cache of pure tool results.
+#[derive(Clone, Debug, Default)]
+pub struct ResultCache {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl ResultCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The cache key for `tool` run with `argv` in `cwd`, over operands
+    /// whose current size and mtime are `stamps`.
+    pub fn key(tool: &str, argv: &[String], cwd: &Path, stamps: &[OperandStamp]) -> String {
+        let mut hasher = Sha256::new();
+        // NUL-separated so `a b` and `ab` never collide.
+        hasher.update(tool.as_bytes());
+        for arg in argv {
+            hasher.update([0]);
+            hasher.update(arg.as_bytes());
+        }
+        hasher.update([0]);
+        hasher.update(cwd.as_os_str().as_encoded_bytes());
+        for stamp in stamps {
+            hasher.update([0]);
+            match stamp {
+                Some((size, modified)) => {
+                    let nanos = modified
+                        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                        .map_or(0, |d| d.as_nanos());
+                    hasher.update(size.to_le_bytes());
+                    hasher.update(nanos.to_le_bytes());
+                }
+                None => hasher.update(b"-"),
+            }
+        }
+        hasher.finalize().iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    /// The unexpired result stored under `key`, if any. An expired entry is
+    /// dropped on the way.
+    pub fn get(&self, key: &str) -> Option<ExecResult> {
+        let mut inner = self.lock();
+        let fresh = inner.entries.get(key).map(|entry| entry.expires > Instant::now())?;
+        if !fresh {
+            inner.remove(key);
+            return None;
+        }
+        inner.hits += 1;
+        inner.entries.get(key).map(|entry| entry.result.clone())
+    }
+
+    /// Store `result` under `key` for `ttl`, evicting to stay under
+    /// [`MAX_ENTRIES`] and [`MAX_BYTES`]. Failed results, and results larger
+    /// than [`MAX_BYTES`] on their own, are not stored.
+    pub fn insert(&self, key: String, command: String, result: &ExecResult, ttl: Duration) {
+        if !result.ok() {
+            return;
+        }
+        let bytes = result.text_out().len() + result.err.len() + result.stderr.len();
+        if bytes > MAX_BYTES {
+            return;
+        }
+        let expires = Instant::now() + ttl;
+        let mut inner = self.lock();
+        inner.remove(&key);
+        inner.make_room(bytes);
+        inner.bytes += bytes;
+        inner.entries.insert(key, Entry { result: result.clone(), command, expires, bytes });
+    }
+
+    /// Unexpired entries, soonest to expire first.
+    pub fn entries(&self) -> Vec<CacheEntryInfo> {
+        let now = Instant::now();
+        let mut entries: Vec<CacheEntryInfo> = self
+            .lock()
+            .entries
+            .values()
+            .filter(|entry| entry.expires > now)
+            .map(|entry| CacheEntryInfo {
+                command: entry.command.clone(),
+                expires_in: entry.expires - now,
+            })
+            .collect();
+        entries.sort_by(|a, b| a.expires_in.cmp(&b.expires_in).then_with(|| a.command.cmp(&b.command)));
+        entries
+    }
+
+    /// Lookups answered from the cache since creation or the last clear.
+    pub fn hits(&self) -> u64 {
+        self.lock().hits
+    }
+
+    /// Drop every entry and zero the hit count. Returns how many entries
+    /// were dropped.
+    pub fn clear(&self) -> usize {
+        let mut inner = self.lock();
+        inner.hits = 0;
+        inner.bytes = 0;
+        let dropped = inner.entries.len();
+        inner.entries.clear();
+        dropped
+    }
+}
+
+/// Whether every operand of `args` is a file on a read-only mount.
+///
+/// The purity test `cat` and `checksum` share: no operands (stdin), a `-`
+/// operand, or any operand on a writable mount makes the call impure.
+pub fn operands_on_read_only_mounts(args: &ToolArgs, ctx: &dyn ToolCtx) -> bool {
+    if args.positional.is_empty() {
+        return false;
+    }
+    let mounts = ctx.backend().mounts();
+    args.positional.iter().all(|operand| {
+        let Value::String(operand) = operand else {
+            return false;
+        };
+        if operand == "-" {
+            return false;
+        }
+        let path = ctx.resolve_path(operand);
+        // The longest mount prefix serves the path.
+        mounts
+            .iter()
+            .filter(|mount| path.starts_with(&mount.path))
+            .max_by_key(|mount| mount.path.components().count())
+            .is_some_and(|mount| mount.read_only)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_separate_tool_argv_and_cwd() {
+        let cwd = Path::new("/");
+        let key = ResultCache::key("cat", &["a".into(), "b".into()], cwd, &[]);
+        assert_eq!(key, ResultCache::key("cat", &["a".into(), "b".into()], cwd, &[]));
+        assert_ne!(key, ResultCache::key("cat", &["ab".into()], cwd, &[]));
+        assert_ne!(key, ResultCache::key("checksum", &["a".into(), "b".into()], cwd, &[]));
+        assert_ne!(key, ResultCache::key("cat", &["a".into(), "b".into()], Path::new("/tmp"), &[]));
+    }
+
+    #[test]
+    fn keys_change_with_operand_size_or_mtime() {
+        let cwd = Path::new("/");
+        let argv = ["a".to_string()];
+        let then = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let key = ResultCache::key("cat", &argv, cwd, &[Some((5, Some(then)))]);
+        assert_eq!(key, ResultCache::key("cat", &argv, cwd, &[Some((5, Some(then)))]));
+        assert_ne!(key, ResultCache::key("cat", &argv, cwd, &[Some((6, Some(then)))]));
+        let later = then + Duration::from_secs(1);
+        assert_ne!(key, ResultCache::key("cat", &argv, cwd, &[Some((5, Some(later)))]));
+        assert_ne!(key, ResultCache::key("cat", &argv, cwd, &[None]));
+    }
+
+    #[test]
+    fn evicts_soonest_to_expire_past_the_entry_cap() {
+        let cache = ResultCache::new();
+        cache.insert("first".into(), "cat 0".into(), &ExecResult::success("x"), Duration::from_secs(10));
+        for i in 1..=MAX_ENTRIES {
+            cache.insert(format!("k{i}"), format!("cat {i}"), &ExecResult::success("x"), Duration::from_secs(60));
+        }
+        assert_eq!(cache.entries().len(), MAX_ENTRIES);
+        assert!(cache.get("first").is_none());
+        assert!(cache.get(&format!("k{MAX_ENTRIES}")).is_some());
+    }
+
+    #[test]
+    fn byte_cap_evicts_and_refuses_oversized_results() {
+        let cache = ResultCache::new();
+        let half = ExecResult::success("x".repeat(MAX_BYTES / 2));
+        cache.insert("a".into(), "cat a".into(), &half, Duration::from_secs(10));
+        cache.insert("b".into(), "cat b".into(), &half, Duration::from_secs(60));
+        cache.insert("c".into(), "cat c".into(), &half, Duration::from_secs(60));
+        assert!(cache.get("a").is_none());
+        assert!(cache.get("b").is_some() && cache.get("c").is_some());
+
+        let huge = ExecResult::success("x".repeat(MAX_BYTES + 1));
+        cache.insert("huge".into(), "cat huge".into(), &huge, Duration::from_secs(60));
+        assert!(cache.get("huge").is_none());
+        assert_eq!(cache.entries().len(), 2);
+    }
+
+    #[test]
+    fn stores_successes_until_they_expire() {
+        let cache = ResultCache::new();
+        cache.insert("ok".into(), "cat x".into(), &ExecResult::success("hi"), Duration::from_secs(60));
+        cache.insert("failed".into(), "cat y".into(), &ExecResult::failure(1, "no"), Duration::from_secs(60));
+        cache.insert("stale".into(), "cat z".into(), &ExecResult::success("old"), Duration::ZERO);
+
+        assert_eq!(cache.get("ok").map(|r| r.text_out().into_owned()), Some("hi".to_string()));
+        assert!(cache.get("failed").is_none());
+        assert!(cache.get("stale").is_none());
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.entries().len(), 1);
+    }
+
+    #[test]
+    fn clones_share_and_clear_empties() {
+        let cache = ResultCache::new();
+        let fork = cache.clone();
+        fork.insert("k".into(), "cat x".into(), &ExecResult::success("hi"), Duration::from_secs(60));
+        assert!(cache.get("k").is_some());
+        assert_eq!(cache.clear(), 1);
+        assert!(fork.get("k").is_none());
+        assert_eq!(fork.hits(), 0);
+    }
+}
@@ -12,6 +12,7 @@ use std::sync::Arc;
 use tokio::sync::{oneshot, Mutex};
 use tokio::task::JoinHandle;
 
+use super::named_pipe::NamedPipes;
 use super::stream::BoundedStream;
 use crate::interpreter::ExecResult;
 
@@ -250,7 +251,8 @@ impl Job {
         } else {
             result.text_out()
         };
-        if !is_bytes && text.is_empty() && result.err.is_empty() {
+        let stderr = result.stderr_text();
+        if !is_bytes && text.is_empty() && stderr.is_empty() {
             return None;
         }
 
@@ -293,10 +295,10 @@ impl Job {
             }
         }
 
-        if !result.err.is_empty() {
+        if !stderr.is_empty() {
             content.push_str("\n## STDERR\n");
-            content.push_str(&result.err);
-            if !result.err.ends_with('\n') {
+            content.push_str(&stderr);
+            if !stderr.ends_with('\n') {
                 content.push('\n');
             }
         }
@@ -456,6 +458,8 @@ pub struct JobManager {
     /// [`set_persist_output_files`](Self::set_persist_output_files)). Stamped
     /// onto each [`Job`] at registration.
     persist_output_files: std::sync::atomic::AtomicBool,
+    /// Named pipes made with `pipe create`, served at `/v/pipes`.
+    pipes: NamedPipes,
 }
 
 impl JobManager {
@@ -485,9 +489,15 @@ impl JobManager {
             next_id: AtomicU64::new(1),
             jobs: Arc::new(Mutex::new(HashMap::new())),
             persist_output_files: std::sync::atomic::AtomicBool::new(true),
+            pipes: NamedPipes::new(),
         }
     }
 
+    /// The session's named pipes (see [`NamedPipes`]).
+    pub fn pipes(&self) -> &NamedPipes {
+        &self.pipes
+    }
+
     /// Toggle whether completed jobs persist their output to a host temp file.
     ///
     /// Disable this for a hermetic / read-only kernel: the host write in
@@ -643,6 +653,12 @@ impl JobManager {
             .collect()
     }
 
+    /// Jobs registered with this manager since it was created, finished and
+    /// reaped ones included.
+    pub fn started_count(&self) -> u64 {
+        self.next_id.load(Ordering::SeqCst).saturating_sub(1)
+    }
+
     /// Get the number of running jobs.
     pub async fn running_count(&self) -> usize {
         let mut jobs = self.jobs.lock().await;
@@ -47,6 +47,7 @@
 //! ```
 
 mod job;
+mod named_pipe;
 mod pipe_stream;
 pub(crate) mod pipeline;
 mod scatter;
@@ -54,6 +55,7 @@ mod stderr_stream;
 mod stream;
 
 pub use job::{Job, JobId, JobInfo, JobManager, JobStatus};
+pub use named_pipe::{NamedPipes, PipeInfo};
 pub use pipe_stream::{pipe_stream, pipe_stream_default, PipeReader, PipeWriter, PIPE_BUFFER_SIZE};
 pub use stderr_stream::{stderr_stream, StderrReceiver, StderrStream};
 pub use stream::{drain_to_stream, BoundedStream, StreamStats, DEFAULT_STREAM_MAX_SIZE};
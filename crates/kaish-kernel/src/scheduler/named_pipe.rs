@@ -0,0 +1,222 @@
+//! Named pipes — kaish's stand-in for process substitution.
+//!
+//! `pipe create NAME` makes `/v/pipes/NAME`. One command writes into it with
+//! a redirect (`producer > /v/pipes/NAME &`) and another reads it back
+//! (`consumer /v/pipes/NAME` or `consumer < /v/pipes/NAME`). A read waits
+//! until a write has landed, then takes everything buffered, so the reader
+//! may start before or after the writer. Writes never truncate: each one
+//! queues behind whatever is still unread.
+//!
+//! The table lives on the [`JobManager`](super::JobManager), which the kernel
+//! shares with every fork, so background jobs and scatter workers see the
+//! same pipes as the session that made them.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use tokio::sync::Notify;
+
+/// One pipe's queued bytes and lifecycle.
+#[derive(Default)]
+struct PipeState {
+    buffer: Vec<u8>,
+    /// A write has landed since the last read took the buffer. Tracked apart
+    /// from `buffer.is_empty()` so an empty write still wakes the reader.
+    ready: bool,
+    /// Removed while a reader may still be waiting.
+    removed: bool,
+}
+
+#[derive(Default)]
+struct NamedPipe {
+    state: Mutex<PipeState>,
+    wake: Notify,
+}
+
+impl NamedPipe {
+    fn state(&self) -> MutexGuard<'_, PipeState> {
+        // A poisoned lock only means a panic mid-update of plain bytes and
+        // flags; the data is still usable.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A pipe as `pipe list` and `ls /v/pipes` report it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipeInfo {
+    pub name: String,
+    /// Bytes written and not yet read.
+    pub buffered: usize,
+}
+
+/// The session's named pipes.
+#[derive(Default)]
+pub struct NamedPipes {
+    pipes: Mutex<BTreeMap<String, Arc<NamedPipe>>>,
+}
+
+impl NamedPipes {
+    /// Create an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn table(&self) -> MutexGuard<'_, BTreeMap<String, Arc<NamedPipe>>> {
+        self.pipes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn get(&self, name: &str) -> io::Result<Arc<NamedPipe>> {
+        self.table()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no pipe named '{name}'")))
+    }
+
+    /// Make a new pipe. Names are single path components.
+    pub fn create(&self, name: &str) -> io::Result<()> {
+        if !is_pipe_name(name) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid pipe name '{name}': use a single name without '/'"),
+            ));
+        }
+        let mut table = self.table();
+        if table.contains_key(name) {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("pipe '{name}' already exists")));
+        }
+        table.insert(name.to_string(), Arc::new(NamedPipe::default()));
+        Ok(())
+    }
+
+    /// Remove a pipe. A reader still waiting on it fails with `NotFound`.
+    pub fn remove(&self, name: &str) -> io::Result<()> {
+        let pipe = self
+            .table()
+            .remove(name)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no pipe named '{name}'")))?;
+        pipe.state().removed = true;
+        pipe.wake.notify_waiters();
+        Ok(())
+    }
+
+    /// Whether a pipe exists.
+    pub fn exists(&self, name: &str) -> bool {
+        self.table().contains_key(name)
+    }
+
+    /// Every pipe, by name.
+    pub fn list(&self) -> Vec<PipeInfo> {
+        self.table()
+            .iter()
+            .map(|(name, pipe)| PipeInfo { name: name.clone(), buffered: pipe.state().buffer.len() })
+            .collect()
+    }
+
+    /// One pipe's entry, without waiting on it.
+    pub fn info(&self, name: &str) -> io::Result<PipeInfo> {
+        let pipe = self.get(name)?;
+        let buffered = pipe.state().buffer.len();
+        Ok(PipeInfo { name: name.to_string(), buffered })
+    }
+
+    /// Queue `data` for the next read and wake any waiting reader.
+    pub fn write(&self, name: &str, data: &[u8]) -> io::Result<()> {
+        let pipe = self.get(name)?;
+        {
+            let mut state = pipe.state();
+            state.buffer.extend_from_slice(data);
+            state.ready = true;
+        }
+        pipe.wake.notify_waiters();
+        Ok(())
+    }
+
+    /// Wait for a write, then take everything buffered.
+    pub async fn read(&self, name: &str) -> io::Result<Vec<u8>> {
+        let pipe = self.get(name)?;
+        loop {
+            // Register for the wake-up before checking, so a write landing
+            // between the check and the await is not missed.
+            let notified = pipe.wake.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            {
+                let mut state = pipe.state();
+                if state.removed {
+                    return Err(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("pipe '{name}' was removed"),
+                    ));
+                }
+                if state.ready {
+                    state.ready = false;
+                    return Ok(std::mem::take(&mut state.buffer));
+                }
+            }
+            notified.await;
+        }
+    }
+}
+
+/// A pipe name is one non-empty path component.
+fn is_pipe_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains('/')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn read_waits_for_a_write() {
+        let pipes = Arc::new(NamedPipes::new());
+        pipes.create("p").unwrap();
+
+        let reader = tokio::spawn({
+            let pipes = pipes.clone();
+            async move { pipes.read("p").await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!reader.is_finished());
+
+        pipes.write("p", b"one\n").unwrap();
+        assert_eq!(reader.await.unwrap().unwrap(), b"one\n");
+        assert_eq!(pipes.info("p").unwrap().buffered, 0);
+    }
+
+    #[tokio::test]
+    async fn writes_queue_until_read() {
+        let pipes = NamedPipes::new();
+        pipes.create("p").unwrap();
+        pipes.write("p", b"a").unwrap();
+        pipes.write("p", b"b").unwrap();
+        assert_eq!(pipes.list(), [PipeInfo { name: "p".into(), buffered: 2 }]);
+        assert_eq!(pipes.read("p").await.unwrap(), b"ab");
+    }
+
+    #[tokio::test]
+    async fn remove_wakes_a_waiting_reader() {
+        let pipes = Arc::new(NamedPipes::new());
+        pipes.create("p").unwrap();
+        let reader = tokio::spawn({
+            let pipes = pipes.clone();
+            async move { pipes.read("p").await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        pipes.remove("p").unwrap();
+        let err = reader.await.unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(!pipes.exists("p"));
+    }
+
+    #[test]
+    fn names_are_checked() {
+        let pipes = NamedPipes::new();
+        assert_eq!(pipes.create("a/b").unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        pipes.create("a").unwrap();
+        assert_eq!(pipes.create("a").unwrap_err().kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(pipes.write("missing", b"x").unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -21,6 +21,22 @@ use super::scatter::{
     parse_gather_options, parse_scatter_options, ScatterGatherRunner,
 };
 
+/// Whether a redirect reshapes what the stage sends down the pipe: stdout
+/// going elsewhere (`> f`, `&> f`, `>&2`), or stderr joining it (`2>&1`),
+/// which [`apply_redirects`] only folds in after the command returns.
+fn reshapes_pipe_output(redirects: &[Redirect]) -> bool {
+    redirects.iter().any(|r| {
+        matches!(
+            r.kind,
+            RedirectKind::StdoutOverwrite
+                | RedirectKind::StdoutAppend
+                | RedirectKind::Both
+                | RedirectKind::MergeStdout
+                | RedirectKind::MergeStderr
+        )
+    })
+}
+
 /// Apply redirects to an execution result.
 ///
 /// Pre-execution redirects (Stdin, HereDoc) should be handled before calling.
@@ -39,11 +55,11 @@ pub(crate) async fn apply_redirects(
     for redir in redirects {
         match redir.kind {
             RedirectKind::MergeStderr => {
-                // 2>&1 - append stderr to stdout
+                // 2>&1 - append stderr (stream, then message) to stdout
                 // Ensure output is materialized for merge
                 result.materialize();
-                if !result.err.is_empty() {
-                    let err = std::mem::take(&mut result.err);
+                let err = result.take_stderr_text();
+                if !err.is_empty() {
                     result.push_out(&err);
                 }
             }
@@ -61,7 +77,7 @@ pub(crate) async fn apply_redirects(
                 result.materialize();
                 if !result.text_out().is_empty() {
                     let out = result.text_out().into_owned();
-                    result.err.push_str(&out);
+                    result.push_stderr(&out);
                 }
                 // `1>&2` is still a stdout redirect: stdout went to stderr, so
                 // drop out/output AND the .data sideband (same as a file
@@ -126,10 +142,10 @@ pub(crate) async fn apply_redirects(
                     Ok(p) => p,
                     Err(e) => return ExecResult::failure(1, format!("redirect: {e}")),
                 };
-                if let Err(e) = redirect_write(ctx, &path, result.err.as_bytes()).await {
+                if let Err(e) = redirect_write(ctx, &path, result.stderr_text().as_bytes()).await {
                     return ExecResult::failure(1, format!("redirect: {e}"));
                 }
-                result.err.clear();
+                result.clear_stderr();
             }
             RedirectKind::Both => {
                 let path = match eval_redirect_target(&redir.target, ctx, dispatcher).await {
@@ -154,13 +170,13 @@ pub(crate) async fn apply_redirects(
                 } else {
                     result.text_out().into_owned().into_bytes()
                 };
-                combined.extend_from_slice(result.err.as_bytes());
+                combined.extend_from_slice(result.stderr_text().as_bytes());
                 if let Err(e) = redirect_write(ctx, &path, &combined).await {
                     return ExecResult::failure(1, format!("redirect: {e}"));
                 }
                 // both streams went to the file: drop stdout (incl. .data) + stderr.
                 result.clear_stdout();
-                result.err.clear();
+                result.clear_stderr();
             }
             // Pre-execution redirects - already handled before command execution
             RedirectKind::Stdin | RedirectKind::HereDoc | RedirectKind::HereString => {}
@@ -514,9 +530,20 @@ impl PipelineRunner {
                 // Structured data received via oneshot (resolved at start of execution)
             }
 
-            // Wire pipe_stdout: last stage writes to ExecResult, others write to pipe
+            // Wire pipe_stdout: last stage writes to ExecResult, others write to pipe.
+            // A stage whose stdout is redirected away (`> f`, `&> f`, `>&2`)
+            // must not stream into the pipe behind the redirect's back, and
+            // one with `2>&1` needs the pipe after it returns to send the
+            // merged stderr. Either way the runner holds its writer and
+            // forwards whatever the redirects left.
+            let mut held_pipe_out = None;
             if i < last_idx {
-                stage_ctx.pipe_stdout = pipe_writers[i].take();
+                let writer = pipe_writers[i].take();
+                if reshapes_pipe_output(&cmd.redirects) {
+                    held_pipe_out = writer;
+                } else {
+                    stage_ctx.pipe_stdout = writer;
+                }
             }
 
             // Set pipeline position
@@ -553,6 +580,13 @@ impl PipelineRunner {
                     Err(e) => ExecResult::failure(1, e.to_string()),
                 };
 
+                // Close our end of the upstream pipe now. A stage that stopped
+                // reading early (`head`) or never read at all (`echo`) hands
+                // its reader back through `stage_ctx`, which outlives this task
+                // until the join below — holding it would leave the upstream
+                // writer parked on a full pipe instead of seeing broken pipe.
+                stage_ctx.pipe_stdin = None;
+
                 // Apply post-execution redirects. Use the stage's own
                 // (forked) dispatcher — the borrowed `dispatcher` can't cross
                 // the spawn boundary, and `stage_ctx.dispatcher` is `None` on a
@@ -562,12 +596,22 @@ impl PipelineRunner {
                 // Flush buffered stderr to the kernel's stderr stream.
                 // This delivers error output from intermediate pipeline stages
                 // in real-time (via the kernel drain) instead of silently discarding it.
-                // Redirects like 2>&1 have already cleared result.err, so merged
-                // stderr goes through the pipe as expected.
-                if !result.err.is_empty() {
-                    if let Some(ref stderr) = stage_ctx.stderr {
-                        stderr.write_str(&result.err);
-                        result.err.clear();
+                // Redirects like 2>&1 have already cleared result.stderr/err, so
+                // merged stderr goes through the pipe as expected. An
+                // intermediate stage's error message is only ever seen on fd 2,
+                // so it flushes too; the last stage keeps its `.err` — that is
+                // the pipeline's own failure message — and flushes only its
+                // stream so ordering with upstream stderr is preserved.
+                if let Some(ref stderr) = stage_ctx.stderr {
+                    if matches!(stage_ctx.pipeline_position, PipelinePosition::Last) {
+                        if !result.stderr.is_empty() {
+                            stderr.write_str(&std::mem::take(&mut result.stderr));
+                        }
+                    } else {
+                        let text = result.take_stderr_text();
+                        if !text.is_empty() {
+                            stderr.write_str(&text);
+                        }
                     }
                 }
 
@@ -582,7 +626,7 @@ impl PipelineRunner {
 
                 // Write output to pipe for next stage (if not last).
                 // Consumer is now unblocked and can drain concurrently.
-                if let Some(mut pipe_out) = stage_ctx.pipe_stdout.take() {
+                if let Some(mut pipe_out) = stage_ctx.pipe_stdout.take().or(held_pipe_out) {
                     // A binary result flows through the pipe as raw bytes;
                     // structured output serializes straight to a byte buffer
                     // (`write_canonical`) rather than building the full
@@ -646,6 +690,7 @@ impl PipelineRunner {
                         ctx.scope = stage_ctx.scope;
                         ctx.cwd = stage_ctx.cwd;
                         ctx.prev_cwd = stage_ctx.prev_cwd;
+                        ctx.dir_stack = stage_ctx.dir_stack;
                         ctx.aliases = stage_ctx.aliases;
                     }
                 }
@@ -916,11 +961,8 @@ pub(crate) fn eval_simple_expr(expr: &Expr, ctx: &ExecContext) -> Result<Option<
         Expr::VarLength(path) => {
             crate::interpreter::resolve_length(&ctx.scope, path).map(|n| Some(Value::Int(n)))
         }
-        Expr::VarWithDefault { path, default } => {
-            match crate::interpreter::resolve_default(&ctx.scope, path)? {
-                Some(value) => Ok(Some(value)),
-                None => Ok(Some(Value::String(eval_string_parts_sync(default, ctx)?))),
-            }
+        Expr::VarWithDefault { path, op, default } => {
+            eval_var_with_default_sync(path, *op, default, ctx).map(Some)
         }
         Expr::GlobPattern(s) => Ok(Some(Value::String(s.clone()))),
         // Bare arithmetic expansion (`scatter --limit $((1+1))`) — mirrors
@@ -969,6 +1011,31 @@ fn eval_literal(value: &Value, _ctx: &ExecContext) -> Value {
     value.clone()
 }
 
+/// `${path<op>word}` for the reduced evaluator, over the same shared decision
+/// as the async path. This evaluator only reads the scope, so `:=` can't
+/// assign here — loud rather than a default that silently never sticks.
+fn eval_var_with_default_sync(
+    path: &crate::ast::VarPath,
+    op: crate::ast::DefaultOp,
+    default: &[crate::ast::StringPart],
+    ctx: &ExecContext,
+) -> Result<Value, String> {
+    use crate::interpreter::DefaultAction;
+    match crate::interpreter::resolve_default_op(&ctx.scope, path, op)? {
+        DefaultAction::Value(value) => Ok(value),
+        DefaultAction::Word => Ok(Value::String(eval_string_parts_sync(default, ctx)?)),
+        DefaultAction::Assign => Err(format!(
+            "{}: `:=` can't assign here; set the variable first or use `:-`",
+            crate::interpreter::format_path(path)
+        )),
+        DefaultAction::Fail => Err(crate::interpreter::unset_error_message(
+            path,
+            &eval_string_parts_sync(default, ctx)?,
+        )),
+        DefaultAction::Empty => Ok(Value::String(String::new())),
+    }
+}
+
 /// Evaluate string parts synchronously (for pipeline context).
 ///
 /// Command substitutions are skipped as they require async. A [`PathError`]
@@ -998,13 +1065,9 @@ fn eval_string_parts_sync(parts: &[crate::ast::StringPart], ctx: &ExecContext) -
                 Err(PathError::UndefinedRoot(_)) => {}
                 Err(PathError::Absence(msg)) | Err(PathError::Shape(msg)) => return Err(msg),
             },
-            crate::ast::StringPart::VarWithDefault { path, default } => {
-                match crate::interpreter::resolve_default(&ctx.scope, path)? {
-                    Some(value) => result.push_str(
-                        &crate::interpreter::value_to_text_sink(&value).map_err(|e| e.to_string())?,
-                    ),
-                    None => result.push_str(&eval_string_parts_sync(default, ctx)?),
-                }
+            crate::ast::StringPart::VarWithDefault { path, op, default } => {
+                let value = eval_var_with_default_sync(path, *op, default, ctx)?;
+                result.push_str(&crate::interpreter::value_to_text_sink(&value).map_err(|e| e.to_string())?);
             }
             crate::ast::StringPart::VarLength(path) => {
                 // Element/key count for collections, byte count for binary;
@@ -1204,6 +1267,7 @@ mod select_leaf_tests {
                 name: "echo".into(),
                 args: vec![],
                 redirects: vec![],
+                span: None,
             }),
         ]))];
         let err = select_leaf(&schema, &args).expect_err("must error");
@@ -1229,7 +1293,7 @@ mod select_leaf_tests {
             word("context"),
             word("list"),
             Arg::Positional(Expr::CommandSubst(vec![crate::ast::Stmt::Command(
-                crate::ast::Command { name: "echo".into(), args: vec![], redirects: vec![] },
+                crate::ast::Command { name: "echo".into(), args: vec![], redirects: vec![], span: None },
             )])),
         ];
         let leaf = select_leaf(&schema, &args).expect("ok");
@@ -1266,6 +1330,7 @@ mod tests {
             name: name.to_string(),
             args: args.iter().map(|s| Arg::Positional(Expr::Literal(Value::String(s.to_string())))).collect(),
             redirects: vec![],
+            span: None,
         }
     }
 
@@ -1288,11 +1353,13 @@ mod tests {
             name: "echo".to_string(),
             args: vec![Arg::Positional(Expr::Literal(Value::String("hello\nworld".to_string())))],
             redirects: vec![],
+            span: None,
         };
         let grep_cmd = Command {
             name: "grep".to_string(),
             args: vec![Arg::Positional(Expr::Literal(Value::String("world".to_string())))],
             redirects: vec![],
+            span: None,
         };
 
         let result = runner.run(&[echo_cmd, grep_cmd], &mut ctx, &dispatcher).await;
@@ -1310,6 +1377,7 @@ mod tests {
             name: "grep".to_string(),
             args: vec![Arg::Positional(Expr::Literal(Value::String("hello".to_string())))],
             redirects: vec![],
+            span: None,
         };
 
         let result = runner.run(&[cat_cmd, grep_cmd], &mut ctx, &dispatcher).await;
@@ -1341,6 +1409,7 @@ mod tests {
             name: "grep".to_string(),
             args: vec![Arg::Positional(Expr::Literal(Value::String("hello".to_string())))],
             redirects: vec![],
+            span: None,
         };
 
         let result = runner.run(&[cat_cmd, grep_cmd], &mut ctx, &dispatcher).await;
@@ -1421,12 +1490,14 @@ mod tests {
             name: "split".to_string(),
             args: vec![Arg::Positional(Expr::Literal(Value::String("a b c".to_string())))],
             redirects: vec![],
+            span: None,
         };
         let scatter_cmd = make_cmd("scatter", vec![]);
         let process_cmd = Command {
             name: "echo".to_string(),
             args: vec![Arg::Positional(Expr::VarRef(crate::ast::VarPath::simple("ITEM")))],
             redirects: vec![],
+            span: None,
         };
         let gather_cmd = make_cmd("gather", vec![]);
 
@@ -1447,12 +1518,14 @@ mod tests {
             name: "echo".to_string(),
             args: vec![Arg::Positional(Expr::Literal(Value::String("".to_string())))],
             redirects: vec![],
+            span: None,
         };
         let scatter_cmd = make_cmd("scatter", vec![]);
         let process_cmd = Command {
             name: "echo".to_string(),
             args: vec![Arg::Positional(Expr::VarRef(crate::ast::VarPath::simple("ITEM")))],
             redirects: vec![],
+            span: None,
         };
         let gather_cmd = make_cmd("gather", vec![]);
 
@@ -1474,6 +1547,7 @@ mod tests {
             name: "echo".to_string(),
             args: vec![Arg::Positional(Expr::VarRef(crate::ast::VarPath::simple("ITEM")))],
             redirects: vec![],
+            span: None,
         };
         let gather_cmd = make_cmd("gather", vec![]);
 
@@ -1497,6 +1571,7 @@ mod tests {
             name: "echo".to_string(),
             args: vec![Arg::Positional(Expr::VarRef(crate::ast::VarPath::simple("ITEM")))],
             redirects: vec![],
+            span: None,
         };
         let gather_cmd = make_cmd("gather", vec![]);
 
@@ -1516,18 +1591,21 @@ mod tests {
             name: "split".to_string(),
             args: vec![Arg::Positional(Expr::Literal(Value::String("a b".to_string())))],
             redirects: vec![],
+            span: None,
         };
         let scatter_cmd = make_cmd("scatter", vec![]);
         let process_cmd = Command {
             name: "echo".to_string(),
             args: vec![Arg::Positional(Expr::VarRef(crate::ast::VarPath::simple("ITEM")))],
             redirects: vec![],
+            span: None,
         };
         let gather_cmd = make_cmd("gather", vec![]);
         let grep_cmd = Command {
             name: "grep".to_string(),
             args: vec![Arg::Positional(Expr::Literal(Value::String("a".to_string())))],
             redirects: vec![],
+            span: None,
         };
 
         let result = runner.run(&[split_cmd, scatter_cmd, process_cmd, gather_cmd, grep_cmd], &mut ctx, &dispatcher).await;
@@ -1552,11 +1630,13 @@ mod tests {
                 value: Expr::Literal(Value::String("URL".to_string())),
             }],
             redirects: vec![],
+            span: None,
         };
         let process_cmd = Command {
             name: "echo".to_string(),
             args: vec![Arg::Positional(Expr::VarRef(crate::ast::VarPath::simple("URL")))],
             redirects: vec![],
+            span: None,
         };
         let gather_cmd = make_cmd("gather", vec![]);
 
@@ -2611,6 +2691,7 @@ mod tests {
                 kind: RedirectKind::MergeStderr,
                 target: Expr::Literal(Value::Null),
             }],
+            span: None,
         };
 
         let result = runner.run(&[cmd], &mut ctx, &dispatcher).await;
@@ -2632,11 +2713,13 @@ mod tests {
                 kind: RedirectKind::MergeStderr,
                 target: Expr::Literal(Value::Null),
             }],
+            span: None,
         };
         let grep_cmd = Command {
             name: "grep".to_string(),
             args: vec![Arg::Positional(Expr::Literal(Value::String("output".to_string())))],
             redirects: vec![],
+            span: None,
         };
 
         let result = runner.run(&[echo_cmd, grep_cmd], &mut ctx, &dispatcher).await;
@@ -13,7 +13,7 @@
 //! then collects all results.
 
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::time::Duration;
 
 use tokio::sync::Semaphore;
@@ -22,7 +22,8 @@ use tracing::Instrument;
 use crate::ast::{Command, Redirect, Value};
 use crate::dispatch::CommandDispatcher;
 use crate::duration::parse_duration;
-use crate::interpreter::ExecResult;
+use crate::hooks::Progress;
+use crate::interpreter::{exit_code, ExecResult};
 use crate::tools::{ExecContext, ToolRegistry};
 
 use super::pipeline::{apply_redirects, PipelineRunner};
@@ -246,6 +247,11 @@ impl ScatterGatherRunner {
         let tools = self.tools.clone();
         let var_name = opts.var_name.clone();
 
+        // Workers report "N of M items done" as they finish, in completion
+        // order, through the embedder's progress hooks.
+        let finished = Arc::new(AtomicUsize::new(0));
+        let total = items.len();
+
         // Spawn parallel tasks
         let mut handles = Vec::with_capacity(items.len());
 
@@ -303,6 +309,7 @@ impl ScatterGatherRunner {
                 })
             });
             let timed_out_check = timed_out_flag.clone();
+            let finished = finished.clone();
 
             let worker_span = tracing::debug_span!("scatter_worker", item = %item.label);
             // Propagate the embedder's trace context across the spawn boundary so
@@ -356,6 +363,12 @@ impl ScatterGatherRunner {
                 // matter what the racing flag says.
                 let timed_out = timed_out_check.load(Ordering::SeqCst) && !result.ok();
 
+                let done = finished.fetch_add(1, Ordering::SeqCst) + 1;
+                worker_ctx.hooks.progress(
+                    "scatter",
+                    &Progress::steps(done, total).with_message(format!("{done}/{total} items")),
+                );
+
                 ScatterResult { item, result, timed_out }
             }.instrument(worker_span)));
 
@@ -502,12 +515,12 @@ fn strip_one_trailing_newline(s: &str) -> &str {
 /// `docs/binary-data.md` for the broader binary-data plan.
 fn result_row(i: usize, r: &ScatterResult) -> serde_json::Value {
     let mut ok = r.result.ok() && !r.timed_out;
-    let mut code = if r.timed_out { 124 } else { r.result.code };
+    let mut code = if r.timed_out { exit_code::TIMEOUT } else { r.result.code };
 
     let (out_text, err_text) = match r.result.try_text_out() {
         Ok(text) => (
             strip_one_trailing_newline(&text).to_string(),
-            strip_one_trailing_newline(&r.result.err).to_string(),
+            strip_one_trailing_newline(&r.result.stderr_text()).to_string(),
         ),
         Err(e) => {
             ok = false;
@@ -1334,6 +1347,7 @@ mod tests {
             name: "sleep".to_string(),
             args: vec![Arg::Positional(Expr::Literal(Value::String("0.02".to_string())))],
             redirects: vec![],
+            span: None,
         }];
         let opts = ScatterOptions {
             timeout: Some(Duration::from_millis(20)),
@@ -0,0 +1,253 @@
+//! Structured breadcrumbs left by scripts with the `log` builtin.
+//!
+//! Each `log` call emits a `tracing` event (target `kaish::log`, so it lands
+//! in whatever subscriber or OTLP exporter the embedder installed, inside the
+//! command's span) and appends a [`LogRecord`] to the session's
+//! [`LogBuffer`]. The buffer keeps the newest [`DEFAULT_LOG_CAPACITY`]
+//! records and is served read-only at `/v/logs`:
+//!
+//! ```text
+//! /v/logs/
+//! ├── kaish.log     ← one line per record: time, level, message, key=value
+//! └── kaish.jsonl   ← one JSON object per record
+//! ```
+//!
+//! The buffer lives on the `ExecContext` and is Arc-shared like the result
+//! cache, so records from pipeline stages and background jobs land in it too.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+
+/// How many records a session keeps before dropping the oldest.
+pub const DEFAULT_LOG_CAPACITY: usize = 1000;
+
+/// Severity of a log record, matching `tracing`'s levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    /// Every level, least to most severe.
+    pub const ALL: [LogLevel; 5] = [LogLevel::Trace, LogLevel::Debug, LogLevel::Info, LogLevel::Warn, LogLevel::Error];
+
+    /// The level named `name` (case-insensitive; `warning` is `warn`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        if name == "warning" {
+            return Some(LogLevel::Warn);
+        }
+        Self::ALL.into_iter().find(|level| level.as_str() == name)
+    }
+
+    /// The level's lowercase name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Trace => "trace",
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One `log` call.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogRecord {
+    /// When it was logged, RFC 3339 in UTC.
+    pub timestamp: String,
+    pub level: LogLevel,
+    pub message: String,
+    /// The call's `key=value` fields.
+    pub fields: BTreeMap<String, String>,
+}
+
+impl LogRecord {
+    /// A record stamped with the current time.
+    pub fn now(level: LogLevel, message: impl Into<String>, fields: BTreeMap<String, String>) -> Self {
+        Self {
+            timestamp: chrono::DateTime::<chrono::Utc>::from(kaish_types::clock::system_now())
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            level,
+            message: message.into(),
+            fields,
+        }
+    }
+
+    /// The fields as `key=value` words, quoting values with spaces.
+    pub fn fields_text(&self) -> String {
+        self.fields
+            .iter()
+            .map(|(key, value)| {
+                if value.is_empty() || value.contains(char::is_whitespace) {
+                    format!("{key}={value:?}")
+                } else {
+                    format!("{key}={value}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The record as one `kaish.log` line, without the newline.
+    pub fn text_line(&self) -> String {
+        let level = self.level.as_str().to_ascii_uppercase();
+        let mut line = format!("{} {level:<5} {}", self.timestamp, self.message);
+        if !self.fields.is_empty() {
+            line.push(' ');
+            line.push_str(&self.fields_text());
+        }
+        line
+    }
+}
+
+#[derive(Debug)]
+struct Inner {
+    records: VecDeque<LogRecord>,
+    capacity: usize,
+}
+
+/// Shared ring buffer of the session's log records.
+#[derive(Clone, Debug)]
+pub struct LogBuffer {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOG_CAPACITY)
+    }
+}
+
+impl LogBuffer {
+    /// An empty buffer keeping at most `capacity` records (at least one).
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            inner: Arc::new(Mutex::new(Inner { records: VecDeque::new(), capacity })),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Append `record`, dropping the oldest once the buffer is full.
+    pub fn push(&self, record: LogRecord) {
+        let mut inner = self.lock();
+        if inner.records.len() == inner.capacity {
+            inner.records.pop_front();
+        }
+        inner.records.push_back(record);
+    }
+
+    /// The records held, oldest first.
+    pub fn records(&self) -> Vec<LogRecord> {
+        self.lock().records.iter().cloned().collect()
+    }
+
+    /// How many records are held.
+    pub fn len(&self) -> usize {
+        self.lock().records.len()
+    }
+
+    /// Whether no records are held.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop every record. Returns how many were dropped.
+    pub fn clear(&self) -> usize {
+        let mut inner = self.lock();
+        let dropped = inner.records.len();
+        inner.records.clear();
+        dropped
+    }
+
+    /// The `kaish.log` rendering: one line per record.
+    pub fn to_text(&self) -> String {
+        self.lock().records.iter().map(|record| record.text_line() + "\n").collect()
+    }
+
+    /// The `kaish.jsonl` rendering: one JSON object per line.
+    pub fn to_jsonl(&self) -> String {
+        self.lock()
+            .records
+            .iter()
+            .filter_map(|record| serde_json::to_string(record).ok())
+            .map(|line| line + "\n")
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(message: &str) -> LogRecord {
+        LogRecord {
+            timestamp: "2026-01-01T00:00:00.000Z".into(),
+            level: LogLevel::Info,
+            message: message.into(),
+            fields: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn levels_parse_by_name() {
+        for level in LogLevel::ALL {
+            assert_eq!(LogLevel::from_name(level.as_str()), Some(level));
+        }
+        assert_eq!(LogLevel::from_name("WARNING"), Some(LogLevel::Warn));
+        assert_eq!(LogLevel::from_name("loud"), None);
+    }
+
+    #[test]
+    fn buffer_keeps_the_newest_records() {
+        let buffer = LogBuffer::new(2);
+        let fork = buffer.clone();
+        for message in ["a", "b", "c"] {
+            fork.push(record(message));
+        }
+        let messages: Vec<String> = buffer.records().into_iter().map(|r| r.message).collect();
+        assert_eq!(messages, ["b", "c"]);
+        assert_eq!(buffer.clear(), 2);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn renders_text_and_jsonl() {
+        let buffer = LogBuffer::default();
+        let mut entry = record("deployed");
+        entry.fields.insert("env".into(), "prod".into());
+        entry.fields.insert("note".into(), "two words".into());
+        buffer.push(entry);
+        assert_eq!(
+            buffer.to_text(),
+            "2026-01-01T00:00:00.000Z INFO  deployed env=prod note=\"two words\"\n"
+        );
+        assert_eq!(
+            buffer.to_jsonl(),
+            concat!(
+                r#"{"timestamp":"2026-01-01T00:00:00.000Z","level":"info","message":"deployed","#,
+                r#""fields":{"env":"prod","note":"two words"}}"#,
+                "\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,531 @@
+//! Portable snapshot of a kernel session: export, import.
+//!
+//! [`KernelState`] is the JSON document `kaish-state export` prints and
+//! `kaish-state import` reads back: variables and the export set, cwd, the
+//! session's checkpoints, and the mount table. It moves a session between
+//! kernels — another process, another machine — or backs one up before a
+//! risky step.
+//!
+//! Mounts are recorded but not recreated on import: a kernel's mount table is
+//! fixed by its `KernelConfig` when it is built. Import reports exported
+//! mounts the target kernel lacks (see [`KernelState::missing_mounts`]) so a
+//! caller can tell its paths may not resolve. Functions, aliases, and shell
+//! options are not part of the state.
+//!
+//! Variables are written through [`typed_vars`], which tags each value with
+//! its type so every variant comes back exactly: structured `Json` (objects
+//! and arrays, and JSON scalars), `Bytes`, and non-finite floats included.
+//!
+//! [`KernelState::diff`] compares two states — two exports, or a checkpoint
+//! against the live session — and reports what changed as a [`StateDiff`].
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::ast::Value;
+use crate::checkpoint::{Checkpoint, CheckpointStore};
+use crate::interpreter::Scope;
+use crate::vfs::MountInfo;
+
+/// Version of the [`KernelState`] document. Bumped on incompatible changes;
+/// import refuses documents from a newer version.
+pub const STATE_FORMAT_VERSION: u32 = 1;
+
+/// One entry of the mount table, as exported.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MountState {
+    /// Mount point.
+    pub path: PathBuf,
+    /// Whether the mount is read-only.
+    pub read_only: bool,
+}
+
+/// Everything `kaish-state export` captures.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KernelState {
+    /// Document version ([`STATE_FORMAT_VERSION`] when written).
+    pub version: u32,
+    /// Every variable (inner frames shadowing outer ones).
+    #[serde(with = "typed_vars")]
+    pub vars: BTreeMap<String, Value>,
+    /// Names marked for export.
+    pub exported: Vec<String>,
+    /// Working directory.
+    pub cwd: PathBuf,
+    /// Mount table (informational on import).
+    #[serde(default)]
+    pub mounts: Vec<MountState>,
+    /// Session checkpoints.
+    #[serde(default)]
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+impl KernelState {
+    /// Capture a session.
+    pub fn capture(scope: &Scope, cwd: &Path, mounts: &[MountInfo], checkpoints: &CheckpointStore) -> Self {
+        Self {
+            version: STATE_FORMAT_VERSION,
+            vars: scope.all().into_iter().collect(),
+            exported: scope.exported_names().into_iter().map(String::from).collect(),
+            cwd: cwd.to_path_buf(),
+            mounts: mounts
+                .iter()
+                .map(|m| MountState {
+                    path: m.path.clone(),
+                    read_only: m.read_only,
+                })
+                .collect(),
+            checkpoints: checkpoints.list(),
+        }
+    }
+
+    /// Serialize as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("serializing kernel state")
+    }
+
+    /// Parse a document written by [`Self::to_json`].
+    pub fn from_json(text: &str) -> Result<Self> {
+        let state: Self = serde_json::from_str(text).context("parsing kernel state")?;
+        if state.version > STATE_FORMAT_VERSION {
+            bail!(
+                "kernel state version {} is newer than this kaish understands ({})",
+                state.version,
+                STATE_FORMAT_VERSION
+            );
+        }
+        Ok(state)
+    }
+
+    /// Put the variables and checkpoints back. Variables replace the
+    /// session's wholesale; the checkpoint list is replaced too. The cwd is
+    /// the caller's to restore — it lives on the `ExecContext`.
+    pub fn restore_into(&self, scope: &mut Scope, checkpoints: &CheckpointStore) {
+        scope.replace_variables(
+            self.vars.iter().map(|(name, value)| (name.clone(), value.clone())),
+            self.exported.iter().cloned(),
+        );
+        checkpoints.replace_all(self.checkpoints.clone());
+    }
+
+    /// A state built from a checkpoint. Checkpoints don't record mounts, so
+    /// the caller supplies the table to compare against (normally the live
+    /// one — mounts can't change after construction anyway).
+    pub fn from_checkpoint(checkpoint: &Checkpoint, mounts: &[MountInfo]) -> Self {
+        Self {
+            version: STATE_FORMAT_VERSION,
+            vars: checkpoint.vars.clone(),
+            exported: checkpoint.exported.clone(),
+            cwd: checkpoint.cwd.clone(),
+            mounts: mounts
+                .iter()
+                .map(|m| MountState {
+                    path: m.path.clone(),
+                    read_only: m.read_only,
+                })
+                .collect(),
+            checkpoints: Vec::new(),
+        }
+    }
+
+    /// What changed going from `self` to `newer`. Checkpoint lists are not
+    /// compared.
+    pub fn diff(&self, newer: &KernelState) -> StateDiff {
+        let mut changes = Vec::new();
+
+        for (name, old) in &self.vars {
+            match newer.vars.get(name) {
+                None => changes.push(StateChange::removed(ChangeKind::Var, name, Some(old.clone()))),
+                Some(new) if new != old => changes.push(StateChange {
+                    kind: ChangeKind::Var,
+                    change: ChangeType::Changed,
+                    name: name.clone(),
+                    old: Some(old.clone()),
+                    new: Some(new.clone()),
+                }),
+                Some(_) => {}
+            }
+        }
+        for (name, new) in &newer.vars {
+            if !self.vars.contains_key(name) {
+                changes.push(StateChange::added(ChangeKind::Var, name, Some(new.clone())));
+            }
+        }
+
+        for name in &self.exported {
+            if !newer.exported.contains(name) {
+                changes.push(StateChange::removed(ChangeKind::Export, name, None));
+            }
+        }
+        for name in &newer.exported {
+            if !self.exported.contains(name) {
+                changes.push(StateChange::added(ChangeKind::Export, name, None));
+            }
+        }
+
+        if self.cwd != newer.cwd {
+            changes.push(StateChange {
+                kind: ChangeKind::Cwd,
+                change: ChangeType::Changed,
+                name: "cwd".to_string(),
+                old: Some(Value::String(self.cwd.display().to_string())),
+                new: Some(Value::String(newer.cwd.display().to_string())),
+            });
+        }
+
+        for mount in &self.mounts {
+            match newer.mounts.iter().find(|m| m.path == mount.path) {
+                None => changes.push(StateChange::removed(ChangeKind::Mount, mount.path.display(), None)),
+                Some(m) if m.read_only != mount.read_only => changes.push(StateChange {
+                    kind: ChangeKind::Mount,
+                    change: ChangeType::Changed,
+                    name: mount.path.display().to_string(),
+                    old: Some(Value::String(access(mount.read_only).to_string())),
+                    new: Some(Value::String(access(m.read_only).to_string())),
+                }),
+                Some(_) => {}
+            }
+        }
+        for mount in &newer.mounts {
+            if !self.mounts.iter().any(|m| m.path == mount.path) {
+                changes.push(StateChange::added(ChangeKind::Mount, mount.path.display(), None));
+            }
+        }
+
+        StateDiff { changes }
+    }
+
+    /// Exported mount points that `current` lacks.
+    pub fn missing_mounts(&self, current: &[MountInfo]) -> Vec<PathBuf> {
+        self.mounts
+            .iter()
+            .filter(|m| !current.iter().any(|c| c.path == m.path))
+            .map(|m| m.path.clone())
+            .collect()
+    }
+}
+
+/// Serde adapter for variable maps that round-trips every [`Value`] exactly.
+///
+/// Plain `Value` serialization goes through `value_to_json`, which is right
+/// for output but lossy as storage: a `Json` scalar reads back as an `Int` or
+/// `String`, a `NaN` float as the string `"NaN"`, and a record shaped like
+/// the bytes envelope as `Bytes`. Here each value is written as
+/// `{"type": …, "value": …}` instead. Untagged values are still accepted on
+/// read (decoded like any JSON input), so a hand-written state file works.
+pub mod typed_vars {
+    use std::collections::BTreeMap;
+
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::ast::Value;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(tag = "type", content = "value", rename_all = "lowercase")]
+    enum Typed {
+        Null,
+        Bool(bool),
+        Int(i64),
+        /// A number, or `"NaN"` / `"inf"` / `"-inf"`.
+        Float(serde_json::Value),
+        String(String),
+        Json(serde_json::Value),
+        /// Base64.
+        Bytes(String),
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Typed(Typed),
+        Plain(Value),
+    }
+
+    fn to_typed(value: &Value) -> Typed {
+        match value {
+            Value::Null => Typed::Null,
+            Value::Bool(b) => Typed::Bool(*b),
+            Value::Int(i) => Typed::Int(*i),
+            Value::Float(f) => Typed::Float(kaish_types::result::value_to_json(&Value::Float(*f))),
+            Value::String(s) => Typed::String(s.clone()),
+            Value::Json(json) => Typed::Json(json.clone()),
+            Value::Bytes(data) => Typed::Bytes(STANDARD.encode(data)),
+        }
+    }
+
+    fn from_typed<E: serde::de::Error>(typed: Typed) -> Result<Value, E> {
+        Ok(match typed {
+            Typed::Null => Value::Null,
+            Typed::Bool(b) => Value::Bool(b),
+            Typed::Int(i) => Value::Int(i),
+            Typed::Float(json) => Value::Float(match &json {
+                serde_json::Value::Number(n) => n.as_f64(),
+                serde_json::Value::String(s) => s.parse().ok(),
+                _ => None,
+            }
+            .ok_or_else(|| E::custom(format!("invalid float: {json}")))?),
+            Typed::String(s) => Value::String(s),
+            Typed::Json(json) => Value::Json(json),
+            Typed::Bytes(b64) => Value::Bytes(
+                STANDARD
+                    .decode(&b64)
+                    .map_err(|e| E::custom(format!("invalid base64 bytes: {e}")))?,
+            ),
+        })
+    }
+
+    /// Serialize `vars` with a type tag on every value.
+    pub fn serialize<S: Serializer>(vars: &BTreeMap<String, Value>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(vars.iter().map(|(name, value)| (name, to_typed(value))))
+    }
+
+    /// Read a map written by [`serialize`] (or untagged values).
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BTreeMap<String, Value>, D::Error> {
+        BTreeMap::<String, Stored>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(name, stored)| -> Result<(String, Value), D::Error> {
+                let value = match stored {
+                    Stored::Typed(typed) => from_typed::<D::Error>(typed)?,
+                    Stored::Plain(value) => value,
+                };
+                Ok((name, value))
+            })
+            .collect()
+    }
+}
+
+fn access(read_only: bool) -> &'static str {
+    if read_only { "ro" } else { "rw" }
+}
+
+/// What part of the state a [`StateChange`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    /// A variable's value.
+    Var,
+    /// A name's export mark.
+    Export,
+    /// The working directory.
+    Cwd,
+    /// A mount point.
+    Mount,
+}
+
+impl ChangeKind {
+    /// Lowercase name, as serialized.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Var => "var",
+            Self::Export => "export",
+            Self::Cwd => "cwd",
+            Self::Mount => "mount",
+        }
+    }
+}
+
+/// How an item changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeType {
+    /// Present only in the newer state.
+    Added,
+    /// Present only in the older state.
+    Removed,
+    /// Present in both with different values.
+    Changed,
+}
+
+impl ChangeType {
+    /// Lowercase name, as serialized.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Added => "added",
+            Self::Removed => "removed",
+            Self::Changed => "changed",
+        }
+    }
+}
+
+/// One difference between two states.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateChange {
+    /// What the change is about.
+    pub kind: ChangeKind,
+    /// Added, removed, or changed.
+    pub change: ChangeType,
+    /// Variable name, mount point, or `cwd`.
+    pub name: String,
+    /// Value in the older state, where there is one.
+    pub old: Option<Value>,
+    /// Value in the newer state, where there is one.
+    pub new: Option<Value>,
+}
+
+impl StateChange {
+    fn added(kind: ChangeKind, name: impl std::fmt::Display, new: Option<Value>) -> Self {
+        Self { kind, change: ChangeType::Added, name: name.to_string(), old: None, new }
+    }
+
+    fn removed(kind: ChangeKind, name: impl std::fmt::Display, old: Option<Value>) -> Self {
+        Self { kind, change: ChangeType::Removed, name: name.to_string(), old, new: None }
+    }
+}
+
+/// The result of [`KernelState::diff`]: variable changes first, then export
+/// marks, cwd, and mounts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StateDiff {
+    /// Every difference found.
+    pub changes: Vec<StateChange>,
+}
+
+impl StateDiff {
+    /// Whether the two states were the same.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_round_trip() {
+        let mut scope = Scope::new();
+        scope.set_exported("NAME", Value::String("kaish".into()));
+        scope.set("N", Value::Int(3));
+        scope.set("LIST", Value::Json(serde_json::json!([1, 2])));
+        let checkpoints = CheckpointStore::new();
+        checkpoints.create(Some("start".into()), &scope, Path::new("/"), 0);
+        let mounts = vec![MountInfo {
+            path: PathBuf::from("/"),
+            read_only: false,
+            resident_bytes: None,
+            quota_bytes: None,
+        }];
+
+        let state = KernelState::capture(&scope, Path::new("/work"), &mounts, &checkpoints);
+        let back = KernelState::from_json(&state.to_json().expect("to_json")).expect("from_json");
+        assert_eq!(back, state);
+    }
+
+    #[test]
+    fn every_value_variant_round_trips_exactly() {
+        let mut scope = Scope::new();
+        scope.set("OBJ", Value::Json(serde_json::json!({"a": 1, "b": [true, null]})));
+        scope.set("ARR", Value::Json(serde_json::json!([1, "two"])));
+        scope.set("JSON_SCALAR", Value::Json(serde_json::json!(5)));
+        scope.set("LOOKS_LIKE_BYTES", Value::Json(kaish_types::bytes::bytes_to_envelope(b"x")));
+        scope.set("BYTES", Value::Bytes(vec![0, 159, 255]));
+        scope.set("WHOLE_FLOAT", Value::Float(2.0));
+        scope.set("INF", Value::Float(f64::INFINITY));
+        scope.set("NUMERIC_STRING", Value::String("42".into()));
+        scope.set("NOTHING", Value::Null);
+        let state = KernelState::capture(&scope, Path::new("/"), &[], &CheckpointStore::new());
+
+        let back = KernelState::from_json(&state.to_json().expect("to_json")).expect("from_json");
+        assert_eq!(back.vars, state.vars);
+
+        scope.set("NAN", Value::Float(f64::NAN));
+        let state = KernelState::capture(&scope, Path::new("/"), &[], &CheckpointStore::new());
+        let back = KernelState::from_json(&state.to_json().expect("to_json")).expect("from_json");
+        assert!(matches!(back.vars.get("NAN"), Some(Value::Float(f)) if f.is_nan()));
+    }
+
+    #[test]
+    fn untagged_values_are_accepted() {
+        let state = KernelState::from_json(
+            r#"{"version": 1, "vars": {"N": 3, "S": "hi", "O": {"k": [1]}}, "exported": [], "cwd": "/"}"#,
+        )
+        .expect("from_json");
+        assert_eq!(state.vars.get("N"), Some(&Value::Int(3)));
+        assert_eq!(state.vars.get("S"), Some(&Value::String("hi".into())));
+        assert_eq!(state.vars.get("O"), Some(&Value::Json(serde_json::json!({"k": [1]}))));
+    }
+
+    #[test]
+    fn restore_replaces_variables_and_checkpoints() {
+        let mut scope = Scope::new();
+        scope.set("KEPT", Value::Int(1));
+        let state = KernelState::capture(&scope, Path::new("/"), &[], &CheckpointStore::new());
+
+        let mut target = Scope::new();
+        target.set("STALE", Value::Int(2));
+        let checkpoints = CheckpointStore::new();
+        checkpoints.create(None, &target, Path::new("/"), 0);
+        state.restore_into(&mut target, &checkpoints);
+
+        assert_eq!(target.get("KEPT"), Some(&Value::Int(1)));
+        assert_eq!(target.get("STALE"), None);
+        assert!(checkpoints.list().is_empty());
+    }
+
+    #[test]
+    fn newer_versions_are_refused() {
+        let err = KernelState::from_json(r#"{"version": 99, "vars": {}, "exported": [], "cwd": "/"}"#)
+            .expect_err("newer version");
+        assert!(err.to_string().contains("newer"), "got: {err}");
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_changed() {
+        let mut before = Scope::new();
+        before.set("SAME", Value::Int(1));
+        before.set("GONE", Value::Int(2));
+        before.set_exported("EDITED", Value::String("a".into()));
+        let old = KernelState::capture(&before, Path::new("/"), &[], &CheckpointStore::new());
+
+        let mut after = Scope::new();
+        after.set("SAME", Value::Int(1));
+        after.set("EDITED", Value::String("b".into()));
+        after.set("NEW", Value::Int(3));
+        let new = KernelState::capture(&after, Path::new("/work"), &[], &CheckpointStore::new());
+
+        let diff = old.diff(&new);
+        let summary: Vec<(ChangeKind, ChangeType, &str)> = diff
+            .changes
+            .iter()
+            .map(|c| (c.kind, c.change, c.name.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (ChangeKind::Var, ChangeType::Changed, "EDITED"),
+                (ChangeKind::Var, ChangeType::Removed, "GONE"),
+                (ChangeKind::Var, ChangeType::Added, "NEW"),
+                (ChangeKind::Export, ChangeType::Removed, "EDITED"),
+                (ChangeKind::Cwd, ChangeType::Changed, "cwd"),
+            ]
+        );
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn reports_missing_mounts() {
+        let state = KernelState {
+            version: STATE_FORMAT_VERSION,
+            vars: BTreeMap::new(),
+            exported: Vec::new(),
+            cwd: PathBuf::from("/"),
+            mounts: vec![
+                MountState { path: PathBuf::from("/"), read_only: false },
+                MountState { path: PathBuf::from("/data"), read_only: true },
+            ],
+            checkpoints: Vec::new(),
+        };
+        let current = vec![MountInfo {
+            path: PathBuf::from("/"),
+            read_only: false,
+            resident_bytes: None,
+            quota_bytes: None,
+        }];
+        assert_eq!(state.missing_mounts(&current), vec![PathBuf::from("/data")]);
+    }
+}
@@ -0,0 +1,270 @@
+//! Named kernel states on disk.
+//!
+//! A [`StateStore`] is a directory of [`KernelState`] documents, one per
+//! name: `NAME.json`, by default under [`kernels_dir`]
+//! (`$XDG_DATA_HOME/kaish/kernels`). A document is exactly what
+//! `kaish-state export` prints, so a session is kept with
+//!
+//! ```text
+//! kaish-state export > ~/.local/share/kaish/kernels/work.json
+//! ```
+//!
+//! and brought back with `kaish-state import`. The store lists, inspects,
+//! deletes, and compacts these files; `kaish kernels` is its command line.
+//!
+//! Several kernels — in one process or many — can share a store without
+//! locking. Every write goes to a temporary file of its own and is renamed
+//! into place, so readers see a whole document, the old one or the new one,
+//! and concurrent saves of one name end with one of them intact. A listing
+//! skips documents deleted out from under it.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+use anyhow::{bail, Context, Result};
+
+use crate::paths::kernels_dir;
+use crate::state::KernelState;
+
+/// File extension of a saved state.
+const EXTENSION: &str = "json";
+
+/// Distinguishes this process's temporary files from one another.
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A directory of named [`KernelState`] documents.
+#[derive(Debug, Clone)]
+pub struct StateStore {
+    dir: PathBuf,
+}
+
+/// A saved state as listed: the file, and what's in it.
+#[derive(Debug, Clone)]
+pub struct SavedKernel {
+    /// Name (the file stem).
+    pub name: String,
+    /// The document's path.
+    pub path: PathBuf,
+    /// Size on disk in bytes.
+    pub size: u64,
+    /// When the file was last written.
+    pub modified: Option<SystemTime>,
+    /// Variable count, or `None` if the document doesn't parse.
+    pub vars: Option<usize>,
+    /// Checkpoint count, or `None` if the document doesn't parse.
+    pub checkpoints: Option<usize>,
+}
+
+impl Default for StateStore {
+    fn default() -> Self {
+        Self::new(kernels_dir())
+    }
+}
+
+impl StateStore {
+    /// A store rooted at `dir`. Nothing is created until a save.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The directory the store reads and writes.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path of state `name`. Errors if `name` isn't a plain file stem.
+    pub fn path(&self, name: &str) -> Result<PathBuf> {
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            bail!("invalid kernel name: {name:?}");
+        }
+        Ok(self.dir.join(format!("{name}.{EXTENSION}")))
+    }
+
+    /// Every saved state, by name. A missing directory is an empty store.
+    pub fn list(&self) -> Result<Vec<SavedKernel>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("reading {}", self.dir.display())),
+        };
+        let mut kernels = Vec::new();
+        for entry in entries {
+            let path = entry.with_context(|| format!("reading {}", self.dir.display()))?.path();
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else { continue };
+            if path.extension().is_none_or(|ext| ext != EXTENSION) || self.path(name).is_err() {
+                continue;
+            }
+            match self.info(name) {
+                Ok(kernel) => kernels.push(kernel),
+                // Deleted since the directory was read.
+                Err(_) if !path.exists() => {}
+                Err(e) => return Err(e),
+            }
+        }
+        kernels.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(kernels)
+    }
+
+    /// The listing entry for state `name`.
+    pub fn info(&self, name: &str) -> Result<SavedKernel> {
+        let path = self.path(name)?;
+        let metadata = std::fs::metadata(&path).with_context(|| format!("no saved kernel named {name}"))?;
+        let state = self.load(name).ok();
+        Ok(SavedKernel {
+            name: name.to_string(),
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            vars: state.as_ref().map(|s| s.vars.len()),
+            checkpoints: state.as_ref().map(|s| s.checkpoints.len()),
+            path,
+        })
+    }
+
+    /// Read state `name`.
+    pub fn load(&self, name: &str) -> Result<KernelState> {
+        let path = self.path(name)?;
+        let text = std::fs::read_to_string(&path).with_context(|| format!("no saved kernel named {name}"))?;
+        KernelState::from_json(&text).with_context(|| format!("reading {}", path.display()))
+    }
+
+    /// Write `state` as `name`, replacing any state already saved there.
+    pub fn save(&self, name: &str, state: &KernelState) -> Result<PathBuf> {
+        let path = self.path(name)?;
+        self.write(&path, &state.to_json()?)?;
+        Ok(path)
+    }
+
+    /// Remove state `name`.
+    pub fn delete(&self, name: &str) -> Result<()> {
+        let path = self.path(name)?;
+        std::fs::remove_file(&path).with_context(|| format!("no saved kernel named {name}"))
+    }
+
+    /// Rewrite state `name` as compact JSON. Returns the sizes before and
+    /// after. The document must parse — compacting never drops a file it
+    /// can't read back.
+    pub fn compact(&self, name: &str) -> Result<(u64, u64)> {
+        let path = self.path(name)?;
+        let before = std::fs::metadata(&path).with_context(|| format!("no saved kernel named {name}"))?.len();
+        let state = self.load(name)?;
+        let text = serde_json::to_string(&state).context("serializing kernel state")?;
+        self.write(&path, &text)?;
+        Ok((before, text.len() as u64))
+    }
+
+    /// Write through a temporary file so neither a crash nor a concurrent
+    /// writer leaves half a state. The temporary name is unique to this
+    /// write and hidden from [`Self::list`].
+    fn write(&self, path: &Path, text: &str) -> Result<()> {
+        std::fs::create_dir_all(&self.dir).with_context(|| format!("creating {}", self.dir.display()))?;
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("state");
+        let tmp = self.dir.join(format!(
+            ".{stem}.{}-{}.tmp",
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        if let Err(e) = std::fs::write(&tmp, text) {
+            // Best-effort cleanup: the write error is what the caller needs,
+            // and a leftover temp file is hidden from `list` anyway.
+            let _ = std::fs::remove_file(&tmp);
+            return Err(e).with_context(|| format!("writing {}", tmp.display()));
+        }
+        std::fs::rename(&tmp, path).with_context(|| format!("writing {}", path.display())).inspect_err(|_| {
+            // Same as above: report the rename failure, not the cleanup.
+            let _ = std::fs::remove_file(&tmp);
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Value;
+
+    fn state() -> KernelState {
+        let mut state = KernelState::from_json(r#"{"version":1,"vars":{},"exported":[],"cwd":"/"}"#).expect("state");
+        state.vars.insert("X".into(), Value::Int(1));
+        state.vars.insert("Y".into(), Value::String("two".into()));
+        state
+    }
+
+    #[test]
+    fn save_list_load_delete() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let store = StateStore::new(dir.path().join("kernels"));
+        assert!(store.list().expect("list").is_empty(), "missing dir is empty");
+
+        store.save("work", &state()).expect("save");
+        store.save("scratch", &state()).expect("save");
+        let listed = store.list().expect("list");
+        assert_eq!(listed.iter().map(|k| k.name.as_str()).collect::<Vec<_>>(), ["scratch", "work"]);
+        assert_eq!(listed[1].vars, Some(2));
+        assert_eq!(listed[1].checkpoints, Some(0));
+        assert!(listed[1].modified.is_some());
+
+        assert_eq!(store.load("work").expect("load").vars.get("X"), Some(&Value::Int(1)));
+        store.delete("work").expect("delete");
+        assert!(store.load("work").is_err());
+        assert!(store.delete("work").is_err());
+    }
+
+    #[test]
+    fn compact_shrinks_and_keeps_the_state() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let store = StateStore::new(dir.path());
+        store.save("work", &state()).expect("save");
+        let (before, after) = store.compact("work").expect("compact");
+        assert!(after < before, "{before} -> {after}");
+        assert_eq!(store.info("work").expect("info").size, after);
+        assert_eq!(store.load("work").expect("load"), state());
+    }
+
+    #[test]
+    fn unreadable_documents_still_list() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let store = StateStore::new(dir.path());
+        std::fs::write(dir.path().join("broken.json"), "not json").expect("write");
+        std::fs::write(dir.path().join("notes.txt"), "ignored").expect("write");
+        let listed = store.list().expect("list");
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].vars, None);
+        assert!(store.compact("broken").is_err());
+    }
+
+    #[test]
+    fn concurrent_saves_leave_one_whole_document() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let store = StateStore::new(dir.path());
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let store = store.clone();
+                std::thread::spawn(move || {
+                    let mut state = state();
+                    state.vars.insert("WRITER".into(), Value::Int(i));
+                    for _ in 0..20 {
+                        store.save("shared", &state).expect("save");
+                        store.list().expect("list while writing");
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("writer thread");
+        }
+
+        let loaded = store.load("shared").expect("a whole document");
+        assert!(matches!(loaded.vars.get("WRITER"), Some(Value::Int(0..8))));
+        let files: Vec<_> = std::fs::read_dir(dir.path()).expect("read_dir").collect();
+        assert_eq!(files.len(), 1, "no temporary files left behind");
+    }
+
+    #[test]
+    fn names_stay_inside_the_store() {
+        let store = StateStore::new("/tmp/kernels");
+        for name in ["", "../x", "a/b", ".hidden"] {
+            assert!(store.path(name).is_err(), "{name:?}");
+        }
+        assert_eq!(store.path("work").expect("path"), Path::new("/tmp/kernels/work.json"));
+    }
+}
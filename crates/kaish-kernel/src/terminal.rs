@@ -1,7 +1,8 @@
 //! Terminal control and job control for interactive mode.
 //!
 //! Handles process group management, terminal ownership, and
-//! foreground wait with WUNTRACED support for Ctrl-Z (SIGTSTP).
+//! foreground wait with WUNTRACED support for Ctrl-Z (SIGTSTP), plus the
+//! echo-free line read behind `read --secret`.
 //!
 //! All functionality is `#[cfg(unix)]` — non-Unix platforms get stubs.
 //!
@@ -15,6 +16,7 @@ mod unix {
     use std::os::unix::io::BorrowedFd;
 
     use nix::sys::signal::{self, SigHandler, Signal};
+    use nix::sys::termios::{self, LocalFlags, SetArg};
     use nix::sys::wait::{WaitPidFlag, WaitStatus, waitpid};
     use nix::unistd::{self, Pid, tcsetpgrp};
 
@@ -136,7 +138,26 @@ mod unix {
             }
         }
     }
+
+    /// Read one line from the terminal with echo off, for secrets.
+    ///
+    /// Echo is restored before returning, even when the read fails. This
+    /// blocks the current thread. Call from `spawn_blocking`.
+    pub fn read_line_hidden() -> std::io::Result<String> {
+        let stdin = std::io::stdin();
+        let saved = termios::tcgetattr(&stdin).map_err(std::io::Error::from)?;
+        let mut hidden = saved.clone();
+        hidden.local_flags.remove(LocalFlags::ECHO);
+        hidden.local_flags.insert(LocalFlags::ECHONL);
+        termios::tcsetattr(&stdin, SetArg::TCSAFLUSH, &hidden).map_err(std::io::Error::from)?;
+        let mut line = String::new();
+        let read = stdin.read_line(&mut line);
+        let restored = termios::tcsetattr(&stdin, SetArg::TCSAFLUSH, &saved);
+        read?;
+        restored.map_err(std::io::Error::from)?;
+        Ok(line)
+    }
 }
 
 #[cfg(unix)]
-pub use unix::{TerminalState, WaitResult};
+pub use unix::{TerminalState, WaitResult, read_line_hidden};
@@ -0,0 +1,110 @@
+//! Tool library: function definitions loaded from directories of `.kai` files.
+//!
+//! A kernel configured with [`KernelConfig::with_tool_library`] scans each
+//! directory for `*.kai` files (in name order, directories in config order)
+//! before its first request and registers every top-level function
+//! definition it finds (`name() { … }` or `function name { … }`), as if it
+//! had been typed into the session. Only definitions are taken — a library
+//! file's other statements never run, so loading one has no side effects. A
+//! later directory's definition of a name replaces an earlier one, which is
+//! how a project-local library overrides the user's.
+//!
+//! `kaish-tools reload` re-scans the same directories
+//! ([`Kernel::load_tool_library`]).
+//!
+//! [`KernelConfig::with_tool_library`]: crate::KernelConfig::with_tool_library
+//! [`Kernel::load_tool_library`]: crate::Kernel::load_tool_library
+
+use std::path::{Path, PathBuf};
+
+use crate::ast::{Stmt, ToolDef};
+use crate::backend::{BackendError, KernelBackend};
+
+/// What loading the tool library registered and what it couldn't.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolLibraryReport {
+    /// Names of the tools registered, in load order.
+    pub loaded: Vec<String>,
+    /// One message per directory or file that could not be read or parsed.
+    pub errors: Vec<String>,
+}
+
+/// Read every library file under `dirs`, returning the definitions found in
+/// load order plus one message per unreadable directory or file. A missing
+/// directory is not an error: most users have no library.
+pub(crate) async fn scan(backend: &dyn KernelBackend, dirs: &[PathBuf]) -> (Vec<ToolDef>, Vec<String>) {
+    let mut defs = Vec::new();
+    let mut errors = Vec::new();
+    for dir in dirs {
+        let mut entries = match backend.list(dir).await {
+            Ok(entries) => entries,
+            Err(BackendError::NotFound(_)) => continue,
+            Err(e) => {
+                errors.push(format!("{}: {}", dir.display(), e));
+                continue;
+            }
+        };
+        entries.retain(|entry| !entry.is_dir() && entry.name.ends_with(".kai"));
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for entry in entries {
+            let path = dir.join(&entry.name);
+            let source = match backend.read(&path, None).await {
+                Ok(bytes) => match String::from_utf8(bytes) {
+                    Ok(source) => source,
+                    Err(e) => {
+                        errors.push(format!("{}: invalid UTF-8: {}", path.display(), e));
+                        continue;
+                    }
+                },
+                Err(e) => {
+                    errors.push(format!("{}: {}", path.display(), e));
+                    continue;
+                }
+            };
+            match tool_defs(&source, &path) {
+                Ok(found) => defs.extend(found),
+                Err(e) => errors.push(e),
+            }
+        }
+    }
+    (defs, errors)
+}
+
+/// The top-level function definitions in `source`, read from `path`.
+pub(crate) fn tool_defs(source: &str, path: &Path) -> Result<Vec<ToolDef>, String> {
+    let program = crate::parser::parse(source).map_err(|errors| {
+        errors
+            .iter()
+            .map(|e| format!("{}:{}", path.display(), e.format(source)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    })?;
+    Ok(program
+        .statements
+        .into_iter()
+        .filter_map(|stmt| match stmt {
+            Stmt::ToolDef(def) => Some(def),
+            _ => None,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_function_definitions_are_taken() {
+        let source = "X=1\ngreet() { echo hi; }\necho side-effect\nfunction bye { echo bye; }\n";
+        let defs = tool_defs(source, Path::new("/lib/a.kai")).expect("parses");
+        let names: Vec<&str> = defs.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["greet", "bye"]);
+    }
+
+    #[test]
+    fn parse_errors_name_the_file() {
+        let err = tool_defs("broken() {", Path::new("/lib/broken.kai")).expect_err("fails");
+        assert!(err.starts_with("/lib/broken.kai:"), "{err}");
+    }
+}
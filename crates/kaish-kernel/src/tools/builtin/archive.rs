@@ -0,0 +1,895 @@
+//! archive — Create, extract, and list tar archives through the VFS.
+//!
+//! Archives are ustar, gzip-compressed when the output name ends in `.gz` or
+//! `.tgz` (or with `-z`); `extract` and `list` sniff the gzip magic instead of
+//! trusting the name. Every read and write goes through the backend, so one
+//! archive can bundle files from any mix of mounts (memory, local, overlay)
+//! and unpack anywhere the VFS can write. The archive is built and read
+//! whole in memory — this is an artifact bundler, not a streaming tar.
+//!
+//! Extraction refuses entries that would land outside the destination
+//! (absolute names are re-rooted; `..` components, symlinks that escape —
+//! directly or through other links — and entries beneath a symlink are
+//! errors) and gates existing files through trash/latch like `write`.
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::io::{Read, Write as _};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::time::UNIX_EPOCH;
+
+use crate::backend::{BackendError, KernelBackend};
+use crate::hooks::Progress;
+use crate::interpreter::{ExecResult, OutputData, OutputNode};
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+
+/// Archive tool: tar/tar.gz bundles over the VFS.
+pub struct Archive;
+
+/// clap-derived argv layer for archive.
+#[derive(Parser, Debug)]
+#[command(name = "archive", about = "Create, extract, and list tar archives")]
+struct ArchiveArgs {
+    /// Gzip the archive on `create` regardless of its name (-z).
+    #[arg(short = 'z', long = "gzip")]
+    gzip: bool,
+
+    /// Base directory: relative inputs and entry names are taken from here
+    /// on `create`; the destination on `extract` when none is given (-C).
+    #[arg(short = 'C', long = "directory")]
+    directory: Option<String>,
+
+    /// Confirmation nonce for a latch-gated overwrite.
+    #[arg(long = "confirm")]
+    confirm: Option<String>,
+
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// Subcommand (`create`, `extract`, `list`) and its arguments.
+    args: Vec<String>,
+}
+
+#[async_trait]
+impl Tool for Archive {
+    fn name(&self) -> &str {
+        "archive"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &ArchiveArgs::command(),
+            "archive",
+            "Create, extract, and list tar (.tar, .tar.gz) archives through the VFS",
+            [
+                ("Bundle files from any mounts", "archive create /scratch/bundle.tar.gz src/ /v/mem/notes.md"),
+                ("Names relative to a directory", "archive create -C /work out.tar build/"),
+                ("Unpack a download", "archive extract /tmp/release.tgz /work/release"),
+                ("List the contents", "archive list /scratch/bundle.tar.gz"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("archive: {e}")),
+        };
+        let parsed = match ArchiveArgs::try_parse_from(
+            std::iter::once("archive".to_string()).chain(argv),
+        ) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("archive: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let Some((subcmd, rest)) = parsed.args.split_first() else {
+            return ExecResult::failure(1, "archive: missing subcommand (create, extract, list)");
+        };
+        match subcmd.as_str() {
+            "create" => cmd_create(rest, &parsed, ctx).await,
+            "extract" => cmd_extract(rest, &parsed, ctx).await,
+            "list" => cmd_list(rest, ctx).await,
+            other => ExecResult::failure(1, format!("archive: unknown subcommand: {}", other)),
+        }
+    }
+}
+
+async fn cmd_create(rest: &[String], parsed: &ArchiveArgs, ctx: &mut ExecContext) -> ExecResult {
+    let Some((out, inputs)) = rest.split_first().filter(|(_, inputs)| !inputs.is_empty()) else {
+        return ExecResult::failure(1, "archive create: expected OUT PATH...");
+    };
+    let base = ctx.resolve_path(parsed.directory.as_deref().unwrap_or("."));
+    let out_resolved = ctx.resolve_path(out);
+
+    let mut entries = Vec::new();
+    for (done, input) in inputs.iter().enumerate() {
+        if ctx.cancel.is_cancelled() {
+            return ExecResult::interrupted("archive create: interrupted");
+        }
+        ctx.report_progress(Progress::steps(done, inputs.len()).with_message(format!("reading {input}")));
+        let resolved = crate::vfs::normalize_path(&base.join(input));
+        // Under the base, names are relative to it; elsewhere they keep the
+        // full path minus the leading `/` (what tar does with absolute names).
+        let name = match resolved.strip_prefix(&base) {
+            Ok(relative) => relative.to_string_lossy().into_owned(),
+            Err(_) => resolved.to_string_lossy().trim_start_matches('/').to_string(),
+        };
+        let info = match ctx.backend.stat(&resolved).await {
+            Ok(info) => info,
+            Err(e) => return ExecResult::failure(1, format!("archive create: {}: {}", input, e)),
+        };
+        let collected = if info.is_dir() {
+            collect_dir(&*ctx.backend, resolved, name, &out_resolved, &mut entries).await
+        } else {
+            collect_file(&*ctx.backend, &resolved, name, &mut entries).await
+        };
+        if let Err(e) = collected {
+            return ExecResult::failure(1, format!("archive create: {}: {}", input, e));
+        }
+    }
+
+    let mut bytes = match encode_tar(&entries) {
+        Ok(bytes) => bytes,
+        Err(e) => return ExecResult::failure(1, format!("archive create: {e}")),
+    };
+    if parsed.gzip || out.ends_with(".gz") || out.ends_with(".tgz") {
+        bytes = match gzip(&bytes) {
+            Ok(bytes) => bytes,
+            Err(e) => return ExecResult::failure(1, format!("archive create: {e}")),
+        };
+    }
+
+    let snapshots = match ctx
+        .gate_overwrites("archive", &[(out.clone(), false)], parsed.confirm.as_deref(), |nonce, joined| {
+            format!("archive --confirm=\"{nonce}\" create {joined} {}", inputs.join(" "))
+        })
+        .await
+    {
+        Ok(s) => s,
+        Err(blocked) => return blocked,
+    };
+    let expected = snapshots.get(&out_resolved).map(|v| v.as_slice());
+    match ctx.overwrite_checked(&out_resolved, &bytes, expected).await {
+        Ok(()) => ExecResult::with_output(OutputData::text(format!(
+            "Archived {} entries ({} bytes) to {}\n",
+            entries.len(),
+            bytes.len(),
+            out
+        ))),
+        Err(e) => ExecResult::failure(1, format!("archive create: {}: {}", out, e)),
+    }
+}
+
+async fn cmd_extract(rest: &[String], parsed: &ArchiveArgs, ctx: &mut ExecContext) -> ExecResult {
+    let (src, dest) = match rest {
+        [src] => (src, parsed.directory.clone().unwrap_or_else(|| ".".to_string())),
+        [src, dest] => (src, dest.clone()),
+        _ => return ExecResult::failure(1, "archive extract: expected SRC [DEST]"),
+    };
+    let entries = match read_archive(src, ctx).await {
+        Ok(entries) => entries,
+        Err(e) => return ExecResult::failure(1, format!("archive extract: {e}")),
+    };
+    let dest_resolved = ctx.resolve_path(&dest);
+
+    // Plan every target before touching the filesystem, so a hostile entry
+    // fails the whole extraction rather than half of it.
+    let mut relatives = Vec::with_capacity(entries.len());
+    for entry in &entries {
+        match safe_relative(&entry.name) {
+            Ok(Some(relative)) => relatives.push((entry, relative)),
+            Ok(None) => continue,
+            Err(e) => return ExecResult::failure(1, format!("archive extract: {e}")),
+        }
+    }
+    let links = match check_links(&relatives) {
+        Ok(links) => links,
+        Err(e) => return ExecResult::failure(1, format!("archive extract: {e}")),
+    };
+    // Symlinks already under the destination are as dangerous as the
+    // archive's own: nothing gets written through one.
+    for (entry, relative) in &relatives {
+        for parent in relative.ancestors().skip(1) {
+            if parent.as_os_str().is_empty() || links.contains_key(parent) {
+                continue;
+            }
+            if let Ok(info) = ctx.backend.lstat(&dest_resolved.join(parent)).await
+                && info.is_symlink()
+            {
+                return ExecResult::failure(
+                    1,
+                    format!("archive extract: {}: {} is a symlink", entry.name, parent.display()),
+                );
+            }
+        }
+    }
+    let plan: Vec<_> = relatives
+        .into_iter()
+        .map(|(entry, relative)| (entry, dest_resolved.join(relative)))
+        .collect();
+
+    let targets: Vec<(String, bool)> = plan
+        .iter()
+        .filter(|(entry, _)| entry.kind == EntryKind::File)
+        .map(|(_, path)| (path.to_string_lossy().into_owned(), false))
+        .collect();
+    let snapshots = match ctx
+        .gate_overwrites("archive", &targets, parsed.confirm.as_deref(), |nonce, _| {
+            format!("archive --confirm=\"{nonce}\" extract {src} {dest}")
+        })
+        .await
+    {
+        Ok(s) => s,
+        Err(blocked) => return blocked,
+    };
+
+    if let Err(e) = ctx.backend.mkdir(&dest_resolved).await {
+        return ExecResult::failure(1, format!("archive extract: {}: {}", dest, e));
+    }
+    for (done, (entry, path)) in plan.iter().enumerate() {
+        if ctx.cancel.is_cancelled() {
+            return ExecResult::interrupted("archive extract: interrupted");
+        }
+        ctx.report_progress(Progress::steps(done, plan.len()).with_message(entry.name.clone()));
+        let written = match &entry.kind {
+            EntryKind::Dir => ctx.backend.mkdir(path).await.map_err(|e| e.to_string()),
+            EntryKind::File => {
+                if let Some(parent) = path.parent()
+                    && let Err(e) = ctx.backend.mkdir(parent).await
+                {
+                    return ExecResult::failure(1, format!("archive extract: {}: {}", parent.display(), e));
+                }
+                let expected = snapshots.get(path).map(|v| v.as_slice());
+                ctx.overwrite_checked(path, &entry.data, expected).await
+            }
+            EntryKind::Symlink(target) => {
+                if ctx.backend.lstat(path).await.is_ok() {
+                    let _ = ctx.backend.remove(path, false).await;
+                }
+                ctx.backend.symlink(Path::new(target), path).await.map_err(|e| e.to_string())
+            }
+        };
+        if let Err(e) = written {
+            return ExecResult::failure(1, format!("archive extract: {}: {}", path.display(), e));
+        }
+        // Best effort: not every backend keeps mtimes.
+        if entry.kind == EntryKind::File && entry.mtime > 0 {
+            let mtime = UNIX_EPOCH + std::time::Duration::from_secs(entry.mtime);
+            let _ = ctx.backend.set_mtime(path, mtime).await;
+        }
+    }
+    ExecResult::with_output(OutputData::text(format!(
+        "Extracted {} entries to {}\n",
+        plan.len(),
+        dest
+    )))
+}
+
+async fn cmd_list(rest: &[String], ctx: &mut ExecContext) -> ExecResult {
+    let [src] = rest else {
+        return ExecResult::failure(1, "archive list: expected SRC");
+    };
+    let entries = match read_archive(src, ctx).await {
+        Ok(entries) => entries,
+        Err(e) => return ExecResult::failure(1, format!("archive list: {e}")),
+    };
+    let nodes: Vec<OutputNode> = entries
+        .iter()
+        .map(|e| OutputNode::new(&e.name).with_cells(vec![e.kind.as_str().to_string(), e.data.len().to_string()]))
+        .collect();
+    let json: Vec<serde_json::Value> = entries
+        .iter()
+        .map(|e| {
+            let mut obj = serde_json::json!({
+                "name": e.name,
+                "type": e.kind.as_str(),
+                "size": e.data.len(),
+                "mode": format!("{:o}", e.mode),
+                "mtime": e.mtime,
+            });
+            if let EntryKind::Symlink(target) = &e.kind {
+                obj["target"] = serde_json::Value::String(target.clone());
+            }
+            obj
+        })
+        .collect();
+    ExecResult::with_output(
+        OutputData::table(vec!["NAME".to_string(), "TYPE".to_string(), "SIZE".to_string()], nodes)
+            .with_rich_json(serde_json::Value::Array(json)),
+    )
+}
+
+/// Read `src` through the VFS, gunzip it if it's gzip, and decode the tar.
+async fn read_archive(src: &str, ctx: &ExecContext) -> Result<Vec<TarEntry>, String> {
+    let bytes = ctx
+        .backend
+        .read(&ctx.resolve_path(src), None)
+        .await
+        .map_err(|e| format!("{}: {}", src, e))?;
+    let tar = if bytes.starts_with(&GZIP_MAGIC) {
+        gunzip(&bytes).map_err(|e| format!("{}: {}", src, e))?
+    } else {
+        bytes
+    };
+    decode_tar(&tar).map_err(|e| format!("{}: {}", src, e))
+}
+
+/// Collect one file (or a symlink, when `collect_dir` meets one) as an entry.
+async fn collect_file(
+    backend: &dyn KernelBackend,
+    path: &Path,
+    name: String,
+    entries: &mut Vec<TarEntry>,
+) -> Result<(), BackendError> {
+    let info = backend.stat(path).await?;
+    let data = backend.read(path, None).await?;
+    entries.push(TarEntry {
+        name,
+        kind: EntryKind::File,
+        data,
+        mode: info.permissions.unwrap_or(0o644),
+        mtime: mtime_secs(info.modified),
+    });
+    Ok(())
+}
+
+/// Collect a directory and everything under it. An empty `name` (the input
+/// was the base directory itself) adds only the children. `skip` is the
+/// archive being written, which must not end up inside itself.
+fn collect_dir<'a>(
+    backend: &'a dyn KernelBackend,
+    path: PathBuf,
+    name: String,
+    skip: &'a Path,
+    entries: &'a mut Vec<TarEntry>,
+) -> Pin<Box<dyn Future<Output = Result<(), BackendError>> + Send + 'a>> {
+    Box::pin(async move {
+        if !name.is_empty() {
+            let info = backend.stat(&path).await?;
+            entries.push(TarEntry {
+                name: name.clone(),
+                kind: EntryKind::Dir,
+                data: Vec::new(),
+                mode: info.permissions.unwrap_or(0o755),
+                mtime: mtime_secs(info.modified),
+            });
+        }
+        let mut children = backend.list(&path).await?;
+        children.sort_by(|a, b| a.name.cmp(&b.name));
+        for child in children {
+            let child_path = path.join(&child.name);
+            if child_path == skip {
+                continue;
+            }
+            let child_name = if name.is_empty() { child.name.clone() } else { format!("{}/{}", name, child.name) };
+            if child.is_symlink() {
+                let target = match child.symlink_target {
+                    Some(target) => target,
+                    None => backend.read_link(&child_path).await?,
+                };
+                entries.push(TarEntry {
+                    name: child_name,
+                    kind: EntryKind::Symlink(target.to_string_lossy().into_owned()),
+                    data: Vec::new(),
+                    mode: 0o777,
+                    mtime: mtime_secs(child.modified),
+                });
+            } else if child.is_dir() {
+                collect_dir(backend, child_path, child_name, skip, entries).await?;
+            } else {
+                collect_file(backend, &child_path, child_name, entries).await?;
+            }
+        }
+        Ok(())
+    })
+}
+
+fn mtime_secs(modified: Option<std::time::SystemTime>) -> u64 {
+    modified
+        .unwrap_or_else(kaish_types::clock::system_now)
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// The relative path an entry extracts to. A leading `/` is dropped (the
+/// entry is re-rooted under the destination), `..` is refused, and a name
+/// that normalizes to nothing (`./`) yields `None`.
+fn safe_relative(name: &str) -> Result<Option<PathBuf>, String> {
+    let mut out = PathBuf::new();
+    for part in name.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => return Err(format!("{}: entry escapes the destination", name)),
+            part => out.push(part),
+        }
+    }
+    Ok((!out.as_os_str().is_empty()).then_some(out))
+}
+
+/// Check the archive's symlinks against each other. Every link must resolve
+/// inside the destination even when its target runs through the archive's
+/// other links (`s1 -> .` then `s2 -> s1/..`), and no entry may sit beneath
+/// a link. Returns the links by relative path.
+fn check_links(relatives: &[(&TarEntry, PathBuf)]) -> Result<HashMap<PathBuf, String>, String> {
+    let links: HashMap<PathBuf, String> = relatives
+        .iter()
+        .filter_map(|(entry, relative)| match &entry.kind {
+            EntryKind::Symlink(target) => Some((relative.clone(), target.clone())),
+            _ => None,
+        })
+        .collect();
+    for (entry, relative) in relatives {
+        if let Some(parent) = relative.ancestors().skip(1).find(|p| links.contains_key(*p)) {
+            return Err(format!("{}: {} is a symlink", entry.name, parent.display()));
+        }
+        if let EntryKind::Symlink(target) = &entry.kind
+            && resolve_through_links(relative, target, &links).is_none()
+        {
+            return Err(format!("{}: symlink target escapes the destination: {}", entry.name, target));
+        }
+    }
+    Ok(links)
+}
+
+/// Resolve `target` as seen from the symlink at `link` (both relative to the
+/// destination), following `links` along the way. `None` when the walk
+/// leaves the destination, meets an absolute target, or loops.
+fn resolve_through_links(link: &Path, target: &str, links: &HashMap<PathBuf, String>) -> Option<PathBuf> {
+    if target.starts_with('/') {
+        return None;
+    }
+    let mut out = link.parent().map(Path::to_path_buf).unwrap_or_default();
+    let mut pending: VecDeque<String> = target.split('/').map(str::to_string).collect();
+    let mut hops = 0;
+    while let Some(part) = pending.pop_front() {
+        match part.as_str() {
+            "" | "." => {}
+            ".." => {
+                if !out.pop() {
+                    return None;
+                }
+            }
+            _ => {
+                out.push(&part);
+                if let Some(next) = links.get(&out) {
+                    hops += 1;
+                    if hops > MAX_LINK_HOPS || next.starts_with('/') {
+                        return None;
+                    }
+                    out.pop();
+                    for p in next.split('/').rev() {
+                        pending.push_front(p.to_string());
+                    }
+                }
+            }
+        }
+    }
+    Some(out)
+}
+
+// ── tar codec ─────────────────────────────────────────────────────────────
+
+const BLOCK: usize = 512;
+/// Link-following budget for [`resolve_through_links`], as in Linux's ELOOP.
+const MAX_LINK_HOPS: usize = 40;
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum EntryKind {
+    File,
+    Dir,
+    Symlink(String),
+}
+
+impl EntryKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            EntryKind::File => "file",
+            EntryKind::Dir => "dir",
+            EntryKind::Symlink(_) => "symlink",
+        }
+    }
+}
+
+/// One archive member. `name` never carries a trailing `/`.
+#[derive(Debug, Clone)]
+struct TarEntry {
+    name: String,
+    kind: EntryKind,
+    data: Vec<u8>,
+    mode: u32,
+    mtime: u64,
+}
+
+fn gzip(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(bytes).map_err(|e| e.to_string())?;
+    encoder.finish().map_err(|e| e.to_string())
+}
+
+fn gunzip(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    flate2::read::MultiGzDecoder::new(bytes)
+        .read_to_end(&mut out)
+        .map_err(|e| format!("bad gzip stream: {e}"))?;
+    Ok(out)
+}
+
+/// Serialize entries as a ustar archive, ending with the two zero blocks.
+fn encode_tar(entries: &[TarEntry]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    for entry in entries {
+        out.extend_from_slice(&encode_header(entry)?);
+        out.extend_from_slice(&entry.data);
+        out.resize(out.len().div_ceil(BLOCK) * BLOCK, 0);
+    }
+    out.resize(out.len() + 2 * BLOCK, 0);
+    Ok(out)
+}
+
+fn encode_header(entry: &TarEntry) -> Result<[u8; BLOCK], String> {
+    let mut header = [0u8; BLOCK];
+    let full_name = match entry.kind {
+        EntryKind::Dir => format!("{}/", entry.name),
+        _ => entry.name.clone(),
+    };
+    let (prefix, name) = split_name(&full_name)?;
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    put_octal(&mut header[100..108], u64::from(entry.mode & 0o7777))?;
+    put_octal(&mut header[108..116], 0)?;
+    put_octal(&mut header[116..124], 0)?;
+    put_octal(&mut header[124..136], entry.data.len() as u64)
+        .map_err(|_| format!("{}: too large for a tar entry", entry.name))?;
+    put_octal(&mut header[136..148], entry.mtime)?;
+    header[156] = match &entry.kind {
+        EntryKind::File => b'0',
+        EntryKind::Dir => b'5',
+        EntryKind::Symlink(target) => {
+            if target.len() > 100 {
+                return Err(format!("{}: symlink target too long for a tar header", entry.name));
+            }
+            header[157..157 + target.len()].copy_from_slice(target.as_bytes());
+            b'2'
+        }
+    };
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+    header[148..156].fill(b' ');
+    let sum: u32 = header.iter().map(|&b| u32::from(b)).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", sum).as_bytes());
+    Ok(header)
+}
+
+/// Split a name into ustar's 155-byte prefix and 100-byte name at a `/`.
+fn split_name(name: &str) -> Result<(&str, &str), String> {
+    if name.len() <= 100 {
+        return Ok(("", name));
+    }
+    // Search from the left so the name part is as long as it can be.
+    name.match_indices('/')
+        .map(|(i, _)| (&name[..i], &name[i + 1..]))
+        .find(|(prefix, rest)| prefix.len() <= 155 && !rest.is_empty() && rest.len() <= 100)
+        .ok_or_else(|| format!("{}: path too long for a tar header", name))
+}
+
+/// Write `value` as zero-padded octal, NUL-terminated, filling `field`.
+fn put_octal(field: &mut [u8], value: u64) -> Result<(), String> {
+    let width = field.len() - 1;
+    let text = format!("{:0width$o}", value, width = width);
+    if text.len() > width {
+        return Err(format!("{value} does not fit a {width}-digit tar field"));
+    }
+    field[..width].copy_from_slice(text.as_bytes());
+    field[width] = 0;
+    Ok(())
+}
+
+/// Parse a numeric header field: octal text, or GNU base-256 when the high
+/// bit of the first byte is set.
+fn parse_number(field: &[u8]) -> Result<u64, String> {
+    if let Some((&first, rest)) = field.split_first()
+        && first & 0x80 != 0
+    {
+        let mut value = u64::from(first & 0x7f);
+        for &b in rest {
+            value = value
+                .checked_mul(256)
+                .and_then(|v| v.checked_add(u64::from(b)))
+                .ok_or("numeric field overflows")?;
+        }
+        return Ok(value);
+    }
+    let text = std::str::from_utf8(field).map_err(|_| "bad numeric field".to_string())?;
+    let text = text.trim_matches(|c: char| c == '\0' || c == ' ');
+    if text.is_empty() {
+        return Ok(0);
+    }
+    u64::from_str_radix(text, 8).map_err(|_| format!("bad numeric field: {text:?}"))
+}
+
+/// A NUL-terminated header string. Names must be UTF-8 — kaish paths are.
+fn c_string(field: &[u8]) -> Result<String, String> {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    String::from_utf8(field[..end].to_vec()).map_err(|_| "non-UTF-8 entry name".to_string())
+}
+
+/// Decode a tar archive. Understands ustar prefixes, GNU long names
+/// (`L`/`K`), and pax `path`/`linkpath` records; hard links, devices, and
+/// FIFOs are refused rather than silently dropped.
+fn decode_tar(bytes: &[u8]) -> Result<Vec<TarEntry>, String> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    let mut long_name: Option<String> = None;
+    let mut long_link: Option<String> = None;
+
+    // Anything shorter than one header block can't be a tar at all; without
+    // this a short junk file "extracted" zero entries and succeeded.
+    if bytes.len() < BLOCK {
+        return Err("not a tar archive (shorter than one header block)".to_string());
+    }
+    while offset + BLOCK <= bytes.len() {
+        let header = &bytes[offset..offset + BLOCK];
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+        verify_checksum(header)?;
+        let size = usize::try_from(parse_number(&header[124..136])?)
+            .map_err(|_| "entry too large".to_string())?;
+        let data_start = offset + BLOCK;
+        let data = data_start
+            .checked_add(size)
+            .and_then(|end| bytes.get(data_start..end))
+            .ok_or_else(|| "truncated archive".to_string())?;
+        offset = data_start + size.div_ceil(BLOCK) * BLOCK;
+
+        let typeflag = header[156];
+        match typeflag {
+            b'L' => {
+                long_name = Some(c_string(data)?);
+                continue;
+            }
+            b'K' => {
+                long_link = Some(c_string(data)?);
+                continue;
+            }
+            b'x' => {
+                for (key, value) in parse_pax(data)? {
+                    match key.as_str() {
+                        "path" => long_name = Some(value),
+                        "linkpath" => long_link = Some(value),
+                        _ => {}
+                    }
+                }
+                continue;
+            }
+            b'g' => continue,
+            _ => {}
+        }
+
+        let name = match long_name.take() {
+            Some(name) => name,
+            None => header_name(header)?,
+        };
+        let link = match long_link.take() {
+            Some(link) => link,
+            None => c_string(&header[157..257])?,
+        };
+        let kind = match typeflag {
+            b'0' | b'7' | 0 if name.ends_with('/') => EntryKind::Dir,
+            b'0' | b'7' | 0 => EntryKind::File,
+            b'5' => EntryKind::Dir,
+            b'2' => EntryKind::Symlink(link),
+            other => {
+                return Err(format!("{}: unsupported entry type '{}'", name, char::from(other)));
+            }
+        };
+        entries.push(TarEntry {
+            name: name.trim_end_matches('/').to_string(),
+            data: if kind == EntryKind::File { data.to_vec() } else { Vec::new() },
+            kind,
+            mode: parse_number(&header[100..108])
+                .ok()
+                .and_then(|m| u32::try_from(m).ok())
+                .unwrap_or(0o644),
+            mtime: parse_number(&header[136..148]).unwrap_or(0),
+        });
+    }
+    Ok(entries)
+}
+
+fn header_name(header: &[u8]) -> Result<String, String> {
+    let name = c_string(&header[..100])?;
+    if &header[257..262] == b"ustar" {
+        let prefix = c_string(&header[345..500])?;
+        if !prefix.is_empty() {
+            return Ok(format!("{}/{}", prefix, name));
+        }
+    }
+    Ok(name)
+}
+
+fn verify_checksum(header: &[u8]) -> Result<(), String> {
+    const BAD: &str = "not a tar archive (bad header checksum)";
+    let stored = parse_number(&header[148..156]).map_err(|_| BAD.to_string())?;
+    let unsigned: u64 = header
+        .iter()
+        .enumerate()
+        .map(|(i, &b)| if (148..156).contains(&i) { u64::from(b' ') } else { u64::from(b) })
+        .sum();
+    // Some old tars summed signed bytes; accept either.
+    let signed: i64 = header
+        .iter()
+        .enumerate()
+        .map(|(i, &b)| if (148..156).contains(&i) { i64::from(b' ') } else { i64::from(i8::from_ne_bytes([b])) })
+        .sum();
+    if stored == unsigned || i64::try_from(stored).ok() == Some(signed) {
+        Ok(())
+    } else {
+        Err(BAD.to_string())
+    }
+}
+
+/// Parse pax extended-header records: `LEN key=value\n`.
+fn parse_pax(data: &[u8]) -> Result<Vec<(String, String)>, String> {
+    let bad = || "malformed pax header".to_string();
+    let mut records = Vec::new();
+    let mut rest = data;
+    while !rest.is_empty() && rest[0] != 0 {
+        let space = rest.iter().position(|&b| b == b' ').ok_or_else(bad)?;
+        let len: usize = std::str::from_utf8(&rest[..space])
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|&len| len > space + 1 && len <= rest.len())
+            .ok_or_else(bad)?;
+        let record = std::str::from_utf8(&rest[space + 1..len]).map_err(|_| bad())?;
+        let record = record.strip_suffix('\n').unwrap_or(record);
+        let (key, value) = record.split_once('=').ok_or_else(bad)?;
+        records.push((key.to_string(), value.to_string()));
+        rest = &rest[len..];
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, data: &[u8]) -> TarEntry {
+        TarEntry { name: name.into(), kind: EntryKind::File, data: data.to_vec(), mode: 0o644, mtime: 1_700_000_000 }
+    }
+
+    #[test]
+    fn tar_round_trip() {
+        let entries = vec![
+            TarEntry { name: "dir".into(), kind: EntryKind::Dir, data: Vec::new(), mode: 0o755, mtime: 0 },
+            file("dir/a.txt", b"hello"),
+            file("dir/blob.bin", &[0, 0xff, 0xfe, 0x80]),
+            TarEntry { name: "dir/link".into(), kind: EntryKind::Symlink("a.txt".into()), data: Vec::new(), mode: 0o777, mtime: 0 },
+        ];
+        let bytes = encode_tar(&entries).unwrap();
+        assert_eq!(bytes.len() % BLOCK, 0);
+
+        let decoded = decode_tar(&bytes).unwrap();
+        assert_eq!(decoded.len(), 4);
+        assert_eq!(decoded[0].kind, EntryKind::Dir);
+        assert_eq!(decoded[0].name, "dir");
+        assert_eq!(decoded[1].data, b"hello");
+        assert_eq!(decoded[1].mtime, 1_700_000_000);
+        assert_eq!(decoded[2].data, vec![0, 0xff, 0xfe, 0x80]);
+        assert_eq!(decoded[3].kind, EntryKind::Symlink("a.txt".into()));
+    }
+
+    #[test]
+    fn truncated_archives_are_errors() {
+        let bytes = encode_tar(&[file("a.txt", &[b'x'; 600])]).unwrap();
+        for len in [0, 1, 100, BLOCK - 1, BLOCK + 10, 2 * BLOCK] {
+            assert!(decode_tar(&bytes[..len]).is_err(), "{len} bytes decoded");
+        }
+    }
+
+    #[test]
+    fn long_names_use_the_ustar_prefix() {
+        let name = format!("{}/{}", "d".repeat(120), "f".repeat(90));
+        let bytes = encode_tar(&[file(&name, b"x")]).unwrap();
+        assert_eq!(decode_tar(&bytes).unwrap()[0].name, name);
+
+        let too_long = "n".repeat(300);
+        assert!(encode_tar(&[file(&too_long, b"x")]).is_err());
+    }
+
+    #[test]
+    fn gzip_round_trip() {
+        let tar = encode_tar(&[file("a", b"abc")]).unwrap();
+        let packed = gzip(&tar).unwrap();
+        assert!(packed.starts_with(&GZIP_MAGIC));
+        assert_eq!(gunzip(&packed).unwrap(), tar);
+    }
+
+    #[test]
+    fn garbage_is_not_a_tar() {
+        let err = decode_tar(&[b'x'; BLOCK * 2]).unwrap_err();
+        assert!(err.contains("checksum"), "got: {err}");
+    }
+
+    #[test]
+    fn pax_path_overrides_the_header_name() {
+        let mut pax = TarEntry { name: "PaxHeader".into(), kind: EntryKind::File, data: b"18 path=long/name\n".to_vec(), mode: 0o644, mtime: 0 };
+        let mut bytes = encode_header(&pax).unwrap().to_vec();
+        bytes[156] = b'x';
+        // Re-checksum after changing the typeflag.
+        bytes[148..156].fill(b' ');
+        let sum: u32 = bytes.iter().map(|&b| u32::from(b)).sum();
+        bytes[148..156].copy_from_slice(format!("{:06o}\0 ", sum).as_bytes());
+        pax.data.resize(BLOCK, 0);
+        bytes.extend_from_slice(&pax.data);
+        bytes.extend_from_slice(&encode_tar(&[file("short", b"x")]).unwrap());
+
+        let decoded = decode_tar(&bytes).unwrap();
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].name, "long/name");
+    }
+
+    #[test]
+    fn extraction_paths_stay_inside() {
+        assert_eq!(safe_relative("/abs/file").unwrap(), Some(PathBuf::from("abs/file")));
+        assert_eq!(safe_relative("./").unwrap(), None);
+        assert!(safe_relative("a/../../etc/passwd").is_err());
+
+        let none = HashMap::new();
+        assert!(resolve_through_links(Path::new("a/link"), "../b", &none).is_some());
+        assert!(resolve_through_links(Path::new("a/link"), "../../b", &none).is_none());
+        assert!(resolve_through_links(Path::new("link"), "/etc/passwd", &none).is_none());
+    }
+
+    #[test]
+    fn chained_symlinks_cannot_escape() {
+        let link = |name: &str, target: &str| TarEntry {
+            name: name.into(),
+            kind: EntryKind::Symlink(target.into()),
+            data: Vec::new(),
+            mode: 0o777,
+            mtime: 0,
+        };
+        // Each link looks harmless alone; s2 resolves to the destination's parent.
+        let entries = [link("s1", "."), link("s2", "s1/.."), file("s2/x", b"pwned")];
+        let relatives: Vec<_> = entries.iter().map(|e| (e, PathBuf::from(&e.name))).collect();
+        let err = check_links(&relatives).unwrap_err();
+        assert!(err.contains("s2"), "got: {err}");
+
+        // Order doesn't help: the link s2 runs through may come later.
+        let entries = [link("s2", "s1/.."), link("s1", ".")];
+        let relatives: Vec<_> = entries.iter().map(|e| (e, PathBuf::from(&e.name))).collect();
+        assert!(check_links(&relatives).unwrap_err().contains("escapes"));
+
+        // Writing through an in-archive link is refused even when it stays inside.
+        let entries = [link("s1", "."), file("s1/x", b"x")];
+        let relatives: Vec<_> = entries.iter().map(|e| (e, PathBuf::from(&e.name))).collect();
+        assert!(check_links(&relatives).unwrap_err().contains("is a symlink"));
+
+        let entries = [link("a", "b"), link("b", "a")];
+        let relatives: Vec<_> = entries.iter().map(|e| (e, PathBuf::from(&e.name))).collect();
+        assert!(check_links(&relatives).is_err(), "link loops must not hang");
+    }
+
+    #[test]
+    fn base256_overflow_is_an_error() {
+        let mut field = [0xffu8; 12];
+        field[0] = 0x80;
+        assert!(parse_number(&field).unwrap_err().contains("overflows"));
+
+        let mut fits = [0u8; 12];
+        fits[0] = 0x80;
+        fits[4..].fill(0xff);
+        assert_eq!(parse_number(&fits).unwrap(), u64::MAX);
+    }
+}
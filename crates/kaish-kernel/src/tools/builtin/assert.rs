@@ -1,23 +1,48 @@
-//! assert — Assert conditions for testing.
+//! assert / fail — Self-checking scripts.
+//!
+//! `assert` passes silently or fails with a message:
+//!
+//! - `assert COND` — COND must be truthy (see `is_truthy`).
+//! - `assert LEFT OP RIGHT` — a comparison, `==`/`=`/`!=` (literal equality)
+//!   or `-eq -ne -gt -lt -ge -le` (numeric), same semantics as `test`.
+//! - Trailing `message=TEXT` and `code=N` set the failure message and exit
+//!   code (default 1). The older `assert COND "message"` form still works.
+//!
+//! `assert` reads its argv raw and in source order, like `test`, so `-gt`
+//! is an operator rather than a flag. A malformed assertion (bad operator,
+//! non-numeric `-gt` operand) exits 2, never a silent pass.
+//!
+//! `fail REASON` always fails, with REASON on stderr and `code=N` / `--code N`
+//! as the exit code.
+//!
+//! # Examples
+//!
+//! ```kaish
+//! make build; assert $? == 0 message="build failed"
+//! assert $(wc -l < out.txt) -ge 3 code=3
+//! [[ -f ready ]] || fail "never became ready" --code 4
+//! ```
 
 use async_trait::async_trait;
 use clap::{CommandFactory, Parser};
 
 use crate::ast::Value;
-use crate::interpreter::ExecResult;
+use crate::interpreter::{numeric_compare, scalar_test_operand_error, value_to_string, values_equal, ExecResult};
 use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
 
 /// Assert tool: verify conditions in tests.
 pub struct Assert;
 
-/// clap-derived argv layer for assert.
+/// clap-derived argv layer for assert. The assertion is read from the raw,
+/// source-ordered `args.positional`; clap only sees the trailing `--json`.
 #[derive(Parser, Debug)]
 #[command(name = "assert", about = "Assert a condition is true (for testing)")]
 struct AssertArgs {
     #[command(flatten)]
     global: GlobalFlags,
 
-    /// Condition to assert, followed by an optional failure message.
+    /// Condition (or LEFT OP RIGHT), then optional message=TEXT and code=N.
+    #[arg(hide = true, allow_hyphen_values = true)]
     args: Vec<String>,
 }
 
@@ -35,40 +60,203 @@ impl Tool for Assert {
             [
                 ("Assert a value is truthy", "assert $RESULT"),
                 ("Assert with custom message", "assert $OK \"deploy failed\""),
+                ("Compare", "assert $? == 0 message=\"build failed\""),
+                ("Numeric, with an exit code", "assert $count -ge 3 code=3"),
             ],
         )
+        .with_raw_argv()
     }
 
     async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
         let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
             return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
         };
-        let argv = match args.to_argv() {
-            Ok(v) => v,
+        let (operands, options) = match split_trailing_options(&args.positional) {
+            Ok(split) => split,
             Err(e) => return ExecResult::failure(2, format!("assert: {e}")),
         };
         let parsed = match AssertArgs::try_parse_from(
-            std::iter::once("assert".to_string()).chain(argv),
+            std::iter::once("assert".to_string()).chain(options.flags),
         ) {
             Ok(p) => p,
             Err(e) => return ExecResult::failure(2, format!("assert: {e}")),
         };
         parsed.global.apply(ctx);
 
-        let condition = match args.get_positional(0) {
-            Some(v) => v,
-            None => return ExecResult::failure(1, "assert: missing condition argument"),
+        let (passed, mut message) = match operands {
+            [] => return ExecResult::failure(1, "assert: missing condition argument"),
+            [cond] => (is_truthy(cond), None),
+            [left, op, right] if is_comparison(&value_to_string(op)) => {
+                let op = value_to_string(op);
+                match compare(left, &op, right) {
+                    Ok(passed) => (
+                        passed,
+                        Some(format!("{} {op} {}", value_to_string(left), value_to_string(right))),
+                    ),
+                    Err(e) => return ExecResult::failure(2, format!("assert: {e}")),
+                }
+            }
+            [cond, legacy_message] => (is_truthy(cond), Some(value_to_string(legacy_message))),
+            _ => {
+                return ExecResult::failure(
+                    2,
+                    "assert: expected COND or LEFT OP RIGHT (==, !=, -eq, -ne, -gt, -lt, -ge, -le)",
+                );
+            }
         };
 
-        let message = args
-            .get_string("message", 1)
-            .unwrap_or_else(|| "assertion failed".to_string());
+        if passed {
+            return ExecResult::success("");
+        }
+        // An explicit message= wins; a comparison's own text becomes the detail.
+        if let Some(explicit) = options.message {
+            message = Some(match message {
+                Some(detail) if operands.len() == 3 => format!("{explicit} ({detail})"),
+                _ => explicit,
+            });
+        }
+        let message = message.unwrap_or_else(|| "assertion failed".to_string());
+        ExecResult::failure(options.code.unwrap_or(1), format!("assert: {message}"))
+    }
+}
+
+/// Options peeled off the end of a raw argv.
+#[derive(Default)]
+struct TrailingOptions {
+    message: Option<String>,
+    code: Option<i64>,
+    /// Global flags (`--json`) for clap.
+    flags: Vec<String>,
+}
 
-        if is_truthy(condition) {
-            ExecResult::success("")
+/// Split `message=`, `code=`, and `--json` off the end of `argv`. Only the tail
+/// is scanned, so an operand in the middle is never mistaken for an option.
+fn split_trailing_options(argv: &[Value]) -> Result<(&[Value], TrailingOptions), String> {
+    let mut options = TrailingOptions::default();
+    let mut end = argv.len();
+    while let Some(Value::String(token)) = end.checked_sub(1).map(|i| &argv[i]) {
+        if let Some(text) = token.strip_prefix("message=").or_else(|| token.strip_prefix("--message=")) {
+            // Scanning backwards, so the first seen is the last written — it wins.
+            if options.message.is_none() {
+                options.message = Some(text.to_string());
+            }
+        } else if let Some(code) = token.strip_prefix("code=").or_else(|| token.strip_prefix("--code=")) {
+            let code = parse_code(code)?;
+            if options.code.is_none() {
+                options.code = Some(code);
+            }
+        } else if token == "--json" {
+            options.flags.push(token.clone());
         } else {
-            ExecResult::failure(1, format!("assert: {}", message))
+            break;
+        }
+        end -= 1;
+    }
+    Ok((&argv[..end], options))
+}
+
+/// A failure exit code: an integer, and not 0 (that would be a pass).
+fn parse_code(text: &str) -> Result<i64, String> {
+    match text.trim().parse::<i64>() {
+        Ok(0) => Err("code must be non-zero".to_string()),
+        Ok(code) => Ok(code),
+        Err(_) => Err(format!("code must be an integer, got '{text}'")),
+    }
+}
+
+fn is_comparison(op: &str) -> bool {
+    matches!(op, "==" | "=" | "!=" | "-eq" | "-ne" | "-gt" | "-lt" | "-ge" | "-le")
+}
+
+/// `test`'s binary operators: literal equality, or numeric ordering.
+fn compare(left: &Value, op: &str, right: &Value) -> Result<bool, String> {
+    match op {
+        "==" | "=" => values_equal(left, right).map_err(|e| e.to_string()),
+        "!=" => values_equal(left, right).map(|eq| !eq).map_err(|e| e.to_string()),
+        _ => {
+            if let Some(msg) = scalar_test_operand_error(op, left).or_else(|| scalar_test_operand_error(op, right)) {
+                return Err(msg);
+            }
+            let ord = numeric_compare(left, right).map_err(|e| e.to_string())?;
+            Ok(match op {
+                "-eq" => ord.is_eq(),
+                "-ne" => !ord.is_eq(),
+                "-gt" => ord.is_gt(),
+                "-lt" => ord.is_lt(),
+                "-ge" => ord.is_ge(),
+                _ => ord.is_le(),
+            })
+        }
+    }
+}
+
+/// Fail tool: always fails with a message.
+pub struct Fail;
+
+/// clap-derived argv layer for fail.
+#[derive(Parser, Debug)]
+#[command(name = "fail", about = "Fail with a message (for testing)")]
+struct FailArgs {
+    /// Exit code (default 1).
+    #[arg(short = 'c', long = "code", allow_hyphen_values = true)]
+    code: Option<String>,
+
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// The reason, printed to stderr.
+    reason: Vec<String>,
+}
+
+#[async_trait]
+impl Tool for Fail {
+    fn name(&self) -> &str {
+        "fail"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &FailArgs::command(),
+            "fail",
+            "Fail with a message and exit code (for testing)",
+            [
+                ("Fail with a reason", "fail \"fixture missing\""),
+                ("Custom exit code", "fail \"timed out\" --code 124"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("fail: {e}")),
+        };
+        let parsed = match FailArgs::try_parse_from(
+            std::iter::once("fail".to_string()).chain(argv),
+        ) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("fail: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        // `code=N` binds as a positional `code=N`; accept it like --code.
+        let mut code = parsed.code;
+        let mut words = Vec::new();
+        for word in &parsed.reason {
+            match word.strip_prefix("code=") {
+                Some(value) => code = Some(value.to_string()),
+                None => words.push(word.as_str()),
+            }
         }
+        let code = match code.as_deref().map(parse_code).transpose() {
+            Ok(code) => code.unwrap_or(1),
+            Err(e) => return ExecResult::failure(2, format!("fail: {e}")),
+        };
+        let reason = if words.is_empty() { "failed".to_string() } else { words.join(" ") };
+        ExecResult::failure(code, reason)
     }
 }
 
@@ -211,4 +399,62 @@ mod tests {
         assert!(!result.ok());
         assert!(result.err.contains("missing condition"));
     }
+
+    fn raw(parts: &[Value]) -> ToolArgs {
+        let mut args = ToolArgs::new();
+        args.positional = parts.to_vec();
+        args
+    }
+
+    fn s(text: &str) -> Value {
+        Value::String(text.into())
+    }
+
+    #[tokio::test]
+    async fn test_assert_comparison() {
+        let mut ctx = make_ctx();
+        let result = Assert.execute(raw(&[Value::Int(0), s("=="), Value::Int(0)]), &mut ctx).await;
+        assert!(result.ok(), "{}", result.err);
+
+        let result = Assert.execute(raw(&[Value::Int(2), s("-gt"), s("10")]), &mut ctx).await;
+        assert_eq!(result.code, 1);
+        assert!(result.err.contains("2 -gt 10"), "{}", result.err);
+    }
+
+    #[tokio::test]
+    async fn test_assert_message_and_code_options() {
+        let mut ctx = make_ctx();
+        let args = raw(&[Value::Int(1), s("=="), Value::Int(0), s("message=build failed"), s("code=3")]);
+        let result = Assert.execute(args, &mut ctx).await;
+        assert_eq!(result.code, 3);
+        assert_eq!(result.err, "assert: build failed (1 == 0)");
+    }
+
+    #[tokio::test]
+    async fn test_assert_malformed_exits_2() {
+        let mut ctx = make_ctx();
+        let result = Assert.execute(raw(&[s("a"), s("-gt"), Value::Int(1)]), &mut ctx).await;
+        assert_eq!(result.code, 2, "non-numeric -gt operand is loud");
+        let result = Assert.execute(raw(&[s("a"), s("b"), s("c")]), &mut ctx).await;
+        assert_eq!(result.code, 2);
+        let result = Assert.execute(raw(&[Value::Bool(false), s("code=0")]), &mut ctx).await;
+        assert_eq!(result.code, 2, "code=0 would read as a pass");
+    }
+
+    #[tokio::test]
+    async fn test_fail_reason_and_code() {
+        let mut ctx = make_ctx();
+        let result = Fail.execute(raw(&[s("fixture"), s("missing")]), &mut ctx).await;
+        assert_eq!(result.code, 1);
+        assert_eq!(result.err, "fixture missing");
+
+        let mut args = raw(&[s("timed out")]);
+        args.named.insert("code".into(), Value::Int(124));
+        let result = Fail.execute(args, &mut ctx).await;
+        assert_eq!(result.code, 124);
+
+        let result = Fail.execute(raw(&[s("nope"), s("code=7")]), &mut ctx).await;
+        assert_eq!(result.code, 7);
+        assert_eq!(result.err, "nope");
+    }
 }
@@ -0,0 +1,463 @@
+//! calc — Float-capable math: expressions and aggregates over numbers.
+//!
+//! `$(( ))` is integer-only by design; `calc` is where fractional math lives
+//! without reaching for `jq`. Two modes, picked by the first argument:
+//!
+//! - **Aggregate** — `sum`, `min`, `max`, `avg`, `count` over a list of
+//!   numbers: a list argument, upstream `.data`, or whitespace-separated
+//!   numbers on stdin.
+//! - **Expression** — anything else is an expression: `+ - * / % **`,
+//!   parentheses, and `abs floor ceil round sqrt min max`. Variables are
+//!   already expanded by the time calc sees them, so `calc "(${A}+${B})/2"`
+//!   works. `/` is real division (`7/2` is `3.5`).
+//!
+//! The result is printed and returned in `.data` as a number — an integer
+//! when it is whole, so `[[ $(calc sum $xs) -gt 10 ]]` compares cleanly.
+//! `--round N` rounds the result to N decimal places.
+//!
+//! # Examples
+//!
+//! ```kaish
+//! calc "(${A}+${B})/2"
+//! calc --round 2 "10 / 3"
+//! seq 1 10 | calc sum
+//! jq '[.items[].price]' cart.json | calc avg
+//! calc max $latencies
+//! ```
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+
+use crate::ast::Value;
+use crate::interpreter::{value_to_string, ExecResult};
+use crate::tools::{schema_from_clap, ExecContext, GlobalFlags, Tool, ToolArgs, ToolCtx, ToolSchema};
+
+/// Calc tool: expressions and aggregates over numbers.
+pub struct Calc;
+
+/// clap-derived argv layer for calc.
+#[derive(Parser, Debug)]
+#[command(name = "calc", about = "Evaluate a math expression or aggregate a list of numbers")]
+struct CalcArgs {
+    /// Round the result to N decimal places (-r).
+    #[arg(short = 'r', long = "round")]
+    round: Option<u32>,
+
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// An aggregate (sum/min/max/avg/count) and optional list, or an
+    /// expression. Hidden sink — the real values are read off
+    /// `args.positional`.
+    #[arg(hide = true, allow_hyphen_values = true)]
+    args: Vec<String>,
+}
+
+/// The aggregates calc knows, by first argument.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Aggregate {
+    Sum,
+    Min,
+    Max,
+    Avg,
+    Count,
+}
+
+impl Aggregate {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sum" => Some(Aggregate::Sum),
+            "min" => Some(Aggregate::Min),
+            "max" => Some(Aggregate::Max),
+            "avg" => Some(Aggregate::Avg),
+            "count" => Some(Aggregate::Count),
+            _ => None,
+        }
+    }
+
+    fn apply(self, numbers: &[f64]) -> Result<f64, String> {
+        match self {
+            Aggregate::Sum => Ok(numbers.iter().sum()),
+            Aggregate::Count => Ok(numbers.len() as f64),
+            _ if numbers.is_empty() => Err("no numbers to aggregate".to_string()),
+            Aggregate::Min => Ok(numbers.iter().copied().fold(f64::INFINITY, f64::min)),
+            Aggregate::Max => Ok(numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max)),
+            Aggregate::Avg => Ok(numbers.iter().sum::<f64>() / numbers.len() as f64),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for Calc {
+    fn name(&self) -> &str {
+        "calc"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &CalcArgs::command(),
+            "calc",
+            "Evaluate a math expression or sum/min/max/avg/count numbers (result in .data)",
+            [
+                ("Average of two variables", "calc \"(${A}+${B})/2\""),
+                ("Round to two places", "calc --round 2 \"10 / 3\""),
+                ("Sum stdin numbers", "seq 1 10 | calc sum"),
+                ("Aggregate a list", "calc max $latencies"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("calc: {e}")),
+        };
+        let parsed = match CalcArgs::try_parse_from(
+            std::iter::once("calc".to_string()).chain(argv),
+        ) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("calc: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let aggregate = match args.positional.first() {
+            Some(Value::String(name)) => Aggregate::from_name(name),
+            Some(_) => None,
+            None => return ExecResult::failure(2, "calc: usage: calc EXPRESSION | calc sum|min|max|avg|count [LIST]"),
+        };
+
+        let result = match aggregate {
+            Some(aggregate) => {
+                let numbers = match args.positional.get(1) {
+                    Some(list) if args.positional.len() == 2 => numbers_from_value(list),
+                    Some(_) => Err("aggregates take one list argument (or stdin)".to_string()),
+                    None => match ctx.resolve_stdin().await {
+                        Ok((Some(data), _)) => numbers_from_value(&data),
+                        Ok((None, text)) => numbers_from_text(&text),
+                        Err(e) => return ExecResult::failure(2, format!("calc: {e}")),
+                    },
+                };
+                numbers.and_then(|n| aggregate.apply(&n))
+            }
+            None => {
+                let expr: Vec<String> = args.positional.iter().map(value_to_string).collect();
+                eval_expression(&expr.join(" "))
+            }
+        };
+
+        match result {
+            Ok(n) => {
+                let n = match parsed.round {
+                    Some(places) => round_to(n, places),
+                    None => n,
+                };
+                let value = number_value(n);
+                ExecResult::success_with_data(format!("{}\n", value_to_string(&value)), value)
+            }
+            Err(e) => ExecResult::failure(1, format!("calc: {e}")),
+        }
+    }
+}
+
+/// Round to `places` decimal places, half away from zero.
+fn round_to(n: f64, places: u32) -> f64 {
+    let scale = 10f64.powi(places.min(15) as i32);
+    (n * scale).round() / scale
+}
+
+/// A whole result within f64's exact-integer range comes back as `Int`, so
+/// `-gt`/`-eq` and `$(( ))` consume it without a float detour.
+fn number_value(n: f64) -> Value {
+    const EXACT: f64 = 9_007_199_254_740_992.0; // 2^53
+    if n.fract() == 0.0 && n.abs() <= EXACT {
+        Value::Int(n as i64)
+    } else {
+        Value::Float(n)
+    }
+}
+
+/// Numbers from a list (or a single number), numeric strings included.
+fn numbers_from_value(value: &Value) -> Result<Vec<f64>, String> {
+    match value {
+        Value::Json(serde_json::Value::Array(items)) => items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| match item {
+                serde_json::Value::Number(n) => n.as_f64().ok_or_else(|| format!("element {i}: not a finite number")),
+                serde_json::Value::String(s) => parse_number(s).map_err(|e| format!("element {i}: {e}")),
+                other => Err(format!("element {i}: expected a number, got {}", json_kind(other))),
+            })
+            .collect(),
+        Value::Int(i) => Ok(vec![*i as f64]),
+        Value::Float(f) => Ok(vec![*f]),
+        Value::String(s) => numbers_from_text(s),
+        other => Err(format!("expected a list of numbers, got {}", super::keys::describe_kind(other))),
+    }
+}
+
+/// Whitespace-separated numbers from text.
+fn numbers_from_text(text: &str) -> Result<Vec<f64>, String> {
+    text.split_whitespace().map(parse_number).collect()
+}
+
+fn parse_number(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(n) if n.is_finite() => Ok(n),
+        _ => Err(format!("not a number: '{s}'")),
+    }
+}
+
+fn json_kind(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "a bool",
+        serde_json::Value::Number(_) => "a number",
+        serde_json::Value::String(_) => "a string",
+        serde_json::Value::Array(_) => "a list",
+        serde_json::Value::Object(_) => "a record",
+    }
+}
+
+/// Evaluate an expression over f64.
+fn eval_expression(input: &str) -> Result<f64, String> {
+    let mut parser = ExprParser { chars: input.chars().collect(), pos: 0 };
+    let n = parser.parse_sum()?;
+    parser.skip_whitespace();
+    if let Some(c) = parser.peek() {
+        return Err(format!("unexpected '{c}' at position {} in '{input}'", parser.pos + 1));
+    }
+    if !n.is_finite() {
+        return Err(format!("'{input}' is not a finite number"));
+    }
+    Ok(n)
+}
+
+/// Recursive descent, lowest precedence first: sum → product → power → unary
+/// → atom. `**` is right-associative and binds tighter than unary minus, so
+/// `-2 ** 2` is `-4`, as in Python.
+struct ExprParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl ExprParser {
+    fn skip_whitespace(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        let end = self.pos + token.chars().count();
+        if end <= self.chars.len() && self.chars[self.pos..end].iter().copied().eq(token.chars()) {
+            self.pos = end;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_sum(&mut self) -> Result<f64, String> {
+        let mut left = self.parse_product()?;
+        loop {
+            if self.eat("+") {
+                left += self.parse_product()?;
+            } else if self.eat("-") {
+                left -= self.parse_product()?;
+            } else {
+                return Ok(left);
+            }
+        }
+    }
+
+    fn parse_product(&mut self) -> Result<f64, String> {
+        let mut left = self.parse_unary()?;
+        loop {
+            // `**` must be checked before `*`; parse_power handles it.
+            if self.peek() == Some('*') && self.chars.get(self.pos + 1) != Some(&'*') {
+                self.pos += 1;
+                left *= self.parse_unary()?;
+            } else if self.eat("/") {
+                let right = self.parse_unary()?;
+                if right == 0.0 {
+                    return Err("division by zero".to_string());
+                }
+                left /= right;
+            } else if self.eat("%") {
+                let right = self.parse_unary()?;
+                if right == 0.0 {
+                    return Err("modulo by zero".to_string());
+                }
+                left %= right;
+            } else {
+                return Ok(left);
+            }
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<f64, String> {
+        if self.eat("-") {
+            Ok(-self.parse_unary()?)
+        } else if self.eat("+") {
+            self.parse_unary()
+        } else {
+            self.parse_power()
+        }
+    }
+
+    fn parse_power(&mut self) -> Result<f64, String> {
+        let base = self.parse_atom()?;
+        if self.eat("**") {
+            let exponent = self.parse_unary()?;
+            Ok(base.powf(exponent))
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn parse_atom(&mut self) -> Result<f64, String> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let n = self.parse_sum()?;
+                if !self.eat(")") {
+                    return Err("missing ')'".to_string());
+                }
+                Ok(n)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.parse_literal(),
+            Some(c) if c.is_ascii_alphabetic() => self.parse_call(),
+            Some(c) => Err(format!("unexpected '{c}' at position {}", self.pos + 1)),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+
+    fn parse_literal(&mut self) -> Result<f64, String> {
+        let start = self.pos;
+        while self.chars.get(self.pos).is_some_and(|c| c.is_ascii_digit() || *c == '.') {
+            self.pos += 1;
+        }
+        // Exponent: `1e3`, `2.5E-4`.
+        if self.chars.get(self.pos).is_some_and(|c| *c == 'e' || *c == 'E') {
+            let mark = self.pos;
+            self.pos += 1;
+            if self.chars.get(self.pos).is_some_and(|c| *c == '+' || *c == '-') {
+                self.pos += 1;
+            }
+            if self.chars.get(self.pos).is_some_and(|c| c.is_ascii_digit()) {
+                while self.chars.get(self.pos).is_some_and(|c| c.is_ascii_digit()) {
+                    self.pos += 1;
+                }
+            } else {
+                self.pos = mark;
+            }
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        parse_number(&text)
+    }
+
+    fn parse_call(&mut self) -> Result<f64, String> {
+        let start = self.pos;
+        while self.chars.get(self.pos).is_some_and(|c| c.is_ascii_alphanumeric() || *c == '_') {
+            self.pos += 1;
+        }
+        let name: String = self.chars[start..self.pos].iter().collect();
+        if !self.eat("(") {
+            return Err(format!("unknown name '{name}' (variables must be written ${{{name}}})"));
+        }
+        let mut args = Vec::new();
+        if !self.eat(")") {
+            loop {
+                args.push(self.parse_sum()?);
+                if self.eat(")") {
+                    break;
+                }
+                if !self.eat(",") {
+                    return Err(format!("{name}(): expected ',' or ')'"));
+                }
+            }
+        }
+        let arity = |n: usize| {
+            if args.len() == n {
+                Ok(())
+            } else {
+                Err(format!("{name}() takes {n} argument(s), got {}", args.len()))
+            }
+        };
+        match name.as_str() {
+            "abs" => arity(1).map(|_| args[0].abs()),
+            "floor" => arity(1).map(|_| args[0].floor()),
+            "ceil" => arity(1).map(|_| args[0].ceil()),
+            "sqrt" => arity(1).map(|_| args[0].sqrt()),
+            "round" => match args.as_slice() {
+                [n] => Ok(n.round()),
+                [n, places] if *places >= 0.0 && places.fract() == 0.0 => Ok(round_to(*n, *places as u32)),
+                [_, _] => Err("round(): places must be a whole number ≥ 0".to_string()),
+                _ => Err(format!("round() takes 1 or 2 arguments, got {}", args.len())),
+            },
+            "min" | "max" if args.is_empty() => Err(format!("{name}() needs at least one argument")),
+            "min" => Ok(args.iter().copied().fold(f64::INFINITY, f64::min)),
+            "max" => Ok(args.iter().copied().fold(f64::NEG_INFINITY, f64::max)),
+            _ => Err(format!("unknown function '{name}' (have abs, ceil, floor, max, min, round, sqrt)")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precedence_and_real_division() {
+        assert_eq!(eval_expression("1 + 2 * 3").unwrap(), 7.0);
+        assert_eq!(eval_expression("(1 + 2) * 3").unwrap(), 9.0);
+        assert_eq!(eval_expression("7 / 2").unwrap(), 3.5);
+        assert_eq!(eval_expression("2 ** 3 ** 2").unwrap(), 512.0);
+        assert_eq!(eval_expression("-2 ** 2").unwrap(), -4.0);
+        assert_eq!(eval_expression("10 % 4 - -1").unwrap(), 3.0);
+        assert_eq!(eval_expression("1.5e2").unwrap(), 150.0);
+    }
+
+    #[test]
+    fn functions() {
+        assert_eq!(eval_expression("max(1, 5, 3) + min(2)").unwrap(), 7.0);
+        assert_eq!(eval_expression("round(2.346, 2)").unwrap(), 2.35);
+        assert_eq!(eval_expression("sqrt(16) + abs(-1) + floor(1.9) + ceil(0.1)").unwrap(), 7.0);
+    }
+
+    #[test]
+    fn expression_errors() {
+        assert_eq!(eval_expression("1 / 0").unwrap_err(), "division by zero");
+        assert!(eval_expression("1 +").is_err());
+        assert!(eval_expression("(1").is_err());
+        assert!(eval_expression("x + 1").unwrap_err().contains("${x}"));
+        assert!(eval_expression("nope(1)").unwrap_err().contains("unknown function"));
+    }
+
+    #[test]
+    fn aggregates() {
+        let n = [3.0, 1.0, 2.0];
+        assert_eq!(Aggregate::Sum.apply(&n).unwrap(), 6.0);
+        assert_eq!(Aggregate::Min.apply(&n).unwrap(), 1.0);
+        assert_eq!(Aggregate::Max.apply(&n).unwrap(), 3.0);
+        assert_eq!(Aggregate::Avg.apply(&n).unwrap(), 2.0);
+        assert_eq!(Aggregate::Count.apply(&[]).unwrap(), 0.0);
+        assert!(Aggregate::Avg.apply(&[]).is_err());
+    }
+
+    #[test]
+    fn whole_results_are_ints() {
+        assert_eq!(number_value(4.0), Value::Int(4));
+        assert_eq!(number_value(2.5), Value::Float(2.5));
+        assert_eq!(number_value(round_to(10.0 / 3.0, 2)), Value::Float(3.33));
+    }
+}
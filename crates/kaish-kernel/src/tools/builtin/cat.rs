@@ -1,15 +1,22 @@
 //! cat — Read and output file contents.
+//!
+//! `--range START:END` prints a slice of lines (1-based, inclusive) instead of
+//! the whole file, so a big log or source file can be read a window at a
+//! time. With `-n` the numbers are the lines' positions in their file, ready
+//! to quote back. A range needs text: binary content is an error rather than
+//! a slice of mojibake.
 
 use async_trait::async_trait;
 use clap::{CommandFactory, Parser};
 use std::path::Path;
+use std::time::Duration;
 
-#[cfg(test)]
 use crate::ast::Value;
 use crate::backend::ReadRange;
 use crate::interpreter::{ExecResult, OutputData};
+use crate::result_cache::{operands_on_read_only_mounts, READ_ONLY_TTL};
 use crate::scheduler::PipeWriter;
-use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolAnnotations, ToolSchema};
 
 /// Cat tool: read and output file contents.
 pub struct Cat;
@@ -22,10 +29,15 @@ struct CatArgs {
     #[arg(short = 'n', long = "number")]
     number: bool,
 
+    /// Print only lines START:END (1-based, inclusive; `START:` runs to the end).
+    #[arg(long = "range", value_name = "START:END")]
+    range: Option<String>,
+
     #[command(flatten)]
     global: GlobalFlags,
 
-    /// Files to read. Reads stdin when no files are given.
+    /// Files to read. Reads stdin when no files are given; `-` reads stdin in
+    /// its place among the files.
     paths: Vec<String>,
 }
 
@@ -43,9 +55,18 @@ impl Tool for Cat {
             [
                 ("Read a file", "cat README.md"),
                 ("Show line numbers", "cat -n src/main.rs"),
+                ("Lines 100-150 of a big file, numbered", "cat -n --range 100:150 server.log"),
                 ("Concatenate files", "cat header.txt body.txt"),
+                ("Read stdin explicitly", "producer | cat -"),
             ],
         )
+        .with_annotations(ToolAnnotations::read_only())
+    }
+
+    /// Files on read-only mounts can't change under the session, so their
+    /// contents are cached for a while.
+    fn cache_ttl(&self, args: &ToolArgs, ctx: &dyn ToolCtx) -> Option<Duration> {
+        operands_on_read_only_mounts(args, ctx).then_some(READ_ONLY_TTL)
     }
 
     async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
@@ -64,11 +85,17 @@ impl Tool for Cat {
         };
         parsed.global.apply(ctx);
         let number_lines = parsed.number;
+        let range = match parsed.range.as_deref().map(LineRange::parse).transpose() {
+            Ok(r) => r,
+            Err(e) => return ExecResult::failure(2, format!("cat: {e}")),
+        };
 
-        // If no files specified, read from stdin (like POSIX cat)
-        if args.positional.is_empty() {
+        // If no files specified, read from stdin (like POSIX cat). A lone `-`
+        // names stdin explicitly.
+        let stdin_only = matches!(args.positional.as_slice(), [Value::String(s)] if s == "-");
+        if args.positional.is_empty() || stdin_only {
             // Streaming path: pipe_stdin → pipe_stdout without buffering
-            if !number_lines && ctx.pipe_stdin.is_some() && ctx.pipe_stdout.is_some() {
+            if !number_lines && range.is_none() && ctx.pipe_stdin.is_some() && ctx.pipe_stdout.is_some() {
                 if let (Some(mut pipe_in), Some(mut pipe_out)) =
                     (ctx.pipe_stdin.take(), ctx.pipe_stdout.take())
                 {
@@ -94,6 +121,14 @@ impl Tool for Cat {
             // we need text for line numbering, so binary is a loud error;
             // without it, stay byte-clean so piped binary survives intact
             // (`dd if=/dev/urandom … | cat` → a Bytes result, not a lossy mangle).
+            if let Some(range) = range {
+                let stdin = ctx.read_stdin_to_bytes().await.unwrap_or_default();
+                let slice = range.read_range().apply(&stdin);
+                return match range.render(slice, number_lines) {
+                    Some(text) => ExecResult::with_output(OutputData::text(text)),
+                    None => ExecResult::failure(1, "cat: stdin: --range needs text, not binary data"),
+                };
+            }
             if number_lines {
                 let stdin = match ctx.read_stdin_to_text().await {
                     Ok(s) => s.unwrap_or_default(),
@@ -127,11 +162,31 @@ impl Tool for Cat {
             return ExecResult::failure(1, "cat: missing path argument");
         }
 
+        // Ranged read: the backend slices each file, so a remote or range-aware
+        // mount only ships the window asked for.
+        if let Some(range) = range {
+            let mut all_content = String::new();
+            for path in paths.iter() {
+                let slice = match read_operand(ctx, path, Some(range.read_range())).await {
+                    Ok(data) => data,
+                    Err(e) => return ExecResult::failure(1, format!("cat: {}: {}", path, e)),
+                };
+                match range.render(slice, number_lines) {
+                    Some(text) => all_content.push_str(&text),
+                    None => {
+                        return ExecResult::failure(1, format!("cat: {}: --range needs text, not binary data", path));
+                    }
+                }
+            }
+            return ExecResult::with_output(OutputData::text(all_content));
+        }
+
         // Binary-capable fast path: a single file with no line numbering. Valid
         // UTF-8 is text as before; anything else becomes a Bytes result (a hex
         // dump in the REPL, a base64 envelope under --json) instead of the old
-        // "invalid UTF-8" error. Multi-file / -n stay text-only below — you
-        // can't line-number or text-concat binary. See docs/binary-data.md.
+        // "invalid UTF-8" error. Multi-file concatenation below is byte-clean
+        // too; only -n stays text-only — you can't line-number binary. See
+        // docs/binary-data.md.
         if paths.len() == 1 && !number_lines {
             let resolved = ctx.resolve_path(&paths[0]);
             // Streaming path: when cat feeds a downstream stage, stream the file
@@ -155,6 +210,22 @@ impl Tool for Cat {
             };
         }
 
+        // Multi-file without `-n`: byte-verbatim concatenation, so joining
+        // binary parts (`cat part1 part2 > whole.tar`) stays intact. Never
+        // synthesize a separator — inserting a newline between files (or after
+        // a file that lacks a trailing one) corrupts the stream: `cat x y`
+        // where x is `a` and y is `b\n` must be `ab\n`, not `a\nb\n`.
+        if !number_lines {
+            let mut all_bytes = Vec::new();
+            for path in paths.iter() {
+                match read_operand(ctx, path, None).await {
+                    Ok(data) => all_bytes.extend_from_slice(&data),
+                    Err(e) => return ExecResult::failure(1, format!("cat: {}: {}", path, e)),
+                }
+            }
+            return ExecResult::success_text_or_bytes(all_bytes);
+        }
+
         let mut all_content = String::new();
         let mut line_num = 1;
         // Track whether the last file processed ends with a newline so we can
@@ -163,27 +234,16 @@ impl Tool for Cat {
         let mut last_had_trailing_newline = false;
 
         for path in paths.iter() {
-            let resolved = ctx.resolve_path(path);
-
-            match ctx.backend.read(Path::new(&resolved), None).await {
+            match read_operand(ctx, path, None).await {
                 Ok(data) => match String::from_utf8(data) {
                     Ok(content) => {
-                        if number_lines {
-                            last_had_trailing_newline = content.ends_with('\n');
-                            for line in content.lines() {
-                                if !all_content.is_empty() {
-                                    all_content.push('\n');
-                                }
-                                all_content.push_str(&format!("{:6}\t{}", line_num, line));
-                                line_num += 1;
+                        last_had_trailing_newline = content.ends_with('\n');
+                        for line in content.lines() {
+                            if !all_content.is_empty() {
+                                all_content.push('\n');
                             }
-                        } else {
-                            // Byte-verbatim concatenation: never synthesize a
-                            // separator. Inserting a newline between files (or
-                            // after a file that lacks a trailing one) corrupts
-                            // the stream — `cat x y` where x is `a` and y is
-                            // `b\n` must be `ab\n`, not `a\nb\n`.
-                            all_content.push_str(&content);
+                            all_content.push_str(&format!("{:6}\t{}", line_num, line));
+                            line_num += 1;
                         }
                     }
                     Err(_) => return ExecResult::failure(1, format!("cat: {}: invalid UTF-8", path)),
@@ -194,7 +254,7 @@ impl Tool for Cat {
 
         // `.lines()` strips trailing newlines; restore one if the last file
         // ended with `\n` (which it almost always does — this was the bug).
-        if number_lines && last_had_trailing_newline {
+        if last_had_trailing_newline {
             all_content.push('\n');
         }
 
@@ -202,6 +262,78 @@ impl Tool for Cat {
     }
 }
 
+/// Read one operand of a multi-file `cat`: `-` is stdin, anything else a
+/// file. Stdin is consumed by the first `-`, so a second one reads nothing,
+/// as in POSIX `cat - -`.
+async fn read_operand(ctx: &mut ExecContext, path: &str, range: Option<ReadRange>) -> Result<Vec<u8>, String> {
+    if path == "-" {
+        let stdin = ctx.read_stdin_to_bytes().await.unwrap_or_default();
+        return Ok(match range {
+            Some(range) => range.apply(&stdin),
+            None => stdin,
+        });
+    }
+    let resolved = ctx.resolve_path(path);
+    ctx.backend.read(Path::new(&resolved), range).await.map_err(|e| e.to_string())
+}
+
+/// A `--range START:END` line window: 1-based, inclusive, open-ended when
+/// `end` is `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LineRange {
+    start: usize,
+    end: Option<usize>,
+}
+
+impl LineRange {
+    /// Parse `START:END`, `START:`, `:END`, or a lone `N` (just line N).
+    fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid --range '{spec}' (expected START:END, e.g. 10:20)");
+        let line = |s: &str| match s.parse::<usize>() {
+            Ok(0) | Err(_) => Err(invalid()),
+            Ok(n) => Ok(n),
+        };
+        let range = match spec.split_once(':') {
+            Some((start, end)) => Self {
+                start: if start.is_empty() { 1 } else { line(start)? },
+                end: if end.is_empty() { None } else { Some(line(end)?) },
+            },
+            None => {
+                let n = line(spec)?;
+                Self { start: n, end: Some(n) }
+            }
+        };
+        if range.end.is_some_and(|end| end < range.start) {
+            return Err(format!("invalid --range '{spec}': END is before START"));
+        }
+        Ok(range)
+    }
+
+    fn read_range(self) -> ReadRange {
+        ReadRange {
+            start_line: Some(self.start),
+            end_line: self.end,
+            ..Default::default()
+        }
+    }
+
+    /// Render a sliced window one line per output line, numbered from
+    /// `start` when asked. `None` when the content isn't text — a line range
+    /// over binary comes back unsliced, and printing it would be mojibake.
+    fn render(self, slice: Vec<u8>, number: bool) -> Option<String> {
+        let text = String::from_utf8(slice).ok()?;
+        let mut out = String::new();
+        for (i, line) in text.lines().enumerate() {
+            if number {
+                out.push_str(&format!("{:6}\t", self.start + i));
+            }
+            out.push_str(line);
+            out.push('\n');
+        }
+        Some(out)
+    }
+}
+
 /// Stream a single file to a pipe in bounded `chunk_size` chunks.
 ///
 /// Exposed as a standalone async function (not a method) so tests can call it
@@ -319,6 +451,32 @@ mod tests {
         assert_eq!(json["len"], 5);
     }
 
+    #[tokio::test]
+    async fn test_cat_dash_reads_stdin() {
+        let mut ctx = make_ctx().await;
+        ctx.set_stdin("piped".to_string());
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("-".into()));
+
+        let result = Cat.execute(args, &mut ctx).await;
+        assert!(result.ok(), "stderr: {}", result.err);
+        assert_eq!(&*result.text_out(), "piped");
+    }
+
+    #[tokio::test]
+    async fn test_cat_dash_between_files() {
+        let mut ctx = make_ctx().await;
+        ctx.set_stdin("-piped-".to_string());
+        let mut args = ToolArgs::new();
+        for path in ["/test.txt", "-", "/other.txt", "-"] {
+            args.positional.push(Value::String(path.into()));
+        }
+
+        let result = Cat.execute(args, &mut ctx).await;
+        assert!(result.ok(), "stderr: {}", result.err);
+        assert_eq!(&*result.text_out(), "hello world-piped-other content");
+    }
+
     #[tokio::test]
     async fn test_cat_not_found() {
         let mut ctx = make_ctx().await;
@@ -379,6 +537,22 @@ mod tests {
         assert!(result.text_out().contains("other content"));
     }
 
+    #[tokio::test]
+    async fn test_cat_multiple_files_binary_concatenates_bytes() {
+        // Joining binary parts must keep every byte, not fail on UTF-8.
+        let mut ctx = make_ctx().await;
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("/blob.bin".into()));
+        args.positional.push(Value::String("/test.txt".into()));
+
+        let result = Cat.execute(args, &mut ctx).await;
+        assert!(result.ok(), "stderr: {}", result.err);
+        assert!(result.is_bytes());
+        let mut expected = vec![0u8, 0xff, 0xfe, 0x41, 0x80];
+        expected.extend_from_slice(b"hello world");
+        assert_eq!(result.out_bytes(), Some(&expected[..]));
+    }
+
     #[tokio::test]
     async fn test_cat_n_line_numbers() {
         let mut ctx = make_ctx().await;
@@ -419,6 +593,52 @@ mod tests {
         assert!(result.text_out().contains("4\thello world"));
     }
 
+    #[tokio::test]
+    async fn test_cat_range_numbers_lines_in_their_file() {
+        let mut ctx = make_ctx().await;
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("/lines.txt".into()));
+        args.positional.push(Value::String("/test.txt".into()));
+        args.named.insert("range".to_string(), Value::String("2:".into()));
+        args.flags.insert("n".to_string());
+
+        let result = Cat.execute(args, &mut ctx).await;
+        assert!(result.ok(), "stderr: {}", result.err);
+        // test.txt has one line, so `2:` takes nothing from it.
+        assert_eq!(&*result.text_out(), "     2\tline2\n     3\tline3\n");
+
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("/lines.txt".into()));
+        args.named.insert("range".to_string(), Value::String("2".into()));
+        let result = Cat.execute(args, &mut ctx).await;
+        assert_eq!(&*result.text_out(), "line2\n");
+
+        ctx.set_stdin("a\nb\nc\n".to_string());
+        let mut args = ToolArgs::new();
+        args.named.insert("range".to_string(), Value::String(":2".into()));
+        let result = Cat.execute(args, &mut ctx).await;
+        assert_eq!(&*result.text_out(), "a\nb\n");
+    }
+
+    #[tokio::test]
+    async fn test_cat_range_rejects_binary_and_bad_specs() {
+        let mut ctx = make_ctx().await;
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("/blob.bin".into()));
+        args.named.insert("range".to_string(), Value::String("1:2".into()));
+        let result = Cat.execute(args, &mut ctx).await;
+        assert_eq!(result.code, 1);
+        assert!(result.err.contains("needs text"), "{}", result.err);
+
+        for spec in ["0:3", "5:2", "a:b", ""] {
+            let mut args = ToolArgs::new();
+            args.positional.push(Value::String("/lines.txt".into()));
+            args.named.insert("range".to_string(), Value::String(spec.into()));
+            let result = Cat.execute(args, &mut ctx).await;
+            assert_eq!(result.code, 2, "{spec}: {}", result.err);
+        }
+    }
+
     #[tokio::test]
     async fn test_cat_glob() {
         let mut ctx = make_ctx().await;
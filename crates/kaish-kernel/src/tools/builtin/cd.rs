@@ -1,4 +1,8 @@
 //! cd — Change working directory.
+//!
+//! A relative operand that doesn't start with `.` is looked up under each
+//! `$CDPATH` entry (colon-separated) before the current directory; when a
+//! `$CDPATH` entry supplies the directory, `cd` prints where it went.
 
 use async_trait::async_trait;
 use clap::{CommandFactory, Parser};
@@ -39,6 +43,7 @@ impl Tool for Cd {
                 ("Go home", "cd"),
                 ("Change directory", "cd /tmp"),
                 ("Previous directory", "cd -"),
+                ("Search CDPATH for a project", "CDPATH=/src cd kaish"),
             ],
         )
     }
@@ -79,35 +84,63 @@ impl Tool for Cd {
             Err(e) => return ExecResult::failure(1, format!("cd: {e}")),
         };
 
-        // Handle `cd -` for previous directory
-        let resolved: PathBuf = if path_arg == "-" {
+        // Handle `cd -` for previous directory; otherwise try CDPATH first.
+        let (resolved, announce): (PathBuf, bool) = if path_arg == "-" {
             match ctx.get_prev_cwd() {
-                Some(prev) => prev.clone(),
+                Some(prev) => (prev.clone(), true),
                 None => return ExecResult::failure(1, "cd: OLDPWD not set"),
             }
         } else {
-            ctx.resolve_path(&path_arg)
+            match search_cdpath(ctx, &path_arg).await {
+                Some(found) => (found, true),
+                None => (ctx.resolve_path(&path_arg), false),
+            }
         };
 
-        // Verify the path exists and is a directory
-        match ctx.backend.stat(Path::new(&resolved)).await {
-            Ok(info) => {
-                if info.is_dir() {
-                    let new_cwd = resolved.clone();
-                    ctx.set_cwd(new_cwd);
-                    // For `cd -`, output the new directory (like bash)
-                    if path_arg == "-" {
-                        ExecResult::with_output(OutputData::text(resolved.to_string_lossy().to_string()))
-                    } else {
-                        ExecResult::success("")
-                    }
-                } else {
-                    ExecResult::failure(1, format!("cd: {}: Not a directory", path_arg))
-                }
-            }
-            Err(e) => ExecResult::failure(1, format!("cd: {}: {}", path_arg, e)),
+        if let Err(e) = check_dir(ctx, &resolved, &path_arg).await {
+            return ExecResult::failure(1, format!("cd: {e}"));
+        }
+        ctx.set_cwd(resolved.clone());
+        // For `cd -` and a CDPATH hit, output the new directory (like bash)
+        if announce {
+            ExecResult::with_output(OutputData::text(resolved.to_string_lossy().to_string()))
+        } else {
+            ExecResult::success("")
+        }
+    }
+}
+
+/// Verify `path` exists and is a directory; the error names it as `shown`.
+/// Shared with `pushd`/`popd`.
+pub(super) async fn check_dir(ctx: &ExecContext, path: &Path, shown: &str) -> Result<(), String> {
+    match ctx.backend.stat(path).await {
+        Ok(info) if info.is_dir() => Ok(()),
+        Ok(_) => Err(format!("{shown}: Not a directory")),
+        Err(e) => Err(format!("{shown}: {e}")),
+    }
+}
+
+/// The first `$CDPATH` entry holding directory `path`, for a relative `path`
+/// that doesn't start with `.`. An empty entry means the current directory,
+/// which plain resolution covers, so it ends the search.
+async fn search_cdpath(ctx: &ExecContext, path: &str) -> Option<PathBuf> {
+    if path.starts_with('/') || path.starts_with('.') {
+        return None;
+    }
+    let cdpath = match ctx.scope.get("CDPATH") {
+        Some(Value::String(s)) => s.clone(),
+        _ => return None,
+    };
+    for base in cdpath.split(':') {
+        if base.is_empty() {
+            return None;
+        }
+        let candidate = ctx.resolve_path(&format!("{base}/{path}"));
+        if ctx.backend.stat(&candidate).await.is_ok_and(|info| info.is_dir()) {
+            return Some(candidate);
         }
     }
+    None
 }
 
 #[cfg(test)]
@@ -227,6 +260,30 @@ mod tests {
         assert!(result.err.contains("OLDPWD not set"));
     }
 
+    #[tokio::test]
+    async fn test_cd_searches_cdpath() {
+        let mut ctx = make_ctx().await;
+        ctx.backend.mkdir(Path::new("/src/kaish")).await.unwrap();
+        ctx.backend.mkdir(Path::new("/subdir/kaish")).await.unwrap();
+        ctx.scope.set("CDPATH", Value::String("/nowhere:/src".into()));
+
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("kaish".into()));
+        let result = Cd.execute(args, &mut ctx).await;
+        assert!(result.ok(), "{}", result.err);
+        assert_eq!(ctx.cwd, PathBuf::from("/src/kaish"));
+        assert_eq!(&*result.text_out(), "/src/kaish");
+
+        // A `./` operand skips CDPATH.
+        ctx.set_cwd(PathBuf::from("/subdir"));
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("./kaish".into()));
+        let result = Cd.execute(args, &mut ctx).await;
+        assert!(result.ok(), "{}", result.err);
+        assert_eq!(ctx.cwd, PathBuf::from("/subdir/kaish"));
+        assert_eq!(&*result.text_out(), "");
+    }
+
     #[tokio::test]
     async fn test_bare_cd_uses_scope_home() {
         let mut ctx = make_ctx().await;
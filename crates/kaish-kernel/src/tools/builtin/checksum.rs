@@ -3,12 +3,14 @@
 use async_trait::async_trait;
 use clap::{CommandFactory, Parser};
 use std::path::Path;
+use std::time::Duration;
 
 use digest::Digest;
 
 use crate::interpreter::{ExecResult, OutputData, OutputNode};
+use crate::result_cache::{operands_on_read_only_mounts, READ_ONLY_TTL};
 use crate::tools::builtin::get_path_string;
-use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolAnnotations, ToolSchema};
 
 /// Checksum tool: compute or verify file hashes.
 pub struct Checksum;
@@ -62,6 +64,14 @@ impl Tool for Checksum {
                 ("Verify", "checksum -c checksums.txt"),
             ],
         )
+        .with_annotations(ToolAnnotations::read_only())
+    }
+
+    /// Hashes of files on read-only mounts are cached for a while. `-c`
+    /// reads the files its list names, so verification always runs.
+    fn cache_ttl(&self, args: &ToolArgs, ctx: &dyn ToolCtx) -> Option<Duration> {
+        let verifying = args.named.contains_key("check") || args.named.contains_key("c");
+        (!verifying && operands_on_read_only_mounts(args, ctx)).then_some(READ_ONLY_TTL)
     }
 
     async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
@@ -11,7 +11,7 @@ use std::path::Path;
 
 use crate::backend::ReadRange;
 use crate::interpreter::ExecResult;
-use crate::tools::{schema_from_clap, ExecContext, GlobalFlags, Tool, ToolArgs, ToolCtx, ToolSchema};
+use crate::tools::{schema_from_clap, ExecContext, GlobalFlags, Tool, ToolArgs, ToolCtx, ToolAnnotations, ToolSchema};
 
 /// cmp tool.
 pub struct Cmp;
@@ -48,6 +48,7 @@ impl Tool for Cmp {
                 ("Compare stdin to a file", "dd if=/dev/urandom bs=16 count=1 | cmp - saved.bin"),
             ],
         )
+        .with_annotations(ToolAnnotations::read_only())
     }
 
     async fn execute(&self, mut args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
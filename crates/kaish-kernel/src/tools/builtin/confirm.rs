@@ -0,0 +1,137 @@
+//! confirm — Ask a yes/no question; the answer is the exit code.
+//!
+//! Usage:
+//!   confirm "Delete 42 files?"          # exit 0 on yes, 1 on no
+//!   echo y | confirm "Proceed?"         # answer read from stdin
+//!   confirm --yes "Proceed?"            # headless: answer yes without asking
+//!
+//! The answer comes from, in order: `--yes`, piped stdin, or — in an
+//! interactive kernel — a prompt on the terminal. A headless kernel with no
+//! stdin has no one to ask, so the question fails rather than blocking.
+//! Embedders with their own way to ask (an MCP elicitation, a UI dialog)
+//! register a tool named `confirm` to replace this one.
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+
+use crate::interpreter::ExecResult;
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+
+/// Confirm tool: ask a yes/no question.
+pub struct Confirm;
+
+/// clap-derived argv layer for confirm.
+#[derive(Parser, Debug)]
+#[command(name = "confirm", about = "Ask a yes/no question; exit 0 on yes, 1 on no")]
+struct ConfirmArgs {
+    /// Answer yes without asking (for headless runs).
+    #[arg(short = 'y', long = "yes")]
+    yes: bool,
+
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// The question to ask.
+    prompt: Vec<String>,
+}
+
+#[async_trait]
+impl Tool for Confirm {
+    fn name(&self) -> &str {
+        "confirm"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &ConfirmArgs::command(),
+            "confirm",
+            "Ask a yes/no question; exit 0 on yes, 1 on no",
+            [
+                ("Branch on the answer", "if confirm 'Delete 42 files?'; then rm *.log; fi"),
+                ("Answer from a pipe", "echo y | confirm 'Proceed?'"),
+                ("Headless: assume yes", "confirm --yes 'Proceed?'"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("confirm: {e}")),
+        };
+        let parsed = match ConfirmArgs::try_parse_from(
+            std::iter::once("confirm".to_string()).chain(argv),
+        ) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("confirm: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let prompt = parsed.prompt.join(" ");
+        if prompt.is_empty() {
+            return ExecResult::failure(2, "confirm: missing question");
+        }
+        if parsed.yes {
+            return ExecResult::success("");
+        }
+
+        let answer = match ctx.read_stdin_to_text().await {
+            Ok(Some(text)) => text,
+            Ok(None) if ctx.interactive => match ask_terminal(&prompt).await {
+                Ok(line) => line,
+                Err(e) => return ExecResult::failure(1, format!("confirm: {e}")),
+            },
+            Ok(None) => {
+                return ExecResult::failure(
+                    1,
+                    format!("confirm: {prompt}: no one to ask (no stdin, not interactive); pass --yes to proceed"),
+                );
+            }
+            Err(e) => return ExecResult::failure(2, format!("confirm: {e}")),
+        };
+
+        if is_yes(answer.lines().next().unwrap_or("")) {
+            ExecResult::success("")
+        } else {
+            ExecResult::failure(1, "")
+        }
+    }
+}
+
+/// `y` or `yes`, any case.
+fn is_yes(answer: &str) -> bool {
+    matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
+/// Prompt on the terminal and read one line.
+async fn ask_terminal(prompt: &str) -> std::io::Result<String> {
+    let prompt = format!("{prompt} [y/N] ");
+    tokio::task::spawn_blocking(move || {
+        use std::io::Write;
+        let mut stderr = std::io::stderr();
+        stderr.write_all(prompt.as_bytes())?;
+        stderr.flush()?;
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        Ok(line)
+    })
+    .await
+    .map_err(std::io::Error::other)?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yes_answers() {
+        assert!(is_yes("y"));
+        assert!(is_yes(" YES \n"));
+        assert!(!is_yes(""));
+        assert!(!is_yes("n"));
+        assert!(!is_yes("yes please"));
+    }
+}
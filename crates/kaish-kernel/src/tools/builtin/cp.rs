@@ -3,10 +3,12 @@
 use async_trait::async_trait;
 use clap::{CommandFactory, Parser};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::backend::{BackendError, KernelBackend, WriteMode};
+use crate::hooks::{KernelHooks, Progress};
 use crate::interpreter::ExecResult;
-use crate::tools::{cas_overwrite, schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+use crate::tools::{cas_overwrite, schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolAnnotations, ToolSchema};
 
 /// Cp tool: copy files and directories.
 pub struct Cp;
@@ -50,6 +52,7 @@ impl Tool for Cp {
                 ("Copy directory recursively", "cp -r src/ backup/"),
             ],
         )
+        .with_annotations(ToolAnnotations::destructive())
     }
 
     async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
@@ -143,6 +146,7 @@ impl Tool for Cp {
             }
         }
 
+        let progress = CopyProgress::new(&ctx.hooks);
         let mut last_err: Option<String> = None;
         for source in &sources {
             let src_path = ctx.resolve_path(source);
@@ -153,6 +157,7 @@ impl Tool for Cp {
                 recursive,
                 no_clobber,
                 expected_dst.as_deref(),
+                &progress,
             )
             .await
             {
@@ -166,6 +171,25 @@ impl Tool for Cp {
     }
 }
 
+/// Reports each copied file to the progress hooks, with a running count —
+/// a recursive copy doesn't know its total up front.
+struct CopyProgress<'a> {
+    hooks: &'a KernelHooks,
+    copied: AtomicUsize,
+}
+
+impl<'a> CopyProgress<'a> {
+    fn new(hooks: &'a KernelHooks) -> Self {
+        Self { hooks, copied: AtomicUsize::new(0) }
+    }
+
+    fn copied(&self, path: &Path) {
+        let n = self.copied.fetch_add(1, Ordering::Relaxed) + 1;
+        let noun = if n == 1 { "file" } else { "files" };
+        self.hooks.progress("cp", &Progress::message(format!("{n} {noun}: {}", path.display())));
+    }
+}
+
 /// Copy a path to destination, optionally recursively.
 async fn copy_path(
     backend: &dyn KernelBackend,
@@ -174,6 +198,7 @@ async fn copy_path(
     recursive: bool,
     no_clobber: bool,
     expected: Option<&[u8]>,
+    progress: &CopyProgress<'_>,
 ) -> Result<(), BackendError> {
     let info = backend.stat(src).await?;
 
@@ -184,7 +209,7 @@ async fn copy_path(
                 src.display()
             )));
         }
-        copy_dir_recursive(backend, src, dst, no_clobber).await
+        copy_dir_recursive(backend, src, dst, no_clobber, progress).await
     } else {
         // Check if destination is a directory
         let final_dst = match backend.stat(dst).await {
@@ -207,7 +232,9 @@ async fn copy_path(
         // CAS against the gate snapshot (`expected`) when this is a gated direct
         // file clobber — a concurrent change is a loud conflict, not a silent
         // clobber. `expected` is `None` for a new file or an ungated path.
-        cas_overwrite(backend, &final_dst, &data, expected).await
+        cas_overwrite(backend, &final_dst, &data, expected).await?;
+        progress.copied(&final_dst);
+        Ok(())
     }
 }
 
@@ -217,6 +244,7 @@ fn copy_dir_recursive<'a>(
     src: &'a Path,
     dst: &'a Path,
     no_clobber: bool,
+    progress: &'a CopyProgress<'a>,
 ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), BackendError>> + Send + 'a>> {
     Box::pin(async move {
         // Create destination directory
@@ -229,7 +257,7 @@ fn copy_dir_recursive<'a>(
             let dst_child: PathBuf = dst.join(&entry.name);
 
             if entry.is_dir() {
-                copy_dir_recursive(backend, &src_child, &dst_child, no_clobber).await?;
+                copy_dir_recursive(backend, &src_child, &dst_child, no_clobber, progress).await?;
             } else {
                 // Check for no-clobber mode
                 if no_clobber && backend.exists(&dst_child).await {
@@ -237,6 +265,7 @@ fn copy_dir_recursive<'a>(
                 }
                 let data = backend.read(&src_child, None).await?;
                 backend.write(&dst_child, &data, WriteMode::Overwrite).await?;
+                progress.copied(&dst_child);
             }
         }
 
@@ -0,0 +1,366 @@
+//! csv — parse CSV/TSV text into typed rows, and serialize rows back.
+//!
+//! The tabular sibling of the `fromjson`/`tojson` doors:
+//!
+//! - **`csv parse [FILE]`** reads a file (or stdin text) and yields a list in
+//!   `.data`: one record per row keyed by the header row, or a list of lists
+//!   with `--no-header`. Fields stay strings — CSV carries no types, and
+//!   guessing (`"007"` → `7`) is silent corruption.
+//! - **`csv write [LIST]`** takes a list (positional or piped `.data`) and
+//!   emits CSV text. Records share one header — the union of their keys in
+//!   first-seen order, missing cells empty; a list of lists is written as
+//!   rows with no header.
+//!
+//! Quoting follows RFC 4180 both ways: quoted fields may hold the delimiter,
+//! `""`, and newlines. A row with the wrong number of fields or an
+//! unterminated quote is a loud error naming the line, never a silent pad.
+//!
+//! # Examples
+//!
+//! ```kaish
+//! csv parse users.csv | jq '.[] | select(.role == "admin") | .email'
+//! cat report.tsv | csv parse --tsv | csv write > report.csv
+//! ```
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+
+use crate::ast::Value;
+use crate::interpreter::ExecResult;
+use crate::tools::{schema_from_clap, ExecContext, GlobalFlags, Tool, ToolArgs, ToolCtx, ToolSchema};
+
+use super::keys::describe_kind;
+
+/// Csv tool: CSV/TSV text ⇄ typed rows.
+pub struct Csv;
+
+/// clap-derived argv layer for csv.
+#[derive(Parser, Debug)]
+#[command(name = "csv", about = "Parse CSV/TSV text into rows, or write rows as CSV")]
+struct CsvArgs {
+    /// Field delimiter, a single character (default `,`) (-d).
+    #[arg(short = 'd', long = "delimiter")]
+    delimiter: Option<String>,
+
+    /// Tab-separated values; shorthand for `-d '\t'` (-t).
+    #[arg(short = 't', long = "tsv")]
+    tsv: bool,
+
+    /// `parse`: the first row is data, yield lists instead of records.
+    /// `write`: omit the header row.
+    #[arg(long = "no-header")]
+    no_header: bool,
+
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// Subcommand (`parse`, `write`) and its argument. Hidden sink for the
+    /// value — `write` reads it off `args.positional` per the Value-typed
+    /// positional rule.
+    args: Vec<String>,
+}
+
+#[async_trait]
+impl Tool for Csv {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &CsvArgs::command(),
+            "csv",
+            "Parse CSV/TSV text into a list of records (in .data), or write a list as CSV text",
+            [
+                ("Parse a file using its header row", "csv parse users.csv"),
+                ("Parse TSV from stdin", "cat report.tsv | csv parse --tsv"),
+                ("Rows without a header", "csv parse --no-header points.csv"),
+                ("Write records as CSV", "csv parse a.csv | jq 'map(select(.ok == \"yes\"))' | csv write"),
+                ("Semicolon-separated output", "csv write -d ';' $rows"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("csv: {e}")),
+        };
+        let parsed = match CsvArgs::try_parse_from(
+            std::iter::once("csv".to_string()).chain(argv),
+        ) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("csv: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let delimiter = match (parsed.tsv, parsed.delimiter.as_deref()) {
+            (true, Some(_)) => return ExecResult::failure(2, "csv: --tsv and --delimiter are exclusive"),
+            (true, None) => '\t',
+            (false, None) => ',',
+            (false, Some("\\t")) => '\t',
+            (false, Some(d)) => {
+                let mut chars = d.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if c != '"' && c != '\n' && c != '\r' => c,
+                    _ => return ExecResult::failure(2, format!("csv: delimiter must be one character other than a quote or newline: {d:?}")),
+                }
+            }
+        };
+
+        let Some(subcmd) = parsed.args.first() else {
+            return ExecResult::failure(1, "csv: missing subcommand (parse, write)");
+        };
+        match subcmd.as_str() {
+            "parse" => cmd_parse(&args, delimiter, parsed.no_header, ctx).await,
+            "write" => cmd_write(&args, delimiter, parsed.no_header, ctx).await,
+            other => ExecResult::failure(1, format!("csv: unknown subcommand: {}", other)),
+        }
+    }
+}
+
+async fn cmd_parse(args: &ToolArgs, delimiter: char, no_header: bool, ctx: &mut ExecContext) -> ExecResult {
+    let text = match args.positional.get(1) {
+        Some(Value::String(path)) => {
+            let bytes = match ctx.backend.read(&ctx.resolve_path(path), None).await {
+                Ok(bytes) => bytes,
+                Err(e) => return ExecResult::failure(1, format!("csv parse: {}: {}", path, e)),
+            };
+            match String::from_utf8(bytes) {
+                Ok(text) => text,
+                Err(_) => return ExecResult::failure(1, format!("csv parse: {}: invalid UTF-8", path)),
+            }
+        }
+        Some(other) => {
+            return ExecResult::failure(1, format!("csv parse: expected a file path, got {}", describe_kind(other)));
+        }
+        None => match ctx.read_stdin_to_text().await {
+            Ok(Some(text)) => text,
+            Ok(None) => return ExecResult::failure(1, "csv parse: no input (pass a file or pipe stdin)"),
+            Err(e) => return ExecResult::failure(2, format!("csv parse: {e}")),
+        },
+    };
+
+    let rows = match parse_rows(&text, delimiter) {
+        Ok(rows) => rows,
+        Err(e) => return ExecResult::failure(1, format!("csv parse: {e}")),
+    };
+
+    let list: Vec<serde_json::Value> = if no_header {
+        rows.into_iter()
+            .map(|(_, fields)| serde_json::Value::Array(fields.into_iter().map(serde_json::Value::String).collect()))
+            .collect()
+    } else {
+        let mut rows = rows.into_iter();
+        let Some((_, header)) = rows.next() else {
+            return ExecResult::success_data(Value::Json(serde_json::Value::Array(Vec::new())));
+        };
+        let mut records = Vec::new();
+        for (line, fields) in rows {
+            if fields.len() != header.len() {
+                return ExecResult::failure(
+                    1,
+                    format!("csv parse: line {}: expected {} fields, found {}", line, header.len(), fields.len()),
+                );
+            }
+            let record: serde_json::Map<String, serde_json::Value> = header
+                .iter()
+                .cloned()
+                .zip(fields.into_iter().map(serde_json::Value::String))
+                .collect();
+            records.push(serde_json::Value::Object(record));
+        }
+        records
+    };
+    ExecResult::success_data(kaish_types::json_to_value_no_envelope(serde_json::Value::Array(list)))
+}
+
+async fn cmd_write(args: &ToolArgs, delimiter: char, no_header: bool, ctx: &mut ExecContext) -> ExecResult {
+    let value = match args.positional.get(1) {
+        Some(v) => v.clone(),
+        None => match ctx.resolve_stdin().await {
+            Ok((Some(data), _)) => data,
+            Ok((None, _)) => {
+                return ExecResult::failure(1, "csv write: no value (pass a list, or pipe .data from an upstream builtin)");
+            }
+            Err(e) => return ExecResult::failure(2, format!("csv write: {e}")),
+        },
+    };
+    let rows = match &value {
+        Value::Json(serde_json::Value::Array(rows)) => rows,
+        other => {
+            return ExecResult::failure(1, format!("csv write: expected a list of records or lists, got {}", describe_kind(other)));
+        }
+    };
+
+    let mut out = String::new();
+    if rows.iter().all(|row| row.is_array()) {
+        for row in rows {
+            if let serde_json::Value::Array(cells) = row {
+                write_row(&mut out, cells.iter().map(cell_text), delimiter);
+            }
+        }
+        return ExecResult::success(out);
+    }
+
+    // Records: the header is the union of keys, first-seen order.
+    let mut header: Vec<&str> = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        let serde_json::Value::Object(record) = row else {
+            return ExecResult::failure(1, format!("csv write: row {}: expected a record like the others", i));
+        };
+        for key in record.keys() {
+            if !header.contains(&key.as_str()) {
+                header.push(key);
+            }
+        }
+    }
+    if !no_header {
+        write_row(&mut out, header.iter().map(|k| (*k).to_string()), delimiter);
+    }
+    for row in rows {
+        if let serde_json::Value::Object(record) = row {
+            write_row(&mut out, header.iter().map(|k| record.get(*k).map(cell_text).unwrap_or_default()), delimiter);
+        }
+    }
+    ExecResult::success(out)
+}
+
+/// A cell's text: strings as-is, null empty, nested values as compact JSON.
+fn cell_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Append one row, quoting fields that need it, terminated by `\n`.
+fn write_row(out: &mut String, fields: impl Iterator<Item = String>, delimiter: char) {
+    for (i, field) in fields.enumerate() {
+        if i > 0 {
+            out.push(delimiter);
+        }
+        if field.contains([delimiter, '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(&field);
+        }
+    }
+    out.push('\n');
+}
+
+/// Split text into rows of fields, each tagged with the line it started on.
+/// Blank lines are skipped; a trailing newline does not make an empty row.
+fn parse_rows(text: &str, delimiter: char) -> Result<Vec<(usize, Vec<String>)>, String> {
+    let mut rows = Vec::new();
+    let mut fields: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    // Whether the current field was quoted, so `"",x` keeps its empty field.
+    let mut quoted = false;
+    let mut line = 1;
+    let mut row_line = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() && !quoted => {
+                in_quotes = true;
+                quoted = true;
+            }
+            '"' => return Err(format!("line {}: stray quote inside an unquoted field", line)),
+            c if c == delimiter => {
+                fields.push(std::mem::take(&mut field));
+                quoted = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                if !fields.is_empty() || !field.is_empty() || quoted {
+                    fields.push(std::mem::take(&mut field));
+                    rows.push((row_line, std::mem::take(&mut fields)));
+                }
+                quoted = false;
+                line += 1;
+                row_line = line;
+            }
+            _ if quoted => {
+                return Err(format!("line {}: text after a closing quote", line));
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(format!("line {}: unterminated quoted field", row_line));
+    }
+    if !fields.is_empty() || !field.is_empty() || quoted {
+        fields.push(field);
+        rows.push((row_line, fields));
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(text: &str, delimiter: char) -> Vec<Vec<String>> {
+        parse_rows(text, delimiter).unwrap().into_iter().map(|(_, f)| f).collect()
+    }
+
+    #[test]
+    fn parses_quotes_and_embedded_newlines() {
+        let rows = fields("a,b\n\"x,1\",\"say \"\"hi\"\"\"\n\"multi\nline\",\r\n", ',');
+        assert_eq!(rows, vec![
+            vec!["a".to_string(), "b".to_string()],
+            vec!["x,1".to_string(), "say \"hi\"".to_string()],
+            vec!["multi\nline".to_string(), String::new()],
+        ]);
+    }
+
+    #[test]
+    fn blank_lines_are_skipped_and_quoted_empties_kept() {
+        assert_eq!(fields("a\n\n\"\"\n", ','), vec![vec!["a".to_string()], vec![String::new()]]);
+    }
+
+    #[test]
+    fn tracks_the_starting_line_of_each_row() {
+        let rows = parse_rows("h\n\"a\nb\"\nc\n", ',').unwrap();
+        let lines: Vec<usize> = rows.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn malformed_quotes_are_errors() {
+        assert!(parse_rows("\"open\n", ',').unwrap_err().contains("unterminated"));
+        assert!(parse_rows("ab\"c\n", ',').unwrap_err().contains("stray quote"));
+        assert!(parse_rows("\"a\"b\n", ',').unwrap_err().contains("after a closing quote"));
+    }
+
+    #[test]
+    fn write_row_quotes_only_when_needed() {
+        let mut out = String::new();
+        write_row(&mut out, ["plain", "a\tb", "q\"", "x,y"].iter().map(|s| s.to_string()), '\t');
+        assert_eq!(out, "plain\t\"a\tb\"\t\"q\"\"\"\tx,y\n");
+    }
+}
@@ -10,8 +10,11 @@
 //! - **`-d` / `--date`** parses the convergent subset the fleet types:
 //!   `@N` epoch, `now`/`today`/`yesterday`/`tomorrow`, `N units ago` / `±N
 //!   units`, `next`/`last <weekday>`, an absolute ISO date/datetime, and the
-//!   nested idiom `<absolute> ± <offset>` (`2026-06-01 -1 day`). Anything
-//!   outside the subset fails loud — no half-supported open grammar.
+//!   nested idiom `<absolute> ± <offset>` (`2026-06-01 -1 day`), plus RFC
+//!   2822 strings and compact offsets (`3d`, `-90m`). Anything outside the
+//!   subset fails loud — no half-supported open grammar.
+//! - **`add=OFFSET`** operands shift whatever instant was resolved (`date
+//!   add=3d`, `date -d 2026-06-01 add=-1w`), using the same DST-aware math.
 //! - **Format path is hardened.** `%N` (and `%3N`/`%6N`/`%9N`) translate to
 //!   chrono's nanosecond formatters so `date +%s%N` *works*; any genuinely
 //!   unknown specifier (`%Q`) returns `exit 2` instead of panicking the worker.
@@ -138,7 +141,8 @@ struct DateArgs {
     #[command(flatten)]
     global: GlobalFlags,
 
-    /// Optional `+FORMAT` (strftime) and/or `@TIMESTAMP` argument.
+    /// Optional `+FORMAT` (strftime), `@TIMESTAMP`, and `add=OFFSET`
+    /// arguments.
     args: Vec<String>,
 }
 
@@ -180,6 +184,7 @@ impl Tool for Date {
                 ("ISO 8601 date", "date -I"),
                 ("Relative", "date -d \"2 days ago\""),
                 ("Decode an epoch", "date -d \"@1700000000\""),
+                ("Three days from now", "date add=3d"),
             ],
         )
     }
@@ -221,11 +226,13 @@ impl Date {
 
         let zone = resolve_zone(parsed.utc, parsed.tz.as_deref(), scope_tz(ctx))?;
 
-        // The format positional is the first `+...` operand; any bare operand
-        // that is neither `+FORMAT` nor `@TIMESTAMP` is an error (GNU rejects
-        // stray operands, and a bare date is almost certainly a missing -d).
+        // The format positional is the first `+...` operand; `add=` operands
+        // accumulate offsets; any other bare operand that isn't `@TIMESTAMP`
+        // is an error (GNU rejects stray operands, and a bare date is almost
+        // certainly a missing -d).
         let mut fmt_positional: Option<&str> = None;
         let mut at_positional: Option<&str> = None;
+        let mut add_terms: Vec<String> = Vec::new();
         for operand in &parsed.args {
             if let Some(stripped) = operand.strip_prefix('+') {
                 if fmt_positional.is_none() {
@@ -233,6 +240,16 @@ impl Date {
                 }
             } else if operand.starts_with('@') {
                 at_positional = Some(operand);
+            } else if let Some(offset) = operand.strip_prefix("add=") {
+                let terms: Vec<String> = offset
+                    .split(|c: char| c.is_whitespace() || c == ',')
+                    .filter(|t| !t.is_empty())
+                    .map(str::to_ascii_lowercase)
+                    .collect();
+                if terms.is_empty() {
+                    return Err(format!("date: empty offset in '{operand}'"));
+                }
+                add_terms.extend(terms);
             } else {
                 return Err(format!(
                     "date: unrecognized operand '{operand}' (did you mean -d \"{operand}\"?)"
@@ -250,6 +267,11 @@ impl Date {
         } else {
             zone.instant(now_utc)
         };
+        let dt = if add_terms.is_empty() {
+            dt
+        } else {
+            shift_instant(dt, &zone, &add_terms)?
+        };
 
         let body = format!("{}\n", render(&dt, parsed, fmt_positional)?);
         // Keep both the text body and the OutputData: `with_output` alone would
@@ -327,6 +349,29 @@ fn resolve_instant(
     }
 }
 
+/// Apply `add=` offset terms to an already-resolved instant, re-expressing it
+/// in `zone`'s real timezone first so calendar shifts stay DST-correct.
+fn shift_instant(
+    dt: DateTime<FixedOffset>,
+    zone: &Zone,
+    terms: &[String],
+) -> Result<DateTime<FixedOffset>, String> {
+    let shifted = match zone {
+        Zone::Utc => shift_in_tz(dt, &Utc, terms),
+        Zone::Local => shift_in_tz(dt, &Local, terms),
+        Zone::Named(tz) => shift_in_tz(dt, tz, terms),
+    };
+    shifted.map_err(|_| format!("date: invalid offset 'add={}'", terms.join(" ")))
+}
+
+fn shift_in_tz<Tz: TimeZone>(
+    dt: DateTime<FixedOffset>,
+    tz: &Tz,
+    terms: &[String],
+) -> Result<DateTime<FixedOffset>, String> {
+    apply_offset_terms(terms, dt.with_timezone(tz)).map(|d| d.fixed_offset())
+}
+
 fn parse_in_tz<Tz: TimeZone>(
     spec: &str,
     tz: &Tz,
@@ -359,6 +404,12 @@ fn parse_date_string<Tz: TimeZone>(
         return Ok(utc.with_timezone(tz));
     }
 
+    // RFC 2822 (`Sun, 14 Jun 2026 09:03:15 -0400`) — what `date -R`, mail
+    // headers, and HTTP dates print. Carries its own offset.
+    if let Ok(dt) = DateTime::parse_from_rfc2822(trimmed) {
+        return Ok(dt.with_timezone(tz));
+    }
+
     let lower = trimmed.to_ascii_lowercase();
     match lower.as_str() {
         "now" | "today" => return Ok(base),
@@ -460,12 +511,20 @@ fn parse_absolute_prefix<Tz: TimeZone>(
 }
 
 /// Apply a sequence of `[in] N unit [N unit ...] [ago]` offset terms to `base`.
+/// Compact terms (`3d`, `-90m`, `2weeks`) are split into `N unit` first.
 /// Errors (rather than no-ops) on anything that isn't a clean offset.
 fn apply_offset_terms<Tz: TimeZone>(
     tokens: &[String],
     base: DateTime<Tz>,
 ) -> Result<DateTime<Tz>, String> {
-    let mut slice = tokens;
+    let expanded: Vec<String> = tokens
+        .iter()
+        .flat_map(|t| match split_compact(t) {
+            Some((n, unit)) => vec![n.to_string(), unit.to_string()],
+            None => vec![t.clone()],
+        })
+        .collect();
+    let mut slice = expanded.as_slice();
 
     // Optional `in ...` prefix and `... ago` suffix; `ago` negates direction.
     if slice.first().map(String::as_str) == Some("in") {
@@ -496,6 +555,31 @@ fn apply_offset_terms<Tz: TimeZone>(
     Ok(dt)
 }
 
+/// Split a compact offset like `3d` / `-90m` / `+2weeks` into its number and
+/// unit. Single-letter units map to their long spellings (`m` is minutes, as
+/// in `sleep`; months need `mo`). `None` if `token` isn't number-then-letters.
+fn split_compact(token: &str) -> Option<(&str, &str)> {
+    let digits_start = usize::from(token.starts_with(['+', '-']));
+    let split = token[digits_start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map(|i| i + digits_start)?;
+    let (number, unit) = token.split_at(split);
+    if split == digits_start || !unit.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let unit = match unit {
+        "s" => "sec",
+        "m" => "min",
+        "h" => "hr",
+        "d" => "day",
+        "w" => "wk",
+        "mo" => "mon",
+        "y" => "yr",
+        other => other,
+    };
+    Some((number, unit))
+}
+
 /// Shift `dt` by `n` of `unit` (negative `n` moves into the past). Sub-day
 /// units add a true physical `Duration`; day/week/month/year are calendar
 /// shifts so they stay DST-correct in a zone with transitions.
@@ -716,6 +800,12 @@ fn build_json(dt: &DateTime<FixedOffset>) -> serde_json::Value {
         "weekday": dt.format("%A").to_string(),
         "tz": dt.format("%:z").to_string(),
         "offset_seconds": dt.offset().local_minus_utc(),
+        "year": dt.year(),
+        "month": dt.month(),
+        "day": dt.day(),
+        "hour": dt.hour(),
+        "minute": dt.minute(),
+        "second": dt.second(),
     })
 }
 
@@ -1209,4 +1299,72 @@ mod tests {
         let r = date.execute_argv(argv(&["-u", "-d", "@1700000000", "+%H"]), &mut ctx).await;
         assert_eq!(r.text_out().trim(), "22");
     }
+
+    // --- add= arithmetic, compact offsets, RFC 2822 input -------------------
+
+    #[tokio::test]
+    async fn add_operand_shifts_now() {
+        let r = run(&fixed_date(), &["-u", "add=3d", "+%Y-%m-%d"]).await;
+        assert!(r.ok(), "{}", r.err);
+        assert_eq!(r.text_out().trim(), "2023-11-17");
+        let r = run(&fixed_date(), &["-u", "add=-90m", "+%H:%M"]).await;
+        assert_eq!(r.text_out().trim(), "20:43");
+    }
+
+    #[tokio::test]
+    async fn add_operands_stack_on_a_parsed_date() {
+        let r = run(
+            &fixed_date(),
+            &["-u", "-d", "2026-06-01", "add=1w,2h", "add=1mo", "+%Y-%m-%dT%H"],
+        )
+        .await;
+        assert_eq!(r.text_out().trim(), "2026-07-08T02");
+    }
+
+    #[tokio::test]
+    async fn add_operand_is_dst_correct() {
+        // Same spring-forward as `calendar_math_is_dst_correct`, via add=.
+        let instant = Utc.with_ymd_and_hms(2026, 3, 6, 17, 0, 0).unwrap();
+        let date = Date::with_clock(Arc::new(FixedClock(instant)));
+        let mut ctx = make_ctx();
+        let r = date
+            .execute_argv(
+                argv(&["--tz", "America/New_York", "add=3d", "+%Y-%m-%dT%H:%M%:z"]),
+                &mut ctx,
+            )
+            .await;
+        assert_eq!(r.text_out().trim(), "2026-03-09T12:00-04:00");
+    }
+
+    #[tokio::test]
+    async fn bad_add_operand_fails_loud() {
+        assert_eq!(run(&fixed_date(), &["add=3fortnights"]).await.code, 2);
+        assert_eq!(run(&fixed_date(), &["add="]).await.code, 2);
+    }
+
+    #[tokio::test]
+    async fn compact_offsets_in_date_string() {
+        let r = run(&fixed_date(), &["-u", "-d", "2d ago", "+%Y-%m-%d"]).await;
+        assert_eq!(r.text_out().trim(), "2023-11-12");
+        let r = run(&fixed_date(), &["-u", "-d", "2026-06-01 -1d", "+%Y-%m-%d"]).await;
+        assert_eq!(r.text_out().trim(), "2026-05-31");
+    }
+
+    #[tokio::test]
+    async fn rfc_2822_input_round_trips() {
+        let r = run(&fixed_date(), &["-u", "-d", "Tue, 14 Nov 2023 17:13:20 -0500", "+%s"]).await;
+        assert!(r.ok(), "{}", r.err);
+        assert_eq!(r.text_out().trim(), FIXED_EPOCH.to_string());
+    }
+
+    #[tokio::test]
+    async fn json_carries_components() {
+        let p = json_for(&["--tz", "Asia/Tokyo", "-d", "@1700000000"]).await;
+        assert_eq!(p["year"], 2023);
+        assert_eq!(p["month"], 11);
+        assert_eq!(p["day"], 15);
+        assert_eq!(p["hour"], 7);
+        assert_eq!(p["minute"], 13);
+        assert_eq!(p["second"], 20);
+    }
 }
@@ -13,7 +13,7 @@ use std::path::Path;
 use crate::ast::Value;
 use crate::backend::ReadRange;
 use crate::interpreter::{value_to_string, ExecResult};
-use crate::tools::{ExecContext, Tool, ToolArgs, ToolCtx, ToolSchema};
+use crate::tools::{ExecContext, Tool, ToolArgs, ToolCtx, ToolAnnotations, ToolSchema};
 
 /// dd tool.
 pub struct Dd;
@@ -51,6 +51,7 @@ impl Tool for Dd {
         )
         .example("Random bytes to a file", "dd if=/dev/urandom of=key.bin bs=16 count=1")
         .example("Discard a measured stream", "dd if=/dev/zero of=/dev/null bs=1k count=10")
+        .with_annotations(ToolAnnotations::destructive())
     }
 
     async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
@@ -195,13 +196,13 @@ impl Tool for Dd {
                 }
                 // Status to stderr, like dd; stdout stays empty.
                 let mut result = ExecResult::success("");
-                result.err = format!("{copied} bytes copied\n");
+                result.stderr = format!("{copied} bytes copied\n");
                 result
             }
             // No of=: the bytes are the result (hex dump in REPL, base64 under --json).
             None => {
                 let mut result = ExecResult::success_bytes(data);
-                result.err = format!("{copied} bytes copied\n");
+                result.stderr = format!("{copied} bytes copied\n");
                 result
             }
         }
@@ -238,7 +239,7 @@ mod tests {
             .execute(args(&["if=/dev/zero", "of=/tmp/z.bin", "bs=8", "count=2"]), &mut ctx)
             .await;
         assert!(r.ok(), "stderr: {}", r.err);
-        assert!(r.err.contains("16 bytes copied"), "status: {}", r.err);
+        assert!(r.stderr.contains("16 bytes copied"), "status: {}", r.stderr);
         // The file holds exactly 16 zero bytes.
         let written = ctx.backend.read(Path::new("/tmp/z.bin"), None).await.unwrap();
         assert_eq!(written, vec![0u8; 16]);
@@ -251,7 +252,7 @@ mod tests {
         assert!(r.ok(), "stderr: {}", r.err);
         assert!(r.is_bytes(), "no of= → Bytes result");
         assert_eq!(r.out_bytes().map(|b| b.len()), Some(16));
-        assert!(r.err.contains("16 bytes copied"));
+        assert!(r.stderr.contains("16 bytes copied"));
     }
 
     #[tokio::test]
@@ -261,7 +262,7 @@ mod tests {
             .execute(args(&["if=/dev/urandom", "of=/dev/null", "bs=1k", "count=10"]), &mut ctx)
             .await;
         assert!(r.ok(), "stderr: {}", r.err);
-        assert!(r.err.contains("10240 bytes copied"), "status: {}", r.err);
+        assert!(r.stderr.contains("10240 bytes copied"), "status: {}", r.stderr);
         assert!(!r.is_bytes(), "of= present → no Bytes result");
     }
 
@@ -273,7 +274,7 @@ mod tests {
             .await;
         let r = Dd.execute(args(&["if=/tmp/r.bin", "of=/dev/null"]), &mut ctx).await;
         assert!(r.ok(), "stderr: {}", r.err);
-        assert!(r.err.contains("10240 bytes copied"), "status: {}", r.err);
+        assert!(r.stderr.contains("10240 bytes copied"), "status: {}", r.stderr);
     }
 
     #[tokio::test]
@@ -0,0 +1,282 @@
+//! df — Report memory use and quotas per mount.
+//!
+//! Memory-backed mounts (`MemoryFs`, overlays) count their resident content
+//! bytes; a mount made with `MemoryFs::with_quota` also reports its cap and
+//! what is left under it. Disk-backed mounts show `-` — disk space is the
+//! host's `df`'s business. The kernel's shared VFS budget, when set, follows
+//! the table.
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+use std::path::Path;
+
+use super::ls::format_human_size;
+use crate::interpreter::{ExecResult, OutputData, OutputNode};
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolAnnotations, ToolSchema};
+use crate::vfs::MountInfo;
+
+/// Df tool: per-mount usage and limits.
+pub struct Df;
+
+/// clap-derived argv layer for df.
+///
+/// `disable_help_flag = true` is required because `-h` is taken by `--human`,
+/// not by clap's auto-injected `--help`.
+#[derive(Parser, Debug)]
+#[command(name = "df", about = "Report memory use and quotas per mount", disable_help_flag = true)]
+struct DfArgs {
+    /// Human-readable sizes.
+    #[arg(short = 'h', long = "human")]
+    human: bool,
+
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// Report only the mounts holding these paths; defaults to every mount.
+    paths: Vec<String>,
+}
+
+#[async_trait]
+impl Tool for Df {
+    fn name(&self) -> &str {
+        "df"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &DfArgs::command(),
+            "df",
+            "Report memory use and quotas per mount",
+            [
+                ("Usage of every mount", "df"),
+                ("The mount holding a path, human-readable", "df -h /v/blobs"),
+            ],
+        )
+        .with_annotations(ToolAnnotations::read_only())
+    }
+
+    async fn execute(&self, mut args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        args.flagify_bool_named(&self.schema());
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("df: {e}")),
+        };
+        let parsed = match DfArgs::try_parse_from(std::iter::once("df".to_string()).chain(argv)) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("df: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let all = ctx.backend.mounts();
+        let mounts: Vec<&MountInfo> = if parsed.paths.is_empty() {
+            all.iter().collect()
+        } else {
+            let mut selected: Vec<&MountInfo> = Vec::new();
+            for path in &parsed.paths {
+                let resolved = ctx.resolve_path(path);
+                if let Err(e) = ctx.backend.stat(&resolved).await {
+                    return ExecResult::failure(1, format!("df: {path}: {e}"));
+                }
+                let Some(mount) = mount_for(&all, &resolved) else {
+                    return ExecResult::failure(1, format!("df: {path}: not under any mount"));
+                };
+                if !selected.iter().any(|m| m.path == mount.path) {
+                    selected.push(mount);
+                }
+            }
+            selected
+        };
+
+        let size = |bytes: u64| {
+            if parsed.human {
+                format_human_size(bytes)
+            } else {
+                bytes.to_string()
+            }
+        };
+        let cell = |bytes: Option<u64>| bytes.map_or_else(|| "-".to_string(), size);
+
+        let headers = vec![
+            "MOUNT".to_string(),
+            "USED".to_string(),
+            "QUOTA".to_string(),
+            "AVAIL".to_string(),
+            "USE%".to_string(),
+        ];
+        let nodes: Vec<OutputNode> = mounts
+            .iter()
+            .map(|m| {
+                let usage = Usage::of(m);
+                OutputNode::new(m.path.to_string_lossy()).with_cells(vec![
+                    cell(usage.used),
+                    cell(usage.quota),
+                    cell(usage.available),
+                    usage.percent.map_or_else(|| "-".to_string(), |p| format!("{p}%")),
+                ])
+            })
+            .collect();
+
+        let budget = ctx.vfs_budget.as_ref();
+        let rich = {
+            let mount_array: Vec<serde_json::Value> = mounts
+                .iter()
+                .map(|m| {
+                    let usage = Usage::of(m);
+                    serde_json::json!({
+                        "path": m.path.to_string_lossy(),
+                        "used_bytes": usage.used,
+                        "quota_bytes": usage.quota,
+                        "available_bytes": usage.available,
+                    })
+                })
+                .collect();
+            let mut top = serde_json::Map::new();
+            top.insert("mounts".to_string(), serde_json::Value::Array(mount_array));
+            if let Some(b) = budget {
+                top.insert(
+                    "budget".to_string(),
+                    serde_json::json!({
+                        "label": b.label(),
+                        "used": b.used(),
+                        "limit": b.limit(),
+                        "remaining": b.remaining(),
+                    }),
+                );
+            }
+            serde_json::Value::Object(top)
+        };
+
+        let output = OutputData::table(headers, nodes).with_rich_json(rich);
+        match budget {
+            Some(b) => {
+                let text = format!(
+                    "{}\n{} budget: {} used / {} limit / {} remaining",
+                    output.to_canonical_string(),
+                    b.label(),
+                    size(b.used()),
+                    size(b.limit()),
+                    size(b.remaining()),
+                );
+                ExecResult::with_output_and_text(output, text)
+            }
+            None => ExecResult::with_output(output),
+        }
+    }
+}
+
+/// The mount serving `path`: the deepest one it lies under. Shared with `ls`.
+pub(super) fn mount_for<'a>(mounts: &'a [MountInfo], path: &Path) -> Option<&'a MountInfo> {
+    mounts
+        .iter()
+        .filter(|m| path.starts_with(&m.path))
+        .max_by_key(|m| m.path.components().count())
+}
+
+/// One mount's row: what it holds, its quota, and what the quota leaves.
+struct Usage {
+    used: Option<u64>,
+    quota: Option<u64>,
+    available: Option<u64>,
+    percent: Option<u64>,
+}
+
+impl Usage {
+    fn of(mount: &MountInfo) -> Self {
+        let used = mount.resident_bytes;
+        let quota = mount.quota_bytes;
+        let (available, percent) = match (used, quota) {
+            (Some(used), Some(quota)) => {
+                // Rounded up, as df does.
+                let percent = match quota {
+                    0 => 100,
+                    quota => used.saturating_mul(100).div_ceil(quota),
+                };
+                (Some(quota.saturating_sub(used)), Some(percent))
+            }
+            _ => (None, None),
+        };
+        Self { used, quota, available, percent }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Value;
+    use crate::interpreter::{apply_output_format, OutputFormat};
+    use crate::vfs::{ByteBudget, Filesystem, MemoryFs, VfsRouter};
+    use std::sync::Arc;
+
+    async fn make_ctx() -> ExecContext {
+        let scratch = MemoryFs::new().with_quota(1000);
+        scratch.write(Path::new("notes.txt"), &[b'x'; 250]).await.expect("write");
+        let mut vfs = VfsRouter::new();
+        vfs.mount("/", MemoryFs::new());
+        vfs.mount("/scratch", scratch);
+        ExecContext::new(Arc::new(vfs))
+    }
+
+    fn row<'a>(text: &'a str, mount: &str) -> Vec<&'a str> {
+        text.lines()
+            .map(|line| line.split('\t').collect::<Vec<_>>())
+            .find(|cells| cells[0] == mount)
+            .unwrap_or_else(|| panic!("no {mount} row in:\n{text}"))
+    }
+
+    #[tokio::test]
+    async fn test_reports_usage_and_quota() {
+        let mut ctx = make_ctx().await;
+        let result = Df.execute(ToolArgs::new(), &mut ctx).await;
+        assert!(result.ok(), "{}", result.err);
+        let text = result.text_out();
+        assert_eq!(row(&text, "/scratch"), ["/scratch", "250", "1000", "750", "25%"]);
+        assert_eq!(row(&text, "/"), ["/", "0", "-", "-", "-"]);
+    }
+
+    #[tokio::test]
+    async fn test_paths_select_their_mounts() {
+        let mut ctx = make_ctx().await;
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("/scratch/notes.txt".into()));
+        args.positional.push(Value::String("/scratch".into()));
+        args.flags.insert("h".to_string());
+        let result = Df.execute(args, &mut ctx).await;
+        assert!(result.ok(), "{}", result.err);
+        let text = result.text_out();
+        assert_eq!(text.lines().filter(|l| l.starts_with('/')).count(), 1, "{text}");
+        assert_eq!(row(&text, "/scratch"), ["/scratch", "250", "1000", "750", "25%"]);
+
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("/scratch/missing".into()));
+        let result = Df.execute(args, &mut ctx).await;
+        assert_eq!(result.code, 1);
+        assert!(result.err.contains("df: /scratch/missing"), "{}", result.err);
+    }
+
+    #[tokio::test]
+    async fn test_json_carries_budget() {
+        let mut ctx = make_ctx().await;
+        let budget = Arc::new(ByteBudget::labeled(4096, "vfs-memory"));
+        budget.try_charge(1024).expect("charge");
+        ctx.vfs_budget = Some(budget);
+
+        let result = Df.execute(ToolArgs::new(), &mut ctx).await;
+        assert!(result.text_out().contains("vfs-memory budget: 1024 used / 4096 limit / 3072 remaining"));
+
+        let result = apply_output_format(result, OutputFormat::Json);
+        let top: serde_json::Value = serde_json::from_str(&result.text_out()).expect("valid JSON");
+        let scratch = top["mounts"]
+            .as_array()
+            .expect("mounts array")
+            .iter()
+            .find(|m| m["path"] == "/scratch")
+            .expect("scratch mount");
+        assert_eq!(scratch["used_bytes"], 250);
+        assert_eq!(scratch["quota_bytes"], 1000);
+        assert_eq!(scratch["available_bytes"], 750);
+        assert_eq!(top["budget"]["remaining"], 3072);
+    }
+}
@@ -17,7 +17,7 @@ use std::path::Path;
 use crate::ast::Value;
 use crate::interpreter::{ExecResult, OutputData};
 use crate::tools::builtin::get_path_string;
-use crate::tools::{schema_from_clap, validate_against_schema, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+use crate::tools::{schema_from_clap, validate_against_schema, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolAnnotations, ToolSchema};
 use crate::validator::{IssueCode, ValidationIssue};
 
 /// Diff tool: compares two files line by line.
@@ -66,6 +66,7 @@ impl Tool for Diff {
                 ("Quiet mode", "diff -q old.txt new.txt"),
             ],
         )
+        .with_annotations(ToolAnnotations::read_only())
     }
 
     fn validate(&self, args: &ToolArgs) -> Vec<ValidationIssue> {
@@ -0,0 +1,295 @@
+//! pushd/popd/dirs — The directory stack.
+//!
+//! `pushd DIR` saves the current directory on the stack and changes to DIR;
+//! bare `pushd` swaps the current directory with the top of the stack.
+//! `popd` changes back to the top entry and drops it. All three print the
+//! stack afterwards, current directory first, as bash does. The stack lives
+//! on the `ExecContext` beside `cd -`'s previous directory, so it follows
+//! the session and is undone with the rest of a `$(…)`'s changes.
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+use std::path::PathBuf;
+
+use super::cd::check_dir;
+use crate::interpreter::{ExecResult, OutputData, OutputNode};
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolAnnotations, ToolSchema};
+
+/// The stack as `dirs` shows it: the current directory, then the saved ones
+/// most recent first. `--json` gives the same order as an array.
+fn render_stack(ctx: &ExecContext, verbose: bool) -> ExecResult {
+    let dirs: Vec<String> = std::iter::once(&ctx.cwd)
+        .chain(ctx.dir_stack.iter().rev())
+        .map(|p| p.to_string_lossy().into_owned())
+        .collect();
+    let rich = serde_json::Value::Array(dirs.iter().map(|d| serde_json::json!(d)).collect());
+    let output = if verbose {
+        let nodes = dirs
+            .iter()
+            .enumerate()
+            .map(|(i, d)| OutputNode::new(i.to_string()).with_cells(vec![d.clone()]))
+            .collect();
+        OutputData::table(vec!["INDEX".to_string(), "DIR".to_string()], nodes)
+    } else {
+        OutputData::text(dirs.join(" "))
+    };
+    ExecResult::with_output(output.with_rich_json(rich))
+}
+
+/// Pushd tool: save the current directory and change to another.
+pub struct Pushd;
+
+/// clap-derived argv layer for pushd.
+#[derive(Parser, Debug)]
+#[command(name = "pushd", about = "Save the current directory on the stack and change directory")]
+struct PushdArgs {
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// Directory to change to; with none, swap with the top of the stack.
+    dir: Option<String>,
+}
+
+#[async_trait]
+impl Tool for Pushd {
+    fn name(&self) -> &str {
+        "pushd"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &PushdArgs::command(),
+            "pushd",
+            "Save the current directory on the stack and change directory",
+            [
+                ("Work somewhere else for a while", "pushd /tmp/build"),
+                ("Swap with the saved directory", "pushd"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("pushd: {e}")),
+        };
+        let parsed = match PushdArgs::try_parse_from(std::iter::once("pushd".to_string()).chain(argv)) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("pushd: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let (target, shown): (PathBuf, String) = match &parsed.dir {
+            Some(dir) => (ctx.resolve_path(dir), dir.clone()),
+            None => match ctx.dir_stack.last() {
+                Some(top) => (top.clone(), top.to_string_lossy().into_owned()),
+                None => return ExecResult::failure(1, "pushd: no other directory"),
+            },
+        };
+        if let Err(e) = check_dir(ctx, &target, &shown).await {
+            return ExecResult::failure(1, format!("pushd: {e}"));
+        }
+        if parsed.dir.is_none() {
+            ctx.dir_stack.pop();
+        }
+        let here = ctx.cwd.clone();
+        ctx.dir_stack.push(here);
+        ctx.set_cwd(target);
+        render_stack(ctx, false)
+    }
+}
+
+/// Popd tool: return to the directory on top of the stack.
+pub struct Popd;
+
+/// clap-derived argv layer for popd.
+#[derive(Parser, Debug)]
+#[command(name = "popd", about = "Change to the directory on top of the stack and remove it")]
+struct PopdArgs {
+    #[command(flatten)]
+    global: GlobalFlags,
+}
+
+#[async_trait]
+impl Tool for Popd {
+    fn name(&self) -> &str {
+        "popd"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &PopdArgs::command(),
+            "popd",
+            "Change to the directory on top of the stack and remove it",
+            [("Go back to where pushd came from", "popd")],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("popd: {e}")),
+        };
+        let parsed = match PopdArgs::try_parse_from(std::iter::once("popd".to_string()).chain(argv)) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("popd: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let Some(top) = ctx.dir_stack.last().cloned() else {
+            return ExecResult::failure(1, "popd: directory stack empty");
+        };
+        // A directory removed since it was pushed stays on the stack, so the
+        // failure can be seen with `dirs` and cleared with `dirs -c`.
+        if let Err(e) = check_dir(ctx, &top, &top.to_string_lossy()).await {
+            return ExecResult::failure(1, format!("popd: {e}"));
+        }
+        ctx.dir_stack.pop();
+        ctx.set_cwd(top);
+        render_stack(ctx, false)
+    }
+}
+
+/// Dirs tool: show or clear the directory stack.
+pub struct Dirs;
+
+/// clap-derived argv layer for dirs.
+#[derive(Parser, Debug)]
+#[command(name = "dirs", about = "Show the directory stack")]
+struct DirsArgs {
+    /// Clear the stack.
+    #[arg(short = 'c')]
+    clear: bool,
+
+    /// One directory per line, with its stack index.
+    #[arg(short = 'v')]
+    verbose: bool,
+
+    #[command(flatten)]
+    global: GlobalFlags,
+}
+
+#[async_trait]
+impl Tool for Dirs {
+    fn name(&self) -> &str {
+        "dirs"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &DirsArgs::command(),
+            "dirs",
+            "Show the directory stack",
+            [
+                ("Show the stack", "dirs"),
+                ("Numbered, one per line", "dirs -v"),
+                ("Forget every saved directory", "dirs -c"),
+            ],
+        )
+        .with_annotations(ToolAnnotations::read_only())
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("dirs: {e}")),
+        };
+        let parsed = match DirsArgs::try_parse_from(std::iter::once("dirs".to_string()).chain(argv)) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("dirs: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        if parsed.clear {
+            ctx.dir_stack.clear();
+            return ExecResult::success("");
+        }
+        render_stack(ctx, parsed.verbose)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Value;
+    use crate::vfs::{Filesystem, MemoryFs, VfsRouter};
+    use std::path::Path;
+    use std::sync::Arc;
+
+    async fn make_ctx() -> ExecContext {
+        let mut vfs = VfsRouter::new();
+        let mem = MemoryFs::new();
+        mem.mkdir(Path::new("a")).await.unwrap();
+        mem.mkdir(Path::new("b")).await.unwrap();
+        mem.write(Path::new("file.txt"), b"data").await.unwrap();
+        vfs.mount("/", mem);
+        ExecContext::new(Arc::new(vfs))
+    }
+
+    fn dir(path: &str) -> ToolArgs {
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String(path.into()));
+        args
+    }
+
+    #[tokio::test]
+    async fn test_pushd_popd_round_trip() {
+        let mut ctx = make_ctx().await;
+        let result = Pushd.execute(dir("/a"), &mut ctx).await;
+        assert!(result.ok(), "{}", result.err);
+        assert_eq!(&*result.text_out(), "/a /");
+
+        let result = Pushd.execute(dir("/b"), &mut ctx).await;
+        assert_eq!(&*result.text_out(), "/b /a /");
+
+        // Bare pushd swaps with the top.
+        let result = Pushd.execute(ToolArgs::new(), &mut ctx).await;
+        assert_eq!(&*result.text_out(), "/a /b /");
+        assert_eq!(ctx.cwd, PathBuf::from("/a"));
+
+        let result = Popd.execute(ToolArgs::new(), &mut ctx).await;
+        assert_eq!(&*result.text_out(), "/b /");
+        let result = Popd.execute(ToolArgs::new(), &mut ctx).await;
+        assert_eq!(&*result.text_out(), "/");
+        assert_eq!(ctx.cwd, PathBuf::from("/"));
+
+        let result = Popd.execute(ToolArgs::new(), &mut ctx).await;
+        assert_eq!(result.code, 1);
+        assert!(result.err.contains("stack empty"), "{}", result.err);
+    }
+
+    #[tokio::test]
+    async fn test_pushd_validates_target() {
+        let mut ctx = make_ctx().await;
+        let result = Pushd.execute(dir("/file.txt"), &mut ctx).await;
+        assert!(result.err.contains("Not a directory"), "{}", result.err);
+        let result = Pushd.execute(dir("/missing"), &mut ctx).await;
+        assert_eq!(result.code, 1);
+        assert!(ctx.dir_stack.is_empty());
+        assert_eq!(ctx.cwd, PathBuf::from("/"));
+    }
+
+    #[tokio::test]
+    async fn test_dirs_verbose_and_clear() {
+        let mut ctx = make_ctx().await;
+        Pushd.execute(dir("/a"), &mut ctx).await;
+        let mut args = ToolArgs::new();
+        args.flags.insert("v".to_string());
+        let result = Dirs.execute(args, &mut ctx).await;
+        assert_eq!(&*result.text_out(), "0\t/a\n1\t/");
+
+        let mut args = ToolArgs::new();
+        args.flags.insert("c".to_string());
+        assert!(Dirs.execute(args, &mut ctx).await.ok());
+        assert_eq!(&*Dirs.execute(ToolArgs::new(), &mut ctx).await.text_out(), "/a");
+    }
+}
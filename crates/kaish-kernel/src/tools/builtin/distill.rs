@@ -0,0 +1,86 @@
+//! distill — Turn the session's recent requests into a kaish script.
+//!
+//! Writes a script from the requests run since the newest checkpoint (or
+//! `--since` a given one; the whole history when there are none): failed
+//! requests dropped, or kept as comments with `--comment-failed`, and with
+//! `--params` quoted strings and absolute paths lifted into variables. See
+//! [`crate::distill`].
+//!
+//! ```text
+//! kaish-checkpoint create before-report
+//! ... work until it does what you want ...
+//! distill --params > /v/scripts/report.kai
+//! ```
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+
+use crate::distill::{distill, history_since, DistillOptions};
+use crate::interpreter::ExecResult;
+use crate::tools::{schema_from_clap, ExecContext, GlobalFlags, Tool, ToolArgs, ToolCtx, ToolSchema};
+
+/// Distill tool: session history to a reusable script.
+pub struct Distill;
+
+/// clap-derived argv layer for distill.
+#[derive(Parser, Debug)]
+#[command(name = "distill", about = "Write a script from the requests since the last checkpoint")]
+struct DistillArgs {
+    /// Checkpoint (id or label) to start from (default: the newest).
+    #[arg(long = "since")]
+    since: Option<String>,
+
+    /// Keep failed requests as comments instead of dropping them.
+    #[arg(short = 'c', long = "comment-failed")]
+    comment_failed: bool,
+
+    /// Lift quoted strings and absolute paths into variables.
+    #[arg(short = 'p', long = "params")]
+    params: bool,
+
+    #[command(flatten)]
+    global: GlobalFlags,
+}
+
+#[async_trait]
+impl Tool for Distill {
+    fn name(&self) -> &str {
+        "distill"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &DistillArgs::command(),
+            "distill",
+            "Write a script from the requests since the last checkpoint",
+            [
+                ("Script the work since the last checkpoint", "distill"),
+                ("Lift literals into variables and save", "distill --params > /v/scripts/report.kai"),
+                ("Start from a named checkpoint", "distill --since before-report"),
+                ("Keep failures as comments", "distill --comment-failed"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("distill: {e}")),
+        };
+        let parsed = match DistillArgs::try_parse_from(std::iter::once("distill".to_string()).chain(argv)) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("distill: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let entries = match history_since(&ctx.checkpoints, &ctx.history, parsed.since.as_deref()) {
+            Ok(entries) => entries,
+            Err(e) => return ExecResult::failure(1, format!("distill: {e}")),
+        };
+        let options = DistillOptions { comment_failed: parsed.comment_failed, parameterize: parsed.params };
+        ExecResult::success(distill(&entries, &options))
+    }
+}
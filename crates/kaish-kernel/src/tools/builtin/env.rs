@@ -346,7 +346,7 @@ async fn execute_with_env(
 #[cfg(feature = "subprocess")]
 fn capture_to_result(code: Option<i32>, stdout: Vec<u8>, stderr: Vec<u8>) -> ExecResult {
     let mut result = ExecResult::success_text_or_bytes(stdout).with_code(code.unwrap_or(-1) as i64);
-    result.err = String::from_utf8_lossy(&stderr).into_owned();
+    result.stderr = String::from_utf8_lossy(&stderr).into_owned();
     result
 }
 
@@ -19,6 +19,7 @@ use clap::{CommandFactory, Parser};
 
 use crate::ast::Value;
 use crate::interpreter::ExecResult;
+use crate::policy::{Policy, POLICY_DENIED_EXIT_CODE};
 use crate::tools::builtin::get_path_string;
 use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
 
@@ -77,8 +78,7 @@ impl Tool for Exec {
         parsed.global.apply(ctx);
 
         if !ctx.allow_external_commands {
-            return ExecResult::failure(1,
-                "exec: external commands are disabled (allow_external_commands=false)");
+            return ExecResult::failure(POLICY_DENIED_EXIT_CODE, Policy::exec_denied_message("exec"));
         }
 
         // First positional is the command, rest are argv. A binary command
@@ -104,10 +104,7 @@ impl Tool for Exec {
             match resolve_in_path(&command_name, &path_var) {
                 Some(resolved) => resolved,
                 None => {
-                    return ExecResult::failure(
-                        127,
-                        format!("exec: {}: command not found", command_name),
-                    )
+                    return ExecResult::not_found(format!("exec: {}: command not found", command_name))
                 }
             }
         };
@@ -141,7 +138,7 @@ impl Tool for Exec {
             // exec() replaces the process — on success it never returns
             let err = cmd.exec();
             // If we get here, exec failed
-            ExecResult::failure(126, format!("exec: {}: {}", command, err))
+            ExecResult::not_executable(format!("exec: {}: {}", command, err))
         }
 
         #[cfg(not(unix))]
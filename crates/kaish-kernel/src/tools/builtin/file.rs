@@ -14,7 +14,7 @@ use kaish_glob::{FileType, SNIFF_PREFIX_LEN};
 use kaish_types::ReadRange;
 
 use crate::interpreter::{ExecResult, OutputData, OutputNode};
-use crate::tools::{schema_from_clap, ExecContext, GlobalFlags, Tool, ToolArgs, ToolCtx, ToolSchema};
+use crate::tools::{schema_from_clap, ExecContext, GlobalFlags, Tool, ToolArgs, ToolCtx, ToolAnnotations, ToolSchema};
 
 /// file tool: identify file type from its leading bytes.
 pub struct File;
@@ -56,6 +56,7 @@ impl Tool for File {
                 ("Identify stdin", "cat photo | file"),
             ],
         )
+        .with_annotations(ToolAnnotations::read_only())
     }
 
     async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
@@ -26,7 +26,7 @@ use crate::backend_walker_fs::BackendWalkerFs;
 use crate::vfs::DirEntry;
 use crate::ignore_config::IgnoreScope;
 use crate::interpreter::{EntryType, ExecResult, OutputData, OutputNode};
-use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolAnnotations, ToolSchema};
 use crate::walker::{EntryTypes, FileWalker, GlobPath, WalkOptions};
 
 /// Find tool: searches for files in directory hierarchy.
@@ -99,6 +99,7 @@ impl Tool for Find {
                 ("Find directories only", "find . -type d"),
             ],
         )
+        .with_annotations(ToolAnnotations::read_only())
     }
 
     async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
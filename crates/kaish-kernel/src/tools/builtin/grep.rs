@@ -17,7 +17,7 @@ use crate::interpreter::{ExecResult, OutputData, OutputNode};
 use crate::tools::builtin::grep_engine::{AccumulatorSink, ContextKind, SearchEvent};
 use crate::tools::builtin::read_repeatable_strings;
 use crate::tools::builtin::regex_dialect::{append_dialect_hint, bre_metas_to_ere};
-use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema, validate_against_schema};
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolAnnotations, ToolSchema, validate_against_schema};
 use crate::validator::{IssueCode, ValidationIssue};
 use crate::walker::{
     build_file_types, list_file_types, FileWalker, GlobPath, IncludeExclude, WalkOptions,
@@ -165,6 +165,7 @@ impl Tool for Grep {
                 ("With file filter", "grep -rn TODO . --include='*.rs'"),
             ],
         )
+        .with_annotations(ToolAnnotations::read_only())
     }
 
     fn validate(&self, args: &ToolArgs) -> Vec<ValidationIssue> {
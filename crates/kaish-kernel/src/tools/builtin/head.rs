@@ -7,7 +7,7 @@ use std::path::Path;
 use crate::ast::Value;
 use crate::backend::ReadRange;
 use crate::interpreter::{ExecResult, OutputData, OutputNode};
-use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolAnnotations, ToolSchema};
 
 /// Head tool: output the first part of files or stdin.
 pub struct Head;
@@ -49,6 +49,7 @@ impl Tool for Head {
                 ("First 100 bytes", "head -c 100 file.txt"),
             ],
         )
+        .with_annotations(ToolAnnotations::read_only())
     }
 
     async fn execute(&self, mut args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
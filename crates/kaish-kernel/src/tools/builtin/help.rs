@@ -1,9 +1,13 @@
 //! help — Display help for topics and tools.
+//!
+//! `help --markdown` renders the registered tools' schemas as a Markdown
+//! reference (`help --markdown > docs/builtins.md`); `help --markdown TOOL`
+//! renders one tool's section.
 
 use async_trait::async_trait;
 use clap::{CommandFactory, Parser};
 
-use crate::help::{get_help, HelpTopic};
+use crate::help::{get_help, tool_markdown, tools_markdown, HelpTopic};
 use crate::interpreter::{ExecResult, OutputData};
 use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
 
@@ -14,6 +18,10 @@ pub struct Help;
 #[derive(Parser, Debug)]
 #[command(name = "help", about = "Display help for kaish topics and tools")]
 struct HelpArgs {
+    /// Render tool help as Markdown (all tools when no tool is named).
+    #[arg(short = 'm', long = "markdown")]
+    markdown: bool,
+
     #[command(flatten)]
     global: GlobalFlags,
 
@@ -36,6 +44,7 @@ impl Tool for Help {
                 ("Show overview", "help"),
                 ("Syntax reference", "help syntax"),
                 ("Help for a tool", "help cat"),
+                ("Export a Markdown reference of every tool", "help --markdown > builtins.md"),
             ],
         )
     }
@@ -57,12 +66,30 @@ impl Tool for Help {
         parsed.global.apply(ctx);
 
         let topic_str = args.get_string("topic", 0).unwrap_or_default();
+        if parsed.markdown {
+            return markdown(&topic_str, &ctx.tool_schemas);
+        }
         let topic = HelpTopic::parse_topic(&topic_str);
         let content = get_help(&topic, &ctx.tool_schemas);
         ExecResult::with_output(OutputData::text(content))
     }
 }
 
+/// `help --markdown [TOOL]`: the whole tool reference, one tool's section,
+/// or — for a documentation topic, which is Markdown already — the topic.
+fn markdown(topic: &str, schemas: &[ToolSchema]) -> ExecResult {
+    if topic.is_empty() {
+        return ExecResult::with_output(OutputData::text(tools_markdown(schemas)));
+    }
+    match HelpTopic::parse_topic(topic) {
+        HelpTopic::Tool(name) => match schemas.iter().find(|s| s.name == name) {
+            Some(schema) => ExecResult::with_output(OutputData::text(tool_markdown(schema))),
+            None => ExecResult::failure(1, format!("help: no such tool: {name}")),
+        },
+        other => ExecResult::with_output(OutputData::text(get_help(&other, schemas))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -176,6 +203,27 @@ mod tests {
         assert!(result.text_out().contains("gather"));
     }
 
+    #[tokio::test]
+    async fn test_help_markdown() {
+        let mut ctx = make_ctx_with_schemas();
+        let mut args = ToolArgs::new();
+        args.flags.insert("markdown".into());
+
+        let result = Help.execute(args, &mut ctx).await;
+        assert!(result.ok());
+        let out = result.text_out();
+        assert!(out.starts_with("# kaish builtins\n"), "{out}");
+        assert!(out.contains("## `cat`"), "{out}");
+        assert!(out.contains("| `--path` | string (required) | File path to read |"), "{out}");
+
+        let mut args = ToolArgs::new();
+        args.flags.insert("markdown".into());
+        args.positional.push(Value::String("nonexistent".into()));
+        let result = Help.execute(args, &mut ctx).await;
+        assert_eq!(result.code, 1);
+        assert!(result.err.contains("no such tool: nonexistent"), "{}", result.err);
+    }
+
     #[tokio::test]
     async fn test_help_limits() {
         let mut ctx = make_ctx_with_schemas();
@@ -22,7 +22,8 @@ struct ToolsArgs {
     #[command(flatten)]
     global: GlobalFlags,
 
-    /// Tool name to introspect; lists all tools when empty.
+    /// Tool name to introspect, or `reload` to re-scan the tool library;
+    /// lists all tools when empty.
     tool: Vec<String>,
 }
 
@@ -40,6 +41,7 @@ impl Tool for Tools {
             [
                 ("List all tools", "kaish-tools"),
                 ("Show tool detail", "kaish-tools cat"),
+                ("Re-load tool definitions from the tool library", "kaish-tools reload"),
             ],
         )
     }
@@ -62,6 +64,9 @@ impl Tool for Tools {
 
         let tool_name = args.get_string("name", 0);
 
+        if tool_name.as_deref() == Some("reload") {
+            return reload_tool_library(ctx).await;
+        }
         if let Some(name) = tool_name {
             format_tool_detail(&ctx.tool_schemas, &name)
         } else {
@@ -70,18 +75,41 @@ impl Tool for Tools {
     }
 }
 
+/// `kaish-tools reload`: re-scan the tool library through the kernel.
+async fn reload_tool_library(ctx: &ExecContext) -> ExecResult {
+    let Some(dispatcher) = ctx.dispatcher.clone() else {
+        return ExecResult::failure(1, "kaish-tools: reload: no kernel to load tools into");
+    };
+    let report = dispatcher.reload_tool_library().await;
+    let mut out = format!("loaded {} tool(s)", report.loaded.len());
+    if !report.loaded.is_empty() {
+        out.push_str(": ");
+        out.push_str(&report.loaded.join(", "));
+    }
+    out.push('\n');
+    if report.errors.is_empty() {
+        ExecResult::success(out)
+    } else {
+        let mut result = ExecResult::failure(1, report.errors.join("\n"));
+        result.set_out(out);
+        result
+    }
+}
+
 fn format_tool_list(schemas: &[ToolSchema]) -> ExecResult {
     let headers = vec![
         "NAME".to_string(),
         "DESCRIPTION".to_string(),
         "PARAMS".to_string(),
+        "HINTS".to_string(),
     ];
 
     let nodes: Vec<OutputNode> = schemas
         .iter()
         .map(|s| {
             let param_count = s.params.len().to_string();
-            OutputNode::new(&s.name).with_cells(vec![s.description.clone(), param_count])
+            let hints = s.annotations.labels().join(",");
+            OutputNode::new(&s.name).with_cells(vec![s.description.clone(), param_count, hints])
         })
         .collect();
 
@@ -93,7 +121,18 @@ fn format_tool_detail(schemas: &[ToolSchema], name: &str) -> ExecResult {
 
     match schema {
         Some(s) => {
-            let mut output = format!("{}\n{}\n\n", s.name, s.description);
+            let mut output = format!("{}\n{}\n", s.name, s.description);
+            let hints = s.annotations.labels();
+            if !hints.is_empty() {
+                output.push_str(&format!("Hints: {}\n", hints.join(", ")));
+            }
+            if let Some(ms) = s.timeout_ms {
+                output.push_str(&format!("Timeout: {ms}ms per call\n"));
+            }
+            if s.retryable {
+                output.push_str("Retryable: yes\n");
+            }
+            output.push('\n');
 
             if !s.params.is_empty() {
                 output.push_str("Parameters:\n");
@@ -261,7 +300,7 @@ mod tests {
     use super::*;
     use crate::ast::Value;
     use crate::interpreter::{apply_output_format, OutputFormat};
-    use crate::tools::ToolSchema as TS;
+    use crate::tools::{ToolAnnotations, ToolSchema as TS};
     use crate::vfs::{MemoryFs, VfsRouter};
     use std::sync::Arc;
 
@@ -272,7 +311,7 @@ mod tests {
         let mut ctx = ExecContext::new(Arc::new(vfs));
         ctx.set_tool_schemas(vec![
             TS::new("echo", "Print arguments"),
-            TS::new("cat", "Concatenate files"),
+            TS::new("cat", "Concatenate files").with_annotations(ToolAnnotations::read_only()),
         ]);
         ctx
     }
@@ -322,6 +361,21 @@ mod tests {
         assert!(result.text_out().contains("Print arguments"));
     }
 
+    #[tokio::test]
+    async fn test_tools_show_annotation_hints() {
+        let mut ctx = make_ctx();
+        let result = Tools.execute(ToolArgs::new(), &mut ctx).await;
+        let result = apply_output_format(result, OutputFormat::Json);
+        let data: Vec<serde_json::Value> = serde_json::from_str(&result.text_out()).expect("valid JSON");
+        let cat = data.iter().find(|row| row["NAME"] == "cat").expect("cat listed");
+        assert_eq!(cat["HINTS"], "read-only,idempotent");
+
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("cat".into()));
+        let result = Tools.execute(args, &mut ctx).await;
+        assert!(result.text_out().contains("Hints: read-only, idempotent"));
+    }
+
     #[tokio::test]
     async fn test_tools_detail_not_found() {
         let mut ctx = make_ctx();
@@ -0,0 +1,214 @@
+//! kaish-audit — Read and prune the kernel's audit log.
+//!
+//! Subcommands: list, search, prune, path.
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+
+use crate::audit::{AuditLog, AuditRetention};
+use crate::duration::parse_duration;
+use crate::interpreter::{ExecResult, OutputData, OutputNode};
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+
+/// Entries `kaish-audit list` shows when no count is given.
+const DEFAULT_LIST_COUNT: usize = 20;
+
+/// KaishAudit tool: inspect the audit log of executed commands.
+pub struct KaishAudit;
+
+/// clap-derived argv layer for kaish-audit.
+#[derive(Parser, Debug)]
+#[command(name = "kaish-audit", about = "Read, search, and prune the audit log of executed commands")]
+struct KaishAuditArgs {
+    /// For `prune`: drop entries older than this (`30d`, `12h`, `90m`).
+    #[arg(long = "older-than")]
+    older_than: Option<String>,
+
+    /// For `prune`: drop the oldest entries until the file fits in this many bytes.
+    #[arg(long = "max-bytes")]
+    max_bytes: Option<u64>,
+
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// Subcommand (`list`, `search`, `prune`, `path`) and its arguments.
+    args: Vec<String>,
+}
+
+#[async_trait]
+impl Tool for KaishAudit {
+    fn name(&self) -> &str {
+        "kaish-audit"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &KaishAuditArgs::command(),
+            "kaish-audit",
+            "Read, search, and prune the audit log of executed commands",
+            [
+                ("Show the last 20 commands", "kaish-audit list"),
+                ("Show the last 100 commands", "kaish-audit list 100"),
+                ("Find past test runs", "kaish-audit search \"cargo test\""),
+                ("Keep only the newest 1000 entries", "kaish-audit prune 1000"),
+                ("Drop entries older than a week", "kaish-audit prune --older-than 7d"),
+                ("Apply the configured retention now", "kaish-audit prune"),
+                ("Print the log file location", "kaish-audit path"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("kaish-audit: {e}")),
+        };
+        let parsed = match KaishAuditArgs::try_parse_from(
+            std::iter::once("kaish-audit".to_string()).chain(argv),
+        ) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("kaish-audit: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let Some(log) = ctx.audit_log.clone() else {
+            return ExecResult::failure(1, "kaish-audit: no audit log configured for this kernel");
+        };
+
+        let (subcmd, rest) = match parsed.args.split_first() {
+            Some((subcmd, rest)) => (subcmd.as_str(), rest),
+            None => ("list", &[][..]),
+        };
+        match subcmd {
+            "list" => cmd_list(&log, rest),
+            "search" => cmd_search(&log, rest),
+            "prune" => cmd_prune(&log, rest, &parsed),
+            "path" => ExecResult::with_output(OutputData::text(log.path().display().to_string())),
+            other => ExecResult::failure(1, format!("kaish-audit: unknown subcommand: {}", other)),
+        }
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn parse_count(subcmd: &str, value: &str) -> Result<usize, ExecResult> {
+    value
+        .parse::<usize>()
+        .map_err(|_| ExecResult::failure(1, format!("kaish-audit {}: invalid count: {}", subcmd, value)))
+}
+
+fn cmd_list(log: &AuditLog, rest: &[String]) -> ExecResult {
+    let count = match rest.first() {
+        Some(n) => match parse_count("list", n) {
+            Ok(n) => n,
+            Err(e) => return e,
+        },
+        None => DEFAULT_LIST_COUNT,
+    };
+    let entries = match log.tail(count) {
+        Ok(entries) => entries,
+        Err(e) => return ExecResult::failure(1, format!("kaish-audit list: {}", e)),
+    };
+
+    if entries.is_empty() {
+        return ExecResult::with_output(OutputData::text("audit log is empty"));
+    }
+
+    let nodes: Vec<OutputNode> = entries
+        .iter()
+        .map(|entry| {
+            OutputNode::new(&entry.timestamp).with_cells(vec![
+                entry.kernel.clone(),
+                entry.origin.clone(),
+                entry.code.to_string(),
+                entry.command_line(),
+            ])
+        })
+        .collect();
+
+    ExecResult::with_output(OutputData::table(
+        vec![
+            "TIME".to_string(),
+            "KERNEL".to_string(),
+            "ORIGIN".to_string(),
+            "CODE".to_string(),
+            "COMMAND".to_string(),
+        ],
+        nodes,
+    ))
+}
+
+fn cmd_search(log: &AuditLog, rest: &[String]) -> ExecResult {
+    if rest.is_empty() {
+        return ExecResult::failure(1, "kaish-audit search: specify a query");
+    }
+    let matches = match log.search(&rest.join(" ")) {
+        Ok(matches) => matches,
+        Err(e) => return ExecResult::failure(1, format!("kaish-audit search: {}", e)),
+    };
+
+    if matches.is_empty() {
+        return ExecResult::with_output(OutputData::text("no matches"));
+    }
+
+    let nodes: Vec<OutputNode> = matches
+        .iter()
+        .map(|m| {
+            OutputNode::new(m.id.to_string()).with_cells(vec![
+                m.entry.timestamp.clone(),
+                m.entry.code.to_string(),
+                m.snippet.clone(),
+            ])
+        })
+        .collect();
+    // --json gets the whole entry (kernel, origin, full argv) for each hit.
+    let mut output = OutputData::table(
+        vec![
+            "ID".to_string(),
+            "TIME".to_string(),
+            "CODE".to_string(),
+            "COMMAND".to_string(),
+        ],
+        nodes,
+    );
+    if let Ok(json) = serde_json::to_value(&matches) {
+        output = output.with_rich_json(json);
+    }
+    ExecResult::with_output(output)
+}
+
+/// `prune [N] [--older-than DUR] [--max-bytes N]`; with no limits given,
+/// apply the log's configured retention.
+fn cmd_prune(log: &AuditLog, rest: &[String], parsed: &KaishAuditArgs) -> ExecResult {
+    let mut retention = AuditRetention::default();
+    if let Some(keep) = rest.first() {
+        match parse_count("prune", keep) {
+            Ok(n) => retention = retention.with_max_entries(n),
+            Err(e) => return e,
+        }
+    }
+    if let Some(age) = &parsed.older_than {
+        let Some(age) = parse_duration(age) else {
+            return ExecResult::failure(1, format!("kaish-audit prune: invalid duration: {}", age));
+        };
+        retention = retention.with_max_age(age);
+    }
+    if let Some(bytes) = parsed.max_bytes {
+        retention = retention.with_max_bytes(bytes);
+    }
+    if retention.is_unbounded() {
+        retention = *log.retention();
+    }
+    if retention.is_unbounded() {
+        return ExecResult::failure(
+            1,
+            "kaish-audit prune: specify a count, --older-than, or --max-bytes (no retention configured)",
+        );
+    }
+    match log.prune_with(&retention) {
+        Ok(removed) => ExecResult::with_output(OutputData::text(format!("pruned {} entries", removed))),
+        Err(e) => ExecResult::failure(1, format!("kaish-audit prune: {}", e)),
+    }
+}
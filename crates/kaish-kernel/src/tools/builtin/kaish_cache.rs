@@ -0,0 +1,82 @@
+//! kaish-cache — Inspect or clear the result cache.
+//!
+//! Subcommands: list (default), clear.
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+
+use crate::interpreter::{ExecResult, OutputData, OutputNode};
+use crate::result_cache::ResultCache;
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+
+/// KaishCache tool: list or clear cached results of pure commands.
+pub struct KaishCache;
+
+/// clap-derived argv layer for kaish-cache.
+#[derive(Parser, Debug)]
+#[command(name = "kaish-cache", about = "Inspect or clear the result cache")]
+struct KaishCacheArgs {
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// Subcommand (`list`, `clear`).
+    args: Vec<String>,
+}
+
+#[async_trait]
+impl Tool for KaishCache {
+    fn name(&self) -> &str {
+        "kaish-cache"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &KaishCacheArgs::command(),
+            "kaish-cache",
+            "List or clear cached results of pure commands (cat, checksum on read-only mounts)",
+            [
+                ("Cached commands and their time to live", "kaish-cache"),
+                ("Drop every cached result", "kaish-cache clear"),
+                ("Bypass the cache for one command", "cat --no-cache /docs/README.md"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("kaish-cache: {e}")),
+        };
+        let parsed = match KaishCacheArgs::try_parse_from(
+            std::iter::once("kaish-cache".to_string()).chain(argv),
+        ) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("kaish-cache: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        match parsed.args.first().map(String::as_str).unwrap_or("list") {
+            "list" => cmd_list(&ctx.result_cache),
+            "clear" => {
+                let dropped = ctx.result_cache.clear();
+                ExecResult::success(format!("cleared {dropped} cached result(s)\n"))
+            }
+            other => ExecResult::failure(1, format!("kaish-cache: unknown subcommand: {}", other)),
+        }
+    }
+}
+
+fn cmd_list(cache: &ResultCache) -> ExecResult {
+    let nodes: Vec<OutputNode> = cache
+        .entries()
+        .into_iter()
+        .map(|entry| OutputNode::new(entry.command).with_cells(vec![format!("{}s", entry.expires_in.as_secs())]))
+        .collect();
+    ExecResult::with_output(OutputData::table(
+        vec!["COMMAND".to_string(), "TTL".to_string()],
+        nodes,
+    ))
+}
@@ -0,0 +1,135 @@
+//! kaish-checkpoint — Snapshot session variables and cwd, and roll back.
+//!
+//! Subcommands: create, list, restore, drop.
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+
+use crate::interpreter::{ExecResult, OutputData, OutputNode};
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+
+/// KaishCheckpoint tool: manage session checkpoints.
+pub struct KaishCheckpoint;
+
+/// clap-derived argv layer for kaish-checkpoint.
+#[derive(Parser, Debug)]
+#[command(name = "kaish-checkpoint", about = "Snapshot session variables and cwd, and roll back")]
+struct KaishCheckpointArgs {
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// Subcommand (`create`, `list`, `restore`, `drop`) and its arguments.
+    args: Vec<String>,
+}
+
+#[async_trait]
+impl Tool for KaishCheckpoint {
+    fn name(&self) -> &str {
+        "kaish-checkpoint"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &KaishCheckpointArgs::command(),
+            "kaish-checkpoint",
+            "Snapshot session variables and cwd, and roll back",
+            [
+                ("Take a checkpoint", "kaish-checkpoint create before-build"),
+                ("List checkpoints", "kaish-checkpoint list"),
+                ("Roll back by label", "kaish-checkpoint restore before-build"),
+                ("Roll back by id", "kaish-checkpoint restore 1"),
+                ("Forget a checkpoint", "kaish-checkpoint drop 1"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("kaish-checkpoint: {e}")),
+        };
+        let parsed = match KaishCheckpointArgs::try_parse_from(
+            std::iter::once("kaish-checkpoint".to_string()).chain(argv),
+        ) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("kaish-checkpoint: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let (subcmd, rest) = match parsed.args.split_first() {
+            Some((subcmd, rest)) => (subcmd.as_str(), rest),
+            None => ("list", &[][..]),
+        };
+        match subcmd {
+            "create" => cmd_create(rest, ctx),
+            "list" => cmd_list(ctx),
+            "restore" => cmd_restore(rest, ctx),
+            "drop" => cmd_drop(rest, ctx),
+            other => ExecResult::failure(1, format!("kaish-checkpoint: unknown subcommand: {}", other)),
+        }
+    }
+}
+
+fn cmd_create(rest: &[String], ctx: &mut ExecContext) -> ExecResult {
+    let label = rest.first().cloned();
+    let id = ctx.checkpoints.create(label, &ctx.scope, &ctx.cwd, ctx.history.mark());
+    ExecResult::with_output(OutputData::text(format!("{id}\n")))
+}
+
+fn cmd_list(ctx: &ExecContext) -> ExecResult {
+    let checkpoints = ctx.checkpoints.list();
+    if checkpoints.is_empty() {
+        return ExecResult::with_output(OutputData::text("no checkpoints"));
+    }
+
+    let nodes: Vec<OutputNode> = checkpoints
+        .iter()
+        .map(|c| {
+            OutputNode::new(c.id.to_string()).with_cells(vec![
+                c.label.clone().unwrap_or_default(),
+                c.created.clone(),
+                c.vars.len().to_string(),
+                c.cwd.display().to_string(),
+            ])
+        })
+        .collect();
+
+    ExecResult::with_output(OutputData::table(
+        vec![
+            "ID".to_string(),
+            "LABEL".to_string(),
+            "CREATED".to_string(),
+            "VARS".to_string(),
+            "CWD".to_string(),
+        ],
+        nodes,
+    ))
+}
+
+fn cmd_restore(rest: &[String], ctx: &mut ExecContext) -> ExecResult {
+    let Some(which) = rest.first() else {
+        return ExecResult::failure(1, "kaish-checkpoint restore: specify a checkpoint id or label");
+    };
+    let Some(checkpoint) = ctx.checkpoints.find(which) else {
+        return ExecResult::failure(1, format!("kaish-checkpoint restore: no such checkpoint: {}", which));
+    };
+    checkpoint.restore_into(&mut ctx.scope);
+    ctx.set_cwd(checkpoint.cwd);
+    ExecResult::success("")
+}
+
+fn cmd_drop(rest: &[String], ctx: &mut ExecContext) -> ExecResult {
+    let Some(which) = rest.first() else {
+        return ExecResult::failure(1, "kaish-checkpoint drop: specify a checkpoint id or label");
+    };
+    match ctx.checkpoints.find(which) {
+        Some(checkpoint) => {
+            ctx.checkpoints.remove(checkpoint.id);
+            ExecResult::success("")
+        }
+        None => ExecResult::failure(1, format!("kaish-checkpoint drop: no such checkpoint: {}", which)),
+    }
+}
@@ -5,6 +5,10 @@
 //! - Else if `.out` is non-empty (captured stdout), emit it verbatim.
 //! - Else exit 1 with "no data" on stderr.
 //!
+//! With `--error`, emit the previous command's classified failure instead —
+//! `{"kind","code","message","hint"?}` (see `ErrorKind`), so a script branches
+//! on `kind` rather than parsing the message. Exits 1 if it succeeded.
+//!
 //! Refuses to run with piped stdin (Middle or Last in a pipeline):
 //! pipeline stages don't see the previous stage's ExecResult, only the
 //! kernel's pre-pipeline `last_result`, so `seq 1 5 | kaish-last` would
@@ -14,6 +18,7 @@
 use async_trait::async_trait;
 use clap::{CommandFactory, Parser};
 
+use crate::ast::Value;
 use crate::dispatch::PipelinePosition;
 use crate::interpreter::{ExecResult, OutputData};
 use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
@@ -31,6 +36,10 @@ struct KaishLastArgs {
     #[command(flatten)]
     global: GlobalFlags,
 
+    /// Emit the previous command's error as {kind, code, message, hint}.
+    #[arg(long)]
+    error: bool,
+
     /// Sink — to_argv() always emits `--` before positionals.
     #[arg(hide = true)]
     rest: Vec<String>,
@@ -50,6 +59,10 @@ impl Tool for KaishLast {
             [
                 ("Pipe structured data through jq", "seq 1 5\nkaish-last | jq '.[2]'"),
                 ("Capture for later use", "seq 1 5\nDATA=$(kaish-last)"),
+                (
+                    "Branch on the kind of failure",
+                    "cat missing.txt\nkaish-last --error | jq -r '.kind'",
+                ),
             ],
         )
     }
@@ -85,6 +98,14 @@ impl Tool for KaishLast {
 
         let prev = ctx.scope.last_result();
 
+        if parsed.error {
+            let Some(info) = prev.error_info() else {
+                return ExecResult::failure(1, "kaish-last: previous command succeeded");
+            };
+            let json = serde_json::to_value(&info).unwrap_or(serde_json::Value::Null);
+            return ExecResult::success_with_data(format!("{json}\n"), Value::Json(json));
+        }
+
         if let Some(ref data) = prev.data {
             let json = value_to_json(data);
             return ExecResult::with_output(OutputData::text(format!("{}\n", json)));
@@ -107,7 +128,6 @@ impl Tool for KaishLast {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ast::Value;
     use crate::interpreter::ExecResult;
     use crate::vfs::{MemoryFs, VfsRouter};
     use std::sync::Arc;
@@ -181,4 +201,34 @@ mod tests {
         assert!(result.ok(), "expected success producing into pipe");
         assert_eq!(result.text_out().trim(), "[1,2]");
     }
+
+    #[tokio::test]
+    async fn error_flag_emits_the_classified_failure() {
+        let mut ctx = make_ctx();
+        ctx.scope
+            .set_last_result(ExecResult::not_found("command not found: nope"));
+
+        let mut args = ToolArgs::new();
+        args.flags.insert("error".to_string());
+        let result = KaishLast.execute(args, &mut ctx).await;
+        assert!(result.ok(), "err: {}", result.err);
+        let json: serde_json::Value =
+            serde_json::from_str(result.text_out().trim()).expect("json");
+        assert_eq!(json["kind"], "not_found");
+        assert_eq!(json["code"], 127);
+        assert_eq!(json["message"], "command not found: nope");
+        assert!(json["hint"].is_string());
+    }
+
+    #[tokio::test]
+    async fn error_flag_fails_after_a_success() {
+        let mut ctx = make_ctx();
+        ctx.scope.set_last_result(ExecResult::success("fine\n"));
+
+        let mut args = ToolArgs::new();
+        args.flags.insert("error".to_string());
+        let result = KaishLast.execute(args, &mut ctx).await;
+        assert_eq!(result.code, 1);
+        assert!(result.err.contains("succeeded"));
+    }
 }
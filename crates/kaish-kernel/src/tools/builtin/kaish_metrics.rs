@@ -0,0 +1,188 @@
+//! kaish-metrics — Show command timings, filesystem activity, and job counts.
+//!
+//! Subcommands: commands (default), vfs, jobs, reset.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+use serde_json::json;
+
+use crate::interpreter::{ExecResult, OutputData, OutputNode};
+use crate::metrics::MetricsSnapshot;
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+
+/// KaishMetrics tool: report the kernel's metrics.
+pub struct KaishMetrics;
+
+/// clap-derived argv layer for kaish-metrics.
+#[derive(Parser, Debug)]
+#[command(name = "kaish-metrics", about = "Show command timings, filesystem activity, and job counts")]
+struct KaishMetricsArgs {
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// Subcommand (`commands`, `vfs`, `jobs`, `reset`).
+    args: Vec<String>,
+}
+
+#[async_trait]
+impl Tool for KaishMetrics {
+    fn name(&self) -> &str {
+        "kaish-metrics"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &KaishMetricsArgs::command(),
+            "kaish-metrics",
+            "Show per-command counts and timings, filesystem operations per mount, and job counts",
+            [
+                ("Per-command calls, failures, and timings", "kaish-metrics"),
+                ("Filesystem operations per mount", "kaish-metrics vfs"),
+                ("Background job counts", "kaish-metrics jobs"),
+                ("Everything, as JSON", "kaish-metrics --json"),
+                ("Start counting afresh", "kaish-metrics reset"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("kaish-metrics: {e}")),
+        };
+        let parsed = match KaishMetricsArgs::try_parse_from(
+            std::iter::once("kaish-metrics".to_string()).chain(argv),
+        ) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("kaish-metrics: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let subcmd = parsed.args.first().map(String::as_str).unwrap_or("commands");
+        if subcmd == "reset" {
+            ctx.metrics.reset();
+            return ExecResult::success("");
+        }
+        let snapshot = ctx.metrics.snapshot().await;
+        match subcmd {
+            "commands" => cmd_commands(&snapshot),
+            "vfs" => cmd_vfs(&snapshot),
+            "jobs" => cmd_jobs(&snapshot),
+            other => ExecResult::failure(1, format!("kaish-metrics: unknown subcommand: {}", other)),
+        }
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn format_millis(duration: Duration) -> String {
+    format!("{:.1}ms", millis(duration))
+}
+
+/// The whole snapshot as one JSON object, durations in milliseconds.
+fn snapshot_json(snapshot: &MetricsSnapshot) -> serde_json::Value {
+    let commands: serde_json::Map<String, serde_json::Value> = snapshot
+        .commands
+        .iter()
+        .map(|(name, stats)| {
+            (
+                name.clone(),
+                json!({
+                    "calls": stats.calls,
+                    "failures": stats.failures,
+                    "total_ms": millis(stats.total),
+                    "mean_ms": millis(stats.mean()),
+                    "max_ms": millis(stats.max),
+                }),
+            )
+        })
+        .collect();
+    let vfs_ops: serde_json::Map<String, serde_json::Value> = snapshot
+        .vfs_ops
+        .iter()
+        .map(|(mount, ops)| (mount.display().to_string(), json!(ops)))
+        .collect();
+    let jobs = &snapshot.jobs;
+    json!({
+        "commands": commands,
+        "vfs_ops": vfs_ops,
+        "jobs": {
+            "started": jobs.started,
+            "running": jobs.running,
+            "stopped": jobs.stopped,
+            "done": jobs.done,
+            "failed": jobs.failed,
+            "latched": jobs.latched,
+        },
+    })
+}
+
+fn cmd_commands(snapshot: &MetricsSnapshot) -> ExecResult {
+    // Busiest first: the commands that took the most time lead.
+    let mut rows: Vec<_> = snapshot.commands.iter().collect();
+    rows.sort_by(|a, b| b.1.total.cmp(&a.1.total).then_with(|| a.0.cmp(b.0)));
+    let nodes: Vec<OutputNode> = rows
+        .into_iter()
+        .map(|(name, stats)| {
+            OutputNode::new(name).with_cells(vec![
+                stats.calls.to_string(),
+                stats.failures.to_string(),
+                format_millis(stats.total),
+                format_millis(stats.mean()),
+                format_millis(stats.max),
+            ])
+        })
+        .collect();
+    let output = OutputData::table(
+        vec![
+            "COMMAND".to_string(),
+            "CALLS".to_string(),
+            "FAILED".to_string(),
+            "TOTAL".to_string(),
+            "MEAN".to_string(),
+            "MAX".to_string(),
+        ],
+        nodes,
+    )
+    .with_rich_json(snapshot_json(snapshot));
+    ExecResult::with_output(output)
+}
+
+fn cmd_vfs(snapshot: &MetricsSnapshot) -> ExecResult {
+    let nodes: Vec<OutputNode> = snapshot
+        .vfs_ops
+        .iter()
+        .map(|(mount, ops)| OutputNode::new(mount.display().to_string()).with_cells(vec![ops.to_string()]))
+        .collect();
+    ExecResult::with_output(OutputData::table(
+        vec!["MOUNT".to_string(), "OPS".to_string()],
+        nodes,
+    ))
+}
+
+fn cmd_jobs(snapshot: &MetricsSnapshot) -> ExecResult {
+    let jobs = &snapshot.jobs;
+    let rows = [
+        ("started", jobs.started.to_string()),
+        ("running", jobs.running.to_string()),
+        ("stopped", jobs.stopped.to_string()),
+        ("done", jobs.done.to_string()),
+        ("failed", jobs.failed.to_string()),
+        ("latched", jobs.latched.to_string()),
+    ];
+    let nodes: Vec<OutputNode> = rows
+        .into_iter()
+        .map(|(name, count)| OutputNode::new(name).with_cells(vec![count]))
+        .collect();
+    ExecResult::with_output(OutputData::table(
+        vec!["JOBS".to_string(), "COUNT".to_string()],
+        nodes,
+    ))
+}
@@ -0,0 +1,212 @@
+//! kaish-state — Export, import, and compare the session state as JSON.
+//!
+//! Subcommands: export, import, diff.
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+
+use crate::ast::Value;
+use crate::interpreter::{value_to_string, ExecResult, OutputData, OutputNode};
+use crate::state::{KernelState, StateDiff};
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+
+/// KaishState tool: move a session's state in and out as JSON.
+pub struct KaishState;
+
+/// clap-derived argv layer for kaish-state.
+#[derive(Parser, Debug)]
+#[command(name = "kaish-state", about = "Export, import, and compare the session state as JSON")]
+struct KaishStateArgs {
+    /// For `diff`: compare this checkpoint (id or label) against the session.
+    #[arg(long)]
+    checkpoint: Option<String>,
+
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// Subcommand (`export`, `import`, `diff`) and its arguments.
+    args: Vec<String>,
+}
+
+#[async_trait]
+impl Tool for KaishState {
+    fn name(&self) -> &str {
+        "kaish-state"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &KaishStateArgs::command(),
+            "kaish-state",
+            "Export, import, and compare the session state (variables, cwd, checkpoints, mounts) as JSON",
+            [
+                ("Save the session", "kaish-state export > /tmp/session.json"),
+                ("Restore it", "kaish-state import /tmp/session.json"),
+                ("Restore from stdin", "cat /tmp/session.json | kaish-state import"),
+                ("What changed since a save", "kaish-state diff /tmp/session.json"),
+                ("Compare two saves", "kaish-state diff /tmp/a.json /tmp/b.json"),
+                ("What changed since a checkpoint", "kaish-state diff --checkpoint before-build"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("kaish-state: {e}")),
+        };
+        let parsed = match KaishStateArgs::try_parse_from(
+            std::iter::once("kaish-state".to_string()).chain(argv),
+        ) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("kaish-state: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let Some((subcmd, rest)) = parsed.args.split_first() else {
+            return ExecResult::failure(1, "kaish-state: missing subcommand (export, import, diff)");
+        };
+        match subcmd.as_str() {
+            "export" => cmd_export(ctx),
+            "import" => cmd_import(rest, ctx).await,
+            "diff" => cmd_diff(rest, parsed.checkpoint.as_deref(), ctx).await,
+            other => ExecResult::failure(1, format!("kaish-state: unknown subcommand: {}", other)),
+        }
+    }
+}
+
+fn capture(ctx: &ExecContext) -> KernelState {
+    KernelState::capture(&ctx.scope, &ctx.cwd, &ctx.backend.mounts(), &ctx.checkpoints)
+}
+
+fn cmd_export(ctx: &ExecContext) -> ExecResult {
+    match capture(ctx).to_json() {
+        Ok(json) => ExecResult::with_output(OutputData::text(json)),
+        Err(e) => ExecResult::failure(1, format!("kaish-state export: {e:#}")),
+    }
+}
+
+/// Read a state document from `path` (through the VFS), or from stdin.
+async fn read_document(rest: &[String], ctx: &mut ExecContext, subcmd: &str) -> Result<String, ExecResult> {
+    match rest.first() {
+        Some(path) => {
+            let resolved = ctx.resolve_path(path);
+            let bytes = ctx
+                .backend
+                .read(&resolved, None)
+                .await
+                .map_err(|e| ExecResult::failure(1, format!("kaish-state {}: {}: {}", subcmd, path, e)))?;
+            String::from_utf8(bytes)
+                .map_err(|e| ExecResult::failure(1, format!("kaish-state {}: {}: {}", subcmd, path, e)))
+        }
+        None => match ctx.read_stdin_to_text().await {
+            Ok(Some(text)) => Ok(text),
+            Ok(None) => Err(ExecResult::failure(
+                1,
+                format!("kaish-state {}: specify a file or pipe a state document on stdin", subcmd),
+            )),
+            Err(e) => Err(ExecResult::failure(1, format!("kaish-state {}: {}", subcmd, e))),
+        },
+    }
+}
+
+async fn cmd_import(rest: &[String], ctx: &mut ExecContext) -> ExecResult {
+    let text = match read_document(rest, ctx, "import").await {
+        Ok(text) => text,
+        Err(e) => return e,
+    };
+    let state = match KernelState::from_json(&text) {
+        Ok(state) => state,
+        Err(e) => return ExecResult::failure(1, format!("kaish-state import: {e:#}")),
+    };
+
+    state.restore_into(&mut ctx.scope, &ctx.checkpoints);
+    ctx.set_cwd(state.cwd.clone());
+
+    let mut result = ExecResult::success("");
+    let missing = state.missing_mounts(&ctx.backend.mounts());
+    if !missing.is_empty() {
+        let paths: Vec<String> = missing.iter().map(|p| p.display().to_string()).collect();
+        result.err = format!(
+            "kaish-state import: mounts not present in this kernel: {}\n",
+            paths.join(", ")
+        );
+    }
+    result
+}
+
+/// Load a state document from a VFS path.
+async fn load(path: &str, ctx: &ExecContext) -> Result<KernelState, ExecResult> {
+    let bytes = ctx
+        .backend
+        .read(&ctx.resolve_path(path), None)
+        .await
+        .map_err(|e| ExecResult::failure(1, format!("kaish-state diff: {}: {}", path, e)))?;
+    let text = String::from_utf8(bytes)
+        .map_err(|e| ExecResult::failure(1, format!("kaish-state diff: {}: {}", path, e)))?;
+    KernelState::from_json(&text).map_err(|e| ExecResult::failure(1, format!("kaish-state diff: {}: {e:#}", path)))
+}
+
+async fn cmd_diff(rest: &[String], checkpoint: Option<&str>, ctx: &ExecContext) -> ExecResult {
+    let (older, newer) = match (checkpoint, rest) {
+        (Some(which), []) => {
+            let Some(checkpoint) = ctx.checkpoints.find(which) else {
+                return ExecResult::failure(1, format!("kaish-state diff: no such checkpoint: {}", which));
+            };
+            (KernelState::from_checkpoint(&checkpoint, &ctx.backend.mounts()), capture(ctx))
+        }
+        (None, [old]) => match load(old, ctx).await {
+            Ok(state) => (state, capture(ctx)),
+            Err(e) => return e,
+        },
+        (None, [old, new]) => match (load(old, ctx).await, load(new, ctx).await) {
+            (Ok(older), Ok(newer)) => (older, newer),
+            (Err(e), _) | (_, Err(e)) => return e,
+        },
+        _ => {
+            return ExecResult::failure(
+                1,
+                "kaish-state diff: expected OLD [NEW] state files, or --checkpoint ID|LABEL alone",
+            );
+        }
+    };
+    render_diff(&older.diff(&newer))
+}
+
+fn render_diff(diff: &StateDiff) -> ExecResult {
+    if diff.is_empty() {
+        return ExecResult::with_output(OutputData::text("no differences"));
+    }
+    let show = |value: &Option<Value>| value.as_ref().map(value_to_string).unwrap_or_default();
+    let nodes: Vec<OutputNode> = diff
+        .changes
+        .iter()
+        .map(|c| {
+            OutputNode::new(&c.name).with_cells(vec![
+                c.kind.as_str().to_string(),
+                c.change.as_str().to_string(),
+                show(&c.old),
+                show(&c.new),
+            ])
+        })
+        .collect();
+    // --json gets the typed changes (old/new keep their value types) rather
+    // than the table's stringified cells.
+    let mut output = OutputData::table(
+        vec![
+            "NAME".to_string(),
+            "KIND".to_string(),
+            "CHANGE".to_string(),
+            "OLD".to_string(),
+            "NEW".to_string(),
+        ],
+        nodes,
+    );
+    if let Ok(json) = serde_json::to_value(&diff.changes) {
+        output = output.with_rich_json(json);
+    }
+    ExecResult::with_output(output)
+}
@@ -1,6 +1,7 @@
-//! kaish-vfs — Inspect and manage the active overlay VFS transaction.
+//! kaish-vfs — Inspect and manage the active overlay VFS transaction, and
+//! snapshot memory-backed directories.
 //!
-//! Subcommands: status, diff, commit, reset.
+//! Subcommands: status, diff, commit, reset, snapshot.
 //!
 //! `status` always works: it reports `mode: transaction` inside an overlay
 //! session and `mode: direct` otherwise, so an agent can ask "what session am
@@ -11,12 +12,20 @@
 //! builds, the `commit` subcommand additionally fails at runtime because
 //! `LocalFs` is absent — other subcommands still work against the in-memory
 //! overlay.
+//!
+//! `snapshot` needs no overlay: `snapshot create [PATH]` captures a directory
+//! on a memory-backed mount (`/scratch`, `/v`, an overlay's layer) and prints
+//! the snapshot's id; `snapshot restore ID [PATH]` rolls the directory back
+//! to it; `snapshot list [PATH]` and `snapshot drop ID [PATH]` manage what
+//! the mount holds. PATH defaults to the working directory — for restore,
+//! list, and drop it only picks the mount.
 
 use async_trait::async_trait;
 use clap::{CommandFactory, Parser};
 
 use crate::interpreter::{ExecResult, OutputData, OutputNode};
 use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+use crate::vfs::SnapshotInfo;
 
 /// kaish-vfs tool: inspect and manage the overlay VFS transaction.
 pub struct KaishVfs;
@@ -28,7 +37,7 @@ struct KaishVfsArgs {
     #[command(flatten)]
     global: GlobalFlags,
 
-    /// Subcommand (status, diff, commit, reset) and its arguments.
+    /// Subcommand (status, diff, commit, reset, snapshot) and its arguments.
     args: Vec<String>,
 }
 
@@ -56,6 +65,8 @@ impl Tool for KaishVfs {
                 ("Commit all changes to real files", "kaish-vfs commit"),
                 ("Discard all virtual edits", "kaish-vfs reset"),
                 ("Discard edits to one path", "kaish-vfs reset src/main.rs"),
+                ("Snapshot a scratch directory", "kaish-vfs snapshot create /scratch"),
+                ("Roll it back to snapshot 1", "kaish-vfs snapshot restore 1 /scratch"),
             ],
         )
     }
@@ -78,7 +89,7 @@ impl Tool for KaishVfs {
 
         let subcmd = match args.get_string("", 0) {
             Some(s) => s,
-            None => return ExecResult::failure(1, format!("kaish-vfs: missing subcommand (status, diff, commit, reset)\n{}", NO_OVERLAY_MSG)),
+            None => return ExecResult::failure(1, format!("kaish-vfs: missing subcommand (status, diff, commit, reset, snapshot)\n{}", NO_OVERLAY_MSG)),
         };
 
         match subcmd.as_str() {
@@ -115,8 +126,17 @@ impl Tool for KaishVfs {
                 let path_arg = args.get_string("", 1);
                 cmd_reset(path_arg.as_deref(), ctx).await
             }
+            "snapshot" => {
+                let words: Vec<String> = args.positional.iter().skip(1)
+                    .filter_map(|v| match v {
+                        crate::ast::Value::String(s) => Some(s.clone()),
+                        _ => None,
+                    })
+                    .collect();
+                cmd_snapshot(&words, ctx).await
+            }
             other => ExecResult::failure(1, format!(
-                "kaish-vfs: unknown subcommand '{}' (try: status, diff, commit, reset)",
+                "kaish-vfs: unknown subcommand '{}' (try: status, diff, commit, reset, snapshot)",
                 other
             )),
         }
@@ -455,6 +475,73 @@ async fn cmd_reset(_path_arg: Option<&str>, ctx: &ExecContext) -> ExecResult {
     ExecResult::failure(1, NO_OVERLAY_MSG)
 }
 
+const SNAPSHOT_USAGE: &str =
+    "usage: kaish-vfs snapshot create [PATH] | list [PATH] | restore ID [PATH] | drop ID [PATH]";
+
+async fn cmd_snapshot(words: &[String], ctx: &ExecContext) -> ExecResult {
+    let Some((action, rest)) = words.split_first() else {
+        return ExecResult::failure(1, format!("kaish-vfs snapshot: missing action\n{SNAPSHOT_USAGE}"));
+    };
+    let fail = |msg: String| ExecResult::failure(1, format!("kaish-vfs snapshot {action}: {msg}"));
+
+    // restore and drop lead with the id; every action ends with an optional path.
+    let takes_id = matches!(action.as_str(), "restore" | "drop");
+    if !takes_id && !matches!(action.as_str(), "create" | "list") {
+        return ExecResult::failure(1, format!("kaish-vfs snapshot: unknown action '{action}'\n{SNAPSHOT_USAGE}"));
+    }
+    let (id, rest) = match (takes_id, rest.split_first()) {
+        (false, _) => (0, rest),
+        (true, Some((id, rest))) => match id.parse::<u64>() {
+            Ok(id) => (id, rest),
+            Err(_) => return fail(format!("invalid snapshot id '{id}'")),
+        },
+        (true, None) => return fail(format!("missing snapshot id\n{SNAPSHOT_USAGE}")),
+    };
+    let path = match rest {
+        [] => ctx.cwd.clone(),
+        [path] => ctx.resolve_path(path),
+        _ => return fail(format!("too many arguments\n{SNAPSHOT_USAGE}")),
+    };
+
+    let outcome = match action.as_str() {
+        "create" => ctx.backend.snapshot(&path).await.map(|id| ExecResult::success(id.to_string())),
+        "list" => ctx.backend.snapshots(&path).await.map(|snapshots| snapshot_table(&snapshots)),
+        "restore" => ctx.backend.restore_snapshot(&path, id).await
+            .map(|()| ExecResult::success(format!("restored snapshot {id}"))),
+        _ => ctx.backend.drop_snapshot(&path, id).await
+            .map(|()| ExecResult::success(format!("dropped snapshot {id}"))),
+    };
+    outcome.unwrap_or_else(|e| fail(e.to_string()))
+}
+
+fn snapshot_table(snapshots: &[SnapshotInfo]) -> ExecResult {
+    let headers = vec!["ID".to_string(), "PATH".to_string(), "FILES".to_string(), "BYTES".to_string()];
+    let nodes = snapshots
+        .iter()
+        .map(|s| {
+            OutputNode::new(s.id.to_string()).with_cells(vec![
+                s.path.to_string_lossy().into_owned(),
+                s.files.to_string(),
+                format_bytes(s.bytes),
+            ])
+        })
+        .collect();
+    let rich = serde_json::Value::Array(
+        snapshots
+            .iter()
+            .map(|s| {
+                serde_json::json!({
+                    "id": s.id,
+                    "path": s.path.to_string_lossy(),
+                    "files": s.files,
+                    "bytes": s.bytes,
+                })
+            })
+            .collect(),
+    );
+    ExecResult::with_output(OutputData::table(headers, nodes).with_rich_json(rich))
+}
+
 // ── Helpers ──────────────────────────────────────────────────────────────────
 
 /// Format a byte count in a human-readable form.
@@ -578,6 +665,46 @@ mod tests {
         assert!(result.err.contains("unknown subcommand"));
     }
 
+    #[tokio::test]
+    async fn test_snapshot_create_restore_drop() {
+        use crate::vfs::Filesystem;
+        use std::path::Path;
+
+        let scratch = MemoryFs::new();
+        scratch.write(Path::new("plan.txt"), b"v1").await.unwrap();
+        let mut vfs = VfsRouter::new();
+        vfs.mount("/", MemoryFs::new());
+        vfs.mount("/scratch", scratch);
+        let mut ctx = ExecContext::new(Arc::new(vfs));
+        ctx.cwd = "/scratch".into();
+        let run = |words: &str| {
+            let mut args = ToolArgs::new();
+            for word in words.split(' ') {
+                args.positional.push(Value::String(word.to_string()));
+            }
+            args
+        };
+
+        let result = KaishVfs.execute(run("snapshot create"), &mut ctx).await;
+        assert!(result.ok(), "{}", result.err);
+        let id = result.text_out().trim().to_string();
+
+        ctx.backend.write(Path::new("/scratch/plan.txt"), b"v2", crate::backend::WriteMode::Overwrite).await.unwrap();
+        let result = KaishVfs.execute(run("snapshot list /scratch"), &mut ctx).await;
+        assert!(result.text_out().lines().any(|l| l == format!("{id}\t/scratch\t1\t2 B")), "{}", result.text_out());
+
+        let result = KaishVfs.execute(run(&format!("snapshot restore {id}")), &mut ctx).await;
+        assert!(result.ok(), "{}", result.err);
+        assert_eq!(ctx.backend.read(Path::new("/scratch/plan.txt"), None).await.unwrap(), b"v1");
+
+        let result = KaishVfs.execute(run(&format!("snapshot drop {id} /scratch")), &mut ctx).await;
+        assert!(result.ok(), "{}", result.err);
+        let result = KaishVfs.execute(run(&format!("snapshot restore {id}")), &mut ctx).await;
+        assert!(result.err.contains(&format!("no snapshot {id}")), "{}", result.err);
+        let result = KaishVfs.execute(run("snapshot restore x"), &mut ctx).await;
+        assert!(result.err.contains("invalid snapshot id 'x'"), "{}", result.err);
+    }
+
     #[test]
     fn test_format_bytes() {
         assert_eq!(format_bytes(0), "0 B");
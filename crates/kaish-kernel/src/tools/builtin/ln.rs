@@ -9,7 +9,7 @@ use std::path::Path;
 
 use crate::interpreter::ExecResult;
 use crate::tools::builtin::get_path_string;
-use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolAnnotations, ToolSchema};
 
 /// Ln tool: create symbolic links.
 pub struct Ln;
@@ -49,6 +49,7 @@ impl Tool for Ln {
                 ("Create with force", "ln -sf target.txt link.txt"),
             ],
         )
+        .with_annotations(ToolAnnotations::destructive())
     }
 
     async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
@@ -0,0 +1,180 @@
+//! log — Leave a structured breadcrumb.
+//!
+//! `log [--level LEVEL] MESSAGE... [KEY=VALUE...]` emits a `tracing` event
+//! (target `kaish::log`) and appends a record to the session's log buffer,
+//! readable at `/v/logs/kaish.log` and `/v/logs/kaish.jsonl`. Words shaped
+//! like `key=value` become fields; the rest form the message. `level=warn`
+//! is accepted as a field-style spelling of `--level warn`.
+//!
+//! ```text
+//! log "deploy started" env=prod build=$n
+//! log --level warn "disk nearly full" mount=/data
+//! grep -c ERROR /v/logs/kaish.log
+//! ```
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+
+use crate::interpreter::ExecResult;
+use crate::script_log::{LogLevel, LogRecord};
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+
+/// Log tool: structured records to tracing and `/v/logs`.
+pub struct Log;
+
+/// clap-derived argv layer for log.
+#[derive(Parser, Debug)]
+#[command(name = "log", about = "Record a structured log message")]
+struct LogArgs {
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// Severity: trace, debug, info, warn, or error.
+    #[arg(short = 'l', long = "level", default_value = "info")]
+    level: String,
+
+    /// Message words and `KEY=VALUE` fields.
+    words: Vec<String>,
+}
+
+#[async_trait]
+impl Tool for Log {
+    fn name(&self) -> &str {
+        "log"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &LogArgs::command(),
+            "log",
+            "Record a structured log message",
+            [
+                ("Log with fields", "log 'deploy started' env=prod build=42"),
+                ("Log a warning", "log --level warn 'disk nearly full' mount=/data"),
+                ("Read the session's log", "cat /v/logs/kaish.log"),
+                ("Query it as JSON", "fromjsonl < /v/logs/kaish.jsonl"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("log: {e}")),
+        };
+        let parsed = match LogArgs::try_parse_from(std::iter::once("log".to_string()).chain(argv)) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("log: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let (message, mut fields) = split_fields(&parsed.words);
+        let level_name = fields.remove("level").unwrap_or(parsed.level);
+        let Some(level) = LogLevel::from_name(&level_name) else {
+            return ExecResult::failure(
+                2,
+                format!("log: unknown level '{level_name}' (expected trace, debug, info, warn, or error)"),
+            );
+        };
+        if message.is_empty() {
+            return ExecResult::failure(2, "log: specify a message");
+        }
+
+        let record = LogRecord::now(level, message, fields);
+        emit(&record);
+        ctx.logs.push(record);
+        ExecResult::success("")
+    }
+}
+
+/// Split words into the message and the `key=value` fields. A key is an
+/// identifier (letters, digits, `_`, `.`, `-`, not starting with a digit or
+/// punctuation); anything else is part of the message.
+fn split_fields(words: &[String]) -> (String, BTreeMap<String, String>) {
+    let mut message = Vec::new();
+    let mut fields = BTreeMap::new();
+    for word in words {
+        match word.split_once('=') {
+            Some((key, value)) if is_field_key(key) => {
+                fields.insert(key.to_string(), value.to_string());
+            }
+            _ => message.push(word.as_str()),
+        }
+    }
+    (message.join(" "), fields)
+}
+
+fn is_field_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+/// Emit `record` as a `tracing` event. Levels are fixed per callsite, so
+/// each gets its own.
+fn emit(record: &LogRecord) {
+    let fields = record.fields_text();
+    let message = &record.message;
+    match record.level {
+        LogLevel::Trace => tracing::trace!(target: "kaish::log", fields = %fields, "{message}"),
+        LogLevel::Debug => tracing::debug!(target: "kaish::log", fields = %fields, "{message}"),
+        LogLevel::Info => tracing::info!(target: "kaish::log", fields = %fields, "{message}"),
+        LogLevel::Warn => tracing::warn!(target: "kaish::log", fields = %fields, "{message}"),
+        LogLevel::Error => tracing::error!(target: "kaish::log", fields = %fields, "{message}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Value;
+    use crate::vfs::{MemoryFs, VfsRouter};
+    use std::sync::Arc;
+
+    fn argv(words: &[&str]) -> ToolArgs {
+        let mut args = ToolArgs::new();
+        for word in words {
+            args.positional.push(Value::String((*word).into()));
+        }
+        args
+    }
+
+    #[test]
+    fn fields_split_from_the_message() {
+        let words: Vec<String> = ["deploy", "env=prod", "started", "a b=c", "2x=y", "build.id=7"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        let (message, fields) = split_fields(&words);
+        assert_eq!(message, "deploy started a b=c 2x=y");
+        assert_eq!(fields.get("env").map(String::as_str), Some("prod"));
+        assert_eq!(fields.get("build.id").map(String::as_str), Some("7"));
+        assert_eq!(fields.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn records_land_in_the_buffer() {
+        let mut vfs = VfsRouter::new();
+        vfs.mount("/", MemoryFs::new());
+        let mut ctx = ExecContext::new(Arc::new(vfs));
+
+        let result = Log.execute(argv(&["level=warn", "disk low", "mount=/data"]), &mut ctx).await;
+        assert!(result.ok(), "{}", result.err);
+        let records = ctx.logs.records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].level, LogLevel::Warn);
+        assert_eq!(records[0].message, "disk low");
+        assert_eq!(records[0].fields_text(), "mount=/data");
+
+        let result = Log.execute(argv(&["level=loud", "x"]), &mut ctx).await;
+        assert!(result.err.contains("unknown level 'loud'"), "{}", result.err);
+        let result = Log.execute(argv(&["env=prod"]), &mut ctx).await;
+        assert!(result.err.contains("specify a message"), "{}", result.err);
+        assert_eq!(ctx.logs.len(), 1);
+    }
+}
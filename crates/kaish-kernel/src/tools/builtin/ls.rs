@@ -1,15 +1,23 @@
 //! ls — List directory contents.
+//!
+//! `--json` renders every listed entry as `{name, kind, size, mtime, mount}`:
+//! `kind` is `file`, `directory`, or `symlink`, `mtime` is Unix seconds
+//! (`null` where the filesystem keeps none), and `mount` is the mount point
+//! serving the entry. A recursive listing is flattened, each `name` relative
+//! to the listed directory (`src/main.rs`).
 
 use async_trait::async_trait;
 use clap::{CommandFactory, Parser};
 use std::cmp::Ordering;
 use std::path::Path;
+use std::time::UNIX_EPOCH;
 
+use super::df::mount_for;
 use crate::ast::Value;
 use crate::glob::contains_glob;
 use crate::interpreter::{EntryType, ExecResult, OutputData, OutputNode};
-use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
-use crate::vfs::DirEntry;
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolAnnotations, ToolSchema};
+use crate::vfs::{DirEntry, MountInfo};
 
 /// Ls tool: list directory contents.
 pub struct Ls;
@@ -49,6 +57,10 @@ struct LsArgs {
     #[arg(short = 'S', long = "sort-size", visible_alias = "sort_size")]
     sort_size: bool,
 
+    /// Sort by `name`, `size` (largest first), or `time` (newest first).
+    #[arg(long = "sort", value_name = "WORD")]
+    sort: Option<String>,
+
     /// List subdirectories recursively.
     #[arg(short = 'R', long = "recursive")]
     recursive: bool,
@@ -75,10 +87,13 @@ impl Tool for Ls {
                 ("List current directory", "ls"),
                 ("Show hidden files with details", "ls -la /path"),
                 ("Sort by size, largest first", "ls -lS /path"),
+                ("Oldest first", "ls --sort time -r /path"),
                 ("Human-readable sizes", "ls -lh /path"),
                 ("Recursive listing", "ls -R src/"),
+                ("Entries with kind, size, mtime, and mount", "ls --json /path"),
             ],
         )
+        .with_annotations(ToolAnnotations::read_only())
     }
 
     async fn execute(&self, mut args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
@@ -114,8 +129,18 @@ impl Tool for Ls {
         let long_format = parsed.long;
         let show_all = parsed.all;
         let human_readable = parsed.human;
-        let sort_time = parsed.sort_time;
-        let sort_size = parsed.sort_size;
+        let (sort_time, sort_size) = match parsed.sort.as_deref() {
+            None => (parsed.sort_time, parsed.sort_size),
+            Some("name") => (false, false),
+            Some("size") => (false, true),
+            Some("time" | "mtime") => (true, false),
+            Some(other) => {
+                return ExecResult::failure(
+                    2,
+                    format!("ls: invalid --sort '{other}' (expected name, size, or time)"),
+                );
+            }
+        };
         let reverse = parsed.reverse;
         let recursive = parsed.recursive;
         // `one_per_line` is parsed so that `-1` is recognized as a flag (not an
@@ -194,7 +219,7 @@ impl Ls {
     /// Real `ls file.txt` just outputs the filename.
     fn list_file(
         &self,
-        _ctx: &mut ExecContext,
+        ctx: &mut ExecContext,
         path: &str,
         info: &DirEntry,
         opts: &ListOptions,
@@ -222,8 +247,10 @@ impl Ls {
         } else {
             OutputData::nodes(vec![node])
         };
+        let mounts = ctx.backend.mounts();
+        let json = entry_json(path, info, &ctx.resolve_path(path), &mounts);
 
-        ExecResult::with_output(output)
+        ExecResult::with_output(output.with_rich_json(serde_json::Value::Array(vec![json])))
     }
 
     /// List files matching a glob pattern.
@@ -361,8 +388,13 @@ impl Ls {
         } else {
             OutputData::nodes(nodes)
         };
+        let mounts = ctx.backend.mounts();
+        let json = infos
+            .iter()
+            .map(|e| entry_json(&e.name, e, &ctx.resolve_path(&e.name), &mounts))
+            .collect();
 
-        let mut result = ExecResult::with_output(output);
+        let mut result = ExecResult::with_output(output.with_rich_json(serde_json::Value::Array(json)));
         // An inaccessible explicit operand is a loud error: stderr message plus
         // a nonzero exit (matching the single-arg `list_single` path's exit 1),
         // even though the readable operands still list successfully.
@@ -438,8 +470,13 @@ impl Ls {
                 } else {
                     OutputData::nodes(nodes)
                 };
+                let mounts = ctx.backend.mounts();
+                let json = filtered
+                    .iter()
+                    .map(|e| entry_json(&e.name, e, &resolved.join(&e.name), &mounts))
+                    .collect();
 
-                ExecResult::with_output(output)
+                ExecResult::with_output(output.with_rich_json(serde_json::Value::Array(json)))
             }
             Err(e) => ExecResult::failure(1, format!("ls: {}: {}", path, e)),
         }
@@ -453,6 +490,8 @@ impl Ls {
     ) -> ExecResult {
         let mut text_output = String::new();
         let mut dir_nodes: Vec<OutputNode> = Vec::new();
+        let mut json: Vec<serde_json::Value> = Vec::new();
+        let mounts = ctx.backend.mounts();
         let mut dirs_to_visit: Vec<(String, String)> = vec![(
             root.to_string_lossy().to_string(),
             ".".to_string(),
@@ -502,6 +541,16 @@ impl Ls {
                 })
                 .collect();
 
+            for e in &filtered {
+                let name = if display_path == "." {
+                    e.name.clone()
+                } else {
+                    format!("{}/{}", display_path, e.name)
+                };
+                let abs = Path::new(&dir_path).join(&e.name);
+                json.push(entry_json(&name, e, &abs, &mounts));
+            }
+
             // Build OutputNodes for this directory's entries
             let child_nodes: Vec<OutputNode> = filtered.iter().map(|e| {
                 let entry_type = dir_entry_to_type(e);
@@ -548,6 +597,7 @@ impl Ls {
         } else {
             OutputData::nodes(dir_nodes)
         };
+        let output = output.with_rich_json(serde_json::Value::Array(json));
         ExecResult::with_output_and_text(output, text_output.trim_end().to_string())
     }
 }
@@ -590,6 +640,28 @@ fn dir_entry_to_type(entry: &DirEntry) -> EntryType {
     }
 }
 
+/// One listed entry as `--json` renders it. `abs` is the entry's full path,
+/// for finding the mount that serves it.
+fn entry_json(name: &str, entry: &DirEntry, abs: &Path, mounts: &[MountInfo]) -> serde_json::Value {
+    let kind = if entry.is_symlink() {
+        "symlink"
+    } else if entry.is_dir() {
+        "directory"
+    } else {
+        "file"
+    };
+    serde_json::json!({
+        "name": name,
+        "kind": kind,
+        "size": entry.size,
+        "mtime": entry
+            .modified
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs()),
+        "mount": mount_for(mounts, abs).map(|m| m.path.to_string_lossy()),
+    })
+}
+
 /// Format entries for output (used by recursive listing).
 fn format_entries(entries: &[DirEntry], long_format: bool, human_readable: bool) -> Vec<String> {
     if long_format {
@@ -648,8 +720,8 @@ fn compare_by_size(a: &DirEntry, b: &DirEntry) -> Ordering {
     b.size.cmp(&a.size) // Largest first
 }
 
-/// Format a size in human-readable form (1K, 2M, etc.).
-fn format_human_size(bytes: u64) -> String {
+/// Format a size in human-readable form (1K, 2M, etc.). Shared with `df`.
+pub(super) fn format_human_size(bytes: u64) -> String {
     const UNITS: &[&str] = &["", "K", "M", "G", "T", "P"];
     let mut size = bytes as f64;
     let mut unit_idx = 0;
@@ -825,6 +897,45 @@ mod tests {
         assert!(result.text_out().contains("lib"));
     }
 
+    #[tokio::test]
+    async fn test_ls_json_recursive_entries() {
+        use crate::interpreter::{apply_output_format, OutputFormat};
+
+        let mut ctx = make_ctx_with_subdirs().await;
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("/".into()));
+        args.flags.insert("R".to_string());
+        let result = apply_output_format(Ls.execute(args, &mut ctx).await, OutputFormat::Json);
+        let entries: serde_json::Value = serde_json::from_str(&result.text_out()).expect("valid JSON");
+        let entries = entries.as_array().expect("array");
+
+        let names: Vec<&str> = entries.iter().map(|e| e["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["README.md", "src", "src/lib", "src/main.rs", "src/lib/utils.rs"]);
+        let main = &entries[3];
+        assert_eq!(main["kind"], "file");
+        assert_eq!(main["size"], 4);
+        assert!(main["mtime"].is_u64(), "{main}");
+        assert_eq!(main["mount"], "/");
+        assert_eq!(entries[1]["kind"], "directory");
+    }
+
+    #[tokio::test]
+    async fn test_ls_sort_word() {
+        let mut ctx = make_ctx_with_subdirs().await;
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("/src".into()));
+        args.named.insert("sort".to_string(), Value::String("size".into()));
+        let result = Ls.execute(args, &mut ctx).await;
+        assert!(result.ok(), "{}", result.err);
+        assert_eq!(result.text_out().lines().next(), Some("main.rs"));
+
+        let mut args = ToolArgs::new();
+        args.named.insert("sort".to_string(), Value::String("color".into()));
+        let result = Ls.execute(args, &mut ctx).await;
+        assert_eq!(result.code, 2);
+        assert!(result.err.contains("invalid --sort 'color'"), "{}", result.err);
+    }
+
     #[test]
     fn test_dir_entry_to_type_directory() {
         let entry = DirEntry::directory("mydir");
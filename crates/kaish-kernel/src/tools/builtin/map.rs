@@ -0,0 +1,354 @@
+//! map — Run a command once per input item, xargs-style.
+//!
+//! Items come from upstream `.data` (a list fans out per element, typed) or,
+//! failing that, from stdin text one non-blank line each. For every item the
+//! command template is dispatched with `{}` replaced: an argument that is
+//! exactly `{}` receives the item as a typed value, one that merely contains
+//! it gets the item's text spliced in. A template without `{}` gets the item
+//! appended as its last argument, like `xargs -n1`.
+//!
+//! Each run goes through a forked dispatcher (the full resolution chain —
+//! builtins, user tools, `.kai` scripts, externals), attached to the parent's
+//! cancellation, at most `-j N` at a time. Output is the runs' stdout in
+//! item order; `.data` is a list of `{item, code, out, err, stderr}` records. Exit is
+//! 0 when every run succeeded, 123 when any failed (xargs' convention, and
+//! scatter/gather's).
+//!
+//! The command is written inline (`map stat '{}'`), as one quoted string split
+//! on whitespace like an alias (`map 'stat {}'`), or in the key=value form
+//! (`map tool=stat arg='{}'`). map's own options must come before the command;
+//! everything from the command word on belongs to it, flags included.
+//!
+//! A single command per item, not a pipeline — that's what
+//! `scatter | … | gather` is for.
+//!
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tracing::Instrument;
+
+use crate::ast::{Arg, Command, Expr, Value};
+use crate::interpreter::{value_to_string, ExecResult};
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+
+/// Map tool: run a command per input item.
+pub struct Map;
+
+/// clap-derived argv layer for map.
+///
+/// The command tokens may themselves look like flags (`map wc -l '{}'`), so the
+/// schema asks for raw argv and map splits its own leading options off by hand
+/// (see [`split_options`]); clap only ever sees that prefix.
+#[derive(Parser, Debug)]
+#[command(name = "map", about = "Run a command once per input item")]
+struct MapArgs {
+    /// Run up to N items at once (default 1).
+    #[arg(short = 'j', long = "jobs")]
+    jobs: Option<usize>,
+
+    /// Placeholder replaced by each item (default `{}`) (-I).
+    #[arg(short = 'I', long = "replace")]
+    replace: Option<String>,
+
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// The command and its arguments.
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    command: Vec<String>,
+}
+
+#[async_trait]
+impl Tool for Map {
+    fn name(&self) -> &str {
+        "map"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &MapArgs::command(),
+            "map",
+            "Run a command once per input item ({} is the item); results in .data",
+            [
+                ("Per-file command", "glob '*.rs' | map wc -l '{}'"),
+                ("Four at a time", "cat hosts.txt | map -j 4 ping -c 1 '{}'"),
+                ("Item appended when there's no {}", "seq 1 3 | map echo item"),
+                ("Typed items from data", "jq '.repos' cfg.json | map git clone '{}'"),
+                ("Key=value form", "glob '*.md' | map tool=stat arg='{}'"),
+            ],
+        )
+        .with_raw_argv()
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let (options, command) = split_options(&args.positional);
+        let parsed = match MapArgs::try_parse_from(
+            std::iter::once("map".to_string()).chain(options.iter().map(value_to_string)),
+        ) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("map: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let jobs = parsed.jobs.unwrap_or(1);
+        if jobs == 0 {
+            return ExecResult::failure(2, "map: --jobs must be at least 1");
+        }
+        let placeholder = parsed.replace.unwrap_or_else(|| "{}".to_string());
+
+        let (name, template) = match command_words(command) {
+            Ok(words) => words,
+            Err(e) => return ExecResult::failure(1, format!("map: {e}")),
+        };
+        let Some(dispatcher) = ctx.dispatcher.clone() else {
+            return ExecResult::failure(1, "map: no dispatcher available (Kernel must be created via into_arc())");
+        };
+
+        let items = match ctx.resolve_stdin().await {
+            Ok((data, text)) => match items_from(data, &text) {
+                Ok(items) => items,
+                Err(e) => return ExecResult::failure(1, format!("map: {e}")),
+            },
+            Err(e) => return ExecResult::failure(2, format!("map: {e}")),
+        };
+
+        let semaphore = Arc::new(Semaphore::new(jobs));
+        let mut handles = Vec::with_capacity(items.len());
+        for (index, item) in items.iter().enumerate() {
+            let Ok(permit) = semaphore.clone().acquire_owned().await else {
+                break;
+            };
+            if ctx.cancel.is_cancelled() {
+                break;
+            }
+            let cmd = Command {
+                name: name.clone(),
+                args: substitute(&template, item, &placeholder),
+                redirects: vec![],
+                span: None,
+            };
+            let worker_dispatcher = dispatcher.fork_attached().await;
+            let mut worker_ctx = ctx.child_for_pipeline();
+            worker_ctx.cancel = ctx.cancel.child_token();
+            let span = tracing::debug_span!("map_item", index);
+            handles.push(tokio::spawn(crate::telemetry::bind_current_context(
+                async move {
+                    let _permit = permit;
+                    let mut result = match worker_dispatcher.dispatch(&cmd, &mut worker_ctx).await {
+                        Ok(result) => result,
+                        Err(e) => ExecResult::failure(1, format!("map: {}: {}", cmd.name, e)),
+                    };
+                    // Same per-worker spill boundary as scatter: N concurrent
+                    // runs must not hold N full outputs in memory.
+                    if worker_ctx.output_limit.is_enabled() {
+                        let _ = crate::output_limit::spill_if_needed(&mut result, &worker_ctx.output_limit).await;
+                    }
+                    result
+                }
+                .instrument(span),
+            )));
+        }
+
+        let mut out = String::new();
+        let mut records = Vec::with_capacity(handles.len());
+        let mut failed = false;
+        for (item, handle) in items.into_iter().zip(handles) {
+            let result = match handle.await {
+                Ok(result) => result,
+                Err(e) => ExecResult::failure(1, format!("map: worker panicked: {e}")),
+            };
+            failed |= !result.ok();
+            let text = result.text_out().into_owned();
+            out.push_str(&text);
+            records.push(serde_json::json!({
+                "item": kaish_types::value_to_json(&item),
+                "code": result.code,
+                "out": text,
+                "err": result.err,
+                "stderr": result.stderr,
+            }));
+        }
+        if ctx.cancel.is_cancelled() {
+            return ExecResult::interrupted("map: cancelled");
+        }
+
+        let mut result = ExecResult::success_with_data(out, Value::Json(serde_json::Value::Array(records)));
+        if failed {
+            result.code = 123;
+        }
+        result
+    }
+}
+
+/// Value-taking options, whose next token is their value rather than the
+/// command word.
+const VALUE_OPTIONS: &[&str] = &["-j", "--jobs", "-I", "--replace"];
+
+/// Split raw argv into map's leading options and the command. The command
+/// starts at the first token that isn't an option (or right after `--`).
+fn split_options(argv: &[Value]) -> (&[Value], &[Value]) {
+    let mut i = 0;
+    while let Some(Value::String(token)) = argv.get(i) {
+        if token == "--" {
+            return (&argv[..i], &argv[i + 1..]);
+        }
+        if !token.starts_with('-') || token == "-" {
+            break;
+        }
+        i += if VALUE_OPTIONS.contains(&token.as_str()) { 2 } else { 1 };
+    }
+    let i = i.min(argv.len());
+    (&argv[..i], &argv[i..])
+}
+
+/// The command name and argument template, from any of the three spellings:
+/// inline words, one whitespace-separated string, or `tool=NAME arg=VALUE...`.
+fn command_words(command: &[Value]) -> Result<(String, Vec<Value>), String> {
+    let usage = "usage: map [-j N] [-I PLACEHOLDER] COMMAND [ARGS...]";
+    let Some((first, rest)) = command.split_first() else {
+        return Err(usage.to_string());
+    };
+    let Value::String(first) = first else {
+        return Err(format!("invalid command: {:?}", first));
+    };
+    if let Some(name) = first.strip_prefix("tool=") {
+        let template = rest
+            .iter()
+            .map(|arg| match arg {
+                Value::String(s) => match s.strip_prefix("arg=") {
+                    Some(value) => Value::String(value.to_string()),
+                    None => arg.clone(),
+                },
+                other => other.clone(),
+            })
+            .collect();
+        return Ok((name.to_string(), template));
+    }
+    if rest.is_empty() && first.contains(char::is_whitespace) {
+        let mut words = first.split_whitespace().map(|w| Value::String(w.to_string()));
+        let Some(Value::String(name)) = words.next() else {
+            return Err(usage.to_string());
+        };
+        return Ok((name, words.collect()));
+    }
+    Ok((first.clone(), rest.to_vec()))
+}
+
+/// The items to run over: a list's elements, a scalar as one item, or the
+/// non-blank lines of stdin text.
+fn items_from(data: Option<Value>, text: &str) -> Result<Vec<Value>, String> {
+    match data {
+        Some(Value::Json(serde_json::Value::Array(elements))) => Ok(elements
+            .into_iter()
+            .map(kaish_types::json_to_value_no_envelope)
+            .collect()),
+        Some(Value::Json(serde_json::Value::Object(_))) => {
+            Err("input is a single record, not a list — select the list to map over".to_string())
+        }
+        Some(Value::Null) => Err("input is null — nothing to map over".to_string()),
+        Some(Value::Bytes(_)) => Err("input is binary, not items".to_string()),
+        Some(scalar) => Ok(vec![scalar]),
+        None => Ok(text
+            .lines()
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Value::String(line.to_string()))
+            .collect()),
+    }
+}
+
+/// Build one run's arguments from the template.
+fn substitute(template: &[Value], item: &Value, placeholder: &str) -> Vec<Arg> {
+    let literal = |v: Value| Arg::Positional(Expr::Literal(v));
+    let mut used = false;
+    let mut args: Vec<Arg> = template
+        .iter()
+        .map(|arg| match arg {
+            Value::String(s) if s == placeholder => {
+                used = true;
+                literal(item.clone())
+            }
+            Value::String(s) if s.contains(placeholder) => {
+                used = true;
+                literal(Value::String(s.replace(placeholder, &value_to_string(item))))
+            }
+            // The command's own words classify like `execute_argv`'s, so
+            // `map echo -n '{}'` hands echo a flag rather than the text "-n".
+            other => crate::kernel::classify_argv_token(other),
+        })
+        .collect();
+    if !used {
+        args.push(literal(item.clone()));
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(args: &[Arg]) -> Vec<String> {
+        args.iter()
+            .map(|a| match a {
+                Arg::Positional(Expr::Literal(v)) => value_to_string(v),
+                Arg::ShortFlag(f) => format!("-{f}"),
+                other => format!("{other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn placeholder_whole_and_spliced() {
+        let template = vec![Value::String("-n".into()), Value::String("{}".into()), Value::String("x{}.txt".into())];
+        let args = substitute(&template, &Value::Int(3), "{}");
+        assert_eq!(args[0], Arg::ShortFlag("n".into()));
+        assert_eq!(texts(&args), vec!["-n", "3", "x3.txt"]);
+    }
+
+    #[test]
+    fn item_appended_without_placeholder() {
+        let template = vec![Value::String("item".into())];
+        assert_eq!(texts(&substitute(&template, &Value::String("a".into()), "{}")), vec!["item", "a"]);
+    }
+
+    fn strings(tokens: &[&str]) -> Vec<Value> {
+        tokens.iter().map(|t| Value::String(t.to_string())).collect()
+    }
+
+    #[test]
+    fn options_stop_at_the_command_word() {
+        let argv = strings(&["-j", "4", "wc", "-l", "{}"]);
+        let (options, command) = split_options(&argv);
+        assert_eq!(options, &argv[..2]);
+        assert_eq!(command, &argv[2..]);
+
+        let argv = strings(&["--", "-weird", "{}"]);
+        let (options, command) = split_options(&argv);
+        assert!(options.is_empty());
+        assert_eq!(command, &argv[1..]);
+    }
+
+    #[test]
+    fn command_spellings_agree() {
+        let expected = ("stat".to_string(), strings(&["{}"]));
+        assert_eq!(command_words(&strings(&["stat", "{}"])).unwrap(), expected);
+        assert_eq!(command_words(&strings(&["stat {}"])).unwrap(), expected);
+        assert_eq!(command_words(&strings(&["tool=stat", "arg={}"])).unwrap(), expected);
+        assert!(command_words(&[]).is_err());
+    }
+
+    #[test]
+    fn items_from_lines_and_lists() {
+        let lines = items_from(None, "a\n\n b \r\n").unwrap();
+        assert_eq!(lines, vec![Value::String("a".into()), Value::String(" b ".into())]);
+
+        let list = items_from(Some(Value::Json(serde_json::json!([1, "x"]))), "").unwrap();
+        assert_eq!(list, vec![Value::Int(1), Value::String("x".into())]);
+
+        assert!(items_from(Some(Value::Json(serde_json::json!({"a": 1}))), "").is_err());
+    }
+}
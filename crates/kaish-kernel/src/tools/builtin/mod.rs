@@ -3,23 +3,31 @@
 //! These tools are always available and provide core functionality.
 
 mod alias;
+mod archive;
 mod assert;
 mod awk;
 mod base64_tool;
 mod basename;
 #[cfg(feature = "subprocess")]
 mod bg;
+mod calc;
 mod cat;
 mod cd;
 mod checksum;
 mod cmp;
+mod confirm;
 mod cp;
+mod csv;
 mod cut;
 mod date;
 mod dd;
+mod df;
 mod diff;
 mod dirname;
+mod dirstack;
+mod distill;
 mod patch;
+mod pipe;
 mod echo;
 mod env;
 #[cfg(feature = "subprocess")]
@@ -52,16 +60,23 @@ mod introspect;
 mod jobs;
 mod jq_native;
 mod kaish_ast;
+mod kaish_audit;
+mod kaish_cache;
+mod kaish_checkpoint;
 mod kaish_clear;
 mod kaish_last;
+mod kaish_metrics;
 mod kaish_trash;
+mod kaish_state;
 mod kaish_status;
 mod kaish_version;
 mod kaish_vfs;
 mod keys;
 mod kill;
 mod ln;
+mod log;
 mod ls;
+mod map;
 mod mkdir;
 mod mktemp;
 mod mv;
@@ -69,12 +84,14 @@ mod output_limit;
 mod printf;
 mod push;
 mod pwd;
+mod random;
 mod read;
 mod readlink;
 mod realpath;
 mod rm;
 mod scatter;
 mod sed;
+mod select;
 mod seq;
 mod split;
 mod set;
@@ -92,6 +109,7 @@ mod tojsonl;
 mod tokens;
 mod touch;
 mod tr;
+mod transcript;
 mod tree;
 mod true_false;
 // Module named `type_of`, not `typeof` — `typeof` is a reserved (but unused)
@@ -101,6 +119,7 @@ mod type_of;
 mod uname;
 mod uniq;
 mod unset;
+mod uuid;
 mod validate;
 mod values;
 mod vars;
@@ -110,6 +129,7 @@ mod wc;
 mod which;
 mod write;
 mod xxd;
+mod yaml_toml;
 
 use super::ToolRegistry;
 
@@ -198,24 +218,36 @@ pub(crate) fn get_path_string(
 
 /// Register all built-in tools with the registry.
 pub fn register_builtins(registry: &mut ToolRegistry) {
+    // `random` and `uuid` share one seeded sequence (`RANDOM_SEED`).
+    let random_stream = random::RandomStream::default();
     registry.register(alias::Alias);
     registry.register(alias::Unalias);
+    registry.register(archive::Archive);
     registry.register(assert::Assert);
+    registry.register(assert::Fail);
     registry.register(awk::Awk);
     registry.register(base64_tool::Base64Tool);
     registry.register(basename::Basename);
     #[cfg(feature = "subprocess")]
     registry.register(bg::Bg);
+    registry.register(calc::Calc);
     registry.register(cat::Cat);
     registry.register(cd::Cd);
     registry.register(checksum::Checksum);
     registry.register(cmp::Cmp);
+    registry.register(confirm::Confirm);
     registry.register(cp::Cp);
+    registry.register(csv::Csv);
     registry.register(cut::Cut);
     registry.register(date::Date::new());
     registry.register(dd::Dd);
+    registry.register(df::Df);
     registry.register(diff::Diff);
     registry.register(dirname::Dirname);
+    registry.register(dirstack::Dirs);
+    registry.register(dirstack::Popd);
+    registry.register(dirstack::Pushd);
+    registry.register(distill::Distill);
     registry.register(echo::Echo);
     registry.register(env::Env);
     #[cfg(feature = "subprocess")]
@@ -242,32 +274,42 @@ pub fn register_builtins(registry: &mut ToolRegistry) {
     registry.register(jobs::Jobs);
     registry.register(jq_native::JqNative);
     registry.register(kaish_ast::KaishAst);
+    registry.register(kaish_audit::KaishAudit);
+    registry.register(kaish_cache::KaishCache);
+    registry.register(kaish_checkpoint::KaishCheckpoint);
     registry.register(kaish_clear::KaishClear);
     registry.register(kaish_last::KaishLast);
+    registry.register(kaish_metrics::KaishMetrics);
     registry.register(kaish_trash::KaishTrash);
+    registry.register(kaish_state::KaishState);
     registry.register(kaish_status::KaishStatus);
     registry.register(kaish_version::KaishVersion);
     registry.register(kaish_vfs::KaishVfs);
     registry.register(keys::Keys);
     registry.register(kill::Kill);
     registry.register(ln::Ln);
+    registry.register(log::Log);
     registry.register(ls::Ls);
+    registry.register(map::Map);
     registry.register(mkdir::Mkdir);
     registry.register(mktemp::Mktemp);
     registry.register(mv::Mv);
     registry.register(output_limit::KaishOutputLimit);
     registry.register(patch::Patch);
+    registry.register(pipe::Pipe);
     registry.register(printf::Printf);
     registry.register(push::Push);
     #[cfg(all(target_os = "linux", feature = "host"))]
     registry.register(kaish_tools_host::Ps);
     registry.register(pwd::Pwd);
+    registry.register(random::Random::new(random_stream.clone()));
     registry.register(read::Read);
     registry.register(readlink::Readlink);
     registry.register(realpath::Realpath);
     registry.register(rm::Rm);
     registry.register(scatter::Scatter);
     registry.register(sed::Sed);
+    registry.register(select::Select);
     registry.register(seq::Seq);
     registry.register(set::Set);
     registry.register(split::Split);
@@ -283,8 +325,10 @@ pub fn register_builtins(registry: &mut ToolRegistry) {
     registry.register(tokens::Tokens);
     registry.register(tojson::ToJson);
     registry.register(tojsonl::ToJsonl);
+    registry.register(yaml_toml::Toml);
     registry.register(touch::Touch);
     registry.register(tr::Tr);
+    registry.register(transcript::Transcript);
     registry.register(tree::Tree);
     registry.register(true_false::True);
     registry.register(true_false::False);
@@ -292,6 +336,7 @@ pub fn register_builtins(registry: &mut ToolRegistry) {
     registry.register(uname::Uname);
     registry.register(uniq::Uniq);
     registry.register(unset::Unset);
+    registry.register(uuid::Uuid::new(random_stream));
     registry.register(validate::Validate);
     registry.register(values::Values);
     registry.register(vars::Vars);
@@ -301,4 +346,6 @@ pub fn register_builtins(registry: &mut ToolRegistry) {
     registry.register(which::Which);
     registry.register(write::Write);
     registry.register(xxd::Xxd);
+    #[cfg(feature = "yaml")]
+    registry.register(yaml_toml::Yaml);
 }
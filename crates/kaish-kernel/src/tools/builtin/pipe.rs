@@ -0,0 +1,191 @@
+//! pipe — Named pipes under /v/pipes.
+//!
+//! Subcommands: create, list, rm.
+//!
+//! kaish has no `<(…)` process substitution; a named pipe covers the same
+//! ground. `pipe create NAME` prints `/v/pipes/NAME`, which one command
+//! writes with a redirect and another reads as a file or with `<`. The
+//! reader waits for the writer, so the producer can run in the background:
+//!
+//! ```text
+//! p=$(pipe create sorted)
+//! sort data.txt > "$p" &
+//! diff "$p" expected.txt
+//! pipe rm sorted
+//! ```
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+
+use crate::interpreter::{ExecResult, OutputData, OutputNode};
+use crate::scheduler::NamedPipes;
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+
+/// Where the kernel mounts `PipeFs`.
+const PIPES_ROOT: &str = "/v/pipes";
+
+/// Pipe tool: make, list, and remove named pipes.
+pub struct Pipe;
+
+/// clap-derived argv layer for pipe.
+#[derive(Parser, Debug)]
+#[command(name = "pipe", about = "Make, list, and remove named pipes under /v/pipes")]
+struct PipeArgs {
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// Subcommand (`create`, `list`, `rm`) and its arguments.
+    args: Vec<String>,
+}
+
+#[async_trait]
+impl Tool for Pipe {
+    fn name(&self) -> &str {
+        "pipe"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &PipeArgs::command(),
+            "pipe",
+            "Make, list, and remove named pipes under /v/pipes",
+            [
+                ("Make a pipe and keep its path", "p=$(pipe create sorted)"),
+                ("Fill it in the background, read it in the foreground", r#"sort data.txt > "$p" & diff "$p" expected.txt"#),
+                ("List pipes and their unread bytes", "pipe list"),
+                ("Remove a pipe", "pipe rm sorted"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("pipe: {e}")),
+        };
+        let parsed = match PipeArgs::try_parse_from(std::iter::once("pipe".to_string()).chain(argv)) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("pipe: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let Some(manager) = ctx.job_manager.clone() else {
+            return ExecResult::failure(1, "pipe: no job manager to hold pipes");
+        };
+        let pipes = manager.pipes();
+
+        let (subcmd, rest) = match parsed.args.split_first() {
+            Some((subcmd, rest)) => (subcmd.as_str(), rest),
+            None => ("list", &[][..]),
+        };
+        match subcmd {
+            "create" => cmd_create(rest, pipes),
+            "list" => cmd_list(pipes),
+            "rm" => cmd_rm(rest, pipes),
+            other => ExecResult::failure(1, format!("pipe: unknown subcommand: {other}")),
+        }
+    }
+}
+
+fn pipe_path(name: &str) -> String {
+    format!("{PIPES_ROOT}/{name}")
+}
+
+fn cmd_create(names: &[String], pipes: &NamedPipes) -> ExecResult {
+    if names.is_empty() {
+        return ExecResult::failure(1, "pipe create: specify a pipe name");
+    }
+    let mut paths = Vec::with_capacity(names.len());
+    for name in names {
+        if let Err(e) = pipes.create(name) {
+            return ExecResult::failure(1, format!("pipe create: {e}"));
+        }
+        paths.push(pipe_path(name));
+    }
+    ExecResult::with_output(OutputData::text(paths.join("\n")))
+}
+
+fn cmd_list(pipes: &NamedPipes) -> ExecResult {
+    let infos = pipes.list();
+    let rich = serde_json::Value::Array(
+        infos
+            .iter()
+            .map(|p| serde_json::json!({ "name": p.name, "path": pipe_path(&p.name), "buffered": p.buffered }))
+            .collect(),
+    );
+    let nodes = infos
+        .iter()
+        .map(|p| OutputNode::new(&p.name).with_cells(vec![p.buffered.to_string()]))
+        .collect();
+    let output = OutputData::table(vec!["NAME".to_string(), "BUFFERED".to_string()], nodes);
+    ExecResult::with_output(output.with_rich_json(rich))
+}
+
+fn cmd_rm(names: &[String], pipes: &NamedPipes) -> ExecResult {
+    if names.is_empty() {
+        return ExecResult::failure(1, "pipe rm: specify a pipe name");
+    }
+    for name in names {
+        if let Err(e) = pipes.remove(name) {
+            return ExecResult::failure(1, format!("pipe rm: {e}"));
+        }
+    }
+    ExecResult::success("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Value;
+    use crate::scheduler::JobManager;
+    use crate::vfs::{MemoryFs, VfsRouter};
+    use std::sync::Arc;
+
+    fn make_ctx() -> ExecContext {
+        let mut vfs = VfsRouter::new();
+        vfs.mount("/", MemoryFs::new());
+        let mut ctx = ExecContext::new(Arc::new(vfs));
+        ctx.set_job_manager(Arc::new(JobManager::new()));
+        ctx
+    }
+
+    fn argv(words: &[&str]) -> ToolArgs {
+        let mut args = ToolArgs::new();
+        for word in words {
+            args.positional.push(Value::String((*word).into()));
+        }
+        args
+    }
+
+    #[tokio::test]
+    async fn test_create_list_rm() {
+        let mut ctx = make_ctx();
+        let result = Pipe.execute(argv(&["create", "a", "b"]), &mut ctx).await;
+        assert!(result.ok(), "{}", result.err);
+        assert_eq!(&*result.text_out(), "/v/pipes/a\n/v/pipes/b");
+
+        let result = Pipe.execute(argv(&["create", "a"]), &mut ctx).await;
+        assert_eq!(result.code, 1);
+        assert!(result.err.contains("already exists"), "{}", result.err);
+
+        let manager = ctx.job_manager.clone().unwrap();
+        manager.pipes().write("b", b"xyz").unwrap();
+        let result = Pipe.execute(argv(&["list"]), &mut ctx).await;
+        assert_eq!(&*result.text_out(), "a\t0\nb\t3");
+
+        assert!(Pipe.execute(argv(&["rm", "a", "b"]), &mut ctx).await.ok());
+        assert!(manager.pipes().list().is_empty());
+        let result = Pipe.execute(argv(&["rm", "a"]), &mut ctx).await;
+        assert!(result.err.contains("no pipe named 'a'"), "{}", result.err);
+    }
+
+    #[tokio::test]
+    async fn test_usage_errors() {
+        let mut ctx = make_ctx();
+        assert!(Pipe.execute(argv(&["create"]), &mut ctx).await.err.contains("specify a pipe name"));
+        assert!(Pipe.execute(argv(&["make", "x"]), &mut ctx).await.err.contains("unknown subcommand"));
+    }
+}
@@ -0,0 +1,506 @@
+//! random — Random integers, floats, picks, and shuffles.
+//!
+//! Test data without reaching for `$RANDOM` arithmetic or `shuf`. The first
+//! argument picks the mode:
+//!
+//! - **`int [MIN MAX]`** — an integer in `MIN..=MAX` (default `0..=100`).
+//!   Bounds can also be spelled `min=N max=N` or `--min N --max N`.
+//! - **`float [MIN MAX]`** — a float in `MIN..MAX` (default `0..1`).
+//! - **`choice ITEMS…`** — one of the arguments, or one element of a single
+//!   list argument, type preserved.
+//! - **`shuffle LIST`** — the list (or the arguments) in random order.
+//!
+//! `-n N` draws N values and returns them as a list. Results land in `.data`,
+//! so `$(random int 1 6)` is an `Int`.
+//!
+//! **Reproducible mode.** `--seed N` makes one call deterministic. Setting
+//! `RANDOM_SEED=N` makes every `random` and `uuid` call after it draw from one
+//! seeded sequence, so a test script replays the same values on every run;
+//! assigning a different seed restarts the sequence. Without either, values
+//! come from the OS CSPRNG.
+//!
+//! # Examples
+//!
+//! ```kaish
+//! random int 1 6
+//! random int min=0 max=100
+//! random choice ${ITEMS}
+//! random float -n 3 --seed 7
+//! RANDOM_SEED=42; random shuffle a b c
+//! ```
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+
+use crate::ast::Value;
+use crate::interpreter::{json_to_value, value_to_json, value_to_string, ExecResult};
+use crate::tools::{schema_from_clap, ExecContext, GlobalFlags, Tool, ToolArgs, ToolCtx, ToolSchema};
+
+/// Random tool: random integers, floats, picks, and shuffles.
+pub struct Random {
+    stream: RandomStream,
+}
+
+impl Random {
+    pub fn new(stream: RandomStream) -> Self {
+        Self { stream }
+    }
+}
+
+/// clap-derived argv layer for random.
+#[derive(Parser, Debug)]
+#[command(name = "random", about = "Random integers, floats, picks, and shuffles")]
+struct RandomArgs {
+    /// Lower bound for int/float.
+    #[arg(long = "min", allow_hyphen_values = true)]
+    min: Option<String>,
+
+    /// Upper bound for int/float (inclusive for int).
+    #[arg(long = "max", allow_hyphen_values = true)]
+    max: Option<String>,
+
+    /// Draw N values and return them as a list.
+    #[arg(short = 'n', long = "count")]
+    count: Option<usize>,
+
+    /// Seed this call for a reproducible result.
+    #[arg(long = "seed")]
+    seed: Option<u64>,
+
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// Mode (int/float/choice/shuffle) and its operands. Hidden sink — the
+    /// real values are read off `args.positional`.
+    #[arg(hide = true, allow_hyphen_values = true)]
+    args: Vec<String>,
+}
+
+#[async_trait]
+impl Tool for Random {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &RandomArgs::command(),
+            "random",
+            "Random int/float/choice/shuffle (result in .data; RANDOM_SEED=N for reproducible runs)",
+            [
+                ("Roll a die", "random int 1 6"),
+                ("Named bounds", "random int min=0 max=100"),
+                ("Pick one element of a list", "random choice ${ITEMS}"),
+                ("Three reproducible floats", "random float -n 3 --seed 7"),
+                ("Shuffle words", "random shuffle a b c"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("random: {e}")),
+        };
+        let parsed = match RandomArgs::try_parse_from(
+            std::iter::once("random".to_string()).chain(argv),
+        ) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("random: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let request = match Request::from_args(&parsed, &args.positional) {
+            Ok(r) => r,
+            Err(e) => return ExecResult::failure(2, format!("random: {e}")),
+        };
+        let drawn = self.stream.draw(parsed.seed, ctx, |rng| match parsed.count {
+            Some(n) if !matches!(request, Request::Shuffle(_)) => (0..n)
+                .map(|_| request.draw(rng).map(|v| value_to_json(&v)))
+                .collect::<Result<Vec<_>, _>>()
+                .map(|items| Value::Json(serde_json::Value::Array(items))),
+            Some(_) => Err("-n doesn't apply to shuffle".to_string()),
+            None => request.draw(rng),
+        });
+
+        match drawn {
+            Ok(value) => ExecResult::success_with_data(render_lines(&value), value),
+            Err(e) => ExecResult::failure(1, format!("random: {e}")),
+        }
+    }
+}
+
+/// One value (or a list, one element) per line.
+pub(super) fn render_lines(value: &Value) -> String {
+    match value {
+        Value::Json(serde_json::Value::Array(items)) => items
+            .iter()
+            .map(|item| format!("{}\n", value_to_string(&json_to_value(item.clone()))))
+            .collect(),
+        other => format!("{}\n", value_to_string(other)),
+    }
+}
+
+/// What a `random` call draws.
+enum Request {
+    Int(i64, i64),
+    Float(f64, f64),
+    Choice(Vec<Value>),
+    Shuffle(Vec<Value>),
+}
+
+impl Request {
+    fn from_args(parsed: &RandomArgs, positional: &[Value]) -> Result<Self, String> {
+        // No mode word (`random`, `random 1 6`, `random max=10`) means int.
+        let (mode, operands) = match positional.first() {
+            Some(Value::String(mode)) if mode.parse::<f64>().is_err() && !mode.contains('=') => {
+                (mode.as_str(), &positional[1..])
+            }
+            _ => ("int", positional),
+        };
+        match mode {
+            "int" => {
+                let (min, max) = bounds(parsed, operands, ("0", "100"))?;
+                let min = parse_int(&min)?;
+                let max = parse_int(&max)?;
+                if min > max {
+                    return Err(format!("min {min} is greater than max {max}"));
+                }
+                Ok(Request::Int(min, max))
+            }
+            "float" => {
+                let (min, max) = bounds(parsed, operands, ("0", "1"))?;
+                let min = parse_float(&min)?;
+                let max = parse_float(&max)?;
+                if min > max {
+                    return Err(format!("min {min} is greater than max {max}"));
+                }
+                Ok(Request::Float(min, max))
+            }
+            "choice" => {
+                let items = items(operands)?;
+                if items.is_empty() {
+                    return Err("choice needs at least one item".to_string());
+                }
+                Ok(Request::Choice(items))
+            }
+            "shuffle" => Ok(Request::Shuffle(items(operands)?)),
+            other => Err(format!("unknown mode '{other}' (expected int, float, choice, or shuffle)")),
+        }
+    }
+
+    fn draw(&self, rng: &mut Rng) -> Result<Value, String> {
+        match self {
+            Request::Int(min, max) => {
+                // Width of the inclusive range; up to 2^64 for the full i64 span.
+                let span = (*max as i128 - *min as i128 + 1) as u128;
+                let offset = (rng.next_u64()? as u128 * span) >> 64;
+                Ok(Value::Int((*min as i128 + offset as i128) as i64))
+            }
+            Request::Float(min, max) => Ok(Value::Float(min + rng.next_f64()? * (max - min))),
+            Request::Choice(items) => {
+                let i = rng.below(items.len())?;
+                Ok(items[i].clone())
+            }
+            Request::Shuffle(items) => {
+                let mut items: Vec<serde_json::Value> = items.iter().map(value_to_json).collect();
+                // Fisher–Yates.
+                for i in (1..items.len()).rev() {
+                    let j = rng.below(i + 1)?;
+                    items.swap(i, j);
+                }
+                Ok(Value::Json(serde_json::Value::Array(items)))
+            }
+        }
+    }
+}
+
+/// Resolve `(min, max)` from `--min/--max`, `min=`/`max=` operands, or two bare
+/// operands, falling back to `default`.
+fn bounds(parsed: &RandomArgs, operands: &[Value], default: (&str, &str)) -> Result<(String, String), String> {
+    let mut min = parsed.min.clone();
+    let mut max = parsed.max.clone();
+    let mut bare = Vec::new();
+    for operand in operands {
+        let text = value_to_string(operand);
+        if let Some(v) = text.strip_prefix("min=") {
+            min = Some(v.to_string());
+        } else if let Some(v) = text.strip_prefix("max=") {
+            max = Some(v.to_string());
+        } else {
+            bare.push(text);
+        }
+    }
+    match bare.as_slice() {
+        [] => {}
+        [lo, hi] if min.is_none() && max.is_none() => {
+            min = Some(lo.clone());
+            max = Some(hi.clone());
+        }
+        _ => return Err("bounds are MIN MAX, min=N max=N, or --min/--max".to_string()),
+    }
+    Ok((
+        min.unwrap_or_else(|| default.0.to_string()),
+        max.unwrap_or_else(|| default.1.to_string()),
+    ))
+}
+
+/// Items for choice/shuffle: the elements of a single list operand, or the
+/// operands themselves.
+fn items(operands: &[Value]) -> Result<Vec<Value>, String> {
+    match operands {
+        [Value::Json(serde_json::Value::Array(items))] => Ok(items.iter().cloned().map(json_to_value).collect()),
+        [single @ Value::Json(_)] => Err(format!("expected a list, got {}", super::keys::describe_kind(single))),
+        _ => Ok(operands.to_vec()),
+    }
+}
+
+fn parse_int(s: &str) -> Result<i64, String> {
+    s.trim().parse().map_err(|_| format!("not an integer: '{s}'"))
+}
+
+fn parse_float(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(n) if n.is_finite() => Ok(n),
+        _ => Err(format!("not a number: '{s}'")),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Generators
+// ---------------------------------------------------------------------------
+
+/// The seeded sequence behind `RANDOM_SEED`, shared by `random` and `uuid`.
+///
+/// One per kernel (created in `register_builtins`), so it survives the scope
+/// snapshot that `$(…)` restores — `a=$(random int); b=$(random int)` under a
+/// seed gets the next two values of the sequence, not the same one twice.
+#[derive(Clone, Default)]
+pub struct RandomStream {
+    inner: Arc<Mutex<Option<SeededState>>>,
+}
+
+#[derive(Clone, Copy)]
+struct SeededState {
+    seed: u64,
+    state: u64,
+}
+
+impl RandomStream {
+    /// Run `f` against the generator this call should use: a fresh one for
+    /// `--seed`, the shared sequence when `RANDOM_SEED` is set, else the OS.
+    pub(super) fn draw<T>(
+        &self,
+        flag_seed: Option<u64>,
+        ctx: &ExecContext,
+        f: impl FnOnce(&mut Rng) -> Result<T, String>,
+    ) -> Result<T, String> {
+        if let Some(seed) = flag_seed {
+            return f(&mut Rng::Seeded(SplitMix64 { state: seed }));
+        }
+        let Some(seed) = scope_seed(ctx)? else {
+            return f(&mut Rng::Os);
+        };
+        let mut guard = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let state = match *guard {
+            Some(s) if s.seed == seed => s.state,
+            _ => seed,
+        };
+        let mut rng = Rng::Seeded(SplitMix64 { state });
+        let out = f(&mut rng);
+        if let Rng::Seeded(g) = rng {
+            *guard = Some(SeededState { seed, state: g.state });
+        }
+        out
+    }
+}
+
+/// `RANDOM_SEED` from the scope, if set.
+fn scope_seed(ctx: &ExecContext) -> Result<Option<u64>, String> {
+    match ctx.scope.get("RANDOM_SEED") {
+        None => Ok(None),
+        Some(Value::Int(n)) => Ok(Some(*n as u64)),
+        Some(other) => {
+            let text = value_to_string(other);
+            if text.is_empty() {
+                return Ok(None);
+            }
+            text.trim()
+                .parse::<i64>()
+                .map(|n| Some(n as u64))
+                .map_err(|_| format!("RANDOM_SEED must be an integer, got '{text}'"))
+        }
+    }
+}
+
+/// A source of random `u64`s.
+pub(super) enum Rng {
+    Seeded(SplitMix64),
+    /// The OS CSPRNG via `getrandom`. No fallback, same as `mktemp`.
+    Os,
+}
+
+impl Rng {
+    pub(super) fn next_u64(&mut self) -> Result<u64, String> {
+        match self {
+            Rng::Seeded(g) => Ok(g.next()),
+            Rng::Os => getrandom::u64().map_err(|e| format!("no system entropy: {e}")),
+        }
+    }
+
+    /// Uniform in `[0, 1)`, 53 bits of precision.
+    fn next_f64(&mut self) -> Result<f64, String> {
+        Ok((self.next_u64()? >> 11) as f64 / (1u64 << 53) as f64)
+    }
+
+    /// Uniform in `0..n`; `n` must be non-zero.
+    fn below(&mut self, n: usize) -> Result<usize, String> {
+        Ok(((self.next_u64()? as u128 * n as u128) >> 64) as usize)
+    }
+
+    pub(super) fn fill(&mut self, bytes: &mut [u8]) -> Result<(), String> {
+        for chunk in bytes.chunks_mut(8) {
+            let word = self.next_u64()?.to_le_bytes();
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+        Ok(())
+    }
+}
+
+/// SplitMix64 — tiny, fast, and plenty for reproducible test data. Not for
+/// anything secret; unseeded calls use the OS generator.
+pub(super) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::{MemoryFs, VfsRouter};
+
+    fn make_ctx() -> ExecContext {
+        let mut vfs = VfsRouter::new();
+        vfs.mount("/", MemoryFs::new());
+        ExecContext::new(Arc::new(vfs))
+    }
+
+    fn args(parts: &[Value]) -> ToolArgs {
+        let mut args = ToolArgs::new();
+        args.positional = parts.to_vec();
+        args
+    }
+
+    fn s(text: &str) -> Value {
+        Value::String(text.into())
+    }
+
+    async fn run(tool: &Random, ctx: &mut ExecContext, parts: &[Value]) -> ExecResult {
+        tool.execute(args(parts), ctx).await
+    }
+
+    #[tokio::test]
+    async fn int_stays_in_bounds() {
+        let tool = Random::new(RandomStream::default());
+        let mut ctx = make_ctx();
+        for _ in 0..200 {
+            let r = run(&tool, &mut ctx, &[s("int"), Value::Int(1), Value::Int(6)]).await;
+            let Some(Value::Int(n)) = r.data else { panic!("expected Int, got {:?}", r.data) };
+            assert!((1..=6).contains(&n), "{n}");
+        }
+    }
+
+    #[tokio::test]
+    async fn named_bounds_and_negative_range() {
+        let tool = Random::new(RandomStream::default());
+        let mut ctx = make_ctx();
+        let r = run(&tool, &mut ctx, &[s("int"), s("min=-3"), s("max=-3")]).await;
+        assert_eq!(r.data, Some(Value::Int(-3)));
+    }
+
+    #[tokio::test]
+    async fn full_i64_range_does_not_overflow() {
+        let tool = Random::new(RandomStream::default());
+        let mut ctx = make_ctx();
+        let r = run(&tool, &mut ctx, &[s("int"), Value::Int(i64::MIN), Value::Int(i64::MAX)]).await;
+        assert!(r.ok(), "{}", r.err);
+    }
+
+    #[tokio::test]
+    async fn bad_bounds_fail() {
+        let tool = Random::new(RandomStream::default());
+        let mut ctx = make_ctx();
+        assert_eq!(run(&tool, &mut ctx, &[s("int"), Value::Int(5), Value::Int(1)]).await.code, 2);
+        assert_eq!(run(&tool, &mut ctx, &[s("int"), s("x"), s("y")]).await.code, 2);
+        assert_eq!(run(&tool, &mut ctx, &[s("dice")]).await.code, 2);
+        let r = run(&tool, &mut ctx, &[Value::Int(2), Value::Int(2)]).await;
+        assert_eq!(r.data, Some(Value::Int(2)), "bare bounds default to int");
+    }
+
+    #[tokio::test]
+    async fn choice_picks_from_a_list_with_type() {
+        let tool = Random::new(RandomStream::default());
+        let mut ctx = make_ctx();
+        let list = Value::Json(serde_json::json!([7, 7, 7]));
+        let r = run(&tool, &mut ctx, &[s("choice"), list]).await;
+        assert_eq!(r.data, Some(Value::Int(7)));
+        assert_eq!(run(&tool, &mut ctx, &[s("choice")]).await.code, 2);
+    }
+
+    #[tokio::test]
+    async fn shuffle_is_a_permutation() {
+        let tool = Random::new(RandomStream::default());
+        let mut ctx = make_ctx();
+        let r = run(&tool, &mut ctx, &[s("shuffle"), s("a"), s("b"), s("c"), s("d")]).await;
+        let Some(Value::Json(serde_json::Value::Array(mut items))) = r.data else {
+            panic!("expected a list");
+        };
+        items.sort_by_key(|v| v.to_string());
+        assert_eq!(serde_json::Value::Array(items), serde_json::json!(["a", "b", "c", "d"]));
+    }
+
+    #[tokio::test]
+    async fn seed_flag_is_deterministic() {
+        let tool = Random::new(RandomStream::default());
+        let mut ctx = make_ctx();
+        let mut parts = args(&[s("float")]);
+        parts.named.insert("seed".into(), Value::Int(7));
+        parts.named.insert("count".into(), Value::Int(3));
+        let a = tool.execute(parts.clone(), &mut ctx).await;
+        let b = tool.execute(parts, &mut ctx).await;
+        assert!(a.ok(), "{}", a.err);
+        assert_eq!(a.data, b.data);
+        assert_eq!(a.text_out().lines().count(), 3);
+    }
+
+    #[tokio::test]
+    async fn random_seed_var_replays_a_sequence() {
+        let draw = |tool: Random| async move {
+            let mut ctx = make_ctx();
+            ctx.scope.set("RANDOM_SEED", Value::Int(42));
+            let mut out = Vec::new();
+            for _ in 0..3 {
+                out.push(run(&tool, &mut ctx, &[s("int"), Value::Int(0), Value::Int(1_000_000)]).await.data);
+            }
+            out
+        };
+        let first = draw(Random::new(RandomStream::default())).await;
+        let second = draw(Random::new(RandomStream::default())).await;
+        assert_eq!(first, second, "same seed, same sequence");
+        assert_ne!(first[0], first[1], "the sequence advances between calls");
+    }
+}
@@ -6,7 +6,15 @@
 //!   read VAR                    # Read line into VAR
 //!   read -r VAR                 # Raw mode (no backslash processing)
 //!   read -p "prompt: " VAR      # Show prompt before reading
+//!   read -s -p "token: " TOKEN  # Hidden input (terminal only)
 //!   read VAR1 VAR2 VAR3         # Split line into multiple variables
+//!   read NAME prompt="Name: " secret=false   # field-style spellings
+//!
+//! The line comes from piped stdin or, in an interactive kernel, from the
+//! terminal after showing the prompt. A headless kernel with no stdin has
+//! no one to ask, so `read` fails rather than blocking. Embedders with
+//! their own way to ask (an MCP elicitation, a UI dialog) register a tool
+//! named `read` to replace this one, as with `confirm`.
 
 use async_trait::async_trait;
 use clap::{CommandFactory, Parser};
@@ -24,11 +32,15 @@ pub struct Read;
 struct ReadArgs {
     /// Raw mode — do not process backslash escapes.
     #[arg(id = "raw", short = 'r', long = "raw")]
-    _raw: bool,
+    raw: bool,
 
     /// Prompt to display before reading.
     #[arg(id = "prompt", short = 'p', long = "prompt")]
-    _prompt: Option<String>,
+    prompt: Option<String>,
+
+    /// Don't echo what is typed at the terminal (passwords, tokens).
+    #[arg(id = "secret", short = 's', long = "secret")]
+    secret: bool,
 
     #[command(flatten)]
     global: GlobalFlags,
@@ -51,6 +63,7 @@ impl Tool for Read {
             [
                 ("Read into variable", "read NAME"),
                 ("Read with prompt", "read -p 'Enter value: ' VAR"),
+                ("Read a secret", "read -s -p 'Token: ' TOKEN"),
             ],
         )
     }
@@ -71,40 +84,55 @@ impl Tool for Read {
         };
         parsed.global.apply(ctx);
 
-        let raw_mode = args.has_flag("r") || args.has_flag("raw");
-
-        // Get prompt if provided (-p "prompt")
-        let prompt = args
-            .get_string("prompt", usize::MAX)
-            .or_else(|| args.get_string("p", usize::MAX));
-
-        // Get variable names from positional arguments
-        let var_names: Vec<String> = args
-            .positional
-            .iter()
-            .filter_map(|v| match v {
-                Value::String(s) => Some(s.clone()),
-                _ => None,
-            })
-            .collect();
+        let raw_mode = parsed.raw;
+        let mut prompt = parsed.prompt;
+        let mut secret = parsed.secret;
+
+        // `prompt=TEXT` and `secret=BOOL` are field-style spellings of the
+        // flags; no variable name contains `=`, so they can't collide.
+        let mut var_names = Vec::new();
+        for word in parsed.names {
+            match word.split_once('=') {
+                Some(("prompt", text)) => prompt = Some(text.to_string()),
+                Some(("secret", value)) => match value {
+                    "true" => secret = true,
+                    "false" => secret = false,
+                    other => {
+                        return ExecResult::failure(
+                            2,
+                            format!("read: secret={other}: expected true or false"),
+                        );
+                    }
+                },
+                _ => var_names.push(word),
+            }
+        }
 
         if var_names.is_empty() {
             return ExecResult::failure(1, "read: missing variable name");
         }
 
-        // If prompt is provided, output it to stderr
-        // (In a real interactive shell this would display before reading)
         let prompt_output = prompt.as_deref().unwrap_or("");
 
-        // Get input from stdin
+        // Get input from stdin, or from the terminal in an interactive kernel.
+        let mut asked = false;
         let input = match ctx.read_stdin_to_text().await {
             Ok(Some(s)) => s,
+            Ok(None) if ctx.interactive => {
+                asked = true;
+                match ask_terminal(prompt_output, secret).await {
+                    Ok(line) => line,
+                    Err(e) => return ExecResult::failure(1, format!("read: {e}")),
+                }
+            }
             Ok(None) => {
-                // No stdin provided - return failure (no data to read)
-                // Include the prompt in the error so it's visible
-                let mut result = ExecResult::failure(1, "read: no input available");
+                // No one to ask. Include the prompt in the error so it's visible.
+                let mut result = ExecResult::failure(
+                    1,
+                    "read: no input available (no stdin, not interactive)",
+                );
                 if !prompt_output.is_empty() {
-                    result.err = format!("{}{}", prompt_output, result.err);
+                    result.push_stderr(prompt_output);
                 }
                 return result;
             }
@@ -145,15 +173,48 @@ impl Tool for Read {
             }
         }
 
-        // Include prompt in stderr output (for visibility to caller)
+        // Include prompt in stderr output (for visibility to caller), unless
+        // it was already shown on the terminal.
         let mut result = ExecResult::success("");
-        if !prompt_output.is_empty() {
-            result.err = prompt_output.to_string();
+        if !prompt_output.is_empty() && !asked {
+            result.push_stderr(prompt_output);
         }
         result
     }
 }
 
+/// Show `prompt` on the terminal and read one line, echo off if `secret`.
+async fn ask_terminal(prompt: &str, secret: bool) -> std::io::Result<String> {
+    let prompt = prompt.to_string();
+    tokio::task::spawn_blocking(move || {
+        use std::io::Write;
+        let mut stderr = std::io::stderr();
+        stderr.write_all(prompt.as_bytes())?;
+        stderr.flush()?;
+        if secret {
+            return read_hidden();
+        }
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        Ok(line)
+    })
+    .await
+    .map_err(std::io::Error::other)?
+}
+
+#[cfg(all(unix, feature = "subprocess"))]
+fn read_hidden() -> std::io::Result<String> {
+    crate::terminal::read_line_hidden()
+}
+
+#[cfg(not(all(unix, feature = "subprocess")))]
+fn read_hidden() -> std::io::Result<String> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "hidden input needs a Unix terminal",
+    ))
+}
+
 /// Process backslash escape sequences.
 fn process_escapes(s: &str) -> String {
     let mut result = String::with_capacity(s.len());
@@ -305,6 +366,42 @@ mod tests {
         assert_eq!(ctx.scope.get("NAME"), Some(&Value::String("first line".into())));
     }
 
+    #[tokio::test]
+    async fn test_read_field_style_options() {
+        let mut ctx = make_ctx();
+        ctx.set_stdin("s3cret".to_string());
+
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("TOKEN".to_string()));
+        args.positional.push(Value::String("prompt=Token: ".to_string()));
+        args.positional.push(Value::String("secret=true".to_string()));
+
+        let result = Read.execute(args, &mut ctx).await;
+        assert!(result.ok(), "{}", result.err);
+        assert_eq!(ctx.scope.get("TOKEN"), Some(&Value::String("s3cret".into())));
+        assert_eq!(result.stderr, "Token: ");
+
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("TOKEN".to_string()));
+        args.positional.push(Value::String("secret=maybe".to_string()));
+        let result = Read.execute(args, &mut ctx).await;
+        assert_eq!(result.code, 2);
+        assert!(result.err.contains("expected true or false"), "{}", result.err);
+    }
+
+    #[tokio::test]
+    async fn test_read_headless_explains_why() {
+        let mut ctx = make_ctx();
+
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("NAME".to_string()));
+        args.flags.insert("s".to_string());
+
+        let result = Read.execute(args, &mut ctx).await;
+        assert!(!result.ok());
+        assert!(result.err.contains("not interactive"), "{}", result.err);
+    }
+
     #[test]
     fn test_process_escapes() {
         assert_eq!(process_escapes("hello"), "hello");
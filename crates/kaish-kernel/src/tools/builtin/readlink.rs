@@ -8,7 +8,7 @@ use clap::{CommandFactory, Parser};
 use std::path::{Path, PathBuf};
 
 use crate::interpreter::{ExecResult, OutputData};
-use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolAnnotations, ToolSchema};
 
 /// Maximum symlink hops to prevent infinite loops (matches Linux MAXSYMLINKS).
 const MAX_SYMLINK_HOPS: usize = 40;
@@ -47,6 +47,7 @@ impl Tool for Readlink {
                 ("Canonicalize path", "readlink -f ../some/./path"),
             ],
         )
+        .with_annotations(ToolAnnotations::read_only())
     }
 
     async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
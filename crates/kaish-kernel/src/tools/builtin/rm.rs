@@ -2,14 +2,21 @@
 //!
 //! Supports confirmation latch (`set -o latch`) and trash-on-delete
 //! (`set -o trash`) for safe autonomous operation.
+//!
+//! Two rails hold regardless of those settings: a mount root — or, under
+//! `-r`, a directory with a mount beneath it — is never removed, and a
+//! recursive permanent delete of more than [`BULK_LIMIT`] entries needs `-f`.
+//! Trashed directories are recoverable, so the bulk rail doesn't apply to
+//! them. The result's data lists what happened to each operand.
 
 use async_trait::async_trait;
 use clap::{CommandFactory, Parser};
 use std::path::{Path, PathBuf};
 
+use crate::ast::Value;
 use crate::backend::BackendError;
 use crate::interpreter::ExecResult;
-use crate::tools::{is_trash_excluded, schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+use crate::tools::{is_trash_excluded, schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolAnnotations, ToolSchema};
 
 /// clap-derived argv layer for rm.
 #[derive(Parser, Debug)]
@@ -41,6 +48,9 @@ struct RmArgs {
 /// Rm tool: remove files and directories.
 pub struct Rm;
 
+/// The most entries `rm -r` deletes permanently without `-f`.
+pub const BULK_LIMIT: usize = 1000;
+
 /// What the rm decision logic chose to do.
 #[derive(Debug, PartialEq)]
 enum RmAction {
@@ -122,6 +132,7 @@ impl Tool for Rm {
                 ("Confirm latched removal", "rm --confirm=a3f7b2c1 bigfile.bin"),
             ],
         )
+        .with_annotations(ToolAnnotations::destructive())
     }
 
     async fn execute(&self, mut args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
@@ -153,6 +164,7 @@ impl Tool for Rm {
         let trash_enabled = ctx.scope.trash_enabled();
         let latch_enabled = ctx.scope.latch_enabled();
         let trash_max_size = ctx.scope.trash_max_size();
+        let mounts = ctx.backend.mounts();
 
         // Collect per-path decisions in one pass so latch can issue ONE nonce
         // that authorizes the whole batch (NonceScope.paths is a set; one
@@ -163,6 +175,7 @@ impl Tool for Rm {
             action: RmAction,
         }
         let mut decisions: Vec<Decision> = Vec::with_capacity(args.positional.len());
+        let mut skipped: Vec<String> = Vec::new();
         for value in &args.positional {
             let path = match crate::interpreter::value_to_text_sink_named(value, "a path") {
                 Ok(p) => p,
@@ -174,7 +187,10 @@ impl Tool for Rm {
             // what keeps `rm`/`rm -r` from following a link to its target.
             let entry = match ctx.backend.lstat(Path::new(&resolved)).await {
                 Ok(info) => Some(info),
-                Err(BackendError::NotFound(_)) if force => continue, // -f skips missing
+                Err(BackendError::NotFound(_)) if force => {
+                    skipped.push(path); // -f skips missing
+                    continue;
+                }
                 Err(BackendError::NotFound(_)) => {
                     return ExecResult::failure(1, format!("rm: {}: No such file or directory", path));
                 }
@@ -184,6 +200,18 @@ impl Tool for Rm {
             let file_size = entry.as_ref().map(|s| s.size);
             let is_dir = entry.as_ref().is_some_and(|s| s.is_dir());
             let is_symlink = entry.as_ref().is_some_and(|s| s.is_symlink());
+            if mounts.iter().any(|m| m.path == resolved) {
+                return ExecResult::failure(1, format!("rm: {}: is a mount point; refusing to remove it", path));
+            }
+            if recursive
+                && is_dir
+                && let Some(m) = mounts.iter().find(|m| m.path != resolved && m.path.starts_with(&resolved))
+            {
+                return ExecResult::failure(
+                    1,
+                    format!("rm: {}: contains mount point {}; refusing to remove it", path, m.path.display()),
+                );
+            }
             let action = decide_rm_action(
                 trash_enabled,
                 latch_enabled,
@@ -193,12 +221,28 @@ impl Tool for Rm {
                 is_dir,
                 is_symlink,
             );
+            if recursive
+                && is_dir
+                && !force
+                && !matches!(action, RmAction::Trash(_))
+                && count_entries(ctx, &resolved, BULK_LIMIT).await > BULK_LIMIT
+            {
+                return ExecResult::failure(
+                    1,
+                    format!(
+                        "rm: {}: more than {} entries; use -f to remove them all",
+                        path, BULK_LIMIT
+                    ),
+                );
+            }
             decisions.push(Decision { path, resolved, action });
         }
 
         if decisions.is_empty() {
             // All paths were missing under -f; nothing to do.
-            return ExecResult::success("");
+            let mut result = ExecResult::success("");
+            result.data = Some(entry_report(&skipped, Vec::new()));
+            return result;
         }
 
         // If ANY decision is Latch, issue one nonce for the full set of
@@ -228,6 +272,7 @@ impl Tool for Rm {
         // every failure rather than just the first; final exit reflects the
         // last failure.
         let mut last_err: Option<String> = None;
+        let mut outcomes: Vec<serde_json::Value> = Vec::with_capacity(decisions.len());
         for d in &decisions {
             let result = match &d.action {
                 RmAction::Trash(real) => {
@@ -256,21 +301,61 @@ impl Tool for Rm {
                     }
                 }
             };
-            if let Err(msg) = result {
-                last_err = Some(msg);
-            }
+            let action = match d.action {
+                RmAction::Trash(_) => "trashed",
+                RmAction::Latch | RmAction::Delete => "removed",
+            };
+            outcomes.push(match result {
+                Ok(()) => serde_json::json!({"path": d.path, "action": action}),
+                Err(msg) => {
+                    let entry = serde_json::json!({"path": d.path, "action": "failed", "error": msg});
+                    last_err = Some(msg);
+                    entry
+                }
+            });
         }
-        match last_err {
+        let mut result = match last_err {
             Some(msg) => ExecResult::failure(1, msg),
             None => ExecResult::success(""),
+        };
+        result.data = Some(entry_report(&skipped, outcomes));
+        result
+    }
+}
+
+/// The per-operand report carried in `rm`'s data: what happened to each path,
+/// then the missing ones `-f` skipped.
+fn entry_report(skipped: &[String], outcomes: impl IntoIterator<Item = serde_json::Value>) -> Value {
+    let mut entries: Vec<serde_json::Value> = outcomes.into_iter().collect();
+    entries.extend(skipped.iter().map(|path| serde_json::json!({"path": path, "action": "skipped"})));
+    Value::Json(serde_json::Value::Array(entries))
+}
+
+/// Count the entries under `dir`, giving up once past `limit`. Symlinks are
+/// counted, not followed, matching how the backend removes them.
+async fn count_entries(ctx: &ExecContext, dir: &Path, limit: usize) -> usize {
+    let mut count = 0;
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = ctx.backend.list(&dir).await else {
+            continue;
+        };
+        for entry in entries {
+            count += 1;
+            if count > limit {
+                return count;
+            }
+            if entry.is_dir() && !entry.is_symlink() {
+                pending.push(dir.join(&entry.name));
+            }
         }
     }
+    count
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ast::Value;
     use crate::vfs::{Filesystem, MemoryFs, VfsRouter};
     use std::sync::Arc;
 
@@ -390,6 +475,71 @@ mod tests {
         assert!(result.ok());
     }
 
+    #[tokio::test]
+    async fn test_rm_refuses_mount_roots() {
+        let mut vfs = VfsRouter::new();
+        let root = MemoryFs::new();
+        root.write(Path::new("work/notes.txt"), b"data").await.unwrap();
+        vfs.mount("/", root);
+        vfs.mount("/work/scratch", MemoryFs::new());
+        let mut ctx = ExecContext::new(Arc::new(vfs));
+
+        for (path, why) in [("/work/scratch", "is a mount point"), ("/", "is a mount point"), ("/work", "contains mount point")] {
+            let mut args = ToolArgs::new();
+            args.positional.push(Value::String(path.into()));
+            args.flags.insert("r".to_string());
+            args.flags.insert("f".to_string());
+            let result = Rm.execute(args, &mut ctx).await;
+            assert_eq!(result.code, 1, "{path}");
+            assert!(result.err.contains(why), "{path}: {}", result.err);
+        }
+        assert!(ctx.backend.exists(Path::new("/work/notes.txt")).await);
+    }
+
+    #[tokio::test]
+    async fn test_rm_r_bulk_limit_needs_force() {
+        let mut ctx = make_ctx().await;
+        for i in 0..=BULK_LIMIT {
+            ctx.backend
+                .write(Path::new(&format!("/big/f{i}")), b"", crate::backend::WriteMode::Overwrite)
+                .await
+                .unwrap();
+        }
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("/big".into()));
+        args.flags.insert("r".to_string());
+        let result = Rm.execute(args, &mut ctx).await;
+        assert_eq!(result.code, 1);
+        assert!(result.err.contains("use -f"), "{}", result.err);
+        assert!(ctx.backend.exists(Path::new("/big/f0")).await);
+
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("/big".into()));
+        args.flags.insert("r".to_string());
+        args.flags.insert("f".to_string());
+        assert!(Rm.execute(args, &mut ctx).await.ok());
+        assert!(!ctx.backend.exists(Path::new("/big")).await);
+    }
+
+    #[tokio::test]
+    async fn test_rm_reports_each_operand() {
+        let mut ctx = make_ctx().await;
+        let mut args = ToolArgs::new();
+        for path in ["/file.txt", "/missing", "/fulldir"] {
+            args.positional.push(Value::String(path.into()));
+        }
+        args.flags.insert("f".to_string());
+        let result = Rm.execute(args, &mut ctx).await;
+        assert_eq!(result.code, 1, "fulldir needs -r");
+        let Some(Value::Json(report)) = &result.data else {
+            panic!("no report: {:?}", result.data);
+        };
+        assert_eq!(report[0], serde_json::json!({"path": "/file.txt", "action": "removed"}));
+        assert_eq!(report[1]["path"], "/fulldir");
+        assert_eq!(report[1]["action"], "failed");
+        assert_eq!(report[2], serde_json::json!({"path": "/missing", "action": "skipped"}));
+    }
+
     async fn make_deep_ctx() -> ExecContext {
         let mut vfs = VfsRouter::new();
         let mem = MemoryFs::new();
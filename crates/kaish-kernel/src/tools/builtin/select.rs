@@ -0,0 +1,212 @@
+//! select — Choose one item from a list.
+//!
+//! Usage:
+//!   pick=$(select red green blue)         # numbered menu on the terminal
+//!   pick=$(select -p "Pick one" $colors)  # a list value offers its elements
+//!   echo 2 | select red green blue        # answer from stdin: number or item
+//!
+//! The choice is printed on stdout and carried as `{index, value}` in the
+//! result's data, so `$(select ...)` yields a record: `${pick[value]}` is
+//! the item and `${pick[index]}` its 0-based position. An answer is a menu
+//! number (1-based, as shown) or the item itself.
+//!
+//! Like `confirm`, the answer comes from piped stdin or — in an interactive
+//! kernel — from the terminal, re-asking until it gets a valid choice. A
+//! headless kernel with no stdin fails rather than blocking. Embedders with
+//! their own way to ask (an MCP elicitation, a UI picker) register a tool
+//! named `select` to replace this one.
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+
+use crate::ast::Value;
+use crate::interpreter::{value_to_string, ExecResult};
+use crate::tools::{schema_from_clap, ExecContext, GlobalFlags, Tool, ToolArgs, ToolCtx, ToolSchema};
+
+/// Select tool: choose one item from a list.
+pub struct Select;
+
+/// clap-derived argv layer for select.
+#[derive(Parser, Debug)]
+#[command(name = "select", about = "Choose one item from a list")]
+struct SelectArgs {
+    /// Prompt shown above the menu.
+    #[arg(short = 'p', long = "prompt")]
+    prompt: Option<String>,
+
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// The items. Hidden sink — the real values are read off
+    /// `args.positional` so a list value offers its elements.
+    #[arg(hide = true)]
+    items: Vec<String>,
+}
+
+#[async_trait]
+impl Tool for Select {
+    fn name(&self) -> &str {
+        "select"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &SelectArgs::command(),
+            "select",
+            "Choose one item from a list; the choice is {index, value} in .data",
+            [
+                ("Pick from words", "pick=$(select red green blue); echo ${pick[value]}"),
+                ("Pick from a list", "pick=$(select -p 'Deploy where?' $targets)"),
+                ("Answer from a pipe", "echo 2 | select red green blue"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("select: {e}")),
+        };
+        let parsed = match SelectArgs::try_parse_from(
+            std::iter::once("select".to_string()).chain(argv),
+        ) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("select: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let mut prompt = parsed.prompt;
+        let mut items = Vec::new();
+        for value in &args.positional {
+            match value {
+                Value::Json(serde_json::Value::Array(elements)) => {
+                    items.extend(elements.iter().map(json_item));
+                }
+                // `prompt=TEXT` is a field-style spelling of `--prompt`.
+                Value::String(word) if word.starts_with("prompt=") => {
+                    prompt = Some(word["prompt=".len()..].to_string());
+                }
+                other => items.push(value_to_string(other)),
+            }
+        }
+        if items.is_empty() {
+            return ExecResult::failure(2, "select: nothing to choose from");
+        }
+
+        let index = match ctx.read_stdin_to_text().await {
+            Ok(Some(text)) => {
+                let answer = text.lines().next().unwrap_or("");
+                match choose(&items, answer) {
+                    Some(index) => index,
+                    None => {
+                        return ExecResult::failure(
+                            1,
+                            format!("select: '{}' is not one of the {} items", answer.trim(), items.len()),
+                        );
+                    }
+                }
+            }
+            Ok(None) if ctx.interactive => match ask_terminal(prompt.as_deref(), &items).await {
+                Ok(Some(index)) => index,
+                Ok(None) => return ExecResult::failure(1, "select: no choice made"),
+                Err(e) => return ExecResult::failure(1, format!("select: {e}")),
+            },
+            Ok(None) => {
+                return ExecResult::failure(
+                    1,
+                    "select: no one to ask (no stdin, not interactive); pipe in a number or an item",
+                );
+            }
+            Err(e) => return ExecResult::failure(2, format!("select: {e}")),
+        };
+
+        let value = items.swap_remove(index);
+        let data = serde_json::json!({ "index": index, "value": value });
+        ExecResult::success_with_data(format!("{value}\n"), Value::Json(data))
+    }
+}
+
+/// A list element as a menu item: strings bare, anything else as JSON.
+fn json_item(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// The item `answer` names: a 1-based menu number, or the item itself.
+fn choose(items: &[String], answer: &str) -> Option<usize> {
+    let answer = answer.trim();
+    if let Ok(n) = answer.parse::<usize>()
+        && (1..=items.len()).contains(&n)
+    {
+        return Some(n - 1);
+    }
+    items.iter().position(|item| item == answer)
+}
+
+/// The numbered menu, one item per line.
+fn menu(items: &[String]) -> String {
+    let width = items.len().to_string().len();
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| format!("{:>width$}) {item}\n", i + 1))
+        .collect()
+}
+
+/// Show the menu on the terminal and read answers until one is valid.
+/// `Ok(None)` when the terminal closes first.
+async fn ask_terminal(prompt: Option<&str>, items: &[String]) -> std::io::Result<Option<usize>> {
+    let header = prompt.map(|p| format!("{p}\n")).unwrap_or_default();
+    let menu = menu(items);
+    let items = items.to_vec();
+    tokio::task::spawn_blocking(move || {
+        use std::io::Write;
+        let mut stderr = std::io::stderr();
+        loop {
+            stderr.write_all(header.as_bytes())?;
+            stderr.write_all(menu.as_bytes())?;
+            stderr.write_all(b"#? ")?;
+            stderr.flush()?;
+            let mut line = String::new();
+            if std::io::stdin().read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            if let Some(index) = choose(&items, &line) {
+                return Ok(Some(index));
+            }
+        }
+    })
+    .await
+    .map_err(std::io::Error::other)?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items() -> Vec<String> {
+        ["red", "green", "12"].iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn answers_by_number_or_item() {
+        let items = items();
+        assert_eq!(choose(&items, "1\n"), Some(0));
+        assert_eq!(choose(&items, " green "), Some(1));
+        assert_eq!(choose(&items, "3"), Some(2));
+        assert_eq!(choose(&items, "12"), Some(2), "an item that looks like a number still matches");
+        assert_eq!(choose(&items, "0"), None);
+        assert_eq!(choose(&items, "blue"), None);
+        assert_eq!(choose(&items, ""), None);
+    }
+
+    #[test]
+    fn menu_numbers_from_one() {
+        assert_eq!(menu(&items()), "1) red\n2) green\n3) 12\n");
+    }
+}
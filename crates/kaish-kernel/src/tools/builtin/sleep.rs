@@ -76,7 +76,7 @@ impl Tool for Sleep {
         // the convention used by the kernel's own cancellation checkpoints.
         tokio::select! {
             _ = tokio::time::sleep(duration) => ExecResult::success(""),
-            _ = ctx.cancel.cancelled() => ExecResult::failure(130, "sleep: interrupted"),
+            _ = ctx.cancel.cancelled() => ExecResult::interrupted("sleep: interrupted"),
         }
     }
 }
@@ -4,6 +4,14 @@
 //! child process and captures its output. Use this when you need explicit
 //! control over env, cwd, timeout, or stdin piping.
 //!
+//! The child gets the same treatment as any external command: it starts in
+//! the real directory behind the VFS cwd (when it has one), and its
+//! environment is kaish's exported variables only, plus `--env`. `--clear-env`
+//! drops the exported ones too. Captured stdout and stderr are each held to
+//! `--max-output` bytes (10MB by default); what overflows is dropped from the
+//! front, with a marker on stderr. The whole tool is off unless the kernel
+//! allows external commands and its policy allows exec.
+//!
 //! # Examples
 //!
 //! ```kaish
@@ -11,16 +19,20 @@
 //! spawn --command /bin/echo --argv '["hello", "world"]'
 //! spawn --command cargo --cwd /workspace              # with working directory
 //! spawn --command sleep --argv 10 --timeout 1000      # with 1 second timeout
+//! spawn --command make --max-output 65536             # keep at most 64KB
 //! ```
 
 use async_trait::async_trait;
 use clap::{CommandFactory, Parser};
 use std::path::Path;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::process::Command;
 
 use crate::ast::Value;
 use crate::interpreter::ExecResult;
+use crate::policy::{Policy, POLICY_DENIED_EXIT_CODE};
+use crate::scheduler::{drain_to_stream, BoundedStream, DEFAULT_STREAM_MAX_SIZE};
 use crate::tools::builtin::get_path_string;
 use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
 
@@ -51,7 +63,11 @@ struct SpawnArgs {
     #[arg(long = "timeout")]
     timeout: Option<String>,
 
-    /// Start with empty environment.
+    /// Bytes of stdout (and of stderr) to keep; defaults to 10MB.
+    #[arg(long = "max-output", visible_alias = "max_output")]
+    max_output: Option<String>,
+
+    /// Start with an empty environment, without kaish's exported variables.
     #[arg(long = "clear-env", visible_alias = "clear_env")]
     clear_env: bool,
 
@@ -76,6 +92,7 @@ impl Tool for Spawn {
             [
                 ("Run a command", "spawn --command cargo --argv build"),
                 ("With timeout", "spawn --command sleep --argv 10 --timeout 1000"),
+                ("Cap captured output", "spawn --command make --max-output 65536"),
             ],
         )
     }
@@ -99,8 +116,7 @@ impl Tool for Spawn {
         parsed.global.apply(ctx);
 
         if !ctx.allow_external_commands {
-            return ExecResult::failure(1,
-                "spawn: external commands are disabled (allow_external_commands=false)");
+            return ExecResult::failure(POLICY_DENIED_EXIT_CODE, Policy::exec_denied_message("spawn"));
         }
 
         // Get command (required). A binary value goes loud rather than
@@ -156,27 +172,13 @@ impl Tool for Spawn {
         // Get timeout (optional, in milliseconds). A malformed or negative
         // value is a usage error — the old parse().ok() fallback silently
         // DISABLED the timeout, the worst possible reading of a typo.
-        let timeout_ms: Option<u64> = match args.get_named("timeout") {
-            None => None,
-            Some(Value::Int(i)) if *i >= 0 => Some(*i as u64),
-            Some(Value::String(s)) => match s.parse::<u64>() {
-                Ok(ms) => Some(ms),
-                Err(_) => {
-                    return ExecResult::failure(
-                        2,
-                        format!("spawn: invalid timeout '{s}': expected non-negative milliseconds"),
-                    )
-                }
-            },
-            Some(other) => {
-                return ExecResult::failure(
-                    2,
-                    format!(
-                        "spawn: invalid timeout '{}': expected non-negative milliseconds",
-                        crate::interpreter::value_to_string(other)
-                    ),
-                )
-            }
+        let timeout_ms = match non_negative(&args, "timeout", "milliseconds") {
+            Ok(ms) => ms,
+            Err(msg) => return ExecResult::failure(2, msg),
+        };
+        let max_output = match non_negative(&args, "max-output", "bytes") {
+            Ok(bytes) => bytes.map_or(DEFAULT_STREAM_MAX_SIZE, |b| b as usize),
+            Err(msg) => return ExecResult::failure(2, msg),
         };
 
         // Get clear_env flag
@@ -195,7 +197,8 @@ impl Tool for Spawn {
         // dispatch.rs and the "backstop" kill_on_drop in kernel.rs.
         cmd.kill_on_drop(true);
 
-        // Set working directory if specified
+        // Set working directory: `--cwd` must map to a real directory; the
+        // session's cwd is used when it does, as for any external command.
         if let Some(ref dir) = cwd {
             let vfs_cwd = ctx.resolve_path(dir);
             // Resolve VFS path to real filesystem path
@@ -209,10 +212,29 @@ impl Tool for Spawn {
                 }
             };
             cmd.current_dir(&real_cwd);
+        } else if let Some(real_cwd) = ctx.backend.resolve_real_path(&ctx.cwd) {
+            cmd.current_dir(&real_cwd);
         }
 
-        if clear_env {
-            cmd.env_clear();
+        // Hermetic env, as for external commands: exported vars only (kept in
+        // sync with kernel.rs::try_execute_external).
+        cmd.env_clear();
+        if !clear_env {
+            let exported = ctx.scope.exported_vars();
+            if let Some(msg) = crate::interpreter::structured_export_error(&exported) {
+                return ExecResult::failure(1, format!("spawn: {msg}"));
+            }
+            for (var_name, value) in exported {
+                match crate::interpreter::value_to_text_sink_named(
+                    &value,
+                    "an exported environment variable value",
+                ) {
+                    Ok(value_str) => {
+                        cmd.env(var_name, value_str);
+                    }
+                    Err(e) => return ExecResult::failure(1, format!("spawn: {e}")),
+                }
+            }
         }
 
         for (key, value) in &env_vars {
@@ -232,7 +254,7 @@ impl Tool for Spawn {
         // Spawn the process
         let mut child = match cmd.spawn() {
             Ok(child) => child,
-            Err(e) => return ExecResult::failure(127, format!("spawn: {}: {}", command, e)),
+            Err(e) => return ExecResult::not_found(format!("spawn: {}: {}", command, e)),
         };
 
         // Write stdin if present
@@ -244,33 +266,77 @@ impl Tool for Spawn {
                 }
             }
 
+        // Drain both pipes into bounded rings while the child runs, so a
+        // chatty child can't grow kaish's memory without limit.
+        let stdout = Arc::new(BoundedStream::new(max_output));
+        let stderr = Arc::new(BoundedStream::new(max_output));
+        let stdout_task = child.stdout.take().map(|pipe| tokio::spawn(drain_to_stream(pipe, stdout.clone())));
+        let stderr_task = child.stderr.take().map(|pipe| tokio::spawn(drain_to_stream(pipe, stderr.clone())));
+        let finished = async move {
+            let status = child.wait().await;
+            for task in [stdout_task, stderr_task].into_iter().flatten() {
+                // A drain task only fails by panicking; whatever it captured
+                // is already in its stream, so report the rest and move on.
+                if let Err(e) = task.await {
+                    tracing::warn!("spawn: output drain task failed: {e}");
+                }
+            }
+            status
+        };
+
         // Wait with optional timeout
-        if let Some(ms) = timeout_ms {
+        let status = if let Some(ms) = timeout_ms {
             let timeout = Duration::from_millis(ms);
-            match tokio::time::timeout(timeout, child.wait_with_output()).await {
-                Ok(Ok(output)) => capture_to_result(output.status.code(), output.stdout, output.stderr),
-                Ok(Err(e)) => ExecResult::failure(1, format!("spawn: failed to wait: {}", e)),
+            match tokio::time::timeout(timeout, finished).await {
+                Ok(status) => status,
                 Err(_) => {
                     // Timeout — dropping this future drops the owned Child;
                     // kill_on_drop (set above) kills and reaps the process
                     // as part of that drop, so it does not outlive us.
-                    ExecResult::failure(124, format!("spawn: {}: timed out after {}ms", command, ms))
+                    return ExecResult::timed_out(format!("spawn: {}: timed out after {}ms", command, ms));
                 }
             }
         } else {
-            match child.wait_with_output().await {
-                Ok(output) => capture_to_result(output.status.code(), output.stdout, output.stderr),
-                Err(e) => ExecResult::failure(1, format!("spawn: failed to wait: {}", e)),
-            }
+            finished.await
+        };
+        match status {
+            Ok(status) => capture_to_result(status.code(), &stdout, &stderr).await,
+            Err(e) => ExecResult::failure(1, format!("spawn: failed to wait: {}", e)),
         }
     }
 }
 
+/// A named argument that must be a non-negative integer, if given; `unit`
+/// names it in the usage error.
+fn non_negative(args: &ToolArgs, name: &str, unit: &str) -> Result<Option<u64>, String> {
+    let invalid = |shown: &str| format!("spawn: invalid {name} '{shown}': expected non-negative {unit}");
+    match args.get_named(name) {
+        None => Ok(None),
+        Some(Value::Int(i)) if *i >= 0 => Ok(Some(*i as u64)),
+        Some(Value::String(s)) => s.parse::<u64>().map(Some).map_err(|_| invalid(s)),
+        Some(other) => Err(invalid(&crate::interpreter::value_to_string(other))),
+    }
+}
+
 /// Build a result from a child's captured stdout/stderr: stdout keeps binary
-/// intact (text if valid UTF-8, else a Bytes result); stderr stays text.
-fn capture_to_result(code: Option<i32>, stdout: Vec<u8>, stderr: Vec<u8>) -> ExecResult {
-    let mut result = ExecResult::success_text_or_bytes(stdout).with_code(code.unwrap_or(-1) as i64);
-    result.err = String::from_utf8_lossy(&stderr).into_owned();
+/// intact (text if valid UTF-8, else a Bytes result); stderr stays text. A
+/// stream that overflowed its ring says so on stderr, and an overflowed
+/// stdout marks the result as spilled.
+async fn capture_to_result(code: Option<i32>, stdout: &BoundedStream, stderr: &BoundedStream) -> ExecResult {
+    let mut result = ExecResult::success_text_or_bytes(stdout.read().await).with_code(code.unwrap_or(-1) as i64);
+    let mut err_text = stderr.read_string().await;
+    for (label, stream) in [("stderr", stderr), ("stdout", stdout)] {
+        if stream.has_overflowed().await {
+            let stats = stream.stats().await;
+            err_text = format!(
+                "[{label} truncated: output exceeded --max-output {} bytes — first {} bytes lost \
+                 ({} bytes total written)]\n{err_text}",
+                stats.max_size, stats.bytes_evicted, stats.total_written,
+            );
+            result.did_spill |= label == "stdout";
+        }
+    }
+    result.stderr = err_text;
     result
 }
 
@@ -286,9 +352,7 @@ fn capture_to_result(code: Option<i32>, stdout: Vec<u8>, stderr: Vec<u8>) -> Exe
 /// this same guard fires for any virtual cwd (a plain in-memory VFS mount
 /// too), and this call site has no way to tell which one it is.
 pub fn virtual_cwd_error(name: &str, cwd: &Path) -> ExecResult {
-    ExecResult::failure(
-        127,
-        format!(
+    ExecResult::not_found(format!(
             "{name}: can't run external commands here — \"{}\" has no location on \
              the real filesystem, so there's nowhere to spawn a child process. Use a \
              kaish builtin instead, or `cd` to a real directory first; if this is a \
@@ -537,6 +601,59 @@ mod tests {
         assert!(result.text_out().contains("tmp"), "expected tmp in output: {}", result.text_out());
     }
 
+    #[tokio::test]
+    async fn test_spawn_env_is_exported_vars_only() {
+        let mut ctx = make_ctx();
+        ctx.scope.set_exported("KAISH_SPAWN_SEEN", Value::String("yes".into()));
+        ctx.scope.set("KAISH_SPAWN_PRIVATE", Value::String("no".into()));
+        let mut args = ToolArgs::new();
+        args.named
+            .insert("command".to_string(), Value::String("/usr/bin/env".into()));
+
+        let result = Spawn.execute(args, &mut ctx).await;
+        assert!(result.ok(), "{}", result.err);
+        let env = result.text_out();
+        assert!(env.contains("KAISH_SPAWN_SEEN=yes"), "{env}");
+        assert!(!env.contains("KAISH_SPAWN_PRIVATE"), "{env}");
+        // Nothing leaks in from kaish's own process environment.
+        assert!(!env.contains("CARGO_PKG_NAME"), "{env}");
+    }
+
+    #[tokio::test]
+    async fn test_spawn_runs_in_the_real_cwd() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut vfs = VfsRouter::new();
+        vfs.mount("/", MemoryFs::new());
+        vfs.mount("/work", crate::vfs::LocalFs::new(dir.path()));
+        let mut ctx = ExecContext::new(Arc::new(vfs));
+        ctx.set_cwd(std::path::PathBuf::from("/work"));
+
+        let mut args = ToolArgs::new();
+        args.named
+            .insert("command".to_string(), Value::String("pwd".into()));
+        let result = Spawn.execute(args, &mut ctx).await;
+        assert!(result.ok(), "{}", result.err);
+        let real = std::fs::canonicalize(dir.path()).unwrap();
+        assert_eq!(std::fs::canonicalize(result.text_out().trim()).unwrap(), real);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_max_output_keeps_the_tail() {
+        let mut ctx = make_ctx();
+        let mut args = ToolArgs::new();
+        args.named
+            .insert("command".to_string(), Value::String("/bin/echo".into()));
+        args.named
+            .insert("argv".to_string(), Value::String("0123456789".into()));
+        args.named
+            .insert("max-output".to_string(), Value::Int(4));
+
+        let result = Spawn.execute(args, &mut ctx).await;
+        assert_eq!(&*result.text_out(), "789\n");
+        assert!(result.stderr.contains("stdout truncated"), "{}", result.stderr);
+        assert!(result.did_spill);
+    }
+
     #[tokio::test]
     async fn test_spawn_with_timeout() {
         let mut ctx = make_ctx();
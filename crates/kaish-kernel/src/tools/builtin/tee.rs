@@ -6,7 +6,7 @@ use std::path::Path;
 
 use crate::backend::WriteMode;
 use crate::interpreter::ExecResult;
-use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolAnnotations, ToolSchema};
 
 /// Tee tool: duplicate stdin to stdout and files.
 pub struct Tee;
@@ -46,6 +46,7 @@ impl Tool for Tee {
                 ("Append to log", "echo entry | tee -a log.txt"),
             ],
         )
+        .with_annotations(ToolAnnotations::destructive())
     }
 
     async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
@@ -19,7 +19,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::ast::{Arg, Command, Expr, Value};
 use crate::duration::parse_duration;
-use crate::interpreter::ExecResult;
+use crate::interpreter::{exit_code, ExecResult};
 use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
 
 /// Timeout tool: run a command with a deadline.
@@ -126,6 +126,7 @@ impl Tool for Timeout {
             name: cmd_name,
             args: inner_args,
             redirects: vec![],
+            span: None,
         };
 
         let Some(dispatcher) = ctx.dispatcher.clone() else {
@@ -160,7 +161,7 @@ impl Tool for Timeout {
         match dispatch_result {
             Ok(mut result) => {
                 if elapsed.load(Ordering::SeqCst) {
-                    result.code = 124;
+                    result.code = exit_code::TIMEOUT;
                     // The timer firing is the authoritative reason, so always
                     // surface "timed out" — even when the inner command wrote
                     // its own cancellation message on the way down (e.g. a
@@ -0,0 +1,128 @@
+//! transcript — Record the session to a Markdown or asciicast file.
+//!
+//! Subcommands: start, stop, status.
+//!
+//! While a transcript runs, the kernel appends every request it finishes —
+//! input, output, exit code, duration — to the file (see
+//! [`crate::transcript`]). `.cast` paths record asciicast v2 for
+//! `asciinema play`; anything else records Markdown.
+//!
+//! ```text
+//! transcript start /mnt/local/session.md
+//! cargo test
+//! transcript stop
+//! ```
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+
+use crate::backend::WriteMode;
+use crate::interpreter::{ExecResult, OutputData};
+use crate::tools::{schema_from_clap, ExecContext, GlobalFlags, Tool, ToolArgs, ToolCtx, ToolSchema};
+use crate::transcript::{TranscriptFormat, TranscriptStatus};
+
+/// Transcript tool: start, stop, and inspect the session transcript.
+pub struct Transcript;
+
+/// clap-derived argv layer for transcript.
+#[derive(Parser, Debug)]
+#[command(name = "transcript", about = "Record the session's commands and output to a file")]
+struct TranscriptArgs {
+    /// File format: md or cast (default: from the extension).
+    #[arg(short = 'f', long = "format")]
+    format: Option<String>,
+
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// Subcommand (`start PATH`, `stop`, `status`).
+    args: Vec<String>,
+}
+
+#[async_trait]
+impl Tool for Transcript {
+    fn name(&self) -> &str {
+        "transcript"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &TranscriptArgs::command(),
+            "transcript",
+            "Record the session's commands and output to a file",
+            [
+                ("Record to Markdown", "transcript start /mnt/local/session.md"),
+                ("Record for asciinema", "transcript start session.cast"),
+                ("Show the running transcript", "transcript status"),
+                ("Stop recording", "transcript stop"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("transcript: {e}")),
+        };
+        let parsed = match TranscriptArgs::try_parse_from(std::iter::once("transcript".to_string()).chain(argv)) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("transcript: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let (subcmd, rest) = match parsed.args.split_first() {
+            Some((subcmd, rest)) => (subcmd.as_str(), rest),
+            None => ("status", &[][..]),
+        };
+        match subcmd {
+            "start" => cmd_start(ctx, rest, parsed.format.as_deref()).await,
+            "stop" => match ctx.transcript.stop() {
+                Some(status) => status_output(&status),
+                None => ExecResult::failure(1, "transcript stop: not recording"),
+            },
+            "status" => match ctx.transcript.status() {
+                Some(status) => status_output(&status),
+                None => ExecResult::with_output(OutputData::text("not recording")),
+            },
+            other => ExecResult::failure(1, format!("transcript: unknown subcommand: {other}")),
+        }
+    }
+}
+
+async fn cmd_start(ctx: &mut ExecContext, rest: &[String], format: Option<&str>) -> ExecResult {
+    let [path] = rest else {
+        return ExecResult::failure(1, "transcript start: specify one file to record to");
+    };
+    let path = ctx.resolve_path(path);
+    let format = match format {
+        Some(name) => match TranscriptFormat::from_name(name) {
+            Some(format) => format,
+            None => return ExecResult::failure(2, format!("transcript start: unknown format '{name}' (expected md or cast)")),
+        },
+        None => TranscriptFormat::for_path(&path),
+    };
+
+    let header = match ctx.transcript.start(path.clone(), format) {
+        Ok(header) => header,
+        Err(e) => return ExecResult::failure(1, format!("transcript start: {e}")),
+    };
+    // Never clobber: a transcript is a new file.
+    if let Err(e) = ctx.backend.write(&path, &header, WriteMode::CreateNew).await {
+        ctx.transcript.stop();
+        return ExecResult::failure(1, format!("transcript start: {}: {e}", path.display()));
+    }
+    ExecResult::with_output(OutputData::text(path.to_string_lossy().to_string()))
+}
+
+fn status_output(status: &TranscriptStatus) -> ExecResult {
+    let text = format!("{} ({}, {} requests)", status.path.display(), status.format, status.requests);
+    let rich = serde_json::json!({
+        "path": status.path.to_string_lossy(),
+        "format": status.format.as_str(),
+        "requests": status.requests,
+    });
+    ExecResult::with_output(OutputData::text(text).with_rich_json(rich))
+}
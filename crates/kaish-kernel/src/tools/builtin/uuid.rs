@@ -0,0 +1,171 @@
+//! uuid — Generate random (version 4) UUIDs.
+//!
+//! Lowercase, hyphenated RFC 9562 v4 — what `uuidgen` and
+//! `/proc/sys/kernel/random/uuid` print. The UUID also lands in `.data`;
+//! `-n N` returns a list of N.
+//!
+//! Seeding follows [`super::random`]: `--seed N` for one deterministic call,
+//! or `RANDOM_SEED=N` to draw from the session's seeded sequence.
+//!
+//! # Examples
+//!
+//! ```kaish
+//! id=$(uuid)
+//! uuid -n 3
+//! RANDOM_SEED=1; uuid           # same UUID on every run
+//! ```
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+
+use crate::ast::Value;
+use crate::interpreter::ExecResult;
+use crate::tools::{schema_from_clap, ExecContext, GlobalFlags, Tool, ToolArgs, ToolCtx, ToolSchema};
+
+use super::random::{render_lines, RandomStream, Rng};
+
+/// Uuid tool: generate random (v4) UUIDs.
+pub struct Uuid {
+    stream: RandomStream,
+}
+
+impl Uuid {
+    pub fn new(stream: RandomStream) -> Self {
+        Self { stream }
+    }
+}
+
+/// clap-derived argv layer for uuid.
+#[derive(Parser, Debug)]
+#[command(name = "uuid", about = "Generate random (version 4) UUIDs")]
+struct UuidArgs {
+    /// Generate N UUIDs and return them as a list.
+    #[arg(short = 'n', long = "count")]
+    count: Option<usize>,
+
+    /// Seed this call for a reproducible result.
+    #[arg(long = "seed")]
+    seed: Option<u64>,
+
+    #[command(flatten)]
+    global: GlobalFlags,
+}
+
+#[async_trait]
+impl Tool for Uuid {
+    fn name(&self) -> &str {
+        "uuid"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &UuidArgs::command(),
+            "uuid",
+            "Generate random (version 4) UUIDs (RANDOM_SEED=N for reproducible runs)",
+            [
+                ("A fresh identifier", "id=$(uuid)"),
+                ("Three at once", "uuid -n 3"),
+                ("Reproducible", "uuid --seed 1"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+            return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+        };
+        let argv = match args.to_argv() {
+            Ok(v) => v,
+            Err(e) => return ExecResult::failure(2, format!("uuid: {e}")),
+        };
+        let parsed = match UuidArgs::try_parse_from(
+            std::iter::once("uuid".to_string()).chain(argv),
+        ) {
+            Ok(p) => p,
+            Err(e) => return ExecResult::failure(2, format!("uuid: {e}")),
+        };
+        parsed.global.apply(ctx);
+
+        let drawn = self.stream.draw(parsed.seed, ctx, |rng| match parsed.count {
+            Some(n) => (0..n)
+                .map(|_| uuid_v4(rng).map(serde_json::Value::String))
+                .collect::<Result<Vec<_>, _>>()
+                .map(|ids| Value::Json(serde_json::Value::Array(ids))),
+            None => uuid_v4(rng).map(Value::String),
+        });
+
+        match drawn {
+            Ok(value) => ExecResult::success_with_data(render_lines(&value), value),
+            Err(e) => ExecResult::failure(1, format!("uuid: {e}")),
+        }
+    }
+}
+
+/// 122 random bits with the version (4) and variant (10xx) bits set.
+fn uuid_v4(rng: &mut Rng) -> Result<String, String> {
+    let mut bytes = [0u8; 16];
+    rng.fill(&mut bytes)?;
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    Ok(format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::{MemoryFs, VfsRouter};
+    use std::sync::Arc;
+
+    fn make_ctx() -> ExecContext {
+        let mut vfs = VfsRouter::new();
+        vfs.mount("/", MemoryFs::new());
+        ExecContext::new(Arc::new(vfs))
+    }
+
+    fn is_v4(id: &str) -> bool {
+        let parts: Vec<&str> = id.split('-').collect();
+        parts.iter().map(|p| p.len()).collect::<Vec<_>>() == [8, 4, 4, 4, 12]
+            && id.chars().all(|c| c == '-' || (c.is_ascii_hexdigit() && !c.is_ascii_uppercase()))
+            && parts[2].starts_with('4')
+            && matches!(parts[3].chars().next(), Some('8' | '9' | 'a' | 'b'))
+    }
+
+    #[tokio::test]
+    async fn generates_a_v4_uuid() {
+        let tool = Uuid::new(RandomStream::default());
+        let mut ctx = make_ctx();
+        let r = tool.execute(ToolArgs::new(), &mut ctx).await;
+        assert!(r.ok(), "{}", r.err);
+        let Some(Value::String(id)) = r.data.clone() else { panic!("expected a string") };
+        assert!(is_v4(&id), "{id}");
+        assert_eq!(r.text_out().trim(), id);
+    }
+
+    #[tokio::test]
+    async fn count_returns_distinct_ids() {
+        let tool = Uuid::new(RandomStream::default());
+        let mut ctx = make_ctx();
+        let mut args = ToolArgs::new();
+        args.named.insert("count".into(), Value::Int(3));
+        let r = tool.execute(args, &mut ctx).await;
+        let Some(Value::Json(serde_json::Value::Array(ids))) = r.data.clone() else {
+            panic!("expected a list");
+        };
+        assert_eq!(ids.len(), 3);
+        assert_ne!(ids[0], ids[1]);
+        assert_eq!(r.text_out().lines().count(), 3);
+    }
+
+    #[tokio::test]
+    async fn seed_is_reproducible() {
+        let mut ctx = make_ctx();
+        let mut args = ToolArgs::new();
+        args.named.insert("seed".into(), Value::Int(1));
+        let a = Uuid::new(RandomStream::default()).execute(args.clone(), &mut ctx).await;
+        let b = Uuid::new(RandomStream::default()).execute(args, &mut ctx).await;
+        assert_eq!(a.data, b.data);
+        let Some(Value::String(id)) = a.data else { panic!("expected a string") };
+        assert!(is_v4(&id), "{id}");
+    }
+}
@@ -129,7 +129,7 @@ impl Tool for Validate {
                 }
                 let msg = errors
                     .iter()
-                    .map(|e| format!("{}: parse error: {}", label, e))
+                    .map(|e| format!("{}:{}", label, e.format(&source)))
                     .collect::<Vec<_>>()
                     .join("\n");
                 return ExecResult::failure(2, msg);
@@ -1,27 +1,35 @@
-//! which — Locate a command in PATH.
+//! which — Show what a command name would run.
+//!
+//! Names resolve in this order — the order the kernel runs them in:
+//! aliases, user functions, builtins, `.kai` scripts on `PATH`, external
+//! commands on `PATH`, then backend tools. A namespaced name
+//! (`builtin:cat`, `user:deploy`, `backend:name`) looks in that namespace
+//! only.
 //!
 //! # Examples
 //!
 //! ```kaish
 //! which cargo                   # Find cargo in PATH
-//! which -a python               # Show all matches, not just first
-//! which ls cat                  # Find multiple commands
+//! which -a cat                  # Everything named cat, shadowed ones too
+//! which ls cat                  # Resolve multiple commands
+//! which builtin:cat             # Check a namespaced name
 //! ```
 
 use async_trait::async_trait;
 use clap::{CommandFactory, Parser};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::ast::Value;
 use crate::interpreter::{ExecResult, OutputData};
 use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+use crate::validator::CommandNamespace;
 
-/// Which tool: locates commands in PATH.
+/// Which tool: reports what a command name resolves to.
 pub struct Which;
 
 /// clap-derived argv layer for which.
 #[derive(Parser, Debug)]
-#[command(name = "which", about = "Locate a command in PATH")]
+#[command(name = "which", about = "Show what a command name would run")]
 struct WhichArgs {
     /// Print all matches, not just the first (-a)
     #[arg(short = 'a', long = "a")]
@@ -30,7 +38,7 @@ struct WhichArgs {
     #[command(flatten)]
     global: GlobalFlags,
 
-    /// Commands to locate on PATH.
+    /// Commands to resolve.
     commands: Vec<String>,
 }
 
@@ -44,10 +52,11 @@ impl Tool for Which {
         schema_from_clap(
             &WhichArgs::command(),
             "which",
-            "Locate a command in PATH",
+            "Show what a command name would run: alias, user function, builtin, script, external, or backend tool",
             [
                 ("Find a command", "which cargo"),
-                ("Show all matches", "which -a python"),
+                ("Show all matches, shadowed ones too", "which -a cat"),
+                ("Check a namespaced name", "which builtin:cat"),
             ],
         )
     }
@@ -93,15 +102,15 @@ impl Tool for Which {
                 _ => continue,
             };
 
-            let matches = find_in_path(name, &path_dirs);
+            let matches = resolve(ctx, name, &path_dirs, all_matches).await;
 
             if matches.is_empty() {
                 not_found.push(name);
             } else {
                 found_any = true;
                 if all_matches {
-                    for path in matches {
-                        output.push_str(&path);
+                    for line in matches {
+                        output.push_str(&line);
                         output.push('\n');
                     }
                 } else {
@@ -142,6 +151,63 @@ impl Tool for Which {
     }
 }
 
+/// Everything `name` could run, in resolution order: one line per match,
+/// stopping at the first unless `all`.
+async fn resolve(ctx: &ExecContext, name: &str, path_dirs: &[&str], all: bool) -> Vec<String> {
+    let mut matches = Vec::new();
+    let (namespace, bare) = match CommandNamespace::split(name) {
+        Some((namespace, bare)) => (Some(namespace), bare),
+        None => (None, name),
+    };
+    let searches = |wanted: CommandNamespace| namespace.is_none_or(|ns| ns == wanted);
+    let done = |matches: &[String]| !all && !matches.is_empty();
+
+    // Aliases only apply to bare names.
+    if namespace.is_none()
+        && let Some(value) = ctx.aliases.get(name)
+    {
+        matches.push(format!("{name}: aliased to {value}"));
+    }
+    if !done(&matches)
+        && searches(CommandNamespace::User)
+        && let Some(dispatcher) = &ctx.dispatcher
+        && dispatcher.has_function(bare).await
+    {
+        matches.push(format!("{name}: user function"));
+    }
+    let is_builtin = ctx.tool_schemas.iter().any(|schema| schema.name == bare);
+    if !done(&matches) && searches(CommandNamespace::Builtin) && is_builtin {
+        matches.push(format!("{name}: kaish builtin"));
+    }
+    if namespace.is_none() {
+        for dir in path_dirs.iter().filter(|dir| !dir.is_empty()) {
+            if done(&matches) {
+                break;
+            }
+            let script = PathBuf::from(dir).join(format!("{name}.kai"));
+            if ctx.backend.exists(&script).await {
+                matches.push(script.to_string_lossy().into_owned());
+            }
+        }
+        if !done(&matches) && ctx.allow_external_commands {
+            for path in find_in_path(name, path_dirs) {
+                matches.push(path);
+                if done(&matches) {
+                    break;
+                }
+            }
+        }
+    }
+    if !done(&matches)
+        && searches(CommandNamespace::Backend)
+        && !is_builtin
+        && let Ok(Some(_)) = ctx.backend.get_tool(bare).await
+    {
+        matches.push(format!("{name}: backend tool"));
+    }
+    matches
+}
+
 /// Find all occurrences of a command in PATH directories.
 fn find_in_path(name: &str, path_dirs: &[&str]) -> Vec<String> {
     let mut results = Vec::new();
@@ -232,6 +298,26 @@ mod tests {
         assert!(result.err.contains("no definitely_not_a_command_xyz"));
     }
 
+    #[tokio::test]
+    async fn test_which_reports_builtins_and_namespaces() {
+        let mut ctx = make_ctx();
+        ctx.set_tool_schemas(vec![ToolSchema::new("cat", "Concatenate files")]);
+        ctx.scope.set("PATH", Value::String("/nonexistent".into()));
+
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("cat".into()));
+        args.positional.push(Value::String("builtin:cat".into()));
+        let result = Which.execute(args, &mut ctx).await;
+        assert!(result.ok(), "{result:?}");
+        assert_eq!(result.text_out(), "cat: kaish builtin\nbuiltin:cat: kaish builtin");
+
+        // No dispatcher, so no user functions to find.
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("user:cat".into()));
+        let result = Which.execute(args, &mut ctx).await;
+        assert!(!result.ok());
+    }
+
     #[tokio::test]
     async fn test_which_no_args() {
         let mut ctx = make_ctx();
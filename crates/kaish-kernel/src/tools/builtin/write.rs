@@ -7,7 +7,7 @@ use std::path::Path;
 use crate::ast::Value;
 use crate::interpreter::{ExecResult, OutputData};
 use crate::tools::builtin::get_path_string;
-use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolSchema};
+use crate::tools::{schema_from_clap, ExecContext, ToolCtx, GlobalFlags, Tool, ToolArgs, ToolAnnotations, ToolSchema};
 
 /// Write tool: write content to a file.
 pub struct Write;
@@ -20,7 +20,7 @@ struct WriteArgs {
     #[arg(long)]
     path: Option<String>,
 
-    /// Content to write (positional or --content). Falls back to stdin when absent.
+    /// Content to write (positional or --content). Falls back to stdin when absent or `-`.
     #[arg(long)]
     content: Option<String>,
 
@@ -49,8 +49,10 @@ impl Tool for Write {
             [
                 ("Write to a file", "write output.txt \"hello world\""),
                 ("Pipe into write", "echo content | write file.txt"),
+                ("Name stdin explicitly", "echo content | write file.txt -"),
             ],
         )
+        .with_annotations(ToolAnnotations::destructive())
     }
 
     async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
@@ -102,14 +104,17 @@ impl Tool for Write {
             Err(blocked) => return blocked,
         };
 
-        // Content can be positional[1], named "content", or stdin. Read it as
+        // Content can be positional[1], named "content", or stdin (also
+        // requested explicitly with a `-` content argument). Read it as
         // raw bytes so a `Value::Bytes` (e.g. from `$(producer)`) is written
         // verbatim instead of stringified to the `[binary: N bytes]` marker —
         // that placeholder reaching a file is silent corruption. Stdin uses the
         // byte reader (pipe-preferred) for the same reason.
         let content: Vec<u8> = if let Some(v) = args.named.get("content") {
             value_to_bytes(v)
-        } else if let Some(v) = args.positional.get(1) {
+        } else if let Some(v) = args.positional.get(1)
+            && !matches!(v, Value::String(s) if s == "-")
+        {
             value_to_bytes(v)
         } else {
             match ctx.read_stdin_to_bytes().await {
@@ -169,6 +174,33 @@ mod tests {
         assert_eq!(String::from_utf8(data).unwrap(), "hello world");
     }
 
+    #[tokio::test]
+    async fn test_write_dash_reads_stdin() {
+        let mut ctx = make_ctx().await;
+        ctx.set_stdin("from stdin".to_string());
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("/test.txt".into()));
+        args.positional.push(Value::String("-".into()));
+
+        let result = Write.execute(args, &mut ctx).await;
+        assert!(result.ok(), "stderr: {}", result.err);
+
+        let data = ctx.backend.read(Path::new("/test.txt"), None).await.unwrap();
+        assert_eq!(String::from_utf8(data).unwrap(), "from stdin");
+    }
+
+    #[tokio::test]
+    async fn test_write_dash_without_stdin_fails() {
+        let mut ctx = make_ctx().await;
+        let mut args = ToolArgs::new();
+        args.positional.push(Value::String("/test.txt".into()));
+        args.positional.push(Value::String("-".into()));
+
+        let result = Write.execute(args, &mut ctx).await;
+        assert!(!result.ok());
+        assert!(result.err.contains("missing content"));
+    }
+
     #[tokio::test]
     async fn test_write_named() {
         let mut ctx = make_ctx().await;
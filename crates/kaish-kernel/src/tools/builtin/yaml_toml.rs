@@ -0,0 +1,451 @@
+//! yaml / toml — parse config documents into values, query them, emit them.
+//!
+//! The YAML and TOML doors beside `fromjson`/`tojson`, sharing one shape:
+//!
+//! - **`parse [FILE]`** reads a file (or stdin text) and lands the document
+//!   in `.data`, converted through JSON's data model so `jq`, `${v[key]}`,
+//!   and `tojson` all see the same thing. YAML keys that aren't strings are
+//!   stringified; TOML datetimes become their RFC 3339 text.
+//! - **`get PATH [FILE]`** answers one question without a pipeline:
+//!   `toml get package.version Cargo.toml`. The path is dotted keys with
+//!   `[N]` indexes (`jobs.build.steps[0].run`, a leading `.` optional,
+//!   `["a.b"]` for keys holding dots). A string prints raw; anything else
+//!   prints as JSON. Both land in `.data`. A missing path is exit 1.
+//! - **`write [VALUE]`** serializes a value (positional or piped `.data`)
+//!   back to text. TOML needs a record at the top and has no `null`.
+//!
+//! A multi-document YAML stream is a loud error unless `--all` asks for the
+//! list of documents.
+//!
+//! `yaml` is behind the `yaml` cargo feature (serde_yaml is unmaintained
+//! upstream); `toml` is always built.
+//!
+//! # Examples
+//!
+//! ```kaish
+//! toml get package.version Cargo.toml
+//! cat .github/workflows/ci.yml | yaml get 'jobs.test.runs-on'
+//! yaml parse deploy.yml | jq '.services | keys'
+//! toml parse Cargo.toml | yaml write
+//! ```
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+
+use crate::ast::Value;
+use crate::interpreter::ExecResult;
+use crate::tools::{schema_from_clap, ExecContext, GlobalFlags, Tool, ToolArgs, ToolCtx, ToolSchema};
+
+use super::keys::describe_kind;
+
+/// Yaml tool: YAML ⇄ values.
+#[cfg(feature = "yaml")]
+pub struct Yaml;
+
+/// Toml tool: TOML ⇄ values.
+pub struct Toml;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    #[cfg(feature = "yaml")]
+    Yaml,
+    Toml,
+}
+
+impl Format {
+    fn name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "yaml")]
+            Format::Yaml => "yaml",
+            Format::Toml => "toml",
+        }
+    }
+}
+
+/// clap-derived argv layer shared by yaml and toml.
+#[derive(Parser, Debug)]
+#[command(about = "Parse, query, and write structured config documents")]
+struct DocArgs {
+    /// yaml only: parse every document of a multi-document stream into a list.
+    #[arg(long = "all")]
+    all: bool,
+
+    #[command(flatten)]
+    global: GlobalFlags,
+
+    /// Subcommand (`parse`, `get`, `write`) and its arguments. Hidden sink
+    /// for `write`'s value — read off `args.positional` per the Value-typed
+    /// positional rule.
+    args: Vec<String>,
+}
+
+#[cfg(feature = "yaml")]
+#[async_trait]
+impl Tool for Yaml {
+    fn name(&self) -> &str {
+        "yaml"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &DocArgs::command().name("yaml"),
+            "yaml",
+            "Parse YAML into a value (in .data), query it by path, or write a value as YAML",
+            [
+                ("Parse a file", "yaml parse deploy.yml"),
+                ("Query one field", "yaml get jobs.test.runs-on .github/workflows/ci.yml"),
+                ("Every document of a stream", "cat k8s.yml | yaml parse --all"),
+                ("Write a value as YAML", "toml parse Cargo.toml | yaml write"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        execute_doc(Format::Yaml, args, ctx).await
+    }
+}
+
+#[async_trait]
+impl Tool for Toml {
+    fn name(&self) -> &str {
+        "toml"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        schema_from_clap(
+            &DocArgs::command().name("toml"),
+            "toml",
+            "Parse TOML into a value (in .data), query it by path, or write a value as TOML",
+            [
+                ("Parse a file", "toml parse Cargo.toml"),
+                ("Query one field", "toml get package.version Cargo.toml"),
+                ("Index into arrays", "toml get 'bin[0].name' Cargo.toml"),
+                ("Write a record as TOML", "yaml parse config.yml | toml write"),
+            ],
+        )
+    }
+
+    async fn execute(&self, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+        execute_doc(Format::Toml, args, ctx).await
+    }
+}
+
+async fn execute_doc(format: Format, args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
+    let name = format.name();
+    let Some(ctx) = ctx.as_any_mut().downcast_mut::<ExecContext>() else {
+        return ExecResult::failure(1, "internal error: kernel builtin requires ExecContext");
+    };
+    let argv = match args.to_argv() {
+        Ok(v) => v,
+        Err(e) => return ExecResult::failure(2, format!("{name}: {e}")),
+    };
+    let parsed = match DocArgs::try_parse_from(std::iter::once(name.to_string()).chain(argv)) {
+        Ok(p) => p,
+        Err(e) => return ExecResult::failure(2, format!("{name}: {e}")),
+    };
+    parsed.global.apply(ctx);
+    if parsed.all && format == Format::Toml {
+        return ExecResult::failure(2, "toml: --all applies to multi-document YAML only");
+    }
+
+    let Some(subcmd) = parsed.args.first() else {
+        return ExecResult::failure(1, format!("{name}: missing subcommand (parse, get, write)"));
+    };
+    match subcmd.as_str() {
+        "parse" => {
+            let doc = match read_doc(format, args.positional.get(1), parsed.all, ctx).await {
+                Ok(doc) => doc,
+                Err(e) => return ExecResult::failure(1, format!("{name} parse: {e}")),
+            };
+            ExecResult::success_data(kaish_types::json_to_value_no_envelope(doc))
+        }
+        "get" => {
+            let path = match args.positional.get(1) {
+                Some(Value::String(path)) => path.clone(),
+                Some(other) => {
+                    return ExecResult::failure(1, format!("{name} get: expected a path, got {}", describe_kind(other)));
+                }
+                None => return ExecResult::failure(1, format!("{name} get: expected PATH [FILE]")),
+            };
+            let doc = match read_doc(format, args.positional.get(2), parsed.all, ctx).await {
+                Ok(doc) => doc,
+                Err(e) => return ExecResult::failure(1, format!("{name} get: {e}")),
+            };
+            let steps = match parse_path(&path) {
+                Ok(steps) => steps,
+                Err(e) => return ExecResult::failure(1, format!("{name} get: {e}")),
+            };
+            let Some(found) = lookup(&doc, &steps) else {
+                return ExecResult::failure(1, format!("{name} get: no such path: {path}"));
+            };
+            // One value per line, like `jq -r`, so consecutive gets stay apart.
+            let text = match found {
+                serde_json::Value::String(s) => format!("{s}\n"),
+                other => format!("{other}\n"),
+            };
+            ExecResult::success_with_data(text, kaish_types::json_to_value_no_envelope(found.clone()))
+        }
+        "write" => {
+            let value = match args.positional.get(1) {
+                Some(v) => v.clone(),
+                None => match ctx.resolve_stdin().await {
+                    Ok((Some(data), _)) => data,
+                    Ok((None, _)) => {
+                        return ExecResult::failure(
+                            1,
+                            format!("{name} write: no value (pass a value, or pipe .data from an upstream builtin)"),
+                        );
+                    }
+                    Err(e) => return ExecResult::failure(2, format!("{name} write: {e}")),
+                },
+            };
+            if let Value::Bytes(b) = &value {
+                return ExecResult::failure(1, format!("{name} write: cannot serialize {} bytes of binary", b.len()));
+            }
+            match emit(format, &kaish_types::value_to_json(&value)) {
+                Ok(text) => ExecResult::success(text),
+                Err(e) => ExecResult::failure(1, format!("{name} write: {e}")),
+            }
+        }
+        other => ExecResult::failure(1, format!("{name}: unknown subcommand: {}", other)),
+    }
+}
+
+/// Read the document from a file argument or stdin, and parse it. `all`
+/// only means something to YAML.
+#[cfg_attr(not(feature = "yaml"), allow(unused_variables))]
+async fn read_doc(
+    format: Format,
+    file: Option<&Value>,
+    all: bool,
+    ctx: &mut ExecContext,
+) -> Result<serde_json::Value, String> {
+    let text = match file {
+        Some(Value::String(path)) => {
+            let bytes = ctx
+                .backend
+                .read(&ctx.resolve_path(path), None)
+                .await
+                .map_err(|e| format!("{}: {}", path, e))?;
+            String::from_utf8(bytes).map_err(|_| format!("{}: invalid UTF-8", path))?
+        }
+        Some(other) => return Err(format!("expected a file path, got {}", describe_kind(other))),
+        None => ctx
+            .read_stdin_to_text()
+            .await?
+            .ok_or_else(|| "no input (pass a file or pipe stdin)".to_string())?,
+    };
+    match format {
+        #[cfg(feature = "yaml")]
+        Format::Yaml => parse_yaml(&text, all),
+        Format::Toml => parse_toml(&text),
+    }
+}
+
+#[cfg(feature = "yaml")]
+fn parse_yaml(text: &str, all: bool) -> Result<serde_json::Value, String> {
+    let mut docs = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(text) {
+        let value: serde_yaml::Value = serde::Deserialize::deserialize(document).map_err(|e| e.to_string())?;
+        docs.push(yaml_to_json(value));
+    }
+    if all {
+        return Ok(serde_json::Value::Array(docs));
+    }
+    match docs.len() {
+        0 => Ok(serde_json::Value::Null),
+        1 => Ok(docs.pop().unwrap_or_default()),
+        n => Err(format!("{n} YAML documents in the stream — use --all for a list of them")),
+    }
+}
+
+#[cfg(feature = "yaml")]
+fn yaml_to_json(value: serde_yaml::Value) -> serde_json::Value {
+    use serde_yaml::Value as Y;
+    match value {
+        Y::Null => serde_json::Value::Null,
+        Y::Bool(b) => serde_json::Value::Bool(b),
+        Y::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                serde_json::Value::from(i)
+            } else if let Some(u) = n.as_u64() {
+                serde_json::Value::from(u)
+            } else {
+                // NaN and the infinities have no JSON number; keep their text.
+                n.as_f64()
+                    .and_then(serde_json::Number::from_f64)
+                    .map(serde_json::Value::Number)
+                    .unwrap_or_else(|| serde_json::Value::String(n.to_string()))
+            }
+        }
+        Y::String(s) => serde_json::Value::String(s),
+        Y::Sequence(items) => serde_json::Value::Array(items.into_iter().map(yaml_to_json).collect()),
+        Y::Mapping(map) => serde_json::Value::Object(
+            map.into_iter()
+                .map(|(k, v)| {
+                    let key = match yaml_to_json(k) {
+                        serde_json::Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    (key, yaml_to_json(v))
+                })
+                .collect(),
+        ),
+        // `!tag value`: the tag is presentation; keep the value.
+        Y::Tagged(tagged) => yaml_to_json(tagged.value),
+    }
+}
+
+fn parse_toml(text: &str) -> Result<serde_json::Value, String> {
+    let table: toml::Table = toml::from_str(text).map_err(|e| e.to_string())?;
+    Ok(toml_to_json(toml::Value::Table(table)))
+}
+
+fn toml_to_json(value: toml::Value) -> serde_json::Value {
+    match value {
+        toml::Value::String(s) => serde_json::Value::String(s),
+        toml::Value::Integer(i) => serde_json::Value::from(i),
+        toml::Value::Float(f) => serde_json::Number::from_f64(f)
+            .map(serde_json::Value::Number)
+            .unwrap_or_else(|| serde_json::Value::String(f.to_string())),
+        toml::Value::Boolean(b) => serde_json::Value::Bool(b),
+        toml::Value::Datetime(dt) => serde_json::Value::String(dt.to_string()),
+        toml::Value::Array(items) => serde_json::Value::Array(items.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => {
+            serde_json::Value::Object(table.into_iter().map(|(k, v)| (k, toml_to_json(v))).collect())
+        }
+    }
+}
+
+fn emit(format: Format, value: &serde_json::Value) -> Result<String, String> {
+    match format {
+        #[cfg(feature = "yaml")]
+        Format::Yaml => serde_yaml::to_string(value).map_err(|e| e.to_string()),
+        Format::Toml => {
+            if !value.is_object() {
+                return Err(format!("TOML needs a record at the top level, got {}", json_kind(value)));
+            }
+            toml::to_string(value).map_err(|e| e.to_string())
+        }
+    }
+}
+
+fn json_kind(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "a bool",
+        serde_json::Value::Number(_) => "a number",
+        serde_json::Value::String(_) => "a string",
+        serde_json::Value::Array(_) => "a list",
+        serde_json::Value::Object(_) => "a record",
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+/// Parse `a.b[0]["c.d"]` into steps. Empty or `.` is the whole document.
+fn parse_path(path: &str) -> Result<Vec<Step>, String> {
+    let bad = |why: &str| format!("bad path {path:?}: {why}");
+    let mut steps = Vec::new();
+    let mut rest = path.strip_prefix('.').unwrap_or(path);
+    while !rest.is_empty() {
+        if let Some(inner) = rest.strip_prefix('[') {
+            let close = inner.find(']').ok_or_else(|| bad("unclosed '['"))?;
+            let token = &inner[..close];
+            if let Some(quoted) = token.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+                steps.push(Step::Key(quoted.to_string()));
+            } else {
+                steps.push(Step::Index(token.parse().map_err(|_| bad("index must be a number or \"key\""))?));
+            }
+            rest = &inner[close + 1..];
+        } else {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            if end == 0 {
+                return Err(bad("empty key"));
+            }
+            steps.push(Step::Key(rest[..end].to_string()));
+            rest = &rest[end..];
+        }
+        if let Some(next) = rest.strip_prefix('.') {
+            if next.is_empty() {
+                return Err(bad("trailing '.'"));
+            }
+            rest = next;
+        }
+    }
+    Ok(steps)
+}
+
+fn lookup<'a>(doc: &'a serde_json::Value, steps: &[Step]) -> Option<&'a serde_json::Value> {
+    steps.iter().try_fold(doc, |value, step| match (step, value) {
+        (Step::Key(key), serde_json::Value::Object(map)) => map.get(key),
+        (Step::Index(i), serde_json::Value::Array(items)) => items.get(*i),
+        // `steps.0` reads as an index on a list, too.
+        (Step::Key(key), serde_json::Value::Array(items)) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn paths_parse_keys_indexes_and_quoted_keys() {
+        assert_eq!(
+            parse_path(".jobs.build.steps[0][\"a.b\"]").unwrap(),
+            vec![
+                Step::Key("jobs".into()),
+                Step::Key("build".into()),
+                Step::Key("steps".into()),
+                Step::Index(0),
+                Step::Key("a.b".into()),
+            ]
+        );
+        assert!(parse_path(".").unwrap().is_empty());
+        assert!(parse_path("a..b").is_err());
+        assert!(parse_path("a.").is_err());
+        assert!(parse_path("a[x]").is_err());
+    }
+
+    #[test]
+    fn lookup_walks_records_and_lists() {
+        let doc = json!({"a": {"list": [10, {"b": "x"}]}});
+        assert_eq!(lookup(&doc, &parse_path("a.list[1].b").unwrap()), Some(&json!("x")));
+        assert_eq!(lookup(&doc, &parse_path("a.list.0").unwrap()), Some(&json!(10)));
+        assert_eq!(lookup(&doc, &parse_path("a.missing").unwrap()), None);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_stringifies_non_string_keys_and_drops_tags() {
+        let doc = parse_yaml("1: one\ntrue: yes\nv: !custom 3\n", false).unwrap();
+        assert_eq!(doc, json!({"1": "one", "true": "yes", "v": 3}));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_multi_document_needs_all() {
+        let text = "a: 1\n---\na: 2\n";
+        assert!(parse_yaml(text, false).unwrap_err().contains("--all"));
+        assert_eq!(parse_yaml(text, true).unwrap(), json!([{"a": 1}, {"a": 2}]));
+    }
+
+    #[test]
+    fn toml_datetimes_become_text() {
+        let doc = parse_toml("[pkg]\nname = \"k\"\nwhen = 1979-05-27T07:32:00Z\n").unwrap();
+        assert_eq!(doc, json!({"pkg": {"name": "k", "when": "1979-05-27T07:32:00Z"}}));
+    }
+
+    #[test]
+    fn toml_emit_requires_a_record() {
+        assert!(emit(Format::Toml, &json!([1])).unwrap_err().contains("record"));
+        let text = emit(Format::Toml, &json!({"a": 1, "t": {"b": "x"}})).unwrap();
+        assert_eq!(parse_toml(&text).unwrap(), json!({"a": 1, "t": {"b": "x"}}));
+    }
+}
@@ -1,18 +1,27 @@
 //! Execution context for tools.
 
 use std::collections::HashMap;
-use std::path::{Component, Path, PathBuf};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use crate::ast::Value;
+use crate::audit::AuditLog;
 use crate::backend::{KernelBackend, LocalBackend};
+use crate::checkpoint::CheckpointStore;
 use crate::dispatch::PipelinePosition;
+use crate::history::RequestHistory;
+use crate::hooks::KernelHooks;
 use crate::ignore_config::IgnoreConfig;
 use crate::interpreter::{ExecResult, Scope};
+use crate::metrics::KernelMetrics;
 use crate::nonce::NonceStore;
 use crate::output_limit::OutputLimitConfig;
+use crate::policy::Policy;
+use crate::result_cache::ResultCache;
 use crate::scheduler::{JobManager, PipeReader, PipeWriter, StderrStream};
+use crate::script_log::LogBuffer;
 use crate::tools::ToolRegistry;
+use crate::transcript::Transcript;
 use crate::trash::TrashBackend;
 use crate::vfs::VfsRouter;
 use kaish_vfs::ByteBudget;
@@ -59,6 +68,9 @@ pub struct ExecContext {
     pub cwd: PathBuf,
     /// Previous working directory (for `cd -`).
     pub prev_cwd: Option<PathBuf>,
+    /// Directory stack for `pushd`/`popd`/`dirs`, most recent last. The
+    /// current directory is not on it.
+    pub dir_stack: Vec<PathBuf>,
     /// Standard input for the tool (from a redirect, heredoc, here-string, or
     /// `ExecuteOptions::stdin`). Bytes-typed (GH #176) so a `< binfile`
     /// redirect over non-UTF-8 content reaches a byte-aware builtin intact
@@ -112,11 +124,42 @@ pub struct ExecContext {
     /// When `false`, external commands (PATH lookup, `exec`, `spawn`) are blocked.
     /// Only kaish builtins and backend-registered tools (MCP) are available.
     pub allow_external_commands: bool,
+    /// Execution policy. Its `allow_exec` is already folded into
+    /// `allow_external_commands`; command and write checks happen in the
+    /// kernel and its backend.
+    pub policy: Arc<Policy>,
+    /// Audit log of executed commands, read by `kaish-audit`. `None` when the
+    /// kernel isn't recording.
+    pub audit_log: Option<Arc<AuditLog>>,
     /// Confirmation nonce store for latch-gated operations.
     ///
     /// Arc-shared across pipeline stages so nonces issued in one stage
     /// can be validated in another.
     pub nonce_store: NonceStore,
+    /// Session checkpoints (`kaish-checkpoint`, `Kernel::create_checkpoint`).
+    ///
+    /// Arc-shared like the nonce store, so a checkpoint taken in a pipeline
+    /// stage or a fork can be restored from the parent.
+    pub checkpoints: CheckpointStore,
+    /// Cached results of pure tool invocations (`kaish-cache`). Arc-shared
+    /// like the checkpoint store.
+    pub result_cache: ResultCache,
+    /// Records written by the `log` builtin, served at `/v/logs`. Arc-shared
+    /// like the result cache.
+    pub logs: LogBuffer,
+    /// The session transcript `transcript start` begins and the kernel
+    /// appends each finished request to. Arc-shared like the log buffer.
+    pub transcript: Transcript,
+    /// The session's top-level requests, recorded by the kernel; `distill`
+    /// reads it. Arc-shared like the checkpoint store.
+    pub history: RequestHistory,
+    /// Kernel metrics collector, read by `kaish-metrics`. Arc-shared with
+    /// pipeline stages and forks.
+    pub metrics: KernelMetrics,
+    /// The embedder's callbacks, so a tool's
+    /// [`report_progress`](kaish_tool_api::ToolCtx::report_progress) reaches
+    /// `on_progress`. Shared with pipeline stages and forks.
+    pub hooks: KernelHooks,
     /// Trash backend for safe file deletion.
     ///
     /// Always present when the kernel creates the context (even if `set -o trash`
@@ -182,6 +225,19 @@ pub struct ExecContext {
     /// timeout is configured — `ToolCtx::patient` then returns an inert guard.
     pub watchdog: Option<Arc<crate::watchdog::Watchdog>>,
 
+    /// Number of shell functions currently executing on this path (0 at top
+    /// level). The kernel increments it around each function call and checks
+    /// it against `KernelConfig::max_call_depth`; pipeline stages, forks and
+    /// background jobs inherit it, so the limit follows a call chain across
+    /// them.
+    pub call_depth: usize,
+
+    /// Files being run by `source` on this path, outermost first. The kernel
+    /// refuses to source a file already on the stack (an include cycle) or
+    /// past `MAX_SOURCE_DEPTH` nested files; forks inherit it like
+    /// `call_depth`.
+    pub sourcing: Vec<PathBuf>,
+
     /// Active overlay handle when the kernel was constructed with `overlay: true`.
     ///
     /// `Arc`-cloned so forks and pipeline stages share the same transaction.
@@ -306,6 +362,7 @@ impl ExecContext {
             scope: Scope::new(),
             cwd: PathBuf::from("/"),
             prev_cwd: None,
+            dir_stack: Vec::new(),
             stdin: None,
             stdin_data: None,
             stdin_data_rx: None,
@@ -321,7 +378,16 @@ impl ExecContext {
             ignore_config: IgnoreConfig::none(),
             output_limit: OutputLimitConfig::none(),
             allow_external_commands: true,
+            policy: Arc::new(Policy::default()),
+            audit_log: None,
             nonce_store: NonceStore::new(),
+            checkpoints: CheckpointStore::new(),
+            result_cache: ResultCache::new(),
+            logs: LogBuffer::default(),
+            transcript: Transcript::default(),
+            history: RequestHistory::default(),
+            metrics: KernelMetrics::default(),
+            hooks: KernelHooks::default(),
             trash_backend: None,
             #[cfg(all(unix, feature = "subprocess"))]
             terminal_state: None,
@@ -331,6 +397,8 @@ impl ExecContext {
             current_invocation: None,
             vfs_budget: None,
             watchdog: None,
+            call_depth: 0,
+            sourcing: Vec::new(),
             #[cfg(all(feature = "localfs", feature = "overlay"))]
             overlay_handle: None,
         }
@@ -346,6 +414,7 @@ impl ExecContext {
             scope: Scope::new(),
             cwd: PathBuf::from("/"),
             prev_cwd: None,
+            dir_stack: Vec::new(),
             stdin: None,
             stdin_data: None,
             stdin_data_rx: None,
@@ -361,7 +430,16 @@ impl ExecContext {
             ignore_config: IgnoreConfig::none(),
             output_limit: OutputLimitConfig::none(),
             allow_external_commands: true,
+            policy: Arc::new(Policy::default()),
+            audit_log: None,
             nonce_store: NonceStore::new(),
+            checkpoints: CheckpointStore::new(),
+            result_cache: ResultCache::new(),
+            logs: LogBuffer::default(),
+            transcript: Transcript::default(),
+            history: RequestHistory::default(),
+            metrics: KernelMetrics::default(),
+            hooks: KernelHooks::default(),
             trash_backend: None,
             #[cfg(all(unix, feature = "subprocess"))]
             terminal_state: None,
@@ -371,6 +449,8 @@ impl ExecContext {
             current_invocation: None,
             vfs_budget: None,
             watchdog: None,
+            call_depth: 0,
+            sourcing: Vec::new(),
             #[cfg(all(feature = "localfs", feature = "overlay"))]
             overlay_handle: None,
         }
@@ -383,6 +463,7 @@ impl ExecContext {
             scope: Scope::new(),
             cwd: PathBuf::from("/"),
             prev_cwd: None,
+            dir_stack: Vec::new(),
             stdin: None,
             stdin_data: None,
             stdin_data_rx: None,
@@ -398,7 +479,16 @@ impl ExecContext {
             ignore_config: IgnoreConfig::none(),
             output_limit: OutputLimitConfig::none(),
             allow_external_commands: true,
+            policy: Arc::new(Policy::default()),
+            audit_log: None,
             nonce_store: NonceStore::new(),
+            checkpoints: CheckpointStore::new(),
+            result_cache: ResultCache::new(),
+            logs: LogBuffer::default(),
+            transcript: Transcript::default(),
+            history: RequestHistory::default(),
+            metrics: KernelMetrics::default(),
+            hooks: KernelHooks::default(),
             trash_backend: None,
             #[cfg(all(unix, feature = "subprocess"))]
             terminal_state: None,
@@ -408,6 +498,8 @@ impl ExecContext {
             current_invocation: None,
             vfs_budget: None,
             watchdog: None,
+            call_depth: 0,
+            sourcing: Vec::new(),
             #[cfg(all(feature = "localfs", feature = "overlay"))]
             overlay_handle: None,
         }
@@ -420,6 +512,7 @@ impl ExecContext {
             scope,
             cwd: PathBuf::from("/"),
             prev_cwd: None,
+            dir_stack: Vec::new(),
             stdin: None,
             stdin_data: None,
             stdin_data_rx: None,
@@ -435,7 +528,16 @@ impl ExecContext {
             ignore_config: IgnoreConfig::none(),
             output_limit: OutputLimitConfig::none(),
             allow_external_commands: true,
+            policy: Arc::new(Policy::default()),
+            audit_log: None,
             nonce_store: NonceStore::new(),
+            checkpoints: CheckpointStore::new(),
+            result_cache: ResultCache::new(),
+            logs: LogBuffer::default(),
+            transcript: Transcript::default(),
+            history: RequestHistory::default(),
+            metrics: KernelMetrics::default(),
+            hooks: KernelHooks::default(),
             trash_backend: None,
             #[cfg(all(unix, feature = "subprocess"))]
             terminal_state: None,
@@ -445,6 +547,8 @@ impl ExecContext {
             current_invocation: None,
             vfs_budget: None,
             watchdog: None,
+            call_depth: 0,
+            sourcing: Vec::new(),
             #[cfg(all(feature = "localfs", feature = "overlay"))]
             overlay_handle: None,
         }
@@ -460,6 +564,7 @@ impl ExecContext {
             scope,
             cwd: PathBuf::from("/"),
             prev_cwd: None,
+            dir_stack: Vec::new(),
             stdin: None,
             stdin_data: None,
             stdin_data_rx: None,
@@ -475,7 +580,16 @@ impl ExecContext {
             ignore_config: IgnoreConfig::none(),
             output_limit: OutputLimitConfig::none(),
             allow_external_commands: true,
+            policy: Arc::new(Policy::default()),
+            audit_log: None,
             nonce_store: NonceStore::new(),
+            checkpoints: CheckpointStore::new(),
+            result_cache: ResultCache::new(),
+            logs: LogBuffer::default(),
+            transcript: Transcript::default(),
+            history: RequestHistory::default(),
+            metrics: KernelMetrics::default(),
+            hooks: KernelHooks::default(),
             trash_backend: None,
             #[cfg(all(unix, feature = "subprocess"))]
             terminal_state: None,
@@ -485,6 +599,8 @@ impl ExecContext {
             current_invocation: None,
             vfs_budget: None,
             watchdog: None,
+            call_depth: 0,
+            sourcing: Vec::new(),
             #[cfg(all(feature = "localfs", feature = "overlay"))]
             overlay_handle: None,
         }
@@ -497,6 +613,7 @@ impl ExecContext {
             scope,
             cwd: PathBuf::from("/"),
             prev_cwd: None,
+            dir_stack: Vec::new(),
             stdin: None,
             stdin_data: None,
             stdin_data_rx: None,
@@ -512,7 +629,16 @@ impl ExecContext {
             ignore_config: IgnoreConfig::none(),
             output_limit: OutputLimitConfig::none(),
             allow_external_commands: true,
+            policy: Arc::new(Policy::default()),
+            audit_log: None,
             nonce_store: NonceStore::new(),
+            checkpoints: CheckpointStore::new(),
+            result_cache: ResultCache::new(),
+            logs: LogBuffer::default(),
+            transcript: Transcript::default(),
+            history: RequestHistory::default(),
+            metrics: KernelMetrics::default(),
+            hooks: KernelHooks::default(),
             trash_backend: None,
             #[cfg(all(unix, feature = "subprocess"))]
             terminal_state: None,
@@ -522,6 +648,8 @@ impl ExecContext {
             current_invocation: None,
             vfs_budget: None,
             watchdog: None,
+            call_depth: 0,
+            sourcing: Vec::new(),
             #[cfg(all(feature = "localfs", feature = "overlay"))]
             overlay_handle: None,
         }
@@ -624,7 +752,7 @@ impl ExecContext {
         } else {
             self.cwd.join(path)
         };
-        normalize_path(&raw)
+        crate::vfs::normalize_path(&raw)
     }
 
     /// Change the current working directory.
@@ -688,6 +816,7 @@ impl ExecContext {
             scope: self.scope.clone(),
             cwd: self.cwd.clone(),
             prev_cwd: self.prev_cwd.clone(),
+            dir_stack: self.dir_stack.clone(),
             stdin: None,
             stdin_data: None,
             stdin_data_rx: None,
@@ -703,7 +832,16 @@ impl ExecContext {
             ignore_config: self.ignore_config.clone(),
             output_limit: self.output_limit.clone(),
             allow_external_commands: self.allow_external_commands,
+            policy: self.policy.clone(),
+            audit_log: self.audit_log.clone(),
             nonce_store: self.nonce_store.clone(),
+            checkpoints: self.checkpoints.clone(),
+            result_cache: self.result_cache.clone(),
+            logs: self.logs.clone(),
+            transcript: self.transcript.clone(),
+            history: self.history.clone(),
+            metrics: self.metrics.clone(),
+            hooks: self.hooks.clone(),
             trash_backend: self.trash_backend.clone(),
             #[cfg(all(unix, feature = "subprocess"))]
             terminal_state: self.terminal_state.clone(),
@@ -718,6 +856,9 @@ impl ExecContext {
             // Watchdog is shared: a patient hold in a pipeline stage or fork
             // suspends the same script clock as foreground execution.
             watchdog: self.watchdog.clone(),
+            // Call depth carries over so recursion through a pipe stays bounded.
+            call_depth: self.call_depth,
+            sourcing: self.sourcing.clone(),
             // Overlay handle is shared: pipeline stages share the same transaction.
             #[cfg(all(feature = "localfs", feature = "overlay"))]
             overlay_handle: self.overlay_handle.clone(),
@@ -1131,6 +1272,15 @@ impl kaish_tool_api::ToolCtx for ExecContext {
         }
     }
 
+    fn cancel_token(&self) -> kaish_tool_api::CancellationToken {
+        self.cancel.clone()
+    }
+
+    fn report_progress(&self, progress: kaish_tool_api::Progress) {
+        let command = self.current_invocation.as_deref().map_or("", |(name, _)| name.as_str());
+        self.hooks.progress(command, &progress);
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -1140,29 +1290,6 @@ impl kaish_tool_api::ToolCtx for ExecContext {
     }
 }
 
-/// Normalize a path by resolving `.` and `..` components lexically (no filesystem access).
-fn normalize_path(path: &std::path::Path) -> PathBuf {
-    let mut parts: Vec<Component> = Vec::new();
-    for component in path.components() {
-        match component {
-            Component::CurDir => {} // skip `.`
-            Component::ParentDir => {
-                // Pop the last normal component, but don't pop past root
-                if let Some(Component::Normal(_)) = parts.last() {
-                    parts.pop();
-                } else {
-                    parts.push(component);
-                }
-            }
-            _ => parts.push(component),
-        }
-    }
-    if parts.is_empty() {
-        PathBuf::from("/")
-    } else {
-        parts.iter().collect()
-    }
-}
 
 #[cfg(test)]
 mod tests {
@@ -46,7 +46,7 @@ mod tests {
 
         // Simulate kernel pre-apply followed by builtin's parsed.global.apply.
         GlobalFlags::apply_from_args(&args, &mut ctx);
-        let gf = GlobalFlags { json: true };
+        let gf = GlobalFlags { json: true, ..Default::default() };
         gf.apply(&mut ctx);
         assert!(matches!(ctx.output_format, Some(OutputFormat::Json)));
     }
@@ -3,7 +3,7 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use super::traits::{Tool, ToolSchema};
+use super::traits::{Tool, ToolBundle, ToolSchema};
 
 /// Registry of available tools.
 #[derive(Default)]
@@ -29,6 +29,14 @@ impl ToolRegistry {
         self.tools.insert(name, tool);
     }
 
+    /// Register every tool in `bundle`. A tool named like one already
+    /// registered replaces it.
+    pub fn register_bundle(&mut self, bundle: &dyn ToolBundle) {
+        for tool in bundle.tools() {
+            self.register_arc(tool);
+        }
+    }
+
     /// Look up a tool by name.
     pub fn get(&self, name: &str) -> Option<Arc<dyn Tool>> {
         self.tools.get(name).cloned()
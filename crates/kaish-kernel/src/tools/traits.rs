@@ -6,7 +6,7 @@
 //! kernel. They are re-exported here so existing `crate::tools::…` paths keep
 //! working.
 
-pub use kaish_tool_api::{is_global_output_flag, validate_against_schema, Tool, ToolCtx};
+pub use kaish_tool_api::{is_global_output_flag, validate_against_schema, Tool, ToolBundle, ToolCtx, UsageError};
 
 // Data types re-exported from kaish-types.
-pub use kaish_types::{ParamSchema, ToolArgs, ToolSchema};
+pub use kaish_types::{ParamSchema, ToolAnnotations, ToolArgs, ToolSchema};
@@ -0,0 +1,291 @@
+//! Session transcripts: every request, its output, and its timing, written
+//! to a file as the session runs.
+//!
+//! `transcript start PATH` begins one; the kernel then appends each
+//! top-level request it finishes — the input as typed, stdout, stderr, exit
+//! code, and duration — until `transcript stop`. Two formats:
+//!
+//! - **Markdown** (the default): a fenced `sh` block per request followed
+//!   by its output, readable as-is or rendered on a forge.
+//! - **asciicast v2** (`.cast` paths, or `--format cast`): the JSON-lines
+//!   recording `asciinema play` replays with the original timing.
+//!
+//! The file is written through the kernel's backend, so it lands wherever
+//! the path resolves — `/mnt/local/session.md` on the host, a VFS scratch
+//! path, or the embedder's own storage. Recording is best effort, like the
+//! audit log: a failed append is logged through `tracing` and never changes
+//! the request's result.
+//!
+//! The handle lives on the `ExecContext` and is Arc-shared, so the builtin
+//! that starts a transcript and the kernel loop that writes it see the same
+//! state. Only top-level requests are recorded; the commands inside a
+//! background job or a function show up through the request that ran them.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use kaish_types::clock::Instant;
+
+use crate::interpreter::ExecResult;
+
+/// asciicast terminal size; the kernel doesn't know the real one.
+const CAST_WIDTH: u16 = 100;
+const CAST_HEIGHT: u16 = 30;
+
+/// How a transcript is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptFormat {
+    /// Markdown: fenced command and output blocks.
+    Markdown,
+    /// asciicast v2 JSON lines, for `asciinema play`.
+    Asciicast,
+}
+
+impl TranscriptFormat {
+    /// The format named `name`: `md`/`markdown` or `cast`/`asciicast`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "md" | "markdown" => Some(Self::Markdown),
+            "cast" | "asciicast" => Some(Self::Asciicast),
+            _ => None,
+        }
+    }
+
+    /// The format a path's extension implies: `.cast` is asciicast,
+    /// anything else Markdown.
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("cast") => Self::Asciicast,
+            _ => Self::Markdown,
+        }
+    }
+
+    /// The format's short name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Asciicast => "cast",
+        }
+    }
+}
+
+impl fmt::Display for TranscriptFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A transcript in progress, as `transcript status` reports it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptStatus {
+    /// Resolved path of the file being written.
+    pub path: PathBuf,
+    pub format: TranscriptFormat,
+    /// Requests recorded so far.
+    pub requests: usize,
+}
+
+#[derive(Debug)]
+struct Recording {
+    status: TranscriptStatus,
+    started: Instant,
+}
+
+/// Shared handle to the session's transcript, if one is running.
+#[derive(Clone, Debug, Default)]
+pub struct Transcript {
+    inner: Arc<Mutex<Option<Recording>>>,
+}
+
+impl Transcript {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<Recording>> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Begin recording to `path`. Returns the file's header, which the
+    /// caller writes before any request lands. Fails if a transcript is
+    /// already running.
+    pub fn start(&self, path: PathBuf, format: TranscriptFormat) -> Result<Vec<u8>, String> {
+        let mut slot = self.lock();
+        if let Some(running) = slot.as_ref() {
+            return Err(format!("already recording to {}", running.status.path.display()));
+        }
+        let header = header(format);
+        *slot = Some(Recording {
+            status: TranscriptStatus { path, format, requests: 0 },
+            started: Instant::now(),
+        });
+        Ok(header)
+    }
+
+    /// Stop recording. Returns what was recorded, or `None` if nothing was
+    /// running.
+    pub fn stop(&self) -> Option<TranscriptStatus> {
+        self.lock().take().map(|recording| recording.status)
+    }
+
+    /// The running transcript, if any.
+    pub fn status(&self) -> Option<TranscriptStatus> {
+        self.lock().as_ref().map(|recording| recording.status.clone())
+    }
+
+    /// Whether a transcript is running.
+    pub fn is_recording(&self) -> bool {
+        self.lock().is_some()
+    }
+
+    /// Render one finished request for the running transcript and count
+    /// it. Returns the path to append to and the bytes to append, or
+    /// `None` if nothing is running.
+    pub fn entry(&self, input: &str, result: &ExecResult, elapsed: Duration) -> Option<(PathBuf, Vec<u8>)> {
+        let mut slot = self.lock();
+        let recording = slot.as_mut()?;
+        recording.status.requests += 1;
+        let bytes = match recording.status.format {
+            TranscriptFormat::Markdown => markdown_entry(input, result, elapsed),
+            TranscriptFormat::Asciicast => {
+                let end = recording.started.elapsed();
+                cast_entry(input, result, end.saturating_sub(elapsed), end)
+            }
+        };
+        Some((recording.status.path.clone(), bytes.into_bytes()))
+    }
+}
+
+fn header(format: TranscriptFormat) -> Vec<u8> {
+    let now = kaish_types::clock::system_now();
+    match format {
+        TranscriptFormat::Markdown => {
+            let started = chrono::DateTime::<chrono::Utc>::from(now)
+                .to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+            format!("# kaish transcript\n\nStarted {started}.\n\n").into_bytes()
+        }
+        TranscriptFormat::Asciicast => {
+            let timestamp = now.duration_since(std::time::UNIX_EPOCH).map_or(0, |d| d.as_secs());
+            let header = serde_json::json!({
+                "version": 2,
+                "width": CAST_WIDTH,
+                "height": CAST_HEIGHT,
+                "timestamp": timestamp,
+                "title": "kaish transcript",
+            });
+            format!("{header}\n").into_bytes()
+        }
+    }
+}
+
+/// A code fence longer than any backtick run in `text`, so the block
+/// can't be closed early by its own contents.
+fn fence(text: &str) -> String {
+    let mut longest = 0;
+    let mut run = 0;
+    for ch in text.chars() {
+        run = if ch == '`' { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    "`".repeat((longest + 1).max(3))
+}
+
+fn fenced(info: &str, text: &str) -> String {
+    let fence = fence(text);
+    let newline = if text.ends_with('\n') { "" } else { "\n" };
+    format!("{fence}{info}\n{text}{newline}{fence}\n\n")
+}
+
+fn markdown_entry(input: &str, result: &ExecResult, elapsed: Duration) -> String {
+    let mut entry = fenced("sh", input.trim_end());
+    let out = result.text_out();
+    if !out.is_empty() {
+        entry.push_str(&fenced("text", &out));
+    }
+    let err = result.stderr_text();
+    if !err.is_empty() {
+        entry.push_str("stderr:\n\n");
+        entry.push_str(&fenced("text", &err));
+    }
+    entry.push_str(&format!("*exit {} · {}*\n\n", result.code, format_duration(elapsed)));
+    entry
+}
+
+/// The request as asciicast output events: the prompt and input when it
+/// started, its output when it finished.
+fn cast_entry(input: &str, result: &ExecResult, start: Duration, end: Duration) -> String {
+    let event = |at: Duration, text: &str| {
+        let text = text.replace('\n', "\r\n");
+        format!("{}\n", serde_json::json!([at.as_secs_f64(), "o", text]))
+    };
+    let mut entry = event(start, &format!("$ {}\n", input.trim_end()));
+    let mut output = result.text_out().into_owned();
+    output.push_str(&result.stderr_text());
+    if !output.is_empty() {
+        if !output.ends_with('\n') {
+            output.push('\n');
+        }
+        entry.push_str(&event(end, &output));
+    }
+    entry
+}
+
+fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    if millis < 1000 {
+        format!("{millis}ms")
+    } else if millis < 60_000 {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else {
+        format!("{}m{:02}s", millis / 60_000, (millis / 1000) % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_entries_fence_input_and_output() {
+        let transcript = Transcript::default();
+        transcript.start(PathBuf::from("/s.md"), TranscriptFormat::Markdown).unwrap();
+        let result = ExecResult::failure(1, "boom");
+        let (path, bytes) = transcript
+            .entry("ls ```\n", &ExecResult::success("a\nb\n"), Duration::from_millis(12))
+            .unwrap();
+        assert_eq!(path, PathBuf::from("/s.md"));
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            "````sh\nls ```\n````\n\n```text\na\nb\n```\n\n*exit 0 · 12ms*\n\n"
+        );
+
+        let (_, bytes) = transcript.entry("false", &result, Duration::from_millis(1500)).unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+        assert!(text.contains("stderr:\n\n```text\nboom\n```"), "{text}");
+        assert!(text.ends_with("*exit 1 · 1.5s*\n\n"), "{text}");
+        assert_eq!(transcript.stop().map(|s| s.requests), Some(2));
+        assert!(transcript.entry("ls", &result, Duration::ZERO).is_none());
+    }
+
+    #[test]
+    fn cast_entries_are_timed_output_events() {
+        let entry = cast_entry(
+            "echo hi",
+            &ExecResult::success("hi\n"),
+            Duration::from_millis(500),
+            Duration::from_millis(750),
+        );
+        let lines: Vec<serde_json::Value> = entry.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines[0], serde_json::json!([0.5, "o", "$ echo hi\r\n"]));
+        assert_eq!(lines[1], serde_json::json!([0.75, "o", "hi\r\n"]));
+    }
+
+    #[test]
+    fn one_transcript_at_a_time() {
+        let transcript = Transcript::default();
+        let header = transcript.start(PathBuf::from("/a.cast"), TranscriptFormat::Asciicast).unwrap();
+        let header: serde_json::Value = serde_json::from_slice(&header).unwrap();
+        assert_eq!(header["version"], 2);
+        assert!(transcript.start(PathBuf::from("/b.md"), TranscriptFormat::Markdown).is_err());
+        assert_eq!(TranscriptFormat::for_path(Path::new("/x/s.cast")), TranscriptFormat::Asciicast);
+        assert_eq!(TranscriptFormat::for_path(Path::new("/x/s.md")), TranscriptFormat::Markdown);
+    }
+}
@@ -8,6 +8,7 @@
 //! - **Semantic constraints**: Regex compiles? seq increment != 0? count > 0?
 //! - **Variable bindings**: Warn on possibly undefined variables
 //! - **Control flow**: break/continue outside loop? return outside function?
+//! - **`par` blocks**: Do the statements share variables or written paths?
 //!
 //! # Example
 //!
@@ -25,12 +26,15 @@
 //! ```
 
 mod issue;
+mod par;
 mod scope_tracker;
 mod walker;
 
 pub use issue::{IssueCode, Severity, Span, ValidationIssue};
+pub use par::{plan_par, ParPlan};
 pub use scope_tracker::ScopeTracker;
 pub use walker::{build_tool_args_for_validation, Validator};
 pub(crate) use walker::{
-    classify_command_name, is_runtime_special_form, is_static_command_name, SpecialForm,
+    classify_command_name, command_suggestion, is_runtime_special_form, is_static_command_name, CommandNamespace,
+    SpecialForm,
 };
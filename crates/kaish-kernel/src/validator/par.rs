@@ -0,0 +1,724 @@
+//! Independence check for `par { ... }` blocks.
+//!
+//! A `par` block runs its statements concurrently only when none of them can
+//! observe another's effects: no statement assigns a variable another one
+//! reads or assigns, no statement writes a path another one reads or writes,
+//! and none changes state the whole block shares (cwd, shell options,
+//! functions) or leaves the block early. The validator and the kernel both
+//! consult [`plan_par`], so a warning from one is a sequential run in the
+//! other.
+//!
+//! Only commands on an explicit list are understood: ones with no effect
+//! beyond their output, ones that only read the paths they are given, and
+//! the file writers (`rm`, `cp`, `mv`, `tee`, …) whose targets are their
+//! operands. Any other command — an external program, a builtin not on the
+//! list — may touch anything, so it forces a sequential run. Paths are
+//! compared lexically after resolving them against the cwd, and a path
+//! conflicts with everything under it (`rm -r build` against `build/out`).
+//! A written path only known at run time (`> $out`, `rm *.log`) forces a
+//! sequential run; a read one conflicts with every write in the block.
+//!
+//! Aliases and symlinks are not followed, so two spellings of one file that
+//! only the filesystem knows are the same still count as independent.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crate::ast::{
+    Arg, Command, Expr, ListElem, RecordKey, RedirectKind, Stmt, StringPart, TestExpr, Value,
+    VarPath, VarSegment,
+};
+
+use super::walker::is_static_command_name;
+
+/// How a `par` block's statements will run.
+#[derive(Debug, Clone, Default)]
+pub struct ParPlan {
+    /// Why the block has to run one statement after another; empty when the
+    /// statements may run concurrently.
+    pub conflicts: Vec<String>,
+    /// Variables each statement may assign, indexed like the block's body.
+    /// After a concurrent run these are copied back from each statement's
+    /// fork.
+    pub writes: Vec<BTreeSet<String>>,
+}
+
+impl ParPlan {
+    /// True when nothing forces a sequential run.
+    pub fn is_concurrent(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Decide whether the statements of a `par` block are independent.
+///
+/// `cwd` anchors relative paths; without it, a relative and an absolute path
+/// are assumed to overlap. `is_function` reports whether a command name is a
+/// user-defined function; a call to one is opaque here, so it forces a
+/// sequential run.
+pub fn plan_par(body: &[Stmt], cwd: Option<&Path>, is_function: impl Fn(&str) -> bool) -> ParPlan {
+    let footprints: Vec<Footprint<'_>> = body
+        .iter()
+        .map(|stmt| {
+            let mut fp = Footprint::new(cwd, &is_function);
+            fp.stmt(stmt);
+            fp
+        })
+        .collect();
+
+    let mut conflicts: Vec<String> = Vec::new();
+    let mut note = |reason: String| {
+        if !conflicts.contains(&reason) {
+            conflicts.push(reason);
+        }
+    };
+    for fp in &footprints {
+        if let Some(reason) = &fp.serial {
+            note(reason.clone());
+        }
+    }
+    for (i, a) in footprints.iter().enumerate() {
+        for b in &footprints[i + 1..] {
+            for name in a.writes.intersection(&b.writes) {
+                note(format!("more than one statement assigns `{name}`"));
+            }
+            for name in a.writes.intersection(&b.reads).chain(b.writes.intersection(&a.reads)) {
+                note(format!("`{name}` is assigned by one statement and read by another"));
+            }
+            for x in &a.written {
+                for y in b.written.iter().filter(|y| overlap(x, y)) {
+                    note(if x == y {
+                        format!("more than one statement writes `{}`", x.display())
+                    } else {
+                        format!("one statement writes `{}` and another `{}`", x.display(), y.display())
+                    });
+                }
+            }
+            for (w, r) in [(a, b), (b, a)] {
+                for path in &w.written {
+                    for read in r.read.iter().filter(|read| overlap(path, read)) {
+                        note(format!(
+                            "one statement writes `{}` and another reads `{}`",
+                            path.display(),
+                            read.display()
+                        ));
+                    }
+                    if r.reads_unknown {
+                        note(format!(
+                            "one statement writes `{}` and another reads a path only known at run time",
+                            path.display()
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    ParPlan {
+        conflicts,
+        writes: footprints.into_iter().map(|fp| fp.writes).collect(),
+    }
+}
+
+/// Whether two normalized paths may name the same file or one may contain
+/// the other. A relative path that climbs out with `..`, or a relative path
+/// set against an absolute one, can't be placed, so it overlaps anything.
+fn overlap(a: &Path, b: &Path) -> bool {
+    let climbs = |p: &Path| p.starts_with("..");
+    if a.is_absolute() != b.is_absolute() || climbs(a) || climbs(b) {
+        return true;
+    }
+    a.starts_with(b) || b.starts_with(a)
+}
+
+/// Commands with no effect beyond their output whose operands are not paths.
+fn is_pure(name: &str) -> bool {
+    matches!(
+        name,
+        "echo"
+            | "printf"
+            | "true"
+            | "false"
+            | "sleep"
+            | "seq"
+            | "date"
+            | "pwd"
+            | "basename"
+            | "dirname"
+            | "hostname"
+            | "uname"
+            | "uuid"
+            | "random"
+            | "typeof"
+            | "calc"
+            | "tokens"
+            | "kaish-version"
+    )
+}
+
+/// Commands whose only effect beyond their output is reading the paths they
+/// are given. Every operand counts as a read path.
+fn only_reads_operands(name: &str) -> bool {
+    matches!(
+        name,
+        "cat"
+            | "head"
+            | "tail"
+            | "tac"
+            | "wc"
+            | "grep"
+            | "sort"
+            | "uniq"
+            | "cut"
+            | "tr"
+            | "ls"
+            | "tree"
+            | "stat"
+            | "file"
+            | "diff"
+            | "cmp"
+            | "checksum"
+            | "base64"
+            | "xxd"
+            | "jq"
+            | "csv"
+            | "fromjson"
+            | "fromjsonl"
+            | "tojson"
+            | "tojsonl"
+            | "keys"
+            | "values"
+            | "readlink"
+            | "realpath"
+            | "which"
+            | "test"
+            | "["
+            | "assert"
+    )
+}
+
+/// Commands that bind the variable names given as operands.
+fn binds_operands(name: &str) -> bool {
+    matches!(name, "read" | "export" | "unset" | "local" | "readonly")
+}
+
+/// Commands that write paths named by their operands; see
+/// [`Footprint::command`] for which operands.
+fn writes_operands(name: &str) -> bool {
+    matches!(name, "rm" | "touch" | "mkdir" | "tee" | "cp" | "mv" | "ln" | "write")
+}
+
+/// Commands whose effect lands on state every statement of the block shares.
+fn changes_shared_state(name: &str) -> bool {
+    matches!(
+        name,
+        "cd" | "pushd"
+            | "popd"
+            | "set"
+            | "source"
+            | "."
+            | "import"
+            | "alias"
+            | "unalias"
+            | "shift"
+            | "kaish-ignore"
+            | "kaish-output-limit"
+    )
+}
+
+/// What one statement reads and writes.
+struct Footprint<'f> {
+    cwd: Option<&'f Path>,
+    is_function: &'f dyn Fn(&str) -> bool,
+    /// Variables read.
+    reads: BTreeSet<String>,
+    /// Variables assigned.
+    writes: BTreeSet<String>,
+    /// Paths read, normalized.
+    read: BTreeSet<PathBuf>,
+    /// Whether some path read is only known at run time.
+    reads_unknown: bool,
+    /// Paths written, normalized.
+    written: BTreeSet<PathBuf>,
+    /// The first reason this statement can't run beside the others.
+    serial: Option<String>,
+    loop_depth: usize,
+}
+
+impl<'f> Footprint<'f> {
+    fn new(cwd: Option<&'f Path>, is_function: &'f dyn Fn(&str) -> bool) -> Self {
+        Self {
+            cwd,
+            is_function,
+            reads: BTreeSet::new(),
+            writes: BTreeSet::new(),
+            read: BTreeSet::new(),
+            reads_unknown: false,
+            written: BTreeSet::new(),
+            serial: None,
+            loop_depth: 0,
+        }
+    }
+
+    fn serial(&mut self, reason: impl Into<String>) {
+        if self.serial.is_none() {
+            self.serial = Some(reason.into());
+        }
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Assignment(assign) => {
+                self.expr(&assign.value);
+                // A subscripted write (`xs[$i]=v`) updates the root in place,
+                // and a dynamic subscript reads its variable.
+                if assign.path.segments.len() > 1 {
+                    self.var_path(&assign.path);
+                }
+                self.writes.insert(assign.name().to_string());
+            }
+            Stmt::Command(cmd) => self.command(cmd),
+            Stmt::Pipeline(pipe) => {
+                for cmd in &pipe.commands {
+                    self.command(cmd);
+                }
+            }
+            Stmt::If(if_stmt) => {
+                self.expr(&if_stmt.condition);
+                self.stmts(&if_stmt.then_branch);
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    self.stmts(else_branch);
+                }
+            }
+            Stmt::For(for_loop) => {
+                for item in &for_loop.items {
+                    self.expr(item);
+                }
+                self.writes.insert(for_loop.variable.clone());
+                self.loop_depth += 1;
+                self.stmts(&for_loop.body);
+                self.loop_depth -= 1;
+            }
+            Stmt::While(while_loop) => {
+                self.expr(&while_loop.condition);
+                self.loop_depth += 1;
+                self.stmts(&while_loop.body);
+                self.loop_depth -= 1;
+            }
+            Stmt::Case(case_stmt) => {
+                self.expr(&case_stmt.expr);
+                for branch in &case_stmt.branches {
+                    self.stmts(&branch.body);
+                }
+            }
+            Stmt::Break(_) | Stmt::Continue(_) if self.loop_depth == 0 => {
+                self.serial("`break`/`continue` leaves the block");
+            }
+            Stmt::Break(_) | Stmt::Continue(_) => {}
+            Stmt::Return(_) => self.serial("`return` leaves the block"),
+            Stmt::Exit(_) => self.serial("`exit` leaves the block"),
+            Stmt::ToolDef(def) => self.serial(format!("defines function `{}`", def.name)),
+            Stmt::Test(test) => self.test(test),
+            Stmt::AndChain { left, right } | Stmt::OrChain { left, right } => {
+                self.stmt(left);
+                self.stmt(right);
+            }
+            Stmt::EnvScoped { assignments, body } => {
+                // The prefix assignments are scoped to `body`, so they are
+                // not writes anyone else could see.
+                for assign in assignments {
+                    self.expr(&assign.value);
+                }
+                self.stmt(body);
+            }
+            Stmt::Par(body) => self.stmts(body),
+            Stmt::Try(try_stmt) => {
+                // A caught failure assigns `${ERROR}`.
+                self.writes.insert("ERROR".to_string());
+                self.stmts(&try_stmt.body);
+                self.stmts(&try_stmt.catch);
+            }
+            Stmt::On(on) => self.serial(format!("registers an `on {}` handler", on.event)),
+            Stmt::Empty => {}
+        }
+    }
+
+    fn command(&mut self, cmd: &Command) {
+        let name = cmd.name.as_str();
+        if !is_static_command_name(name) {
+            self.serial("runs a command whose name is only known at run time");
+        } else if changes_shared_state(name) {
+            self.serial(format!("`{name}` changes state the whole block shares"));
+        } else if (self.is_function)(name) {
+            self.serial(format!("calls function `{name}`, which may assign any variable"));
+        } else if !(is_pure(name)
+            || only_reads_operands(name)
+            || binds_operands(name)
+            || writes_operands(name))
+        {
+            self.serial(format!("`{name}` may have effects the block can't see"));
+        }
+
+        for arg in &cmd.args {
+            match arg {
+                Arg::Positional(expr) => self.expr(expr),
+                Arg::Named { value, .. } => self.expr(value),
+                Arg::WordAssign { key, value } => {
+                    self.expr(value);
+                    if matches!(name, "export" | "local" | "readonly") {
+                        self.writes.insert(key.clone());
+                    }
+                }
+                Arg::ShortFlag(_) | Arg::LongFlag(_) | Arg::DoubleDash => {}
+            }
+        }
+
+        let positionals: Vec<&Expr> = cmd
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                Arg::Positional(expr) => Some(expr),
+                _ => None,
+            })
+            .collect();
+        let by = format!("`{name}`");
+        match name {
+            _ if binds_operands(name) => {
+                for expr in &positionals {
+                    match literal(expr) {
+                        Some(var) => {
+                            self.writes.insert(var.to_string());
+                        }
+                        None => self.serial(format!("`{name}` names a variable only known at run time")),
+                    }
+                }
+            }
+            _ if only_reads_operands(name) => {
+                for arg in &cmd.args {
+                    if let Arg::Positional(expr) | Arg::Named { value: expr, .. } = arg {
+                        self.read_path(expr);
+                    }
+                }
+            }
+            "rm" | "touch" | "mkdir" | "tee" | "mv" => {
+                // `mv` removes its sources as surely as it writes its target.
+                for expr in &positionals {
+                    self.written_path(&by, expr);
+                }
+            }
+            "cp" | "ln" => {
+                if let Some((dest, sources)) = positionals.split_last() {
+                    for expr in sources {
+                        self.read_path(expr);
+                    }
+                    self.written_path(&by, dest);
+                }
+            }
+            "write" => {
+                if let Some(dest) = positionals.first() {
+                    self.written_path(&by, dest);
+                }
+            }
+            _ => {}
+        }
+
+        for redirect in &cmd.redirects {
+            self.expr(&redirect.target);
+            match redirect.kind {
+                RedirectKind::StdoutOverwrite
+                | RedirectKind::StdoutAppend
+                | RedirectKind::Stderr
+                | RedirectKind::Both => self.written_path("a redirect", &redirect.target),
+                RedirectKind::Stdin => self.read_path(&redirect.target),
+                RedirectKind::HereDoc
+                | RedirectKind::HereString
+                | RedirectKind::MergeStderr
+                | RedirectKind::MergeStdout => {}
+            }
+        }
+    }
+
+    /// `path` resolved against the cwd (when known) and normalized.
+    fn resolve(&self, path: &str) -> PathBuf {
+        match self.cwd {
+            Some(cwd) => crate::vfs::normalize_path(&cwd.join(path)),
+            None => crate::vfs::normalize_path(Path::new(path)),
+        }
+    }
+
+    /// Record a path `by` writes; `by` names the writer in the conflict.
+    fn written_path(&mut self, by: &str, expr: &Expr) {
+        match path_operand(expr) {
+            // Every write to the null device is lost, so none can collide.
+            Some("/dev/null") => {}
+            Some(path) => {
+                let path = self.resolve(path);
+                self.written.insert(path);
+            }
+            None => self.serial(format!("{by} writes a path only known at run time")),
+        }
+    }
+
+    /// Record a path read.
+    fn read_path(&mut self, expr: &Expr) {
+        match expr {
+            // Numbers and other typed literals are option values, not paths.
+            Expr::Literal(value) if !matches!(value, Value::String(_)) => {}
+            _ => match path_operand(expr) {
+                Some(path) => {
+                    let path = self.resolve(path);
+                    self.read.insert(path);
+                }
+                None => self.reads_unknown = true,
+            },
+        }
+    }
+
+    fn var_path(&mut self, path: &VarPath) {
+        for (i, segment) in path.segments.iter().enumerate() {
+            match segment {
+                VarSegment::Field(name) if i == 0 => {
+                    self.reads.insert(name.clone());
+                }
+                VarSegment::Dynamic(name) => {
+                    self.reads.insert(name.clone());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Literal(_)
+            | Expr::Positional(_)
+            | Expr::AllArgs
+            | Expr::ArgCount
+            | Expr::LastExitCode
+            | Expr::CurrentPid
+            | Expr::GlobPattern(_) => {}
+            Expr::VarRef(path) | Expr::VarLength(path) => self.var_path(path),
+            Expr::Interpolated(parts) => self.parts(parts),
+            Expr::HereDocBody { parts, .. } => {
+                for spanned in parts {
+                    self.part(&spanned.part);
+                }
+            }
+            Expr::BinaryOp { left, right, .. } => {
+                self.expr(left);
+                self.expr(right);
+            }
+            Expr::CommandSubst(stmts) => self.stmts(stmts),
+            Expr::Test(test) => self.test(test),
+            Expr::VarWithDefault { path, op, default } => self.var_with_default(path, *op, default),
+            Expr::Arithmetic(src) => self.arithmetic(src),
+            Expr::Command(cmd) => self.command(cmd),
+            Expr::ListLiteral(elems) => {
+                for elem in elems {
+                    match elem {
+                        ListElem::Item(e) | ListElem::Spread(e) => self.expr(e),
+                    }
+                }
+            }
+            Expr::RecordLiteral(entries) => {
+                for entry in entries {
+                    if let RecordKey::Interpolated(parts) = &entry.key {
+                        self.parts(parts);
+                    }
+                    self.expr(&entry.value);
+                }
+            }
+            Expr::Conditional { condition, then_value, else_value } => {
+                self.expr(condition);
+                self.expr(then_value);
+                self.expr(else_value);
+            }
+        }
+    }
+
+    fn parts(&mut self, parts: &[StringPart]) {
+        for part in parts {
+            self.part(part);
+        }
+    }
+
+    fn part(&mut self, part: &StringPart) {
+        match part {
+            StringPart::Literal(_)
+            | StringPart::Positional(_)
+            | StringPart::AllArgs
+            | StringPart::ArgCount
+            | StringPart::LastExitCode
+            | StringPart::CurrentPid => {}
+            StringPart::Var(path) | StringPart::VarLength(path) => self.var_path(path),
+            StringPart::VarWithDefault { path, op, default } => {
+                self.var_with_default(path, *op, default)
+            }
+            StringPart::Arithmetic(src) => self.arithmetic(src),
+            StringPart::CommandSubst(stmts) => self.stmts(stmts),
+        }
+    }
+
+    fn var_with_default(&mut self, path: &VarPath, op: crate::ast::DefaultOp, default: &[StringPart]) {
+        self.var_path(path);
+        self.parts(default);
+        if op == crate::ast::DefaultOp::AssignDefault
+            && let Some(VarSegment::Field(name)) = path.segments.first()
+        {
+            self.writes.insert(name.clone());
+        }
+    }
+
+    /// Arithmetic source is kept as text; every identifier in it counts as
+    /// a read.
+    fn arithmetic(&mut self, src: &str) {
+        let mut ident = String::new();
+        for c in src.chars().chain(std::iter::once(' ')) {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                ident.push(c);
+            } else {
+                if ident.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+                    self.reads.insert(std::mem::take(&mut ident));
+                }
+                ident.clear();
+            }
+        }
+    }
+
+    fn test(&mut self, test: &TestExpr) {
+        match test {
+            TestExpr::FileTest { path, .. } => {
+                self.expr(path);
+                self.read_path(path);
+            }
+            TestExpr::StringTest { value, .. } => self.expr(value),
+            TestExpr::Comparison { left, right, .. }
+            | TestExpr::In { left, right }
+            | TestExpr::NotIn { left, right } => {
+                self.expr(left);
+                self.expr(right);
+            }
+            TestExpr::And { left, right } | TestExpr::Or { left, right } => {
+                self.test(left);
+                self.test(right);
+            }
+            TestExpr::Not { expr } => self.test(expr),
+        }
+    }
+}
+
+/// The text of a plain literal word, if `expr` is one.
+fn literal(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Literal(Value::String(s)) => Some(s),
+        _ => None,
+    }
+}
+
+/// The path a literal operand names. `~` expands at run time, so a word
+/// starting with it is not known here.
+fn path_operand(expr: &Expr) -> Option<&str> {
+    literal(expr).filter(|path| !path.starts_with('~'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn plan(src: &str) -> ParPlan {
+        plan_in(src, None)
+    }
+
+    fn plan_in(src: &str, cwd: Option<&Path>) -> ParPlan {
+        let program = parse(src).expect("parse");
+        match program.statements.iter().find(|s| !matches!(s, Stmt::Empty)) {
+            Some(Stmt::Par(body)) => plan_par(body, cwd, |name| name == "myfn"),
+            other => panic!("expected a par block, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn independent_fetches_run_concurrently() {
+        let plan = plan("par {\n  A=$(echo one)\n  B=$(echo two)\n}");
+        assert!(plan.is_concurrent(), "{:?}", plan.conflicts);
+        assert!(plan.writes[0].contains("A"));
+        assert!(plan.writes[1].contains("B"));
+    }
+
+    #[test]
+    fn read_after_write_is_a_conflict() {
+        let plan = plan("par {\n  A=1\n  echo $A\n}");
+        assert_eq!(plan.conflicts, vec!["`A` is assigned by one statement and read by another"]);
+    }
+
+    #[test]
+    fn arithmetic_reads_count() {
+        let plan = plan("par {\n  N=3\n  M=$((N + 1))\n}");
+        assert!(!plan.is_concurrent());
+    }
+
+    #[test]
+    fn same_redirect_target_is_a_conflict() {
+        let plan = plan("par {\n  echo a > out.txt\n  echo b >> out.txt\n}");
+        assert_eq!(plan.conflicts, vec!["more than one statement writes `out.txt`"]);
+    }
+
+    #[test]
+    fn paths_are_normalized_against_the_cwd() {
+        let plan = plan_in(
+            "par {\n  echo a > out.txt\n  echo b > /work/./x/../out.txt\n}",
+            Some(Path::new("/work")),
+        );
+        assert_eq!(plan.conflicts, vec!["more than one statement writes `/work/out.txt`"]);
+        // Without a cwd, a relative path can't be placed against an absolute one.
+        assert!(!plan("par {\n  echo a > out.txt\n  echo b > /work/out.txt\n}").is_concurrent());
+    }
+
+    #[test]
+    fn a_path_conflicts_with_everything_under_it() {
+        let plan = plan("par {\n  rm -r build\n  touch build/out\n}");
+        assert_eq!(plan.conflicts, vec!["one statement writes `build` and another `build/out`"]);
+        assert!(!plan("par {\n  cat build/out\n  rm -r build\n}").is_concurrent());
+    }
+
+    #[test]
+    fn reads_conflict_with_writes() {
+        let plan = plan("par {\n  cat in.txt\n  echo x > in.txt\n}");
+        assert_eq!(plan.conflicts, vec!["one statement writes `in.txt` and another reads `in.txt`"]);
+        // `mv` removes its source.
+        assert!(!plan("par {\n  mv a.txt b.txt\n  wc -l a.txt\n}").is_concurrent());
+        assert!(!plan("par {\n  cp a.txt b.txt\n  echo x > a.txt\n}").is_concurrent());
+        assert!(!plan("par {\n  grep x < log.txt\n  echo y >> log.txt\n}").is_concurrent());
+        // A read only known at run time may be any written path.
+        assert!(!plan("par {\n  cat $F\n  echo y > log.txt\n}").is_concurrent());
+        // Reads alone never conflict.
+        assert!(plan("par {\n  cat a.txt\n  head -n 5 a.txt\n  cat $F\n}").is_concurrent());
+    }
+
+    #[test]
+    fn unknown_commands_force_sequential() {
+        let plan = plan("par {\n  curl -s example.com\n  echo hi\n}");
+        assert_eq!(plan.conflicts, vec!["`curl` may have effects the block can't see"]);
+        assert!(!plan("par {\n  split big.txt\n  echo hi\n}").is_concurrent());
+    }
+
+    #[test]
+    fn shared_state_and_functions_force_sequential() {
+        assert!(!plan("par {\n  cd /tmp\n  echo hi\n}").is_concurrent());
+        assert!(!plan("par {\n  myfn\n  echo hi\n}").is_concurrent());
+        assert!(!plan("par {\n  echo hi\n  exit 1\n}").is_concurrent());
+    }
+
+    #[test]
+    fn break_inside_a_nested_loop_stays_concurrent() {
+        let plan = plan("par {\n  for i in 1 2; do break; done\n  echo hi\n}");
+        assert!(plan.is_concurrent(), "{:?}", plan.conflicts);
+    }
+}
@@ -12,6 +12,9 @@ use std::collections::HashSet;
 pub struct ScopeTracker {
     /// Stack of scope frames, each containing bound variable names.
     frames: Vec<HashSet<String>>,
+    /// Indices into `frames` of function-body frames, innermost last —
+    /// mirrors the interpreter, where `local` binds in the function's frame.
+    function_frames: Vec<usize>,
 }
 
 impl Default for ScopeTracker {
@@ -25,6 +28,7 @@ impl ScopeTracker {
     pub fn new() -> Self {
         let mut tracker = Self {
             frames: vec![HashSet::new()],
+            function_frames: Vec::new(),
         };
 
         // Register built-in special variables
@@ -74,9 +78,18 @@ impl ScopeTracker {
     pub fn pop_frame(&mut self) {
         if self.frames.len() > 1 {
             self.frames.pop();
+            if self.function_frames.last() == Some(&self.frames.len()) {
+                self.function_frames.pop();
+            }
         }
     }
 
+    /// Push a frame for a function body (see [`Self::bind_local`]).
+    pub fn push_function_frame(&mut self) {
+        self.function_frames.push(self.frames.len());
+        self.push_frame();
+    }
+
     /// Bind a variable name in the current scope.
     pub fn bind(&mut self, name: impl Into<String>) {
         if let Some(frame) = self.frames.last_mut() {
@@ -84,6 +97,15 @@ impl ScopeTracker {
         }
     }
 
+    /// Bind a `local` declaration: in the innermost function frame, or the
+    /// global frame outside any function.
+    pub fn bind_local(&mut self, name: impl Into<String>) {
+        let index = self.function_frames.last().copied().unwrap_or(0);
+        if let Some(frame) = self.frames.get_mut(index) {
+            frame.insert(name.into());
+        }
+    }
+
     /// Check if a variable is bound in any scope.
     ///
     /// Searches from innermost to outermost scope.
@@ -145,6 +167,18 @@ mod tests {
         assert!(tracker.is_bound("OUTER")); // Still there
     }
 
+    #[test]
+    fn local_binds_in_the_function_frame() {
+        let mut tracker = ScopeTracker::new();
+        tracker.push_function_frame();
+        tracker.push_frame(); // a loop body
+        tracker.bind_local("TOTAL");
+        tracker.pop_frame();
+        assert!(tracker.is_bound("TOTAL"), "outlives the loop");
+        tracker.pop_frame();
+        assert!(!tracker.is_bound("TOTAL"), "ends with the function");
+    }
+
     #[test]
     fn underscore_convention() {
         assert!(ScopeTracker::should_skip_undefined_check("_EXTERNAL"));
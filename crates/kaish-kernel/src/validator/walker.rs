@@ -1,19 +1,23 @@
 //! AST walker for pre-execution validation.
 
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
 use crate::ast::{
-    Arg, Assignment, CaseBranch, CaseStmt, Command, Expr, ForLoop, IfStmt, ListElem, Pipeline,
-    Program, SpannedPart, Stmt, StringPart, TestExpr, ToolDef, VarPath, VarSegment, WhileLoop,
-    Value,
+    Arg, Assignment, CaseBranch, CaseStmt, Command, DefaultOp, Expr, ForLoop, IfStmt, ListElem,
+    OnStmt, Pipeline, Program, SpannedPart, Stmt, StringPart, TestExpr, ToolDef, TryStmt, VarPath, VarSegment,
+    WhileLoop, Value,
 };
+use crate::hooks::HookEvent;
 use crate::kernel::{bind_glued_short_value, push_repeatable_value};
+use crate::policy::Policy;
 use crate::scheduler::{is_bool_type, schema_param_lookup};
 use crate::validator::issue::Span;
 use crate::tools::{ToolArgs, ToolRegistry, ToolSchema};
 use kaish_types::CommandKind;
 
 use super::issue::{IssueCode, ValidationIssue};
+use super::par::plan_par;
 use super::scope_tracker::ScopeTracker;
 
 /// AST validator that checks for issues before execution.
@@ -22,12 +26,23 @@ pub struct Validator<'a> {
     registry: &'a ToolRegistry,
     /// User-defined tools.
     user_tools: &'a HashMap<String, ToolDef>,
+    /// Execution policy to check command names against, if any.
+    policy: Option<&'a Policy>,
+    /// Working directory relative paths resolve against, if known.
+    cwd: Option<&'a Path>,
     /// Variable scope tracker.
     scope: ScopeTracker,
     /// Current loop nesting depth.
     loop_depth: usize,
     /// Current function nesting depth.
     function_depth: usize,
+    /// Nesting depth inside a string-embedded `$(...)` (double-quoted or
+    /// `${X:-$(...)}`), whose nodes are parsed from the string alone and so
+    /// carry spans relative to it rather than to the source.
+    string_subst_depth: usize,
+    /// Namespaces an `import` earlier in the program will load; calls into
+    /// them can only be checked once it has run.
+    imports: HashSet<String>,
     /// Collected validation issues.
     issues: Vec<ValidationIssue>,
 }
@@ -38,13 +53,30 @@ impl<'a> Validator<'a> {
         Self {
             registry,
             user_tools,
+            policy: None,
+            cwd: None,
             scope: ScopeTracker::new(),
             loop_depth: 0,
             function_depth: 0,
+            string_subst_depth: 0,
+            imports: HashSet::new(),
             issues: Vec::new(),
         }
     }
 
+    /// Also warn about commands the kernel's policy will refuse.
+    pub fn with_policy(mut self, policy: &'a Policy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Resolve relative paths against `cwd` when checking `par` blocks, as
+    /// the kernel does when it runs them.
+    pub fn with_cwd(mut self, cwd: &'a Path) -> Self {
+        self.cwd = Some(cwd);
+        self
+    }
+
     /// Validate a program and return all issues found.
     pub fn validate(mut self, program: &Program) -> Vec<ValidationIssue> {
         for stmt in &program.statements {
@@ -77,6 +109,9 @@ impl<'a> Validator<'a> {
                 self.validate_stmt(left);
                 self.validate_stmt(right);
             }
+            Stmt::Par(body) => self.validate_par(body),
+            Stmt::Try(try_stmt) => self.validate_try(try_stmt),
+            Stmt::On(on) => self.validate_on(on),
             Stmt::EnvScoped { assignments, body } => {
                 // Validate each prefix assignment (values + bind the name so the
                 // body's references resolve), then the command it scopes.
@@ -99,6 +134,12 @@ impl<'a> Validator<'a> {
     /// already be bound — a path-set never autovivifies the root (see
     /// `docs/arrays-and-hashes.md`, "Assignment lvalues").
     fn validate_assignment(&mut self, assign: &Assignment) {
+        let issues_before = self.issues.len();
+        self.check_assignment(assign);
+        self.attach_node_span(issues_before, assign.span);
+    }
+
+    fn check_assignment(&mut self, assign: &Assignment) {
         // Validate the value expression
         self.validate_expr(&assign.value);
 
@@ -118,7 +159,11 @@ impl<'a> Validator<'a> {
                 );
             }
             // Bind the variable name in scope
-            self.scope.bind(name);
+            if assign.local {
+                self.scope.bind_local(name);
+            } else {
+                self.scope.bind(name);
+            }
         } else if !self.scope.is_bound(name) {
             self.issues.push(
                 ValidationIssue::error(
@@ -138,29 +183,119 @@ impl<'a> Validator<'a> {
 
     /// Validate a command invocation.
     fn validate_command(&mut self, cmd: &Command) {
+        let issues_before = self.issues.len();
+        self.check_command(cmd);
+        self.attach_node_span(issues_before, cmd.span);
+    }
+
+    fn check_command(&mut self, cmd: &Command) {
         // Skip source/. commands - they're dynamic
         if cmd.name == "source" || cmd.name == "." {
             return;
         }
 
+        // `import PATH as NAME` defines `NAME.*` when it runs.
+        if cmd.name == "import" {
+            if let [_, Arg::Positional(Expr::Literal(Value::String(keyword))), Arg::Positional(Expr::Literal(Value::String(namespace)))] =
+                cmd.args.as_slice()
+                && keyword == "as"
+            {
+                self.imports.insert(namespace.clone());
+            }
+            for arg in &cmd.args {
+                self.validate_arg(arg);
+            }
+            return;
+        }
+
         // Skip dynamic command names (variable expansions)
         if !is_static_command_name(&cmd.name) {
             return;
         }
 
+        // A namespaced name (`builtin:cat`) resolves in its namespace only.
+        let namespace = CommandNamespace::split(&cmd.name);
+        let name = namespace.map_or(cmd.name.as_str(), |(_, bare)| bare);
+        let searches = |wanted: CommandNamespace| namespace.is_none_or(|(ns, _)| ns == wanted);
+
         // Check if command exists
-        let is_builtin = self.registry.contains(&cmd.name);
-        let is_user_tool = self.user_tools.contains_key(&cmd.name);
-        let is_special = is_special_command(&cmd.name);
+        let is_builtin = searches(CommandNamespace::Builtin) && self.registry.contains(name);
+        let is_user_tool = searches(CommandNamespace::User) && self.user_tools.contains_key(name);
+        let is_special = namespace.is_none() && is_special_command(name);
+
+        // A call into a loaded module must name one of its functions; one
+        // into a module imported by this program is checked when it runs.
+        let module_call = if namespace.is_none() && !is_user_tool {
+            crate::module::split_qualified(name)
+        } else {
+            None
+        };
+        if let Some((module, function)) = module_call {
+            if self.imports.contains(module) {
+                for arg in &cmd.args {
+                    self.validate_arg(arg);
+                }
+                return;
+            }
+            let mut available: Vec<&str> = self
+                .user_tools
+                .values()
+                .filter(|def| def.module.as_deref() == Some(module))
+                .map(|def| def.name.as_str())
+                .collect();
+            if !available.is_empty() {
+                available.sort_unstable();
+                self.issues.push(
+                    ValidationIssue::error(
+                        IssueCode::UnknownModuleFunction,
+                        format!("module '{module}' has no function '{function}'"),
+                    )
+                    .with_suggestion(format!("available: {}", available.join(", "))),
+                );
+                return;
+            }
+        }
 
-        if !is_builtin && !is_user_tool && !is_special {
+        if let Some((ns, bare)) = namespace {
+            // Backend tools aren't known until runtime.
+            if ns != CommandNamespace::Backend && !is_builtin && !is_user_tool {
+                let (kind, known): (_, Vec<&str>) = if ns == CommandNamespace::User {
+                    ("user function", self.user_tools.keys().map(String::as_str).collect())
+                } else {
+                    ("builtin", self.registry.names())
+                };
+                let suggestion = kaish_tool_api::did_you_mean(bare, known)
+                    .map(|command| format!("did you mean `{}{command}`?", ns.prefix()));
+                let mut issue = ValidationIssue::warning(
+                    IssueCode::UndefinedCommand,
+                    format!("command '{}' not found: no {} named '{}'", cmd.name, kind, bare),
+                );
+                issue.suggestion = suggestion;
+                self.issues.push(issue);
+            }
+        } else if !is_builtin && !is_user_tool && !is_special {
             // Warning only - command might be a script in PATH or external tool.
             // (`test` is now a first-class builtin — VFS-aware, validated — so it
             // takes the `is_builtin` path above and never lands here.)
+            let known = self.registry.names().into_iter().chain(self.user_tools.keys().map(String::as_str));
+            let suggestion = command_suggestion(name, known)
+                .unwrap_or_else(|| "this may be a script in PATH or external command".to_string());
             self.issues.push(ValidationIssue::warning(
                 IssueCode::UndefinedCommand,
                 format!("command '{}' not found in builtin registry", cmd.name),
-            ).with_suggestion("this may be a script in PATH or external command"));
+            ).with_suggestion(suggestion));
+        }
+
+        if !is_user_tool
+            && !is_special
+            && namespace.is_none_or(|(ns, _)| ns != CommandNamespace::User)
+            && let Some(policy) = self.policy
+            && !policy.allows_tool(name)
+        {
+            self.issues.push(ValidationIssue::warning(
+                IssueCode::PolicyDenied,
+                format!("command '{}' is not allowed by the kernel's policy", cmd.name),
+            ));
         }
 
         // Validate arguments expressions
@@ -172,14 +307,16 @@ impl<'a> Validator<'a> {
         // arg-builder binds glued/value short-flags the same way execute does —
         // otherwise a tool whose validate() reads positionals semantically (sed,
         // awk) misreads them (docs/issues.md: schema-blind validation builder).
-        if let Some(tool) = self.registry.get(&cmd.name) {
+        // A user function shadows a builtin of the same name, so its
+        // parameters are the ones that apply.
+        if is_user_tool && let Some(user_tool) = self.user_tools.get(name) {
+            // Validate against user-defined tool parameters
+            self.validate_user_tool_args(user_tool, &cmd.args);
+        } else if is_builtin && let Some(tool) = self.registry.get(name) {
             let schema = tool.schema();
             let tool_args = build_tool_args_for_validation(&cmd.args, Some(&schema));
             let tool_issues = tool.validate(&tool_args);
             self.issues.extend(tool_issues);
-        } else if let Some(user_tool) = self.user_tools.get(&cmd.name) {
-            // Validate against user-defined tool parameters
-            self.validate_user_tool_args(user_tool, &cmd.args);
         }
 
         // Validate redirects
@@ -217,6 +354,64 @@ impl<'a> Validator<'a> {
         }
     }
 
+    /// Validate a `par` block: its statements as usual, plus a warning for
+    /// each reason the kernel will run them one after another.
+    fn validate_par(&mut self, body: &[Stmt]) {
+        let user_tools = self.user_tools;
+        let plan = plan_par(body, self.cwd, |name| user_tools.contains_key(name));
+        for reason in plan.conflicts {
+            self.issues.push(
+                ValidationIssue::warning(
+                    IssueCode::ParConflict,
+                    format!("par block runs sequentially: {reason}"),
+                )
+                .with_suggestion("move the dependent statements out of the block"),
+            );
+        }
+        for stmt in body {
+            self.validate_stmt(stmt);
+        }
+    }
+
+    /// Validate a try block and its handler. The handler sees `${ERROR}`,
+    /// which stays set after the block like any assignment.
+    fn validate_try(&mut self, try_stmt: &TryStmt) {
+        self.scope.push_frame();
+        for stmt in &try_stmt.body {
+            self.validate_stmt(stmt);
+        }
+        self.scope.pop_frame();
+
+        self.scope.bind("ERROR");
+        self.scope.push_frame();
+        for stmt in &try_stmt.catch {
+            self.validate_stmt(stmt);
+        }
+        self.scope.pop_frame();
+    }
+
+    /// Validate an event handler. Its body runs like a function's, with the
+    /// event's details as positional arguments.
+    fn validate_on(&mut self, on: &OnStmt) {
+        if HookEvent::from_name(&on.event).is_none() {
+            self.issues.push(
+                ValidationIssue::error(
+                    IssueCode::UnknownHookEvent,
+                    format!("on: unknown event '{}'", on.event),
+                )
+                .with_suggestion(format!("events: {}", HookEvent::names())),
+            );
+        }
+
+        self.function_depth += 1;
+        self.scope.push_function_frame();
+        for stmt in &on.body {
+            self.validate_stmt(stmt);
+        }
+        self.scope.pop_frame();
+        self.function_depth -= 1;
+    }
+
     /// Validate an if statement.
     fn validate_if(&mut self, if_stmt: &IfStmt) {
         self.validate_expr(&if_stmt.condition);
@@ -386,8 +581,18 @@ impl<'a> Validator<'a> {
 
     /// Validate a tool definition.
     fn validate_tool_def(&mut self, tool_def: &ToolDef) {
+        if self.registry.contains(&tool_def.name) {
+            self.issues.push(
+                ValidationIssue::warning(
+                    IssueCode::ShadowsBuiltin,
+                    format!("function '{}' shadows the builtin of the same name", tool_def.name),
+                )
+                .with_suggestion(format!("call the builtin as builtin:{}", tool_def.name)),
+            );
+        }
+
         self.function_depth += 1;
-        self.scope.push_frame();
+        self.scope.push_function_frame();
 
         // Bind parameters
         for param in &tool_def.params {
@@ -399,7 +604,7 @@ impl<'a> Validator<'a> {
         }
 
         // Validate body
-        for stmt in &tool_def.body {
+        for stmt in tool_def.body.iter() {
             self.validate_stmt(stmt);
         }
 
@@ -424,10 +629,36 @@ impl<'a> Validator<'a> {
             TestExpr::In { left, right } | TestExpr::NotIn { left, right } => {
                 self.validate_expr(left);
                 self.validate_expr(right);
+                self.check_membership_rhs(right);
             }
         }
     }
 
+    /// Reject `[[ e in X ]]` when `X` is a string or number written in the
+    /// script. `in` is collection-only (see `docs/arrays-and-hashes.md`), so
+    /// this fails every run. A `$var` or `$(cmd)` right-hand side could hold
+    /// a collection, so only literals are checked.
+    fn check_membership_rhs(&mut self, right: &Expr) {
+        let kind = match right {
+            Expr::Literal(Value::String(_)) | Expr::Interpolated(_) => "a string",
+            Expr::Literal(Value::Int(_) | Value::Float(_)) => "a number",
+            Expr::Literal(Value::Bool(_)) => "a boolean",
+            _ => return,
+        };
+        self.issues.push(
+            ValidationIssue::error(
+                IssueCode::MembershipOnScalar,
+                format!("`in` needs a list or record on the right, got {kind}"),
+            )
+            .with_suggestion(concat!(
+                "membership is for collections; for substrings use a glob or regex:\n",
+                "    if [[ $x in [a b c] ]]; then ...; fi      # list literal\n",
+                "    if [[ $s == *sub* ]]; then ...; fi        # substring (glob)\n",
+                "    if [[ $s =~ sub ]]; then ...; fi          # substring (regex)",
+            )),
+        );
+    }
+
     /// Validate an expression.
     fn validate_expr(&mut self, expr: &Expr) {
         match expr {
@@ -459,8 +690,9 @@ impl<'a> Validator<'a> {
                     self.check_var_defined(root);
                 }
             }
-            Expr::VarWithDefault { .. } => {
+            Expr::VarWithDefault { path, op, .. } => {
                 // Don't warn — the default handles the undefined/absent case.
+                self.bind_assigned_default(path, *op);
             }
             Expr::Arithmetic(_) => {
                 // Arithmetic parsing is done at runtime
@@ -480,6 +712,11 @@ impl<'a> Validator<'a> {
                     self.validate_expr(&entry.value);
                 }
             }
+            Expr::Conditional { condition, then_value, else_value } => {
+                self.validate_expr(condition);
+                self.validate_expr(then_value);
+                self.validate_expr(else_value);
+            }
         }
     }
 
@@ -495,7 +732,8 @@ impl<'a> Validator<'a> {
                         "${?.field} is removed; $? is the POSIX exit code",
                     )
                     .with_suggestion(
-                        "use `kaish-last` to read the previous command's data or stdout",
+                        "use `kaish-last` to read the previous command's data or stdout, \
+                         `kaish-last --error` for its classified error",
                     ),
                 );
                 return;
@@ -518,16 +756,33 @@ impl<'a> Validator<'a> {
         }
     }
 
+    /// Attach a node's span to the issues raised since `issues_before` that
+    /// don't carry one yet, so the innermost spanned node wins. Skipped inside
+    /// a string-embedded `$(...)`, where the enclosing command's span is the
+    /// only one that points into the source.
+    fn attach_node_span(&mut self, issues_before: usize, span: Option<Span>) {
+        let Some(span) = span else { return };
+        if self.string_subst_depth > 0 {
+            return;
+        }
+        for issue in &mut self.issues[issues_before..] {
+            if issue.span.is_none() {
+                issue.span = Some(span);
+            }
+        }
+    }
+
     /// Validate a string interpolation part.
     fn validate_string_part(&mut self, part: &StringPart) {
         match part {
             StringPart::Literal(_) => {}
             StringPart::Var(path) => self.validate_var_ref(path),
-            StringPart::VarWithDefault { default, .. } => {
+            StringPart::VarWithDefault { path, op, default } => {
                 // Validate nested parts in the default value
                 for p in default {
                     self.validate_string_part(p);
                 }
+                self.bind_assigned_default(path, *op);
             }
             StringPart::VarLength(path) => {
                 if let Some(VarSegment::Field(root)) = path.segments.first() {
@@ -537,14 +792,25 @@ impl<'a> Validator<'a> {
             StringPart::Positional(_) | StringPart::AllArgs | StringPart::ArgCount => {}
             StringPart::Arithmetic(_) => {} // Arithmetic expressions are validated at eval time
             StringPart::CommandSubst(stmts) => {
+                self.string_subst_depth += 1;
                 for stmt in stmts {
                     self.validate_stmt(stmt);
                 }
+                self.string_subst_depth -= 1;
             }
             StringPart::LastExitCode | StringPart::CurrentPid => {}
         }
     }
 
+    /// `${NAME:=word}` assigns NAME, so later references are bound.
+    fn bind_assigned_default(&mut self, path: &VarPath, op: DefaultOp) {
+        if op == DefaultOp::AssignDefault
+            && let [VarSegment::Field(name)] = path.segments.as_slice()
+        {
+            self.scope.bind(name);
+        }
+    }
+
     /// Check if a variable is defined and warn if not.
     fn check_var_defined(&mut self, name: &str) {
         // Skip underscore-prefixed vars (external/unchecked convention)
@@ -576,7 +842,7 @@ impl<'a> Validator<'a> {
         let required_count = tool_def
             .params
             .iter()
-            .filter(|p| p.default.is_none())
+            .filter(|p| p.default.is_none() && !p.variadic)
             .count();
 
         if positional_count < required_count {
@@ -588,9 +854,43 @@ impl<'a> Validator<'a> {
                 ),
             ));
         }
+
+        // A literal argument that can't take its parameter's type fails the
+        // same way on every run; dynamic values are checked at call time.
+        let literals: Vec<Option<&Value>> = args
+            .iter()
+            .filter_map(|a| match a {
+                Arg::Positional(Expr::Literal(value)) => Some(Some(value)),
+                Arg::Positional(_) | Arg::WordAssign { .. } => Some(None),
+                _ => None,
+            })
+            .collect();
+        for (i, param) in tool_def.params.iter().enumerate() {
+            let Some(ty) = &param.param_type else { continue };
+            let slots = if param.variadic {
+                literals.get(i..).unwrap_or_default()
+            } else {
+                literals.get(i..=i).unwrap_or_default()
+            };
+            for value in slots.iter().flatten() {
+                if let Err(e) = crate::interpreter::params::coerce_param((*value).clone(), ty) {
+                    self.issues.push(ValidationIssue::error(
+                        IssueCode::InvalidArgType,
+                        format!("{}: argument '{}' {e}", tool_def.name, param.name),
+                    ));
+                }
+            }
+        }
     }
 }
 
+/// The hint for an unknown command `name`: "did you mean `grep`?" with the
+/// closest of `candidates`, if any is a likely typo of it. Shared by the
+/// validator's `UndefinedCommand` warning and the kernel's exit-127 error.
+pub(crate) fn command_suggestion<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    kaish_tool_api::did_you_mean(name, candidates).map(|command| format!("did you mean `{command}`?"))
+}
+
 /// Check if a command name is static (not a variable expansion).
 ///
 /// The parser only ever produces a literal `Command.name`, so for the AST-walk
@@ -627,6 +927,8 @@ pub(crate) enum SpecialForm {
     False,
     /// `source` / `.` — execute a script in the current shell.
     Source,
+    /// `import PATH as NAME` — load a module's functions into a namespace.
+    Import,
 }
 
 impl SpecialForm {
@@ -637,6 +939,7 @@ impl SpecialForm {
             "true" => Some(Self::True),
             "false" => Some(Self::False),
             "source" | "." => Some(Self::Source),
+            "import" => Some(Self::Import),
             _ => None,
         }
     }
@@ -647,6 +950,45 @@ pub(crate) fn is_runtime_special_form(name: &str) -> bool {
     SpecialForm::from_name(name).is_some()
 }
 
+/// Explicit command namespaces. A name carrying one of these prefixes
+/// resolves in that namespace only, skipping aliases and the rest of the
+/// resolution order — `builtin:cat` reaches the builtin even when a user
+/// function named `cat` shadows it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CommandNamespace {
+    /// `builtin:` — a tool in the kernel's registry.
+    Builtin,
+    /// `user:` — a user-defined function.
+    User,
+    /// `backend:` — a tool the backend registers (embedder engines, MCP
+    /// proxies).
+    Backend,
+}
+
+impl CommandNamespace {
+    /// Split `name` into its namespace and bare name, or `None` when it
+    /// carries no known prefix (or nothing after one).
+    pub(crate) fn split(name: &str) -> Option<(Self, &str)> {
+        let (prefix, bare) = name.split_once(':')?;
+        let namespace = match prefix {
+            "builtin" => Self::Builtin,
+            "user" => Self::User,
+            "backend" => Self::Backend,
+            _ => return None,
+        };
+        (!bare.is_empty()).then_some((namespace, bare))
+    }
+
+    /// The prefix that selects this namespace, colon included.
+    pub(crate) fn prefix(self) -> &'static str {
+        match self {
+            Self::Builtin => "builtin:",
+            Self::User => "user:",
+            Self::Backend => "backend:",
+        }
+    }
+}
+
 /// Classify a command name the way the interpreter resolves it, given whether
 /// the registry and user-tool table contain it. Shared by the validator's
 /// triage and `Kernel::classify_command` so the two never diverge.
@@ -661,6 +1003,13 @@ pub(crate) fn classify_command_name(
     if is_runtime_special_form(name) {
         return CommandKind::Special;
     }
+    // A namespaced name never reaches `PATH`; backend tools run in-process
+    // from the kernel's point of view, like builtins.
+    match CommandNamespace::split(name) {
+        Some((CommandNamespace::User, _)) => return CommandKind::UserTool,
+        Some((CommandNamespace::Builtin | CommandNamespace::Backend, _)) => return CommandKind::Builtin,
+        None => {}
+    }
     // User functions are checked before builtins in `execute_command_depth`, so
     // a user function shadows a builtin of the same name.
     if is_user_tool {
@@ -942,6 +1291,7 @@ mod tests {
                 name: "nonexistent_command".to_string(),
                 args: vec![],
                 redirects: vec![],
+                span: None,
             })],
         };
 
@@ -950,6 +1300,33 @@ mod tests {
         assert!(issues.iter().any(|i| i.code == IssueCode::UndefinedCommand));
     }
 
+    #[test]
+    fn undefined_command_suggests_the_closest_builtin() {
+        let (registry, user_tools) = make_validator();
+        let check = |name: &str| {
+            let program = Program {
+                statements: vec![Stmt::Command(Command {
+                    name: name.to_string(),
+                    args: vec![],
+                    redirects: vec![],
+                    span: None,
+                })],
+            };
+            Validator::new(&registry, &user_tools)
+                .validate(&program)
+                .into_iter()
+                .find(|i| i.code == IssueCode::UndefinedCommand)
+                .and_then(|i| i.suggestion)
+        };
+
+        assert_eq!(check("grpe").as_deref(), Some("did you mean `grep`?"));
+        assert_eq!(check("builtin:cta").as_deref(), Some("did you mean `builtin:cat`?"));
+        assert_eq!(
+            check("frobnicate").as_deref(),
+            Some("this may be a script in PATH or external command")
+        );
+    }
+
     /// `test` is a first-class builtin now, so it validates through the
     /// registry like any other command — no POSIX-conditional advisory, and no
     /// spurious undefined-command warning.
@@ -966,6 +1343,7 @@ mod tests {
                     Arg::Positional(Expr::Literal(Value::String("hi".to_string()))),
                 ],
                 redirects: vec![],
+                span: None,
             })],
         };
 
@@ -988,6 +1366,7 @@ mod tests {
                     "hello".to_string(),
                 )))],
                 redirects: vec![],
+                span: None,
             })],
         };
 
@@ -1015,6 +1394,7 @@ mod tests {
                     Arg::Positional(Expr::Literal(Value::String("file.txt".to_string()))),
                 ],
                 redirects: vec![],
+                span: None,
             })],
         };
 
@@ -1069,6 +1449,7 @@ mod tests {
                     "UNDEFINED_VAR",
                 )))],
                 redirects: vec![],
+                span: None,
             })],
         };
 
@@ -1090,12 +1471,14 @@ mod tests {
                     path: VarPath::simple("MY_VAR"),
                     value: Expr::Literal(Value::String("value".to_string())),
                     local: false,
+                    span: None,
                 }),
                 // Then use it
                 Stmt::Command(Command {
                     name: "echo".to_string(),
                     args: vec![Arg::Positional(Expr::VarRef(VarPath::simple("MY_VAR")))],
                     redirects: vec![],
+                    span: None,
                 }),
             ],
         };
@@ -1118,6 +1501,7 @@ mod tests {
                 name: "echo".to_string(),
                 args: vec![Arg::Positional(Expr::VarRef(VarPath::simple("_EXTERNAL")))],
                 redirects: vec![],
+                span: None,
             })],
         };
 
@@ -1142,6 +1526,7 @@ mod tests {
                     Arg::Positional(Expr::VarRef(VarPath::simple("PWD"))),
                 ],
                 redirects: vec![],
+                span: None,
             })],
         };
 
@@ -1163,11 +1548,15 @@ mod tests {
                     Command { name: "seq".to_string(), args: vec![
                         Arg::Positional(Expr::Literal(Value::String("1".into()))),
                         Arg::Positional(Expr::Literal(Value::String("3".into()))),
-                    ], redirects: vec![] },
-                    Command { name: "scatter".to_string(), args: vec![], redirects: vec![] },
+                    ], redirects: vec![],
+                        span: None,
+ },
+                    Command { name: "scatter".to_string(), args: vec![], redirects: vec![], span: None },
                     Command { name: "echo".to_string(), args: vec![
                         Arg::Positional(Expr::Literal(Value::String("hi".into()))),
-                    ], redirects: vec![] },
+                    ], redirects: vec![],
+                        span: None,
+ },
                 ],
                 background: false,
             })],
@@ -1189,12 +1578,16 @@ mod tests {
                     Command { name: "seq".to_string(), args: vec![
                         Arg::Positional(Expr::Literal(Value::String("1".into()))),
                         Arg::Positional(Expr::Literal(Value::String("3".into()))),
-                    ], redirects: vec![] },
-                    Command { name: "scatter".to_string(), args: vec![], redirects: vec![] },
+                    ], redirects: vec![],
+                        span: None,
+ },
+                    Command { name: "scatter".to_string(), args: vec![], redirects: vec![], span: None },
                     Command { name: "echo".to_string(), args: vec![
                         Arg::Positional(Expr::Literal(Value::String("hi".into()))),
-                    ], redirects: vec![] },
-                    Command { name: "gather".to_string(), args: vec![], redirects: vec![] },
+                    ], redirects: vec![],
+                        span: None,
+ },
+                    Command { name: "gather".to_string(), args: vec![], redirects: vec![], span: None },
                 ],
                 background: false,
             })],
@@ -1215,8 +1608,10 @@ mod tests {
                     name: "input".to_string(),
                     param_type: None,
                     default: None,
+                    variadic: false,
                 }],
-                body: vec![],
+                body: Vec::new().into(),
+                module: None,
             },
         );
         user_tools
@@ -1240,6 +1635,7 @@ mod tests {
                     value: Expr::Literal(Value::String("bar".to_string())),
                 }],
                 redirects: vec![],
+                span: None,
             })],
         };
 
@@ -1265,6 +1661,7 @@ mod tests {
                 name: "mytool".to_string(),
                 args: vec![],
                 redirects: vec![],
+                span: None,
             })],
         };
 
@@ -0,0 +1,119 @@
+//! LogFs — read-only view of the session's `log` records under `/v/logs/`.
+//!
+//! ```text
+//! /v/logs/
+//! ├── kaish.log     ← one line per record
+//! └── kaish.jsonl   ← one JSON object per record
+//! ```
+//!
+//! Both files render the same [`LogBuffer`](crate::script_log::LogBuffer)
+//! on every read, so they always show its current contents.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+
+use super::{DirEntry, Filesystem};
+use crate::script_log::LogBuffer;
+
+const TEXT_FILE: &str = "kaish.log";
+const JSONL_FILE: &str = "kaish.jsonl";
+
+/// A read-only filesystem serving a [`LogBuffer`] as text and JSONL.
+pub struct LogFs {
+    logs: LogBuffer,
+}
+
+impl LogFs {
+    /// Serve `logs`.
+    pub fn new(logs: LogBuffer) -> Self {
+        Self { logs }
+    }
+
+    fn render(&self, path: &Path) -> io::Result<String> {
+        match path.to_str().unwrap_or("").trim_matches('/') {
+            TEXT_FILE => Ok(self.logs.to_text()),
+            JSONL_FILE => Ok(self.logs.to_jsonl()),
+            "" => Err(io::Error::new(io::ErrorKind::IsADirectory, "cannot read directory")),
+            other => Err(io::Error::new(io::ErrorKind::NotFound, format!("no log named '{other}'"))),
+        }
+    }
+}
+
+impl std::fmt::Debug for LogFs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LogFs").finish()
+    }
+}
+
+#[async_trait]
+impl Filesystem for LogFs {
+    async fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.render(path).map(String::into_bytes)
+    }
+
+    async fn write(&self, _path: &Path, _data: &[u8]) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::PermissionDenied, "/v/logs is read-only; write records with `log`"))
+    }
+
+    async fn list(&self, path: &Path) -> io::Result<Vec<DirEntry>> {
+        if !path.to_str().unwrap_or("").trim_matches('/').is_empty() {
+            return Err(io::Error::new(io::ErrorKind::NotADirectory, "not a directory"));
+        }
+        Ok(vec![
+            DirEntry::file(TEXT_FILE, self.logs.to_text().len() as u64),
+            DirEntry::file(JSONL_FILE, self.logs.to_jsonl().len() as u64),
+        ])
+    }
+
+    async fn stat(&self, path: &Path) -> io::Result<DirEntry> {
+        let name = path.to_str().unwrap_or("").trim_matches('/');
+        if name.is_empty() {
+            return Ok(DirEntry::directory("."));
+        }
+        let content = self.render(path)?;
+        Ok(DirEntry::file(name, content.len() as u64))
+    }
+
+    async fn mkdir(&self, _path: &Path) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::PermissionDenied, "/v/logs is read-only"))
+    }
+
+    async fn remove(&self, _path: &Path) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::PermissionDenied, "/v/logs is read-only"))
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    fn real_path(&self, _path: &Path) -> Option<PathBuf> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::script_log::{LogLevel, LogRecord};
+
+    #[tokio::test]
+    async fn serves_the_buffer_as_text_and_jsonl() {
+        let logs = LogBuffer::default();
+        let fs = LogFs::new(logs.clone());
+        assert!(fs.read(Path::new("kaish.log")).await.unwrap().is_empty());
+
+        logs.push(LogRecord::now(LogLevel::Warn, "disk low", Default::default()));
+        let text = String::from_utf8(fs.read(Path::new("/kaish.log")).await.unwrap()).unwrap();
+        assert!(text.ends_with(" WARN  disk low\n"), "{text}");
+        let jsonl = String::from_utf8(fs.read(Path::new("kaish.jsonl")).await.unwrap()).unwrap();
+        assert!(jsonl.contains(r#""level":"warn""#), "{jsonl}");
+
+        let names: Vec<String> = fs.list(Path::new("")).await.unwrap().into_iter().map(|e| e.name).collect();
+        assert_eq!(names, ["kaish.log", "kaish.jsonl"]);
+        assert_eq!(fs.stat(Path::new("kaish.log")).await.unwrap().size, text.len() as u64);
+        assert_eq!(fs.read(Path::new("other")).await.unwrap_err().kind(), io::ErrorKind::NotFound);
+        assert!(fs.write(Path::new("kaish.log"), b"x").await.is_err());
+    }
+}
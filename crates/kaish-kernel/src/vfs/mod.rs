@@ -12,7 +12,7 @@
 //!
 //! ```text
 //! /                      # kernel root
-//! ├── /v/                # MemoryFs (blobs, jobs)
+//! ├── /v/                # MemoryFs (blobs), JobFs (jobs), PipeFs (pipes), LogFs (logs)
 //! ├── /mnt/project/      # LocalFs (worktree, rw)
 //! └── /mnt/reference/    # LocalFs (repo, ro)
 //! ```
@@ -21,18 +21,63 @@
 
 mod builtin_fs;
 mod jobfs;
+mod logfs;
+mod pipefs;
 mod router;
 
 pub use builtin_fs::BuiltinFs;
 pub use jobfs::JobFs;
+pub use logfs::LogFs;
+pub use pipefs::PipeFs;
 pub use router::{MountInfo, VfsRouter};
 
+use std::path::{Component, Path, PathBuf};
+
 // The `Filesystem` trait + `LocalFs` + `MemoryFs` moved to the leaf `kaish-vfs`
 // crate so out-of-tree backends and overlay consumers can implement/compose the
 // trait without depending on the kernel. Re-exported here so existing
 // `crate::vfs::{Filesystem, DirEntry, LocalFs, MemoryFs}` paths keep working.
 // `ByteBudget` rides along so a `with_backend` embedder can name the type it
 // hands to `MemoryFs::with_budget` without a direct kaish-vfs dependency.
-pub use kaish_vfs::{ByteBudget, DevFs, DirEntry, DirEntryKind, Filesystem, MemoryFs};
+pub use kaish_vfs::{ByteBudget, DevFs, DirEntry, DirEntryKind, Filesystem, MemoryFs, SnapshotInfo};
 #[cfg(feature = "localfs")]
-pub use kaish_vfs::LocalFs;
+pub use kaish_vfs::{LocalFs, SymlinkPolicy};
+
+/// Resolve `.` and `..` in `path` lexically, without touching a filesystem.
+///
+/// An absolute path stays absolute, and `..` at the root stays at the root,
+/// as in POSIX. A relative path stays relative and keeps any leading `..` it
+/// cannot resolve (`a/../../b` is `../b`), so it is never mistaken for a path
+/// under the directory it climbs out of. The router, `cd`-style resolution,
+/// execution policy, and `archive` all normalize through here.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match out.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    out.pop();
+                }
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                Some(Component::ParentDir | Component::CurDir) | None => out.push(".."),
+            },
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_path_is_lexical() {
+        assert_eq!(normalize_path(Path::new("/a/./b/../c/")), Path::new("/a/c"));
+        assert_eq!(normalize_path(Path::new("/../a")), Path::new("/a"));
+        assert_eq!(normalize_path(Path::new("a/./b/..")), Path::new("a"));
+        assert_eq!(normalize_path(Path::new("a/../../b")), Path::new("../b"));
+        assert_eq!(normalize_path(Path::new("../../x")), Path::new("../../x"));
+    }
+}
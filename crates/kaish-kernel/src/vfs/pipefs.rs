@@ -0,0 +1,173 @@
+//! PipeFs — Named pipes as files.
+//!
+//! Serves the [`NamedPipes`](crate::scheduler::NamedPipes) table held by the
+//! JobManager:
+//!
+//! ```text
+//! /v/pipes/
+//! └── {name}   ← write queues bytes; read waits for a write, then drains
+//! ```
+//!
+//! Pipes are made with the `pipe` builtin, not by writing a new name here:
+//! a redirect typo should fail, not quietly create a pipe nobody reads.
+
+use async_trait::async_trait;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::{DirEntry, DirEntryKind, Filesystem};
+use crate::scheduler::{JobManager, PipeInfo};
+
+/// Virtual filesystem serving the session's named pipes.
+///
+/// Mounted at `/v/pipes`:
+/// - List root to see every pipe, sized by its unread bytes
+/// - Write `{name}` to queue bytes (writes append, never truncate)
+/// - Read `{name}` to wait for a write and take everything queued
+/// - Remove `{name}` to delete the pipe
+pub struct PipeFs {
+    jobs: Arc<JobManager>,
+}
+
+impl PipeFs {
+    /// Create a new PipeFs over the given JobManager's pipes.
+    pub fn new(jobs: Arc<JobManager>) -> Self {
+        Self { jobs }
+    }
+
+    /// The pipe name a path refers to; `None` for the root.
+    fn parse_path(path: &Path) -> io::Result<Option<&str>> {
+        let name = path
+            .to_str()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid pipe path"))?
+            .trim_matches('/');
+        if name.is_empty() {
+            return Ok(None);
+        }
+        if name.contains('/') {
+            return Err(io::Error::new(io::ErrorKind::NotFound, format!("no pipe named '{name}'")));
+        }
+        Ok(Some(name))
+    }
+
+    fn pipe_name(path: &Path) -> io::Result<&str> {
+        Self::parse_path(path)?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::IsADirectory, "cannot read directory"))
+    }
+}
+
+fn pipe_entry(info: PipeInfo) -> DirEntry {
+    DirEntry {
+        name: info.name,
+        kind: DirEntryKind::File,
+        modified: None,
+        permissions: None,
+        size: info.buffered as u64,
+        symlink_target: None,
+    }
+}
+
+impl std::fmt::Debug for PipeFs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PipeFs").finish()
+    }
+}
+
+#[async_trait]
+impl Filesystem for PipeFs {
+    async fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let name = Self::pipe_name(path)?;
+        self.jobs.pipes().read(name).await
+    }
+
+    async fn write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        let name = Self::pipe_name(path)?;
+        self.jobs.pipes().write(name, data)
+    }
+
+    async fn append(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        // Every write already appends; reading first would drain the pipe.
+        self.write(path, data).await
+    }
+
+    async fn list(&self, path: &Path) -> io::Result<Vec<DirEntry>> {
+        if Self::parse_path(path)?.is_some() {
+            return Err(io::Error::new(io::ErrorKind::NotADirectory, "not a directory"));
+        }
+        Ok(self.jobs.pipes().list().into_iter().map(pipe_entry).collect())
+    }
+
+    async fn stat(&self, path: &Path) -> io::Result<DirEntry> {
+        match Self::parse_path(path)? {
+            None => {
+                let name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "/".to_string());
+                Ok(DirEntry::directory(name))
+            }
+            Some(name) => self.jobs.pipes().info(name).map(pipe_entry),
+        }
+    }
+
+    async fn mkdir(&self, _path: &Path) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "pipefs holds no directories; make pipes with `pipe create`",
+        ))
+    }
+
+    async fn remove(&self, path: &Path) -> io::Result<()> {
+        let name = Self::pipe_name(path)?;
+        self.jobs.pipes().remove(name)
+    }
+
+    fn read_only(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_fs() -> (Arc<JobManager>, PipeFs) {
+        let jobs = Arc::new(JobManager::new());
+        let fs = PipeFs::new(jobs.clone());
+        (jobs, fs)
+    }
+
+    #[tokio::test]
+    async fn test_write_then_read_drains() {
+        let (jobs, fs) = make_fs();
+        jobs.pipes().create("p").unwrap();
+        fs.write(Path::new("p"), b"hello\n").await.unwrap();
+        fs.append(Path::new("p"), b"more\n").await.unwrap();
+        assert_eq!(fs.stat(Path::new("p")).await.unwrap().size, 11);
+        assert_eq!(fs.read(Path::new("p")).await.unwrap(), b"hello\nmore\n");
+        assert_eq!(fs.stat(Path::new("p")).await.unwrap().size, 0);
+    }
+
+    #[tokio::test]
+    async fn test_list_and_remove() {
+        let (jobs, fs) = make_fs();
+        jobs.pipes().create("a").unwrap();
+        jobs.pipes().create("b").unwrap();
+        let names: Vec<String> = fs.list(Path::new("")).await.unwrap().into_iter().map(|e| e.name).collect();
+        assert_eq!(names, ["a", "b"]);
+
+        fs.remove(Path::new("a")).await.unwrap();
+        assert_eq!(fs.stat(Path::new("a")).await.unwrap_err().kind(), io::ErrorKind::NotFound);
+        assert!(fs.stat(Path::new("")).await.unwrap().is_dir());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_pipe_is_not_created() {
+        let (_jobs, fs) = make_fs();
+        let err = fs.write(Path::new("typo"), b"x").await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(fs.list(Path::new("")).await.unwrap().is_empty());
+        assert_eq!(fs.read(Path::new("")).await.unwrap_err().kind(), io::ErrorKind::IsADirectory);
+    }
+}
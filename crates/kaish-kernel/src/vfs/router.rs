@@ -1,8 +1,15 @@
 //! VFS router for mount point management.
 //!
 //! Routes filesystem operations to the appropriate backend based on path.
-
-use super::{DirEntry, Filesystem};
+//!
+//! Every path is normalized before it is routed: made absolute, with `.` and
+//! `..` resolved lexically. A `..` can therefore never carry a path from the
+//! mount it names into another (`/scratch/../home/x` routes to `/home`, not
+//! to `/scratch`), and a mount only ever sees paths inside it. Keeping the
+//! path inside the mount's *own* root — symlinks on disk — is the mount's
+//! job; see `LocalFs` and its `SymlinkPolicy`.
+
+use super::{DirEntry, Filesystem, SnapshotInfo};
 use async_trait::async_trait;
 use std::collections::BTreeMap;
 use std::io;
@@ -23,6 +30,8 @@ pub use kaish_types::backend::MountInfo;
 pub struct VfsRouter {
     /// Mount points, keyed by path. Uses BTreeMap for ordered iteration.
     mounts: BTreeMap<PathBuf, Arc<dyn Filesystem>>,
+    /// Filesystem operations routed to each mount, for kernel metrics.
+    ops: std::sync::Mutex<BTreeMap<PathBuf, u64>>,
 }
 
 impl std::fmt::Debug for VfsRouter {
@@ -38,6 +47,7 @@ impl VfsRouter {
     pub fn new() -> Self {
         Self {
             mounts: BTreeMap::new(),
+            ops: std::sync::Mutex::new(BTreeMap::new()),
         }
     }
 
@@ -72,10 +82,29 @@ impl VfsRouter {
                 path: path.clone(),
                 read_only: fs.read_only(),
                 resident_bytes: fs.resident_bytes(),
+                quota_bytes: fs.quota_bytes(),
             })
             .collect()
     }
 
+    /// Filesystem operations routed to each mount point since construction
+    /// (or the last [`Self::reset_op_counts`]). Mounts that saw none are
+    /// omitted.
+    pub fn op_counts(&self) -> BTreeMap<PathBuf, u64> {
+        self.ops.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Zero the per-mount operation counts.
+    pub fn reset_op_counts(&self) {
+        self.ops.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// Normalize a VFS path: make it absolute and resolve `.` and `..`
+    /// lexically (see [`super::normalize_path`]).
+    pub fn normalize_path(path: &Path) -> PathBuf {
+        super::normalize_path(&Path::new("/").join(path))
+    }
+
     /// Normalize a mount path: ensure it starts with `/` and has no trailing slash.
     fn normalize_mount_path(path: PathBuf) -> PathBuf {
         let s = path.to_string_lossy();
@@ -118,7 +147,7 @@ impl VfsRouter {
     /// `/v` as an existing directory (the union of its child mounts) while still
     /// delegating unclaimed leaves to the embedder's backend.
     pub(crate) fn has_mount_under(&self, dir: &Path) -> bool {
-        let dir = Self::normalize_mount_path(dir.to_path_buf());
+        let dir = Self::normalize_path(dir);
         let dir_str = dir.to_string_lossy();
         self.mounts.keys().any(|mount_path| {
             let mount_str = mount_path.to_string_lossy();
@@ -136,7 +165,7 @@ impl VfsRouter {
     /// expected to be a non-root ancestor with no mount of its own; root is
     /// handled by `list_root`, which also folds in a `/` mount's real contents.
     fn list_mount_children(&self, dir: &Path) -> Vec<DirEntry> {
-        let dir = Self::normalize_mount_path(dir.to_path_buf());
+        let dir = Self::normalize_path(dir);
         let prefix = format!("{}/", dir.to_string_lossy());
         let mut seen = std::collections::HashSet::new();
         let mut entries = Vec::new();
@@ -161,16 +190,29 @@ impl VfsRouter {
             .unwrap_or_else(|| "/".to_string())
     }
 
+    /// [`Self::find_mount`] for a filesystem operation: also counts the
+    /// operation against the mount that serves it.
+    fn route(&self, path: &Path) -> io::Result<(Arc<dyn Filesystem>, PathBuf)> {
+        let (mount_path, fs, relative) = self.locate(path)?;
+        *self
+            .ops
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(mount_path)
+            .or_insert(0) += 1;
+        Ok((fs, relative))
+    }
+
     /// Find the mount point for a given path.
     ///
     /// Returns the mount and the path relative to that mount.
     fn find_mount(&self, path: &Path) -> io::Result<(Arc<dyn Filesystem>, PathBuf)> {
-        let path_str = path.to_string_lossy();
-        let normalized = if path_str.starts_with('/') {
-            path.to_path_buf()
-        } else {
-            PathBuf::from(format!("/{}", path_str))
-        };
+        self.locate(path).map(|(_, fs, relative)| (fs, relative))
+    }
+
+    /// [`Self::find_mount`], also returning the mount point itself.
+    fn locate(&self, path: &Path) -> io::Result<(PathBuf, Arc<dyn Filesystem>, PathBuf)> {
+        let normalized = Self::normalize_path(path);
 
         // Find longest matching mount point
         let mut best_match: Option<(&PathBuf, &Arc<dyn Filesystem>)> = None;
@@ -214,7 +256,7 @@ impl VfsRouter {
                         .to_string()
                 };
 
-                Ok((Arc::clone(fs), PathBuf::from(relative)))
+                Ok((mount_path.clone(), Arc::clone(fs), PathBuf::from(relative)))
             }
             None => Err(io::Error::new(
                 io::ErrorKind::NotFound,
@@ -228,7 +270,7 @@ impl VfsRouter {
 impl Filesystem for VfsRouter {
     #[tracing::instrument(level = "trace", skip(self), fields(path = %path.display()))]
     async fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
-        let (fs, relative) = self.find_mount(path)?;
+        let (fs, relative) = self.route(path)?;
         fs.read(&relative).await
     }
 
@@ -242,25 +284,31 @@ impl Filesystem for VfsRouter {
         // /dev/zero) see the requested byte count. Falling through to the trait
         // default would call our own `read` (whole file) and slice afterwards,
         // which would hang or error on an infinite device.
-        let (fs, relative) = self.find_mount(path)?;
+        let (fs, relative) = self.route(path)?;
         fs.read_range(&relative, range).await
     }
 
     #[tracing::instrument(level = "trace", skip(self, data), fields(path = %path.display(), size = data.len()))]
     async fn write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
-        let (fs, relative) = self.find_mount(path)?;
+        let (fs, relative) = self.route(path)?;
         fs.write(&relative, data).await
     }
 
+    #[tracing::instrument(level = "trace", skip(self, data), fields(path = %path.display(), size = data.len()))]
+    async fn append(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        let (fs, relative) = self.route(path)?;
+        fs.append(&relative, data).await
+    }
+
     #[tracing::instrument(level = "trace", skip(self), fields(path = %path.display()))]
     async fn list(&self, path: &Path) -> io::Result<Vec<DirEntry>> {
         // Special case: listing root might need to show mount points
-        let path_str = path.to_string_lossy();
-        if path_str.is_empty() || path_str == "/" {
+        let path = &Self::normalize_path(path);
+        if path == Path::new("/") {
             return self.list_root().await;
         }
 
-        match self.find_mount(path) {
+        match self.route(path) {
             Ok((fs, relative)) => fs.list(&relative).await,
             // Not covered by a mount, but an ancestor of one (e.g. `/v` above
             // `/v/jobs`): synthesize its child mount directories rather than 404.
@@ -277,14 +325,13 @@ impl Filesystem for VfsRouter {
     #[tracing::instrument(level = "trace", skip(self), fields(path = %path.display()))]
     async fn stat(&self, path: &Path) -> io::Result<DirEntry> {
         // Special case: root always exists
-        let path_str = path.to_string_lossy();
-        if path_str.is_empty() || path_str == "/" {
+        let path = &Self::normalize_path(path);
+        if path == Path::new("/") {
             return Ok(DirEntry::directory("/"));
         }
 
         // Check if path is a mount point itself
-        let normalized = Self::normalize_mount_path(path.to_path_buf());
-        if self.mounts.contains_key(&normalized) {
+        if self.mounts.contains_key(path) {
             let name = path
                 .file_name()
                 .map(|n| n.to_string_lossy().into_owned())
@@ -292,7 +339,7 @@ impl Filesystem for VfsRouter {
             return Ok(DirEntry::directory(name));
         }
 
-        match self.find_mount(path) {
+        match self.route(path) {
             Ok((fs, relative)) => fs.stat(&relative).await,
             // Intermediate ancestor of a mount (e.g. `/v` above `/v/jobs`)
             // exists as a synthesized directory.
@@ -307,25 +354,24 @@ impl Filesystem for VfsRouter {
     }
 
     async fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
-        let (fs, relative) = self.find_mount(path)?;
+        let (fs, relative) = self.route(path)?;
         fs.read_link(&relative).await
     }
 
     async fn symlink(&self, target: &Path, link: &Path) -> io::Result<()> {
-        let (fs, relative) = self.find_mount(link)?;
+        let (fs, relative) = self.route(link)?;
         fs.symlink(target, &relative).await
     }
 
     async fn lstat(&self, path: &Path) -> io::Result<DirEntry> {
         // Special case: root always exists
-        let path_str = path.to_string_lossy();
-        if path_str.is_empty() || path_str == "/" {
+        let path = &Self::normalize_path(path);
+        if path == Path::new("/") {
             return Ok(DirEntry::directory("/"));
         }
 
         // Check if path is a mount point itself
-        let normalized = Self::normalize_mount_path(path.to_path_buf());
-        if self.mounts.contains_key(&normalized) {
+        if self.mounts.contains_key(path) {
             let name = path
                 .file_name()
                 .map(|n| n.to_string_lossy().into_owned())
@@ -333,7 +379,7 @@ impl Filesystem for VfsRouter {
             return Ok(DirEntry::directory(name));
         }
 
-        match self.find_mount(path) {
+        match self.route(path) {
             Ok((fs, relative)) => fs.lstat(&relative).await,
             // Intermediate ancestor of a mount (e.g. `/v` above `/v/jobs`)
             // exists as a synthesized directory.
@@ -348,22 +394,22 @@ impl Filesystem for VfsRouter {
     }
 
     async fn mkdir(&self, path: &Path) -> io::Result<()> {
-        let (fs, relative) = self.find_mount(path)?;
+        let (fs, relative) = self.route(path)?;
         fs.mkdir(&relative).await
     }
 
     async fn set_mtime(&self, path: &Path, mtime: std::time::SystemTime) -> io::Result<()> {
-        let (fs, relative) = self.find_mount(path)?;
+        let (fs, relative) = self.route(path)?;
         fs.set_mtime(&relative, mtime).await
     }
 
     async fn remove(&self, path: &Path) -> io::Result<()> {
-        let (fs, relative) = self.find_mount(path)?;
+        let (fs, relative) = self.route(path)?;
         fs.remove(&relative).await
     }
 
     async fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
-        let (from_fs, from_relative) = self.find_mount(from)?;
+        let (from_fs, from_relative) = self.route(from)?;
         let (to_fs, to_relative) = self.find_mount(to)?;
 
         // Check if both paths are on the same mount by comparing Arc pointers
@@ -377,6 +423,37 @@ impl Filesystem for VfsRouter {
         from_fs.rename(&from_relative, &to_relative).await
     }
 
+    async fn snapshot(&self, path: &Path) -> io::Result<u64> {
+        let (fs, relative) = self.route(path)?;
+        fs.snapshot(&relative).await
+    }
+
+    async fn restore_snapshot(&self, path: &Path, id: u64) -> io::Result<()> {
+        let (fs, relative) = self.route(path)?;
+        fs.restore_snapshot(&relative, id).await
+    }
+
+    async fn drop_snapshot(&self, path: &Path, id: u64) -> io::Result<()> {
+        let (fs, relative) = self.route(path)?;
+        fs.drop_snapshot(&relative, id).await
+    }
+
+    async fn snapshots(&self, path: &Path) -> io::Result<Vec<SnapshotInfo>> {
+        let (mount_path, fs, relative) = self.locate(path)?;
+        let mut snapshots = fs.snapshots(&relative).await?;
+        // The filesystem names the directory it captured from its own root.
+        for snapshot in &mut snapshots {
+            let inner = snapshot.path.strip_prefix("/").unwrap_or(&snapshot.path);
+            // `join("")` would leave a trailing slash.
+            snapshot.path = if inner.as_os_str().is_empty() {
+                mount_path.clone()
+            } else {
+                mount_path.join(inner)
+            };
+        }
+        Ok(snapshots)
+    }
+
     fn read_only(&self) -> bool {
         // Router is read-only iff every mount is. Empty router returns
         // false — a router with no mounts isn't meaningfully read-only,
@@ -441,6 +518,45 @@ mod tests {
         assert_eq!(data, b"hello");
     }
 
+    #[tokio::test]
+    async fn test_op_counts_per_mount() {
+        let mut router = VfsRouter::new();
+        router.mount("/scratch", MemoryFs::new());
+        router.mount("/data", MemoryFs::new());
+
+        router.write(Path::new("/scratch/a.txt"), b"a").await.unwrap();
+        router.read(Path::new("/scratch/a.txt")).await.unwrap();
+        router.stat(Path::new("/data")).await.unwrap(); // the mount point itself
+        router.mkdir(Path::new("/data/dir")).await.unwrap();
+
+        let counts = router.op_counts();
+        assert_eq!(counts.get(Path::new("/scratch")), Some(&2));
+        assert_eq!(counts.get(Path::new("/data")), Some(&1));
+        router.reset_op_counts();
+        assert!(router.op_counts().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dot_dot_routes_to_the_mount_it_reaches() {
+        let mut router = VfsRouter::new();
+        let scratch = MemoryFs::new();
+        scratch.write(Path::new("etc/passwd"), b"scratch copy").await.unwrap();
+        router.mount("/scratch", scratch);
+        let etc = MemoryFs::new();
+        etc.write(Path::new("passwd"), b"real").await.unwrap();
+        router.mount("/etc", etc);
+
+        for hostile in ["/scratch/../etc/passwd", "/../../etc/passwd", "/scratch/./../etc//passwd", "etc/passwd"] {
+            assert_eq!(router.read(Path::new(hostile)).await.unwrap(), b"real", "{hostile}");
+        }
+        assert_eq!(router.read(Path::new("/scratch/etc/../etc/passwd")).await.unwrap(), b"scratch copy");
+
+        assert_eq!(VfsRouter::normalize_path(Path::new("a/./b/../c/")), Path::new("/a/c"));
+        assert_eq!(VfsRouter::normalize_path(Path::new("/..")), Path::new("/"));
+        assert!(router.stat(Path::new("/scratch/..")).await.unwrap().is_dir());
+        assert_eq!(router.list(Path::new("/etc/..")).await.unwrap().len(), 2);
+    }
+
     #[tokio::test]
     async fn test_multiple_mounts() {
         let mut router = VfsRouter::new();
@@ -520,7 +636,7 @@ mod tests {
     #[tokio::test]
     async fn test_list_mounts() {
         let mut router = VfsRouter::new();
-        router.mount("/scratch", MemoryFs::new());
+        router.mount("/scratch", MemoryFs::new().with_quota(64));
         router.mount("/data", MemoryFs::new());
 
         let mounts = router.list_mounts();
@@ -529,6 +645,9 @@ mod tests {
         let paths: Vec<_> = mounts.iter().map(|m| &m.path).collect();
         assert!(paths.contains(&&PathBuf::from("/scratch")));
         assert!(paths.contains(&&PathBuf::from("/data")));
+        let quotas: Vec<_> = mounts.iter().map(|m| (m.path.to_string_lossy().into_owned(), m.quota_bytes)).collect();
+        assert!(quotas.contains(&("/scratch".to_string(), Some(64))), "{quotas:?}");
+        assert!(quotas.contains(&("/data".to_string(), None)), "{quotas:?}");
     }
 
     #[tokio::test]
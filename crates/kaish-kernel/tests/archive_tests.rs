@@ -0,0 +1,76 @@
+//! The `archive` builtin: tar and tar.gz bundles created, listed, and
+//! extracted entirely through the VFS.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+#[tokio::test]
+async fn gzip_round_trip_preserves_tree_and_bytes() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(
+            r#"
+            mkdir -p /src/sub
+            echo hello > /src/a.txt
+            echo nested > /src/sub/b.txt
+            echo 'AP/+' | base64 -d > /src/blob.bin
+            ln -s a.txt /src/link
+            archive create -C /src /out/bundle.tar.gz .
+            archive extract /out/bundle.tar.gz /dest
+            cat /dest/a.txt /dest/sub/b.txt
+            readlink /dest/link
+            cmp /src/blob.bin /dest/blob.bin && echo same
+            "#,
+        )
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().lines().skip(2).collect::<Vec<_>>(), vec!["hello", "nested", "a.txt", "same"]);
+}
+
+#[tokio::test]
+async fn list_reports_entries_with_types() {
+    let kernel = kernel();
+    kernel
+        .execute("mkdir -p /w/d; echo x > /w/d/f; archive create -C /w /w.tar d")
+        .await
+        .expect("execute");
+    let result = kernel.execute("archive list /w.tar --json").await.expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    let json: serde_json::Value = serde_json::from_str(&result.text_out()).expect("json");
+    let summary: Vec<String> = json
+        .as_array()
+        .expect("array")
+        .iter()
+        .map(|e| format!("{} {} {}", e["name"].as_str().unwrap(), e["type"].as_str().unwrap(), e["size"]))
+        .collect();
+    assert_eq!(summary, vec!["d dir 0", "d/f file 2"]);
+}
+
+#[tokio::test]
+async fn absolute_inputs_keep_their_path() {
+    let kernel = kernel();
+    let result = kernel
+        .execute("mkdir -p /data; echo v > /data/v.txt; cd /; archive create /b.tar /data/v.txt; archive list /b.tar")
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert!(result.text_out().contains("data/v.txt"), "got: {result:?}");
+}
+
+#[tokio::test]
+async fn extracting_garbage_fails_loudly() {
+    let kernel = kernel();
+    let result = kernel
+        .execute("echo 'not a tar' > /junk.tar; archive extract /junk.tar /out")
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 1, "got: {result:?}");
+    assert!(result.err.contains("archive extract"), "got: {result:?}");
+}
@@ -0,0 +1,52 @@
+//! `assert` comparisons with message=/code=, and the `fail` builtin.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+#[tokio::test]
+async fn assert_on_the_last_exit_code() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"true; assert $? == 0 message="build failed"; echo reached"#)
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().trim(), "reached");
+
+    let result = kernel
+        .execute(r#"false; assert $? == 0 message="build failed" code=4"#)
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 4, "got: {result:?}");
+    assert!(result.err.contains("assert: build failed (1 == 0)"), "got: {result:?}");
+}
+
+/// `-gt` etc. arrive as operators, not as flags hoisted out of order.
+#[tokio::test]
+async fn numeric_operators_read_in_source_order() {
+    let kernel = kernel();
+    let result = kernel
+        .execute("n=5; assert $n -ge 5 && assert $n -lt 6 && echo ok")
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().trim(), "ok");
+}
+
+#[tokio::test]
+async fn fail_stops_a_set_e_script() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"set -e; fail "fixture missing" code=3; echo unreachable"#)
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 3, "got: {result:?}");
+    assert!(result.stderr_text().contains("fixture missing"), "got: {result:?}");
+    assert!(!result.text_out().contains("unreachable"));
+}
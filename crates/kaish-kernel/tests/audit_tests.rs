@@ -0,0 +1,133 @@
+//! `KernelConfig::audit_log`: every dispatched command is appended to the
+//! JSONL log with its evaluated arguments and exit code, and `kaish-audit`
+//! reads it back.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::sync::Arc;
+
+use kaish_kernel::{AuditLog, AuditRetention, Kernel, KernelConfig};
+
+fn audited_kernel() -> (Kernel, Arc<AuditLog>, tempfile::TempDir) {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let log = Arc::new(AuditLog::new(dir.path().join("audit.jsonl"), "test"));
+    let kernel = Kernel::new(KernelConfig::isolated().with_audit_log(log.clone()))
+        .expect("kernel creation");
+    (kernel, log, dir)
+}
+
+#[tokio::test]
+async fn records_evaluated_arguments_and_exit_codes() {
+    let (kernel, log, _dir) = audited_kernel();
+    kernel.execute("NAME=world; echo hello $NAME; false").await.expect("execute");
+
+    let entries = log.entries().expect("entries");
+    let echo = entries.iter().find(|e| e.command == "echo").expect("echo recorded");
+    assert_eq!(echo.argv, vec!["hello".to_string(), "world".to_string()]);
+    assert_eq!(echo.code, 0);
+    assert_eq!(echo.origin, "test");
+
+    let failed = entries.iter().find(|e| e.command == "false").expect("false recorded");
+    assert_eq!(failed.code, 1);
+}
+
+/// The argv is recorded as typed, flags where they were written.
+#[tokio::test]
+async fn records_arguments_in_source_order() {
+    let (kernel, log, _dir) = audited_kernel();
+    kernel.execute("echo cargo test --workspace").await.expect("execute");
+
+    let entries = log.entries().expect("entries");
+    let echo = entries.iter().find(|e| e.command == "echo").expect("echo recorded");
+    assert_eq!(echo.argv, vec!["cargo", "test", "--workspace"]);
+}
+
+/// Commands inside a pipeline and inside a function body are recorded; the
+/// function call itself is not a dispatched tool.
+#[tokio::test]
+async fn records_pipeline_stages_and_function_bodies() {
+    let (kernel, log, _dir) = audited_kernel();
+    kernel
+        .execute("greet() { echo hi; }; greet | wc -l")
+        .await
+        .expect("execute");
+
+    let commands: Vec<String> = log.entries().expect("entries").into_iter().map(|e| e.command).collect();
+    assert!(commands.contains(&"echo".to_string()), "got: {commands:?}");
+    assert!(commands.contains(&"wc".to_string()), "got: {commands:?}");
+    assert!(!commands.contains(&"greet".to_string()), "got: {commands:?}");
+}
+
+#[tokio::test]
+async fn kaish_audit_lists_and_prunes() {
+    let (kernel, log, _dir) = audited_kernel();
+    kernel.execute("echo one; echo two; echo three").await.expect("execute");
+
+    let listed = kernel.execute("kaish-audit list 2").await.expect("execute");
+    assert_eq!(listed.code, 0, "got: {listed:?}");
+    assert!(listed.text_out().contains("echo three"), "got: {listed:?}");
+    assert!(!listed.text_out().contains("echo one"), "got: {listed:?}");
+
+    let pruned = kernel.execute("kaish-audit prune 1").await.expect("execute");
+    assert_eq!(pruned.code, 0, "got: {pruned:?}");
+    // The prune call is recorded after it finishes, on top of the one kept.
+    assert_eq!(log.entries().expect("entries").len(), 2);
+}
+
+#[tokio::test]
+async fn kaish_audit_without_a_log_fails() {
+    let kernel = Kernel::new(KernelConfig::isolated()).expect("kernel creation");
+    let result = kernel.execute("kaish-audit list").await.expect("execute");
+    assert_eq!(result.code, 1, "got: {result:?}");
+    assert!(result.err.contains("no audit log"), "got: {result:?}");
+}
+
+#[tokio::test]
+async fn kaish_audit_prune_applies_the_configured_retention() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let log = Arc::new(
+        AuditLog::new(dir.path().join("audit.jsonl"), "test")
+            .with_retention(AuditRetention::default().with_max_entries(2)),
+    );
+    let kernel = Kernel::new(KernelConfig::isolated().with_audit_log(log.clone()))
+        .expect("kernel creation");
+    kernel.execute("echo 1; echo 2; echo 3; echo 4").await.expect("execute");
+
+    let fresh = kernel.execute("kaish-audit prune --older-than 1h").await.expect("execute");
+    assert_eq!(fresh.text_out().trim(), "pruned 0 entries", "got: {fresh:?}");
+
+    let pruned = kernel.execute("kaish-audit prune").await.expect("execute");
+    assert_eq!(pruned.code, 0, "got: {pruned:?}");
+    // Two kept, plus the prune call recorded after it finished.
+    assert_eq!(log.entries().expect("entries").len(), 3);
+}
+
+#[tokio::test]
+async fn kaish_audit_prune_needs_a_limit() {
+    let (kernel, _log, _dir) = audited_kernel();
+    let result = kernel.execute("kaish-audit prune").await.expect("execute");
+    assert_eq!(result.code, 1, "got: {result:?}");
+    assert!(result.err.contains("no retention configured"), "got: {result:?}");
+}
+
+#[tokio::test]
+async fn kaish_audit_search_finds_past_commands() {
+    let (kernel, _log, _dir) = audited_kernel();
+    kernel
+        .execute("echo cargo test --workspace; echo cargo build; echo CARGO TEST again")
+        .await
+        .expect("execute");
+
+    let result = kernel.execute(r#"kaish-audit search "cargo test" --json"#).await.expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    let hits: serde_json::Value = serde_json::from_str(&result.text_out()).expect("json");
+    let hits = hits.as_array().expect("array");
+    let ids: Vec<u64> = hits.iter().map(|h| h["id"].as_u64().unwrap()).collect();
+    assert_eq!(ids, vec![1, 3]);
+    assert_eq!(hits[0]["snippet"], "echo cargo test --workspace");
+    assert_eq!(hits[0]["origin"], "test");
+
+    let none = kernel.execute("kaish-audit search deploy").await.expect("execute");
+    assert_eq!(none.text_out().trim(), "no matches", "got: {none:?}");
+}
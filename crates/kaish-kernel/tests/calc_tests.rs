@@ -0,0 +1,64 @@
+//! The `calc` builtin: float math and aggregates, with the result in `.data`.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+#[tokio::test]
+async fn expression_expands_variables() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"A=3; B=4; calc "(${A}+${B})/2""#)
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().trim(), "3.5");
+}
+
+#[tokio::test]
+async fn aggregates_read_stdin_and_lists() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"seq 1 10 | calc sum; xs=$(fromjson '[2, 4.5, "1"]'); calc max $xs; calc avg $xs"#)
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    let out = result.text_out();
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines, vec!["55", "4.5", "2.5"]);
+}
+
+#[tokio::test]
+async fn whole_results_compare_as_integers() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"n=$(seq 1 4 | calc sum); [[ $n -gt 9 ]] && echo big"#)
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().trim(), "big");
+}
+
+#[tokio::test]
+async fn round_option() {
+    let kernel = kernel();
+    let result = kernel.execute(r#"calc --round 2 "10 / 3""#).await.expect("execute");
+    assert_eq!(result.text_out().trim(), "3.33", "got: {result:?}");
+}
+
+#[tokio::test]
+async fn errors_are_loud() {
+    let kernel = kernel();
+    let result = kernel.execute(r#"calc "1 / 0""#).await.expect("execute");
+    assert_eq!(result.code, 1, "got: {result:?}");
+    assert_eq!(result.err.trim(), "calc: division by zero");
+
+    let result = kernel.execute(r#"echo "1 two" | calc sum"#).await.expect("execute");
+    assert_eq!(result.code, 1, "got: {result:?}");
+    assert!(result.err.contains("not a number: 'two'"), "got: {result:?}");
+}
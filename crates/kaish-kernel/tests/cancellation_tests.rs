@@ -245,13 +245,12 @@ async fn kernel_cancel_kills_running_external() {
         .await
         .expect("execute");
 
-    // Cancel returns control with the kernel's "interrupted" path; exit code
-    // is 130 (SIGINT-style) on the cancellation checkpoint.
-    assert!(
-        result.code == 130 || result.code == 143,
-        "expected 130 or 143, got {}",
-        result.code,
-    );
+    // Cancel returns control with the kernel's "interrupted" path: exit code
+    // 130 (SIGINT-style), not the SIGTERM death (143) of the killed child, and
+    // `$?` for the next request agrees.
+    assert_eq!(result.code, 130, "expected 130, got {}: {}", result.code, result.err);
+    let status = kernel.execute("echo $?").await.expect("execute");
+    assert_eq!(status.text_out().trim(), "130");
 
     let pid = wait_for_pid(&pid_file, Duration::from_secs(2)).await.expect("pid_file");
     assert!(
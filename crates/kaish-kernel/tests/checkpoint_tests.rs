@@ -0,0 +1,88 @@
+//! Session checkpoints: `Kernel::create_checkpoint` / `restore_checkpoint` and
+//! the `kaish-checkpoint` builtin roll variables and cwd back to a snapshot.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::path::PathBuf;
+
+use kaish_kernel::ast::Value;
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+#[tokio::test]
+async fn restore_checkpoint_rolls_back_variables_and_cwd() {
+    let kernel = kernel();
+    kernel.execute("mkdir -p /work; X=1; export KEEP=kept").await.expect("execute");
+    let id = kernel.create_checkpoint(Some("clean")).await;
+
+    kernel.execute("X=2; NEW=3; unset KEEP; cd /work").await.expect("execute");
+    assert_eq!(kernel.cwd().await, PathBuf::from("/work"));
+
+    kernel.restore_checkpoint(id).await.expect("restore");
+    assert_eq!(kernel.get_var("X").await, Some(Value::Int(1)));
+    assert_eq!(kernel.get_var("NEW").await, None);
+    assert_eq!(kernel.get_var("KEEP").await, Some(Value::String("kept".into())));
+    assert_eq!(kernel.cwd().await, PathBuf::from("/"));
+
+    let checkpoints = kernel.checkpoints().await;
+    assert_eq!(checkpoints.len(), 1);
+    assert_eq!(checkpoints[0].label.as_deref(), Some("clean"));
+}
+
+#[tokio::test]
+async fn restoring_an_unknown_checkpoint_is_an_error() {
+    let kernel = kernel();
+    assert!(kernel.restore_checkpoint(42).await.is_err());
+}
+
+#[tokio::test]
+async fn builtin_create_and_restore_by_label() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(
+            r#"
+            STEP=one
+            kaish-checkpoint create before
+            STEP=two
+            kaish-checkpoint restore before
+            echo $STEP
+            "#,
+        )
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().lines().last(), Some("one"), "got: {result:?}");
+}
+
+/// Restoring from inside a function must not disturb the function's frame:
+/// the call still returns normally.
+#[tokio::test]
+async fn restore_inside_a_function_unwinds_cleanly() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(
+            r#"
+            V=start
+            kaish-checkpoint create base
+            rollback() { local TMP=1; V=changed; kaish-checkpoint restore base; }
+            rollback
+            echo $V
+            "#,
+        )
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().lines().last(), Some("start"), "got: {result:?}");
+}
+
+#[tokio::test]
+async fn builtin_reports_missing_checkpoints() {
+    let kernel = kernel();
+    let result = kernel.execute("kaish-checkpoint restore nope").await.expect("execute");
+    assert_eq!(result.code, 1, "got: {result:?}");
+    assert!(result.err.contains("no such checkpoint"), "got: {result:?}");
+}
@@ -22,7 +22,7 @@ async fn setup() -> Arc<Kernel> {
 /// Run a script; return (trimmed stdout, exit code, stderr).
 async fn run(k: &Kernel, script: &str) -> (String, i64, String) {
     let r = k.execute(script).await.expect("kernel execute");
-    (r.text_out().trim().to_string(), r.code, r.err.clone())
+    (r.text_out().trim().to_string(), r.code, r.stderr_text().into_owned())
 }
 
 // ── List indexing ──────────────────────────────────────────────────────────
@@ -443,6 +443,65 @@ async fn bare_default_fires_on_null_but_not_on_false_or_zero() {
     assert_eq!(out_zero, "0", "zero is present, not absent");
 }
 
+#[tokio::test]
+async fn assign_default_sets_a_bare_name_and_a_missing_key() {
+    let k = setup().await;
+    let (out, code, err) = run(&k, r#"echo ${port:=8080}; echo $port"#).await;
+    assert_eq!(code, 0, "err: {err}");
+    assert_eq!(out, "8080\n8080");
+
+    // A subscripted path writes through like the `cfg[host]=v` lvalue.
+    let (out, code, err) = run(
+        &k,
+        r#"cfg=$(fromjson '{"port":9000}'); echo "${cfg[host]:=localhost}"; echo ${cfg[host]}"#,
+    )
+    .await;
+    assert_eq!(code, 0, "err: {err}");
+    assert_eq!(out, "localhost\nlocalhost");
+}
+
+#[tokio::test]
+async fn error_if_unset_fails_with_the_word() {
+    let k = setup().await;
+    let (out, code, err) = run(&k, r#"echo ${token:?set token first}; echo after"#).await;
+    assert_ne!(code, 0, "`:?` on an unset name must fail");
+    assert!(err.contains("${token}: set token first"), "got: {err}");
+    assert!(!out.contains("after"), "got: {out}");
+
+    let (_, code, err) = run(&k, r#"cfg=$(fromjson '{}'); echo "${cfg[host]:?}""#).await;
+    assert_ne!(code, 0);
+    assert!(err.contains("${cfg[host]}: parameter null or not set"), "got: {err}");
+}
+
+#[tokio::test]
+async fn error_if_unset_stops_every_statement_kind() {
+    let k = setup().await;
+    for script in [
+        r#"X=${token:?set token first}; echo after"#,
+        r#"if echo ${token:?set token first}; then echo then; fi; echo after"#,
+        r#"if [[ -n ${token:?set token first} ]]; then echo then; fi; echo after"#,
+        r#"for i in ${token:?set token first}; do echo loop; done; echo after"#,
+        r#"echo $(echo ${token:?set token first}) | cat; echo after"#,
+    ] {
+        let (out, code, err) = run(&k, script).await;
+        assert_eq!(code, 1, "{script}: err: {err}");
+        assert!(err.contains("${token}: set token first"), "{script}: got: {err}");
+        assert!(out.is_empty(), "{script}: got: {out}");
+    }
+}
+
+#[tokio::test]
+async fn alternate_fires_only_when_present() {
+    let k = setup().await;
+    let (out, code, err) = run(
+        &k,
+        r#"cfg=$(fromjson '{"debug":true}'); echo "[${cfg[debug]:+--verbose}][${cfg[nope]:+--x}]""#,
+    )
+    .await;
+    assert_eq!(code, 0, "err: {err}");
+    assert_eq!(out, "[--verbose][]");
+}
+
 #[tokio::test]
 async fn length_of_a_subscripted_path_is_the_element_count() {
     // `${#u[tags]}` resolves the path and counts — expression position exercises
@@ -0,0 +1,71 @@
+//! Command namespaces: `builtin:`, `user:`, and `backend:` prefixes reach one
+//! kind of command past anything shadowing it, `which` reports what a name
+//! resolves to, and defining a function over a builtin draws a W009 warning.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{CommandKind, Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+async fn out(kernel: &Kernel, script: &str) -> String {
+    let result = kernel.execute(script).await.expect("execute");
+    assert!(result.ok(), "{script}: {result:?}");
+    result.text_out().into_owned()
+}
+
+#[tokio::test]
+async fn builtin_prefix_reaches_a_shadowed_builtin() {
+    let kernel = kernel();
+    out(&kernel, "echo() { builtin:echo \"wrapped: $1\"; }").await;
+
+    assert_eq!(out(&kernel, "echo hi").await, "wrapped: hi\n");
+    assert_eq!(out(&kernel, "builtin:echo hi").await, "hi\n");
+    assert_eq!(out(&kernel, "/v/bin/echo hi").await, "hi\n");
+    assert_eq!(out(&kernel, "user:echo hi").await, "wrapped: hi\n");
+    assert_eq!(kernel.classify_command("builtin:echo").await, CommandKind::Builtin);
+    assert_eq!(kernel.classify_command("user:echo").await, CommandKind::UserTool);
+}
+
+#[tokio::test]
+async fn prefixes_skip_aliases_and_do_not_fall_through() {
+    let kernel = kernel();
+    out(&kernel, "alias greet='echo alias'").await;
+    assert_eq!(out(&kernel, "greet").await, "alias\n");
+
+    let result = kernel.execute("user:greet").await.expect("execute");
+    assert_eq!(result.code, 127, "{result:?}");
+    assert!(result.err.contains("user:greet"), "{result:?}");
+
+    let result = kernel.execute("builtin:deploy").await.expect("execute");
+    assert_eq!(result.code, 127, "{result:?}");
+    let result = kernel.execute("backend:deploy").await.expect("execute");
+    assert_eq!(result.code, 127, "{result:?}");
+}
+
+#[tokio::test]
+async fn which_reports_the_resolution_order() {
+    let kernel = kernel();
+    out(&kernel, "cat() { builtin:cat; }; alias ll='ls -la'").await;
+
+    assert_eq!(out(&kernel, "which cat").await, "cat: user function");
+    assert_eq!(out(&kernel, "which -a cat").await, "cat: user function\ncat: kaish builtin");
+    assert_eq!(out(&kernel, "which builtin:cat").await, "builtin:cat: kaish builtin");
+    assert_eq!(out(&kernel, "which ll").await, "ll: aliased to ls -la");
+    assert!(!kernel.execute("which user:ls").await.expect("execute").ok());
+}
+
+#[tokio::test]
+async fn shadowing_a_builtin_warns() {
+    let kernel = kernel();
+    let result = kernel.execute("head() { echo mine; }").await.expect("execute");
+    assert!(result.ok(), "{result:?}");
+    assert!(result.err.contains("function 'head' shadows the builtin"), "{result:?}");
+    assert!(result.err.contains("builtin:head"), "{result:?}");
+
+    let result = kernel.execute("deploy() { echo mine; }").await.expect("execute");
+    assert!(!result.err.contains("shadows"), "{result:?}");
+}
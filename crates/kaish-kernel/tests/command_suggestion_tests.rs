@@ -0,0 +1,41 @@
+//! Exit-127 errors name the closest known command — builtin, user tool, or
+//! alias — so a typo comes back with its fix.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+#[tokio::test]
+async fn typo_of_a_builtin_suggests_it() {
+    let kernel = kernel();
+    let result = kernel.execute("grpe x /v/notes.txt").await.expect("execute");
+    assert_eq!(result.code, 127, "{result:?}");
+    assert!(result.err.contains("command not found: grpe (did you mean `grep`?)"), "{result:?}");
+}
+
+#[tokio::test]
+async fn user_tools_and_aliases_are_candidates() {
+    let kernel = kernel();
+    kernel.execute("deploy_site() { echo deployed; }").await.expect("execute");
+    kernel.execute("alias lsl='ls -l'").await.expect("execute");
+
+    let result = kernel.execute("deploy_stie").await.expect("execute");
+    assert!(result.err.contains("did you mean `deploy_site`?"), "{result:?}");
+
+    let result = kernel.execute("lsll").await.expect("execute");
+    assert!(result.err.contains("did you mean `lsl`?"), "{result:?}");
+}
+
+#[tokio::test]
+async fn nothing_close_means_no_suggestion() {
+    let kernel = kernel();
+    let result = kernel.execute("frobnicate-widgets").await.expect("execute");
+    assert_eq!(result.code, 127, "{result:?}");
+    assert!(result.err.contains("command not found: frobnicate-widgets"), "{result:?}");
+    assert!(!result.err.contains("did you mean"), "{result:?}");
+}
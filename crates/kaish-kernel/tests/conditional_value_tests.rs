@@ -0,0 +1,82 @@
+//! Conditional values: `X=if COND then A [elif …] else B fi` at assignment
+//! value position. See docs/LANGUAGE.md, "Conditional values".
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+/// Run a script; return (trimmed stdout, exit code, stderr).
+async fn run(k: &Kernel, script: &str) -> (String, i64, String) {
+    let r = k.execute(script).await.expect("kernel execute");
+    (r.text_out().trim().to_string(), r.code, r.err.clone())
+}
+
+#[tokio::test]
+async fn picks_the_branch_the_condition_selects() {
+    let k = kernel();
+    for (n, expected) in [(7, "big"), (3, "small")] {
+        let script = format!("N={n}; SIZE=if [[ $N -gt 5 ]] then big else small fi; echo $SIZE");
+        let (out, code, err) = run(&k, &script).await;
+        assert_eq!(code, 0, "err: {err}");
+        assert_eq!(out, expected);
+    }
+}
+
+#[tokio::test]
+async fn elif_chains_and_multiple_lines() {
+    let k = kernel();
+    let script = "N=50\n\
+                  TIER=if [[ $N -gt 100 ]]\n\
+                  then high\n\
+                  elif [[ $N -gt 10 ]]; then mid\n\
+                  else low\n\
+                  fi\n\
+                  echo $TIER";
+    let (out, code, err) = run(&k, script).await;
+    assert_eq!(code, 0, "err: {err}");
+    assert_eq!(out, "mid");
+}
+
+#[tokio::test]
+async fn branches_take_any_value() {
+    let k = kernel();
+    let (out, code, err) =
+        run(&k, "ENV=prod; PORTS=if [[ $ENV == prod ]]; then [443]; else [8080 8443]; fi; echo $PORTS").await;
+    assert_eq!(code, 0, "err: {err}");
+    assert_eq!(out, "[443]");
+
+    let (out, _, err) = run(&k, r#"NAME=if false; then "a b"; else "$ENV-$(echo x)"; fi; echo $NAME"#).await;
+    assert_eq!(out, "prod-x", "err: {err}");
+}
+
+#[tokio::test]
+async fn command_conditions_and_locals() {
+    let k = kernel();
+    run(&k, "echo apple > /v/fruit.txt").await;
+    let script = "pick() { local FOUND=if grep -q $1 /v/fruit.txt; then yes; else no; fi; echo $FOUND; }\n\
+                  pick apple; pick pear";
+    let (out, code, err) = run(&k, script).await;
+    assert_eq!(code, 0, "err: {err}");
+    assert_eq!(out, "yes\nno");
+}
+
+#[tokio::test]
+async fn only_the_chosen_branch_runs() {
+    let k = kernel();
+    let (out, _, err) = run(&k, "X=if false; then $(echo ran > /v/ran.txt); else skipped; fi; echo $X").await;
+    assert_eq!(out, "skipped", "err: {err}");
+    let (out, _, _) = run(&k, "if [[ -f /v/ran.txt ]]; then echo ran; else echo absent; fi").await;
+    assert_eq!(out, "absent");
+}
+
+#[tokio::test]
+async fn else_is_required() {
+    let k = kernel();
+    let result = k.execute("X=if true; then a; fi").await;
+    assert!(result.is_err() || result.is_ok_and(|r| r.code != 0));
+}
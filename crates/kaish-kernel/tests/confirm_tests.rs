@@ -0,0 +1,38 @@
+//! `confirm`: the answer to a yes/no question lands in `$?`, read from
+//! `--yes` or stdin; a headless kernel with neither fails instead of blocking.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+#[tokio::test]
+async fn piped_answer_sets_the_exit_code() {
+    let kernel = kernel();
+    let yes = kernel.execute("echo y | confirm 'Delete 42 files?'").await.expect("execute");
+    assert_eq!(yes.code, 0, "{yes:?}");
+    let no = kernel.execute("echo n | confirm 'Delete 42 files?'").await.expect("execute");
+    assert_eq!(no.code, 1, "{no:?}");
+}
+
+#[tokio::test]
+async fn yes_flag_answers_without_asking() {
+    let kernel = kernel();
+    let result = kernel
+        .execute("if confirm --yes 'Proceed?'; then echo go; else echo stop; fi")
+        .await
+        .expect("execute");
+    assert_eq!(result.text_out(), "go\n");
+}
+
+#[tokio::test]
+async fn headless_without_stdin_fails_instead_of_blocking() {
+    let kernel = kernel();
+    let result = kernel.execute("confirm 'Proceed?'").await.expect("execute");
+    assert_eq!(result.code, 1);
+    assert!(result.err.contains("--yes"), "{result:?}");
+}
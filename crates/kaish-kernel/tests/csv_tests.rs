@@ -0,0 +1,65 @@
+//! The `csv` builtin: CSV/TSV text into typed rows and back.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+#[tokio::test]
+async fn parse_yields_records_keyed_by_header() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"printf 'name,id\nada,007\n"lin, b",2\n' > /u.csv; csv parse /u.csv | jq -c '.'"#)
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(
+        result.text_out().trim(),
+        r#"[{"name":"ada","id":"007"},{"name":"lin, b","id":"2"}]"#
+    );
+}
+
+#[tokio::test]
+async fn tsv_round_trips_through_write() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"printf 'a\tb\n1\tx y\n' | csv parse --tsv | csv write"#)
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out(), "a,b\n1,x y\n");
+}
+
+#[tokio::test]
+async fn write_unions_keys_and_quotes() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"rows=$(fromjson '[{"a":"1"},{"b":"say \"hi\"","a":null}]'); csv write $rows"#)
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out(), "a,b\n1,\n,\"say \"\"hi\"\"\"\n");
+}
+
+#[tokio::test]
+async fn no_header_parses_lists() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"printf '1;2\n3;4\n' | csv parse --no-header -d ';' | jq -c '.'"#)
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().trim(), r#"[["1","2"],["3","4"]]"#);
+}
+
+#[tokio::test]
+async fn ragged_rows_fail_loudly() {
+    let kernel = kernel();
+    let result = kernel.execute(r#"printf 'a,b\n1\n' | csv parse"#).await.expect("execute");
+    assert_eq!(result.code, 1, "got: {result:?}");
+    assert!(result.err.contains("line 2: expected 2 fields, found 1"), "got: {result:?}");
+}
@@ -0,0 +1,65 @@
+//! `distill` and `Kernel::distill`: the requests since a checkpoint come back
+//! as a script — failures dropped, session commands left out — that runs.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::distill::DistillOptions;
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+async fn run_session(kernel: &Kernel) {
+    kernel.execute("echo before").await.expect("execute");
+    kernel.execute("kaish-checkpoint create start").await.expect("execute");
+    kernel.execute("mkdir -p /v/out").await.expect("execute");
+    kernel.execute("no-such-command x").await.expect("execute");
+    kernel.execute("echo \"hello\" > /v/out/a.txt").await.expect("execute");
+}
+
+#[tokio::test]
+async fn distill_scripts_the_work_since_the_checkpoint() {
+    let kernel = kernel();
+    run_session(&kernel).await;
+
+    let result = kernel.execute("distill").await.expect("execute");
+    assert!(result.ok(), "{result:?}");
+    assert_eq!(
+        result.text_out(),
+        "#!/usr/bin/env kaish\n\nmkdir -p /v/out\necho \"hello\" > /v/out/a.txt\n"
+    );
+
+    let commented = kernel.execute("distill --comment-failed").await.expect("execute");
+    assert!(commented.text_out().contains("# failed (exit 127):\n# no-such-command x\n"), "{commented:?}");
+}
+
+#[tokio::test]
+async fn parameterized_scripts_still_run() {
+    let session = kernel();
+    run_session(&session).await;
+    let options = DistillOptions { parameterize: true, ..Default::default() };
+    let script = session.distill(Some("start"), &options).await.expect("distill");
+    assert!(script.contains("ARG1='/v/out'\n"), "{script}");
+    assert!(script.contains("echo \"$ARG2\" > \"$ARG3\"\n"), "{script}");
+
+    let fresh = kernel();
+    let result = fresh.execute(&script).await.expect("execute");
+    assert!(result.ok(), "{script}\n{result:?}");
+    let written = fresh.execute("cat /v/out/a.txt").await.expect("execute");
+    assert_eq!(written.text_out(), "hello\n");
+}
+
+#[tokio::test]
+async fn without_checkpoints_the_whole_history_counts() {
+    let kernel = kernel();
+    kernel.execute("echo one").await.expect("execute");
+    let script = kernel.distill(None, &DistillOptions::default()).await.expect("distill");
+    assert_eq!(script, "#!/usr/bin/env kaish\n\necho one\n");
+
+    assert!(kernel.distill(Some("nope"), &DistillOptions::default()).await.is_err());
+    let result = kernel.execute("distill --since nope").await.expect("execute");
+    assert!(result.err.contains("no such checkpoint: nope"), "{result:?}");
+    assert_eq!(kernel.history().await.last().map(|e| e.code), Some(Some(1)));
+}
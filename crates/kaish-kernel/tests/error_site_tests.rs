@@ -0,0 +1,35 @@
+//! Runtime errors point at the statement that raised them: the parser keeps
+//! each command's and assignment's source span, and `execute` renders the
+//! innermost one as `at line L, col C in `<source>``.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+#[tokio::test]
+async fn assignment_error_names_line_and_column() {
+    let kernel = kernel();
+    let err = kernel
+        .execute("echo ok\n  X=$((1 / 0))\necho never")
+        .await
+        .expect_err("division by zero in an assignment is an error");
+    let msg = format!("{err:#}");
+    assert!(msg.contains("division by zero"), "got: {msg}");
+    assert!(msg.contains("at line 2, col 3 in `X=$((1 / 0))`"), "got: {msg}");
+}
+
+#[tokio::test]
+async fn nested_statement_error_points_inside_the_block() {
+    let kernel = kernel();
+    let err = kernel
+        .execute("if true; then\n  echo ok\n  Y=$((2 / 0))\nfi")
+        .await
+        .expect_err("division by zero in an assignment is an error");
+    let msg = format!("{err:#}");
+    assert!(msg.contains("at line 3, col 3 in `Y=$((2 / 0))`"), "got: {msg}");
+}
@@ -0,0 +1,45 @@
+//! `Kernel::execute_typed`: a script's `.data` deserialized into a Rust type.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+use serde::Deserialize;
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Repo {
+    name: String,
+    stars: u32,
+}
+
+#[tokio::test]
+async fn data_deserializes_into_the_requested_type() {
+    let kernel = kernel();
+    let repo: Repo = kernel
+        .execute_typed(r#"echo '{"name":"kaish","stars":3}' | jq '.'"#)
+        .await
+        .expect("typed result");
+    assert_eq!(repo, Repo { name: "kaish".into(), stars: 3 });
+
+    let text: String = kernel.execute_typed("printf hello --json").await.expect("typed result");
+    assert_eq!(text, "hello");
+}
+
+#[tokio::test]
+async fn missing_or_mismatched_data_is_an_error() {
+    let kernel = kernel();
+
+    // Stdout is never sniffed, even when it happens to be JSON.
+    let err = kernel.execute_typed::<Repo>(r#"echo '{"name":"kaish","stars":3}'"#).await.unwrap_err();
+    assert!(err.to_string().contains("--json"), "{err:#}");
+
+    let err = kernel.execute_typed::<Repo>("false").await.unwrap_err();
+    assert!(err.to_string().contains("exit 1"), "{err:#}");
+
+    let err = kernel.execute_typed::<Repo>(r#"echo '[1,2]' | jq '.'"#).await.unwrap_err();
+    assert!(err.to_string().contains("requested type"), "{err:#}");
+}
@@ -429,6 +429,58 @@ async fn pipeline_builtin_to_builtin() {
     assert_eq!(lines, vec!["1", "2", "3"], "Should have first 3: {:?}", lines);
 }
 
+#[tokio::test]
+async fn endless_external_stops_once_head_is_done() {
+    // `yes` never exits on its own. Its stdout streams into the pipe rather
+    // than being captured whole, so once `head` has its lines and drops the
+    // reader, `yes` takes SIGPIPE and the pipeline finishes.
+    let kernel = repl_kernel();
+    let result = tokio::time::timeout(Duration::from_secs(10), kernel.execute("yes | head -n 3"))
+        .await
+        .expect("yes | head must terminate")
+        .expect("execute");
+    assert!(result.ok(), "pipeline should succeed: {:?}", result);
+    assert_eq!(result.text_out(), "y\ny\ny\n");
+}
+
+#[tokio::test]
+async fn endless_external_stops_when_downstream_never_reads() {
+    // `echo` ignores its stdin; the runner closes the unread pipe when the
+    // stage returns so `yes` sees a broken pipe instead of blocking forever.
+    let kernel = repl_kernel();
+    let result = tokio::time::timeout(Duration::from_secs(10), kernel.execute("yes | echo done"))
+        .await
+        .expect("yes | echo must terminate")
+        .expect("execute");
+    assert_eq!(result.text_out().trim(), "done");
+}
+
+#[tokio::test]
+async fn merged_stderr_of_an_external_stage_reaches_the_pipe() {
+    let kernel = repl_kernel();
+    let result = kernel
+        .execute("sh -c 'echo o; echo e >&2' 2>&1 | cat")
+        .await
+        .unwrap();
+    assert!(result.ok(), "pipeline should succeed: {:?}", result);
+    let out = result.text_out();
+    assert!(out.contains("o\n"), "stdout reached cat: {:?}", result);
+    assert!(out.contains("e\n"), "merged stderr reached cat: {:?}", result);
+}
+
+#[tokio::test]
+async fn redirected_first_stage_does_not_stream_into_the_pipe() {
+    let kernel = repl_kernel();
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("out.txt").to_string_lossy().to_string();
+    let result = kernel
+        .execute(&format!("sh -c 'echo hi' > {path} | wc -l"))
+        .await
+        .unwrap();
+    assert_eq!(result.text_out().trim(), "0", "stdout went to the file: {:?}", result);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "hi\n");
+}
+
 // ============================================================================
 // Environment Variable Tests
 // ============================================================================
@@ -719,7 +771,7 @@ async fn external_binary_output_redirects_raw() {
         .execute(&format!(r#"sh -c 'printf "\377\376\375\374"' > {p}; dd if={p} of=/dev/null"#))
         .await
         .unwrap();
-    assert!(r.err.contains("4 bytes copied"), "raw redirect size: {}", r.err);
+    assert!(r.stderr.contains("4 bytes copied"), "raw redirect size: {}", r.stderr);
 }
 
 #[cfg(target_os = "linux")]
@@ -773,14 +825,14 @@ async fn external_stdout_overflow_is_loud_by_default() {
         "the child's own exit status (0) should be preserved as original_code"
     );
     assert!(
-        result.err.contains("stdout truncated"),
+        result.stderr.contains("stdout truncated"),
         "stderr should carry a loud truncation marker: {}",
-        result.err
+        result.stderr
     );
     assert!(
-        result.err.contains("output-limit"),
+        result.stderr.contains("output-limit"),
         "marker should point at the fix (enable output-limit to spill to disk): {}",
-        result.err
+        result.stderr
     );
 
     // Stdout is the tail window, capped at the ring size, and never
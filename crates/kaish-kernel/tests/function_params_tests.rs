@@ -0,0 +1,63 @@
+//! Declared function parameters: `name(a b:int=2 rest:string...)` binds each
+//! positional to a local variable, coerces it to the declared type, collects
+//! a trailing variadic into a list, and fails a mismatched call with a message
+//! naming the parameter.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+async fn out(kernel: &Kernel, script: &str) -> String {
+    let result = kernel.execute(script).await.expect("execute");
+    assert!(result.ok(), "{script}: {result:?}");
+    result.text_out().into_owned()
+}
+
+#[tokio::test]
+async fn parameters_bind_with_defaults() {
+    let kernel = kernel();
+    out(&kernel, "scale(name replicas:int=2) { echo \"$name x$replicas\"; echo $(( replicas * 10 )); }").await;
+
+    assert_eq!(out(&kernel, "scale web").await, "web x2\n20\n");
+    assert_eq!(out(&kernel, "scale api \"3\"").await, "api x3\n30\n");
+    // Parameters are locals: nothing leaks into the caller.
+    assert_eq!(out(&kernel, "echo \"[${name:-unset}]\"").await, "[unset]\n");
+    // Positionals are still set alongside the named bindings.
+    assert_eq!(out(&kernel, "both(a) { echo \"$a $1 $#\"; }; both x y").await, "x x 2\n");
+}
+
+#[tokio::test]
+async fn variadic_collects_the_rest_as_a_list() {
+    let kernel = kernel();
+    out(&kernel, "total(label nums:int...) { echo \"$label ${#nums}\"; for n in $(values $nums); do echo $n; done; }").await;
+
+    assert_eq!(out(&kernel, "total sum 1 2 3").await, "sum 3\n1\n2\n3\n");
+    assert_eq!(out(&kernel, "total none").await, "none 0\n");
+    assert_eq!(out(&kernel, "each(args: array...) { echo ${#args}; }; each a b").await, "2\n");
+}
+
+#[tokio::test]
+async fn type_mismatches_fail_with_the_parameter_name() {
+    let kernel = kernel();
+    out(&kernel, "wait_for(seconds:int) { echo $seconds; }").await;
+
+    let result = kernel.execute("x=soon; wait_for $x").await.expect("execute");
+    assert_eq!(result.code, 2, "{result:?}");
+    assert!(result.err.contains("wait_for: argument 'seconds' expects int, got 'soon'"), "{result:?}");
+
+    // Literal mismatches and missing arguments are caught before anything runs.
+    let result = kernel.execute("echo ran; wait_for").await.expect("execute");
+    assert!(!result.ok(), "{result:?}");
+    assert!(!result.text_out().contains("ran"), "{result:?}");
+    assert!(result.err.contains("'wait_for' requires 1 arguments"), "{result:?}");
+
+    let result = kernel.execute("echo ran; wait_for soon").await.expect("execute");
+    assert!(!result.ok(), "{result:?}");
+    assert!(!result.text_out().contains("ran"), "{result:?}");
+    assert!(result.err.contains("expects int, got 'soon'"), "{result:?}");
+}
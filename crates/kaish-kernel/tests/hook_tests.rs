@@ -0,0 +1,151 @@
+//! Event hooks: `on EVENT { ... }` handlers run with the event's details as
+//! positional arguments, and an embedder's `KernelHooks` callbacks see every
+//! command and assignment, and tools' progress reports.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::sync::{Arc, Mutex};
+
+use kaish_kernel::ast::Value;
+use kaish_kernel::{Kernel, KernelConfig, KernelHooks};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+async fn out(kernel: &Kernel, script: &str) -> String {
+    let result = kernel.execute(script).await.expect("execute");
+    assert!(result.ok(), "{script}: {result:?}");
+    result.text_out().into_owned()
+}
+
+#[tokio::test]
+async fn command_error_handler_sees_the_failure() {
+    let kernel = kernel();
+    out(&kernel, "on command_error { echo \"[$1] $2: $3 ($?)\"; }").await;
+
+    assert_eq!(out(&kernel, "false; echo after").await, "[false] 1: exit code 1 (1)\nafter\n");
+    assert_eq!(out(&kernel, "echo x | false; echo after").await, "[echo | false] 1: exit code 1 (1)\nafter\n");
+
+    let output = out(&kernel, "cat /no/such/file; echo after").await;
+    assert!(output.starts_with("[cat] 1: "), "{output}");
+    assert!(output.contains("/no/such/file"), "{output}");
+
+    // The failing status survives the handler.
+    let result = kernel.execute("false").await.expect("execute");
+    assert_eq!(result.code, 1, "{result:?}");
+    assert_eq!(out(&kernel, "false; echo $?").await, "[false] 1: exit code 1 (1)\n1\n");
+}
+
+#[tokio::test]
+async fn handled_failures_raise_nothing() {
+    let kernel = kernel();
+    out(&kernel, "on command_error { echo raised; }").await;
+    assert_eq!(out(&kernel, "false || echo recovered").await, "recovered\n");
+    assert_eq!(out(&kernel, "false && echo never; echo next").await, "next\n");
+    assert_eq!(out(&kernel, "if false; then echo yes; else echo no; fi").await, "no\n");
+}
+
+#[tokio::test]
+async fn handlers_do_not_reenter() {
+    let kernel = kernel();
+    out(&kernel, "on command_error { false; echo handled; }").await;
+    let result = kernel.execute("false").await.expect("execute");
+    assert_eq!(result.code, 1, "{result:?}");
+    assert_eq!(result.text_out(), "handled\n");
+}
+
+#[tokio::test]
+async fn unset_parameter_in_a_handler_stops_only_the_handler() {
+    let kernel = kernel();
+    out(&kernel, "on command_error { echo ${missing:?no detail}; echo handled; }").await;
+    assert_eq!(out(&kernel, "false; echo after").await, "after\n");
+}
+
+#[tokio::test]
+async fn cwd_change_and_removal() {
+    let kernel = kernel();
+    out(&kernel, "mkdir -p /tmp/sub; cd /").await;
+    out(&kernel, "on cwd_change { echo \"$1 -> $2\"; }").await;
+
+    assert_eq!(out(&kernel, "cd /tmp/sub").await, "/ -> /tmp/sub\n");
+    // Staying put is not a change.
+    assert_eq!(out(&kernel, "cd .").await, "");
+
+    out(&kernel, "on cwd_change {}").await;
+    assert_eq!(out(&kernel, "cd /").await, "");
+}
+
+#[tokio::test]
+async fn unknown_event_is_rejected() {
+    let kernel = kernel();
+    let result = kernel.execute("echo ran; on comand_error { echo x; }").await.expect("execute");
+    assert!(!result.ok(), "{result:?}");
+    assert!(!result.text_out().contains("ran"), "{result:?}");
+    assert!(result.err.contains("unknown event 'comand_error'"), "{result:?}");
+    assert!(result.err.contains("command_error"), "{result:?}");
+}
+
+#[tokio::test]
+async fn embedder_callbacks_see_commands_and_assignments() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let (pre, post, var) = (seen.clone(), seen.clone(), seen.clone());
+    let hooks = KernelHooks::new()
+        .on_pre_exec(move |name| pre.lock().unwrap().push(format!("pre {name}")))
+        .on_post_exec(move |name, result, _| post.lock().unwrap().push(format!("post {name} {}", result.code)))
+        .on_var_change(move |name, _| var.lock().unwrap().push(format!("var {name}")));
+    let kernel = Kernel::new(KernelConfig::isolated().with_hooks(hooks)).expect("kernel creation");
+
+    kernel.execute("X=1; echo $X; false").await.expect("execute");
+    kernel.set_var("Y", Value::Bool(true)).await;
+    assert_eq!(
+        *seen.lock().unwrap(),
+        ["var X", "pre echo", "post echo 0", "pre false", "post false 1", "var Y"]
+    );
+}
+
+#[tokio::test]
+async fn progress_reaches_the_embedder() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = seen.clone();
+    let hooks = KernelHooks::new().on_progress(move |name, progress| {
+        sink.lock().unwrap().push(format!("{name} {:?} {:?}", progress.percent, progress.message));
+    });
+    let kernel = Kernel::new(KernelConfig::isolated().with_hooks(hooks)).expect("kernel creation");
+
+    out(&kernel, "mkdir -p /tmp; echo a > /tmp/a; echo b > /tmp/b").await;
+    out(&kernel, "archive create /tmp/x.tar /tmp/a /tmp/b").await;
+    assert_eq!(
+        *seen.lock().unwrap(),
+        [
+            r#"archive Some(0.0) Some("reading /tmp/a")"#,
+            r#"archive Some(50.0) Some("reading /tmp/b")"#,
+        ]
+    );
+
+    // Pipeline stages share the hooks.
+    seen.lock().unwrap().clear();
+    out(&kernel, "archive extract /tmp/x.tar /out | cat").await;
+    assert_eq!(seen.lock().unwrap().len(), 2, "{:?}", seen.lock().unwrap());
+}
+
+#[tokio::test]
+async fn copies_and_scatter_workers_report_progress() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = seen.clone();
+    let hooks = KernelHooks::new().on_progress(move |name, progress| {
+        sink.lock().unwrap().push(format!("{name} {:?} {:?}", progress.percent, progress.message));
+    });
+    let kernel = Kernel::new(KernelConfig::isolated().with_hooks(hooks)).expect("kernel creation");
+
+    out(&kernel, "mkdir -p /tmp/src; echo a > /tmp/src/a; cp -r /tmp/src /tmp/dst").await;
+    assert_eq!(*seen.lock().unwrap(), [r#"cp None Some("1 file: /tmp/dst/a")"#]);
+
+    // Workers finish in any order; the count still climbs to the total.
+    seen.lock().unwrap().clear();
+    out(&kernel, "seq 1 4 | scatter | echo $ITEM | gather").await;
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 4, "{seen:?}");
+    assert_eq!(seen[3], r#"scatter Some(100.0) Some("4/4 items")"#);
+}
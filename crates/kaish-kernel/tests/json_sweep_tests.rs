@@ -76,10 +76,12 @@ const SKIPS: &[Skip] = &[
 
 const CASES: &[Case] = &[
     Case { name: "alias", setup: &["alias g=grep"], cmd: "alias --json", expect: Expect::Array },
+    Case { name: "archive", setup: &["archive create tmp/a.tar tmp/data.json"], cmd: "archive list tmp/a.tar --json", expect: Expect::Array },
     Case { name: "assert", setup: &[], cmd: "assert 1 --json", expect: Expect::Empty },
     Case { name: "awk", setup: &[], cmd: r#"printf 'a b\nc d\n' | awk '{print $1}' --json"#, expect: Expect::String },
     Case { name: "base64", setup: &[], cmd: "echo hi | base64 --json", expect: Expect::String },
     Case { name: "basename", setup: &[], cmd: "basename /a/b.txt --json", expect: Expect::String },
+    Case { name: "calc", setup: &[], cmd: "seq 1 4 | calc sum --json", expect: Expect::Number },
     Case { name: "cat", setup: &[], cmd: "cat tmp/data.json --json", expect: Expect::String },
     // Pins the error-envelope contract: a failure carrying a diagnostic still
     // honors --json, emitting {"error","code"} rather than leaking plain text.
@@ -88,24 +90,30 @@ const CASES: &[Case] = &[
     Case { name: "checksum", setup: &[], cmd: "checksum tmp/data.json --json", expect: Expect::Array },
     // Identical files → exit 0, no output (clean success untouched by --json).
     Case { name: "cmp", setup: &[], cmd: "cmp tmp/data.json tmp/data.json --json", expect: Expect::Empty },
+    Case { name: "confirm", setup: &[], cmd: "confirm --yes Proceed --json", expect: Expect::Empty },
     Case { name: "cp", setup: &[], cmd: "cp tmp/data.json tmp/copy.json --json", expect: Expect::Empty },
     // cut populates `.data` with a per-line array (the same structure that
     // drives `for v in $(cut …)`), so `--json` surfaces that array, not the
     // text-flattened scalar.
+    Case { name: "csv", setup: &[], cmd: r#"printf 'a,b\n1,2\n' | csv parse --json"#, expect: Expect::Array },
     Case { name: "cut", setup: &[], cmd: r#"printf 'a,b\n' | cut -d ',' -f 1 --json"#, expect: Expect::Array },
     Case { name: "date", setup: &[], cmd: "date --json", expect: Expect::Object },
     // dd with no of= emits a Bytes result; --json renders the base64 envelope
     // (an object). Reads a finite fixture — never a real /dev device, which
     // would hang here in passthrough mode.
     Case { name: "dd", setup: &[], cmd: "dd if=tmp/data.json bs=4 count=1 --json", expect: Expect::Object },
+    Case { name: "df", setup: &[], cmd: "df --json", expect: Expect::Object },
     // Identical files → exit 0 with empty *text*, but `--json` still emits a
     // consistent object (`{old_file, new_file, differ:false, hunks:[]}`) so a
     // consumer iterating file pairs always parses an object, never "".
     Case { name: "diff", setup: &[], cmd: "diff tmp/data.json tmp/data.json --json", expect: Expect::Object },
     Case { name: "dirname", setup: &[], cmd: "dirname /a/b.txt --json", expect: Expect::String },
+    Case { name: "dirs", setup: &["pushd src"], cmd: "dirs --json", expect: Expect::Array },
+    Case { name: "distill", setup: &[], cmd: "distill --json", expect: Expect::String },
     Case { name: "echo", setup: &[], cmd: "echo hi --json", expect: Expect::String },
     Case { name: "env", setup: &["export FOO=bar"], cmd: "env --json", expect: Expect::String },
     Case { name: "export", setup: &[], cmd: "export FOO=bar --json", expect: Expect::Empty },
+    Case { name: "fail", setup: &[], cmd: "fail oops --code 3 --json", expect: Expect::FailsEnvelope(3) },
     Case { name: "false", setup: &[], cmd: "false --json", expect: Expect::FailsClean(1) },
     Case { name: "file", setup: &[], cmd: "file tmp/data.json --json", expect: Expect::Array },
     Case { name: "find", setup: &[], cmd: "find src -name '*.rs' --json", expect: Expect::Array },
@@ -144,12 +152,18 @@ const CASES: &[Case] = &[
     // apply_output_format prefers `.data` over text when both are present.
     Case { name: "jq", setup: &[], cmd: r#"echo '{"a":1}' | jq '.a' --json"#, expect: Expect::Number },
     Case { name: "kaish-ast", setup: &[], cmd: "kaish-ast 'echo hi' --json", expect: Expect::String },
+    // The sweep kernel has no audit log, so this pins the failure envelope.
+    Case { name: "kaish-audit", setup: &[], cmd: "kaish-audit list --json", expect: Expect::FailsEnvelope(1) },
+    Case { name: "kaish-cache", setup: &[], cmd: "kaish-cache --json", expect: Expect::Array },
+    Case { name: "kaish-checkpoint", setup: &["kaish-checkpoint create start"], cmd: "kaish-checkpoint list --json", expect: Expect::Array },
     Case { name: "kaish-clear", setup: &[], cmd: "kaish-clear --json", expect: Expect::String },
     Case { name: "kaish-ignore", setup: &[], cmd: "kaish-ignore --json", expect: Expect::Array },
     Case { name: "kaish-last", setup: &["echo hi"], cmd: "kaish-last --json", expect: Expect::String },
+    Case { name: "kaish-metrics", setup: &["echo hi"], cmd: "kaish-metrics --json", expect: Expect::Object },
     // Breaking shape change in 0.8.1: {mounts, budget?} object, not a bare array.
     Case { name: "kaish-mounts", setup: &[], cmd: "kaish-mounts --json", expect: Expect::Object },
     Case { name: "kaish-output-limit", setup: &[], cmd: "kaish-output-limit --json", expect: Expect::Array },
+    Case { name: "kaish-state", setup: &[], cmd: "kaish-state export --json", expect: Expect::String },
     Case { name: "kaish-status", setup: &[], cmd: "kaish-status --json", expect: Expect::Array },
     Case { name: "kaish-tools", setup: &[], cmd: "kaish-tools --json", expect: Expect::Array },
     Case { name: "kaish-validate", setup: &[], cmd: "kaish-validate -e 'echo hi' --json", expect: Expect::String },
@@ -159,7 +173,9 @@ const CASES: &[Case] = &[
     Case { name: "keys", setup: &["u=$(fromjson '{\"a\":1,\"b\":2}')"], cmd: "keys $u --json", expect: Expect::Array },
     Case { name: "kill", setup: &["sleep 5 &"], cmd: "kill %1 --json", expect: Expect::Empty },
     Case { name: "ln", setup: &[], cmd: "ln -s tmp/data.json link2.json --json", expect: Expect::Empty },
+    Case { name: "log", setup: &[], cmd: "log hello key=value --json", expect: Expect::Empty },
     Case { name: "ls", setup: &[], cmd: "ls src --json", expect: Expect::Array },
+    Case { name: "map", setup: &[], cmd: "seq 1 2 | map --json echo", expect: Expect::Array },
     Case { name: "mkdir", setup: &[], cmd: "mkdir newdir --json", expect: Expect::Empty },
     Case { name: "mktemp", setup: &[], cmd: "mktemp -p tmp --json", expect: Expect::String },
     Case { name: "mv", setup: &[], cmd: "mv tmp/data.json tmp/moved.json --json", expect: Expect::Empty },
@@ -169,16 +185,21 @@ const CASES: &[Case] = &[
         cmd: "patch tmp/app.log --dry-run --json < fix.patch",
         expect: Expect::String,
     },
+    Case { name: "pipe", setup: &["pipe create p"], cmd: "pipe list --json", expect: Expect::Array },
+    Case { name: "popd", setup: &["pushd src"], cmd: "popd --json", expect: Expect::Array },
     Case { name: "printf", setup: &[], cmd: "printf 'x' --json", expect: Expect::String },
     Case { name: "ps", setup: &[], cmd: "ps --json", expect: Expect::Array },
     // push mutates in place and is silent on success, like unset.
     Case { name: "push", setup: &["xs=[a b]"], cmd: "push xs c --json", expect: Expect::Empty },
+    Case { name: "pushd", setup: &[], cmd: "pushd src --json", expect: Expect::Array },
     Case { name: "pwd", setup: &[], cmd: "pwd --json", expect: Expect::String },
+    Case { name: "random", setup: &[], cmd: "random int 1 6 --json", expect: Expect::Number },
     Case { name: "read", setup: &[], cmd: "echo hi | read X --json", expect: Expect::Empty },
     Case { name: "readlink", setup: &["ln -s tmp/data.json link.json"], cmd: "readlink link.json --json", expect: Expect::String },
     Case { name: "realpath", setup: &[], cmd: "realpath tmp/data.json --json", expect: Expect::String },
     Case { name: "rm", setup: &["touch del.txt"], cmd: "rm del.txt --json", expect: Expect::Empty },
     Case { name: "sed", setup: &[], cmd: r#"printf 'a\n' | sed 's/a/b/' --json"#, expect: Expect::String },
+    Case { name: "select", setup: &[], cmd: "echo 2 | select a b c --json", expect: Expect::Object },
     Case { name: "seq", setup: &[], cmd: "seq 1 3 --json", expect: Expect::Array },
     Case { name: "set", setup: &[], cmd: "set --json", expect: Expect::Empty },
     Case { name: "sleep", setup: &[], cmd: "sleep 0 --json", expect: Expect::Empty },
@@ -198,8 +219,10 @@ const CASES: &[Case] = &[
         expect: Expect::String,
     },
     Case { name: "tokens", setup: &[], cmd: "echo hello | tokens --json", expect: Expect::Array },
+    Case { name: "toml", setup: &[], cmd: r#"printf 'a = 1\n' | toml parse --json"#, expect: Expect::Object },
     Case { name: "touch", setup: &[], cmd: "touch new.txt --json", expect: Expect::Empty },
     Case { name: "tr", setup: &[], cmd: "printf 'abc' | tr a x --json", expect: Expect::String },
+    Case { name: "transcript", setup: &[], cmd: "transcript status --json", expect: Expect::String },
     Case { name: "tree", setup: &[], cmd: "tree src --json", expect: Expect::Object },
     Case { name: "true", setup: &[], cmd: "true --json", expect: Expect::Empty },
     Case { name: "typeof", setup: &["x=$(fromjson '[1,2,3]')"], cmd: "typeof $x --json", expect: Expect::String },
@@ -207,12 +230,14 @@ const CASES: &[Case] = &[
     Case { name: "uname", setup: &[], cmd: "uname --json", expect: Expect::String },
     Case { name: "uniq", setup: &[], cmd: r#"printf 'a\na\nb\n' | uniq --json"#, expect: Expect::String },
     Case { name: "unset", setup: &["X=1"], cmd: "unset X --json", expect: Expect::Empty },
+    Case { name: "uuid", setup: &[], cmd: "uuid --json", expect: Expect::String },
     Case { name: "values", setup: &["u=$(fromjson '{\"a\":1,\"b\":2}')"], cmd: "values $u --json", expect: Expect::Array },
     Case { name: "wait", setup: &[], cmd: "wait --json", expect: Expect::String },
     Case { name: "wc", setup: &[], cmd: "wc -l tmp/app.log --json", expect: Expect::Array },
     Case { name: "which", setup: &["export PATH=/usr/bin:/bin"], cmd: "which sh --json", expect: Expect::String },
     Case { name: "write", setup: &[], cmd: "write out2.txt hello --json", expect: Expect::String },
     Case { name: "xxd", setup: &[], cmd: "printf 'a' | xxd --json", expect: Expect::String },
+    Case { name: "yaml", setup: &[], cmd: r#"printf 'a: [1, 2]\n' | yaml parse --json"#, expect: Expect::Object },
 ];
 
 fn touch(dir: &std::path::Path, name: &str, contents: &str) {
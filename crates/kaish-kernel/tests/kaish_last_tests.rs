@@ -127,3 +127,25 @@ async fn refuses_with_piped_stdin() {
     assert_eq!(r.code, 2);
     assert!(r.err.contains("piped stdin"), "stderr: {}", r.err);
 }
+
+#[tokio::test]
+async fn error_flag_classifies_the_previous_failure() {
+    let k = setup().await;
+    let r = k
+        .execute(r#"no-such-command-xyz; kaish-last --error | jq -r '.kind'"#)
+        .await
+        .expect("script ran");
+    assert!(r.ok(), "exit: {} err: {}", r.code, r.err);
+    assert_eq!(last_line(&r.text_out()), "not_found");
+}
+
+#[tokio::test]
+async fn error_flag_after_or_chain() {
+    let k = setup().await;
+    let r = k
+        .execute(r#"cat /missing.txt || kaish-last --error | jq -r '.kind'"#)
+        .await
+        .expect("script ran");
+    assert!(r.ok(), "exit: {} err: {}", r.code, r.err);
+    assert_eq!(last_line(&r.text_out()), "failure");
+}
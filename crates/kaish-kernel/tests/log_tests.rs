@@ -0,0 +1,55 @@
+//! The `log` builtin: records land in `/v/logs` as text and JSONL, from the
+//! foreground, pipeline stages, and background jobs alike.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+#[tokio::test]
+async fn records_are_readable_under_v_logs() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"n=7; log --level warn "disk low" mount=/data build=$n"#)
+        .await
+        .expect("execute");
+    assert!(result.ok(), "got: {result:?}");
+    assert!(result.text_out().is_empty(), "log prints nothing: {result:?}");
+
+    let text = kernel.execute("cat /v/logs/kaish.log").await.expect("execute").text_out();
+    assert!(text.contains(" WARN  disk low build=7 mount=/data"), "got: {text}");
+
+    let jsonl = kernel.execute("cat /v/logs/kaish.jsonl").await.expect("execute").text_out();
+    let record: serde_json::Value = serde_json::from_str(jsonl.trim()).expect("one JSON record");
+    assert_eq!(record["level"], "warn");
+    assert_eq!(record["message"], "disk low");
+    assert_eq!(record["fields"]["mount"], "/data");
+}
+
+#[tokio::test]
+async fn pipeline_stages_and_background_jobs_share_the_buffer() {
+    let kernel = kernel();
+    kernel
+        .execute("echo x | log from=pipe stage; log from=job background & wait")
+        .await
+        .expect("execute");
+
+    let text = kernel.execute("cat /v/logs/kaish.log").await.expect("execute").text_out();
+    assert!(text.contains("INFO  stage from=pipe"), "got: {text}");
+    assert!(text.contains("INFO  background from=job"), "got: {text}");
+}
+
+#[tokio::test]
+async fn logs_are_read_only_and_levels_are_checked() {
+    let kernel = kernel();
+    let result = kernel.execute("echo x > /v/logs/kaish.log").await.expect("execute");
+    assert!(!result.ok(), "got: {result:?}");
+
+    let result = kernel.execute("log --level loud oops").await.expect("execute");
+    assert_eq!(result.code, 2, "got: {result:?}");
+    assert!(result.err.contains("unknown level"), "got: {result:?}");
+}
@@ -0,0 +1,87 @@
+//! `KernelConfig::max_loop_iterations`: a runaway `for`/`while` loop stops the
+//! request with `LIMIT_EXCEEDED_EXIT_CODE` and a loud stderr message, instead
+//! of spinning until a timeout (or forever, with none configured).
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig, LIMIT_EXCEEDED_EXIT_CODE};
+
+fn capped_kernel(iterations: u64) -> Kernel {
+    Kernel::new(KernelConfig::isolated().with_max_loop_iterations(iterations))
+        .expect("kernel creation")
+}
+
+#[tokio::test]
+async fn runaway_while_loop_hits_the_cap() {
+    let kernel = capped_kernel(100);
+    let result = kernel
+        .execute("while true; do true; done; echo unreachable")
+        .await
+        .expect("execute");
+    assert_eq!(result.code, LIMIT_EXCEEDED_EXIT_CODE, "got: {result:?}");
+    assert!(
+        result.stderr.contains("loop iteration limit (100)"),
+        "expected a loop-limit error, got: {result:?}"
+    );
+    assert!(!result.text_out().contains("unreachable"), "request must stop: {result:?}");
+}
+
+#[tokio::test]
+async fn for_loop_past_the_cap_stops() {
+    let kernel = capped_kernel(3);
+    let result = kernel
+        .execute("for i in $(seq 1 10); do echo $i; done")
+        .await
+        .expect("execute");
+    assert_eq!(result.code, LIMIT_EXCEEDED_EXIT_CODE, "got: {result:?}");
+    assert!(result.stderr.contains("for: loop iteration limit"), "got: {result:?}");
+}
+
+/// A loop that runs exactly the cap is within it — the cap counts executed
+/// iterations, not condition checks.
+#[tokio::test]
+async fn loops_up_to_the_cap_succeed() {
+    let kernel = capped_kernel(3);
+    let result = kernel
+        .execute(
+            r#"
+            N=0
+            while [[ $N -lt 3 ]]; do
+                N=$((N + 1))
+            done
+            for i in 1 2 3; do
+                echo $i
+            done
+            "#,
+        )
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().trim(), "1\n2\n3");
+}
+
+/// The cap is per loop, not per request: sequential loops each get the full
+/// budget.
+#[tokio::test]
+async fn cap_is_per_loop() {
+    let kernel = capped_kernel(3);
+    let result = kernel
+        .execute("for i in 1 2 3; do true; done; for j in 1 2 3; do true; done; echo done")
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().trim(), "done");
+}
+
+#[test]
+fn presets_default_the_cap() {
+    assert_eq!(KernelConfig::isolated().max_loop_iterations, None);
+    #[cfg(feature = "localfs")]
+    {
+        assert_eq!(KernelConfig::repl().max_loop_iterations, None);
+        let agent = KernelConfig::agent();
+        assert_eq!(agent.max_loop_iterations, Some(kaish_kernel::DEFAULT_AGENT_LOOP_ITERATIONS));
+        assert_eq!(agent.without_max_loop_iterations().max_loop_iterations, None);
+    }
+}
@@ -0,0 +1,63 @@
+//! The `map` builtin: run a command once per input item.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::sync::Arc;
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Arc<Kernel> {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation").into_arc()
+}
+
+#[tokio::test]
+async fn lines_run_in_order_with_placeholder() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"printf 'a\nb\n\nc\n' | map -j 3 echo 'item-{}'"#)
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out(), "item-a\nitem-b\nitem-c\n");
+}
+
+#[tokio::test]
+async fn inner_flags_belong_to_the_command() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"printf 'x\ny\n' | map echo -n '{}'"#)
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out(), "xy");
+}
+
+#[tokio::test]
+async fn quoted_and_key_value_forms() {
+    let kernel = kernel();
+    let quoted = kernel.execute(r#"seq 1 2 | map 'echo n{}'"#).await.expect("execute");
+    assert_eq!(quoted.text_out(), "n1\nn2\n", "got: {quoted:?}");
+    let keyed = kernel
+        .execute(r#"seq 1 2 | map tool=echo arg='{}'"#)
+        .await
+        .expect("execute");
+    assert_eq!(keyed.text_out(), "1\n2\n", "got: {keyed:?}");
+}
+
+#[tokio::test]
+async fn data_records_per_item_and_failure_exit() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"printf '/a\n/missing\n' | map cat; echo "$?""#)
+        .await
+        .expect("execute");
+    assert!(result.text_out().ends_with("123\n"), "got: {result:?}");
+
+    let result = kernel
+        .execute(r#"echo hi > /a; fromjson '["/a"]' | map cat | jq -c '.[0] | [.item, .code, .out]'"#)
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().trim(), r#"["/a",0,"hi\n"]"#);
+}
@@ -0,0 +1,66 @@
+//! Kernel metrics: `Kernel::metrics` and the `kaish-metrics` builtin report
+//! per-command counts and timings, filesystem operations per mount, and job
+//! counts.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+#[tokio::test]
+async fn counts_commands_and_failures() {
+    let kernel = kernel();
+    kernel.execute("echo a; echo b | cat; false; greet() { echo hi; }; greet").await.expect("execute");
+
+    let metrics = kernel.metrics().await;
+    let echo = metrics.commands.get("echo").expect("echo counted");
+    assert_eq!(echo.calls, 3, "two top-level plus one inside the function");
+    assert_eq!(echo.failures, 0);
+    assert_eq!(metrics.commands["cat"].calls, 1);
+    assert_eq!(metrics.commands["false"].failures, 1);
+    assert_eq!(metrics.commands["greet"].calls, 1);
+    assert!(metrics.commands["greet"].total >= metrics.commands["greet"].max);
+}
+
+#[tokio::test]
+async fn counts_filesystem_operations_per_mount() {
+    let kernel = kernel();
+    kernel.execute("echo x > /tmp/a.txt; cat /tmp/a.txt").await.expect("execute");
+
+    let metrics = kernel.metrics().await;
+    let total: u64 = metrics.vfs_ops.values().sum();
+    assert!(total >= 2, "got: {:?}", metrics.vfs_ops);
+
+    kernel.reset_metrics();
+    let metrics = kernel.metrics().await;
+    assert!(metrics.commands.is_empty() && metrics.vfs_ops.is_empty(), "got: {metrics:?}");
+}
+
+#[tokio::test]
+async fn background_jobs_are_counted() {
+    let kernel = kernel();
+    kernel.execute("echo bg & wait %1").await.expect("execute");
+    assert_eq!(kernel.metrics().await.jobs.started, 1);
+}
+
+#[tokio::test]
+async fn builtin_reports_json_and_resets() {
+    let kernel = kernel();
+    // Counts land as each command finishes, so the report already sees the
+    // echoes that ran earlier in the same request.
+    let result = kernel.execute("echo one; echo two; kaish-metrics --json").await.expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    let text = result.text_out();
+    let report = text.find('{').map(|start| &text[start..]).expect("json report after the echoes");
+    let json: serde_json::Value = serde_json::from_str(report).expect("json");
+    assert_eq!(json["commands"]["echo"]["calls"], 2);
+    assert!(json["jobs"]["started"].is_u64());
+
+    let result = kernel.execute("kaish-metrics reset; kaish-metrics").await.expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert!(!result.text_out().contains("echo"), "got: {result:?}");
+}
@@ -0,0 +1,117 @@
+//! Modules: `import PATH as NAME` registers a file's functions as
+//! `NAME.function` without defining anything in the flat namespace, and the
+//! validator checks calls into a loaded module against what it defines.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::path::Path;
+use std::sync::Arc;
+
+use kaish_kernel::vfs::{Filesystem, MemoryFs, VfsRouter};
+use kaish_kernel::{CommandKind, Kernel, KernelBackend, KernelConfig, LocalBackend};
+
+const GIT_HELPERS: &str = r#"
+# git-helpers: sync and release
+sync() {
+    fetch "$1"
+    echo "synced $(branch)"
+}
+release(version) { echo "released $version"; }
+fetch() { echo "fetching $1"; }
+branch() { echo main; }
+echo "module side effect"
+"#;
+
+async fn module_kernel(files: &[(&str, &str)]) -> Kernel {
+    let fs = MemoryFs::new();
+    for (path, text) in files {
+        let path = Path::new(path);
+        if let Some(parent) = path.parent() {
+            fs.mkdir(parent).await.ok();
+        }
+        fs.write(path, text.as_bytes()).await.expect("write");
+    }
+    let mut vfs = VfsRouter::new();
+    vfs.mount("/", fs);
+    let backend: Arc<dyn KernelBackend> = Arc::new(LocalBackend::new(Arc::new(vfs)));
+    Kernel::with_backend(backend, KernelConfig::isolated(), |_| {}, |_| {}).expect("with_backend kernel")
+}
+
+async fn out(kernel: &Kernel, script: &str) -> String {
+    let result = kernel.execute(script).await.expect("execute");
+    assert!(result.ok(), "{script}: {result:?}");
+    result.text_out().into_owned()
+}
+
+#[tokio::test]
+async fn imported_functions_live_in_their_namespace() {
+    let kernel = module_kernel(&[("/lib/git-helpers.kai", GIT_HELPERS)]).await;
+
+    // Importing runs none of the module's other statements.
+    assert_eq!(out(&kernel, "import /lib/git-helpers.kai as git").await, "");
+    // Calls between the module's functions find each other.
+    assert_eq!(out(&kernel, "git.sync origin").await, "fetching origin\nsynced main\n");
+    assert_eq!(out(&kernel, "git.release 1.2").await, "released 1.2\n");
+
+    // Nothing lands in the flat namespace.
+    assert_eq!(kernel.classify_command("git.sync").await, CommandKind::UserTool);
+    assert_ne!(kernel.classify_command("sync").await, CommandKind::UserTool);
+    assert_eq!(kernel.execute("fetch x").await.expect("execute").code, 127);
+}
+
+#[tokio::test]
+async fn one_module_under_two_names() {
+    let kernel = module_kernel(&[("/lib/git-helpers.kai", GIT_HELPERS)]).await;
+    out(&kernel, "cd /lib; import git-helpers.kai as git; import ./git-helpers.kai as vcs").await;
+    assert_eq!(out(&kernel, "vcs.sync up").await, "fetching up\nsynced main\n");
+    assert_eq!(out(&kernel, "git.sync up").await, "fetching up\nsynced main\n");
+}
+
+#[tokio::test]
+async fn reimporting_replaces_the_namespace() {
+    let kernel = module_kernel(&[
+        ("/lib/v1.kai", "old() { echo v1; }\nshared() { echo v1; }"),
+        ("/lib/v2.kai", "shared() { echo v2; }"),
+    ])
+    .await;
+    out(&kernel, "import /lib/v1.kai as m").await;
+    assert_eq!(out(&kernel, "m.shared").await, "v1\n");
+
+    out(&kernel, "import /lib/v2.kai as m").await;
+    assert_eq!(out(&kernel, "m.shared").await, "v2\n");
+    assert_ne!(kernel.classify_command("m.old").await, CommandKind::UserTool);
+}
+
+#[tokio::test]
+async fn validator_checks_calls_into_a_loaded_module() {
+    let kernel = module_kernel(&[("/lib/git-helpers.kai", GIT_HELPERS)]).await;
+
+    // Imported and called in the same request: checked when it runs.
+    assert_eq!(
+        out(&kernel, "import /lib/git-helpers.kai as git; git.release 2").await,
+        "released 2\n"
+    );
+
+    let result = kernel.execute("echo ran; git.relase 3").await.expect("execute");
+    assert!(!result.ok(), "{result:?}");
+    assert!(!result.text_out().contains("ran"), "{result:?}");
+    assert!(result.err.contains("module 'git' has no function 'relase'"), "{result:?}");
+    assert!(result.err.contains("git.release"), "{result:?}");
+}
+
+#[tokio::test]
+async fn import_failures_are_reported() {
+    let kernel = module_kernel(&[("/lib/broken.kai", "broken() {")]).await;
+
+    let result = kernel.execute("import /lib/missing.kai as m").await.expect("execute");
+    assert_eq!(result.code, 1, "{result:?}");
+    assert!(result.err.contains("import: /lib/missing.kai"), "{result:?}");
+
+    let result = kernel.execute("import /lib/broken.kai as m").await.expect("execute");
+    assert!(result.err.contains("/lib/broken.kai:"), "{result:?}");
+
+    let result = kernel.execute("import /lib/broken.kai m").await.expect("execute");
+    assert_eq!(result.code, 2, "{result:?}");
+    assert!(result.err.contains("import: usage: import PATH as NAME"), "{result:?}");
+}
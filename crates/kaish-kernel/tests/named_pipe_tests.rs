@@ -0,0 +1,66 @@
+//! Named pipes: `pipe create` makes `/v/pipes/NAME`, one command writes it
+//! with a redirect and another reads it, across background jobs.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::time::Duration;
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+/// Run a script, failing the test rather than hanging if a pipe read never
+/// sees its writer.
+async fn out(kernel: &Kernel, script: &str) -> String {
+    let result = tokio::time::timeout(Duration::from_secs(10), kernel.execute(script))
+        .await
+        .unwrap_or_else(|_| panic!("{script}: timed out"))
+        .expect("execute");
+    assert!(result.ok(), "{script}: {result:?}");
+    result.text_out().into_owned()
+}
+
+#[tokio::test]
+async fn background_writer_feeds_foreground_reader() {
+    let kernel = kernel();
+    assert_eq!(out(&kernel, "pipe create sorted").await, "/v/pipes/sorted");
+
+    out(&kernel, "printf 'b\\na\\n' | sort > /v/pipes/sorted &").await;
+    assert_eq!(out(&kernel, "cat /v/pipes/sorted").await, "a\nb\n");
+
+    out(&kernel, "echo again > /v/pipes/sorted &").await;
+    assert_eq!(out(&kernel, "wc -l < /v/pipes/sorted").await.trim(), "1");
+}
+
+#[tokio::test]
+async fn reader_may_start_first() {
+    let kernel = kernel();
+    out(&kernel, "pipe create p; mkdir -p /tmp").await;
+    out(&kernel, "cat /v/pipes/p > /tmp/got &").await;
+    out(&kernel, "echo hello > /v/pipes/p; wait").await;
+    assert_eq!(out(&kernel, "cat /tmp/got").await, "hello\n");
+}
+
+#[tokio::test]
+async fn writes_queue_and_append_does_not_drain() {
+    let kernel = kernel();
+    out(&kernel, "pipe create p").await;
+    out(&kernel, "echo one > /v/pipes/p; echo two >> /v/pipes/p").await;
+    assert_eq!(out(&kernel, "pipe list").await, "p\t8");
+    assert_eq!(out(&kernel, "cat /v/pipes/p").await, "one\ntwo\n");
+}
+
+#[tokio::test]
+async fn unknown_pipe_is_an_error() {
+    let kernel = kernel();
+    let result = kernel.execute("echo x > /v/pipes/typo").await.expect("execute");
+    assert!(!result.ok(), "{result:?}");
+    assert!(out(&kernel, "ls /v/pipes").await.is_empty());
+
+    out(&kernel, "pipe create p; pipe rm p").await;
+    let result = kernel.execute("cat /v/pipes/p").await.expect("execute");
+    assert!(!result.ok(), "{result:?}");
+}
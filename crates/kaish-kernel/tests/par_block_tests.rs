@@ -0,0 +1,105 @@
+//! `par { ... }` blocks: independent statements run concurrently, their
+//! assignments land in the caller's scope, and a block whose statements
+//! depend on each other falls back to a sequential run with a warning.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+/// Each statement reads its own FIFO, and the writer opens both before
+/// writing to either. Opening a FIFO for writing blocks until a reader has
+/// it open, so the data only flows once both `cat`s are running at the same
+/// time; a sequential run deadlocks instead, and the timeout reports it.
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn independent_statements_overlap() {
+    use std::io::Write as _;
+    use std::time::Duration;
+
+    let dir = tempfile::tempdir().expect("tempdir");
+    let fifos = ["a.fifo", "b.fifo"].map(|name| dir.path().join(name));
+    for fifo in &fifos {
+        let status = std::process::Command::new("mkfifo").arg(fifo).status().expect("mkfifo");
+        assert!(status.success(), "mkfifo {}", fifo.display());
+    }
+    let writer = {
+        let fifos = fifos.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut files: Vec<std::fs::File> = fifos
+                .iter()
+                .map(|fifo| std::fs::OpenOptions::new().write(true).open(fifo).expect("open fifo"))
+                .collect();
+            for (file, text) in files.iter_mut().zip(["a\n", "b\n"]) {
+                file.write_all(text.as_bytes()).expect("write fifo");
+            }
+        })
+    };
+
+    let config = KernelConfig::repl()
+        .with_cwd(dir.path().to_path_buf())
+        .with_latch(false)
+        .with_trash(false);
+    let kernel = Kernel::new(config).expect("kernel creation");
+    let run = kernel.execute("par {\n  cat a.fifo\n  cat b.fifo\n}");
+    match tokio::time::timeout(Duration::from_secs(30), run).await {
+        Ok(result) => {
+            let result = result.expect("execute");
+            assert_eq!(result.text_out(), "a\nb\n", "{result:?}");
+            writer.await.expect("writer");
+        }
+        Err(_) => {
+            // The writer is still waiting for a reader on `b.fifo`; give it
+            // one so the blocking pool can wind down, then fail.
+            let b = fifos[1].clone();
+            std::thread::spawn(move || drop(std::fs::File::open(b)));
+            panic!("the two statements never ran at the same time");
+        }
+    }
+}
+
+#[tokio::test]
+async fn assignments_are_visible_after_the_block() {
+    let kernel = kernel();
+    let result = kernel
+        .execute("par {\n  A=$(echo one)\n  B=$(echo two)\n}\necho $A $B")
+        .await
+        .expect("execute");
+    assert_eq!(result.text_out(), "one two\n");
+}
+
+#[tokio::test]
+async fn output_follows_statement_order() {
+    let kernel = kernel();
+    let result = kernel
+        .execute("par {\n  sleep 0.2 && echo first\n  echo second\n}")
+        .await
+        .expect("execute");
+    assert_eq!(result.text_out(), "first\nsecond\n");
+}
+
+#[tokio::test]
+async fn exit_code_is_the_first_failure() {
+    let kernel = kernel();
+    let result = kernel.execute("par {\n  true\n  false\n  true\n}").await.expect("execute");
+    assert_eq!(result.code, 1);
+}
+
+#[tokio::test]
+async fn dependent_statements_run_sequentially_with_a_warning() {
+    let kernel = kernel();
+    let result = kernel
+        .execute("par {\n  A=1\n  B=$A\n}\necho $B")
+        .await
+        .expect("execute");
+    assert_eq!(result.text_out(), "1\n");
+    assert!(
+        result.err.contains("par block runs sequentially"),
+        "expected a W008 advisory, got: {:?}",
+        result.err
+    );
+}
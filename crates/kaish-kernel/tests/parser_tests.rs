@@ -1250,3 +1250,52 @@ fn glued_short_flag_then_single_fragment_still_parses() {
     one_stmt_sexpr("echo -f$(echo x)");
     one_stmt_sexpr("cut -d, -f2");
 }
+
+// ---------------------------------------------------------------------------
+// Error recovery: a bad top-level statement is skipped to its `;`/newline and
+// parsing resumes, so one run reports every bad statement — and renders each
+// with the offending line and a caret run under the span.
+// ---------------------------------------------------------------------------
+
+#[test]
+fn recovery_reports_every_bad_statement() {
+    let source = "echo ok\necho )\necho fine\necho )\n";
+    let errors = parse(source).expect_err("expected parse errors");
+    assert_eq!(errors.len(), 2, "got: {errors:?}");
+    let lines: Vec<usize> = errors
+        .iter()
+        .map(|e| source[..e.span.start].matches('\n').count() + 1)
+        .collect();
+    assert_eq!(lines, vec![2, 4], "got: {errors:?}");
+}
+
+/// A bad line inside a block must not resync mid-block, where the block's
+/// own `then`/`fi` would each surface a second, spurious error.
+#[test]
+fn recovery_skips_the_whole_enclosing_block() {
+    let source = "if true; then\n  echo )\nfi\necho after )\n";
+    let errors = parse(source).expect_err("expected parse errors");
+    assert_eq!(errors.len(), 2, "got: {errors:?}");
+}
+
+#[test]
+fn format_errors_renders_source_line_with_carets() {
+    let source = "echo ok\n\techo )\n";
+    let errors = parse(source).expect_err("expected a parse error");
+    let rendered = kaish_kernel::parser::format_errors(&errors, source);
+    let mut lines = rendered.lines();
+    assert!(
+        lines.next().is_some_and(|l| l.starts_with("2:7 [parse]: ")),
+        "got: {rendered}"
+    );
+    assert_eq!(lines.next(), Some("  | \techo )"));
+    assert_eq!(lines.next(), Some("  | \t     ^"));
+}
+
+/// Loops open at `for`/`while`, so the `;` before `do` doesn't end the
+/// recovered statement early.
+#[test]
+fn recovery_spans_loop_headers() {
+    let errors = parse("for x in a b; do echo ); done\n").expect_err("expected a parse error");
+    assert_eq!(errors.len(), 1, "got: {errors:?}");
+}
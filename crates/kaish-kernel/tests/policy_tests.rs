@@ -0,0 +1,115 @@
+//! `KernelConfig::policy`: disallowed commands, external execution, and writes
+//! outside the writable prefixes are refused, with `POLICY_DENIED_EXIT_CODE`
+//! for commands and a validator advisory for statically named ones.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig, Policy, POLICY_DENIED_EXIT_CODE};
+
+fn kernel_with(policy: Policy) -> Kernel {
+    Kernel::new(KernelConfig::isolated().with_policy(policy)).expect("kernel creation")
+}
+
+#[tokio::test]
+async fn disallowed_tool_is_refused_with_policy_exit_code() {
+    let kernel = kernel_with(Policy::default().with_allowed_tools(["echo"]));
+    let result = kernel.execute("cat /etc/hosts").await.expect("execute");
+    assert_eq!(result.code, POLICY_DENIED_EXIT_CODE, "got: {result:?}");
+    assert!(result.err.contains("cat: denied by policy"), "got: {result:?}");
+}
+
+#[tokio::test]
+async fn allowed_tools_still_run() {
+    let kernel = kernel_with(Policy::default().with_allowed_tools(["echo"]));
+    let result = kernel.execute("echo hi").await.expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().trim(), "hi");
+}
+
+/// A statically named disallowed command is flagged before anything runs, so
+/// the agent reads which command the policy refuses (W007) alongside the exit.
+#[tokio::test]
+async fn validator_surfaces_policy_warning() {
+    let kernel = kernel_with(Policy::default().with_allowed_tools(["echo"]));
+    let result = kernel.execute("echo before; rm /x").await.expect("execute");
+    assert!(result.err.contains("W007"), "expected a policy advisory, got: {result:?}");
+    assert!(result.text_out().contains("before"), "earlier statements still run: {result:?}");
+    assert_eq!(result.code, POLICY_DENIED_EXIT_CODE, "got: {result:?}");
+}
+
+/// Shell functions aren't tools: defining and calling one is allowed, but the
+/// commands inside are still checked.
+#[tokio::test]
+async fn functions_are_not_gated_but_their_commands_are() {
+    let kernel = kernel_with(Policy::default().with_allowed_tools(["echo"]));
+    let result = kernel
+        .execute("greet() { echo hello; }; greet")
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().trim(), "hello");
+
+    let result = kernel
+        .execute("sneaky() { cat /etc/hosts; }; sneaky")
+        .await
+        .expect("execute");
+    assert_eq!(result.code, POLICY_DENIED_EXIT_CODE, "got: {result:?}");
+}
+
+#[tokio::test]
+async fn writes_outside_writable_prefixes_are_refused() {
+    let kernel = kernel_with(Policy::default().with_writable_prefixes(["/work"]));
+    kernel.execute("mkdir -p /work").await.expect("execute");
+
+    let ok = kernel.execute("echo data > /work/a.txt; cat /work/a.txt").await.expect("execute");
+    assert_eq!(ok.code, 0, "got: {ok:?}");
+    assert_eq!(ok.text_out().trim(), "data");
+
+    let denied = kernel.execute("echo data > /elsewhere.txt").await.expect("execute");
+    assert_ne!(denied.code, 0, "write outside /work must fail: {denied:?}");
+    assert!(denied.err.contains("denied by policy"), "got: {denied:?}");
+
+    let escaped = kernel.execute("echo data > /work/../escape.txt").await.expect("execute");
+    assert_ne!(escaped.code, 0, "`..` must not escape the prefix: {escaped:?}");
+}
+
+#[tokio::test]
+async fn symlinks_out_of_writable_prefixes_are_refused() {
+    let kernel = kernel_with(Policy::default().with_writable_prefixes(["/work"]));
+    kernel.execute("mkdir -p /work/sub").await.expect("execute");
+
+    let ok = kernel.execute("ln -s sub /work/inner").await.expect("execute");
+    assert_eq!(ok.code, 0, "a link inside /work is fine: {ok:?}");
+
+    for cmd in ["ln -s /outside /work/out", "ln -s ../outside /work/out", "ln -s sub/../../outside /work/out"] {
+        let denied = kernel.execute(cmd).await.expect("execute");
+        assert_ne!(denied.code, 0, "`{cmd}` must fail: {denied:?}");
+        assert!(denied.err.contains("denied by policy"), "got: {denied:?}");
+    }
+
+    let left = kernel.execute("ls /work").await.expect("execute");
+    assert!(!left.text_out().contains("out"), "no link may be left behind: {left:?}");
+}
+
+#[cfg(all(unix, feature = "subprocess"))]
+#[tokio::test]
+async fn exec_denied_refuses_external_commands() {
+    let config = KernelConfig::transient()
+        .with_var("PATH", kaish_kernel::ast::Value::String(std::env::var("PATH").unwrap_or_default()))
+        .with_policy(Policy::default().with_exec(false));
+    let kernel = Kernel::new(config).expect("kernel creation");
+
+    // External commands aren't looked up at all, exactly as with
+    // `allow_external_commands = false`: the command never runs.
+    let result = kernel.execute("sh -c 'echo escaped'").await.expect("execute");
+    assert_eq!(result.code, 127, "got: {result:?}");
+    assert!(!result.text_out().contains("escaped"), "got: {result:?}");
+
+    let result = kernel.execute("exec sh -c 'echo escaped'").await.expect("execute");
+    assert_eq!(result.code, POLICY_DENIED_EXIT_CODE, "got: {result:?}");
+    assert!(result.err.contains("external commands are not allowed"), "got: {result:?}");
+
+    let result = kernel.execute("spawn sh").await.expect("execute");
+    assert_eq!(result.code, POLICY_DENIED_EXIT_CODE, "got: {result:?}");
+}
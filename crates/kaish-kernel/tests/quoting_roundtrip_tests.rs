@@ -0,0 +1,68 @@
+//! Quoting round-trips: any string, quoted the way kaish documents, lexes,
+//! parses, and evaluates back to itself.
+//!
+//! The lexer, the composed scanner, and the interpolation parser each handle
+//! escapes on their own; a disagreement between them shows up here as a
+//! string that doesn't come back intact.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::ast::Value;
+use kaish_kernel::{Kernel, KernelConfig};
+use proptest::prelude::*;
+
+/// Shell metacharacters, quotes, escapes, and whitespace — the characters
+/// quoting exists to protect — plus a few plain ones to separate them.
+const ALPHABET: &str = r#"[a-z0-9 $"'\\`{}()\[\]|;&<>#*?~=.,:!%@+\n\t-]{0,24}"#;
+
+/// Double-quote `s` so it reads back verbatim: escape the characters that
+/// are special inside `"..."`, and spell control characters as escapes.
+fn double_quote(s: &str) -> String {
+    let mut out = String::from("\"");
+    for ch in s.chars() {
+        match ch {
+            '\\' => out.push_str(r"\\"),
+            '"' => out.push_str("\\\""),
+            '$' => out.push_str("\\$"),
+            '\n' => out.push_str(r"\n"),
+            '\t' => out.push_str(r"\t"),
+            other => out.push(other),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Run `script` on a fresh kernel and return the value it left in `X`.
+fn eval_x(script: &str) -> Option<Value> {
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    runtime.block_on(async {
+        let kernel = Kernel::new(KernelConfig::isolated()).expect("kernel creation");
+        let result = kernel.execute(script).await.expect("execute");
+        assert!(result.ok(), "{script}: {result:?}");
+        kernel.get_var("X").await
+    })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn double_quoted_strings_round_trip(s in ALPHABET) {
+        let script = format!("X={}", double_quote(&s));
+        prop_assert_eq!(eval_x(&script), Some(Value::String(s)), "{}", script);
+    }
+
+    #[test]
+    fn single_quoted_strings_round_trip(s in ALPHABET.replace('\'', "")) {
+        let script = format!("X='{s}'");
+        prop_assert_eq!(eval_x(&script), Some(Value::String(s)), "{}", script);
+    }
+
+    #[test]
+    fn interpolation_splices_values_intact(s in ALPHABET, pre in "[a-z]{0,3}", post in "[a-z]{0,3}") {
+        let script = format!("V={}; X=\"{pre}${{V}}{post}\"", double_quote(&s));
+        prop_assert_eq!(eval_x(&script), Some(Value::String(format!("{pre}{s}{post}"))), "{}", script);
+    }
+}
@@ -0,0 +1,66 @@
+//! The `random` and `uuid` builtins, including the `RANDOM_SEED` replay mode.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+#[tokio::test]
+async fn named_bounds_bind_as_operands() {
+    let kernel = kernel();
+    let result = kernel
+        .execute("n=$(random int min=5 max=5); echo $(( n + 1 ))")
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().trim(), "6");
+}
+
+#[tokio::test]
+async fn choice_picks_from_a_list_variable() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"ITEMS=$(fromjson '["x", "x"]'); random choice ${ITEMS}"#)
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().trim(), "x");
+}
+
+/// Under `RANDOM_SEED`, successive `$(…)` draws advance one sequence, and a
+/// fresh kernel with the same seed replays it exactly.
+#[tokio::test]
+async fn random_seed_replays_across_kernels() {
+    let script = "RANDOM_SEED=42; a=$(random int 0 1000000); b=$(random int 0 1000000); id=$(uuid); echo $a $b $id";
+    let first = kernel().execute(script).await.expect("execute");
+    let second = kernel().execute(script).await.expect("execute");
+    assert_eq!(first.code, 0, "got: {first:?}");
+    assert_eq!(first.text_out(), second.text_out());
+    let out = first.text_out();
+    let words: Vec<&str> = out.split_whitespace().collect();
+    assert_eq!(words.len(), 3);
+    assert_ne!(words[0], words[1], "draws advance the sequence");
+}
+
+#[tokio::test]
+async fn uuid_is_fresh_without_a_seed() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"a=$(uuid); b=$(uuid); [[ $a != $b ]] && echo ${#a}"#)
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().trim(), "36");
+}
+
+#[tokio::test]
+async fn bad_seed_fails_loud() {
+    let kernel = kernel();
+    let result = kernel.execute("RANDOM_SEED=banana; random int").await.expect("execute");
+    assert_ne!(result.code, 0);
+    assert!(result.err.contains("RANDOM_SEED"), "got: {result:?}");
+}
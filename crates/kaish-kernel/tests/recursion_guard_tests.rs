@@ -19,6 +19,11 @@ use kaish_kernel::{Kernel, KernelConfig, MAX_RECURSION_DEPTH, RECOMMENDED_STACK_
 /// Execute `script` through a fresh in-memory kernel on a recommended-size
 /// stack (so the recursion can reach the cap before it could overflow).
 fn run_on_recommended_stack(script: String) -> ExecResult {
+    run_with_config(KernelConfig::isolated(), script)
+}
+
+/// [`run_on_recommended_stack`] with a caller-supplied kernel config.
+fn run_with_config(config: KernelConfig, script: String) -> ExecResult {
     std::thread::Builder::new()
         .name("recursion-test".to_string())
         .stack_size(RECOMMENDED_STACK_SIZE)
@@ -28,7 +33,7 @@ fn run_on_recommended_stack(script: String) -> ExecResult {
                 .build()
                 .expect("current-thread runtime");
             rt.block_on(async move {
-                let kernel = Kernel::new(KernelConfig::isolated()).expect("kernel");
+                let kernel = Kernel::new(config).expect("kernel");
                 kernel.execute(&script).await.expect("execute returns a result")
             })
         })
@@ -136,3 +141,49 @@ fn counter_is_balanced_after_a_tripped_recursion() {
     assert_eq!(after.code, 0, "a command after a tripped recursion must succeed: {after:?}");
     assert_eq!(after.text_out().trim(), "alive", "recursion counter leaked across executes: {after:?}");
 }
+
+/// `KernelConfig::max_call_depth` caps function nesting below the stack guard,
+/// naming the function that went one level too deep.
+#[test]
+fn configured_call_depth_is_enforced() {
+    let down = "down() { if [[ $1 -le 0 ]]; then echo done; else down $(($1 - 1)); fi; }";
+    let config = || KernelConfig::isolated().with_max_call_depth(5);
+
+    let result = run_with_config(config(), format!("{down}; down 4"));
+    assert_eq!(result.code, 0, "five nested calls fit a cap of five: {result:?}");
+
+    let result = run_with_config(config(), format!("{down}; down 5"));
+    assert_ne!(result.code, 0, "six nested calls must fail: {result:?}");
+    assert!(
+        result.err.contains("maximum recursion depth exceeded calling 'down'"),
+        "expected a call-depth error, got: {result:?}"
+    );
+}
+
+/// Functions compose: one function calling another (and itself) counts every
+/// level against the same cap.
+#[test]
+fn functions_call_each_other() {
+    let script = "twice() { echo \"$1$1\"; }; shout() { twice \"$1!\"; }; shout hi".to_string();
+    let result = run_on_recommended_stack(script);
+    assert_eq!(result.text_out(), "hi!hi!\n", "{result:?}");
+
+    let script = "ping() { if [[ $1 -gt 0 ]]; then pong $(($1 - 1)); fi; }; \
+                  pong() { ping $1; }; ping 10"
+        .to_string();
+    let result = run_with_config(KernelConfig::isolated().with_max_call_depth(8), script);
+    assert!(
+        result.err.contains("maximum recursion depth exceeded"),
+        "mutual recursion past the cap must fail: {result:?}"
+    );
+}
+
+/// Pipeline stages run on forks with a fresh stack guard, but the call depth
+/// travels with them — recursion through a pipe stops instead of spawning
+/// stages forever.
+#[test]
+fn recursion_through_a_pipe_is_bounded() {
+    let script = "f() { f | cat; }; f; echo after".to_string();
+    let result = run_with_config(KernelConfig::isolated().with_max_call_depth(8), script);
+    assert_eq!(result.text_out().trim_end().lines().last(), Some("after"), "{result:?}");
+}
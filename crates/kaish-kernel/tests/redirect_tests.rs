@@ -0,0 +1,61 @@
+//! Redirects through the real `kernel.execute()` path: `>`, `>>`, `2>`, `&>`
+//! and `<` all go through the VFS, on a single command and on the last stage
+//! of a pipeline, and a write to a read-only mount fails loudly instead of
+//! dropping the output.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel")
+}
+
+#[tokio::test]
+async fn stdout_overwrite_and_append_land_in_the_vfs() {
+    let kernel = kernel();
+    let result = kernel
+        .execute("echo one > /out.txt; echo two >> /out.txt; echo three | tr a-z A-Z >> /out.txt; cat /out.txt")
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out(), "one\ntwo\nTHREE\n");
+}
+
+#[tokio::test]
+async fn stderr_and_both_redirects() {
+    let kernel = kernel();
+    let result = kernel
+        .execute("cat /missing 2> /err.txt; echo hi &> /both.txt; cat /err.txt; cat /both.txt")
+        .await
+        .expect("execute");
+    assert!(result.err.is_empty(), "no stderr should leak past the redirects: {result:?}");
+    let out = result.text_out();
+    assert!(out.contains("/missing"), "stderr should be in the file: {out}");
+    assert!(out.ends_with("hi\n"), "&> should capture stdout: {out}");
+}
+
+#[tokio::test]
+async fn stdin_redirect_feeds_the_command() {
+    let kernel = kernel();
+    let result = kernel
+        .execute("printf 'b\\na\\n' > /in.txt; sort < /in.txt")
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out(), "a\nb\n");
+
+    let missing = kernel.execute("sort < /nope.txt").await.expect("execute");
+    assert_ne!(missing.code, 0, "a missing stdin file must fail: {missing:?}");
+}
+
+#[tokio::test]
+async fn write_to_read_only_mount_fails_loudly() {
+    let kernel = kernel();
+    let result = kernel.execute("echo hi > /v/bin/nope").await.expect("execute");
+    assert_eq!(result.code, 1, "got: {result:?}");
+    assert!(result.err.starts_with("redirect:"), "got: {result:?}");
+    assert!(result.err.contains("read-only"), "got: {result:?}");
+    assert!(result.text_out().is_empty(), "output must not fall through: {result:?}");
+}
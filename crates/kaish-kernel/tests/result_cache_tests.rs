@@ -0,0 +1,83 @@
+//! Result cache: `cat` and `checksum` of files on read-only mounts are
+//! answered from the cache, `--no-cache` refreshes an entry, and
+//! `kaish-cache clear` drops everything.
+
+#![cfg(feature = "localfs")]
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::sync::Arc;
+
+use kaish_kernel::vfs::{LocalFs, VfsRouter};
+use kaish_kernel::{Kernel, KernelBackend, KernelConfig, LocalBackend};
+
+/// A kernel whose root is `dir`, mounted read-only.
+fn read_only_kernel(dir: &std::path::Path) -> Kernel {
+    let mut vfs = VfsRouter::new();
+    vfs.mount("/", LocalFs::read_only(dir));
+    let backend: Arc<dyn KernelBackend> = Arc::new(LocalBackend::new(Arc::new(vfs)));
+    Kernel::with_backend(backend, KernelConfig::isolated(), |_| {}, |_| {})
+        .expect("with_backend kernel")
+}
+
+async fn out(kernel: &Kernel, script: &str) -> String {
+    let result = kernel.execute(script).await.expect("execute");
+    assert!(result.ok(), "{script}: {result:?}");
+    result.text_out().into_owned()
+}
+
+#[tokio::test]
+async fn cat_of_a_read_only_file_is_cached() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "first\n").unwrap();
+    let kernel = read_only_kernel(dir.path());
+
+    assert_eq!(out(&kernel, "cat /notes.txt").await, "first\n");
+    // Changed behind the kernel's back: the cached result still answers.
+    std::fs::write(dir.path().join("notes.txt"), "second\n").unwrap();
+    assert_eq!(out(&kernel, "cat /notes.txt").await, "first\n");
+    assert_eq!(out(&kernel, "cat /notes.txt | wc -l").await.trim(), "1");
+
+    let entries = kernel.result_cache_entries().await;
+    assert_eq!(entries.len(), 1, "{entries:?}");
+    assert_eq!(entries[0].command, "cat /notes.txt");
+}
+
+#[tokio::test]
+async fn no_cache_refreshes_the_entry() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "first\n").unwrap();
+    let kernel = read_only_kernel(dir.path());
+
+    out(&kernel, "cat /notes.txt").await;
+    std::fs::write(dir.path().join("notes.txt"), "second\n").unwrap();
+    assert_eq!(out(&kernel, "cat --no-cache /notes.txt").await, "second\n");
+    assert_eq!(out(&kernel, "cat /notes.txt").await, "second\n");
+}
+
+#[tokio::test]
+async fn kaish_cache_clear_drops_results() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("data.bin"), "one").unwrap();
+    let kernel = read_only_kernel(dir.path());
+
+    let before = out(&kernel, "checksum /data.bin").await;
+    std::fs::write(dir.path().join("data.bin"), "two").unwrap();
+    assert_eq!(out(&kernel, "checksum /data.bin").await, before);
+
+    assert_eq!(out(&kernel, "kaish-cache clear").await, "cleared 1 cached result(s)\n");
+    assert_ne!(out(&kernel, "checksum /data.bin").await, before);
+}
+
+#[tokio::test]
+async fn writable_files_and_failures_are_not_cached() {
+    let kernel = Kernel::new(KernelConfig::isolated()).expect("kernel creation");
+    out(&kernel, "echo first > /notes.txt; cat /notes.txt").await;
+    assert_eq!(out(&kernel, "echo second > /notes.txt; cat /notes.txt").await, "second\n");
+
+    let dir = tempfile::tempdir().unwrap();
+    let kernel = read_only_kernel(dir.path());
+    let missing = kernel.execute("cat /missing.txt").await.expect("execute");
+    assert!(!missing.ok());
+    assert!(kernel.result_cache_entries().await.is_empty());
+}
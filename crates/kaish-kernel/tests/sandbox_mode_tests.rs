@@ -358,7 +358,7 @@ async fn sandbox_dd_urandom_to_dev_null() {
         .await
         .expect("execute failed");
     assert!(r.ok(), "stderr: {}", r.err);
-    assert!(r.err.contains("10240 bytes copied"), "status: {}", r.err);
+    assert!(r.stderr.contains("10240 bytes copied"), "status: {}", r.stderr);
 }
 
 #[tokio::test]
@@ -374,7 +374,7 @@ async fn sandbox_dd_roundtrip_file_size_exact() {
         .await
         .expect("execute failed");
     assert!(r.ok(), "stderr: {}", r.err);
-    assert!(r.err.contains("10240 bytes copied"), "readback size: {}", r.err);
+    assert!(r.stderr.contains("10240 bytes copied"), "readback size: {}", r.stderr);
 }
 
 #[tokio::test]
@@ -547,7 +547,7 @@ async fn sandbox_binary_redirect_then_cmp() {
     assert!(r.ok(), "stderr: {}", r.err);
     // And the file is exactly 16 bytes (binary-safe size check via dd).
     let size = k.execute("dd if=/tmp/r.bin of=/dev/null").await.expect("execute failed");
-    assert!(size.err.contains("16 bytes copied"), "size: {}", size.err);
+    assert!(size.stderr.contains("16 bytes copied"), "size: {}", size.stderr);
 }
 
 #[tokio::test]
@@ -558,5 +558,5 @@ async fn sandbox_dd_zero_counted() {
         .await
         .expect("execute failed");
     assert!(r.ok(), "stderr: {}", r.err);
-    assert!(r.err.contains("1024 bytes copied"), "status: {}", r.err);
+    assert!(r.stderr.contains("1024 bytes copied"), "status: {}", r.stderr);
 }
@@ -0,0 +1,44 @@
+//! `select`: a choice from a list, read from stdin as a menu number or the
+//! item itself, comes back as `{index, value}`; a headless kernel with no
+//! stdin fails instead of blocking.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+#[tokio::test]
+async fn piped_number_picks_the_item() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"pick=$(echo 2 | select red green blue); echo "${pick[value]} ${pick[index]}""#)
+        .await
+        .expect("execute");
+    assert_eq!(result.text_out(), "green 1\n", "{result:?}");
+}
+
+#[tokio::test]
+async fn list_values_offer_their_elements() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"colors=[red green blue]; echo blue | select prompt="Pick one" $colors"#)
+        .await
+        .expect("execute");
+    assert_eq!(result.text_out(), "blue\n", "{result:?}");
+}
+
+#[tokio::test]
+async fn unknown_answers_and_headless_runs_fail() {
+    let kernel = kernel();
+    let result = kernel.execute("echo 9 | select red green").await.expect("execute");
+    assert_eq!(result.code, 1);
+    assert!(result.err.contains("not one of the 2 items"), "{result:?}");
+
+    let result = kernel.execute("select red green").await.expect("execute");
+    assert_eq!(result.code, 1);
+    assert!(result.err.contains("not interactive"), "{result:?}");
+}
@@ -207,6 +207,42 @@ echo $count
     );
 }
 
+#[tokio::test]
+async fn test_local_in_a_loop_lives_for_the_function() {
+    // `local` binds in the function's frame, not the loop's: the value
+    // declared on the last iteration is still visible after `done`, while the
+    // loop variable itself ends with the loop.
+    let kernel = Kernel::transient().unwrap();
+    let result = kernel
+        .execute(
+            r#"
+i=outer
+last=outer
+f() {
+    for i in $(seq 1 3); do
+        local last = $i
+    done
+    if true; then local flag = set; fi
+    echo "in func: $last $flag [${i:-}]"
+}
+f
+echo "after func: $last $i"
+"#,
+        )
+        .await
+        .unwrap();
+    assert!(
+        result.text_out().contains("in func: 3 set [outer]"),
+        "local outlives loop/if, loop var doesn't leak: {}",
+        result.text_out()
+    );
+    assert!(
+        result.text_out().contains("after func: outer outer"),
+        "caller untouched: {}",
+        result.text_out()
+    );
+}
+
 #[tokio::test]
 async fn test_local_with_command_substitution() {
     let kernel = Kernel::transient().unwrap();
@@ -336,10 +372,10 @@ async fn test_stdout_to_stderr_redirect_1_ampersand_2() {
     let result = kernel.execute("echo error 1>&2").await.unwrap();
     // Output should go to stderr, not stdout
     assert!(
-        result.err.contains("error"),
+        result.stderr.contains("error"),
         "Expected 'error' in stderr: stdout={}, stderr={}",
         result.text_out(),
-        result.err
+        result.stderr
     );
 }
 
@@ -349,10 +385,10 @@ async fn test_stdout_to_stderr_redirect_ampersand_2() {
     // Shorthand form: >&2 is equivalent to 1>&2
     let result = kernel.execute("echo warning >&2").await.unwrap();
     assert!(
-        result.err.contains("warning"),
+        result.stderr.contains("warning"),
         "Expected 'warning' in stderr: stdout={}, stderr={}",
         result.text_out(),
-        result.err
+        result.stderr
     );
 }
 
@@ -282,6 +282,26 @@ shell_compat! {
     eq: "deepest",
 }
 
+// ---- ${VAR:=default}, ${VAR:+alt} ------------------------------------------
+
+shell_compat! {
+    name: var_assign_default_persists,
+    script: r#"echo "${A:=first}"; echo "${A:=second} $A""#,
+    eq: "first\nfirst first",
+}
+
+shell_compat! {
+    name: var_alternate_set_and_unset,
+    script: r#"A=x; echo "[${A:+alt}][${B:+alt}]""#,
+    eq: "[alt][]",
+}
+
+shell_compat! {
+    name: var_alternate_empty_counts_as_unset,
+    script: r#"A=""; echo "[${A:+alt}]""#,
+    eq: "[]",
+}
+
 // ---- Command substitution in [[ ]], case ----------------------------------
 
 shell_compat! {
@@ -0,0 +1,138 @@
+//! Session state export/import: `Kernel::export_state` / `import_state` and
+//! the `kaish-state` builtin carry variables, cwd, and checkpoints between
+//! kernels.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::path::PathBuf;
+
+use kaish_kernel::ast::Value;
+use kaish_kernel::{Kernel, KernelConfig, KernelState};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+#[tokio::test]
+async fn state_moves_between_kernels() {
+    let source = kernel();
+    source
+        .execute("mkdir -p /work; cd /work; export GREETING=hello; COUNT=3; kaish-checkpoint create saved")
+        .await
+        .expect("execute");
+    let json = source.export_state().await.to_json().expect("to_json");
+
+    let target = kernel();
+    target.execute("mkdir -p /work; LEFTOVER=1").await.expect("execute");
+    let state = KernelState::from_json(&json).expect("from_json");
+    let missing = target.import_state(&state).await;
+    assert!(missing.is_empty(), "same mount layout: {missing:?}");
+
+    assert_eq!(target.get_var("GREETING").await, Some(Value::String("hello".into())));
+    assert_eq!(target.get_var("COUNT").await, Some(Value::Int(3)));
+    assert_eq!(target.get_var("LEFTOVER").await, None);
+    assert_eq!(target.cwd().await, PathBuf::from("/work"));
+
+    let checkpoints = target.checkpoints().await;
+    assert_eq!(checkpoints.len(), 1);
+    assert_eq!(checkpoints[0].label.as_deref(), Some("saved"));
+
+    // Exported variables still reach children after the move.
+    let result = target.execute("env").await.expect("execute");
+    assert!(result.text_out().contains("GREETING=hello"), "got: {result:?}");
+}
+
+#[tokio::test]
+async fn builtin_export_then_import_restores_the_session() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(
+            r#"
+            PHASE=before
+            kaish-state export > /session.json
+            PHASE=after
+            EXTRA=1
+            kaish-state import /session.json
+            echo "$PHASE ${EXTRA:-unset}"
+            "#,
+        )
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().trim(), "before unset");
+}
+
+#[tokio::test]
+async fn builtin_import_reads_stdin() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(
+            r#"
+            MODE=saved
+            kaish-state export > /session.json
+            MODE=changed
+            cat /session.json | kaish-state import
+            echo $MODE
+            "#,
+        )
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().trim(), "saved");
+}
+
+#[tokio::test]
+async fn import_rejects_garbage() {
+    let kernel = kernel();
+    let result = kernel
+        .execute("echo 'not json' > /bad.json; kaish-state import /bad.json")
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 1, "got: {result:?}");
+    assert!(result.err.contains("parsing kernel state"), "got: {result:?}");
+}
+
+#[tokio::test]
+async fn diff_against_a_checkpoint_reports_changes() {
+    let kernel = kernel();
+    kernel
+        .execute("KEPT=1; EDITED=old; DROPPED=x; kaish-checkpoint create base; EDITED=new; unset DROPPED; ADDED=y")
+        .await
+        .expect("execute");
+
+    let result = kernel.execute("kaish-state diff --checkpoint base --json").await.expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    let changes: serde_json::Value = serde_json::from_str(&result.text_out()).expect("json");
+    let summary: Vec<String> = changes
+        .as_array()
+        .expect("array")
+        .iter()
+        .map(|c| format!("{} {} {}", c["name"].as_str().unwrap(), c["kind"].as_str().unwrap(), c["change"].as_str().unwrap()))
+        .collect();
+    assert_eq!(summary, vec!["DROPPED var removed", "EDITED var changed", "ADDED var added"]);
+    assert_eq!(changes[1]["old"], "old");
+    assert_eq!(changes[1]["new"], "new");
+}
+
+#[tokio::test]
+async fn diff_of_two_exports() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(
+            r#"
+            X=1
+            kaish-state export > /a.json
+            X=2
+            kaish-state export > /b.json
+            kaish-state diff /a.json /b.json
+            kaish-state diff /b.json
+            "#,
+        )
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    let out = result.text_out();
+    assert!(out.contains("changed"), "got: {out}");
+    assert!(out.trim_end().ends_with("no differences"), "got: {out}");
+}
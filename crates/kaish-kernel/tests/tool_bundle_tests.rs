@@ -0,0 +1,68 @@
+//! Tool bundles (`KernelConfig::with_tool_bundle`): an out-of-tree set of
+//! tools is registered in every kernel built from the config, after the
+//! builtins.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use kaish_kernel::tools::{ToolArgs, ToolCtx, ToolSchema};
+use kaish_kernel::{Kernel, KernelConfig, Tool, ToolBundle};
+use kaish_types::ExecResult;
+
+/// A tool that prints a fixed greeting.
+struct Greet(&'static str, &'static str);
+
+#[async_trait]
+impl Tool for Greet {
+    fn name(&self) -> &str {
+        self.0
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema::new(self.0, "test tool: print a greeting")
+    }
+
+    async fn execute(&self, _args: ToolArgs, _ctx: &mut dyn ToolCtx) -> ExecResult {
+        ExecResult::success(format!("{}\n", self.1))
+    }
+}
+
+struct Greetings;
+
+impl ToolBundle for Greetings {
+    fn name(&self) -> &str {
+        "greetings"
+    }
+
+    fn tools(&self) -> Vec<Arc<dyn Tool>> {
+        vec![Arc::new(Greet("hello", "hello from a bundle")), Arc::new(Greet("uname", "shadowed"))]
+    }
+}
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated().with_tool_bundle(Arc::new(Greetings))).expect("kernel creation")
+}
+
+#[tokio::test]
+async fn bundle_tools_are_callable() {
+    let result = kernel().execute("hello | wc -l").await.expect("execute");
+    assert_eq!(result.text_out().trim(), "1");
+    let result = kernel().execute("hello").await.expect("execute");
+    assert_eq!(result.text_out(), "hello from a bundle\n");
+}
+
+#[tokio::test]
+async fn bundle_tool_replaces_a_builtin_of_the_same_name() {
+    let result = kernel().execute("uname").await.expect("execute");
+    assert_eq!(result.text_out(), "shadowed\n");
+}
+
+#[tokio::test]
+async fn bundles_do_not_leak_into_other_configs() {
+    let plain = Kernel::new(KernelConfig::isolated()).expect("kernel creation");
+    let result = plain.execute("hello").await.expect("execute");
+    assert_eq!(result.code, 127);
+}
@@ -0,0 +1,84 @@
+//! Tool library (`KernelConfig::with_tool_library`): function definitions in
+//! a directory's `.kai` files are registered before the first request, later
+//! directories override earlier ones, and `kaish-tools reload` re-scans.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::path::Path;
+use std::sync::Arc;
+
+use kaish_kernel::vfs::{Filesystem, MemoryFs, VfsRouter};
+use kaish_kernel::{Kernel, KernelBackend, KernelConfig, LocalBackend};
+
+/// A kernel over `fs` whose tool library is `/user/tools` then `/project/tools`.
+fn library_kernel(fs: MemoryFs) -> Kernel {
+    let mut vfs = VfsRouter::new();
+    vfs.mount("/", fs);
+    let backend: Arc<dyn KernelBackend> = Arc::new(LocalBackend::new(Arc::new(vfs)));
+    let config = KernelConfig::isolated()
+        .with_tool_library("/user/tools")
+        .with_tool_library("/project/tools");
+    Kernel::with_backend(backend, config, |_| {}, |_| {}).expect("with_backend kernel")
+}
+
+async fn write(fs: &MemoryFs, path: &str, text: &str) {
+    let path = Path::new(path);
+    if let Some(parent) = path.parent() {
+        fs.mkdir(parent).await.ok();
+    }
+    fs.write(path, text.as_bytes()).await.expect("write");
+}
+
+async fn out(kernel: &Kernel, script: &str) -> String {
+    let result = kernel.execute(script).await.expect("execute");
+    assert!(result.ok(), "{script}: {result:?}");
+    result.text_out().into_owned()
+}
+
+#[tokio::test]
+async fn library_tools_are_defined_on_the_first_request() {
+    let fs = MemoryFs::new();
+    fs.mkdir(Path::new("/user")).await.unwrap();
+    write(&fs, "/user/tools/greet.kai", "greet() { echo \"hello $1\"; }\n").await;
+    // Only definitions load: the file's other statements never run.
+    write(&fs, "/user/tools/side.kai", "echo ran > /side-effect\nfunction bye { echo bye; }\n").await;
+    write(&fs, "/user/tools/README.md", "ignored() { echo no; }\n").await;
+    let kernel = library_kernel(fs);
+
+    assert_eq!(out(&kernel, "greet world").await, "hello world\n");
+    assert_eq!(out(&kernel, "bye").await, "bye\n");
+    assert!(!kernel.has_function("ignored").await);
+    assert!(!kernel.execute("cat /side-effect").await.expect("execute").ok());
+}
+
+#[tokio::test]
+async fn project_library_overrides_user_library() {
+    let fs = MemoryFs::new();
+    fs.mkdir(Path::new("/user")).await.unwrap();
+    fs.mkdir(Path::new("/project")).await.unwrap();
+    write(&fs, "/user/tools/deploy.kai", "deploy() { echo user; }\n").await;
+    write(&fs, "/project/tools/deploy.kai", "deploy() { echo project; }\n").await;
+    let kernel = library_kernel(fs);
+
+    let report = kernel.load_tool_library().await;
+    assert_eq!(report.loaded, ["deploy", "deploy"]);
+    assert!(report.errors.is_empty(), "{report:?}");
+    assert_eq!(out(&kernel, "deploy").await, "project\n");
+}
+
+#[tokio::test]
+async fn reload_picks_up_new_definitions_and_reports_errors() {
+    let kernel = library_kernel(MemoryFs::new());
+    assert!(!kernel.has_function("later").await);
+
+    out(&kernel, "mkdir -p /user/tools; echo 'later() { echo now; }' > /user/tools/later.kai").await;
+    assert_eq!(out(&kernel, "kaish-tools reload").await, "loaded 1 tool(s): later\n");
+    assert_eq!(out(&kernel, "later").await, "now\n");
+
+    out(&kernel, "echo 'broken() {' > /user/tools/broken.kai").await;
+    let result = kernel.execute("kaish-tools reload").await.expect("execute");
+    assert_eq!(result.code, 1);
+    assert!(result.err.contains("/user/tools/broken.kai"), "{result:?}");
+    assert_eq!(result.text_out(), "loaded 1 tool(s): later\n");
+}
@@ -0,0 +1,80 @@
+//! Per-tool timeouts (`ToolSchema::timeout_ms`): the kernel fails a call that
+//! outlives its tool's declared limit with exit code 124 naming the tool, so
+//! one hung stage can't stall a pipeline.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use kaish_kernel::tools::{ToolArgs, ToolCtx, ToolSchema};
+use kaish_kernel::vfs::{MemoryFs, VfsRouter};
+use kaish_kernel::{Kernel, KernelBackend, KernelConfig, LocalBackend, Tool};
+use kaish_types::{ExecResult, Value};
+
+/// `hang <ms>` sleeps, ignoring cancellation, under a 200ms tool limit.
+struct Hang;
+
+#[async_trait]
+impl Tool for Hang {
+    fn name(&self) -> &str {
+        "hang"
+    }
+
+    fn schema(&self) -> ToolSchema {
+        ToolSchema::new("hang", "test tool: sleep past its own limit")
+            .with_timeout(Duration::from_millis(200))
+            .with_retryable()
+    }
+
+    async fn execute(&self, args: ToolArgs, _ctx: &mut dyn ToolCtx) -> ExecResult {
+        let ms = match args.get_positional(0) {
+            Some(Value::Int(i)) => u64::try_from(*i).unwrap_or(0),
+            other => panic!("hang: bad positional: {other:?}"),
+        };
+        tokio::time::sleep(Duration::from_millis(ms)).await;
+        ExecResult::success("hang: done\n")
+    }
+}
+
+fn hang_kernel() -> Kernel {
+    let mut vfs = VfsRouter::new();
+    vfs.mount("/", MemoryFs::new());
+    let backend: Arc<dyn KernelBackend> = Arc::new(LocalBackend::new(Arc::new(vfs)));
+    Kernel::with_backend(backend, KernelConfig::isolated(), |_| {}, |tools| {
+        tools.register(Hang);
+    })
+    .expect("with_backend kernel")
+}
+
+#[tokio::test]
+async fn call_within_the_limit_completes() {
+    let kernel = hang_kernel();
+    let result = kernel.execute("hang 10").await.expect("execute");
+    assert!(result.ok(), "{result:?}");
+    assert_eq!(result.text_out(), "hang: done\n");
+}
+
+#[tokio::test]
+async fn call_past_the_limit_fails_with_124() {
+    let kernel = hang_kernel();
+    let started = Instant::now();
+    let result = kernel.execute("hang 10000 | wc -l; echo after").await.expect("execute");
+    assert!(started.elapsed() < Duration::from_secs(5), "took {:?}", started.elapsed());
+    assert_eq!(result.text_out().trim_end().lines().last(), Some("after"));
+
+    let result = kernel.execute("hang 10000").await.expect("execute");
+    assert_eq!(result.code, 124);
+    assert!(result.err.contains("hang: timed out after 200ms"), "{result:?}");
+}
+
+#[tokio::test]
+async fn limit_and_retry_metadata_show_in_kaish_tools() {
+    let kernel = hang_kernel();
+    let result = kernel.execute("kaish-tools hang").await.expect("execute");
+    let text = result.text_out();
+    assert!(text.contains("Timeout: 200ms per call"), "{text}");
+    assert!(text.contains("Retryable: yes"), "{text}");
+}
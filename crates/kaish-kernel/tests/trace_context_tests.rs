@@ -10,6 +10,10 @@
 //!      `tokio::spawn` boundary into forked tasks, which otherwise start with
 //!      an empty OTel current-context and would emit orphan-root spans.
 //!
+//! It also checks the kernel's own structure under the execution span: a
+//! `statement` span per top-level statement and a `command` span per
+//! dispatched command.
+//!
 //! Both tests share one process-global subscriber, so they live in a single
 //! test function (a second `set_global_default` would panic).
 
@@ -89,6 +93,26 @@ async fn embedder_trace_context_reaches_foreground_and_forked_spans() {
         "execution span must parent directly onto the embedder's span id",
     );
 
+    // Inside the execution span: the statement, and the command it ran, with
+    // its name and exit code recorded once it finished.
+    let attr = |span: &opentelemetry_sdk::trace::SpanData, key: &str| {
+        span.attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| kv.value.to_string())
+    };
+    let statement = spans
+        .iter()
+        .find(|s| s.name.as_ref() == "statement" && s.parent_span_id == exec.span_context.span_id())
+        .expect("a statement span under the execution span");
+    assert_eq!(attr(statement, "kind").as_deref(), Some("command"));
+    let command = spans
+        .iter()
+        .find(|s| s.name.as_ref() == "command" && s.parent_span_id == statement.span_context.span_id())
+        .expect("a command span under the statement span");
+    assert_eq!(attr(command, "command").as_deref(), Some("true"));
+    assert_eq!(attr(command, "code").as_deref(), Some("0"));
+
     // Forked worker spans: present, and every one shares the embedder's trace.
     let worker_spans: Vec<_> = spans
         .iter()
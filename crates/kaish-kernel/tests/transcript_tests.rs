@@ -0,0 +1,72 @@
+//! `transcript`: requests between `start` and `stop` land in the file as
+//! Markdown or asciicast, the start and stop themselves stay out, and an
+//! existing file is never overwritten.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+#[tokio::test]
+async fn markdown_records_each_request() {
+    let kernel = kernel();
+    let started = kernel.execute("transcript start /tmp/session.md").await.expect("execute");
+    assert!(started.ok(), "{started:?}");
+    kernel.execute("echo hi").await.expect("execute");
+    kernel.execute("echo oops >&2; false").await.expect("execute");
+    let stopped = kernel.execute("transcript stop").await.expect("execute");
+    assert_eq!(stopped.text_out().trim_end(), "/tmp/session.md (md, 2 requests)", "{stopped:?}");
+
+    let result = kernel.execute("cat /tmp/session.md").await.expect("execute");
+    let text = result.text_out();
+    assert!(text.starts_with("# kaish transcript\n"), "{text}");
+    assert!(text.contains("```sh\necho hi\n```\n\n```text\nhi\n```\n\n*exit 0 · "), "{text}");
+    assert!(text.contains("stderr:\n\n```text\noops\n```\n\n*exit 1 · "), "{text}");
+    assert!(!text.contains("transcript st"), "start and stop aren't recorded: {text}");
+
+    // Nothing is appended once stopped.
+    let after = kernel.execute("cat /tmp/session.md").await.expect("execute");
+    assert_eq!(after.text_out(), text);
+}
+
+#[tokio::test]
+async fn cast_paths_record_asciicast() {
+    let kernel = kernel();
+    kernel.execute("transcript start /tmp/session.cast").await.expect("execute");
+    kernel.execute("echo hi").await.expect("execute");
+    kernel.execute("transcript stop").await.expect("execute");
+
+    let result = kernel.execute("cat /tmp/session.cast").await.expect("execute");
+    let lines: Vec<serde_json::Value> =
+        result.text_out().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(lines[0]["version"], 2);
+    assert_eq!(lines[1][1], "o");
+    assert_eq!(lines[1][2], "$ echo hi\r\n");
+    assert_eq!(lines[2][2], "hi\r\n");
+}
+
+#[tokio::test]
+async fn existing_files_are_not_overwritten() {
+    let kernel = kernel();
+    kernel.execute("echo keep > /tmp/notes.md").await.expect("execute");
+    let result = kernel.execute("transcript start /tmp/notes.md").await.expect("execute");
+    assert!(!result.ok(), "{result:?}");
+    let status = kernel.execute("transcript status").await.expect("execute");
+    assert_eq!(status.text_out().trim_end(), "not recording");
+    let notes = kernel.execute("cat /tmp/notes.md").await.expect("execute");
+    assert_eq!(notes.text_out(), "keep\n");
+}
+
+#[tokio::test]
+async fn one_transcript_at_a_time() {
+    let kernel = kernel();
+    kernel.execute("transcript start /tmp/a.md").await.expect("execute");
+    let result = kernel.execute("transcript start /tmp/b.md").await.expect("execute");
+    assert!(result.err.contains("already recording to /tmp/a.md"), "{result:?}");
+    let stop = kernel.execute("transcript stop; transcript stop").await.expect("execute");
+    assert!(stop.err.contains("not recording"), "{stop:?}");
+}
@@ -0,0 +1,98 @@
+//! `try { ... } catch { ... }`: the first failing statement in the body stops
+//! it and runs the handler with `${ERROR}` and `$?` describing the failure,
+//! while `&&`/`||` chains, `exit`, and functions keep their usual meaning.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+async fn out(kernel: &Kernel, script: &str) -> String {
+    let result = kernel.execute(script).await.expect("execute");
+    assert!(result.ok(), "{script}: {result:?}");
+    result.text_out().into_owned()
+}
+
+#[tokio::test]
+async fn failure_routes_to_the_handler() {
+    let kernel = kernel();
+    let output = out(
+        &kernel,
+        "try {\n  echo before\n  cat /no/such/file\n  echo after\n} catch {\n  echo \"caught $?: $ERROR\"\n}\necho end",
+    )
+    .await;
+    assert!(output.starts_with("before\ncaught 1: "), "{output}");
+    assert!(output.contains("/no/such/file"), "{output}");
+    assert!(output.ends_with("end\n"), "{output}");
+    assert!(!output.contains("after"), "{output}");
+
+    assert_eq!(out(&kernel, "try { false; } catch { echo \"$ERROR\"; }").await, "exit code 1\n");
+    assert_eq!(out(&kernel, "try { echo fine; } catch { echo never; }").await, "fine\n");
+}
+
+#[tokio::test]
+async fn caught_unset_parameter_stops_only_the_body() {
+    let kernel = kernel();
+    assert_eq!(
+        out(&kernel, "try { echo ${token:?set token}; echo no; } catch { echo caught; }; echo after").await,
+        "caught\nafter\n"
+    );
+}
+
+#[tokio::test]
+async fn chains_handle_their_own_failures() {
+    let kernel = kernel();
+    // A failure on the left of `||` is the chain's to handle, as under `set -e`.
+    assert_eq!(
+        out(&kernel, "try { false || echo recovered; echo next; } catch { echo caught; }").await,
+        "recovered\nnext\n"
+    );
+    // A caught block has its handler's status.
+    assert_eq!(
+        out(&kernel, "try { false; } catch { echo handled; } && echo then").await,
+        "handled\nthen\n"
+    );
+    assert_eq!(
+        out(&kernel, "try { false; } catch { false; } || echo handler failed").await,
+        "handler failed\n"
+    );
+    // A `try` on the left of a chain still catches inside its body.
+    assert_eq!(
+        out(&kernel, "try { false; echo no; } catch { echo caught; } && echo then").await,
+        "caught\nthen\n"
+    );
+}
+
+#[tokio::test]
+async fn failures_inside_functions_are_caught() {
+    let kernel = kernel();
+    out(&kernel, "deploy() { echo start; cat /missing.cfg; echo never; }").await;
+    let output = out(&kernel, "try { deploy; echo skipped; } catch { echo \"caught $?: $ERROR\"; }").await;
+    assert!(output.starts_with("start\ncaught 1: "), "{output}");
+    assert!(output.contains("/missing.cfg"), "{output}");
+    assert!(!output.contains("never") && !output.contains("skipped"), "{output}");
+}
+
+#[tokio::test]
+async fn exit_is_not_caught() {
+    let kernel = kernel();
+    let result = kernel
+        .execute("try { exit 3; } catch { echo caught; }; echo unreachable")
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 3, "{result:?}");
+    assert!(!result.text_out().contains("caught"), "{result:?}");
+    assert!(!result.text_out().contains("unreachable"), "{result:?}");
+}
+
+#[tokio::test]
+async fn error_is_known_to_the_validator() {
+    let kernel = kernel();
+    let result = kernel.execute("try { false; } catch { echo $ERROR; }").await.expect("execute");
+    assert!(result.ok(), "{result:?}");
+    assert!(!result.err.contains("may be undefined"), "{result:?}");
+}
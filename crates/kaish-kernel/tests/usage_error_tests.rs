@@ -0,0 +1,55 @@
+//! Usage errors at dispatch: when a builtin rejects its arguments, the
+//! kernel answers from the tool's schema — what's wrong, the closest flag,
+//! the usage line, and the parameters — as text and as `.data`.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+use kaish_types::Value;
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+#[tokio::test]
+async fn mistyped_flag_suggests_the_schema_spelling() {
+    let kernel = kernel();
+    let result = kernel.execute("cat --nubmer /v/notes.txt").await.expect("execute");
+    assert_eq!(result.code, 2, "{result:?}");
+    assert_eq!(
+        result.err.trim_end(),
+        "cat: unknown flag '--nubmer' (did you mean '--number'?)\n\
+         usage: cat [OPTIONS] [PATHS]\n\
+         parameters: -n/--number, --range, PATHS"
+    );
+
+    let Some(Value::Json(data)) = &result.data else {
+        panic!("usage error carries structured data: {result:?}");
+    };
+    assert_eq!(data["tool"], "cat");
+    assert_eq!(data["problems"][0]["hint"], "did you mean '--number'?");
+    assert_eq!(data["usage"], "cat [OPTIONS] [PATHS]");
+}
+
+#[tokio::test]
+async fn flags_far_from_any_name_get_no_suggestion() {
+    let kernel = kernel();
+    let result = kernel.execute("head --bogus /v/notes.txt").await.expect("execute");
+    assert_eq!(result.code, 2, "{result:?}");
+    assert!(result.err.starts_with("head: unknown flag '--bogus'\n"), "{result:?}");
+    assert!(result.err.contains("usage: head"), "{result:?}");
+}
+
+#[tokio::test]
+async fn calls_the_schema_accepts_keep_the_tool_result() {
+    let kernel = kernel();
+    kernel.execute("echo hi > /v/notes.txt").await.expect("execute");
+    let result = kernel.execute("cat -n /v/notes.txt").await.expect("execute");
+    assert!(result.ok(), "{result:?}");
+
+    // A failure that isn't about usage passes through untouched.
+    let missing = kernel.execute("cat /v/missing.txt").await.expect("execute");
+    assert_ne!(missing.code, 2, "{missing:?}");
+    assert!(!missing.err.contains("usage:"), "{missing:?}");
+}
@@ -137,6 +137,31 @@ async fn validation_blocks_bare_var_in_for_loop() {
     );
 }
 
+#[tokio::test]
+async fn validation_blocks_membership_in_a_literal_scalar() {
+    let kernel = make_kernel().await;
+    for script in [
+        r#"if [[ alice in "alice,bob" ]]; then echo hit; fi"#,
+        r#"if [[ 1 not in 123 ]]; then echo hit; fi"#,
+    ] {
+        let result = kernel.execute(script).await;
+        assert!(result.is_err(), "`in` on a literal scalar should fail validation: {script}");
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("E020") && err.contains("list or record"), "got: {err}");
+    }
+}
+
+#[tokio::test]
+async fn validation_allows_membership_in_a_variable() {
+    // A variable may hold a collection, so only the runtime can judge it.
+    let kernel = make_kernel().await;
+    let result = kernel
+        .execute(r#"users=[alice bob]; if [[ alice in $users ]]; then echo hit; fi"#)
+        .await
+        .expect("membership in a list variable should pass validation");
+    assert_eq!(result.text_out().trim(), "hit");
+}
+
 #[tokio::test]
 async fn validation_allows_split_in_for_loop() {
     let kernel = make_kernel().await;
@@ -738,9 +763,8 @@ async fn validation_issue_in_heredoc_body_carries_span() {
 
 #[tokio::test]
 async fn validation_issue_in_double_quoted_string_still_works() {
-    // Sibling check: spanless interpolation (regular double-quoted strings)
-    // still produces issues, just without spans. This pins the asymmetry —
-    // universal spanning is a follow-up refactor.
+    // Sibling check: double-quoted strings carry no per-part spans, so an
+    // issue inside one falls back to the span of the command it sits in.
     use std::collections::HashMap;
     use kaish_kernel::parser::parse;
     use kaish_kernel::tools::{register_builtins, ToolRegistry};
@@ -759,11 +783,32 @@ async fn validation_issue_in_double_quoted_string_still_works() {
         .iter()
         .find(|i| i.message.contains("UNDEFINED_VAR_TWO"))
         .expect("should still warn about double-quoted-string undefs");
-    // Spanless path — span is None until universal spanning lands.
-    assert!(
-        undef.span.is_none(),
-        "double-quoted strings remain spanless until follow-up refactor",
-    );
+    let span = undef.span.expect("issue should carry the enclosing command's span");
+    assert_eq!(span.start, 0);
+    assert_eq!(span.end, source.len());
+}
+
+#[tokio::test]
+async fn validation_issues_carry_command_spans() {
+    use std::collections::HashMap;
+    use kaish_kernel::parser::parse;
+    use kaish_kernel::tools::{register_builtins, ToolRegistry};
+    use kaish_kernel::validator::Validator;
+
+    let source = "echo ok\nif true; then\n  nosuch_cmd_xyz --flag\nfi";
+    let program = parse(source).expect("source parses");
+    let mut registry = ToolRegistry::new();
+    register_builtins(&mut registry);
+    let user_tools = HashMap::new();
+    let issues = Validator::new(&registry, &user_tools).validate(&program);
+
+    let undef = issues
+        .iter()
+        .find(|i| i.message.contains("nosuch_cmd_xyz"))
+        .expect("expected an undefined-command warning");
+    let span = undef.span.expect("command issues should carry the command's span");
+    assert_eq!(span.to_line_col(source), (3, 3));
+    assert_eq!(&source[span.start..span.end], "nosuch_cmd_xyz --flag");
 }
 
 #[tokio::test]
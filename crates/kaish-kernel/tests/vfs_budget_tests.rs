@@ -303,3 +303,35 @@ async fn byte_budget_reexport_serves_with_backend_embedders() {
     let result = kernel.execute("echo probe").await.expect("execute");
     assert_eq!(result.code, 0, "kernel still healthy after budget rejection");
 }
+
+// ---------------------------------------------------------------------------
+// Per-mount quotas and `df`
+// ---------------------------------------------------------------------------
+
+/// A quota caps one mount on its own: the mount next to it keeps taking
+/// writes, and `df` reports the capped mount's usage against its quota.
+#[tokio::test]
+async fn mount_quota_fails_loudly_and_shows_in_df() {
+    use std::sync::Arc;
+    use kaish_kernel::vfs::{MemoryFs, VfsRouter};
+    use kaish_kernel::{KernelBackend, LocalBackend};
+
+    let mut vfs = VfsRouter::new();
+    vfs.mount("/", MemoryFs::new());
+    vfs.mount("/scratch", MemoryFs::new().with_quota(16));
+    let backend: Arc<dyn KernelBackend> = Arc::new(LocalBackend::new(Arc::new(vfs)));
+    let kernel = kaish_kernel::Kernel::with_backend(backend, KernelConfig::isolated(), |_| {}, |_| {})
+        .expect("with_backend kernel");
+
+    let (_, code) = run(&kernel, r#"write /scratch/a.txt "0123456789""#).await;
+    assert_eq!(code, 0);
+    let result = kernel.execute(r#"write /scratch/b.txt "0123456789""#).await.expect("execute");
+    assert_ne!(result.code, 0, "write past the quota must fail: {result:?}");
+    assert!(result.err.contains("quota"), "error must name the quota: {result:?}");
+    let (_, code) = run(&kernel, r#"write /b.txt "0123456789""#).await;
+    assert_eq!(code, 0, "other mounts are not capped by /scratch's quota");
+
+    let (out, code) = run(&kernel, "df /scratch").await;
+    assert_eq!(code, 0);
+    assert!(out.lines().any(|l| l == "/scratch\t10\t16\t6\t63%"), "df row: {out}");
+}
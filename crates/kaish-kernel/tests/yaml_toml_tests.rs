@@ -0,0 +1,76 @@
+//! The `yaml` and `toml` builtins: parse config documents, query a path,
+//! and write values back out.
+
+// Test-fixture code: unwrap/expect on known-good setup is the idiom here.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use kaish_kernel::{Kernel, KernelConfig};
+
+fn kernel() -> Kernel {
+    Kernel::new(KernelConfig::isolated()).expect("kernel creation")
+}
+
+#[tokio::test]
+async fn toml_get_reads_a_cargo_manifest_field() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(
+            r#"
+            printf '[package]\nname = "demo"\nversion = "0.3.1"\n\n[[bin]]\nname = "demo-cli"\n' > /Cargo.toml
+            toml get package.version /Cargo.toml
+            toml get 'bin[0].name' /Cargo.toml
+            "#,
+        )
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    let out = result.text_out();
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines, vec!["0.3.1", "demo-cli"]);
+}
+
+#[cfg(feature = "yaml")]
+#[tokio::test]
+async fn yaml_parse_feeds_jq() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"printf 'jobs:\n  test:\n    runs-on: ubuntu\n    steps: [a, b]\n' | yaml parse | jq -c '.jobs.test'"#)
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    assert_eq!(result.text_out().trim(), r#"{"runs-on":"ubuntu","steps":["a","b"]}"#);
+}
+
+#[cfg(feature = "yaml")]
+#[tokio::test]
+async fn toml_to_yaml_round_trip() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"printf 'a = 1\n[t]\nb = "x"\n' | toml parse | yaml write | yaml parse | toml write"#)
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 0, "got: {result:?}");
+    let out = result.text_out();
+    assert!(out.contains("a = 1"), "got: {out}");
+    assert!(out.contains("[t]") && out.contains("b = \"x\""), "got: {out}");
+}
+
+#[cfg(feature = "yaml")]
+#[tokio::test]
+async fn missing_path_is_exit_1() {
+    let kernel = kernel();
+    let result = kernel
+        .execute(r#"printf 'a: 1\n' | yaml get b.c"#)
+        .await
+        .expect("execute");
+    assert_eq!(result.code, 1, "got: {result:?}");
+    assert!(result.err.contains("no such path: b.c"), "got: {result:?}");
+}
+
+#[tokio::test]
+async fn parse_errors_are_loud() {
+    let kernel = kernel();
+    let result = kernel.execute(r#"printf 'a = = 1\n' | toml parse"#).await.expect("execute");
+    assert_eq!(result.code, 1, "got: {result:?}");
+    assert!(result.err.starts_with("toml parse:"), "got: {result:?}");
+}
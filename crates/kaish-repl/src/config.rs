@@ -0,0 +1,410 @@
+//! REPL configuration file (`~/.config/kaish/repl.toml`).
+//!
+//! Top-level keys are REPL-only presentation settings and land in
+//! [`ReplConfig`]. The `[kernel]` table holds settings the kernel owns; they
+//! are applied onto a [`KernelConfig`] with [`KernelSettings::apply`] so the
+//! kernel is configured the same way an embedder would configure it.
+//!
+//! ```toml
+//! prompt = "会sh> "
+//! history_size = 5000
+//! theme = "light"          # dark | light | none
+//! startup = "~/.kaishrc"   # rc file sourced through the kernel at launch
+//! truncate_lines = 500     # 0 disables truncation
+//!
+//! [kernel]
+//! vfs = "sandboxed"        # passthrough | sandboxed | none
+//! sandbox_root = "~/src"
+//! overlay = false
+//! latch = true
+//! trash = true
+//! timeout_ms = 60000      # per-command deadline; exit 124 when it fires
+//! audit = true             # append every command to $XDG_DATA_HOME/kaish/audit.jsonl
+//! audit_max_entries = 50000  # retention for that log; each limit is optional
+//! audit_max_age = "30d"
+//! audit_max_bytes = 10485760
+//!
+//! [kernel.policy]          # see kaish_kernel::policy; refusals exit 77
+//! tools = ["ls", "cat", "grep", "jq"]
+//! writable = ["~/scratch"]
+//! exec = false
+//! ```
+//!
+//! Unknown keys are an error rather than silently ignored, so a typo doesn't
+//! leave a setting quietly unapplied.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use kaish_kernel::{AuditLog, AuditRetention, KernelConfig, Policy, VfsMountMode};
+use toml::{Table, Value};
+
+use crate::history;
+use crate::theme::Theme;
+
+/// REPL-only settings, plus the kernel-owned `[kernel]` table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplConfig {
+    /// Prompt shown when no `kaish_prompt` function is defined.
+    pub prompt: Option<String>,
+    /// Maximum entries kept in the line history and the rich `/history`.
+    pub history_size: usize,
+    /// Color theme; `NO_COLOR` still wins.
+    pub theme: Option<Theme>,
+    /// rc file sourced at launch, instead of the default search.
+    /// `$KAISH_INIT` still takes precedence.
+    pub startup: Option<PathBuf>,
+    /// Interactive output truncation limit; `None` disables truncation.
+    pub truncate_lines: Option<usize>,
+    /// Settings applied onto the kernel's [`KernelConfig`].
+    pub kernel: KernelSettings,
+}
+
+impl Default for ReplConfig {
+    fn default() -> Self {
+        Self {
+            prompt: None,
+            history_size: history::MAX_ENTRIES,
+            theme: None,
+            startup: None,
+            truncate_lines: Some(crate::DEFAULT_TRUNCATE_LINES),
+            kernel: KernelSettings::default(),
+        }
+    }
+}
+
+/// How the local filesystem is exposed, as named in `[kernel] vfs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VfsSetting {
+    /// Native paths, full access (the REPL default).
+    Passthrough,
+    /// Local filesystem restricted to `sandbox_root` (default `$HOME`).
+    Sandboxed,
+    /// Memory only, no host filesystem.
+    None,
+}
+
+/// Kernel-owned settings from the `[kernel]` table. `None` leaves the
+/// kernel's own default in place.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KernelSettings {
+    /// Filesystem exposure mode.
+    pub vfs: Option<VfsSetting>,
+    /// Sandbox root for `vfs = "sandboxed"`.
+    pub sandbox_root: Option<PathBuf>,
+    /// Copy-on-write overlay mode.
+    pub overlay: Option<bool>,
+    /// Confirmation latch for destructive operations.
+    pub latch: Option<bool>,
+    /// Trash-on-delete.
+    pub trash: Option<bool>,
+    /// Per-command deadline ([`KernelConfig::with_request_timeout`]).
+    pub timeout: Option<Duration>,
+    /// Execution policy from `[kernel.policy]` ([`KernelConfig::with_policy`]).
+    pub policy: Option<Policy>,
+    /// Record executed commands to [`AuditLog::default_path`]
+    /// ([`KernelConfig::with_audit_log`]), with origin `repl`.
+    pub audit: Option<bool>,
+    /// Retention for the audit log ([`AuditLog::with_retention`]), from the
+    /// `audit_max_*` keys.
+    pub audit_retention: AuditRetention,
+}
+
+impl KernelSettings {
+    /// Apply these settings onto `config`.
+    pub fn apply(&self, mut config: KernelConfig) -> KernelConfig {
+        match self.vfs {
+            Some(VfsSetting::Passthrough) => config = config.with_vfs_mode(VfsMountMode::Passthrough),
+            Some(VfsSetting::Sandboxed) => {
+                config = config.with_vfs_mode(VfsMountMode::Sandboxed {
+                    root: self.sandbox_root.clone(),
+                });
+            }
+            Some(VfsSetting::None) => config = config.with_vfs_mode(VfsMountMode::NoLocal),
+            None => {}
+        }
+        if let Some(overlay) = self.overlay {
+            config = config.with_overlay(overlay);
+        }
+        if let Some(latch) = self.latch {
+            config = config.with_latch(latch);
+        }
+        if let Some(trash) = self.trash {
+            config = config.with_trash(trash);
+        }
+        if let Some(timeout) = self.timeout {
+            config = config.with_request_timeout(timeout);
+        }
+        if let Some(policy) = &self.policy {
+            config = config.with_policy(policy.clone());
+        }
+        if self.audit == Some(true) {
+            let log = AuditLog::new(AuditLog::default_path(), "repl").with_retention(self.audit_retention);
+            config = config.with_audit_log(Arc::new(log));
+        }
+        config
+    }
+}
+
+impl ReplConfig {
+    /// Default config file location: `$XDG_CONFIG_HOME/kaish/repl.toml`.
+    pub fn default_path() -> PathBuf {
+        kaish_kernel::paths::config_dir().join("repl.toml")
+    }
+
+    /// Load the config from [`ReplConfig::default_path`]; a missing file
+    /// yields the defaults.
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::default_path())
+    }
+
+    /// Load the config from `path`; a missing file yields the defaults.
+    pub fn load_from(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).with_context(|| format!("in {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("reading {}", path.display())),
+        }
+    }
+
+    /// Parse config text.
+    pub fn parse(text: &str) -> Result<Self> {
+        let document: Table = text.parse().context("invalid TOML")?;
+        let mut config = Self::default();
+        for (key, item) in &document {
+            match key.as_str() {
+                "prompt" => config.prompt = Some(string(key, item)?.to_string()),
+                "history_size" => config.history_size = count(key, item)?,
+                "theme" => {
+                    let name = string(key, item)?;
+                    config.theme = Some(Theme::parse(name).with_context(|| {
+                        format!("theme: unknown theme '{name}' (expected dark, light, or none)")
+                    })?);
+                }
+                "startup" => config.startup = Some(expand_home(string(key, item)?)),
+                "truncate_lines" => {
+                    config.truncate_lines = Some(count(key, item)?).filter(|lines| *lines > 0);
+                }
+                "kernel" => {
+                    let table = item
+                        .as_table()
+                        .with_context(|| "kernel: expected a [kernel] table")?;
+                    config.kernel = parse_kernel(table)?;
+                }
+                other => bail!("unknown key '{other}'"),
+            }
+        }
+        Ok(config)
+    }
+}
+
+fn parse_kernel(table: &Table) -> Result<KernelSettings> {
+    let mut settings = KernelSettings::default();
+    for (key, item) in table {
+        match key.as_str() {
+            "vfs" => {
+                settings.vfs = Some(match string(key, item)? {
+                    "passthrough" => VfsSetting::Passthrough,
+                    "sandboxed" => VfsSetting::Sandboxed,
+                    "none" => VfsSetting::None,
+                    other => bail!(
+                        "kernel.vfs: unknown mode '{other}' (expected passthrough, sandboxed, or none)"
+                    ),
+                });
+            }
+            "sandbox_root" => settings.sandbox_root = Some(expand_home(string(key, item)?)),
+            "overlay" => settings.overlay = Some(boolean(key, item)?),
+            "latch" => settings.latch = Some(boolean(key, item)?),
+            "trash" => settings.trash = Some(boolean(key, item)?),
+            "audit" => settings.audit = Some(boolean(key, item)?),
+            "audit_max_entries" => settings.audit_retention.max_entries = Some(count(key, item)?),
+            "audit_max_age" => {
+                let text = string(key, item)?;
+                settings.audit_retention.max_age = Some(
+                    kaish_kernel::duration::parse_duration(text)
+                        .with_context(|| format!("{key}: invalid duration '{text}' (e.g. \"30d\", \"12h\")"))?,
+                );
+            }
+            "audit_max_bytes" => {
+                let bytes = u64::try_from(count(key, item)?)
+                    .with_context(|| format!("{key}: out of range"))?;
+                settings.audit_retention.max_bytes = Some(bytes);
+            }
+            "timeout_ms" => {
+                let millis = u64::try_from(count(key, item)?)
+                    .with_context(|| format!("{key}: out of range"))?;
+                settings.timeout = Some(Duration::from_millis(millis));
+            }
+            "policy" => {
+                let table = item
+                    .as_table()
+                    .with_context(|| "kernel.policy: expected a [kernel.policy] table")?;
+                settings.policy = Some(parse_policy(table)?);
+            }
+            other => bail!("unknown key 'kernel.{other}'"),
+        }
+    }
+    Ok(settings)
+}
+
+fn parse_policy(table: &Table) -> Result<Policy> {
+    let mut policy = Policy::default();
+    for (key, item) in table {
+        match key.as_str() {
+            "tools" => policy = policy.with_allowed_tools(strings(key, item)?),
+            "writable" => {
+                let prefixes: Vec<PathBuf> =
+                    strings(key, item)?.iter().map(|p| expand_home(p)).collect();
+                policy = policy.with_writable_prefixes(prefixes);
+            }
+            "exec" => policy = policy.with_exec(boolean(key, item)?),
+            other => bail!("unknown key 'kernel.policy.{other}'"),
+        }
+    }
+    Ok(policy)
+}
+
+fn string<'a>(key: &str, item: &'a Value) -> Result<&'a str> {
+    item.as_str().with_context(|| format!("{key}: expected a string"))
+}
+
+fn strings(key: &str, item: &Value) -> Result<Vec<String>> {
+    let array = item
+        .as_array()
+        .with_context(|| format!("{key}: expected an array of strings"))?;
+    array
+        .iter()
+        .map(|value| {
+            value
+                .as_str()
+                .map(str::to_string)
+                .with_context(|| format!("{key}: expected an array of strings"))
+        })
+        .collect()
+}
+
+fn boolean(key: &str, item: &Value) -> Result<bool> {
+    item.as_bool().with_context(|| format!("{key}: expected true or false"))
+}
+
+fn count(key: &str, item: &Value) -> Result<usize> {
+    let value = item
+        .as_integer()
+        .with_context(|| format!("{key}: expected an integer"))?;
+    usize::try_from(value).with_context(|| format!("{key}: expected a non-negative integer"))
+}
+
+/// Expand a leading `~/` to the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/")
+        && let Some(dirs) = directories::BaseDirs::new()
+    {
+        return dirs.home_dir().join(rest);
+    }
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_config_is_default() {
+        assert_eq!(ReplConfig::parse("").expect("parse"), ReplConfig::default());
+    }
+
+    #[test]
+    fn parses_repl_and_kernel_settings() {
+        let config = ReplConfig::parse(
+            r#"
+            prompt = "k> "
+            history_size = 50
+            theme = "light"
+            startup = "/etc/kaish/init.kai"
+            truncate_lines = 0
+
+            [kernel]
+            vfs = "sandboxed"
+            sandbox_root = "/srv"
+            latch = true
+            timeout_ms = 1500
+            audit = true
+            audit_max_entries = 100
+            audit_max_age = "7d"
+            "#,
+        )
+        .expect("parse");
+        assert_eq!(config.prompt.as_deref(), Some("k> "));
+        assert_eq!(config.history_size, 50);
+        assert_eq!(config.theme, Some(Theme::Light));
+        assert_eq!(config.startup, Some(PathBuf::from("/etc/kaish/init.kai")));
+        assert_eq!(config.truncate_lines, None);
+        assert_eq!(config.kernel.vfs, Some(VfsSetting::Sandboxed));
+        assert_eq!(config.kernel.sandbox_root, Some(PathBuf::from("/srv")));
+        assert_eq!(config.kernel.latch, Some(true));
+        assert_eq!(config.kernel.trash, None);
+        assert_eq!(config.kernel.timeout, Some(Duration::from_millis(1500)));
+        assert_eq!(config.kernel.audit, Some(true));
+        assert_eq!(config.kernel.audit_retention.max_entries, Some(100));
+        assert_eq!(config.kernel.audit_retention.max_age, Some(Duration::from_secs(7 * 86400)));
+        assert_eq!(config.kernel.audit_retention.max_bytes, None);
+    }
+
+    #[test]
+    fn kernel_settings_apply_onto_kernel_config() {
+        let settings = KernelSettings {
+            vfs: Some(VfsSetting::None),
+            latch: Some(true),
+            trash: Some(true),
+            timeout: Some(Duration::from_secs(5)),
+            ..KernelSettings::default()
+        };
+        let config = settings.apply(KernelConfig::repl());
+        assert!(matches!(config.vfs_mode, VfsMountMode::NoLocal));
+        assert!(config.latch_enabled);
+        assert!(config.trash_enabled);
+        assert_eq!(config.request_timeout, Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn parses_kernel_policy_table() {
+        let config = ReplConfig::parse(
+            r#"
+            [kernel.policy]
+            tools = ["echo", "cat"]
+            writable = ["/scratch"]
+            exec = false
+            "#,
+        )
+        .expect("parse");
+        let policy = config.kernel.policy.expect("policy");
+        assert!(policy.allows_tool("echo") && !policy.allows_tool("rm"));
+        assert!(policy.allows_write(Path::new("/scratch/a")));
+        assert!(!policy.allows_write(Path::new("/etc/a")));
+        assert!(!policy.allow_exec);
+
+        let err = ReplConfig::parse("[kernel.policy]\ntools = \"echo\"").expect_err("type");
+        assert!(format!("{err:#}").contains("array of strings"), "got: {err:#}");
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_bad_values() {
+        let err = ReplConfig::parse("promt = \"x\"").expect_err("typo");
+        assert!(err.to_string().contains("unknown key 'promt'"), "got: {err}");
+        let err = ReplConfig::parse("[kernel]\nvfs = \"cloud\"").expect_err("bad mode");
+        assert!(format!("{err:#}").contains("unknown mode 'cloud'"), "got: {err:#}");
+        let err = ReplConfig::parse("history_size = -1").expect_err("negative");
+        assert!(format!("{err:#}").contains("non-negative"), "got: {err:#}");
+        let err = ReplConfig::parse("theme = 3").expect_err("type");
+        assert!(format!("{err:#}").contains("expected a string"), "got: {err:#}");
+    }
+
+    #[test]
+    fn load_missing_file_is_default() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config = ReplConfig::load_from(&dir.path().join("repl.toml")).expect("load");
+        assert_eq!(config, ReplConfig::default());
+    }
+}
@@ -15,15 +15,21 @@ use kaish_kernel::interpreter::{EntryType, ExecResult, OutputData, OutputNode};
 use kaish_kernel::tools::OutputContext;
 use unicode_width::UnicodeWidthStr;
 
+use crate::theme::{self, Role};
+
+/// How much of a binary result the REPL dumps before summarizing the rest.
+const HEX_PREVIEW_BYTES: usize = 512;
+
 /// Format an ExecResult for display based on the output context.
 ///
 /// This is the main entry point for formatting command output. It uses
 /// structured OutputData if present, otherwise uses raw output.
 pub fn format_output(result: &ExecResult, context: OutputContext) -> String {
     // Binary payload: a human can't read raw bytes — show a hex dump rather
-    // than letting text_out() decode it lossily. See docs/binary-data.md.
+    // than letting text_out() decode it lossily — and only the first few
+    // hundred bytes of it. See docs/binary-data.md.
     if let Some(bytes) = result.out_bytes() {
-        return kaish_kernel::interpreter::hex_dump(bytes);
+        return kaish_kernel::interpreter::hex_preview(bytes, HEX_PREVIEW_BYTES);
     }
 
     // Use OutputData if present
@@ -32,7 +38,7 @@ pub fn format_output(result: &ExecResult, context: OutputContext) -> String {
     }
 
     // No structured output - use raw output
-    result.text_out().into_owned()
+    format_text(&result.text_out(), context)
 }
 
 /// Format OutputData for display based on context.
@@ -50,7 +56,7 @@ pub fn format_output_data(output: &OutputData, context: OutputContext) -> String
 
     // Simple text output
     if let Some(text) = output.as_text() {
-        return text.to_string();
+        return format_text(text, context);
     }
 
     // Check if we have nested children (tree structure)
@@ -268,27 +274,36 @@ pub fn detect_context() -> OutputContext {
     }
 }
 
-/// Colorize an entry based on its type.
+/// Colorize an entry based on its type, in the active theme.
 fn colorize_entry(name: &str, entry_type: Option<EntryType>) -> String {
-    use owo_colors::OwoColorize;
-
-    // Check NO_COLOR environment variable
-    if std::env::var("NO_COLOR").is_ok() {
-        return name.to_string();
-    }
+    let role = match entry_type {
+        Some(EntryType::Directory) => Role::Directory,
+        Some(EntryType::Executable) => Role::Executable,
+        Some(EntryType::Symlink) => Role::Symlink,
+        // EntryType is #[non_exhaustive] — unknown variants render unstyled
+        Some(EntryType::File) | Some(EntryType::Text) | Some(_) | None => return name.to_string(),
+    };
+    theme::paint(name, role)
+}
 
-    // Check TERM=dumb
-    if std::env::var("TERM").map(|t| t == "dumb").unwrap_or(false) {
-        return name.to_string();
+/// Format a failure status line (`✗ …`), colored only for interactive display.
+pub fn format_failure(status: &str, context: OutputContext) -> String {
+    if matches!(context, OutputContext::Interactive) {
+        theme::paint(status, Role::Failure)
+    } else {
+        status.to_string()
     }
+}
 
-    match entry_type {
-        Some(EntryType::Directory) => name.blue().bold().to_string(),
-        Some(EntryType::Executable) => name.green().bold().to_string(),
-        Some(EntryType::Symlink) => name.cyan().to_string(),
-        // EntryType is #[non_exhaustive] — unknown variants render unstyled
-        Some(EntryType::File) | Some(EntryType::Text) | Some(_) | None => name.to_string(),
+/// Format plain text output: JSON is syntax-colored for interactive display,
+/// everything else passes through untouched.
+pub fn format_text(text: &str, context: OutputContext) -> String {
+    if matches!(context, OutputContext::Interactive)
+        && let Some(highlighted) = theme::highlight_json(theme::active(), text)
+    {
+        return highlighted;
     }
+    text.to_string()
 }
 
 #[cfg(test)]
@@ -311,6 +326,14 @@ mod tests {
         assert!(formatted.ends_with("|....A|"), "ascii gutter: {formatted}");
     }
 
+    #[test]
+    fn test_large_binary_result_is_previewed() {
+        let result = ExecResult::success_bytes(vec![0u8; 4096]);
+        let formatted = format_output(&result, OutputContext::Interactive);
+        assert_eq!(formatted.lines().count(), HEX_PREVIEW_BYTES / 16 + 1, "{formatted}");
+        assert!(formatted.ends_with("3584 more bytes (4096 total; pipe to xxd for all of it)"), "{formatted}");
+    }
+
     #[test]
     fn test_detect_context_not_terminal() {
         // In test environment, stdout is typically not a terminal
@@ -0,0 +1,366 @@
+//! Rich command history for the REPL.
+//!
+//! rustyline keeps the plain line history that powers arrow-key recall and
+//! Ctrl-R incremental reverse search. This module keeps a parallel record of
+//! every command the REPL actually executed, with its exit code and wall-clock
+//! duration, so `/history [n]` can show how each one went and `!N` can re-run
+//! one by its index.
+//!
+//! Persisted as one entry per line: `code<TAB>duration_ms<TAB>command`, with
+//! backslashes, tabs, and newlines in the command escaped so multi-line input
+//! (an `if` block, a heredoc) round-trips as a single entry. Once
+//! [`RichHistory::persist_to`] names the file, each entry is appended as it is
+//! recorded, so a crash loses nothing already run; [`RichHistory::save`]
+//! rewrites the file down to the entry cap.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+/// Default cap on entries kept in memory and on disk; the oldest are dropped first.
+pub const MAX_ENTRIES: usize = 1000;
+
+/// Entries shown by a bare `/history`.
+pub const DEFAULT_SHOWN: usize = 20;
+
+/// One executed REPL command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// The command text as submitted.
+    pub command: String,
+    /// Exit code of the command.
+    pub code: i64,
+    /// Wall-clock time the command took.
+    pub duration: Duration,
+}
+
+impl HistoryEntry {
+    /// Whether the command succeeded.
+    pub fn ok(&self) -> bool {
+        self.code == 0
+    }
+}
+
+/// Ordered record of executed commands, oldest first.
+///
+/// Indices shown to the user (and accepted by `!N`) are 1-based positions in
+/// this list.
+#[derive(Debug, Clone)]
+pub struct RichHistory {
+    entries: Vec<HistoryEntry>,
+    max_entries: usize,
+    /// File each new entry is appended to, if any.
+    file: Option<PathBuf>,
+}
+
+impl Default for RichHistory {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            max_entries: MAX_ENTRIES,
+            file: None,
+        }
+    }
+}
+
+impl RichHistory {
+    /// Create an empty history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an executed command, dropping the oldest entry past the cap,
+    /// and append it to the persisted file if there is one. A failed append
+    /// is logged, not returned: the command already ran.
+    pub fn record(&mut self, command: impl Into<String>, code: i64, duration: Duration) {
+        let entry = HistoryEntry {
+            command: command.into(),
+            code,
+            duration,
+        };
+        if let Some(path) = &self.file
+            && let Err(e) = append(path, &entry)
+        {
+            tracing::warn!("Failed to append to rich history: {:#}", e);
+        }
+        self.entries.push(entry);
+        self.enforce_cap();
+    }
+
+    /// Append every entry recorded from now on to `path`. The file only
+    /// grows; call [`Self::save`] first to trim it to the cap.
+    pub fn persist_to(&mut self, path: impl Into<PathBuf>) {
+        self.file = Some(path.into());
+    }
+
+    /// Change the entry cap (default [`MAX_ENTRIES`]), dropping the oldest
+    /// entries if already over it.
+    pub fn set_max_entries(&mut self, max_entries: usize) {
+        self.max_entries = max_entries;
+        self.enforce_cap();
+    }
+
+    fn enforce_cap(&mut self) {
+        if self.entries.len() > self.max_entries {
+            let excess = self.entries.len() - self.max_entries;
+            self.entries.drain(..excess);
+        }
+    }
+
+    /// Number of recorded entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Look up an entry by its 1-based display index.
+    pub fn get(&self, index: usize) -> Option<&HistoryEntry> {
+        index.checked_sub(1).and_then(|i| self.entries.get(i))
+    }
+
+    /// Render the last `count` entries, one per line:
+    /// `  12 ✓   3ms  echo hi`.
+    pub fn render(&self, count: usize) -> String {
+        let start = self.entries.len().saturating_sub(count);
+        let mut out = String::new();
+        for (offset, entry) in self.entries[start..].iter().enumerate() {
+            let glyph = if entry.ok() { "✓" } else { "✗" };
+            let command = entry.command.replace('\n', " ⏎ ");
+            out.push_str(&format!(
+                "{:>5} {} {:>7}  {}\n",
+                start + offset + 1,
+                glyph,
+                format_duration(entry.duration),
+                command
+            ));
+        }
+        out
+    }
+
+    /// Load history from `path`. A missing file yields an empty history;
+    /// malformed lines are skipped.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::new()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("reading {}", path.display()));
+            }
+        };
+        let mut history = Self::new();
+        for line in text.lines() {
+            if let Some(entry) = parse_line(line) {
+                history.entries.push(entry);
+            }
+        }
+        history.enforce_cap();
+        Ok(history)
+    }
+
+    /// Write the history to `path`, creating parent directories as needed.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("creating {}", parent.display()))?;
+        }
+        let text: String = self.entries.iter().map(format_line).collect();
+        std::fs::write(path, text).with_context(|| format!("writing {}", path.display()))
+    }
+}
+
+/// Append one entry to the file at `path`, creating it (and its parent
+/// directories) as needed.
+fn append(path: &Path, entry: &HistoryEntry) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("opening {}", path.display()))?;
+    file.write_all(format_line(entry).as_bytes())
+        .with_context(|| format!("writing {}", path.display()))
+}
+
+fn format_line(entry: &HistoryEntry) -> String {
+    format!("{}\t{}\t{}\n", entry.code, entry.duration.as_millis(), escape(&entry.command))
+}
+
+/// Parse the argument of a `!N` re-run request. Returns `None` when the line
+/// isn't of that shape, so `! cmd` negation still reaches the kernel.
+pub fn parse_rerun(line: &str) -> Option<usize> {
+    let digits = line.strip_prefix('!')?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    if millis < 1000 {
+        format!("{millis}ms")
+    } else if millis < 60_000 {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else {
+        format!("{}m{:02}s", millis / 60_000, (millis / 1000) % 60)
+    }
+}
+
+fn parse_line(line: &str) -> Option<HistoryEntry> {
+    let mut fields = line.splitn(3, '\t');
+    let code = fields.next()?.parse().ok()?;
+    let millis = fields.next()?.parse().ok()?;
+    let command = unescape(fields.next()?);
+    Some(HistoryEntry {
+        command,
+        code,
+        duration: Duration::from_millis(millis),
+    })
+}
+
+fn escape(command: &str) -> String {
+    let mut out = String::with_capacity(command.len());
+    for ch in command.chars() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+fn unescape(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_and_get_are_one_based() {
+        let mut history = RichHistory::new();
+        history.record("echo a", 0, Duration::from_millis(1));
+        history.record("false", 1, Duration::from_millis(2));
+        assert_eq!(history.get(0), None);
+        assert_eq!(history.get(1).map(|e| e.command.as_str()), Some("echo a"));
+        assert_eq!(history.get(2).map(|e| e.code), Some(1));
+        assert_eq!(history.get(3), None);
+    }
+
+    #[test]
+    fn render_shows_index_glyph_and_command() {
+        let mut history = RichHistory::new();
+        history.record("echo a", 0, Duration::from_millis(3));
+        history.record("false", 1, Duration::from_millis(1500));
+        let rendered = history.render(DEFAULT_SHOWN);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("1 ✓") && lines[0].contains("3ms") && lines[0].ends_with("echo a"));
+        assert!(lines[1].contains("2 ✗") && lines[1].contains("1.5s") && lines[1].ends_with("false"));
+    }
+
+    #[test]
+    fn render_limits_to_most_recent() {
+        let mut history = RichHistory::new();
+        for i in 0..5 {
+            history.record(format!("echo {i}"), 0, Duration::ZERO);
+        }
+        let rendered = history.render(2);
+        assert!(!rendered.contains("echo 2"));
+        assert!(rendered.contains("    4 ") && rendered.contains("echo 3"));
+        assert!(rendered.contains("    5 ") && rendered.contains("echo 4"));
+    }
+
+    #[test]
+    fn record_caps_at_max_entries() {
+        let mut history = RichHistory::new();
+        for i in 0..MAX_ENTRIES + 3 {
+            history.record(format!("echo {i}"), 0, Duration::ZERO);
+        }
+        assert_eq!(history.len(), MAX_ENTRIES);
+        assert_eq!(history.get(1).map(|e| e.command.as_str()), Some("echo 3"));
+    }
+
+    #[test]
+    fn set_max_entries_trims_oldest() {
+        let mut history = RichHistory::new();
+        for i in 0..5 {
+            history.record(format!("echo {i}"), 0, Duration::ZERO);
+        }
+        history.set_max_entries(2);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.get(1).map(|e| e.command.as_str()), Some("echo 3"));
+    }
+
+    #[test]
+    fn save_and_load_round_trip_multiline() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("nested").join("history.tsv");
+        let mut history = RichHistory::new();
+        history.record("if true; then\n\techo 'a\\b'\nfi", 0, Duration::from_millis(12));
+        history.record("false", 1, Duration::from_millis(4));
+        history.save(&path).expect("save");
+
+        let loaded = RichHistory::load(&path).expect("load");
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.get(1), history.get(1));
+        assert_eq!(loaded.get(2), history.get(2));
+    }
+
+    #[test]
+    fn persisted_entries_are_on_disk_as_recorded() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("nested").join("history.tsv");
+        let mut history = RichHistory::new();
+        history.persist_to(&path);
+        history.record("echo a", 0, Duration::from_millis(1));
+        history.record("false", 1, Duration::from_millis(2));
+
+        // No save: a crash here must not lose either entry.
+        let loaded = RichHistory::load(&path).expect("load");
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.get(2), history.get(2));
+    }
+
+    #[test]
+    fn load_missing_file_is_empty() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let loaded = RichHistory::load(&dir.path().join("absent")).expect("load");
+        assert!(loaded.is_empty());
+    }
+
+    #[test]
+    fn parse_rerun_only_accepts_bang_digits() {
+        assert_eq!(parse_rerun("!3"), Some(3));
+        assert_eq!(parse_rerun("!42"), Some(42));
+        assert_eq!(parse_rerun("!"), None);
+        assert_eq!(parse_rerun("! true"), None);
+        assert_eq!(parse_rerun("!x"), None);
+        assert_eq!(parse_rerun("echo !3"), None);
+    }
+}
@@ -0,0 +1,193 @@
+//! `kaish kernels`: list and manage saved kernel states.
+//!
+//! The states live in a [`StateStore`] — `NAME.json` documents under
+//! `$XDG_DATA_HOME/kaish/kernels`, as written by `kaish-state export`.
+//!
+//! ```text
+//! kaish kernels list                 # name, size, last used, counts
+//! kaish kernels show NAME            # one state in detail
+//! kaish kernels delete NAME...       # remove states
+//! kaish kernels compact [NAME...]    # rewrite as compact JSON (default: all)
+//! ```
+
+use std::time::SystemTime;
+
+use anyhow::{bail, Result};
+use kaish_kernel::state_store::{SavedKernel, StateStore};
+
+/// The usage line for a bad invocation.
+const USAGE: &str = "usage: kaish kernels <list | show NAME | delete NAME... | compact [NAME...]>";
+
+/// Run `kaish kernels ARGS` against `store`; returns the text to print.
+pub fn run(store: &StateStore, args: &[&str]) -> Result<String> {
+    let now = SystemTime::now();
+    match args {
+        [] | ["list"] => Ok(render_list(&store.list()?, store, now)),
+        ["show", name] => show(store, name, now),
+        ["delete", names @ ..] if !names.is_empty() => {
+            let mut out = String::new();
+            for name in names {
+                store.delete(name)?;
+                out.push_str(&format!("deleted {name}\n"));
+            }
+            Ok(out)
+        }
+        ["compact", names @ ..] => {
+            let names: Vec<String> = if names.is_empty() {
+                store.list()?.into_iter().map(|k| k.name).collect()
+            } else {
+                names.iter().map(|n| n.to_string()).collect()
+            };
+            let mut out = String::new();
+            for name in names {
+                let (before, after) = store.compact(&name)?;
+                out.push_str(&format!("{name}: {} -> {}\n", format_size(before), format_size(after)));
+            }
+            Ok(out)
+        }
+        _ => bail!("{USAGE}"),
+    }
+}
+
+fn render_list(kernels: &[SavedKernel], store: &StateStore, now: SystemTime) -> String {
+    if kernels.is_empty() {
+        return format!("no saved kernels in {}\n", store.dir().display());
+    }
+    let count = |n: Option<usize>| n.map_or_else(|| "?".to_string(), |n| n.to_string());
+    let rows: Vec<[String; 5]> = kernels
+        .iter()
+        .map(|k| {
+            [
+                k.name.clone(),
+                format_size(k.size),
+                ago(k.modified, now),
+                count(k.vars),
+                count(k.checkpoints),
+            ]
+        })
+        .collect();
+    let header = ["NAME", "SIZE", "LAST USED", "VARS", "CHECKPOINTS"];
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let mut out = String::new();
+    for cells in std::iter::once(header.map(String::from)).chain(rows) {
+        let line: Vec<String> = cells.iter().zip(widths).map(|(cell, width)| format!("{cell:<width$}")).collect();
+        out.push_str(line.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+fn show(store: &StateStore, name: &str, now: SystemTime) -> Result<String> {
+    let info = store.info(name)?;
+    let state = store.load(name)?;
+    let mut out = format!(
+        "name: {}\npath: {}\nsize: {}\nlast used: {}\ncwd: {}\nvariables: {} ({} exported)\ncheckpoints: {}\n",
+        info.name,
+        info.path.display(),
+        format_size(info.size),
+        ago(info.modified, now),
+        state.cwd.display(),
+        state.vars.len(),
+        state.exported.len(),
+        state.checkpoints.len(),
+    );
+    for checkpoint in &state.checkpoints {
+        let label = checkpoint.label.as_deref().unwrap_or("-");
+        out.push_str(&format!("  {}  {label}  {}\n", checkpoint.id, checkpoint.created));
+    }
+    if !state.mounts.is_empty() {
+        let mounts: Vec<String> = state.mounts.iter().map(|m| m.path.display().to_string()).collect();
+        out.push_str(&format!("mounts: {}\n", mounts.join(", ")));
+    }
+    Ok(out)
+}
+
+/// `modified` relative to `now`: `just now`, `5m ago`, `3h ago`, `2d ago`.
+fn ago(modified: Option<SystemTime>, now: SystemTime) -> String {
+    let Some(secs) = modified.and_then(|m| now.duration_since(m).ok()).map(|d| d.as_secs()) else {
+        return "-".to_string();
+    };
+    match secs {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..86400 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+/// A byte size for display: `512B`, `4.2KB`, `1.3MB`.
+fn format_size(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = 1024 * 1024;
+    if bytes >= MB {
+        format!("{:.1}MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.1}KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{bytes}B")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    use kaish_kernel::state::KernelState;
+
+    fn store_with_work(dir: &std::path::Path) -> StateStore {
+        let store = StateStore::new(dir);
+        let state = KernelState::from_json(r#"{"version":1,"vars":{},"exported":[],"cwd":"/work"}"#)
+            .expect("state");
+        store.save("work", &state).expect("save");
+        store
+    }
+
+    #[test]
+    fn list_show_compact_delete() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let store = store_with_work(dir.path());
+
+        let list = run(&store, &["list"]).expect("list");
+        let lines: Vec<&str> = list.lines().collect();
+        assert!(lines[0].starts_with("NAME"), "{list}");
+        assert!(lines[1].starts_with("work"), "{list}");
+        assert!(lines[1].contains("just now"), "{list}");
+
+        let show = run(&store, &["show", "work"]).expect("show");
+        assert!(show.contains("cwd: /work\n"), "{show}");
+        assert!(show.contains("variables: 0 (0 exported)\n"), "{show}");
+
+        let compact = run(&store, &["compact"]).expect("compact");
+        assert!(compact.starts_with("work: "), "{compact}");
+
+        assert_eq!(run(&store, &["delete", "work"]).expect("delete"), "deleted work\n");
+        assert!(run(&store, &[]).expect("list").starts_with("no saved kernels in "));
+    }
+
+    #[test]
+    fn errors_name_the_problem() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let store = StateStore::new(dir.path());
+        assert!(run(&store, &["show", "missing"]).is_err());
+        assert!(run(&store, &["delete"]).unwrap_err().to_string().starts_with("usage:"));
+        assert!(run(&store, &["frob"]).unwrap_err().to_string().starts_with("usage:"));
+    }
+
+    #[test]
+    fn ages_and_sizes() {
+        let now = SystemTime::now();
+        assert_eq!(ago(Some(now), now), "just now");
+        assert_eq!(ago(Some(now - Duration::from_secs(300)), now), "5m ago");
+        assert_eq!(ago(Some(now - Duration::from_secs(7200)), now), "2h ago");
+        assert_eq!(ago(Some(now - Duration::from_secs(3 * 86400)), now), "3d ago");
+        assert_eq!(ago(None, now), "-");
+        assert_eq!(format_size(512), "512B");
+        assert_eq!(format_size(4300), "4.2KB");
+    }
+}
@@ -6,13 +6,22 @@
 //! - Tab completion for commands, variables, and paths
 //! - Command execution via the Kernel
 //! - Result formatting with OutputData
-//! - Command history via rustyline
+//! - Command history via rustyline, plus a rich per-command record (`/history`, `!N`)
+//! - A status line for tools that report progress
 
+pub mod config;
 pub mod format;
+pub mod history;
+pub mod kernels;
+pub mod pager;
+pub mod progress;
+pub mod prompt;
+pub mod theme;
 
 use std::borrow::Cow;
 use std::io::IsTerminal;
 use std::path::PathBuf;
+use std::time::Instant;
 
 use anyhow::{Context, Result};
 use rustyline::completion::{Completer, FilenameCompleter, Pair};
@@ -30,6 +39,11 @@ use kaish_kernel::ast::Value;
 use kaish_kernel::interpreter::ExecResult;
 use kaish_kernel::{ExecuteOptions, Kernel, KernelConfig};
 
+use crate::config::ReplConfig;
+use crate::history::RichHistory;
+use crate::pager::{Pager, PagerCommand};
+use crate::theme::Theme;
+
 /// Snapshot the OS environment as a map of `String` → `Value::String`.
 ///
 /// The kernel itself is hermetic — it never reads `std::env::vars()`. The REPL
@@ -421,6 +435,13 @@ impl Helper for KaishHelper {}
 pub struct Repl {
     client: EmbeddedClient,
     runtime: Runtime,
+    history: RichHistory,
+    truncate_lines: Option<usize>,
+    /// Lines held back by the last truncation, shown by `/more`.
+    more: Vec<String>,
+    /// Prompt template (see [`prompt`]) used when no `kaish_prompt`
+    /// function is defined.
+    prompt: Option<String>,
 }
 
 /// Build the tokio runtime kaish execution runs on, with worker threads sized
@@ -455,6 +476,10 @@ impl Repl {
         Ok(Self {
             client: EmbeddedClient::new(kernel),
             runtime,
+            history: RichHistory::new(),
+            truncate_lines: None,
+            more: Vec::new(),
+            prompt: None,
         })
     }
 
@@ -474,6 +499,10 @@ impl Repl {
         Ok(Self {
             client: EmbeddedClient::new(kernel),
             runtime,
+            history: RichHistory::new(),
+            truncate_lines: None,
+            more: Vec::new(),
+            prompt: None,
         })
     }
 
@@ -486,7 +515,93 @@ impl Repl {
     }
 
     /// Process a single line of input.
+    ///
+    /// With a truncation limit set (see [`Repl::set_truncate_lines`]), output
+    /// longer than the limit is cut short with a notice, and the rest is held
+    /// for `/more`.
     pub fn process_line(&mut self, line: &str) -> ProcessResult {
+        match self.process_line_untruncated(line) {
+            ProcessResult::Output(output) => ProcessResult::Output(self.truncate(output)),
+            other => other,
+        }
+    }
+
+    /// Limit interactive output to `lines` lines per command (`None` = never
+    /// truncate, the default for embedders and tests).
+    pub fn set_truncate_lines(&mut self, lines: Option<usize>) {
+        self.truncate_lines = lines;
+    }
+
+    /// Set the prompt template used when no `kaish_prompt` function is
+    /// defined (`None` restores the built-in `会sh> `).
+    pub fn set_prompt(&mut self, prompt: Option<String>) {
+        self.prompt = prompt;
+    }
+
+    /// Render the prompt for the next input line: the output of a
+    /// `kaish_prompt` function if one is defined, else the prompt template.
+    pub fn render_prompt(&self) -> String {
+        let has_fn = self
+            .runtime
+            .block_on(self.client.has_function("kaish_prompt"))
+            .unwrap_or(false);
+        if has_fn
+            && let Ok(result) = self.execute_interruptible("kaish_prompt")
+            && result.ok()
+        {
+            let text = result.text_out().trim_end().to_string();
+            if !text.is_empty() {
+                return text;
+            }
+        }
+        let Some(template) = &self.prompt else {
+            return DEFAULT_PROMPT.to_string();
+        };
+        prompt::render(template, theme::active(), |name| self.prompt_variable(name))
+    }
+
+    /// Resolve a variable named in a prompt template. Lookup failures render
+    /// as unset — a prompt is best-effort and must never block input.
+    fn prompt_variable(&self, name: &str) -> Option<String> {
+        match name {
+            "?" => self
+                .runtime
+                .block_on(self.client.last_result())
+                .ok()
+                .map(|result| result.code.to_string()),
+            "CWD" | "PWD" => self.runtime.block_on(self.client.cwd()).ok(),
+            _ => self
+                .runtime
+                .block_on(self.client.get_var(name))
+                .ok()
+                .flatten()
+                .map(|value| kaish_kernel::interpreter::value_to_string(&value)),
+        }
+    }
+
+    /// Take the lines held back by the last truncation, leaving none for `/more`.
+    pub fn take_more(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.more)
+    }
+
+    /// Cut `output` to the truncation limit, stashing the rest for `/more`.
+    fn truncate(&mut self, output: String) -> String {
+        self.more.clear();
+        let Some(limit) = self.truncate_lines else {
+            return output;
+        };
+        let line_count = output.lines().count();
+        if line_count <= limit {
+            return output;
+        }
+        let mut lines = output.lines();
+        let shown: Vec<&str> = lines.by_ref().take(limit).collect();
+        self.more = lines.map(str::to_string).collect();
+        let notice = format!("… ({} more lines, use /more)", self.more.len());
+        format!("{}\n{}", shown.join("\n"), theme::paint(&notice, theme::Role::Dim))
+    }
+
+    fn process_line_untruncated(&mut self, line: &str) -> ProcessResult {
         let trimmed = line.trim();
 
         // Skip empty lines
@@ -499,34 +614,187 @@ impl Repl {
             return ProcessResult::Exit;
         }
 
-        // Execute via the client with SIGINT handling.
-        // A per-execute signal listener catches Ctrl-C during execution,
-        // cancels the kernel, and returns exit code 130.
+        if let Some(result) = self.process_meta_command(trimmed) {
+            return result;
+        }
+
+        if let Some(index) = history::parse_rerun(trimmed) {
+            let Some(entry) = self.history.get(index) else {
+                return ProcessResult::Output(format!("kaish: !{index}: no such history entry"));
+            };
+            let command = entry.command.clone();
+            return match self.execute_and_record(&command) {
+                ProcessResult::Output(output) => ProcessResult::Output(format!("{command}\n{output}")),
+                _ => ProcessResult::Output(command),
+            };
+        }
+
+        self.execute_and_record(trimmed)
+    }
+
+    /// Rich history of commands executed by this REPL.
+    pub fn history(&self) -> &RichHistory {
+        &self.history
+    }
+
+    /// Replace the rich history, e.g. with one loaded from disk at startup.
+    pub fn set_history(&mut self, history: RichHistory) {
+        self.history = history;
+    }
+
+    /// Handle a REPL meta-command (`/history`, `/theme`, `/prompt`, `/more`,
+    /// `/mount`, `/jobs`, `/mcp`).
+    /// Returns `None` when `line` isn't one, so it falls through to the kernel.
+    ///
+    /// `/mount` and `/jobs` are views over the kernel's own `kaish-mounts` and
+    /// `jobs` builtins, so they show exactly what a script would see. They
+    /// aren't recorded in the rich history.
+    fn process_meta_command(&mut self, line: &str) -> Option<ProcessResult> {
+        let mut words = line.split_whitespace();
+        match words.next()? {
+            "/history" => {
+                let count = match words.next() {
+                    None => history::DEFAULT_SHOWN,
+                    Some(arg) => match arg.parse() {
+                        Ok(count) => count,
+                        Err(_) => {
+                            return Some(ProcessResult::Output(format!(
+                                "/history: expected a count, got '{arg}'"
+                            )));
+                        }
+                    },
+                };
+                let rendered = self.history.render(count);
+                Some(if rendered.is_empty() {
+                    ProcessResult::Empty
+                } else {
+                    ProcessResult::Output(rendered)
+                })
+            }
+            "/theme" => Some(match words.next() {
+                None => {
+                    let active = theme::active();
+                    let listing = Theme::ALL
+                        .iter()
+                        .map(|t| {
+                            let marker = if *t == active { "*" } else { " " };
+                            format!("{marker} {}", t.name())
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    ProcessResult::Output(listing)
+                }
+                Some(name) => match Theme::parse(name) {
+                    Some(theme) => {
+                        theme::set_active(theme);
+                        ProcessResult::Empty
+                    }
+                    None => ProcessResult::Output(format!(
+                        "/theme: unknown theme '{name}' (expected dark, light, or none)"
+                    )),
+                },
+            }),
+            "/prompt" => {
+                let argument = line["/prompt".len()..].trim();
+                Some(match argument {
+                    "" => ProcessResult::Output(
+                        self.prompt.clone().unwrap_or_else(|| DEFAULT_PROMPT.to_string()),
+                    ),
+                    "default" => {
+                        self.prompt = None;
+                        ProcessResult::Empty
+                    }
+                    template => {
+                        self.prompt = Some(unquote(template).to_string());
+                        ProcessResult::Empty
+                    }
+                })
+            }
+            "/more" => Some(if self.more.is_empty() {
+                ProcessResult::Output("/more: nothing more to show".to_string())
+            } else {
+                ProcessResult::Output(std::mem::take(&mut self.more).join("\n"))
+            }),
+            "/mount" => Some(self.run_meta_builtin("kaish-mounts", words)),
+            "/jobs" => Some(self.run_meta_builtin("jobs", words)),
+            "/mcp" => Some(ProcessResult::Output(
+                "/mcp: kaish has no MCP client; MCP servers are configured in the embedder \
+                 (e.g. kaibo, kaijutsu), not the REPL"
+                    .to_string(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Run a builtin on behalf of a meta-command, passing any extra words
+    /// through as its arguments.
+    fn run_meta_builtin<'a>(
+        &self,
+        builtin: &'a str,
+        args: impl Iterator<Item = &'a str>,
+    ) -> ProcessResult {
+        let command = std::iter::once(builtin).chain(args).collect::<Vec<_>>().join(" ");
+        render_outcome(self.execute_interruptible(&command))
+    }
+
+    /// Execute `input` on the kernel and record it in the rich history.
+    fn execute_and_record(&mut self, input: &str) -> ProcessResult {
+        let started = Instant::now();
+        let result = self.execute_interruptible(input);
+        let code = result.as_ref().map_or(1, |r| r.code);
+        self.history.record(input, code, started.elapsed());
+        render_outcome(result)
+    }
+
+    /// Execute via the client with SIGINT handling.
+    ///
+    /// The first Ctrl-C cancels the kernel and then waits for the execution
+    /// to unwind, so the kernel records the interruption in `$?` (exit 130)
+    /// just as it does a timeout (exit 124). A second Ctrl-C stops waiting,
+    /// for a command that doesn't reach a cancellation checkpoint.
+    fn execute_interruptible(&self, input: &str) -> kaish_client::ClientResult<ExecResult> {
         let client = self.client.clone();
-        let input = trimmed.to_string();
-        let result = self.runtime.block_on(async {
+        let input = input.to_string();
+        self.runtime.block_on(async {
             let mut sigint = tokio::signal::unix::signal(
                 tokio::signal::unix::SignalKind::interrupt(),
             )?;
+            let execution = client.execute(&input);
+            tokio::pin!(execution);
             tokio::select! {
-                result = client.execute(&input) => result,
-                _ = sigint.recv() => {
-                    client.cancel().await?;
-                    Ok(ExecResult::failure(130, ""))
-                }
+                result = &mut execution => return result,
+                _ = sigint.recv() => client.cancel().await?,
+            }
+            tokio::select! {
+                result = &mut execution => result,
+                _ = sigint.recv() => Ok(ExecResult::interrupted("interrupted")),
             }
-        });
+        })
+    }
+}
 
-        match result {
-            Ok(exec_result) => {
-                if exec_result.ok() && !exec_result.has_output() && exec_result.text_out().is_empty() {
-                    ProcessResult::Empty
-                } else {
-                    ProcessResult::Output(format_result(&exec_result))
-                }
+/// Strip one layer of matching single or double quotes, so `/prompt` can
+/// take a template with trailing whitespace.
+fn unquote(text: &str) -> &str {
+    for quote in ['"', '\''] {
+        if text.len() >= 2 && text.starts_with(quote) && text.ends_with(quote) {
+            return &text[1..text.len() - 1];
+        }
+    }
+    text
+}
+
+/// Turn an execution outcome into what the REPL prints.
+fn render_outcome(result: kaish_client::ClientResult<ExecResult>) -> ProcessResult {
+    match result {
+        Ok(exec_result) => {
+            if exec_result.ok() && !exec_result.has_output() && exec_result.text_out().is_empty() {
+                ProcessResult::Empty
+            } else {
+                ProcessResult::Output(format_result(&exec_result))
             }
-            Err(e) => ProcessResult::Output(format!("Error: {}", e)),
         }
+        Err(e) => ProcessResult::Output(format!("Error: {}", e)),
     }
 }
 
@@ -542,24 +810,31 @@ impl Default for Repl {
 /// Format an ExecResult for display.
 ///
 /// Uses OutputData when available, otherwise falls back to status+output format.
+/// The captured stderr stream is shown after stdout on success and failure
+/// alike; the `✗` status line carries only the error message (`.err`), so a
+/// diagnostic is never printed twice.
 fn format_result(result: &ExecResult) -> String {
     // If there's structured output, use the formatter
     if result.has_output() {
         let context = format::detect_context();
-        let formatted = format::format_output(result, context);
+        let mut formatted = format::format_output(result, context);
+        push_stderr_stream(&mut formatted, result);
 
         // For failures, append error info
         if !result.ok() && !result.err.is_empty() {
-            return format!("{}\n✗ code={} err=\"{}\"", formatted, result.code, result.err);
+            let status = format!("✗ code={} err=\"{}\"", result.code, result.err);
+            return format!("{}\n{}", formatted, format::format_failure(&status, context));
         }
         return formatted;
     }
 
     // No structured output — just pass through the raw text.
     // Success: show output directly (no status prefix).
-    // Failure: show stderr or exit code so the user notices.
+    // Failure: show the error message or exit code so the user notices.
     if result.ok() {
-        result.text_out().into_owned()
+        let mut output = format::format_text(&result.text_out(), format::detect_context());
+        push_stderr_stream(&mut output, result);
+        output
     } else {
         let mut output = String::new();
         let text = result.text_out();
@@ -569,15 +844,32 @@ fn format_result(result: &ExecResult) -> String {
                 output.push('\n');
             }
         }
-        if !result.err.is_empty() {
-            output.push_str(&format!("✗ {}", result.err));
-        } else {
-            output.push_str(&format!("✗ [exit {}]", result.code));
+        push_stderr_stream(&mut output, result);
+        if !output.is_empty() && !output.ends_with('\n') {
+            output.push('\n');
         }
+        let status = if !result.err.is_empty() {
+            format!("✗ {}", result.err)
+        } else {
+            format!("✗ [exit {}]", result.code)
+        };
+        output.push_str(&format::format_failure(&status, format::detect_context()));
         output
     }
 }
 
+/// Append the result's captured stderr stream, on its own line.
+fn push_stderr_stream(output: &mut String, result: &ExecResult) {
+    let stderr = result.stderr.trim_end_matches('\n');
+    if stderr.is_empty() {
+        return;
+    }
+    if !output.is_empty() && !output.ends_with('\n') {
+        output.push('\n');
+    }
+    output.push_str(stderr);
+}
+
 // ── History ─────────────────────────────────────────────────────────
 
 /// Save REPL history to disk.
@@ -593,6 +885,36 @@ fn save_history(rl: &mut Editor<KaishHelper, DefaultHistory>, history_path: &Opt
     }
 }
 
+/// Where the rich `/history` record is persisted, next to rustyline's history.
+fn rich_history_path() -> Option<PathBuf> {
+    directories::BaseDirs::new().map(|b| b.data_dir().join("kaish").join("history.tsv"))
+}
+
+/// Load the rich history into the REPL, capped at `max_entries`, and append
+/// each command to the same file as it runs. The file is trimmed back to the
+/// cap here, since appending only grows it. Warns (doesn't fail) on a bad
+/// file.
+fn open_rich_history(repl: &mut Repl, path: &Option<PathBuf>, max_entries: usize) {
+    let Some(path) = path else {
+        repl.history.set_max_entries(max_entries);
+        return;
+    };
+    let mut history = match RichHistory::load(path) {
+        Ok(history) => history,
+        Err(e) => {
+            tracing::warn!("Failed to load rich history: {:#}", e);
+            repl.history.set_max_entries(max_entries);
+            return;
+        }
+    };
+    history.set_max_entries(max_entries);
+    if let Err(e) = history.save(path) {
+        tracing::warn!("Failed to trim rich history: {:#}", e);
+    }
+    history.persist_to(path);
+    repl.set_history(history);
+}
+
 /// Load REPL history from disk.
 fn load_history(rl: &mut Editor<KaishHelper, DefaultHistory>) -> Option<PathBuf> {
     let history_path = directories::BaseDirs::new()
@@ -621,7 +943,8 @@ fn load_history(rl: &mut Editor<KaishHelper, DefaultHistory>) -> Option<PathBuf>
 fn rc_file_warning(path: &std::path::Path, outcome: &kaish_client::ClientResult<ExecResult>) -> Option<String> {
     match outcome {
         Ok(result) if !result.ok() => {
-            let err = result.err.trim_end();
+            let stderr = result.stderr_text();
+            let err = stderr.trim_end();
             Some(if err.is_empty() {
                 format!("kaish: warning: {} exited with code {}", path.display(), result.code)
             } else {
@@ -640,10 +963,16 @@ fn rc_file_warning(path: &std::path::Path, outcome: &kaish_client::ClientResult<
 
 /// Load the RC file for interactive sessions.
 ///
-/// Search order: `$KAISH_INIT` → `~/.config/kaish/init.kai` → `~/.kaishrc`
-fn load_rc_file(repl: &Repl) {
+/// Search order: `$KAISH_INIT` → `startup` from `repl.toml` →
+/// `~/.config/kaish/init.kai` → `~/.kaishrc`
+///
+/// Runs interruptibly, so Ctrl-C stops an RC file that hangs and the REPL
+/// starts anyway.
+fn load_rc_file(repl: &Repl, startup: Option<&std::path::Path>) {
     let candidates: Vec<PathBuf> = if let Ok(path) = std::env::var("KAISH_INIT") {
         vec![PathBuf::from(path)]
+    } else if let Some(path) = startup {
+        vec![path.to_path_buf()]
     } else {
         vec![
             kaish_kernel::paths::config_dir().join("init.kai"),
@@ -656,7 +985,7 @@ fn load_rc_file(repl: &Repl) {
     for path in &candidates {
         if path.is_file() {
             let cmd = format!(r#"source "{}""#, path.display());
-            let outcome = repl.runtime.block_on(repl.client.execute(&cmd));
+            let outcome = repl.execute_interruptible(&cmd);
             if let Some(warning) = rc_file_warning(path, &outcome) {
                 eprintln!("{warning}");
             }
@@ -665,23 +994,14 @@ fn load_rc_file(repl: &Repl) {
     }
 }
 
-/// Resolve the prompt string: call `kaish_prompt()` if defined, else default.
-fn resolve_prompt(repl: &Repl) -> String {
-    let has_fn = repl
-        .runtime
-        .block_on(repl.client.has_function("kaish_prompt"))
-        .unwrap_or(false);
-    if has_fn {
-        if let Ok(result) = repl.runtime.block_on(repl.client.execute("kaish_prompt")) {
-            if result.ok() {
-                let text = result.text_out().trim_end().to_string();
-                if !text.is_empty() {
-                    return text;
-                }
-            }
-        }
+/// Load the user (`~/.config/kaish/tools/`) and project (`.kaish/tools/`)
+/// tool libraries up front so a broken file is reported at startup rather
+/// than logged behind the first command.
+fn load_tool_library(repl: &Repl) {
+    let report = repl.runtime.block_on(repl.client.kernel().load_tool_library());
+    for error in &report.errors {
+        eprintln!("kaish: warning: tool library: {error}");
     }
-    "会sh> ".to_string()
 }
 
 /// Check the JobManager for jobs that finished since the last prompt, print a
@@ -702,97 +1022,117 @@ fn notify_finished_jobs(repl: &Repl) {
 
 // ── Entry points ────────────────────────────────────────────────────
 
+/// Prompt used when neither a `kaish_prompt` function nor a template is set.
+pub const DEFAULT_PROMPT: &str = "会sh> ";
+
+/// Interactive output is cut to this many lines per command; `/more` shows the rest.
+pub const DEFAULT_TRUNCATE_LINES: usize = 200;
+
+/// Options for an interactive REPL session.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunOptions {
+    /// Writes are virtual (copy-on-write overlay).
+    /// Use `kaish-vfs commit` to apply changes to real files.
+    pub overlay: bool,
+    /// Leave no trace on disk: neither the line history nor the rich
+    /// `/history` record is loaded or saved. The rc file is still sourced.
+    pub transient: bool,
+}
+
 /// Run the REPL with optional overlay mode.
 ///
 /// When `overlay` is `true`, writes are virtual (copy-on-write overlay).
 /// Use `kaish-vfs commit` to apply changes to real files.
 pub fn run_with_overlay(overlay: bool) -> Result<()> {
-    println!("会sh — kaish v{}", env!("CARGO_PKG_VERSION"));
-    use kaish_kernel::help::{compose, Recipe, SchemaContent};
-
-    if overlay {
-        println!("[overlay mode: writes are virtual — use 'kaish-vfs commit' to apply]");
-    }
-    println!("{}", compose(&Recipe::repl_welcome(), &SchemaContent::new(&[])));
-
-    let config = KernelConfig::repl()
-        .with_interactive(true)
-        .with_initial_vars(os_env_vars())
-        .with_overlay(overlay);
-    let mut repl = Repl::with_config(config)?;
-
-    // Source RC file (interactive only)
-    load_rc_file(&repl);
-
-    let helper = KaishHelper::new(
-        Box::new(repl.client.clone()),
-        repl.runtime.handle().clone(),
-    );
-
-    let mut rl: Editor<KaishHelper, DefaultHistory> =
-        Editor::new().context("Failed to create editor")?;
-    rl.set_helper(Some(helper));
-
-    let history_path = load_history(&mut rl);
-
-    loop {
-        notify_finished_jobs(&repl);
-        let prompt_string = resolve_prompt(&repl);
-        let prompt: &str = &prompt_string;
+    run_with_options(RunOptions {
+        overlay,
+        ..RunOptions::default()
+    })
+}
 
-        match rl.readline(prompt) {
-            Ok(line) => {
-                if let Err(e) = rl.add_history_entry(line.as_str()) {
-                    tracing::warn!("Failed to add history entry: {}", e);
-                }
+/// Run the REPL.
+pub fn run() -> Result<()> {
+    run_with_options(RunOptions::default())
+}
 
-                match repl.process_line(&line) {
-                    ProcessResult::Output(output) => {
-                        if output.ends_with('\n') {
-                            print!("{}", output);
-                        } else {
-                            println!("{}", output);
-                        }
-                    }
-                    ProcessResult::Empty => {}
-                    ProcessResult::Exit => {
-                        save_history(&mut rl, &history_path);
-                        return Ok(());
-                    }
-                }
-            }
-            Err(ReadlineError::Interrupted) => {
-                println!("^C");
-                continue;
-            }
-            Err(ReadlineError::Eof) => {
-                println!("^D");
-                break;
-            }
-            Err(err) => {
-                eprintln!("Error: {}", err);
-                break;
-            }
+/// Walk `pager` a page at a time, reading navigation keys at its prompt.
+fn run_pager(rl: &mut Editor<KaishHelper, DefaultHistory>, mut pager: Pager) {
+    while let Some(page) = pager.next_page() {
+        println!("{page}");
+        if pager.is_done() {
+            return;
+        }
+        let command = match rl.readline(&pager.prompt()) {
+            Ok(input) => PagerCommand::parse(&input),
+            // Ctrl-C, Ctrl-D, or a read error at the pager prompt all just stop paging.
+            Err(_) => PagerCommand::Quit,
+        };
+        match command {
+            PagerCommand::Next => {}
+            PagerCommand::Back => pager.back(),
+            PagerCommand::Quit => return,
         }
     }
-
-    save_history(&mut rl, &history_path);
-
-    Ok(())
 }
 
-/// Run the REPL.
-pub fn run() -> Result<()> {
+/// Run the REPL with the given session options.
+pub fn run_with_options(options: RunOptions) -> Result<()> {
     println!("会sh — kaish v{}", env!("CARGO_PKG_VERSION"));
     // Welcome text comes from the canonical kaish-help corpus (via the kernel
     // re-export), so it stays in sync with the MCP/embedder instructions.
     use kaish_kernel::help::{compose, Recipe, SchemaContent};
+
+    // A broken repl.toml shouldn't lock the user out of their shell: warn
+    // and carry on with the defaults.
+    let repl_config = match ReplConfig::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("kaish: warning: ignoring {}: {:#}", ReplConfig::default_path().display(), e);
+            ReplConfig::default()
+        }
+    };
+
+    let mut config = repl_config.kernel.apply(
+        KernelConfig::repl()
+            .with_interactive(true)
+            .with_initial_vars(os_env_vars())
+            .with_tool_library(kaish_kernel::paths::config_dir().join("tools"))
+            .with_tool_library(".kaish/tools"),
+    );
+    if options.overlay {
+        config = config.with_overlay(true);
+    }
+    // Progress from long-running tools draws as a status line, which only
+    // makes sense when both streams are a terminal: with stdout redirected,
+    // a bar on stderr would interleave with whatever reads the output.
+    if std::io::stdout().is_terminal() && std::io::stderr().is_terminal() {
+        config = config.with_hooks(progress::status_line_hooks());
+    }
+
+    if config.overlay {
+        println!("[overlay mode: writes are virtual — use 'kaish-vfs commit' to apply]");
+    }
+    if options.transient {
+        println!("[transient mode: history is not loaded or saved]");
+    }
     println!("{}", compose(&Recipe::repl_welcome(), &SchemaContent::new(&[])));
 
-    let mut repl = Repl::new()?;
+    if let Some(theme) = repl_config.theme
+        && Theme::from_env() != Theme::NoColor
+    {
+        theme::set_active(theme);
+    }
+
+    let mut repl = Repl::with_config(config)?;
+    if std::io::stdout().is_terminal() {
+        repl.set_truncate_lines(repl_config.truncate_lines);
+    }
+    repl.set_prompt(repl_config.prompt.clone());
 
-    // Source RC file (interactive only)
-    load_rc_file(&repl);
+    // Tool library, then RC file (interactive only) — so the RC file can
+    // call library functions.
+    load_tool_library(&repl);
+    load_rc_file(&repl, repl_config.startup.as_deref());
 
     // Build the helper with a client handle (sharing the REPL's kernel) and
     // a runtime handle. Boxed as a trait object so completion runs purely
@@ -802,16 +1142,25 @@ pub fn run() -> Result<()> {
         repl.runtime.handle().clone(),
     );
 
+    let editor_config = rustyline::Config::builder()
+        .max_history_size(repl_config.history_size)
+        .context("Invalid history_size")?
+        .build();
     let mut rl: Editor<KaishHelper, DefaultHistory> =
-        Editor::new().context("Failed to create editor")?;
+        Editor::with_config(editor_config).context("Failed to create editor")?;
     rl.set_helper(Some(helper));
 
-    let history_path = load_history(&mut rl);
+    let (history_path, rich_path) = if options.transient {
+        (None, None)
+    } else {
+        (load_history(&mut rl), rich_history_path())
+    };
+    open_rich_history(&mut repl, &rich_path, repl_config.history_size);
 
     loop {
         notify_finished_jobs(&repl);
         // Dynamic prompt: call kaish_prompt() if defined, else default
-        let prompt_string = resolve_prompt(&repl);
+        let prompt_string = repl.render_prompt();
         let prompt: &str = &prompt_string;
 
         match rl.readline(prompt) {
@@ -820,6 +1169,16 @@ pub fn run() -> Result<()> {
                     tracing::warn!("Failed to add history entry: {}", e);
                 }
 
+                // At a terminal, `/more` pages through held output
+                // interactively instead of printing the next chunk.
+                if line.trim() == "/more" && std::io::stdin().is_terminal() {
+                    let held = repl.take_more();
+                    if !held.is_empty() {
+                        run_pager(&mut rl, Pager::new(held, Pager::terminal_page_lines()));
+                        continue;
+                    }
+                }
+
                 match repl.process_line(&line) {
                     ProcessResult::Output(output) => {
                         if output.ends_with('\n') {
@@ -829,10 +1188,7 @@ pub fn run() -> Result<()> {
                         }
                     }
                     ProcessResult::Empty => {}
-                    ProcessResult::Exit => {
-                        save_history(&mut rl, &history_path);
-                        return Ok(());
-                    }
+                    ProcessResult::Exit => break,
                 }
             }
             Err(ReadlineError::Interrupted) => {
@@ -1086,6 +1442,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn format_result_shows_stderr_stream_on_success() {
+        let result = ExecResult::from_output(0, "out\n", "warning: heads up\n");
+        let shown = format_result(&result);
+        assert!(shown.contains("out"), "{shown}");
+        assert!(shown.contains("warning: heads up"), "{shown}");
+    }
+
+    #[test]
+    fn format_result_does_not_duplicate_err_on_failure() {
+        let mut result = ExecResult::failure(1, "cat: nope: not found");
+        result.push_stderr("partial\n");
+        let shown = format_result(&result);
+        assert_eq!(shown.matches("cat: nope: not found").count(), 1, "{shown}");
+        assert!(shown.find("partial").unwrap() < shown.find("✗").unwrap(), "{shown}");
+    }
+
     // GH #129: an rc-file source that returns `Ok(ExecResult)` with a nonzero
     // exit code used to be silently discarded — only a hard `Err` warned.
     #[test]
@@ -1099,7 +1472,7 @@ mod tests {
     fn rc_file_warning_reports_nonzero_exit_with_stderr() {
         let path = std::path::Path::new("/home/user/.config/kaish/init.kai");
         let outcome: kaish_client::ClientResult<ExecResult> =
-            Ok(ExecResult::failure(127, "source: unknown-command: command not found"));
+            Ok(ExecResult::not_found("source: unknown-command: command not found"));
         let warning = rc_file_warning(path, &outcome).expect("nonzero exit must warn");
         assert!(warning.contains("exited with code 127"), "{warning}");
         assert!(warning.contains("unknown-command: command not found"), "{warning}");
@@ -4,6 +4,7 @@
 //!   kaish                      # Interactive REPL
 //!   kaish -c <command>         # Execute command and exit
 //!   kaish script.kai           # Run a script
+//!   kaish kernels list         # Manage saved kernel states
 
 use std::env;
 use std::io::{IsTerminal, Read};
@@ -91,8 +92,9 @@ fn execute_noninteractive(
                 print!("{}", text);
             }
         }
-        if !r.err.is_empty() {
-            eprint!("{}", r.err);
+        let stderr = r.stderr_text();
+        if !stderr.is_empty() {
+            eprint!("{}", stderr);
         }
     };
     let result = match spawn_stdin_bridge(rt.handle().clone()) {
@@ -149,11 +151,12 @@ fn main() -> ExitCode {
 fn run() -> Result<ExitCode> {
     let args: Vec<String> = env::args().collect();
 
-    // Extract --overlay flag (can appear anywhere before positionals).
+    // Extract --overlay / --transient flags (can appear anywhere before positionals).
     let overlay = args.iter().any(|a| a == "--overlay");
-    // Remaining args with --overlay stripped out.
+    let transient = args.iter().any(|a| a == "--transient");
+    // Remaining args with the session flags stripped out.
     let rest: Vec<&str> = args.iter().skip(1)
-        .filter(|a| *a != "--overlay")
+        .filter(|a| *a != "--overlay" && *a != "--transient")
         .map(|a| a.as_str())
         .collect();
 
@@ -161,7 +164,7 @@ fn run() -> Result<ExitCode> {
     match rest.first().copied() {
         None => {
             // No args: interactive REPL
-            kaish_repl::run_with_overlay(overlay)?;
+            kaish_repl::run_with_options(kaish_repl::RunOptions { overlay, transient })?;
             Ok(ExitCode::SUCCESS)
         }
 
@@ -184,6 +187,12 @@ fn run() -> Result<ExitCode> {
             run_command(cmd, overlay)
         }
 
+        Some("kernels") => {
+            let store = kaish_kernel::state_store::StateStore::default();
+            print!("{}", kaish_repl::kernels::run(&store, &rest[1..])?);
+            Ok(ExitCode::SUCCESS)
+        }
+
         Some(path) if !path.starts_with('-') => {
             // Treat as script file
             run_script(path, overlay)
@@ -204,10 +213,16 @@ Usage:
   kaish                        Interactive REPL
   kaish -c <command>           Execute command and exit
   kaish <script.kai>           Run a script file
+  kaish kernels <subcommand>   Manage saved kernel states in
+                               $XDG_DATA_HOME/kaish/kernels:
+                               list, show NAME, delete NAME...,
+                               compact [NAME...]
 
 Options:
   --overlay                    Enable copy-on-write overlay mode (writes are
                                virtual; use kaish-vfs commit to apply them)
+  --transient                  Interactive REPL that neither loads nor saves
+                               history (nothing is written to disk)
   -c <command>                 Execute command string and exit
   -h, --help                   Show this help
   -V, --version                Show version
@@ -215,9 +230,11 @@ Options:
 Examples:
   kaish                        # Start interactive REPL
   kaish --overlay              # REPL with virtual writes (overlay mode)
+  kaish --transient            # REPL that leaves no history behind
   kaish -c 'echo hello'       # Run a command
   kaish --overlay -c 'echo test > file.txt; kaish-vfs diff'
   kaish deploy.kai             # Run a deployment script
+  kaish kernels list           # Saved states: size, last used, counts
 "#, env!("CARGO_PKG_VERSION"));
 }
 
@@ -0,0 +1,155 @@
+//! Screenful-at-a-time paging for long REPL output.
+//!
+//! When an interactive command's output is truncated, `/more` hands the held
+//! lines to a [`Pager`], which the REPL loop walks one page per keypress:
+//! Enter or space shows the next page, `b` goes back a page, `q` stops.
+//! Navigation reads whole lines through the line editor, so it needs no raw
+//! terminal mode of its own.
+
+/// Lines shown per page when the terminal height is unknown.
+pub const DEFAULT_PAGE_LINES: usize = 23;
+
+/// A keypress at the pager prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PagerCommand {
+    /// Show the next page.
+    Next,
+    /// Show the previous page.
+    Back,
+    /// Stop paging.
+    Quit,
+}
+
+impl PagerCommand {
+    /// Interpret what the user typed at the pager prompt. Anything
+    /// unrecognized advances, like `more`.
+    pub fn parse(input: &str) -> Self {
+        match input.trim() {
+            "q" | "Q" => PagerCommand::Quit,
+            "b" | "B" => PagerCommand::Back,
+            _ => PagerCommand::Next,
+        }
+    }
+}
+
+/// A cursor over held output lines.
+#[derive(Debug, Clone)]
+pub struct Pager {
+    lines: Vec<String>,
+    page_lines: usize,
+    /// Index of the first line of the next page to show.
+    position: usize,
+    /// Index of the first line of the page shown last. A short final page
+    /// ends at the last line, so this can't be derived from `position`.
+    shown_start: usize,
+}
+
+impl Pager {
+    /// Page over `lines`, `page_lines` at a time (at least one).
+    pub fn new(lines: Vec<String>, page_lines: usize) -> Self {
+        Self {
+            lines,
+            page_lines: page_lines.max(1),
+            position: 0,
+            shown_start: 0,
+        }
+    }
+
+    /// Page size fitted to the terminal, leaving a row for the prompt.
+    pub fn terminal_page_lines() -> usize {
+        terminal_size::terminal_size()
+            .map(|(_, height)| usize::from(height.0).saturating_sub(1))
+            .filter(|rows| *rows > 0)
+            .unwrap_or(DEFAULT_PAGE_LINES)
+    }
+
+    /// Lines not yet shown.
+    pub fn remaining(&self) -> usize {
+        self.lines.len() - self.position
+    }
+
+    /// Whether every line has been shown.
+    pub fn is_done(&self) -> bool {
+        self.position >= self.lines.len()
+    }
+
+    /// The next page, joined with newlines, or `None` once everything is shown.
+    pub fn next_page(&mut self) -> Option<String> {
+        if self.is_done() {
+            return None;
+        }
+        let end = (self.position + self.page_lines).min(self.lines.len());
+        let page = self.lines[self.position..end].join("\n");
+        self.shown_start = self.position;
+        self.position = end;
+        Some(page)
+    }
+
+    /// Step back so the next page re-shows the one before the page just shown.
+    pub fn back(&mut self) {
+        self.position = self.shown_start.saturating_sub(self.page_lines);
+        self.shown_start = self.position;
+    }
+
+    /// The prompt shown between pages.
+    pub fn prompt(&self) -> String {
+        format!(
+            "-- more ({} lines left; Enter/space: next, b: back, q: quit) -- ",
+            self.remaining()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(n: usize) -> Vec<String> {
+        (1..=n).map(|i| i.to_string()).collect()
+    }
+
+    #[test]
+    fn pages_through_all_lines() {
+        let mut pager = Pager::new(lines(5), 2);
+        assert_eq!(pager.next_page().as_deref(), Some("1\n2"));
+        assert_eq!(pager.remaining(), 3);
+        assert_eq!(pager.next_page().as_deref(), Some("3\n4"));
+        assert_eq!(pager.next_page().as_deref(), Some("5"));
+        assert!(pager.is_done());
+        assert_eq!(pager.next_page(), None);
+    }
+
+    #[test]
+    fn back_reshows_previous_page() {
+        let mut pager = Pager::new(lines(6), 2);
+        let _ = pager.next_page();
+        let _ = pager.next_page();
+        pager.back();
+        assert_eq!(pager.next_page().as_deref(), Some("1\n2"));
+        pager.back();
+        assert_eq!(pager.next_page().as_deref(), Some("1\n2"));
+    }
+
+    #[test]
+    fn back_after_a_short_last_page() {
+        let mut pager = Pager::new(lines(25), 10);
+        while pager.next_page().is_some() {}
+        pager.back();
+        let expected = (11..=20).map(|i| i.to_string()).collect::<Vec<_>>().join("\n");
+        assert_eq!(pager.next_page(), Some(expected));
+    }
+
+    #[test]
+    fn zero_page_size_still_advances() {
+        let mut pager = Pager::new(lines(2), 0);
+        assert_eq!(pager.next_page().as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(PagerCommand::parse(""), PagerCommand::Next);
+        assert_eq!(PagerCommand::parse(" "), PagerCommand::Next);
+        assert_eq!(PagerCommand::parse("q"), PagerCommand::Quit);
+        assert_eq!(PagerCommand::parse("b"), PagerCommand::Back);
+    }
+}
@@ -0,0 +1,106 @@
+//! A one-line status display for tools that report progress.
+//!
+//! Long-running tools (`archive` over a big tree, `cp -r`, a `scatter`
+//! fan-out) call `report_progress`; the kernel hands each update to its
+//! `on_progress` hooks. [`status_line_hooks`] draws them on stderr as a
+//! single line — a bar when the tool knows how far along it is, a running
+//! message otherwise — rewritten in place and erased when the command
+//! finishes, so the command's own output never lands after a stale status.
+
+use std::io::Write as _;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use kaish_kernel::{KernelHooks, Progress};
+
+/// Minimum gap between redraws: a tool may report per entry, and a redraw
+/// per entry would cost more than the work.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Escape sequence: return to column 0 and erase the line.
+const CLEAR_LINE: &str = "\r\x1b[2K";
+
+/// Columns inside the bar's brackets.
+const BAR_WIDTH: usize = 20;
+
+/// Hooks that draw progress as a status line on stderr.
+///
+/// Only install these when stdout and stderr are a terminal — the line is
+/// redrawn with carriage returns and escape codes.
+pub fn status_line_hooks() -> KernelHooks {
+    // When the line was last drawn; `None` while nothing is showing.
+    let drawn: Arc<Mutex<Option<Instant>>> = Arc::new(Mutex::new(None));
+    let on_progress = drawn.clone();
+    KernelHooks::new()
+        .on_progress(move |command, progress| {
+            let Ok(mut last) = on_progress.lock() else { return };
+            if last.is_some_and(|at| at.elapsed() < REDRAW_INTERVAL) {
+                return;
+            }
+            let width = terminal_size::terminal_size().map_or(80, |(w, _)| usize::from(w.0));
+            let mut stderr = std::io::stderr().lock();
+            let _ = write!(stderr, "{CLEAR_LINE}{}", render(command, progress, width));
+            let _ = stderr.flush();
+            *last = Some(Instant::now());
+        })
+        .on_post_exec(move |_, _, _| {
+            let Ok(mut last) = drawn.lock() else { return };
+            if last.take().is_some() {
+                let mut stderr = std::io::stderr().lock();
+                let _ = write!(stderr, "{CLEAR_LINE}");
+                let _ = stderr.flush();
+            }
+        })
+}
+
+/// The status text for one update, cut to fit a terminal `width` columns
+/// wide (leaving the last column free so the line never wraps).
+pub fn render(command: &str, progress: &Progress, width: usize) -> String {
+    let mut line = if command.is_empty() { "…".to_string() } else { command.to_string() };
+    if let Some(percent) = progress.percent {
+        line.push_str(&format!(" {} {percent:>3.0}%", bar(percent)));
+    }
+    if let Some(message) = &progress.message {
+        line.push_str(": ");
+        line.push_str(message.lines().next().unwrap_or_default());
+    }
+    let limit = width.saturating_sub(1).max(1);
+    if line.chars().count() > limit {
+        line = line.chars().take(limit.saturating_sub(1)).collect();
+        line.push('…');
+    }
+    line
+}
+
+/// `[=====>    ]` filled to `percent`.
+fn bar(percent: f64) -> String {
+    let filled = ((percent.clamp(0.0, 100.0) / 100.0) * BAR_WIDTH as f64).round() as usize;
+    let body = match filled {
+        0 => " ".repeat(BAR_WIDTH),
+        n if n >= BAR_WIDTH => "=".repeat(BAR_WIDTH),
+        n => format!("{}>{}", "=".repeat(n - 1), " ".repeat(BAR_WIDTH - n)),
+    };
+    format!("[{body}]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_shows_percent_and_message() {
+        let progress = Progress::steps(1, 4).with_message("docs/a.md");
+        assert_eq!(render("archive", &progress, 80), "archive [====>               ]  25%: docs/a.md");
+        assert_eq!(render("cp", &Progress::message("3 files: a\nmore"), 80), "cp: 3 files: a");
+        assert_eq!(render("", &Progress::percent(150.0), 80), "… [====================] 100%");
+        assert_eq!(render("scatter", &Progress::percent(0.0), 80), "scatter [                    ]   0%");
+    }
+
+    #[test]
+    fn render_fits_the_terminal() {
+        let progress = Progress::message("a".repeat(100));
+        let line = render("archive", &progress, 20);
+        assert_eq!(line.chars().count(), 19);
+        assert!(line.ends_with('…'));
+    }
+}
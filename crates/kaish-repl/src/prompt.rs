@@ -0,0 +1,148 @@
+//! Prompt templates.
+//!
+//! A template is re-rendered before every input line:
+//!
+//! - `$NAME` / `${NAME}` — a shell variable (unset renders empty)
+//! - `$?` / `${?}` — exit code of the last command
+//! - `${CWD}` — the kernel's working directory (`$PWD` works too)
+//! - `%{red}` … `%{reset}` — color helpers, dropped under the `none` theme
+//! - `\$`, `%%` — a literal `$` / `%`
+//!
+//! Rendering is done here rather than by executing the template through the
+//! kernel so that drawing the prompt never disturbs `$?`. For anything that
+//! needs command substitution, define a `kaish_prompt` function instead.
+
+use crate::theme::Theme;
+
+/// Color helper names accepted inside `%{…}`.
+const COLORS: &[(&str, &str)] = &[
+    ("reset", "\x1b[0m"),
+    ("bold", "\x1b[1m"),
+    ("dim", "\x1b[2m"),
+    ("red", "\x1b[31m"),
+    ("green", "\x1b[32m"),
+    ("yellow", "\x1b[33m"),
+    ("blue", "\x1b[34m"),
+    ("magenta", "\x1b[35m"),
+    ("cyan", "\x1b[36m"),
+    ("white", "\x1b[37m"),
+];
+
+/// Render `template`, resolving variable names through `lookup`.
+///
+/// `lookup` receives the bare name (`?`, `CWD`, `HOME`, …) and returns its
+/// display text, or `None` for an unset variable.
+pub fn render(template: &str, theme: Theme, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' if chars.peek() == Some(&'$') => {
+                chars.next();
+                out.push('$');
+            }
+            '$' => match chars.peek().copied() {
+                Some('{') => {
+                    chars.next();
+                    let mut name = String::new();
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        name.push(c);
+                    }
+                    if closed {
+                        out.push_str(&lookup(&name).unwrap_or_default());
+                    } else {
+                        out.push_str("${");
+                        out.push_str(&name);
+                    }
+                }
+                Some('?') => {
+                    chars.next();
+                    out.push_str(&lookup("?").unwrap_or_default());
+                }
+                Some(c) if c == '_' || c.is_ascii_alphabetic() => {
+                    let mut name = String::new();
+                    while let Some(&c) = chars.peek() {
+                        if c == '_' || c.is_ascii_alphanumeric() {
+                            name.push(c);
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                    out.push_str(&lookup(&name).unwrap_or_default());
+                }
+                _ => out.push('$'),
+            },
+            '%' => match chars.peek().copied() {
+                Some('%') => {
+                    chars.next();
+                    out.push('%');
+                }
+                Some('{') => {
+                    let rest: String = chars.clone().skip(1).take_while(|c| *c != '}').collect();
+                    match COLORS.iter().find(|(name, _)| *name == rest) {
+                        Some((_, escape)) => {
+                            // Consume `{name}`.
+                            for _ in 0..rest.chars().count() + 2 {
+                                chars.next();
+                            }
+                            if theme != Theme::NoColor {
+                                out.push_str(escape);
+                            }
+                        }
+                        None => out.push('%'),
+                    }
+                }
+                _ => out.push('%'),
+            },
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "?" => Some("1".to_string()),
+            "CWD" => Some("/src".to_string()),
+            "USER" => Some("amy".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn interpolates_variables_and_status() {
+        assert_eq!(
+            render("会sh ${CWD} [$?] $USER> ", Theme::NoColor, lookup),
+            "会sh /src [1] amy> "
+        );
+        assert_eq!(render("[${?}]", Theme::NoColor, lookup), "[1]");
+    }
+
+    #[test]
+    fn unset_variables_render_empty() {
+        assert_eq!(render("<$NOPE${ALSO_NOPE}>", Theme::NoColor, lookup), "<>");
+    }
+
+    #[test]
+    fn literals_and_escapes() {
+        assert_eq!(render(r"\$HOME 100%% $ %x", Theme::NoColor, lookup), "$HOME 100% $ %x");
+        assert_eq!(render("${unterminated", Theme::NoColor, lookup), "${unterminated");
+    }
+
+    #[test]
+    fn color_helpers_follow_theme() {
+        assert_eq!(render("%{red}>%{reset}", Theme::NoColor, lookup), ">");
+        assert_eq!(render("%{red}>%{reset}", Theme::Dark, lookup), "\x1b[31m>\x1b[0m");
+        assert_eq!(render("%{mauve}>", Theme::Dark, lookup), "%{mauve}>");
+    }
+}
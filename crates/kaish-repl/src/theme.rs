@@ -0,0 +1,270 @@
+//! Color themes for REPL output.
+//!
+//! One theme is active per process. It starts from the environment —
+//! `NO_COLOR` (any value) or `TERM=dumb` selects [`Theme::NoColor`], anything
+//! else [`Theme::Dark`] — and `/theme <name>` switches it for the rest of the
+//! session. Rendering asks [`paint`] for a semantic [`Role`] rather than
+//! picking colors itself, so a theme is the only place a color is chosen.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use owo_colors::{OwoColorize, Style};
+
+/// A named color scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    /// Bright colors for dark terminal backgrounds (the default).
+    Dark,
+    /// Deeper colors that stay readable on light backgrounds.
+    Light,
+    /// Plain text, no escape sequences.
+    NoColor,
+}
+
+/// What a piece of output *is*, independent of how a theme colors it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// A directory name in a listing.
+    Directory,
+    /// An executable file name in a listing.
+    Executable,
+    /// A symlink name in a listing.
+    Symlink,
+    /// A failure status line (`✗ …`).
+    Failure,
+    /// An object key in JSON output.
+    JsonKey,
+    /// A string value in JSON output.
+    JsonString,
+    /// A number in JSON output.
+    JsonNumber,
+    /// `true`, `false`, or `null` in JSON output.
+    JsonLiteral,
+    /// De-emphasized chrome such as the truncation notice.
+    Dim,
+}
+
+impl Theme {
+    /// Every theme, in the order `/theme` lists them.
+    pub const ALL: [Theme; 3] = [Theme::Dark, Theme::Light, Theme::NoColor];
+
+    /// The theme the environment asks for.
+    pub fn from_env() -> Self {
+        let no_color = std::env::var_os("NO_COLOR").is_some();
+        let dumb = std::env::var("TERM").is_ok_and(|t| t == "dumb");
+        if no_color || dumb { Theme::NoColor } else { Theme::Dark }
+    }
+
+    /// Parse a theme name as accepted by `/theme`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Theme::Dark),
+            "light" => Some(Theme::Light),
+            "none" | "no-color" | "nocolor" => Some(Theme::NoColor),
+            _ => None,
+        }
+    }
+
+    /// Canonical name, the inverse of [`Theme::parse`].
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+            Theme::NoColor => "none",
+        }
+    }
+
+    fn style(self, role: Role) -> Option<Style> {
+        let style = Style::new();
+        match self {
+            Theme::NoColor => None,
+            Theme::Dark => Some(match role {
+                Role::Directory => style.blue().bold(),
+                Role::Executable => style.green().bold(),
+                Role::Symlink => style.cyan(),
+                Role::Failure => style.red(),
+                Role::JsonKey => style.bright_blue(),
+                Role::JsonString => style.green(),
+                Role::JsonNumber => style.yellow(),
+                Role::JsonLiteral => style.magenta(),
+                Role::Dim => style.dimmed(),
+            }),
+            Theme::Light => Some(match role {
+                Role::Directory => style.blue().bold(),
+                Role::Executable => style.green().bold(),
+                Role::Symlink => style.purple(),
+                Role::Failure => style.red().bold(),
+                Role::JsonKey => style.blue(),
+                Role::JsonString => style.green(),
+                Role::JsonNumber => style.red(),
+                Role::JsonLiteral => style.purple(),
+                Role::Dim => style.dimmed(),
+            }),
+        }
+    }
+
+    fn to_tag(self) -> u8 {
+        match self {
+            Theme::Dark => 1,
+            Theme::Light => 2,
+            Theme::NoColor => 3,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            1 => Some(Theme::Dark),
+            2 => Some(Theme::Light),
+            3 => Some(Theme::NoColor),
+            _ => None,
+        }
+    }
+}
+
+/// 0 = not chosen yet; resolved from the environment on first use.
+static ACTIVE: AtomicU8 = AtomicU8::new(0);
+
+/// The active theme.
+pub fn active() -> Theme {
+    match Theme::from_tag(ACTIVE.load(Ordering::Relaxed)) {
+        Some(theme) => theme,
+        None => {
+            let theme = Theme::from_env();
+            ACTIVE.store(theme.to_tag(), Ordering::Relaxed);
+            theme
+        }
+    }
+}
+
+/// Switch the active theme for the rest of the process.
+pub fn set_active(theme: Theme) {
+    ACTIVE.store(theme.to_tag(), Ordering::Relaxed);
+}
+
+/// Render `text` in the active theme's style for `role`.
+pub fn paint(text: &str, role: Role) -> String {
+    paint_with(active(), text, role)
+}
+
+/// Render `text` in `theme`'s style for `role`.
+pub fn paint_with(theme: Theme, text: &str, role: Role) -> String {
+    match theme.style(role) {
+        Some(style) => text.style(style).to_string(),
+        None => text.to_string(),
+    }
+}
+
+/// Syntax-color JSON text, or `None` if `text` doesn't scan as JSON.
+///
+/// A lexical pass, not a parser: it only needs to tell keys from string
+/// values and find numbers and literals, and to bail on anything that plainly
+/// isn't JSON (`[1] Done`, prose) so that text is left alone.
+pub fn highlight_json(theme: Theme, text: &str) -> Option<String> {
+    let trimmed = text.trim_start();
+    if !(trimmed.starts_with('{') || trimmed.starts_with('[')) {
+        return None;
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len() * 2);
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i];
+        match ch {
+            '"' => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                if i >= chars.len() {
+                    return None;
+                }
+                i += 1;
+                let literal: String = chars[start..i].iter().collect();
+                let is_key = chars[i..]
+                    .iter()
+                    .find(|c| !c.is_whitespace())
+                    .is_some_and(|c| *c == ':');
+                let role = if is_key { Role::JsonKey } else { Role::JsonString };
+                out.push_str(&paint_with(theme, &literal, role));
+                continue;
+            }
+            '-' | '0'..='9' => {
+                let start = i;
+                while i < chars.len()
+                    && matches!(chars[i], '-' | '+' | '.' | 'e' | 'E' | '0'..='9')
+                {
+                    i += 1;
+                }
+                let number: String = chars[start..i].iter().collect();
+                out.push_str(&paint_with(theme, &number, Role::JsonNumber));
+                continue;
+            }
+            't' | 'f' | 'n' => {
+                let rest: String = chars[i..chars.len().min(i + 5)].iter().collect();
+                let word = ["true", "false", "null"]
+                    .into_iter()
+                    .find(|w| rest.starts_with(w))?;
+                out.push_str(&paint_with(theme, word, Role::JsonLiteral));
+                i += word.len();
+                continue;
+            }
+            '{' | '}' | '[' | ']' | ',' | ':' => out.push(ch),
+            _ if ch.is_whitespace() => out.push(ch),
+            _ => return None,
+        }
+        i += 1;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_name_round_trip() {
+        for theme in Theme::ALL {
+            assert_eq!(Theme::parse(theme.name()), Some(theme));
+        }
+        assert_eq!(Theme::parse("no-color"), Some(Theme::NoColor));
+        assert_eq!(Theme::parse("solarized"), None);
+    }
+
+    #[test]
+    fn no_color_paints_plain_text() {
+        assert_eq!(paint_with(Theme::NoColor, "dir", Role::Directory), "dir");
+    }
+
+    #[test]
+    fn dark_paints_with_escapes() {
+        let painted = paint_with(Theme::Dark, "dir", Role::Directory);
+        assert!(painted.contains("\x1b["), "got: {painted:?}");
+        assert!(painted.contains("dir"));
+    }
+
+    #[test]
+    fn highlight_json_no_color_is_identity() {
+        let json = r#"{"a": [1, -2.5e3, true, null], "b": "x\"y"}"#;
+        assert_eq!(highlight_json(Theme::NoColor, json).as_deref(), Some(json));
+    }
+
+    #[test]
+    fn highlight_json_distinguishes_keys_from_values() {
+        let out = highlight_json(Theme::Dark, r#"{"k": "v"}"#).expect("json");
+        let key = paint_with(Theme::Dark, "\"k\"", Role::JsonKey);
+        let value = paint_with(Theme::Dark, "\"v\"", Role::JsonString);
+        assert!(out.contains(&key) && out.contains(&value), "got: {out:?}");
+    }
+
+    #[test]
+    fn highlight_json_rejects_non_json() {
+        assert_eq!(highlight_json(Theme::Dark, "[1] Done sleep 5"), None);
+        assert_eq!(highlight_json(Theme::Dark, "hello"), None);
+        assert_eq!(highlight_json(Theme::Dark, r#"{"unterminated"#), None);
+    }
+}
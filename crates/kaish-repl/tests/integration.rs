@@ -1587,3 +1587,139 @@ fn dirname_multi_positional_prints_each() {
         lines
     );
 }
+
+// ============================================================================
+// Rich history: /history and !N
+// ============================================================================
+
+#[test]
+fn history_meta_command_lists_executed_commands_with_status() {
+    let outputs = run_script("echo one\nfalse\n/history");
+    let listing = outputs.last().expect("history output");
+    assert!(listing.contains("1 ✓") && listing.contains("echo one"), "got: {listing}");
+    assert!(listing.contains("2 ✗") && listing.contains("false"), "got: {listing}");
+    assert!(!listing.contains("/history"), "meta-commands aren't recorded: {listing}");
+}
+
+#[test]
+fn history_rerun_by_index_executes_and_echoes_command() {
+    let mut repl = Repl::new().expect("Failed to create REPL");
+    let _ = repl.process_line("echo rerun-me");
+    let ProcessResult::Output(output) = repl.process_line("!1") else {
+        panic!("expected output from !1");
+    };
+    assert!(output.starts_with("echo rerun-me\n"), "got: {output}");
+    assert!(output.contains("rerun-me\n") || output.ends_with("rerun-me"), "got: {output}");
+    assert_eq!(repl.history().len(), 2);
+}
+
+#[test]
+fn history_rerun_unknown_index_reports_error() {
+    let outputs = run_script("!9");
+    assert!(outputs_contain(&outputs, &["!9: no such history entry"]), "got: {outputs:?}");
+}
+
+// ============================================================================
+// Runtime meta-commands: /mount, /jobs, /mcp
+// ============================================================================
+
+#[test]
+fn mount_meta_command_matches_kaish_mounts() {
+    let meta = run_script("/mount --json").join("\n");
+    let builtin = run_script("kaish-mounts --json").join("\n");
+    assert!(meta.contains("\"mounts\""), "got: {meta}");
+    assert_eq!(meta, builtin);
+}
+
+#[test]
+fn jobs_meta_command_lists_background_jobs() {
+    let mut repl = Repl::new().expect("Failed to create REPL");
+    let _ = repl.process_line("sleep 5 &");
+    let ProcessResult::Output(listing) = repl.process_line("/jobs") else {
+        panic!("expected /jobs output");
+    };
+    assert!(listing.contains("sleep 5"), "got: {listing}");
+    assert!(repl.history().len() == 1, "meta-commands aren't recorded");
+}
+
+#[test]
+fn mcp_meta_command_explains_mcp_lives_in_embedders() {
+    let outputs = run_script("/mcp");
+    assert!(outputs_contain(&outputs, &["/mcp:", "embedder"]), "got: {outputs:?}");
+}
+
+// ============================================================================
+// Themes and truncation: /theme, /more
+// ============================================================================
+
+#[test]
+fn theme_meta_command_lists_and_switches() {
+    let mut repl = Repl::new().expect("Failed to create REPL");
+    let ProcessResult::Output(listing) = repl.process_line("/theme") else {
+        panic!("expected /theme listing");
+    };
+    for name in ["dark", "light", "none"] {
+        assert!(listing.contains(name), "missing {name}: {listing}");
+    }
+    assert!(matches!(repl.process_line("/theme none"), ProcessResult::Empty));
+    let ProcessResult::Output(listing) = repl.process_line("/theme") else {
+        panic!("expected /theme listing");
+    };
+    assert!(listing.contains("* none"), "got: {listing}");
+    let ProcessResult::Output(err) = repl.process_line("/theme solarized") else {
+        panic!("expected an error for an unknown theme");
+    };
+    assert!(err.contains("unknown theme"), "got: {err}");
+}
+
+#[test]
+fn long_output_truncates_and_more_shows_rest() {
+    let mut repl = Repl::new().expect("Failed to create REPL");
+    repl.set_truncate_lines(Some(3));
+    let ProcessResult::Output(first) = repl.process_line("seq 1 8") else {
+        panic!("expected seq output");
+    };
+    assert!(first.starts_with("1\n2\n3\n"), "got: {first}");
+    assert!(first.contains("5 more lines, use /more"), "got: {first}");
+
+    let ProcessResult::Output(second) = repl.process_line("/more") else {
+        panic!("expected /more output");
+    };
+    assert!(second.starts_with("4\n5\n6\n"), "got: {second}");
+    assert!(second.contains("2 more lines"), "got: {second}");
+
+    let ProcessResult::Output(third) = repl.process_line("/more") else {
+        panic!("expected /more output");
+    };
+    assert_eq!(third, "7\n8");
+
+    let ProcessResult::Output(done) = repl.process_line("/more") else {
+        panic!("expected /more notice");
+    };
+    assert!(done.contains("nothing more"), "got: {done}");
+}
+
+// ============================================================================
+// Prompt templates: /prompt
+// ============================================================================
+
+#[test]
+fn prompt_template_interpolates_vars_and_last_status() {
+    let mut repl = Repl::new().expect("Failed to create REPL");
+    assert_eq!(repl.render_prompt(), kaish_repl::DEFAULT_PROMPT);
+
+    assert!(matches!(repl.process_line(r#"/prompt "[$?] ${WHO}> ""#), ProcessResult::Empty));
+    let _ = repl.process_line("WHO=amy");
+    let _ = repl.process_line("false");
+    assert_eq!(repl.render_prompt(), "[1] amy> ");
+    // Rendering the prompt must not disturb $?.
+    assert_eq!(repl.render_prompt(), "[1] amy> ");
+
+    let ProcessResult::Output(shown) = repl.process_line("/prompt") else {
+        panic!("expected the current template");
+    };
+    assert_eq!(shown, "[$?] ${WHO}> ");
+
+    assert!(matches!(repl.process_line("/prompt default"), ProcessResult::Empty));
+    assert_eq!(repl.render_prompt(), kaish_repl::DEFAULT_PROMPT);
+}
@@ -10,7 +10,8 @@ use std::path::{Path, PathBuf};
 use async_trait::async_trait;
 
 use kaish_types::backend::{
-    BackendResult, MountInfo, PatchOp, ReadRange, ToolInfo, ToolResult, WriteMode,
+    BackendError, BackendResult, MountInfo, PatchOp, ReadRange, SnapshotInfo, ToolInfo,
+    ToolResult, WriteMode,
 };
 use kaish_types::{DirEntry, ToolArgs};
 
@@ -73,6 +74,33 @@ pub trait KernelBackend: Send + Sync {
     /// Create a symlink.
     async fn symlink(&self, target: &Path, link: &Path) -> BackendResult<()>;
 
+    // ═══════════════════════════════════════════════════════════════════════
+    // Snapshots
+    // ═══════════════════════════════════════════════════════════════════════
+
+    /// Capture the directory at `path` and return the snapshot's id.
+    ///
+    /// Only memory-backed mounts keep snapshots; the default refuses.
+    async fn snapshot(&self, path: &Path) -> BackendResult<u64> {
+        Err(snapshots_unsupported(path))
+    }
+
+    /// Put the directory a snapshot captured back as it was. `path` picks the
+    /// mount holding the snapshot.
+    async fn restore_snapshot(&self, path: &Path, _id: u64) -> BackendResult<()> {
+        Err(snapshots_unsupported(path))
+    }
+
+    /// Forget a snapshot. `path` picks the mount holding it.
+    async fn drop_snapshot(&self, path: &Path, _id: u64) -> BackendResult<()> {
+        Err(snapshots_unsupported(path))
+    }
+
+    /// Snapshots held by the mount containing `path`.
+    async fn snapshots(&self, path: &Path) -> BackendResult<Vec<SnapshotInfo>> {
+        Err(snapshots_unsupported(path))
+    }
+
     // ═══════════════════════════════════════════════════════════════════════
     // Tool Dispatch
     // ═══════════════════════════════════════════════════════════════════════
@@ -115,3 +143,7 @@ pub trait KernelBackend: Send + Sync {
     /// `git` that hand paths to external C libraries need the real path.
     fn resolve_real_path(&self, path: &Path) -> Option<PathBuf>;
 }
+
+fn snapshots_unsupported(path: &Path) -> BackendError {
+    BackendError::InvalidOperation(format!("snapshots not supported for {}", path.display()))
+}
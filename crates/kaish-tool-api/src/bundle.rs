@@ -0,0 +1,27 @@
+//! Tool bundles: a named set of tools installed into a kernel in one step.
+//!
+//! A bundle is how an out-of-tree crate contributes tools without forking the
+//! kernel: it implements [`ToolBundle`], the embedder hands it to
+//! `KernelConfig::with_tool_bundle`, and every kernel built from that config
+//! registers the bundle's tools after the builtins. Bundles are linked at
+//! compile time — there is no dynamic loading.
+
+use std::sync::Arc;
+
+use crate::tool::Tool;
+
+/// A named set of tools.
+pub trait ToolBundle: Send + Sync {
+    /// The bundle's name, for diagnostics.
+    fn name(&self) -> &str;
+
+    /// The tools this bundle contributes. Called once per kernel; a tool
+    /// named like a builtin replaces it.
+    fn tools(&self) -> Vec<Arc<dyn Tool>>;
+}
+
+impl std::fmt::Debug for dyn ToolBundle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ToolBundle").field(&self.name()).finish()
+    }
+}
@@ -7,9 +7,9 @@
 //!
 //! See the clap builtin pattern in CLAUDE.md (Contributor conventions).
 
-use clap::{Arg, ArgAction, Command};
+use clap::{Arg, ArgAction, Command, Parser};
 
-use kaish_types::{ParamSchema, ToolSchema, Value};
+use kaish_types::{ParamSchema, ToolArgs, ToolSchema, Value};
 
 /// Build a `ToolSchema` for a builtin from its clap [`Command`] reflection plus
 /// hand-written description and examples.
@@ -66,6 +66,26 @@ pub fn schema_tree_from_clap(
     schema
 }
 
+/// Parse a tool's arguments into its clap-derived struct.
+///
+/// Renders `args` with [`ToolArgs::to_argv`] and hands the result to clap
+/// with `name` as argv[0] — the parse half of the builtin pattern, whose
+/// schema half is [`schema_from_clap`]. Either failure comes back as a usage
+/// message prefixed with the tool name, ready for [`ExecResult::usage`]:
+///
+/// ```ignore
+/// let parsed: CatArgs = match parse_clap_args("cat", &args) {
+///     Ok(p) => p,
+///     Err(e) => return ExecResult::usage(e),
+/// };
+/// ```
+///
+/// [`ExecResult::usage`]: kaish_types::ExecResult::usage
+pub fn parse_clap_args<P: Parser>(name: &str, args: &ToolArgs) -> Result<P, String> {
+    let argv = args.to_argv().map_err(|e| format!("{name}: {e}"))?;
+    P::try_parse_from(std::iter::once(name.to_string()).chain(argv)).map_err(|e| format!("{name}: {e}"))
+}
+
 /// Reflect a clap subcommand (and its descendants) into a child [`ToolSchema`].
 ///
 /// Name and description are taken from the clap command; command-level aliases
@@ -115,7 +135,7 @@ pub fn params_from_clap(cmd: &Command) -> Vec<ParamSchema> {
 
 fn is_skipped(arg: &Arg) -> bool {
     let id = arg.get_id().as_str();
-    if matches!(id, "help" | "version" | "json") {
+    if matches!(id, "help" | "version" | "json" | "no_cache") {
         return true;
     }
     // Keep hidden positionals (real user surface, just marked hidden to keep
@@ -213,7 +233,7 @@ fn arg_to_param(arg: &Arg) -> ParamSchema {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use clap::{CommandFactory, Parser};
+    use clap::CommandFactory;
 
     #[derive(Parser, Debug)]
     #[command(name = "demo", about = "demo tool")]
@@ -243,6 +263,24 @@ mod tests {
         paths: Vec<String>,
     }
 
+    #[test]
+    fn parse_clap_args_binds_and_reports_usage() {
+        let mut args = ToolArgs::new();
+        args.flags.insert("n".to_string());
+        args.named.insert("lines".to_string(), Value::Int(3));
+        args.positional.push(Value::String("a.txt".into()));
+        let parsed: DemoArgs = parse_clap_args("demo", &args).expect("parses");
+        assert!(parsed.number);
+        assert_eq!(parsed.lines, 3);
+        assert_eq!(parsed.paths, ["a.txt"]);
+
+        let mut args = ToolArgs::new();
+        args.named.insert("lines".to_string(), Value::String("many".into()));
+        let err = parse_clap_args::<DemoArgs>("demo", &args).unwrap_err();
+        assert!(err.starts_with("demo: "), "{err}");
+        assert!(err.contains("many"), "{err}");
+    }
+
     #[test]
     fn bool_flag_becomes_bool_param() {
         let cmd = DemoArgs::command();
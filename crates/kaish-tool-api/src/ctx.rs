@@ -6,9 +6,50 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use kaish_types::{OutputFormat, Value};
+use tokio_util::sync::CancellationToken;
 
 use crate::backend::KernelBackend;
 
+/// A status update from a long-running tool, sent with
+/// [`ToolCtx::report_progress`].
+///
+/// Either half may be absent: a download knows its percentage, a directory
+/// walk only what it is looking at.
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Progress {
+    /// How far along the work is, 0–100.
+    pub percent: Option<f64>,
+    /// What the tool is doing now.
+    pub message: Option<String>,
+}
+
+impl Progress {
+    /// Progress as a percentage, clamped to 0–100.
+    pub fn percent(percent: f64) -> Self {
+        Self { percent: Some(percent.clamp(0.0, 100.0)), message: None }
+    }
+
+    /// Progress as a status message alone.
+    pub fn message(message: impl Into<String>) -> Self {
+        Self { percent: None, message: Some(message.into()) }
+    }
+
+    /// `done` of `total` steps, as a percentage; zero steps counts as done.
+    pub fn steps(done: usize, total: usize) -> Self {
+        if total == 0 {
+            return Self::percent(100.0);
+        }
+        Self::percent(done as f64 * 100.0 / total as f64)
+    }
+
+    /// Attach a status message.
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
 /// RAII guard returned by [`ToolCtx::patient`].
 ///
 /// While held, the kernel's script-level timeout watchdog is suspended for
@@ -103,6 +144,32 @@ pub trait ToolCtx: Send + Sync {
         PatientGuard::inert()
     }
 
+    /// The token that fires when this execution is cancelled
+    /// (`Kernel::cancel()`, the embedder's token, a timeout).
+    ///
+    /// A tool doing long work should check `is_cancelled()` between steps or
+    /// `select!` its waits against `cancelled()`, then stop and return
+    /// [`ExecResult::interrupted`](kaish_types::ExecResult::interrupted) —
+    /// the kernel reports the request as interrupted either way.
+    ///
+    /// The default implementation returns a fresh token that never fires;
+    /// the kernel's context overrides it.
+    fn cancel_token(&self) -> CancellationToken {
+        CancellationToken::new()
+    }
+
+    /// Report how far along a long-running tool is.
+    ///
+    /// The kernel hands the update to the embedder's progress hooks — the
+    /// REPL draws it as a status line. Updates are advisory: they may be
+    /// dropped, so a tool should send them freely and never depend on one
+    /// being seen.
+    ///
+    /// The default implementation drops the update.
+    fn report_progress(&self, progress: Progress) {
+        let _ = progress;
+    }
+
     /// Escape hatch for trusted in-tree tools: recover the concrete context.
     ///
     /// Out-of-tree tools must not rely on this — downcasting to a kernel type
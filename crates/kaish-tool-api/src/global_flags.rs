@@ -1,6 +1,6 @@
 //! Global flags shared by every builtin via `#[command(flatten)]`.
 //!
-//! Today this is just `--json`. Every builtin flattens `GlobalFlags` into its
+//! Today this is `--json` and `--no-cache`. Every builtin flattens `GlobalFlags` into its
 //! own clap struct and calls `parsed.global.apply(ctx)` after parsing; the
 //! kernel reads the output format the flag set (via
 //! [`ToolCtx::set_output_format`](crate::ToolCtx::set_output_format)) after
@@ -22,6 +22,11 @@ pub struct GlobalFlags {
     /// Render structured output as JSON.
     #[arg(long)]
     pub json: bool,
+
+    /// Run even if a cached result exists, and refresh the cache entry.
+    /// Read by the kernel at dispatch; builtins only need to accept it.
+    #[arg(long = "no-cache")]
+    pub no_cache: bool,
 }
 
 impl GlobalFlags {
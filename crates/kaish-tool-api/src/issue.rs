@@ -63,6 +63,22 @@ pub enum IssueCode {
     /// for other uses (filenames, `source foo.kai`), so this is caught here
     /// rather than by tightening the lexer regex.
     DottedAssignmentTarget,
+    /// Command is refused by the kernel's execution policy.
+    PolicyDenied,
+    /// A `par { ... }` block's statements share variables or written paths,
+    /// so they will run one after another.
+    ParConflict,
+    /// A function is defined with a builtin's name, so it runs instead of
+    /// the builtin (reachable as `builtin:name`).
+    ShadowsBuiltin,
+    /// A call names a function of an imported module (`git.sync`) that the
+    /// module does not define.
+    UnknownModuleFunction,
+    /// `on EVENT { ... }` names an event the kernel never raises.
+    UnknownHookEvent,
+    /// `[[ e in X ]]` where `X` is a string or number literal. Membership is
+    /// collection-only, so this fails every time it runs.
+    MembershipOnScalar,
 }
 
 impl IssueCode {
@@ -93,6 +109,12 @@ impl IssueCode {
             IssueCode::LastResultFieldAccess => "E015",
             IssueCode::LvalueUndefinedRoot => "E016",
             IssueCode::DottedAssignmentTarget => "E017",
+            IssueCode::UnknownModuleFunction => "E018",
+            IssueCode::UnknownHookEvent => "E019",
+            IssueCode::MembershipOnScalar => "E020",
+            IssueCode::PolicyDenied => "W007",
+            IssueCode::ParConflict => "W008",
+            IssueCode::ShadowsBuiltin => "W009",
         }
     }
 
@@ -104,12 +126,13 @@ impl IssueCode {
     /// noise. Opt a code in here only when its guidance is worth interrupting
     /// for. This is the surfacing seam for the "did-you-mean" guidance pass.
     ///
-    /// Currently dormant: the one opted-in code (`PosixTestCommand`) was retired
-    /// when `test` became a builtin. The seam stays wired for the next code that
-    /// earns surfacing — add a `matches!(self, IssueCode::Foo | …)` arm here.
+    /// `PolicyDenied` surfaces: the command will be refused at runtime, and
+    /// the agent should learn which one before reading a bare exit code.
+    /// `ParConflict` surfaces too: the agent asked for concurrency and is
+    /// getting a sequential run, which it should know about. So does
+    /// `ShadowsBuiltin`: every later call of that name changes meaning.
     pub fn surfaces_to_agent(&self) -> bool {
-        let _ = self;
-        false
+        matches!(self, IssueCode::PolicyDenied | IssueCode::ParConflict | IssueCode::ShadowsBuiltin)
     }
 
     /// Default severity for this issue code.
@@ -127,7 +150,10 @@ impl IssueCode {
             | IssueCode::ScatterWithoutGather
             | IssueCode::LastResultFieldAccess
             | IssueCode::LvalueUndefinedRoot
-            | IssueCode::DottedAssignmentTarget => Severity::Error,
+            | IssueCode::DottedAssignmentTarget
+            | IssueCode::UnknownModuleFunction
+            | IssueCode::UnknownHookEvent
+            | IssueCode::MembershipOnScalar => Severity::Error,
 
             // These are warnings because context matters:
             // - MissingRequiredArg: might be provided by pipeline stdin or environment
@@ -138,6 +164,16 @@ impl IssueCode {
             | IssueCode::UndefinedCommand
             | IssueCode::UnknownFlag
             | IssueCode::PossiblyUndefinedVariable => Severity::Warning,
+
+            // A warning, not an error: the refusal happens at runtime with a
+            // dedicated exit code, and statements before it still run.
+            IssueCode::PolicyDenied => Severity::Warning,
+
+            // The block still runs, just sequentially.
+            IssueCode::ParConflict => Severity::Warning,
+
+            // Shadowing is often deliberate (a wrapper function).
+            IssueCode::ShadowsBuiltin => Severity::Warning,
         }
     }
 }
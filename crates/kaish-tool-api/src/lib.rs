@@ -13,34 +13,48 @@
 //!   public API.
 //! - [`ToolCtx`] — the **trimmed** execution context. It exposes only what a
 //!   well-behaved, portable tool needs (backend I/O, cwd, variable access,
-//!   output format). Trusted in-tree builtins that need deeper kernel state
-//!   (job control, pipes, the dispatcher) recover the concrete context via
-//!   [`ToolCtx::as_any_mut`] — a documented escape hatch, not part of the
-//!   portable contract.
+//!   output format, cancellation, progress). Trusted in-tree builtins that
+//!   need deeper kernel state (job control, pipes, the dispatcher) recover
+//!   the concrete context via [`ToolCtx::as_any_mut`] — a documented escape
+//!   hatch, not part of the portable contract.
 //! - [`KernelBackend`] — the I/O + tool-dispatch backend a tool reaches
 //!   through `ctx.backend()`.
-//! - [`GlobalFlags`], [`schema_from_clap`], [`validate_against_schema`] — the
-//!   clap-reflection and validation machinery shared by all builtins.
+//! - [`ToolBundle`] — a named set of tools an embedder installs into a
+//!   kernel in one step.
+//! - [`GlobalFlags`], [`schema_from_clap`], [`parse_clap_args`],
+//!   [`validate_against_schema`] — the clap-reflection, parsing, and
+//!   validation machinery shared by all builtins.
+//! - [`usage_problems`], [`UsageError`] — the schema checks the validator
+//!   and the kernel's runtime dispatch share, with did-you-mean suggestions
+//!   for mistyped flags.
 //!
 //! The pure-data types tools traffic in (`Value`, `ToolArgs`, `ToolSchema`,
 //! `ExecResult`, `OutputData`, …) live one layer down in `kaish-types`.
 
 mod backend;
+mod bundle;
 mod clap_schema;
 mod ctx;
 mod global_flags;
 mod issue;
 mod tool;
+mod usage;
 
 pub use backend::KernelBackend;
-pub use clap_schema::{params_from_clap, schema_from_clap, schema_tree_from_clap};
-pub use ctx::{PatientGuard, ToolCtx};
+pub use bundle::ToolBundle;
+pub use clap_schema::{params_from_clap, parse_clap_args, schema_from_clap, schema_tree_from_clap};
+pub use ctx::{PatientGuard, Progress, ToolCtx};
 pub use global_flags::GlobalFlags;
 pub use issue::{IssueCode, Severity, Span, ValidationIssue};
 pub use tool::{is_global_output_flag, validate_against_schema, Tool};
+pub use usage::{did_you_mean, usage_problems, UsageError, UsageProblem};
 
 // Re-export the data types tool authors need most often, so a tool crate can
 // depend on just `kaish-tool-api` for the common case.
 pub use kaish_types::{
-    ExecResult, OutputData, OutputFormat, ParamSchema, ToolArgs, ToolSchema, Value,
+    ExecResult, OutputData, OutputFormat, ParamSchema, ToolAnnotations, ToolArgs, ToolSchema, Value,
 };
+
+/// The token [`ToolCtx::cancel_token`] hands out, re-exported so a tool can
+/// `select!` on it without naming `tokio-util` itself.
+pub use tokio_util::sync::CancellationToken;
@@ -1,13 +1,14 @@
 //! The `Tool` trait and argument validation.
 
-use std::collections::HashSet;
+use std::time::Duration;
 
 use async_trait::async_trait;
 
 use kaish_types::{ExecResult, ParamSchema, ToolArgs, ToolSchema, Value};
 
 use crate::ctx::ToolCtx;
-use crate::issue::{IssueCode, Severity, ValidationIssue};
+use crate::issue::{IssueCode, ValidationIssue};
+use crate::usage::{usage_problems, UsageProblem};
 
 /// A tool that can be executed.
 ///
@@ -33,6 +34,15 @@ pub trait Tool: Send + Sync {
     fn validate(&self, args: &ToolArgs) -> Vec<ValidationIssue> {
         validate_against_schema(args, &self.schema())
     }
+
+    /// How long a result of this invocation may be reused, if at all.
+    ///
+    /// Return `Some(ttl)` only when the output is determined by `args` alone
+    /// — `cat` of a read-only mount, say — and the kernel caches successful
+    /// results keyed by the evaluated command. Default `None`: never cached.
+    fn cache_ttl(&self, _args: &ToolArgs, _ctx: &dyn ToolCtx) -> Option<Duration> {
+        None
+    }
 }
 
 /// Validate arguments against a tool schema.
@@ -50,81 +60,21 @@ pub trait Tool: Send + Sync {
 pub fn validate_against_schema(args: &ToolArgs, schema: &ToolSchema) -> Vec<ValidationIssue> {
     let mut issues = Vec::new();
 
-    let positional_params: Vec<&ParamSchema> = schema.params.iter().filter(|p| p.positional).collect();
-    let flag_params: Vec<&ParamSchema> = schema.params.iter().filter(|p| !p.positional).collect();
-
-    // Required positionals: matched by slot among positional params only.
-    for (slot, param) in positional_params.iter().enumerate() {
-        if !param.required {
-            continue;
-        }
-        let has_positional = args.positional.len() > slot;
-        // A required positional can also be supplied as a named arg if the
-        // caller knows the param name (e.g. `mkdir paths=foo`).
-        let has_named = args.named.contains_key(&param.name);
-        if !has_positional && !has_named {
-            let code = IssueCode::MissingRequiredArg;
-            issues.push(ValidationIssue {
-                severity: code.default_severity(),
-                code,
-                message: format!("required parameter '{}' not provided", param.name),
-                span: None,
-                suggestion: Some(format!("add {} or {}=<value>", param.name, param.name)),
-            });
-        }
-    }
-
-    // Required flags: matched by name (or alias) against args.named / args.flags.
-    for param in &flag_params {
-        if !param.required {
-            continue;
-        }
-        let has_named = args.named.contains_key(&param.name);
-        let has_flag = param.param_type == "bool" && args.has_flag(&param.name);
-        if !has_named && !has_flag {
-            let code = IssueCode::MissingRequiredArg;
-            issues.push(ValidationIssue {
-                severity: code.default_severity(),
-                code,
-                message: format!("required parameter '{}' not provided", param.name),
-                span: None,
-                suggestion: Some(format!("add --{} <value>", param.name)),
-            });
-        }
-    }
-
-    // Check for unknown flags (only warn - tools may accept dynamic flags).
-    // Only bool flags are gathered for the strict known-flag set; the
-    // alias-fallback below catches value-taking flags via `matches_flag`.
-    let known_flags: HashSet<&str> = flag_params
-        .iter()
-        .filter(|p| p.param_type == "bool")
-        .flat_map(|p| {
-            std::iter::once(p.name.as_str())
-                .chain(p.aliases.iter().map(|a| a.as_str()))
-        })
-        .collect();
-
-    for flag in &args.flags {
-        // Strip leading dashes for comparison
-        let flag_name = flag.trim_start_matches('-');
-        // Global output flags are handled by the kernel, not the tool
-        if is_global_output_flag(flag_name) {
-            continue;
-        }
-        if !known_flags.contains(flag_name) && !known_flags.contains(flag.as_str()) {
-            // Check if any flag param matches this flag via aliases
-            let matches_alias = flag_params.iter().any(|p| p.matches_flag(flag));
-            if !matches_alias {
-                issues.push(ValidationIssue {
-                    severity: Severity::Warning,
-                    code: IssueCode::UnknownFlag,
-                    message: format!("unknown flag '{}'", flag),
-                    span: None,
-                    suggestion: None,
-                });
-            }
-        }
+    // Missing required parameters and unknown flags — the checks runtime
+    // dispatch shares (see `crate::usage`). Unknown flags only warn: tools
+    // may accept dynamic flags.
+    for problem in usage_problems(args, schema) {
+        let code = match problem {
+            UsageProblem::UnknownFlag { .. } => IssueCode::UnknownFlag,
+            UsageProblem::MissingParam { .. } => IssueCode::MissingRequiredArg,
+        };
+        issues.push(ValidationIssue {
+            severity: code.default_severity(),
+            code,
+            message: problem.message(),
+            span: None,
+            suggestion: problem.hint(),
+        });
     }
 
     // Type compatibility for named args (search the full schema — callers
@@ -139,6 +89,7 @@ pub fn validate_against_schema(args: &ToolArgs, schema: &ToolSchema) -> Vec<Vali
     // Type compatibility for positional args (matched by slot among
     // positional params). Extra positionals past the schema are ignored —
     // many builtins (cat, cp, mkdir) accept variadic positionals.
+    let positional_params: Vec<&ParamSchema> = schema.params.iter().filter(|p| p.positional).collect();
     for (slot, value) in args.positional.iter().enumerate() {
         if let Some(param) = positional_params.get(slot)
             && let Some(issue) = check_type_compatibility(&param.name, value, &param.param_type) {
@@ -158,14 +109,15 @@ pub fn validate_against_schema(args: &ToolArgs, schema: &ToolSchema) -> Vec<Vali
 // output format via `ToolCtx::set_output_format`; the kernel applies the
 // format after execute() returns.
 
-/// Check if a flag name is the kernel-owned `--json` flag.
+/// Check if a flag name is a kernel-owned global flag (`--json`,
+/// `--no-cache`).
 ///
 /// External commands (no schema) bypass clap entirely and the kernel
 /// doesn't touch their argv — `cargo --json` and similar work as
 /// expected. `is_global_output_flag` is retained for the validator's
 /// unknown-flag check.
 pub fn is_global_output_flag(name: &str) -> bool {
-    name == "json"
+    matches!(name, "json" | "no-cache")
 }
 
 /// Check if a value is compatible with a type.
@@ -304,4 +256,19 @@ mod validate_tests {
             issues
         );
     }
+
+    /// The validator's unknown-flag warning carries the same did-you-mean
+    /// the runtime usage error gives.
+    #[test]
+    fn unknown_flag_warning_suggests_the_closest_flag() {
+        let schema = schema_with_positionals_after_flags();
+        let mut args = ToolArgs::new();
+        args.flags.insert("verbsoe".into());
+        args.positional.push(Value::String("foo.txt".into()));
+
+        let issues = validate_against_schema(&args, &schema);
+        let issue = issues.iter().find(|i| i.code == IssueCode::UnknownFlag).expect("unknown flag issue");
+        assert_eq!(issue.message, "unknown flag '--verbsoe'");
+        assert_eq!(issue.suggestion.as_deref(), Some("did you mean '--verbose'?"));
+    }
 }
@@ -0,0 +1,268 @@
+//! Usage checks shared by the validator and runtime dispatch.
+//!
+//! [`usage_problems`] compares bound arguments with a tool's schema and
+//! reports unknown flags (with a did-you-mean over the schema's flag names)
+//! and missing required parameters. The validator turns each problem into a
+//! [`ValidationIssue`](crate::ValidationIssue) before a script runs; the
+//! kernel turns them into a [`UsageError`] when a tool rejects its
+//! arguments at run time, so both say the same thing.
+//!
+//! Unknown flags are only ever advisory here — plenty of tools accept flags
+//! their schema doesn't spell out — so the kernel consults a [`UsageError`]
+//! only after the tool itself has failed with a usage error.
+
+use std::collections::HashSet;
+
+use kaish_types::{ParamSchema, ToolArgs, ToolSchema};
+
+use crate::tool::is_global_output_flag;
+
+/// One way a call doesn't fit its tool's schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UsageProblem {
+    /// A flag the schema doesn't declare, with the closest declared
+    /// spelling when one is near enough.
+    UnknownFlag { flag: String, suggestion: Option<String> },
+    /// A required parameter with no value.
+    MissingParam { name: String, positional: bool },
+}
+
+impl UsageProblem {
+    /// What is wrong, in a sentence fragment: `unknown flag '--nubmer'`.
+    pub fn message(&self) -> String {
+        match self {
+            Self::UnknownFlag { flag, .. } => format!("unknown flag '{flag}'"),
+            Self::MissingParam { name, .. } => format!("required parameter '{name}' not provided"),
+        }
+    }
+
+    /// How to fix it, if there's something to say.
+    pub fn hint(&self) -> Option<String> {
+        match self {
+            Self::UnknownFlag { suggestion, .. } => suggestion.as_ref().map(|s| format!("did you mean '{s}'?")),
+            Self::MissingParam { name, positional: true } => Some(format!("add {name} or {name}=<value>")),
+            Self::MissingParam { name, positional: false } => Some(format!("add --{name} <value>")),
+        }
+    }
+}
+
+/// Every [`UsageProblem`] in `args` against `schema`: missing required
+/// parameters (positionals by slot among positional params, flags by name),
+/// then unknown flags. Kernel-owned flags (`--json`, `--no-cache`) are
+/// never unknown.
+pub fn usage_problems(args: &ToolArgs, schema: &ToolSchema) -> Vec<UsageProblem> {
+    let mut problems = Vec::new();
+
+    let positional_params: Vec<&ParamSchema> = schema.params.iter().filter(|p| p.positional).collect();
+    let flag_params: Vec<&ParamSchema> = schema.params.iter().filter(|p| !p.positional).collect();
+
+    for (slot, param) in positional_params.iter().enumerate() {
+        // A required positional can also be supplied as a named arg if the
+        // caller knows the param name (e.g. `mkdir paths=foo`).
+        if param.required && args.positional.len() <= slot && !args.named.contains_key(&param.name) {
+            problems.push(UsageProblem::MissingParam { name: param.name.clone(), positional: true });
+        }
+    }
+
+    for param in &flag_params {
+        let has_named = args.named.contains_key(&param.name);
+        let has_flag = param.param_type == "bool" && args.has_flag(&param.name);
+        if param.required && !has_named && !has_flag {
+            problems.push(UsageProblem::MissingParam { name: param.name.clone(), positional: false });
+        }
+    }
+
+    // Only bool flags are gathered for the strict known-flag set; the
+    // alias fallback below catches value-taking flags via `matches_flag`.
+    let known_flags: HashSet<&str> = flag_params
+        .iter()
+        .filter(|p| p.param_type == "bool")
+        .flat_map(|p| std::iter::once(p.name.as_str()).chain(p.aliases.iter().map(|a| a.as_str())))
+        .collect();
+
+    let mut flags: Vec<&String> = args.flags.iter().collect();
+    flags.sort();
+    for flag in flags {
+        let flag_name = flag.trim_start_matches('-');
+        if is_global_output_flag(flag_name)
+            || known_flags.contains(flag_name)
+            || known_flags.contains(flag.as_str())
+            || flag_params.iter().any(|p| p.matches_flag(flag))
+        {
+            continue;
+        }
+        let candidates = flag_params
+            .iter()
+            .flat_map(|p| std::iter::once(p.name.as_str()).chain(p.aliases.iter().map(|a| a.as_str())))
+            .filter(|name| name.chars().count() > 1);
+        problems.push(UsageProblem::UnknownFlag {
+            flag: dashed(flag_name),
+            suggestion: did_you_mean(flag_name, candidates).map(dashed),
+        });
+    }
+
+    problems
+}
+
+/// A tool call that doesn't fit the tool's schema: what's wrong, plus the
+/// usage line and the parameters the tool does take.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageError {
+    /// The tool called.
+    pub tool: String,
+    /// What's wrong with the call; never empty.
+    pub problems: Vec<UsageProblem>,
+    /// [`ToolSchema::usage_line`].
+    pub usage: String,
+    /// Each parameter's spellings joined with `/` (`-n/--number`, `PATH`).
+    pub params: Vec<String>,
+}
+
+impl UsageError {
+    /// The usage error for calling `schema`'s tool with `args`, or `None`
+    /// if nothing is wrong that the schema can see.
+    pub fn check(args: &ToolArgs, schema: &ToolSchema) -> Option<Self> {
+        let problems = usage_problems(args, schema);
+        if problems.is_empty() {
+            return None;
+        }
+        Some(Self {
+            tool: schema.name.clone(),
+            problems,
+            usage: schema.usage_line(),
+            params: schema.params.iter().map(|p| p.spellings().join("/")).collect(),
+        })
+    }
+
+    /// The error for people: one line per problem, then the usage line and
+    /// the parameters.
+    ///
+    /// ```text
+    /// cat: unknown flag '--nubmer' (did you mean '--number'?)
+    /// usage: cat [OPTIONS] [PATHS]
+    /// parameters: -n/--number, PATHS
+    /// ```
+    pub fn message(&self) -> String {
+        let mut message = String::new();
+        for problem in &self.problems {
+            message.push_str(&format!("{}: {}", self.tool, problem.message()));
+            if let Some(hint) = problem.hint() {
+                message.push_str(&format!(" ({hint})"));
+            }
+            message.push('\n');
+        }
+        message.push_str(&format!("usage: {}", self.usage));
+        if !self.params.is_empty() {
+            message.push_str(&format!("\nparameters: {}", self.params.join(", ")));
+        }
+        message
+    }
+}
+
+/// The candidate closest to `word` by edit distance, if it is within a
+/// third of the word's length (at least one edit).
+pub fn did_you_mean<'a>(word: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let limit = (word.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(word, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Edit distance: insertions, deletions, substitutions, and swaps of two
+/// adjacent characters (the commonest typo, `grpe` for `grep`), each one
+/// edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, &ca) in a.iter().enumerate() {
+        let mut next = vec![i + 1; b.len() + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let mut best = (row[j + 1] + 1).min(next[j] + 1).min(row[j] + usize::from(ca != cb));
+            if i > 0 && j > 0 && ca == b[j - 1] && a[i - 1] == cb {
+                best = best.min(before[j - 1] + 1);
+            }
+            next[j + 1] = best;
+        }
+        before = std::mem::replace(&mut row, next);
+    }
+    row[b.len()]
+}
+
+/// A flag as typed: `-n` for one letter, `--name` otherwise.
+fn dashed(flag: &str) -> String {
+    let bare = flag.trim_start_matches('-');
+    if bare.chars().count() == 1 { format!("-{bare}") } else { format!("--{bare}") }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaish_types::Value;
+
+    fn cat() -> ToolSchema {
+        ToolSchema::new("cat", "concatenate")
+            .param(ParamSchema::new("number", "bool").with_aliases(["n"]))
+            .param(ParamSchema::new("squeeze-blank", "bool").with_aliases(["s", "squeeze_blank"]))
+            .param(ParamSchema::required("path", "string", "file to read").positional())
+    }
+
+    #[test]
+    fn edit_distance_counts_edits() {
+        assert_eq!(edit_distance("number", "number"), 0);
+        assert_eq!(edit_distance("nubmer", "number"), 1);
+        assert_eq!(edit_distance("grpe", "grep"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn suggestions_stay_close() {
+        let names = ["number", "squeeze-blank"];
+        assert_eq!(did_you_mean("nubmer", names), Some("number"));
+        assert_eq!(did_you_mean("squeeze", names), None);
+        assert_eq!(did_you_mean("xyz", names), None);
+    }
+
+    #[test]
+    fn unknown_flags_and_missing_params() {
+        let mut args = ToolArgs::new();
+        args.flags.insert("nubmer".into());
+        args.flags.insert("n".into());
+        args.flags.insert("json".into());
+        args.flags.insert("q".into());
+
+        let problems = usage_problems(&args, &cat());
+        assert_eq!(
+            problems,
+            [
+                UsageProblem::MissingParam { name: "path".into(), positional: true },
+                UsageProblem::UnknownFlag { flag: "--nubmer".into(), suggestion: Some("--number".into()) },
+                UsageProblem::UnknownFlag { flag: "-q".into(), suggestion: None },
+            ]
+        );
+
+        args.positional.push(Value::String("notes.txt".into()));
+        args.flags.clear();
+        assert!(usage_problems(&args, &cat()).is_empty());
+    }
+
+    #[test]
+    fn usage_error_lists_problems_and_parameters() {
+        let mut args = ToolArgs::new();
+        args.flags.insert("nubmer".into());
+        args.positional.push(Value::String("notes.txt".into()));
+
+        let error = UsageError::check(&args, &cat()).expect("usage error");
+        assert_eq!(
+            error.message(),
+            "cat: unknown flag '--nubmer' (did you mean '--number'?)\n\
+             usage: cat [OPTIONS] PATH\n\
+             parameters: -n/--number, -s/--squeeze-blank, PATH"
+        );
+    }
+}
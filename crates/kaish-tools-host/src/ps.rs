@@ -9,7 +9,7 @@ use std::collections::HashMap;
 
 use kaish_types::Value;
 use kaish_types::{ExecResult, OutputData, OutputNode};
-use kaish_tool_api::{schema_from_clap, GlobalFlags, Tool, ToolArgs, ToolCtx, ToolSchema};
+use kaish_tool_api::{parse_clap_args, schema_from_clap, GlobalFlags, Tool, ToolArgs, ToolCtx, ToolSchema};
 
 /// clap-derived argv layer for ps.
 #[derive(Parser, Debug)]
@@ -80,15 +80,9 @@ impl Tool for Ps {
     async fn execute(&self, mut args: ToolArgs, ctx: &mut dyn ToolCtx) -> ExecResult {
         args.flagify_bool_named(&self.schema());
 
-        let argv = match args.to_argv() {
-            Ok(v) => v,
-            Err(e) => return ExecResult::failure(2, format!("ps: {e}")),
-        };
-        let parsed = match PsArgs::try_parse_from(
-            std::iter::once("ps".to_string()).chain(argv),
-        ) {
+        let parsed: PsArgs = match parse_clap_args("ps", &args) {
             Ok(p) => p,
-            Err(e) => return ExecResult::failure(2, format!("ps: {e}")),
+            Err(e) => return ExecResult::usage(e),
         };
         parsed.global.apply(ctx);
 
@@ -34,6 +34,25 @@ pub struct MountInfo {
     /// values that do not track residency should use `None` so `kaish-mounts`
     /// renders `-` rather than a misleading number.
     pub resident_bytes: Option<u64>,
+    /// The mount's own cap on `resident_bytes`, if it has one
+    /// (`MemoryFs::with_quota`). `None` means no per-mount quota; a shared
+    /// VFS budget may still apply.
+    pub quota_bytes: Option<u64>,
+}
+
+/// A snapshot a memory-backed filesystem holds for rollback.
+///
+/// Returned by `Filesystem::snapshots` and `KernelBackend::snapshots`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotInfo {
+    /// Id to pass to restore or drop; unique within the filesystem.
+    pub id: u64,
+    /// The directory captured (e.g., "/scratch").
+    pub path: PathBuf,
+    /// Files captured.
+    pub files: usize,
+    /// Content bytes captured.
+    pub bytes: u64,
 }
 
 /// Backend operation errors.
@@ -373,6 +392,8 @@ impl From<ExecResult> for ToolResult {
         // `docs/binary-data.md`.
         let stdout = exec.text_out().into_owned();
         let output = exec.take_output();
+        // One stderr string on this side: the full fd-2 view.
+        let stderr = exec.take_stderr_text();
 
         // Convert ast::Value to serde_json::Value if present
         let data = exec.data.map(|v| value_to_json(&v));
@@ -380,7 +401,7 @@ impl From<ExecResult> for ToolResult {
         Self {
             code,
             stdout,
-            stderr: exec.err,
+            stderr,
             data,
             output,
             did_spill: exec.did_spill,
@@ -404,8 +425,19 @@ impl From<ToolResult> for ExecResult {
     /// round-trip conversion: a backend tool's JSON is external input, so an
     /// object shaped like the byte envelope must stay a plain record, never
     /// silently auto-decode to `Value::Bytes`.
+    ///
+    /// `ToolResult` carries a single `stderr` string, so the split has to be
+    /// inferred: on failure it is the tool's error message (`.err`, which is
+    /// what `--json` error envelopes read), on success it is stream output
+    /// (`.stderr`, e.g. a progress note alongside a clean exit).
     fn from(result: ToolResult) -> Self {
-        let mut exec = ExecResult::from_output(result.code as i64, result.stdout, result.stderr);
+        let mut exec = if result.code == 0 {
+            ExecResult::from_output(0, result.stdout, result.stderr)
+        } else {
+            let mut exec = ExecResult::from_output(result.code as i64, result.stdout, "");
+            exec.err = result.stderr;
+            exec
+        };
         exec.set_output(result.output);
         exec.data = result.data.map(json_to_value_no_envelope);
         exec.did_spill = result.did_spill;
@@ -93,6 +93,22 @@ pub fn hex_dump(data: &[u8]) -> String {
     out
 }
 
+/// A hex dump of at most the first `max` bytes of `data`, followed by a note
+/// of how much was left out. Binary shown to a human is for recognising what
+/// the bytes are, and a few hundred of them do that; dumping a whole image or
+/// archive floods the terminal. `xxd` still dumps everything.
+pub fn hex_preview(data: &[u8], max: usize) -> String {
+    if data.len() <= max {
+        return hex_dump(data);
+    }
+    format!(
+        "{}\n... {} more bytes ({} total; pipe to xxd for all of it)",
+        hex_dump(&data[..max]),
+        data.len() - max,
+        data.len()
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -147,4 +163,13 @@ mod tests {
         // 'A' 'B' printable, 0x00 -> '.', 'C' printable
         assert!(dump.ends_with("|AB.C|"), "gutter: {dump}");
     }
+
+    #[test]
+    fn hex_preview_truncates_long_data() {
+        let data = vec![0u8; 100];
+        assert_eq!(hex_preview(&data[..32], 32), hex_dump(&data[..32]));
+        let preview = hex_preview(&data, 32);
+        assert_eq!(preview.lines().count(), 3, "{preview}");
+        assert!(preview.ends_with("... 68 more bytes (100 total; pipe to xxd for all of it)"), "{preview}");
+    }
 }
@@ -18,7 +18,7 @@ pub mod value;
 
 // Flat re-exports for convenience
 pub use backend::*;
-pub use bytes::{bytes_to_envelope, envelope_to_bytes, hex_dump};
+pub use bytes::{bytes_to_envelope, envelope_to_bytes, hex_dump, hex_preview};
 pub use command::*;
 pub use dir_entry::*;
 pub use job::*;
@@ -7,7 +7,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::result::{ExecResult, LatchRequest};
+use crate::result::{ErrorKind, ExecResult, LatchRequest};
 
 // ============================================================
 // Structured Output (Tree-of-Tables Model)
@@ -538,9 +538,9 @@ pub enum OutputFormat {
 }
 
 /// Build the `--json` error/latch envelope for a result carrying a pending
-/// confirmation latch (`.latch` is `Some`) — `{"error", "code", "data"?,
-/// "latch"}`. The ONE place a latch gets folded into `--json` output, so a
-/// latched `wait` (which carries text output, e.g. `"[1] Latched\n"`) and a
+/// confirmation latch (`.latch` is `Some`) — `{"error", "code", "kind",
+/// "data"?, "latch"}`. The ONE place a latch gets folded into `--json` output,
+/// so a latched `wait` (which carries text output, e.g. `"[1] Latched\n"`) and a
 /// latched `rm` (bare exit-2 failure, no output at all) converge on the same
 /// shape instead of diverging by which branch of [`apply_output_format`] they
 /// happen to take. `error` is `result.err` if non-empty, else the rendered
@@ -557,6 +557,7 @@ fn latch_envelope(result: &ExecResult, latch: &LatchRequest) -> serde_json::Valu
     let mut obj = serde_json::json!({
         "error": error,
         "code": result.code,
+        "kind": ErrorKind::Latch.as_str(),
     });
     if let Some(data) = &result.data {
         obj["data"] = crate::result::value_to_json(data);
@@ -621,6 +622,11 @@ pub fn apply_output_format(mut result: ExecResult, format: OutputFormat) -> Exec
                         "error": result.err,
                         "code": result.code,
                     });
+                    // The error class, so a JSON consumer branches on `kind`
+                    // instead of parsing `error`.
+                    if let Some(kind) = result.error_kind() {
+                        obj["kind"] = kind.as_str().into();
+                    }
                     // A tool that attached structured data to an error result
                     // must keep it reachable under --json — nest it under `data`
                     // so the envelope holds the diagnostic *and* the structured
@@ -900,8 +906,8 @@ mod tests {
     #[test]
     fn apply_output_format_emits_json_error_object_on_failure() {
         // A failure with empty stdout and a populated err must still honor
-        // --json: emit {"error", "code"} rather than leaking the message as
-        // plain text (e.g. `grep --json --bogus-flag`).
+        // --json: emit {"error", "code", "kind"} rather than leaking the
+        // message as plain text (e.g. `grep --json --bogus-flag`).
         let result = ExecResult::failure(2, "grep: unknown flag --bogus-flag");
         assert!(!result.has_output());
         assert!(result.text_out().is_empty());
@@ -911,7 +917,7 @@ mod tests {
         let parsed: serde_json::Value = serde_json::from_str(&out).expect("valid JSON");
         assert_eq!(
             parsed,
-            serde_json::json!({"error": "grep: unknown flag --bogus-flag", "code": 2})
+            serde_json::json!({"error": "grep: unknown flag --bogus-flag", "code": 2, "kind": "usage"})
         );
         // .data mirrors the JSON object.
         assert!(matches!(formatted.data, Some(crate::value::Value::Json(_))));
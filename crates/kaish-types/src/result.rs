@@ -134,6 +134,128 @@ impl std::fmt::Display for BinaryNotText {
 
 impl std::error::Error for BinaryNotText {}
 
+/// Exit codes with a fixed meaning across kaish.
+///
+/// Builtins, the dispatcher and the scheduler all draw from this table, so a
+/// script (or an embedder reading [`ExecResult::error_info`]) can branch on
+/// the code without parsing the message. Codes not listed here are a
+/// command's own — `grep`'s 1 for "no match", `diff`'s 1 for "differs".
+pub mod exit_code {
+    /// The command succeeded.
+    pub const SUCCESS: i64 = 0;
+    /// General failure.
+    pub const FAILURE: i64 = 1;
+    /// Usage or validation error: a bad flag, a missing operand, a value of
+    /// the wrong shape. Also the code of a pending confirmation latch.
+    pub const USAGE: i64 = 2;
+    /// Output exceeded the output limit and was spilled or truncated.
+    pub const OUTPUT_SPILLED: i64 = 3;
+    /// The kernel's execution policy refused the command. Borrowed from
+    /// sysexits' `EX_NOPERM`.
+    pub const POLICY_DENIED: i64 = 77;
+    /// The command ran past its deadline (`timeout`, request timeout).
+    pub const TIMEOUT: i64 = 124;
+    /// A configured execution limit stopped the request (loop iterations).
+    pub const LIMIT_EXCEEDED: i64 = 125;
+    /// The command was found but could not be executed.
+    pub const NOT_EXECUTABLE: i64 = 126;
+    /// No such command.
+    pub const NOT_FOUND: i64 = 127;
+    /// Killed by a signal: the code is `SIGNAL_BASE + signo`.
+    pub const SIGNAL_BASE: i64 = 128;
+    /// Interrupted or cancelled (`SIGNAL_BASE + SIGINT`).
+    pub const INTERRUPTED: i64 = 130;
+}
+
+/// What kind of failure an [`ExecResult`] represents, derived from its exit
+/// code (see [`exit_code`]) and the latch/spill markers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Any failure without a more specific code.
+    Failure,
+    /// Bad usage or a validation error (exit 2).
+    Usage,
+    /// A destructive operation is waiting for confirmation (exit 2 + latch).
+    Latch,
+    /// Output was capped (exit 3).
+    OutputSpilled,
+    /// Refused by the execution policy (exit 77).
+    PolicyDenied,
+    /// Deadline elapsed (exit 124).
+    Timeout,
+    /// An execution limit stopped the request (exit 125).
+    LimitExceeded,
+    /// Found but not executable (exit 126).
+    NotExecutable,
+    /// Command not found (exit 127).
+    NotFound,
+    /// Interrupted or cancelled (exit 130).
+    Interrupted,
+    /// Killed by another signal (exit 128+n).
+    Signal,
+}
+
+impl ErrorKind {
+    /// The stable snake_case name, as serialized.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::Failure => "failure",
+            ErrorKind::Usage => "usage",
+            ErrorKind::Latch => "latch",
+            ErrorKind::OutputSpilled => "output_spilled",
+            ErrorKind::PolicyDenied => "policy_denied",
+            ErrorKind::Timeout => "timeout",
+            ErrorKind::LimitExceeded => "limit_exceeded",
+            ErrorKind::NotExecutable => "not_executable",
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::Interrupted => "interrupted",
+            ErrorKind::Signal => "signal",
+        }
+    }
+
+    /// A short next step for the kinds that have an obvious one.
+    pub fn hint(self) -> Option<&'static str> {
+        match self {
+            ErrorKind::Usage => Some("run `help <command>` for usage"),
+            ErrorKind::Latch => Some("re-run with the --confirm nonce from the message"),
+            ErrorKind::OutputSpilled => {
+                Some("read the spill file, or `set +o output-limit` before re-running")
+            }
+            ErrorKind::PolicyDenied => {
+                Some("the kernel's policy forbids this; use an allowed command")
+            }
+            ErrorKind::Timeout => Some("raise the deadline or narrow the work"),
+            ErrorKind::LimitExceeded => Some("bound the loop, or raise the kernel's limit"),
+            ErrorKind::NotExecutable => Some("check the file's permissions and interpreter"),
+            ErrorKind::NotFound => Some("check the spelling; `help` lists builtins"),
+            ErrorKind::Failure | ErrorKind::Interrupted | ErrorKind::Signal => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A failed result, classified: what `kaish-last --error` prints and what the
+/// `--json` error envelope carries under `kind`/`hint`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ErrorInfo {
+    /// The failure class.
+    pub kind: ErrorKind,
+    /// The exit code it was derived from.
+    pub code: i64,
+    /// The error message ([`ExecResult::err`], else the stderr stream).
+    pub message: String,
+    /// A suggested next step, when the kind has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
 /// The result of executing a command or pipeline.
 ///
 /// `$?` in script syntax is the POSIX exit code (an integer). To read the
@@ -143,7 +265,8 @@ impl std::error::Error for BinaryNotText {}
 ///
 /// Notes on the fields:
 /// - `code` — exit code (0 = success)
-/// - `err` — error message if failed
+/// - `err` — kaish's own error message for a failure (`cat: no such file`)
+/// - `stderr` — the captured standard error stream of the command
 /// - `out` — raw stdout as string
 /// - `data` — structured data; only set by builtins/tools that opt in
 ///   (e.g. `seq`, `jq`, `cut`, `find`, `glob`, `split`). External commands
@@ -155,8 +278,16 @@ pub struct ExecResult {
     pub code: i64,
     /// Standard output payload — text (canonical for pipes) or raw bytes.
     out: OutputPayload,
-    /// Raw standard error as a string.
+    /// The mechanical error message — what kaish (or a builtin) says about
+    /// *why* the command failed. Empty on success.
     pub err: String,
+    /// The captured standard error stream — bytes the command itself wrote
+    /// to fd 2 (an external's stderr, `echo x >&2`, drained pipeline stderr).
+    /// Kept apart from [`Self::err`] so a consumer can show both without
+    /// losing one or printing the other twice. Use [`Self::stderr_text`] for
+    /// the combined fd-2 view a redirect or terminal sees.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub stderr: String,
     /// Structured data — only populated when a builtin/tool sets it explicitly.
     /// Stdout is *never* sniffed; this stays `None` for external commands.
     pub data: Option<Value>,
@@ -215,6 +346,7 @@ impl ExecResult {
             code: 0,
             out: OutputPayload::Text(out.into()),
             err: String::new(),
+            stderr: String::new(),
             data: None,
             output: None,
             did_spill: false,
@@ -238,6 +370,7 @@ impl ExecResult {
                 code: 0,
                 out: OutputPayload::Text(String::new()),
                 err: String::new(),
+                stderr: String::new(),
                 data: None,
                 output: Some(Box::new(output)),
                 did_spill: false,
@@ -275,6 +408,7 @@ impl ExecResult {
             code: 0,
             out: OutputPayload::Text(out),
             err: String::new(),
+            stderr: String::new(),
             data: Some(data),
             output: None,
             did_spill: false,
@@ -298,6 +432,7 @@ impl ExecResult {
             code: 0,
             out: OutputPayload::Text(out.into()),
             err: String::new(),
+            stderr: String::new(),
             data: Some(data),
             output: None,
             did_spill: false,
@@ -314,6 +449,7 @@ impl ExecResult {
             code,
             out: OutputPayload::Text(String::new()),
             err: err.into(),
+            stderr: String::new(),
             data: None,
             output: None,
             did_spill: false,
@@ -326,6 +462,9 @@ impl ExecResult {
 
     /// Create a result from raw output streams.
     ///
+    /// `stderr` lands in [`Self::stderr`] (the captured stream), not in
+    /// [`Self::err`] — a command that wrote to fd 2 has not necessarily failed.
+    ///
     /// `data` is left empty — kaish does not sniff stdout for JSON. To get
     /// structured iteration from an external command, pipe through `jq`:
     /// `for i in $(curl ... | jq .); do ...`.
@@ -333,7 +472,8 @@ impl ExecResult {
         Self {
             code,
             out: OutputPayload::Text(stdout.into()),
-            err: stderr.into(),
+            err: String::new(),
+            stderr: stderr.into(),
             data: None,
             output: None,
             did_spill: false,
@@ -353,6 +493,7 @@ impl ExecResult {
             code: 0,
             out: OutputPayload::Text(text.into()),
             err: String::new(),
+            stderr: String::new(),
             data: None,
             output: Some(Box::new(output)),
             did_spill: false,
@@ -374,6 +515,7 @@ impl ExecResult {
             code,
             out: OutputPayload::Text(out),
             err,
+            stderr: String::new(),
             data,
             output: None,
             did_spill: false,
@@ -390,6 +532,31 @@ impl ExecResult {
         self
     }
 
+    /// Failure with [`exit_code::USAGE`]: bad flags, operands or values.
+    pub fn usage(err: impl Into<String>) -> Self {
+        Self::failure(exit_code::USAGE, err)
+    }
+
+    /// Failure with [`exit_code::TIMEOUT`].
+    pub fn timed_out(err: impl Into<String>) -> Self {
+        Self::failure(exit_code::TIMEOUT, err)
+    }
+
+    /// Failure with [`exit_code::NOT_EXECUTABLE`].
+    pub fn not_executable(err: impl Into<String>) -> Self {
+        Self::failure(exit_code::NOT_EXECUTABLE, err)
+    }
+
+    /// Failure with [`exit_code::NOT_FOUND`].
+    pub fn not_found(err: impl Into<String>) -> Self {
+        Self::failure(exit_code::NOT_FOUND, err)
+    }
+
+    /// Failure with [`exit_code::INTERRUPTED`].
+    pub fn interrupted(err: impl Into<String>) -> Self {
+        Self::failure(exit_code::INTERRUPTED, err)
+    }
+
     // ── Read accessors ──
 
     /// Get text output, materializing from OutputData on demand.
@@ -530,11 +697,92 @@ impl ExecResult {
         }
     }
 
+    // ── Stderr ──
+
+    /// The full fd-2 view of this result: the captured [`Self::stderr`]
+    /// stream followed by the [`Self::err`] message, newline-separated.
+    ///
+    /// This is what a `2>` redirect writes and what a terminal shows — a
+    /// builtin's error message *is* its stderr in shell terms. Borrows when
+    /// only one side is populated.
+    pub fn stderr_text(&self) -> Cow<'_, str> {
+        match (self.stderr.is_empty(), self.err.is_empty()) {
+            (true, _) => Cow::Borrowed(&self.err),
+            (false, true) => Cow::Borrowed(&self.stderr),
+            (false, false) => {
+                let mut s = String::with_capacity(self.stderr.len() + self.err.len() + 1);
+                s.push_str(&self.stderr);
+                if !s.ends_with('\n') {
+                    s.push('\n');
+                }
+                s.push_str(&self.err);
+                Cow::Owned(s)
+            }
+        }
+    }
+
+    /// Append captured stream text to [`Self::stderr`].
+    pub fn push_stderr(&mut self, s: &str) {
+        self.stderr.push_str(s);
+    }
+
+    /// Drop both the stderr stream and the error message — fd 2 was
+    /// redirected elsewhere, so nothing is left to report on it.
+    pub fn clear_stderr(&mut self) {
+        self.stderr.clear();
+        self.err.clear();
+    }
+
+    /// Take the full fd-2 view (see [`Self::stderr_text`]), leaving both
+    /// [`Self::stderr`] and [`Self::err`] empty.
+    pub fn take_stderr_text(&mut self) -> String {
+        let text = self.stderr_text().into_owned();
+        self.clear_stderr();
+        text
+    }
+
     /// True if the command succeeded (exit code 0).
     pub fn ok(&self) -> bool {
         self.code == 0
     }
 
+    /// The failure class of this result, or `None` on success.
+    pub fn error_kind(&self) -> Option<ErrorKind> {
+        let kind = match self.code {
+            exit_code::SUCCESS => return None,
+            _ if self.did_spill => ErrorKind::OutputSpilled,
+            exit_code::USAGE if self.latch.is_some() => ErrorKind::Latch,
+            exit_code::USAGE => ErrorKind::Usage,
+            exit_code::POLICY_DENIED => ErrorKind::PolicyDenied,
+            exit_code::TIMEOUT => ErrorKind::Timeout,
+            exit_code::LIMIT_EXCEEDED => ErrorKind::LimitExceeded,
+            exit_code::NOT_EXECUTABLE => ErrorKind::NotExecutable,
+            exit_code::NOT_FOUND => ErrorKind::NotFound,
+            exit_code::INTERRUPTED => ErrorKind::Interrupted,
+            c if c > exit_code::SIGNAL_BASE && c < exit_code::SIGNAL_BASE + 64 => {
+                ErrorKind::Signal
+            }
+            _ => ErrorKind::Failure,
+        };
+        Some(kind)
+    }
+
+    /// The structured error for a failed result, or `None` on success.
+    pub fn error_info(&self) -> Option<ErrorInfo> {
+        let kind = self.error_kind()?;
+        let message = if self.err.is_empty() {
+            self.stderr.trim_end().to_string()
+        } else {
+            self.err.clone()
+        };
+        Some(ErrorInfo {
+            kind,
+            code: self.code,
+            message,
+            hint: kind.hint().map(str::to_string),
+        })
+    }
+
     /// The pending confirmation-latch request, if this result is a latch gate.
     ///
     /// A gated destructive op (`rm`/`kaish-trash`/an overwrite under `set -o
@@ -702,6 +950,32 @@ mod tests {
         assert_eq!(result.data, Some(value));
     }
 
+    #[test]
+    fn from_output_keeps_stderr_apart_from_err() {
+        let result = ExecResult::from_output(0, "out", "warning: heads up\n");
+        assert!(result.ok());
+        assert!(result.err.is_empty());
+        assert_eq!(result.stderr, "warning: heads up\n");
+    }
+
+    #[test]
+    fn stderr_text_joins_stream_then_message() {
+        let mut result = ExecResult::failure(1, "boom");
+        assert_eq!(result.stderr_text(), "boom");
+        result.push_stderr("partial output");
+        assert_eq!(result.stderr_text(), "partial output\nboom");
+        assert_eq!(result.take_stderr_text(), "partial output\nboom");
+        assert!(result.err.is_empty() && result.stderr.is_empty());
+    }
+
+    #[test]
+    fn empty_stderr_is_not_serialized() {
+        let json = serde_json::to_value(ExecResult::failure(1, "x")).unwrap();
+        assert!(json.get("stderr").is_none());
+        let json = serde_json::to_value(ExecResult::from_output(0, "", "w")).unwrap();
+        assert_eq!(json["stderr"], "w");
+    }
+
     #[test]
     fn did_spill_defaults_to_false() {
         assert!(!ExecResult::success("hi").did_spill);
@@ -1014,4 +1288,42 @@ mod tests {
         assert!(taken.is_none());
         assert!(result.has_output()); // not taken
     }
+
+    #[test]
+    fn error_kind_follows_the_exit_code_table() {
+        assert_eq!(ExecResult::success("").error_kind(), None);
+        assert_eq!(ExecResult::failure(1, "x").error_kind(), Some(ErrorKind::Failure));
+        assert_eq!(ExecResult::usage("x").error_kind(), Some(ErrorKind::Usage));
+        assert_eq!(ExecResult::timed_out("x").error_kind(), Some(ErrorKind::Timeout));
+        assert_eq!(ExecResult::not_executable("x").error_kind(), Some(ErrorKind::NotExecutable));
+        assert_eq!(ExecResult::not_found("x").error_kind(), Some(ErrorKind::NotFound));
+        assert_eq!(ExecResult::interrupted("x").error_kind(), Some(ErrorKind::Interrupted));
+        assert_eq!(ExecResult::failure(137, "").error_kind(), Some(ErrorKind::Signal));
+        assert_eq!(ExecResult::failure(77, "x").error_kind(), Some(ErrorKind::PolicyDenied));
+        assert_eq!(ExecResult::failure(125, "x").error_kind(), Some(ErrorKind::LimitExceeded));
+
+        let mut latched = ExecResult::usage("rm: confirmation required");
+        latched.latch = Some(Box::new(latch_req(&["a.txt"])));
+        assert_eq!(latched.error_kind(), Some(ErrorKind::Latch));
+
+        let mut spilled = ExecResult::failure(3, "");
+        spilled.did_spill = true;
+        assert_eq!(spilled.error_kind(), Some(ErrorKind::OutputSpilled));
+    }
+
+    #[test]
+    fn error_info_falls_back_to_the_stderr_stream() {
+        let info = ExecResult::not_found("command not found: nope").error_info().unwrap();
+        assert_eq!(info.kind, ErrorKind::NotFound);
+        assert_eq!(info.message, "command not found: nope");
+        assert!(info.hint.is_some());
+
+        let info = ExecResult::from_output(1, "", "boom\n").error_info().unwrap();
+        assert_eq!(info.message, "boom");
+        assert_eq!(info.hint, None);
+        assert_eq!(
+            serde_json::to_value(&info).unwrap(),
+            serde_json::json!({"kind": "failure", "code": 1, "message": "boom"})
+        );
+    }
 }
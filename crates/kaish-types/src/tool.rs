@@ -170,6 +170,31 @@ impl ParamSchema {
         }
         self.aliases.iter().any(|a| a == flag)
     }
+
+    /// How this parameter is written on a command line: `PATH` for a
+    /// positional, otherwise the short flags then the long ones (`-n`,
+    /// `--number`). Aliases that only repeat the flag's Rust field name are
+    /// left out.
+    pub fn spellings(&self) -> Vec<String> {
+        if self.positional {
+            return vec![self.name.to_uppercase()];
+        }
+        let field_name = self.name.replace('-', "_");
+        let mut spellings: Vec<String> = self
+            .aliases
+            .iter()
+            .filter(|alias| alias.chars().count() == 1)
+            .map(|alias| format!("-{alias}"))
+            .collect();
+        spellings.push(format!("--{}", self.name));
+        spellings.extend(
+            self.aliases
+                .iter()
+                .filter(|alias| alias.chars().count() > 1 && **alias != field_name)
+                .map(|alias| format!("--{}", alias.trim_start_matches('-'))),
+        );
+        spellings
+    }
 }
 
 /// An example showing how to use a tool.
@@ -191,6 +216,82 @@ impl Example {
     }
 }
 
+/// Behavior hints for clients that apply their own confirmation policy.
+///
+/// Mirrors MCP tool annotations. Every field is a hint, not a guarantee, and
+/// `None` means "not declared" — a client should treat an unannotated tool as
+/// possibly destructive.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[non_exhaustive]
+pub struct ToolAnnotations {
+    /// Human-readable title, if different from the tool name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The tool never modifies its environment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only_hint: Option<bool>,
+    /// The tool may destroy or overwrite data (only meaningful when not
+    /// read-only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destructive_hint: Option<bool>,
+    /// Repeating a call with the same arguments has no further effect.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotent_hint: Option<bool>,
+}
+
+impl ToolAnnotations {
+    /// A tool that only reads: read-only and idempotent.
+    pub fn read_only() -> Self {
+        Self {
+            read_only_hint: Some(true),
+            idempotent_hint: Some(true),
+            ..Self::default()
+        }
+    }
+
+    /// A tool that may delete or overwrite data.
+    pub fn destructive() -> Self {
+        Self {
+            read_only_hint: Some(false),
+            destructive_hint: Some(true),
+            ..Self::default()
+        }
+    }
+
+    /// Set the human-readable title.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Set the idempotent hint.
+    pub fn with_idempotent(mut self, idempotent: bool) -> Self {
+        self.idempotent_hint = Some(idempotent);
+        self
+    }
+
+    /// True when nothing is declared.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Short labels for the declared hints (`read-only`, `destructive`,
+    /// `idempotent`), for listings.
+    pub fn labels(&self) -> Vec<&'static str> {
+        let mut labels = Vec::new();
+        if self.read_only_hint == Some(true) {
+            labels.push("read-only");
+        }
+        if self.destructive_hint == Some(true) {
+            labels.push("destructive");
+        }
+        if self.idempotent_hint == Some(true) {
+            labels.push("idempotent");
+        }
+        labels
+    }
+}
+
 /// Schema describing a tool's interface.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[non_exhaustive]
@@ -258,6 +359,19 @@ pub struct ToolSchema {
     /// See [`ToolSchema::with_glob_passthrough`].
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub glob_passthrough: bool,
+    /// Behavior hints (read-only, destructive, idempotent) for clients that
+    /// gate tool calls. Empty by default. See [`ToolSchema::with_annotations`].
+    #[serde(default, skip_serializing_if = "ToolAnnotations::is_empty")]
+    pub annotations: ToolAnnotations,
+    /// Per-call time limit in milliseconds. The kernel cancels a call that
+    /// runs longer and fails it with exit code 124, independently of the
+    /// script timeout. See [`ToolSchema::with_timeout`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// A failed call may be retried with the same arguments (e.g. a network
+    /// read). Metadata for callers; the kernel does not retry on its own.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub retryable: bool,
 }
 
 impl ToolSchema {
@@ -274,9 +388,30 @@ impl ToolSchema {
             owns_output: false,
             raw_argv: false,
             glob_passthrough: false,
+            annotations: ToolAnnotations::default(),
+            timeout_ms: None,
+            retryable: false,
         }
     }
 
+    /// Bound every call of this tool to `timeout`. See [`ToolSchema::timeout_ms`].
+    pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout_ms = Some(u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX));
+        self
+    }
+
+    /// Declare that a failed call may be retried. See [`ToolSchema::retryable`].
+    pub fn with_retryable(mut self) -> Self {
+        self.retryable = true;
+        self
+    }
+
+    /// Attach behavior hints. See [`ToolSchema::annotations`].
+    pub fn with_annotations(mut self, annotations: ToolAnnotations) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
     /// Declare that this tool wants its argv in source order with types
     /// preserved (no flag/positional split). See [`ToolSchema::raw_argv`].
     pub fn with_raw_argv(mut self) -> Self {
@@ -330,6 +465,28 @@ impl ToolSchema {
         self.name == word || self.aliases.iter().any(|a| a == word)
     }
 
+    /// One-line usage: `name [OPTIONS] POSITIONAL`, with optional
+    /// positionals in brackets and `<SUBCOMMAND>` for a tool that has
+    /// subcommands.
+    pub fn usage_line(&self) -> String {
+        let mut usage = self.name.clone();
+        if !self.subcommands.is_empty() {
+            usage.push_str(" <SUBCOMMAND>");
+        }
+        if self.params.iter().any(|p| !p.positional) {
+            usage.push_str(" [OPTIONS]");
+        }
+        for param in self.params.iter().filter(|p| p.positional) {
+            let name = param.name.to_uppercase();
+            if param.required {
+                usage.push_str(&format!(" {name}"));
+            } else {
+                usage.push_str(&format!(" [{name}]"));
+            }
+        }
+        usage
+    }
+
     /// Declare that this tool renders its own output (including `--json`), so
     /// the kernel won't re-format its result.
     ///
@@ -704,6 +861,34 @@ mod schema_serde_tests {
         let obj = json.as_object().expect("object");
         assert!(!obj.contains_key("subcommands"), "flat tool leaks subcommands: {json}");
         assert!(!obj.contains_key("aliases"), "flat tool leaks command aliases: {json}");
+        assert!(!obj.contains_key("annotations"), "unannotated tool leaks annotations: {json}");
+        assert!(!obj.contains_key("timeout_ms"), "unbounded tool leaks timeout_ms: {json}");
+        assert!(!obj.contains_key("retryable"), "tool leaks retryable=false: {json}");
+    }
+
+    #[test]
+    fn usage_line_and_spellings() {
+        let schema = ToolSchema::new("cat", "concatenate")
+            .param(ParamSchema::new("number", "bool").with_aliases(["n"]))
+            .param(ParamSchema::new("line-range", "string").with_aliases(["line_range", "lines"]))
+            .param(ParamSchema::required("path", "string", "file to read").positional());
+        assert_eq!(schema.usage_line(), "cat [OPTIONS] PATH");
+        assert_eq!(schema.params[0].spellings(), ["-n", "--number"]);
+        assert_eq!(schema.params[1].spellings(), ["--line-range", "--lines"]);
+        assert_eq!(schema.params[2].spellings(), ["PATH"]);
+    }
+
+    #[test]
+    fn annotations_round_trip_and_skip_undeclared_hints() {
+        let schema = ToolSchema::new("rm", "remove files").with_annotations(ToolAnnotations::destructive());
+        let json = serde_json::to_value(&schema).expect("serialize");
+        assert_eq!(
+            json["annotations"],
+            serde_json::json!({"read_only_hint": false, "destructive_hint": true})
+        );
+        let back: ToolSchema = serde_json::from_value(json).expect("deserialize");
+        assert_eq!(back.annotations, ToolAnnotations::destructive());
+        assert_eq!(ToolAnnotations::read_only().labels(), vec!["read-only", "idempotent"]);
     }
 
     /// Round-trip the skip: a flat tool serializes *without* the keys, so the
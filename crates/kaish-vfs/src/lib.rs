@@ -14,14 +14,14 @@ mod traits;
 
 pub use budget::ByteBudget;
 pub use dev::DevFs;
-pub use traits::{DirEntry, DirEntryKind, Filesystem, ReadRange};
+pub use traits::{DirEntry, DirEntryKind, Filesystem, ReadRange, SnapshotInfo};
 
 // `LocalFs` pulls in `tokio/fs`; gated so the in-memory/wasm sandbox build
 // (which doesn't enable `localfs`) stays free of a real-filesystem dependency.
 #[cfg(feature = "localfs")]
 mod local;
 #[cfg(feature = "localfs")]
-pub use local::LocalFs;
+pub use local::{LocalFs, SymlinkPolicy};
 
 // `MemoryFs` only needs `tokio/sync` (runtime-free), but gated so the bare
 // trait-only build stays dependency-free.
@@ -8,15 +8,32 @@ use std::io;
 use std::path::{Path, PathBuf};
 use tokio::fs;
 
+/// What a [`LocalFs`] does with symlinks under its root.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// Follow symlinks whose targets stay under the root; refuse any path
+    /// that a symlink would lead out of it.
+    #[default]
+    Contained,
+    /// Refuse any path that passes through a symlink, and refuse to create
+    /// them. `list`, `lstat`, and `read_link` still show the links themselves.
+    Deny,
+}
+
 /// Local filesystem backend.
 ///
 /// All operations are relative to `root`. For example, if `root` is
 /// `/home/amy/project`, then `read("src/main.rs")` reads
 /// `/home/amy/project/src/main.rs`.
+///
+/// No path reaches outside the root: `..` is resolved before touching the
+/// disk and may not climb above it, and symlinks are handled by the mount's
+/// [`SymlinkPolicy`].
 #[derive(Debug, Clone)]
 pub struct LocalFs {
     root: PathBuf,
     read_only: bool,
+    symlinks: SymlinkPolicy,
 }
 
 impl LocalFs {
@@ -27,6 +44,7 @@ impl LocalFs {
         Self {
             root: root.into(),
             read_only: false,
+            symlinks: SymlinkPolicy::default(),
         }
     }
 
@@ -35,9 +53,16 @@ impl LocalFs {
         Self {
             root: root.into(),
             read_only: true,
+            symlinks: SymlinkPolicy::default(),
         }
     }
 
+    /// Set how symlinks under the root are treated.
+    pub fn with_symlink_policy(mut self, policy: SymlinkPolicy) -> Self {
+        self.symlinks = policy;
+        self
+    }
+
     /// Set whether this filesystem is read-only.
     pub fn set_read_only(&mut self, read_only: bool) {
         self.read_only = read_only;
@@ -48,120 +73,116 @@ impl LocalFs {
         &self.root
     }
 
-    /// Resolve a relative path to an absolute path within the root.
+    /// How symlinks under the root are treated.
+    pub fn symlink_policy(&self) -> SymlinkPolicy {
+        self.symlinks
+    }
+
+    /// Resolve a relative path to an absolute path within the root,
+    /// following symlinks.
     ///
-    /// Returns an error if the path escapes the root (via `..`).
+    /// Returns an error if the path escapes the root (via `..` or a symlink).
     fn resolve(&self, path: &Path) -> io::Result<PathBuf> {
-        // Strip leading slash if present
-        let path = path.strip_prefix("/").unwrap_or(path);
-
-        // Join with root
-        let full = self.root.join(path);
+        let relative = Self::contain(path)?;
+        self.check_symlinks(&relative)?;
+        let canonical = Self::canonicalize_partial(&self.root.join(&relative))?;
+        self.check_under_root(canonical)
+    }
 
-        // Canonicalize to resolve symlinks and ..
-        // For non-existent paths, we need to check parent
-        let canonical = if full.exists() {
-            full.canonicalize()?
-        } else {
-            // For new files, canonicalize parent and append filename
-            let parent = full.parent().ok_or_else(|| {
-                io::Error::new(io::ErrorKind::InvalidInput, "invalid path")
-            })?;
-            let filename = full.file_name().ok_or_else(|| {
-                io::Error::new(io::ErrorKind::InvalidInput, "invalid path")
-            })?;
-
-            if parent.exists() {
-                parent.canonicalize()?.join(filename)
-            } else {
-                // Parent doesn't exist, just use the path as-is
-                // (will fail on actual operation)
-                full
+    /// Resolve a path within the root WITHOUT following its final component.
+    ///
+    /// Used by `lstat()` and `read_link()` which must not follow symlinks.
+    /// Intermediate components are still followed (and checked), so a
+    /// symlinked directory can't be used to stat what lies outside the root.
+    fn resolve_no_follow(&self, path: &Path) -> io::Result<PathBuf> {
+        let relative = Self::contain(path)?;
+        match (relative.parent(), relative.file_name()) {
+            (Some(parent), Some(name)) => {
+                self.check_symlinks(parent)?;
+                let canonical = Self::canonicalize_partial(&self.root.join(parent))?;
+                Ok(self.check_under_root(canonical)?.join(name))
             }
-        };
-
-        // Verify we haven't escaped the root
-        let canonical_root = self.root.canonicalize().unwrap_or_else(|_| self.root.clone());
-        if !canonical.starts_with(&canonical_root) {
-            return Err(io::Error::new(
-                io::ErrorKind::PermissionDenied,
-                format!(
-                    "path escapes root: {} is not under {}",
-                    canonical.display(),
-                    canonical_root.display()
-                ),
-            ));
+            // The root itself.
+            _ => Ok(self.root.clone()),
         }
-
-        Ok(canonical)
     }
 
-    /// Resolve a path within the root WITHOUT following symlinks.
+    /// Resolve a path for an unlink/remove: follow intermediate symlinks but
+    /// NOT the final component, mirroring `unlink(2)`/`rmdir(2)` semantics.
     ///
-    /// Used by `lstat()` and `read_link()` which must not follow symlinks.
-    /// Validates that the path stays within the sandbox by normalizing
-    /// path components (resolving `.` and `..`) without canonicalization.
-    fn resolve_no_follow(&self, path: &Path) -> io::Result<PathBuf> {
-        let path = path.strip_prefix("/").unwrap_or(path);
+    /// `resolve()` canonicalizes the *whole* path, so removing a symlink would
+    /// resolve to (and operate on) its target — `rm symlink-to-dir` could then
+    /// delete the target's contents. Here the final component is re-attached
+    /// literally, so `symlink_metadata` + `remove_*` act on the link itself.
+    /// The root itself can't be removed.
+    fn resolve_for_unlink(&self, path: &Path) -> io::Result<PathBuf> {
+        if Self::contain(path)?.as_os_str().is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid path"));
+        }
+        self.resolve_no_follow(path)
+    }
 
-        let mut normalized = self.root.clone();
+    /// Resolve `.` and `..` in a root-relative path without touching the
+    /// disk, refusing a `..` that would climb above the root.
+    fn contain(path: &Path) -> io::Result<PathBuf> {
+        let mut relative = PathBuf::new();
         for component in path.components() {
             match component {
+                std::path::Component::Normal(c) => relative.push(c),
                 std::path::Component::ParentDir => {
-                    if normalized == self.root {
-                        return Err(io::Error::new(
-                            io::ErrorKind::PermissionDenied,
-                            "path escapes root",
-                        ));
-                    }
-                    normalized.pop();
-                    if !normalized.starts_with(&self.root) {
+                    if !relative.pop() {
                         return Err(io::Error::new(
                             io::ErrorKind::PermissionDenied,
-                            "path escapes root",
+                            format!("path escapes root: {}", path.display()),
                         ));
                     }
                 }
-                std::path::Component::Normal(c) => normalized.push(c),
-                std::path::Component::CurDir => {} // skip
-                _ => {}
+                std::path::Component::RootDir
+                | std::path::Component::CurDir
+                | std::path::Component::Prefix(_) => {}
             }
         }
-
-        // Final containment check
-        if !normalized.starts_with(&self.root) {
-            return Err(io::Error::new(
-                io::ErrorKind::PermissionDenied,
-                "path escapes root",
-            ));
-        }
-        Ok(normalized)
+        Ok(relative)
     }
 
-    /// Resolve a path for an unlink/remove: follow intermediate symlinks but
-    /// NOT the final component, mirroring `unlink(2)`/`rmdir(2)` semantics.
+    /// Canonicalize a path that may not exist yet: its deepest existing
+    /// ancestor is canonicalized (following symlinks) and the rest appended.
     ///
-    /// `resolve()` canonicalizes the *whole* path, so removing a symlink would
-    /// resolve to (and operate on) its target — `rm symlink-to-dir` could then
-    /// delete the target's contents. Here we canonicalize the parent only and
-    /// re-attach the literal final component, so `symlink_metadata` + `remove_*`
-    /// act on the link itself. The root-containment check still applies to the
-    /// resolved parent, so an intermediate symlink escaping the root is rejected.
-    fn resolve_for_unlink(&self, path: &Path) -> io::Result<PathBuf> {
-        let path = path.strip_prefix("/").unwrap_or(path);
-        let full = self.root.join(path);
-
-        let parent = full
-            .parent()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid path"))?;
-        let filename = full
-            .file_name()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid path"))?;
-
-        // Canonicalize the parent (following intermediate symlinks); if the
-        // parent doesn't exist there's nothing to remove, so surface NotFound.
-        let canonical = parent.canonicalize()?.join(filename);
+    /// A dangling symlink is an error rather than a missing path — writing
+    /// through one would create its target wherever it points.
+    fn canonicalize_partial(full: &Path) -> io::Result<PathBuf> {
+        let mut existing = full;
+        let mut missing = Vec::new();
+        let meta = loop {
+            match std::fs::symlink_metadata(existing) {
+                Ok(meta) => break meta,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    let (Some(parent), Some(name)) = (existing.parent(), existing.file_name())
+                    else {
+                        return Err(e);
+                    };
+                    missing.push(name);
+                    existing = parent;
+                }
+                Err(e) => return Err(e),
+            }
+        };
+        let mut canonical = match existing.canonicalize() {
+            Ok(canonical) => canonical,
+            Err(e) if e.kind() == io::ErrorKind::NotFound && meta.file_type().is_symlink() => {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("dangling symlink: {}", existing.display()),
+                ));
+            }
+            Err(e) => return Err(e),
+        };
+        canonical.extend(missing.iter().rev());
+        Ok(canonical)
+    }
 
+    /// Pass `canonical` through if it lies under the root.
+    fn check_under_root(&self, canonical: PathBuf) -> io::Result<PathBuf> {
         let canonical_root = self.root.canonicalize().unwrap_or_else(|_| self.root.clone());
         if !canonical.starts_with(&canonical_root) {
             return Err(io::Error::new(
@@ -176,6 +197,34 @@ impl LocalFs {
         Ok(canonical)
     }
 
+    /// Under [`SymlinkPolicy::Deny`], refuse a root-relative path if any of
+    /// its existing components is a symlink.
+    fn check_symlinks(&self, relative: &Path) -> io::Result<()> {
+        if self.symlinks != SymlinkPolicy::Deny {
+            return Ok(());
+        }
+        let mut current = self.root.clone();
+        for component in relative.components() {
+            current.push(component);
+            match std::fs::symlink_metadata(&current) {
+                Ok(meta) if meta.file_type().is_symlink() => {
+                    return Err(Self::symlinks_denied(relative));
+                }
+                Ok(_) => {}
+                // Nothing below a missing component can be a symlink.
+                Err(_) => break,
+            }
+        }
+        Ok(())
+    }
+
+    fn symlinks_denied(path: &Path) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("symlinks are not allowed on this mount: {}", path.display()),
+        )
+    }
+
     /// Check if write operations are allowed.
     fn check_writable(&self) -> io::Result<()> {
         if self.read_only {
@@ -409,6 +458,9 @@ impl Filesystem for LocalFs {
 
     async fn symlink(&self, target: &Path, link: &Path) -> io::Result<()> {
         self.check_writable()?;
+        if self.symlinks == SymlinkPolicy::Deny {
+            return Err(Self::symlinks_denied(link));
+        }
 
         // Validate absolute symlink targets stay within sandbox.
         // `resolve` would strip the leading slash and treat `/etc/passwd` as
@@ -471,8 +523,8 @@ impl Filesystem for LocalFs {
         self.check_writable()?;
         // Don't follow the source's final symlink: `mv link new` must rename the
         // link itself, not canonicalize to (and move) its target. The dest keeps
-        // normal resolution — it's the path we're creating, and resolve()'s
-        // missing-parent fallback pairs with the create_dir_all below.
+        // normal resolution — it's the path we're creating, and resolve()
+        // accepts missing parents, which the create_dir_all below makes.
         let from_path = self.resolve_for_unlink(from)?;
         let to_path = self.resolve(to)?;
 
@@ -847,6 +899,87 @@ mod tests {
         cleanup(&dir).await;
     }
 
+    #[tokio::test]
+    async fn test_dot_dot_through_missing_dirs_blocked() {
+        // `a` doesn't exist, so the OS never resolved `a/..`; creating the
+        // parents must not be able to land beside the root.
+        let (fs, dir) = setup().await;
+        let outside = dir.with_extension("escaped");
+
+        let escape = format!("a/../../{}/x.txt", outside.file_name().unwrap().to_string_lossy());
+        let result = fs.write(Path::new(&escape), b"out").await;
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+        assert!(!outside.exists());
+
+        // `..` that stays inside resolves normally.
+        fs.write(Path::new("a/../b/./c.txt"), b"in").await.unwrap();
+        assert_eq!(fs.read(Path::new("b/c.txt")).await.unwrap(), b"in");
+
+        cleanup(&dir).await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_symlinks_out_of_root_blocked() {
+        let (fs, dir) = setup().await;
+        let outside = dir.with_extension("outside");
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(outside.join("secret"), b"secret").unwrap();
+        std::os::unix::fs::symlink(&outside, dir.join("out")).unwrap();
+        std::os::unix::fs::symlink(outside.join("new"), dir.join("dangling")).unwrap();
+
+        fn denied<T>(result: io::Result<T>) {
+            match result {
+                Ok(_) => panic!("reached outside the root"),
+                Err(e) => assert!(
+                    matches!(e.kind(), io::ErrorKind::PermissionDenied | io::ErrorKind::NotFound),
+                    "{e}"
+                ),
+            }
+        }
+        denied(fs.read(Path::new("out/secret")).await);
+        denied(fs.lstat(Path::new("out/secret")).await);
+        denied(fs.write(Path::new("out/planted"), b"x").await);
+        denied(fs.remove(Path::new("out/secret")).await);
+        // Writing through a dangling link would create its target outside.
+        denied(fs.write(Path::new("dangling"), b"x").await);
+        assert!(!outside.join("new").exists());
+        assert!(!outside.join("planted").exists());
+        assert!(outside.join("secret").exists());
+
+        // The links themselves are still visible and removable.
+        assert!(fs.lstat(Path::new("out")).await.unwrap().is_symlink());
+        fs.remove(Path::new("dangling")).await.unwrap();
+
+        let _ = std::fs::remove_dir_all(&outside);
+        cleanup(&dir).await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_symlink_policy_deny() {
+        let (_, dir) = setup().await;
+        let fs = LocalFs::new(&dir).with_symlink_policy(SymlinkPolicy::Deny);
+        assert_eq!(fs.symlink_policy(), SymlinkPolicy::Deny);
+        fs.write(Path::new("real/file.txt"), b"data").await.unwrap();
+        std::os::unix::fs::symlink("real", dir.join("link")).unwrap();
+
+        // Even a link that stays inside the root is refused...
+        let error = fs.read(Path::new("link/file.txt")).await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+        assert!(error.to_string().contains("symlinks are not allowed"), "{error}");
+        let error = fs.symlink(Path::new("real"), Path::new("link2")).await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+
+        // ...but the link itself can still be seen and removed.
+        assert!(fs.lstat(Path::new("link")).await.unwrap().is_symlink());
+        assert_eq!(fs.read_link(Path::new("link")).await.unwrap(), Path::new("real"));
+        fs.remove(Path::new("link")).await.unwrap();
+        assert_eq!(fs.read(Path::new("real/file.txt")).await.unwrap(), b"data");
+
+        cleanup(&dir).await;
+    }
+
     #[cfg(unix)]
     #[tokio::test]
     async fn test_symlink_relative_target_allowed() {
@@ -1,11 +1,16 @@
 //! In-memory filesystem implementation.
 //!
 //! Used for `/v` and testing. All data is ephemeral.
+//!
+//! File contents are immutable, reference-counted buffers: a write replaces a
+//! file's buffer rather than changing it. That makes a snapshot a copy of the
+//! entry table that shares every buffer with the live tree — cheap to take,
+//! and a later write to the live tree leaves the snapshot's copy alone.
 
 use crate::budget::ByteBudget;
-use crate::traits::{DirEntry, DirEntryKind, Filesystem, ReadRange};
+use crate::traits::{DirEntry, DirEntryKind, Filesystem, ReadRange, SnapshotInfo};
 use async_trait::async_trait;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -17,7 +22,7 @@ use tokio::sync::RwLock;
 /// Entry in the memory filesystem.
 #[derive(Debug, Clone)]
 enum Entry {
-    File { data: Vec<u8>, modified: SystemTime },
+    File { data: Arc<[u8]>, modified: SystemTime },
     Directory { modified: SystemTime },
     Symlink { target: PathBuf, modified: SystemTime },
 }
@@ -26,13 +31,35 @@ enum Entry {
 ///
 /// Thread-safe via internal `RwLock`. All data is lost when dropped.
 /// Tracks its resident content bytes exactly (net: an overwrite charges the
-/// delta, a remove credits) and optionally draws them from a shared
-/// [`ByteBudget`].
+/// delta, a remove credits), optionally caps them with a quota of its own,
+/// and optionally draws them from a shared [`ByteBudget`].
+///
+/// Snapshots ([`Filesystem::snapshot`]) share contents with the live tree,
+/// so only the live tree counts toward the resident bytes, quota, and
+/// budget; bytes a snapshot alone still holds are reported by
+/// [`Filesystem::snapshots`] until it is dropped.
 #[derive(Debug)]
 pub struct MemoryFs {
     entries: RwLock<HashMap<PathBuf, Entry>>,
     resident: AtomicU64,
+    quota: Option<u64>,
     budget: Option<Arc<ByteBudget>>,
+    snapshots: std::sync::Mutex<Snapshots>,
+}
+
+/// The snapshots a [`MemoryFs`] holds, by id.
+#[derive(Debug, Default)]
+struct Snapshots {
+    next_id: u64,
+    taken: BTreeMap<u64, Snapshot>,
+}
+
+/// A captured directory: its normalized path and every entry at or under it.
+#[derive(Debug)]
+struct Snapshot {
+    root: PathBuf,
+    entries: HashMap<PathBuf, Entry>,
+    bytes: u64,
 }
 
 impl Default for MemoryFs {
@@ -53,6 +80,14 @@ impl MemoryFs {
         Self::build(Some(budget))
     }
 
+    /// Cap this filesystem's own content bytes at `limit`, on top of any
+    /// shared budget. A write that would take it past the quota fails
+    /// loudly, ENOSPC-style, and leaves the filesystem untouched.
+    pub fn with_quota(mut self, limit: u64) -> Self {
+        self.quota = Some(limit);
+        self
+    }
+
     fn build(budget: Option<Arc<ByteBudget>>) -> Self {
         let mut entries = HashMap::new();
         // Root directory always exists
@@ -65,7 +100,9 @@ impl MemoryFs {
         Self {
             entries: RwLock::new(entries),
             resident: AtomicU64::new(0),
+            quota: None,
             budget,
+            snapshots: std::sync::Mutex::new(Snapshots::default()),
         }
     }
 
@@ -77,13 +114,45 @@ impl MemoryFs {
         }
     }
 
-    /// Reserve budget for a growth *before* mutating, so a refused charge
-    /// leaves the filesystem untouched.
+    /// Content bytes of the entries at or under `root`.
+    fn subtree_len(entries: &HashMap<PathBuf, Entry>, root: &Path) -> u64 {
+        entries
+            .iter()
+            .filter(|(path, _)| path.starts_with(root))
+            .map(|(_, entry)| Self::file_len(Some(entry)))
+            .sum()
+    }
+
+    fn lock_snapshots(&self) -> std::sync::MutexGuard<'_, Snapshots> {
+        self.snapshots.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn no_snapshot(id: u64) -> io::Error {
+        io::Error::new(io::ErrorKind::NotFound, format!("no snapshot {id}"))
+    }
+
+    /// Check the quota and reserve budget for a growth *before* mutating, so
+    /// a refusal leaves the filesystem untouched. Callers hold the entries
+    /// write lock, so the quota check and the later `settle` can't interleave
+    /// with another write.
     fn charge_grow(&self, old: u64, new: u64) -> io::Result<()> {
-        if new > old
-            && let Some(budget) = &self.budget
-        {
-            budget.try_charge(new - old)?;
+        if new <= old {
+            return Ok(());
+        }
+        let grow = new - old;
+        if let Some(quota) = self.quota {
+            let used = self.resident.load(Ordering::Acquire);
+            if used.saturating_add(grow) > quota {
+                return Err(io::Error::new(
+                    io::ErrorKind::StorageFull,
+                    format!(
+                        "quota exceeded: {used} bytes used + {grow} requested exceeds the {quota} byte quota"
+                    ),
+                ));
+            }
+        }
+        if let Some(budget) = &self.budget {
+            budget.try_charge(grow)?;
         }
         Ok(())
     }
@@ -129,7 +198,7 @@ impl MemoryFs {
             let entries = self.entries.read().await;
 
             match entries.get(&normalized) {
-                Some(Entry::File { data, .. }) => Ok(data.clone()),
+                Some(Entry::File { data, .. }) => Ok(data.to_vec()),
                 Some(Entry::Directory { .. }) => Err(io::Error::new(
                     io::ErrorKind::IsADirectory,
                     format!("is a directory: {}", path.display()),
@@ -369,7 +438,7 @@ impl Filesystem for MemoryFs {
         entries.insert(
             normalized,
             Entry::File {
-                data: data.to_vec(),
+                data: Arc::from(data),
                 modified: system_now(),
             },
         );
@@ -729,6 +798,89 @@ impl Filesystem for MemoryFs {
     fn resident_bytes(&self) -> Option<u64> {
         Some(self.resident.load(Ordering::Acquire))
     }
+
+    fn quota_bytes(&self) -> Option<u64> {
+        self.quota
+    }
+
+    async fn snapshot(&self, path: &Path) -> io::Result<u64> {
+        let root = Self::normalize(path);
+        let entries = self.entries.read().await;
+        if !root.as_os_str().is_empty() {
+            match entries.get(&root) {
+                Some(Entry::Directory { .. }) => {}
+                Some(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::NotADirectory,
+                        format!("not a directory: {}", path.display()),
+                    ));
+                }
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("not found: {}", path.display()),
+                    ));
+                }
+            }
+        }
+        let captured: HashMap<PathBuf, Entry> = entries
+            .iter()
+            .filter(|(path, _)| path.starts_with(&root))
+            .map(|(path, entry)| (path.clone(), entry.clone()))
+            .collect();
+        let bytes = Self::subtree_len(&captured, &root);
+        drop(entries);
+
+        let mut snapshots = self.lock_snapshots();
+        snapshots.next_id += 1;
+        let id = snapshots.next_id;
+        snapshots.taken.insert(id, Snapshot { root, entries: captured, bytes });
+        Ok(id)
+    }
+
+    async fn restore_snapshot(&self, _path: &Path, id: u64) -> io::Result<()> {
+        let mut entries = self.entries.write().await;
+        let (root, captured, new_len) = {
+            let snapshots = self.lock_snapshots();
+            let snapshot = snapshots.taken.get(&id).ok_or_else(|| Self::no_snapshot(id))?;
+            (snapshot.root.clone(), snapshot.entries.clone(), snapshot.bytes)
+        };
+
+        // The directory may have been removed since; recreate its parents.
+        Self::ensure_parents_locked(&mut entries, &root)?;
+        let old_len = Self::subtree_len(&entries, &root);
+        self.charge_grow(old_len, new_len)?;
+
+        entries.retain(|path, _| !path.starts_with(&root));
+        entries.extend(captured);
+        self.settle(old_len, new_len);
+        Ok(())
+    }
+
+    async fn drop_snapshot(&self, _path: &Path, id: u64) -> io::Result<()> {
+        match self.lock_snapshots().taken.remove(&id) {
+            Some(_) => Ok(()),
+            None => Err(Self::no_snapshot(id)),
+        }
+    }
+
+    async fn snapshots(&self, _path: &Path) -> io::Result<Vec<SnapshotInfo>> {
+        Ok(self
+            .lock_snapshots()
+            .taken
+            .iter()
+            .map(|(&id, snapshot)| SnapshotInfo {
+                id,
+                path: Path::new("/").join(&snapshot.root),
+                files: snapshot
+                    .entries
+                    .values()
+                    .filter(|entry| matches!(entry, Entry::File { .. }))
+                    .count(),
+                bytes: snapshot.bytes,
+            })
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -1381,6 +1533,83 @@ mod tests {
         assert_eq!(fs_two.resident_bytes(), Some(4));
     }
 
+    #[tokio::test]
+    async fn test_quota_caps_one_filesystem() {
+        let budget = Arc::new(ByteBudget::new(100));
+        let fs = MemoryFs::with_budget(budget.clone()).with_quota(10);
+        assert_eq!(fs.quota_bytes(), Some(10));
+
+        fs.write(Path::new("a.txt"), b"01234567").await.unwrap();
+        let error = fs.write(Path::new("b.txt"), b"012").await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::StorageFull);
+        assert!(error.to_string().contains("10 byte quota"), "{error}");
+        assert!(!fs.exists(Path::new("b.txt")).await);
+        // The refusal charged nothing to the shared budget either.
+        assert_eq!(budget.used(), 8);
+
+        // An overwrite is measured by its net growth.
+        fs.write(Path::new("a.txt"), b"0123456789").await.unwrap();
+        assert_eq!(fs.resident_bytes(), Some(10));
+        fs.remove(Path::new("a.txt")).await.unwrap();
+        fs.write(Path::new("b.txt"), b"012").await.unwrap();
+        assert_eq!(MemoryFs::new().quota_bytes(), None);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_restores_one_directory() {
+        let budget = Arc::new(ByteBudget::new(100));
+        let fs = MemoryFs::with_budget(budget.clone());
+        fs.write(Path::new("scratch/a.txt"), b"before").await.unwrap();
+        fs.write(Path::new("scratch/b.txt"), b"kept").await.unwrap();
+        fs.write(Path::new("other.txt"), b"x").await.unwrap();
+        let id = fs.snapshot(Path::new("/scratch")).await.unwrap();
+
+        fs.write(Path::new("scratch/a.txt"), b"after, and longer").await.unwrap();
+        fs.remove(Path::new("scratch/b.txt")).await.unwrap();
+        fs.write(Path::new("scratch/c.txt"), b"new").await.unwrap();
+        fs.write(Path::new("other.txt"), b"changed").await.unwrap();
+
+        let info = fs.snapshots(Path::new("/")).await.unwrap();
+        assert_eq!(info, [SnapshotInfo { id, path: PathBuf::from("/scratch"), files: 2, bytes: 10 }]);
+
+        fs.restore_snapshot(Path::new("/"), id).await.unwrap();
+        assert_eq!(fs.read(Path::new("scratch/a.txt")).await.unwrap(), b"before");
+        assert_eq!(fs.read(Path::new("scratch/b.txt")).await.unwrap(), b"kept");
+        assert!(!fs.exists(Path::new("scratch/c.txt")).await);
+        // Outside the snapshot's directory, nothing moves.
+        assert_eq!(fs.read(Path::new("other.txt")).await.unwrap(), b"changed");
+        assert_eq!(fs.resident_bytes(), Some(17));
+        assert_eq!(budget.used(), 17);
+
+        // The snapshot survives a restore, and restores a removed directory.
+        fs.write(Path::new("scratch/a.txt"), b"again").await.unwrap();
+        fs.remove(Path::new("scratch/a.txt")).await.unwrap();
+        fs.remove(Path::new("scratch/b.txt")).await.unwrap();
+        fs.remove(Path::new("scratch")).await.unwrap();
+        fs.restore_snapshot(Path::new("/"), id).await.unwrap();
+        assert_eq!(fs.read(Path::new("scratch/a.txt")).await.unwrap(), b"before");
+
+        fs.drop_snapshot(Path::new("/"), id).await.unwrap();
+        assert!(fs.snapshots(Path::new("/")).await.unwrap().is_empty());
+        let error = fs.restore_snapshot(Path::new("/"), id).await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert!(fs.snapshot(Path::new("other.txt")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_restore_respects_quota() {
+        let fs = MemoryFs::new().with_quota(10);
+        fs.write(Path::new("scratch/a.txt"), b"01234567").await.unwrap();
+        let id = fs.snapshot(Path::new("scratch")).await.unwrap();
+        fs.remove(Path::new("scratch/a.txt")).await.unwrap();
+        fs.write(Path::new("b.txt"), b"0123").await.unwrap();
+
+        let error = fs.restore_snapshot(Path::new("/"), id).await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::StorageFull);
+        assert!(!fs.exists(Path::new("scratch/a.txt")).await);
+        assert_eq!(fs.resident_bytes(), Some(4));
+    }
+
     #[tokio::test]
     async fn test_ensure_parents_rejects_file_as_dir() {
         let fs = MemoryFs::new();
@@ -5,8 +5,8 @@ use std::io;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
-// DirEntry and DirEntryKind live in kaish-types.
-pub use kaish_types::{DirEntry, DirEntryKind, ReadRange};
+// DirEntry, DirEntryKind, and SnapshotInfo live in kaish-types.
+pub use kaish_types::{DirEntry, DirEntryKind, ReadRange, SnapshotInfo};
 
 /// Abstract filesystem interface.
 ///
@@ -38,6 +38,21 @@ pub trait Filesystem: Send + Sync {
     /// Returns `Err` if the filesystem is read-only.
     async fn write(&self, path: &Path, data: &[u8]) -> io::Result<()>;
 
+    /// Append data to a file, creating it if it doesn't exist.
+    ///
+    /// The default reads the current content and writes it back extended.
+    /// Filesystems whose reads have side effects (a pipe that drains, say)
+    /// override this to append without reading.
+    async fn append(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        let mut content = match self.read(path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        content.extend_from_slice(data);
+        self.write(path, &content).await
+    }
+
     /// List entries in a directory.
     async fn list(&self, path: &Path) -> io::Result<Vec<DirEntry>>;
 
@@ -85,6 +100,13 @@ pub trait Filesystem: Send + Sync {
         None
     }
 
+    /// The cap on this filesystem's own [`resident_bytes`](Self::resident_bytes),
+    /// if it has one (`MemoryFs::with_quota`). A shared `ByteBudget` is a
+    /// separate, pooled limit and is not reported here.
+    fn quota_bytes(&self) -> Option<u64> {
+        None
+    }
+
     /// Check if a path exists.
     async fn exists(&self, path: &Path) -> bool {
         self.stat(path).await.is_ok()
@@ -155,4 +177,39 @@ pub trait Filesystem: Send + Sync {
         // Default: same as stat (for backends that don't support symlinks)
         self.stat(path).await
     }
+
+    /// Capture the directory at `path` and everything under it for a later
+    /// [`restore_snapshot`](Self::restore_snapshot), returning the
+    /// snapshot's id.
+    ///
+    /// The default errors with `Unsupported`. `MemoryFs` shares file
+    /// contents between its live tree and its snapshots, so taking one
+    /// copies the directory structure, not the bytes.
+    async fn snapshot(&self, path: &Path) -> io::Result<u64> {
+        Err(snapshots_unsupported(path))
+    }
+
+    /// Put the directory snapshot `id` captured back the way it was. `path`
+    /// is any path on this filesystem; through a router it picks the mount.
+    /// The snapshot is kept, so it can be restored again.
+    async fn restore_snapshot(&self, path: &Path, _id: u64) -> io::Result<()> {
+        Err(snapshots_unsupported(path))
+    }
+
+    /// Forget snapshot `id`, releasing whatever contents only it held.
+    async fn drop_snapshot(&self, path: &Path, _id: u64) -> io::Result<()> {
+        Err(snapshots_unsupported(path))
+    }
+
+    /// The snapshots this filesystem holds, oldest first.
+    async fn snapshots(&self, path: &Path) -> io::Result<Vec<SnapshotInfo>> {
+        Err(snapshots_unsupported(path))
+    }
+}
+
+fn snapshots_unsupported(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("snapshots not supported for {}", path.display()),
+    )
 }
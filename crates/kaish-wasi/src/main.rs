@@ -69,6 +69,7 @@ async fn run() {
             "code": result.code,
             "out": result.text_out().as_ref(),
             "err": if result.err.is_empty() { None } else { Some(&result.err) },
+            "stderr": if result.stderr.is_empty() { None } else { Some(&result.stderr) },
         });
 
         let _ = serde_json::to_writer(&mut stdout, &json);